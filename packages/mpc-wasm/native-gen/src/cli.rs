@@ -0,0 +1,617 @@
+//! Command-line interface (clap derive).
+//!
+//! Parsing and validation only — `main` dispatches on [`Command`]. Party
+//! counts, thresholds and execution ids are checked here so a mistyped
+//! invocation fails with a usage error instead of running with defaults.
+
+use std::path::PathBuf;
+
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+
+use crate::bench;
+use crate::eid::{self, EidPurpose};
+use crate::party_limits;
+use crate::primes_check::DEFAULT_MR_ROUNDS;
+use crate::share_cache;
+use crate::share_format::{CurveId, Encoding, Level};
+
+/// `--version`: this crate, the cggmp24 release it is built against and
+/// its bignum backend (`BIGNUM_BACKEND` in main, as a literal for concat!).
+#[cfg(feature = "gmp")]
+const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), " (cggmp24 0.7.0-alpha, rug)");
+#[cfg(not(feature = "gmp"))]
+const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), " (cggmp24 0.7.0-alpha, num-bigint)");
+
+/// Default for `--timeout`.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+/// Native CGGMP24 / FROST key generation and signing on the GMP backend
+/// (or pure-Rust num-bigint, in builds without `gmp`).
+#[derive(Parser)]
+#[command(name = "guardian-gen-primes", version = VERSION)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Args)]
+pub struct GlobalArgs {
+    /// Paillier security level, 128 or 192 (signing reads it from the aux header)
+    #[arg(long, global = true, value_name = "BITS", value_parser = Level::parse)]
+    pub security_level: Option<Level>,
+
+    /// Curve for new keys (signing reads it from the share header)
+    #[arg(long, global = true, value_parser = CurveId::parse, default_value = "secp256k1")]
+    pub curve: CurveId,
+
+    /// Encoding of new blobs and of `sign` / `dkg-party` protocol messages,
+    /// json or cbor (blobs are read in either)
+    #[arg(long, global = true, value_parser = Encoding::parse, default_value = "json")]
+    pub encoding: Encoding,
+
+    /// Encrypt emitted shares under the passphrase in environment variable VAR
+    #[arg(long = "encrypt-passphrase-env", global = true, value_name = "VAR")]
+    pub passphrase_env: Option<String>,
+
+    /// Format of the progress lines on stderr
+    #[arg(long, global = true, value_enum, default_value_t = ProgressFormat::Text)]
+    pub progress: ProgressFormat,
+
+    /// Signing: give up after this many seconds without input
+    #[arg(long, global = true, value_name = "SECS", default_value_t = DEFAULT_TIMEOUT_SECS)]
+    pub timeout: u64,
+
+    /// Largest party count accepted for new keys
+    #[arg(long, global = true, value_name = "N", value_parser = parse_party_count, default_value_t = party_limits::DEFAULT_MAX_PARTIES)]
+    pub max_parties: u16,
+
+    /// Signing: combined key shares kept cached across sessions (0 disables)
+    #[arg(long, global = true, value_name = "N", default_value_t = share_cache::DEFAULT_CAPACITY)]
+    pub share_cache: usize,
+
+    /// Signing: append each completed ECDSA session's audit record to FILE,
+    /// one JSON line per session
+    #[arg(long, global = true, value_name = "FILE")]
+    pub audit_log: Option<PathBuf>,
+
+    /// Mix seed material read from PATH (e.g. a hardware TRNG device) into
+    /// the randomness of every protocol run
+    #[arg(long, global = true, value_name = "PATH")]
+    pub entropy_source: Option<PathBuf>,
+
+    /// Refuse shares whose metadata names another environment (e.g. prod)
+    #[arg(long, global = true, value_name = "ENV")]
+    pub expected_environment: Option<String>,
+
+    /// Read input from FILE instead of stdin
+    #[arg(long, global = true, value_name = "FILE")]
+    pub input: Option<PathBuf>,
+
+    /// Write output to FILE instead of stdout
+    #[arg(long, global = true, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressFormat {
+    Text,
+    Json,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Full two-phase DKG, generating Paillier primes inline (slow)
+    Dkg {
+        #[command(flatten)]
+        args: DkgArgs,
+        /// Save the aux infos to DIR once Phase A is done, and resume from
+        /// there when rerun with the same eid; deleted on success
+        #[arg(long, value_name = "DIR")]
+        checkpoint_dir: Option<PathBuf>,
+        /// Encrypt the checkpoint under the passphrase in environment
+        /// variable VAR
+        #[arg(long, value_name = "VAR", requires = "checkpoint_dir")]
+        checkpoint_passphrase_env: Option<String>,
+        /// Mint the shares with a trusted dealer instead: no MPC rounds, and
+        /// every blob flagged as dealer-generated. Tests only
+        #[cfg(feature = "insecure-dev")]
+        #[arg(long, conflicts_with = "checkpoint_dir")]
+        trusted_dealer: bool,
+    },
+    /// DKG with pre-generated primes (one base64 line per party on the input)
+    DkgWithPrimes {
+        #[command(flatten)]
+        args: DkgArgs,
+        /// Take the primes from a `primes --out-dir` directory instead,
+        /// each checked against its sidecar
+        #[arg(long, value_name = "DIR")]
+        primes_dir: Option<PathBuf>,
+        /// Refuse primes whose fingerprint is in FILE (one per line, from
+        /// earlier ceremonies' `primes_fingerprints`)
+        #[arg(long, value_name = "FILE")]
+        used_primes: Option<PathBuf>,
+        /// Skip the duplicate / already-used primes checks (test
+        /// environments only: parties sharing primes share a Paillier modulus)
+        #[arg(long)]
+        allow_unsafe_prime_reuse: bool,
+    },
+    /// Keygen only, with a pre-generated AuxInfo JSON line on the input
+    DkgWithAux {
+        #[command(flatten)]
+        args: DkgArgs,
+        /// Take the set from an `aux-pool` directory instead, as
+        /// `aux-pool take` does
+        #[arg(long, value_name = "DIR")]
+        aux_pool: Option<PathBuf>,
+        #[command(flatten)]
+        policy: AuxPolicy,
+    },
+    /// Generate Paillier prime pairs, one base64 line each
+    #[command(args_conflicts_with_subcommands = true)]
+    Primes {
+        #[arg(default_value_t = 3)]
+        count: usize,
+        /// Same as the positional count
+        #[arg(long = "count", value_name = "N", conflicts_with = "count")]
+        count_flag: Option<usize>,
+        /// Write one binary file per pair, each with a `.meta.json` sidecar,
+        /// into DIR (created if missing) instead of base64 lines
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<PathBuf>,
+        #[command(subcommand)]
+        action: Option<PrimesCommand>,
+    },
+    /// Pre-generate AuxInfo sets (Phase A) for `dkg-with-aux`
+    GenAux {
+        /// Number of parties (≥ 2)
+        #[arg(value_parser = parse_party_count)]
+        n: u16,
+        /// Number of sets
+        #[arg(default_value_t = 1)]
+        count: usize,
+    },
+    /// A directory of `gen-aux` sets reused across DKGs, each handed out
+    /// under a max-age / max-uses policy
+    AuxPool {
+        #[command(subcommand)]
+        action: AuxPoolCommand,
+    },
+    /// New AuxInfo for the key whose core shares (base64, one per line) are
+    /// on the input; the core shares and public key stay as they are
+    RefreshAux {
+        /// Execution id, 64 hex chars (default: random)
+        #[arg(value_parser = parse_eid)]
+        eid: Option<[u8; 32]>,
+        /// Derive the execution id for purpose `refresh` instead
+        #[arg(long, value_name = "WALLET_ID[:NONCE_HEX]", value_parser = parse_eid_seed, conflicts_with = "eid")]
+        derive_eid: Option<EidSeed>,
+        /// Pre-generated primes, one base64 line per party (default: generate)
+        #[arg(long, value_name = "FILE")]
+        primes: Option<PathBuf>,
+    },
+    /// Interactive ECDSA signing over JSON lines
+    Sign,
+    /// One party of a distributed DKG (aux_info_gen, then keygen) over JSON
+    /// lines, exchanging messages like `sign`
+    DkgParty,
+    /// FROST (BIP-340) DKG
+    FrostDkg(DkgArgs),
+    /// Interactive FROST signing over JSON lines
+    FrostSign,
+    /// Reconstruct the full private key from the core shares on the input
+    ExportKey {
+        /// Must be EXPORT_PRIVATE_KEY
+        #[arg(long, value_name = "PHRASE")]
+        confirm: Option<String>,
+    },
+    /// Convert Binance tss-lib ECDSA keygen save files (one per party) into
+    /// core shares; mint their aux info with `refresh-aux` afterwards
+    ImportTsslib {
+        /// Signers the key needs: tss-lib's threshold + 1
+        #[arg(long)]
+        threshold: u16,
+        /// `LocalPartySaveData` JSON files
+        #[arg(required = true, value_name = "FILE")]
+        files: Vec<PathBuf>,
+    },
+    /// Print the commitment to a share's public parts (from FILE or the
+    /// input); with --verify, check hex commitments on the input (one per
+    /// line) belong to one key and exit 0 iff they do
+    Commitment {
+        #[arg(long = "in", value_name = "FILE", conflicts_with = "verify")]
+        share: Option<PathBuf>,
+        #[arg(long)]
+        verify: bool,
+        #[arg(long, value_name = "HEX", requires = "verify")]
+        expect_pubkey: Option<String>,
+    },
+    /// Print the shared public key of a KeyShare or CoreKeyShare (base64 or
+    /// raw, from FILE or the input)
+    ExtractPubkey {
+        #[arg(long = "in", value_name = "FILE")]
+        share: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = PubkeyFormat::Hex)]
+        format: PubkeyFormat,
+    },
+    /// Combine a core share with its aux info into a KeyShare; without
+    /// --core / --aux, both are read from the input (core line first)
+    Combine {
+        #[arg(long, value_name = "FILE")]
+        core: Option<PathBuf>,
+        #[arg(long, value_name = "FILE")]
+        aux: Option<PathBuf>,
+        /// Write the JSON result to FILE (default: the output)
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+    /// Offline checks printing a JSON report; exits 0 iff all passed
+    #[command(subcommand)]
+    Verify(VerifyCommand),
+    /// Check that a core share / aux info pair still signs under this
+    /// build's cggmp24 (same JSON as the WASM `check_share_compat`); exits 0
+    /// iff compatible
+    Check {
+        #[arg(long, value_name = "FILE")]
+        core: PathBuf,
+        #[arg(long, value_name = "FILE")]
+        aux: PathBuf,
+    },
+    /// Write the frozen wire-format test vectors (a dealer key, its signing
+    /// transcripts with reliable broadcast on and off, a manifest) to DIR;
+    /// the WASM `verify_vectors` replays them. Tests only
+    #[cfg(feature = "insecure-dev")]
+    GenVectors {
+        /// Created if missing; files in it are replaced
+        #[arg(long, value_name = "DIR")]
+        out: PathBuf,
+    },
+    /// Time fixed-seed local ceremonies; same JSON report as the WASM bench exports
+    #[command(subcommand)]
+    Bench(BenchCommand),
+    /// Calibrate this machine (under two seconds) and estimate a ceremony's
+    /// time and peak memory; same JSON as the WASM `estimate_*` exports
+    #[command(subcommand)]
+    Estimate(EstimateCommand),
+    /// Self-check this machine before it signs: entropy, prime search
+    /// speed, the given directories, bignum backend and a sign/verify
+    /// round trip. Exits 0 if all passed, 2 on warnings only, 1 on a failure
+    Doctor {
+        /// An `aux-pool` directory that must be writable
+        #[arg(long, value_name = "DIR")]
+        aux_pool: Option<PathBuf>,
+        /// A `dkg --checkpoint-dir` that must be writable
+        #[arg(long, value_name = "DIR")]
+        checkpoint_dir: Option<PathBuf>,
+    },
+    /// Print versions, curves and features as JSON (same shape as the WASM
+    /// `capabilities`)
+    Capabilities,
+    /// Convert every JSON share / aux / primes file in a directory to the
+    /// binary encoding, writing a manifest; exits 0 iff no file failed
+    Migrate {
+        #[arg(long, value_name = "DIR")]
+        in_dir: PathBuf,
+        /// Created if missing; existing files in it are never overwritten
+        #[arg(long, value_name = "DIR")]
+        out_dir: PathBuf,
+        /// Convert and verify, but write nothing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print the Bitcoin P2WPKH (bech32) address of a compressed secp256k1 key
+    Address {
+        /// 33-byte compressed key
+        #[arg(long, value_name = "HEX")]
+        pubkey: String,
+        #[arg(long, default_value = "mainnet", value_parser = ["mainnet", "testnet"])]
+        network: String,
+    },
+    /// Bundle a share with its metadata for cold storage, or restore one
+    #[command(subcommand)]
+    Backup(BackupCommand),
+    /// Print a signature as Bitcoin DER plus sighash byte (low-s enforced)
+    EncodeSig {
+        #[arg(long, value_name = "HEX")]
+        r: String,
+        #[arg(long, value_name = "HEX")]
+        s: String,
+        /// Sighash type byte (default SIGHASH_ALL)
+        #[arg(long, value_parser = parse_sighash_type, default_value = "0x01")]
+        sighash_type: u8,
+    },
+}
+
+/// `extract-pubkey --format`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PubkeyFormat {
+    /// 33-byte compressed key, hex
+    Hex,
+    /// EIP-55 checksummed Ethereum address (secp256k1 only)
+    Address,
+}
+
+#[derive(Subcommand)]
+pub enum PrimesCommand {
+    /// Check the base64 primes blobs on the input (one per line): format,
+    /// bit lengths and primality. One JSON report per line; exits 0 iff all
+    /// passed
+    Validate {
+        /// Miller-Rabin rounds per prime
+        #[arg(long, default_value_t = DEFAULT_MR_ROUNDS)]
+        rounds: u32,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AuxPoolCommand {
+    /// Generate sets into DIR (created if missing), one JSON file each;
+    /// prints each set's status line
+    Fill {
+        #[arg(long, value_name = "DIR")]
+        dir: PathBuf,
+        /// Number of parties (≥ 2)
+        #[arg(value_parser = parse_party_count)]
+        n: u16,
+        /// Number of sets
+        #[arg(default_value_t = 1)]
+        count: usize,
+    },
+    /// One JSON line per set in DIR: n, level, age, uses and whether
+    /// `take` would hand it out under the policy
+    Status {
+        #[arg(long, value_name = "DIR")]
+        dir: PathBuf,
+        #[command(flatten)]
+        policy: AuxPolicy,
+    },
+    /// Print the oldest set for N parties the policy allows (a
+    /// `dkg-with-aux` input line) and record the use in its file
+    Take {
+        #[arg(long, value_name = "DIR")]
+        dir: PathBuf,
+        /// Number of parties (≥ 2)
+        #[arg(value_parser = parse_party_count)]
+        n: u16,
+        #[command(flatten)]
+        policy: AuxPolicy,
+    },
+}
+
+/// When an `aux-pool` set may no longer be handed out. Unset means no limit.
+#[derive(Args)]
+pub struct AuxPolicy {
+    /// Refuse sets generated more than SECS seconds ago
+    #[arg(long, value_name = "SECS")]
+    pub max_age: Option<u64>,
+    /// Refuse sets already handed to N DKGs
+    #[arg(long, value_name = "N")]
+    pub max_uses: Option<u32>,
+}
+
+/// Both print the bundle's metadata as JSON; neither overwrites `--out`.
+#[derive(Subcommand)]
+pub enum BackupCommand {
+    /// Bundle a KeyShare or CoreKeyShare (base64 or raw) into `--out`
+    Create {
+        #[arg(long = "in", value_name = "FILE")]
+        share: PathBuf,
+        #[arg(long, value_name = "FILE")]
+        out: PathBuf,
+        #[arg(long)]
+        label: Option<String>,
+        /// Encrypt the share under the passphrase in environment variable VAR
+        #[arg(long, value_name = "VAR")]
+        passphrase_env: Option<String>,
+    },
+    /// Check a bundle and write the share inside to `--out`
+    Restore {
+        #[arg(long = "in", value_name = "FILE")]
+        bundle: PathBuf,
+        #[arg(long, value_name = "FILE")]
+        out: PathBuf,
+        /// Passphrase of an encrypted bundle, from environment variable VAR
+        #[arg(long, value_name = "VAR")]
+        passphrase_env: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BenchCommand {
+    /// t-of-(t + 1) secp256k1 signing, 2-of-3 by default (key generation
+    /// runs first, untimed)
+    Sign {
+        #[arg(long, default_value_t = 5)]
+        iterations: u32,
+        /// Co-signing parties (≥ 2)
+        #[arg(long, value_name = "N", value_parser = parse_party_count, default_value_t = bench::DEFAULT_SIGNERS)]
+        parties: u16,
+        /// Presign, then sign from the presignature
+        #[arg(long)]
+        presign: bool,
+        /// Ceremonies run at once, each on its own thread
+        #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrency: u32,
+    },
+    /// Keygen without the aux info phase
+    #[command(alias = "dkg-keygen")]
+    DkgKeygenOnly {
+        /// Number of parties (≥ 2)
+        #[arg(value_parser = parse_party_count)]
+        n: u16,
+        /// Signing threshold (2 ≤ t ≤ n)
+        threshold: u16,
+        #[arg(long, default_value_t = 5)]
+        iterations: u32,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EstimateCommand {
+    /// A local DKG (`dkg`, or `dkg-with-primes` with `--with-primes`)
+    Dkg {
+        /// Number of parties (≥ 2)
+        #[arg(value_parser = parse_party_count)]
+        n: u16,
+        /// Signing threshold (2 ≤ t ≤ n)
+        threshold: u16,
+        /// The primes are pregenerated: no prime search
+        #[arg(long)]
+        with_primes: bool,
+    },
+    /// One party's share of an ECDSA signing session
+    Sign {
+        /// Co-signing parties (≥ 2)
+        #[arg(long, value_name = "N", value_parser = parse_party_count, default_value_t = bench::DEFAULT_SIGNERS)]
+        parties: u16,
+    },
+}
+
+/// Arguments are optional here so that missing ones show up in the JSON
+/// report rather than as a usage error.
+#[derive(Subcommand)]
+pub enum VerifyCommand {
+    /// Combine a core share with its aux info and check the public key
+    Share {
+        #[arg(long, value_name = "FILE")]
+        core: Option<PathBuf>,
+        #[arg(long, value_name = "FILE")]
+        aux: Option<PathBuf>,
+        #[arg(long, value_name = "HEX")]
+        expect_pubkey: Option<String>,
+    },
+    /// Check an ECDSA signature over a 32-byte hash
+    Signature {
+        #[arg(long, value_name = "HEX")]
+        pubkey: Option<String>,
+        #[arg(long, value_name = "HEX")]
+        hash: Option<String>,
+        #[arg(long, value_name = "HEX")]
+        r: Option<String>,
+        #[arg(long, value_name = "HEX")]
+        s: Option<String>,
+    },
+}
+
+#[derive(Args)]
+pub struct DkgArgs {
+    /// Number of parties (≥ 2)
+    #[arg(value_parser = parse_party_count)]
+    pub n: u16,
+    /// Signing threshold (2 ≤ t ≤ n)
+    pub threshold: u16,
+    /// Execution id, 64 hex chars (default: random)
+    #[arg(value_parser = parse_eid)]
+    pub eid: Option<[u8; 32]>,
+    /// Derive the execution id for purpose `dkg` instead (as the WASM
+    /// `derive_eid` does)
+    #[arg(long, value_name = "WALLET_ID[:NONCE_HEX]", value_parser = parse_eid_seed, conflicts_with = "eid")]
+    pub derive_eid: Option<EidSeed>,
+}
+
+/// `--derive-eid` inputs.
+#[derive(Clone)]
+pub struct EidSeed {
+    pub wallet_id: String,
+    pub nonce: Vec<u8>,
+}
+
+impl EidSeed {
+    pub fn derive(&self, purpose: EidPurpose) -> [u8; 32] {
+        eid::derive_eid(&self.wallet_id, purpose, &self.nonce).expect("wallet id checked when parsed")
+    }
+}
+
+impl DkgArgs {
+    /// The given or derived execution id, or a fresh random one.
+    pub fn eid(&self) -> [u8; 32] {
+        if let Some(seed) = &self.derive_eid {
+            return seed.derive(EidPurpose::Dkg);
+        }
+        self.eid.unwrap_or_else(|| {
+            let mut eid = [0u8; 32];
+            getrandom::getrandom(&mut eid).expect("getrandom");
+            eid
+        })
+    }
+
+    fn validate(&self, max_parties: u16) -> Result<(), String> {
+        party_limits::check_key_params_within(self.n, self.threshold, max_parties)
+    }
+}
+
+fn parse_party_count(s: &str) -> Result<u16, String> {
+    let n: u16 = s.parse().map_err(|e| format!("{e}"))?;
+    if n < 2 {
+        return Err(format!("need at least 2 parties, got {n}"));
+    }
+    Ok(n)
+}
+
+fn parse_eid(s: &str) -> Result<[u8; 32], String> {
+    if s.len() != 64 {
+        return Err(format!("must be 64 hex chars, got {}", s.len()));
+    }
+    let mut eid = [0u8; 32];
+    hex::decode_to_slice(s, &mut eid).map_err(|e| format!("{e}"))?;
+    Ok(eid)
+}
+
+/// `WALLET_ID` or `WALLET_ID:NONCE_HEX`; the nonce follows the last colon.
+fn parse_eid_seed(s: &str) -> Result<EidSeed, String> {
+    let (wallet_id, nonce) = match s.rsplit_once(':') {
+        Some((wallet_id, nonce)) => (wallet_id, hex::decode(nonce).map_err(|e| format!("nonce: {e}"))?),
+        None => (s, Vec::new()),
+    };
+    if wallet_id.is_empty() {
+        return Err("wallet id is empty".into());
+    }
+    Ok(EidSeed {
+        wallet_id: wallet_id.to_string(),
+        nonce,
+    })
+}
+
+/// `0x`-prefixed hex or decimal byte.
+fn parse_sighash_type(s: &str) -> Result<u8, String> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u8::from_str_radix(hex, 16),
+        None => s.parse(),
+    }
+    .map_err(|e| format!("not a byte: {e}"))
+}
+
+impl Cli {
+    /// Parse the process arguments, exiting with a usage error on failure.
+    pub fn parse_args() -> Self {
+        Self::try_parse_args(std::env::args()).unwrap_or_else(|e| e.exit())
+    }
+
+    /// Parse `args`, including the cross-argument checks clap can't express.
+    pub fn try_parse_args(args: impl IntoIterator<Item = String>) -> Result<Self, clap::Error> {
+        let cli = Self::try_parse_from(legacy_argv(args.into_iter().collect()))?;
+        let dkg = match &cli.command {
+            Command::Dkg { args: a, .. }
+            | Command::DkgWithPrimes { args: a, .. }
+            | Command::DkgWithAux { args: a, .. }
+            | Command::FrostDkg(a) => Some(a),
+            _ => None,
+        };
+        if let Some(Err(e)) = dkg.map(|args| args.validate(cli.global.max_parties)) {
+            return Err(Self::command().error(ErrorKind::ValueValidation, e));
+        }
+        Ok(cli)
+    }
+}
+
+/// `guardian-gen-primes <count>` predates subcommands; rewrite it to
+/// `primes <count>` with a warning. Deprecated — remove in the next release.
+fn legacy_argv(mut args: Vec<String>) -> Vec<String> {
+    if args.get(1).is_some_and(|a| a.parse::<usize>().is_ok()) {
+        eprintln!("warning: `guardian-gen-primes <count>` is deprecated, use `guardian-gen-primes primes <count>`");
+        args.insert(1, "primes".into());
+    }
+    args
+}
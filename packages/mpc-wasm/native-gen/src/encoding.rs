@@ -0,0 +1,102 @@
+//! How bytes are written into the JSON lines.
+//!
+//! One rule for every command: blobs (shares, aux info, primes, encrypted
+//! envelopes) are standard padded base64, fixed-size values (keys, hashes,
+//! ids, signature halves, tags) are lowercase hex. Hex input may carry a
+//! `0x` prefix and either case, as on the command line.
+//!
+//! | field                                          | encoding |
+//! |------------------------------------------------|----------|
+//! | `dkg` `shares[].core_share`, `shares[].aux_info` | base64 |
+//! | `dkg` `public_key`                             | hex      |
+//! | `sign` init `core_share`, `aux_info`           | base64   |
+//! | `sign` init `eid`, `message_hash`, `message`   | hex      |
+//! | `sign` reply `r`, `s`, `signatures[].r`, `.s`  | hex      |
+//! | message `payload`                              | base64 (text for `abort`) |
+//! | message `binding`, `auth_tag`                  | hex      |
+//!
+//! The WASM crate passes bytes as `Uint8Array`, except for the same
+//! message envelope (its `WasmSignMessage` is wire-compatible) and the
+//! audit record's hex fields. Fields that were already following the rule
+//! keep their exact output, so existing callers see no change.
+//!
+//! Message `payload` stays a plain string: abort notices carry text there,
+//! and message tags are computed over the string as sent.
+
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Bytes written as lowercase hex; read with or without `0x`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HexBytes(pub Vec<u8>);
+
+impl HexBytes {
+    /// Parse `text`, naming `field` in the error.
+    pub fn parse(field: &str, text: &str) -> Result<Self, String> {
+        let digits = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")).unwrap_or(text);
+        hex::decode(digits)
+            .map(HexBytes)
+            .map_err(|e| format!("INVALID_HEX: {field}: {e}"))
+    }
+}
+
+impl From<&[u8]> for HexBytes {
+    fn from(bytes: &[u8]) -> Self {
+        HexBytes(bytes.to_vec())
+    }
+}
+
+impl Serialize for HexBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for HexBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        HexBytes::parse("hex field", &text).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Bytes written as standard padded base64.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct B64Bytes(pub Vec<u8>);
+
+impl B64Bytes {
+    /// Parse `text`, naming `field` in the error.
+    pub fn parse(field: &str, text: &str) -> Result<Self, String> {
+        base64::engine::general_purpose::STANDARD
+            .decode(text)
+            .map(B64Bytes)
+            .map_err(|e| {
+                // Hex digits are base64 characters too, so hex only fails
+                // here on its length; point at the likely mix-up
+                let hint = if !text.is_empty() && text.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    " (looks like hex; this field is base64)"
+                } else {
+                    ""
+                };
+                format!("INVALID_BASE64: {field}: {e}{hint}")
+            })
+    }
+}
+
+impl From<Vec<u8>> for B64Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        B64Bytes(bytes)
+    }
+}
+
+impl Serialize for B64Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for B64Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        B64Bytes::parse("base64 field", &text).map_err(serde::de::Error::custom)
+    }
+}
@@ -2,25 +2,547 @@
 //!
 //! Runs the complete CGGMP24 two-phase DKG ceremony (aux_info_gen + keygen)
 //! natively with the rug/GMP backend, which is 10-100x faster than WASM
-//! for big number operations.
+//! for big number operations. Built without the default `gmp` feature it
+//! runs on the pure-Rust num-bigint backend instead — slower, but with no
+//! GMP to build (musl, cross-compiled appliances); `--version` and
+//! `capabilities` (`bignum_backend`) say which one a binary has.
 //!
-//! Output: JSON to stdout with shares and public key.
+//! Output: JSON to stdout (or `--output FILE`) with shares and public key.
 //!
-//! Usage:
-//!   guardian-gen-primes dkg <n> <threshold> <eid_hex>
-//!   guardian-gen-primes primes <count>
+//! Usage (`--help` for the full list; arguments are parsed in `cli`):
+//!   guardian-gen-primes dkg <n> <threshold> [eid_hex | --derive-eid WALLET_ID[:NONCE_HEX]]
+//!                          [--encrypt-passphrase-env VAR]
+//!                          [--trusted-dealer]   (`insecure-dev` builds only)
+//!                          [--checkpoint-dir DIR [--checkpoint-passphrase-env VAR]]
+//!   guardian-gen-primes primes [count | --count N] [--out-dir DIR]
+//!   guardian-gen-primes dkg-with-primes <n> <threshold> [--primes-dir DIR]   (else base64 lines on stdin)
+//!                          [--used-primes FILE] [--allow-unsafe-prime-reuse]
+//!   guardian-gen-primes primes validate [--rounds N]   (base64 blobs on stdin)
+//!   guardian-gen-primes gen-aux <n> [count]
+//!   guardian-gen-primes dkg-with-aux <n> <threshold> [--aux-pool DIR]   (else a gen-aux line on stdin)
+//!                          [--max-age SECS] [--max-uses N]
+//!   guardian-gen-primes aux-pool fill --dir DIR <n> [count]
+//!   guardian-gen-primes aux-pool status|take --dir DIR [<n>] [--max-age SECS] [--max-uses N]
+//!   guardian-gen-primes frost-dkg <n> <threshold> [eid_hex]
+//!   guardian-gen-primes refresh-aux [eid_hex] [--primes FILE]   (core shares on stdin)
+//!   guardian-gen-primes sign         (stdin/stdout JSON lines; one session, or
+//!                                     many keyed by `session_id`)
+//!   guardian-gen-primes frost-sign   (stdin/stdout JSON lines, like `sign`)
+//!   guardian-gen-primes dkg-party    (one party of a distributed DKG, JSON lines like `sign`)
+//!   guardian-gen-primes export-key --confirm EXPORT_PRIVATE_KEY   (core shares on stdin)
+//!   guardian-gen-primes import-tsslib --threshold T FILE...   (tss-lib save data)
+//!   guardian-gen-primes extract-pubkey [--in FILE] [--format hex|address]
+//!   guardian-gen-primes commitment [--in FILE]
+//!   guardian-gen-primes commitment --verify [--expect-pubkey HEX]   (hex commitments on stdin)
+//!   guardian-gen-primes combine [--core F --aux F] [--out FILE]
+//!   guardian-gen-primes verify share --core F --aux F [--expect-pubkey HEX]
+//!   guardian-gen-primes verify signature --pubkey HEX --hash HEX --r HEX --s HEX
+//!   guardian-gen-primes check --core F --aux F   (does the pair still sign under this cggmp24?)
+//!   guardian-gen-primes bench sign [--iterations N] [--parties N] [--presign] [--concurrency N]
+//!   guardian-gen-primes bench dkg-keygen-only <n> <threshold> [--iterations N]
+//!   guardian-gen-primes estimate dkg <n> <threshold> [--with-primes]
+//!   guardian-gen-primes estimate sign [--parties N]
+//!   guardian-gen-primes doctor [--aux-pool DIR] [--checkpoint-dir DIR]
+//!   guardian-gen-primes gen-vectors --out DIR   (insecure-dev builds; wire-format test vectors)
+//!   guardian-gen-primes capabilities   (same JSON as the WASM `capabilities`)
+//!   guardian-gen-primes migrate --in-dir D --out-dir E [--dry-run]   (JSON → binary shares)
+//!   guardian-gen-primes address --pubkey HEX [--network mainnet|testnet]
+//!   guardian-gen-primes encode-sig --r HEX --s HEX [--sighash-type 0x01]
+//!   guardian-gen-primes backup create --in SHARE --out FILE [--label L] [--passphrase-env VAR]
+//!   guardian-gen-primes backup restore --in FILE --out SHARE [--passphrase-env VAR]
+//!
+//! Every subcommand accepts `--security-level 128|192` (default 128); the DKG
+//! subcommands also accept `--curve secp256k1|secp256r1` (default secp256k1).
+//! Subcommands that read stdin / write stdout take `--input FILE` /
+//! `--output FILE` instead. New keys have at most `--max-parties N`
+//! (default 16) parties; more fail with `N_TOO_LARGE`.
+//! Non-default blobs carry a `share_format` header; default output is
+//! unchanged. `sign` reads both from the share headers.
+//!
+//! Blobs in the JSON lines are base64, fixed-size values (keys, hashes,
+//! eids, `r` / `s`) hex; hex input may start with `0x`. The `encoding`
+//! module has the per-field table.
+//!
+//! `--progress json` replaces the human stderr lines with one JSON event per
+//! line (`{"event":"phase_start","phase":"aux_info","n":3}`, `prime_done`,
+//! `dkg_done`, ...); see [`Event`] for the schema.
+//!
+//! SIGTERM / SIGINT never cut an output line short: `sign` reports
+//! `SHUTTING_DOWN` for each session awaiting input and exits 0 after dropping
+//! its key material; `primes` stops after the current pair; DKG stops
+//! between parties.
+//!
+//! `sign` keeps the last `--share-cache N` (default 8) combined key shares,
+//! keyed by `sha256(core_share || aux_info)`, so repeated signings with one
+//! wallet skip deserialising it; `metrics.cache_hit` reports a hit. A
+//! multi-session `destroy_key` line evicts one.
+//!
+//! `--encoding cbor` writes new blobs in CBOR rather than JSON, and makes
+//! `sign` / `dkg-party` exchange CBOR protocol messages (a `sign` init's
+//! `encoding` overrides it per session); a peer on the other encoding has
+//! its messages rejected with `ENCODING_MISMATCH`. FROST messages stay JSON.
+//!
+//! A `sign` init's `digest` (`"sha256"`, the default, or `"keccak256"`)
+//! picks the hash the protocol runs with; a peer on another digest has its
+//! messages rejected with `DIGEST_MISMATCH`, from its first.
+//!
+//! `sign` brings `s` to its low form (`n - s` above `n / 2`) and says so
+//! with `normalized: true` on the completing reply; an init with
+//! `"normalize_s": false` gets the protocol's raw `s` instead.
+//!
+//! Every `sign` reply carries the `outgoing_bytes` of its messages. With
+//! `max_outgoing_bytes` in the init, a reply over it carries what fits and
+//! `more: true`; a `[]` line (`"messages": []` in multi-session mode)
+//! fetches the rest. A single-session run exits only once nothing is held.
+//!
+//! Every `sign` reply acknowledges its line: `accepted` holds the indices
+//! of the messages taken, `rejected` a `{ index, code, detail }` per
+//! message refused — wrong version, binding, encoding, digest or auth tag,
+//! over the limits, or a payload that doesn't decode. The rest of the line
+//! is delivered regardless, so a relay knows what to stop resending. Three
+//! messages with the second corrupted give `accepted: [0, 2]` and one
+//! rejection at index 1. `dkg-party` still fails on a rejected message.
+//!
+//! With `--audit-log FILE`, `sign` appends the audit record of each session
+//! it completes (same JSON as the WASM `sign_audit_record`) to FILE as one
+//! line; the completing reply carries it as `audit` too.
+//!
+//! `sign` / `frost-sign` give up after `--timeout SECS` (default 120) without
+//! input, or at once on stdin EOF: they drop their key material, print
+//! `{"error":{"code":"TIMEOUT"|"EOF",...}}` and exit with code 3.
+//!
+//! With `--encrypt-passphrase-env VAR`, every emitted core_share / aux_info
+//! is encrypted (Argon2id + AES-256-GCM, same envelope as the WASM
+//! `encrypt_share`) under the passphrase read from environment variable VAR.
+//!
+//! `dkg --checkpoint-dir DIR` saves the aux infos once Phase A is done; a
+//! rerun under the same eid after a crash in keygen picks up there instead
+//! of regenerating primes. The checkpoint is deleted when the DKG completes.
+//!
+//! With `--entropy-source PATH` (e.g. a hardware TRNG device), 64 bytes are
+//! read from PATH at startup and mixed into every draw of the process, prime
+//! generation and protocol state machines alike (`rng`, as the WASM
+//! `extra_entropy`); a source yielding fewer than 32 bytes stops the run
+//! with `ENTROPY_SOURCE_UNAVAILABLE`.
+//!
+//! Shares may carry a metadata envelope (label, environment; see the WASM
+//! `attach_metadata`), which every command looks through and `verify share`
+//! reports as `metadata`. With `--expected-environment ENV`, `sign`,
+//! `combine` and `verify share` refuse a share tagged for another
+//! environment with `ENVIRONMENT_MISMATCH`.
+//!
+//! `bench` prints one JSON line (p50 / p95 latency, `per_second`, traffic)
+//! that is stable across builds, so CI can compare it with a baseline run.
+//! `bench sign --concurrency N` runs N ceremonies at a time on threads.
+//! `estimate` prints the WASM `estimate_dkg` / `estimate_sign` JSON for
+//! this binary: its calibration measures this machine and bignum backend,
+//! so a native estimate says nothing about a browser, and vice versa.
+//!
+//! `doctor` checks a signer appliance before it is put to work: the OS rng
+//! (and `/dev/urandom`), a short prime-search speed burst against what the
+//! bignum backend should manage, the backend itself, that `--aux-pool` /
+//! `--checkpoint-dir` take a new file, and a dealer-keyed sign / verify
+//! round trip (the fixed-seed `bench` key outside `insecure-dev` builds).
+//! It prints a line per check, then one JSON report, and exits 0 when all
+//! passed, 2 on warnings only and 1 on any failure.
+//!
+//! `gen-vectors --out DIR` (insecure-dev builds) writes the frozen test
+//! vectors for other implementations of the wire format — `key.json`,
+//! `sign-reliable.json`, `sign-unreliable.json` and a `manifest.json` of
+//! their SHA-256s — from a fixed seed, so reruns are byte-identical until
+//! the format changes. The WASM `verify_vectors` replays them. Pointed at
+//! `packages/mpc-wasm/fixtures` it refreshes the key the `fixtures` feature
+//! embeds.
 
-use std::collections::VecDeque;
-use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::mem::ManuallyDrop;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
 
 use base64::Engine;
-use cggmp24::security_level::SecurityLevel128;
-use cggmp24::supported_curves::Secp256k1;
-use generic_ec::Scalar;
-use rand::rngs::OsRng;
+use cggmp24::key_share::AnyKeyShare;
+use cggmp24::security_level::SecurityLevel;
+use generic_ec::coords::HasAffineX;
+use generic_ec::{Curve, Point, Scalar};
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
 use round_based::state_machine::{ProceedResult, StateMachine};
 use round_based::{Incoming, MessageDestination, MessageType};
+use serde::de::DeserializeOwned;
+use signal_hook::consts::{SIGINT, SIGTERM};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+mod cli;
+mod encoding;
+
+use cli::{
+    AuxPolicy, AuxPoolCommand, BackupCommand, BenchCommand, Cli, Command, EstimateCommand, PrimesCommand, ProgressFormat,
+    PubkeyFormat, VerifyCommand,
+};
+use encoding::{B64Bytes, HexBytes};
+
+// Shared with the WASM crate; not every helper is used by the CLI.
+#[allow(dead_code)]
+#[path = "../../src/share_crypto.rs"]
+mod share_crypto;
+
+#[path = "../../src/audit.rs"]
+mod audit;
+
+#[path = "../../src/aux_refresh.rs"]
+mod aux_refresh;
+
+#[path = "../../src/aux_set.rs"]
+mod aux_set;
+
+// `is_bundle` only serves the WASM `get_share_info`
+#[allow(dead_code)]
+#[path = "../../src/backup.rs"]
+mod backup;
+
+#[path = "../../src/bench.rs"]
+mod bench;
+
+#[path = "../../src/estimate.rs"]
+mod estimate;
+
+#[path = "../../src/binding.rs"]
+mod binding;
+
+#[path = "../../src/bitcoin.rs"]
+mod bitcoin;
+
+#[path = "../../src/capabilities.rs"]
+mod capabilities;
+
+#[path = "../../src/clock.rs"]
+mod clock;
+
+#[path = "../../src/commitment.rs"]
+mod commitment;
+
+// `EidPurpose::parse` / `as_str` only serve the WASM exports
+#[allow(dead_code)]
+#[path = "../../src/eid.rs"]
+mod eid;
+
+#[path = "../../src/drive_guard.rs"]
+mod drive_guard;
+
+#[path = "../../src/fingerprint.rs"]
+mod fingerprint;
+
+#[path = "../../src/hex_codec.rs"]
+mod hex_codec;
+
+#[path = "../../src/interop.rs"]
+mod interop;
+
+#[path = "../../src/key_export.rs"]
+mod key_export;
+
+// `check_key_material` only backs the WASM export
+#[allow(dead_code)]
+#[path = "../../src/key_material.rs"]
+mod key_material;
+
+#[path = "../../src/limits.rs"]
+mod limits;
+
+// `check_party_indices` / `check_party_count` only serve the WASM phase exports
+#[allow(dead_code)]
+#[path = "../../src/party_limits.rs"]
+mod party_limits;
+
+#[path = "../../src/message_auth.rs"]
+mod message_auth;
+
+#[path = "../../src/message_codec.rs"]
+mod message_codec;
+
+// `release_pinned` and the key registry counters only serve the WASM crate
+#[allow(dead_code)]
+#[path = "../../src/metrics.rs"]
+mod metrics;
+
+#[path = "../../src/migrate.rs"]
+mod migrate;
+
+#[path = "../../src/protocol_digest.rs"]
+mod protocol_digest;
+
+#[path = "../../src/protocol_failure.rs"]
+mod protocol_failure;
+
+// `RejectedMessage` and `SeenMessages` serve the CLI; the routing helpers back WASM exports
+#[allow(dead_code)]
+#[path = "../../src/relay.rs"]
+mod relay;
+
+#[path = "../../src/rng.rs"]
+mod rng;
+
+// `capacity` only serves the WASM `get_config`
+#[allow(dead_code)]
+#[path = "../../src/share_cache.rs"]
+mod share_cache;
+
+#[path = "../../src/share_compat.rs"]
+mod share_compat;
+
+#[allow(dead_code)]
+#[path = "../../src/share_format.rs"]
+mod share_format;
+
+// `attach_metadata` and `keygen_indices` only back the WASM exports
+#[allow(dead_code)]
+#[path = "../../src/share_metadata.rs"]
+mod share_metadata;
+
+#[path = "../../src/primes_check.rs"]
+mod primes_check;
+
+#[path = "../../src/primes_reuse.rs"]
+mod primes_reuse;
+
+// `recovery_id_from_v` only backs the WASM `recover_public_key`
+#[allow(dead_code)]
+#[path = "../../src/signature.rs"]
+mod signature;
+
+// `simulate()` below is the CLI's own loop (shutdown-aware); the shared
+// module backs `bench`.
+#[allow(dead_code)]
+#[path = "../../src/simulate.rs"]
+mod simulate;
+
+// `gen-vectors` writes the set; `verify_vectors` backs the WASM export
+#[cfg(feature = "insecure-dev")]
+#[allow(dead_code)]
+#[path = "../../src/test_vectors.rs"]
+mod test_vectors;
+
+use audit::{AuditRecord, AuditTrail};
+use aux_set::AuxInfoOutput;
+use drive_guard::YieldGuard;
+use limits::{LimitOverrides, MessageLimits};
+use message_auth::{AuthKey, Envelope};
+use metrics::{MemoryStats, PhaseTimes, RoundMetrics, SessionMetrics};
+use protocol_digest::{with_digest, ProtocolDigest};
+use protocol_failure::ProtocolFailure;
+use relay::{RejectedMessage, SeenMessages};
+use rng::MixedRng;
+use share_cache::ShareCache;
+use share_format::{with_curve, with_security_level, BlobKind, CurveId, Encoding, Level};
+
+// ---------------------------------------------------------------------------
+// Input / output (`--input FILE` / `--output FILE`, else stdin / stdout)
+// ---------------------------------------------------------------------------
+
+static INPUT: OnceLock<PathBuf> = OnceLock::new();
+static OUTPUT: OnceLock<Mutex<File>> = OnceLock::new();
+/// `--audit-log FILE`
+static AUDIT_LOG: OnceLock<PathBuf> = OnceLock::new();
+
+fn open_input_output(input: Option<PathBuf>, output: Option<&Path>) {
+    if let Some(path) = input {
+        INPUT.set(path).expect("input set once");
+    }
+    if let Some(path) = output {
+        let file = File::create(path).unwrap_or_else(|e| {
+            eprintln!("cannot create {}: {e}", path.display());
+            std::process::exit(1);
+        });
+        OUTPUT.set(Mutex::new(file)).expect("output set once");
+    }
+}
+
+/// The `--input` file, or stdin.
+fn input() -> Box<dyn Read + Send> {
+    match INPUT.get() {
+        Some(path) => Box::new(File::open(path).unwrap_or_else(|e| {
+            eprintln!("cannot open {}: {e}", path.display());
+            std::process::exit(1);
+        })),
+        None => Box::new(std::io::stdin()),
+    }
+}
+
+/// All non-empty input lines (for the subcommands that read their input
+/// in one go).
+fn read_input_lines() -> Vec<String> {
+    let mut text = String::new();
+    input().read_to_string(&mut text).expect("failed to read input");
+    text.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.to_string())
+        .collect()
+}
+
+/// Result sink: the `--output` file, or stdout.
+struct Output;
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match OUTPUT.get() {
+            Some(file) => file.lock().expect("output lock").write(buf),
+            None => std::io::stdout().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match OUTPUT.get() {
+            Some(file) => file.lock().expect("output lock").flush(),
+            None => std::io::stdout().flush(),
+        }
+    }
+}
+
+/// Write one complete result line and flush it.
+fn emit_line(line: &str) {
+    writeln!(Output, "{line}").expect("write output");
+    Output.flush().expect("flush output");
+}
+
+// ---------------------------------------------------------------------------
+// Protocol randomness (`--entropy-source PATH`)
+// ---------------------------------------------------------------------------
+
+/// Bytes read from `--entropy-source` at startup.
+const ENTROPY_SOURCE_BYTES: usize = 64;
+
+/// Read seed material from `path` (a file, or a character device such as a
+/// hardware TRNG) for `rng::MixedRng` to mix in. Fails with
+/// `ENTROPY_SOURCE_UNAVAILABLE` if it can't be read or yields fewer than
+/// `rng::MIN_EXTRA_ENTROPY` bytes.
+fn load_entropy_source(path: &Path) -> Result<(), String> {
+    let mut bytes = zeroize::Zeroizing::new(Vec::with_capacity(ENTROPY_SOURCE_BYTES));
+    File::open(path)
+        .and_then(|file| file.take(ENTROPY_SOURCE_BYTES as u64).read_to_end(&mut bytes))
+        .map_err(|e| format!("ENTROPY_SOURCE_UNAVAILABLE: {}: {e}", path.display()))?;
+    if bytes.len() < rng::MIN_EXTRA_ENTROPY {
+        return Err(format!(
+            "ENTROPY_SOURCE_UNAVAILABLE: {} yielded {} bytes, at least {} are needed",
+            path.display(),
+            bytes.len(),
+            rng::MIN_EXTRA_ENTROPY
+        ));
+    }
+    if rng::has_extra() {
+        return Err("entropy source loaded twice".into());
+    }
+    rng::add_extra(&bytes);
+    Ok(())
+}
+
+/// Rng for one party's protocol state machine, seeded from `MixedRng`.
+fn protocol_rng() -> Result<ChaCha20Rng, String> {
+    ChaCha20Rng::from_rng(MixedRng).map_err(|e| format!("ENTROPY_UNAVAILABLE: getrandom failed: {e}"))
+}
+
+// ---------------------------------------------------------------------------
+// Shutdown (SIGTERM / SIGINT)
+// ---------------------------------------------------------------------------
+
+/// Set by the signal handlers; long-running work polls it at safe points.
+fn shutdown_flag() -> &'static Arc<AtomicBool> {
+    static FLAG: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+    FLAG.get_or_init(Default::default)
+}
+
+fn shutting_down() -> bool {
+    shutdown_flag().load(Ordering::Relaxed)
+}
+
+/// Turn SIGTERM / SIGINT into a shutdown request instead of dying mid-write.
+/// A second signal while the first is being handled exits immediately.
+fn install_signal_handlers() {
+    for signal in [SIGTERM, SIGINT] {
+        signal_hook::flag::register_conditional_shutdown(signal, 1, Arc::clone(shutdown_flag()))
+            .and_then(|_| signal_hook::flag::register(signal, Arc::clone(shutdown_flag())))
+            .expect("install signal handler");
+    }
+}
+
+/// `Err(SHUTTING_DOWN: progress)` once a shutdown was requested, so DKG
+/// stops between parties and drops what it holds on the way out.
+fn ensure_running(progress: impl FnOnce() -> String) -> Result<(), String> {
+    if shutting_down() {
+        return Err(format!("SHUTTING_DOWN: {}", progress()));
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Progress reporting (stderr; `--progress json` for machine consumers)
+// ---------------------------------------------------------------------------
+
+static PROGRESS_JSON: AtomicBool = AtomicBool::new(false);
+
+/// One progress event. Under `--progress json` each is written to stderr as
+/// a single JSON line tagged by `event`; the schema is shared by every
+/// subcommand.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a> {
+    /// `phase` is `primes`, `aux_info`, `keygen`, `frost_keygen` or
+    /// `trusted_dealer`
+    PhaseStart { phase: &'static str, n: u16 },
+    PhaseDone { phase: &'static str, secs: f64 },
+    /// `secs` counts from the start of the primes phase
+    PrimeDone { party: usize, secs: f64 },
+    InputRead { what: &'static str, count: usize },
+    AuxSetDone { set: usize, count: usize, secs: f64 },
+    DkgDone { secs: f64 },
+    CheckpointSaved { path: String },
+    CheckpointResumed { path: String },
+    SessionCreated {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        session_id: Option<&'a str>,
+        party: u16,
+    },
+    SignDone { secs: f64 },
+    SessionsDropped { count: usize },
+    /// A `doctor` check finished
+    CheckDone {
+        check: &'static str,
+        status: CheckStatus,
+        detail: &'a str,
+    },
+    Shutdown { detail: String },
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        session_id: Option<&'a str>,
+        message: &'a str,
+    },
+}
+
+/// Report `event`: a JSON line under `--progress json`, otherwise the human
+/// `text` (worded as before the flag existed).
+fn progress(event: Event<'_>, text: impl FnOnce() -> String) {
+    if PROGRESS_JSON.load(Ordering::Relaxed) {
+        progress_json(event);
+    } else {
+        eprintln!("{}", text());
+    }
+}
+
+/// Report an event that has no human-format line.
+fn progress_json(event: Event<'_>) {
+    if PROGRESS_JSON.load(Ordering::Relaxed) {
+        eprintln!("{}", serde_json::to_string(&event).expect("serialize progress event"));
+    }
+}
+
+/// Seconds since `start`, to the 0.1s the human format prints.
+fn secs_since(start: Instant) -> f64 {
+    (start.elapsed().as_secs_f64() * 10.0).round() / 10.0
+}
 
 // ---------------------------------------------------------------------------
 // Simulation (same logic as simulate.rs in WASM crate)
@@ -43,6 +565,9 @@ where
             if outputs[i].is_some() {
                 continue;
             }
+            ensure_running(|| format!("{done}/{n} parties finished"))?;
+            let sender = party_limits::index(i)?;
+            let mut yields = YieldGuard::default();
             loop {
                 if wants_msg[i] {
                     if let Some(msg) = queues[i].pop_front() {
@@ -54,14 +579,18 @@ where
                         break;
                     }
                 }
-                match parties[i].proceed() {
+                let proceeded = parties[i].proceed();
+                yields
+                    .step(matches!(proceeded, ProceedResult::Yielded))
+                    .map_err(|e| format!("party {i}: {e}"))?;
+                match proceeded {
                     ProceedResult::SendMsg(outgoing) => match outgoing.recipient {
                         MessageDestination::AllParties => {
                             for j in 0..n {
                                 if j != i {
                                     queues[j].push_back(Incoming {
                                         id: next_id,
-                                        sender: i as u16,
+                                        sender,
                                         msg_type: MessageType::Broadcast,
                                         msg: outgoing.msg.clone(),
                                     });
@@ -70,9 +599,12 @@ where
                             }
                         }
                         MessageDestination::OneParty(dest) => {
-                            queues[dest as usize].push_back(Incoming {
+                            let queue = queues
+                                .get_mut(usize::from(dest))
+                                .ok_or_else(|| format!("party {i} sent to unknown party {dest}"))?;
+                            queue.push_back(Incoming {
                                 id: next_id,
-                                sender: i as u16,
+                                sender,
                                 msg_type: MessageType::P2P,
                                 msg: outgoing.msg,
                             });
@@ -117,71 +649,230 @@ where
 #[derive(Serialize)]
 struct DkgOutput {
     shares: Vec<DkgShare>,
-    /// hex-encoded compressed public key (33 bytes)
-    public_key: String,
+    /// Compressed public key (33 bytes)
+    public_key: HexBytes,
+    /// first 16 hex chars of sha256(compressed public key)
+    wallet_id: String,
+    /// `dkg-with-primes`: fingerprint of each party's primes, for
+    /// `--used-primes`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    primes_fingerprints: Vec<String>,
 }
 
 #[derive(Serialize)]
 struct DkgShare {
-    /// base64-encoded serialized CoreKeyShare
-    core_share: String,
-    /// base64-encoded serialized AuxInfo
-    aux_info: String,
+    /// Serialized CoreKeyShare
+    core_share: B64Bytes,
+    /// Serialized AuxInfo
+    aux_info: B64Bytes,
+    /// first 16 hex chars of sha256(serialized CoreKeyShare), taken before
+    /// any `--encrypt`
+    share_fingerprint: String,
+}
+
+/// Replace every share in `output` with its encrypted envelope.
+fn encrypt_dkg_output(output: &mut DkgOutput, passphrase: &str) -> Result<(), String> {
+    let params = share_crypto::KdfParams::default();
+    for share in output.shares.iter_mut() {
+        let core = zeroize::Zeroizing::new(std::mem::take(&mut share.core_share.0));
+        let aux = zeroize::Zeroizing::new(std::mem::take(&mut share.aux_info.0));
+        share.core_share = share_crypto::encrypt_share(&core, passphrase, params)?.into();
+        share.aux_info = share_crypto::encrypt_share(&aux, passphrase, params)?.into();
+    }
+    Ok(())
+}
+
+/// Replace every FROST share in `output` with its encrypted envelope.
+fn encrypt_frost_shares(output: &mut FrostDkgOutput, passphrase: &str) -> Result<(), String> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let params = share_crypto::KdfParams::default();
+    for (i, share) in output.shares.iter_mut().enumerate() {
+        let plain = zeroize::Zeroizing::new(
+            b64.decode(&*share).map_err(|e| format!("decode frost share {i}: {e}"))?,
+        );
+        *share = b64.encode(share_crypto::encrypt_share(&plain, passphrase, params)?);
+    }
+    Ok(())
+}
+
+/// Resolve `--encrypt-passphrase-env VAR` to the passphrase stored in VAR.
+fn passphrase_from_env(var: Option<String>) -> Option<String> {
+    let var = var?;
+    match std::env::var(&var) {
+        Ok(p) if !p.is_empty() => Some(p),
+        _ => {
+            eprintln!("environment variable {var} is unset or empty");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Print a DKG result as JSON, encrypting the shares first if requested.
+fn emit_dkg_output(mut output: DkgOutput, passphrase: Option<&str>) {
+    if let Some(passphrase) = passphrase {
+        if let Err(e) = encrypt_dkg_output(&mut output, passphrase) {
+            eprintln!("share encryption failed: {e}");
+            std::process::exit(1);
+        }
+    }
+    emit_line(&serde_json::to_string(&output).expect("serialize output"));
 }
 
 // ---------------------------------------------------------------------------
 // Full DKG (generates primes inline — slow)
 // ---------------------------------------------------------------------------
 
-fn run_dkg(n: u16, threshold: u16, eid_bytes: &[u8]) -> Result<DkgOutput, String> {
+/// With a `checkpoint`, the aux infos are saved once Phase A is done and a
+/// saved set for the same ceremony skips primes and Phase A altogether (see
+/// [`DkgCheckpoint`]).
+fn run_dkg<E, L>(
+    n: u16,
+    threshold: u16,
+    eid_bytes: &[u8],
+    curve: CurveId,
+    level: Level,
+    checkpoint: Option<&DkgCheckpoint>,
+) -> Result<DkgOutput, String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+{
+    let Some(checkpoint) = checkpoint else {
+        let primes_list = gen_primes_inline::<L>(n)?;
+        return run_dkg_inner::<E, L>(n, threshold, eid_bytes, curve, level, primes_list);
+    };
+    let aux_infos = match checkpoint.load::<L>(n, threshold, eid_bytes, level)? {
+        Some(aux_infos) => {
+            let path = checkpoint.path(eid_bytes).display().to_string();
+            progress(Event::CheckpointResumed { path: path.clone() }, || {
+                format!("Resuming from checkpoint {path}: skipping primes and Phase A")
+            });
+            aux_infos
+        }
+        None => {
+            let primes_list = gen_primes_inline::<L>(n)?;
+            let aux_infos = run_aux_info_phase::<L>(n, eid_bytes, primes_list)?;
+            let path = checkpoint.save(n, threshold, eid_bytes, level, &aux_infos)?;
+            let path = path.display().to_string();
+            progress(Event::CheckpointSaved { path: path.clone() }, || format!("Checkpoint written to {path}"));
+            aux_infos
+        }
+    };
+    let output = run_keygen_phase::<E, L>(n, threshold, eid_bytes, curve, level, aux_infos)?;
+    checkpoint.remove(eid_bytes)?;
+    Ok(output)
+}
+
+/// Fresh Paillier primes for `n` parties, generated here (the slow part).
+fn gen_primes_inline<L: SecurityLevel>(n: u16) -> Result<Vec<cggmp24::PregeneratedPrimes<L>>, String> {
     let mut primes_list = Vec::new();
-    let prime_start = std::time::Instant::now();
+    let prime_start = Instant::now();
+    progress_json(Event::PhaseStart { phase: "primes", n });
     for i in 0..n {
-        let primes: cggmp24::PregeneratedPrimes<SecurityLevel128> =
-            cggmp24::PregeneratedPrimes::generate(&mut OsRng);
-        eprintln!("  party {i}: primes generated in {:.1}s", prime_start.elapsed().as_secs_f64());
+        ensure_running(|| format!("primes generated for {i}/{n} parties"))?;
+        let primes: cggmp24::PregeneratedPrimes<L> =
+            cggmp24::PregeneratedPrimes::generate(&mut MixedRng);
+        let secs = secs_since(prime_start);
+        progress(Event::PrimeDone { party: i.into(), secs }, || {
+            format!("  party {i}: primes generated in {secs:.1}s")
+        });
         primes_list.push(primes);
     }
-    run_dkg_inner(n, threshold, eid_bytes, primes_list)
+    progress_json(Event::PhaseDone { phase: "primes", secs: secs_since(prime_start) });
+    Ok(primes_list)
 }
 
 // ---------------------------------------------------------------------------
 // DKG with pre-generated primes (fast — skips prime generation)
 // ---------------------------------------------------------------------------
 
-fn run_dkg_with_primes(n: u16, threshold: u16, eid_bytes: &[u8], prime_lines: &[String]) -> Result<DkgOutput, String> {
-    let b64 = base64::engine::general_purpose::STANDARD;
-    if prime_lines.len() < n as usize {
-        return Err(format!("Need {} prime sets, got {}", n, prime_lines.len()));
+/// The level comes from `--security-level` or, failing that, from the prime
+/// blobs themselves; every set must be of the same level.
+/// Fails with `DUPLICATE_PRIMES` / `PRIMES_ALREADY_USED` (see
+/// `primes_reuse`) unless `allow_reuse`.
+#[allow(clippy::too_many_arguments)]
+fn run_dkg_with_primes(
+    n: u16,
+    threshold: u16,
+    eid_bytes: &[u8],
+    prime_blobs: &[Vec<u8>],
+    curve: CurveId,
+    requested: Option<Level>,
+    used_primes: &[String],
+    allow_reuse: bool,
+) -> Result<DkgOutput, String> {
+    if prime_blobs.len() < usize::from(n) {
+        return Err(format!("Need {} prime sets, got {}", n, prime_blobs.len()));
     }
-    let mut primes_list = Vec::new();
-    for (i, line) in prime_lines.iter().take(n as usize).enumerate() {
-        let bytes = b64.decode(line.trim()).map_err(|e| format!("decode prime {i}: {e}"))?;
-        let primes: cggmp24::PregeneratedPrimes<SecurityLevel128> =
-            serde_json::from_slice(&bytes).map_err(|e| format!("deserialize prime {i}: {e}"))?;
-        primes_list.push(primes);
+    let blobs = &prime_blobs[..usize::from(n)];
+    let mut level = requested;
+    for (i, bytes) in blobs.iter().enumerate() {
+        let (header, _) = share_format::split_expect(bytes, BlobKind::Primes)
+            .map_err(|e| format!("prime {i}: {e}"))?;
+        level = Some(share_format::ensure_level(&header, level).map_err(|e| format!("prime {i}: {e}"))?);
     }
-    run_dkg_inner(n, threshold, eid_bytes, primes_list)
+    let level = level.unwrap_or_default();
+
+    with_curve!(curve, E => with_security_level!(level, L => {
+        let mut primes_list = Vec::new();
+        for (i, bytes) in blobs.iter().enumerate() {
+            let (_, payload) = share_format::split(bytes)?;
+            let primes: cggmp24::PregeneratedPrimes<L> = share_format::deserialize(BlobKind::Primes, payload)
+                .map_err(|e| format!("prime {i}: {e}"))?;
+            primes_list.push(primes);
+        }
+        let fingerprints = primes_list
+            .iter()
+            .map(primes_reuse::fingerprint)
+            .collect::<Result<Vec<_>, _>>()?;
+        if !allow_reuse {
+            primes_reuse::check_reuse(&fingerprints, used_primes)?;
+        }
+        run_dkg_inner::<E, L>(n, threshold, eid_bytes, curve, level, primes_list)
+            .map(|output| DkgOutput { primes_fingerprints: fingerprints, ..output })
+    }))
 }
 
 // ---------------------------------------------------------------------------
 // DKG inner logic (shared by both modes)
 // ---------------------------------------------------------------------------
 
-fn run_dkg_inner(n: u16, threshold: u16, eid_bytes: &[u8], primes_list: Vec<cggmp24::PregeneratedPrimes<SecurityLevel128>>) -> Result<DkgOutput, String> {
-    let b64 = base64::engine::general_purpose::STANDARD;
+fn run_dkg_inner<E, L>(
+    n: u16,
+    threshold: u16,
+    eid_bytes: &[u8],
+    curve: CurveId,
+    level: Level,
+    primes_list: Vec<cggmp24::PregeneratedPrimes<L>>,
+) -> Result<DkgOutput, String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+{
+    let aux_infos = run_aux_info_phase::<L>(n, eid_bytes, primes_list)?;
+    run_keygen_phase::<E, L>(n, threshold, eid_bytes, curve, level, aux_infos)
+}
 
+/// Phase A: every party's aux info, from its primes.
+fn run_aux_info_phase<L: SecurityLevel>(
+    n: u16,
+    eid_bytes: &[u8],
+    primes_list: Vec<cggmp24::PregeneratedPrimes<L>>,
+) -> Result<Vec<cggmp24::key_share::AuxInfo<L>>, String> {
     // Phase A: Auxiliary Info Generation (ZK proofs using provided primes)
-    eprintln!("Phase A: aux_info_gen ({n} parties)...");
-    let phase_a_start = std::time::Instant::now();
+    progress(Event::PhaseStart { phase: "aux_info", n }, || {
+        format!("Phase A: aux_info_gen ({n} parties)...")
+    });
+    let phase_a_start = Instant::now();
 
     let mut aux_parties = Vec::new();
-    for (i, primes) in primes_list.into_iter().enumerate() {
-        let i = i as u16;
+    for (i, primes) in (0..n).zip(primes_list) {
         let eid = cggmp24::ExecutionId::new(eid_bytes);
+        let mut rng = protocol_rng()?;
         aux_parties.push(round_based::state_machine::wrap_protocol(
             move |party| async move {
-                let mut rng = OsRng;
                 cggmp24::aux_info_gen(eid, i, n, primes)
                     .start(&mut rng, party)
                     .await
@@ -195,19 +886,38 @@ fn run_dkg_inner(n: u16, threshold: u16, eid_bytes: &[u8], primes_list: Vec<cggm
         let aux = result.map_err(|e| format!("aux_info_gen party {i}: {e:?}"))?;
         aux_infos.push(aux);
     }
-    eprintln!("Phase A complete in {:.1}s", phase_a_start.elapsed().as_secs_f64());
+    let secs = secs_since(phase_a_start);
+    progress(Event::PhaseDone { phase: "aux_info", secs }, || format!("Phase A complete in {secs:.1}s"));
+    Ok(aux_infos)
+}
 
+/// Phase B: keygen, and the output with the aux infos of Phase A.
+fn run_keygen_phase<E, L>(
+    n: u16,
+    threshold: u16,
+    eid_bytes: &[u8],
+    curve: CurveId,
+    level: Level,
+    aux_infos: Vec<cggmp24::key_share::AuxInfo<L>>,
+) -> Result<DkgOutput, String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+{
     // Phase B: Key Generation (lightweight)
-    eprintln!("Phase B: keygen ({n} parties, threshold {threshold})...");
-    let phase_b_start = std::time::Instant::now();
+    progress(Event::PhaseStart { phase: "keygen", n }, || {
+        format!("Phase B: keygen ({n} parties, threshold {threshold})...")
+    });
+    let phase_b_start = Instant::now();
 
     let mut kg_parties = Vec::new();
     for i in 0..n {
         let eid = cggmp24::ExecutionId::new(eid_bytes);
+        let mut rng = protocol_rng()?;
         kg_parties.push(round_based::state_machine::wrap_protocol(
             move |party| async move {
-                let mut rng = OsRng;
-                cggmp24::keygen::<Secp256k1>(eid, i, n)
+                cggmp24::keygen::<E>(eid, i, n)
                     .set_threshold(threshold)
                     .start(&mut rng, party)
                     .await
@@ -221,439 +931,3891 @@ fn run_dkg_inner(n: u16, threshold: u16, eid_bytes: &[u8], primes_list: Vec<cggm
         let share = result.map_err(|e| format!("keygen party {i}: {e:?}"))?;
         core_shares.push(share);
     }
-    eprintln!("Phase B complete in {:.1}s", phase_b_start.elapsed().as_secs_f64());
+    let secs = secs_since(phase_b_start);
+    progress(Event::PhaseDone { phase: "keygen", secs }, || format!("Phase B complete in {secs:.1}s"));
 
     // Extract public key
     let pk = core_shares[0].shared_public_key();
     let pk_bytes = pk.to_bytes(true);
-    let pk_hex = hex::encode(pk_bytes.as_bytes());
 
     // Serialize shares
     let mut shares = Vec::new();
-    for i in 0..n as usize {
-        let core_bytes = serde_json::to_vec(&core_shares[i])
-            .map_err(|e| format!("serialize core share {i}: {e}"))?;
-        let aux_bytes = serde_json::to_vec(&aux_infos[i])
-            .map_err(|e| format!("serialize aux info {i}: {e}"))?;
+    for i in 0..usize::from(n) {
+        let core_bytes = share_format::serialize(BlobKind::CoreShare, Some(curve), None, &core_shares[i])
+            .map_err(|e| format!("party {i}: {e}"))?;
+        let aux_bytes = share_format::serialize(BlobKind::AuxInfo, None, Some(level), &aux_infos[i])
+            .map_err(|e| format!("party {i}: {e}"))?;
         shares.push(DkgShare {
-            core_share: b64.encode(&core_bytes),
-            aux_info: b64.encode(&aux_bytes),
+            share_fingerprint: fingerprint::share_fingerprint(&core_bytes),
+            core_share: core_bytes.into(),
+            aux_info: aux_bytes.into(),
         });
     }
 
     Ok(DkgOutput {
         shares,
-        public_key: pk_hex,
+        public_key: pk_bytes.as_bytes().into(),
+        wallet_id: fingerprint::wallet_id(pk_bytes.as_bytes())?,
+        primes_fingerprints: Vec::new(),
     })
 }
 
 // ---------------------------------------------------------------------------
-// Prime generation (original mode)
+// DKG checkpoints (`dkg --checkpoint-dir`)
 // ---------------------------------------------------------------------------
 
-fn gen_primes(count: usize) {
-    let b64 = base64::engine::general_purpose::STANDARD;
-    for i in 0..count {
-        let start = std::time::Instant::now();
-        let primes: cggmp24::PregeneratedPrimes<SecurityLevel128> =
-            cggmp24::PregeneratedPrimes::generate(&mut OsRng);
-        let bytes = serde_json::to_vec(&primes).expect("serialize primes");
-        eprintln!(
-            "prime {}/{}: {:.1}s ({} bytes)",
-            i + 1,
-            count,
-            start.elapsed().as_secs_f64(),
-            bytes.len()
-        );
-        println!("{}", b64.encode(&bytes));
-    }
+/// Where `dkg` keeps the aux infos of a finished Phase A, so a run that
+/// dies during keygen resumes there instead of redoing primes and Phase A.
+///
+/// One file per ceremony, `dkg-<eid hex>.checkpoint`: only a run under the
+/// same eid (given or `--derive-eid`d; a random eid never resumes) finds
+/// it, and it must also be for the same `n`, threshold and level or the
+/// run fails with `CHECKPOINT_MISMATCH` rather than mixing ceremonies. The
+/// file holds Paillier secret keys: with a passphrase it is encrypted like
+/// a share (`share_crypto`), and it is deleted once the DKG completes.
+struct DkgCheckpoint {
+    dir: PathBuf,
+    passphrase: Option<String>,
 }
 
-// ---------------------------------------------------------------------------
-// AuxInfo generation (pre-generate Phase A for fast DKG)
-// ---------------------------------------------------------------------------
-
-/// JSON output from `gen-aux` — serialized AuxInfo for each party
+/// A checkpoint file's contents (JSON, before any encryption).
 #[derive(Serialize, Deserialize)]
-struct AuxInfoOutput {
-    /// base64-encoded serialized AuxInfo, one per party
-    aux_infos: Vec<String>,
+struct DkgCheckpointFile {
+    version: u8,
     n: u16,
+    threshold: u16,
+    eid: HexBytes,
+    /// One aux info blob per party, in party order
+    aux_infos: Vec<B64Bytes>,
 }
 
-/// Run only Phase A (aux_info_gen) and output serialized AuxInfo.
-/// This is the expensive part of DKG. Pre-generating it makes DKG ~1s.
-fn gen_aux_info(n: u16) -> Result<AuxInfoOutput, String> {
-    let b64 = base64::engine::general_purpose::STANDARD;
+const DKG_CHECKPOINT_VERSION: u8 = 1;
 
-    // Generate primes (expensive but unavoidable for fresh aux_info)
-    eprintln!("Generating primes for {n} parties...");
-    let mut primes_list = Vec::new();
-    let prime_start = std::time::Instant::now();
-    for i in 0..n {
-        let primes: cggmp24::PregeneratedPrimes<SecurityLevel128> =
-            cggmp24::PregeneratedPrimes::generate(&mut OsRng);
-        eprintln!("  party {i}: primes in {:.1}s", prime_start.elapsed().as_secs_f64());
-        primes_list.push(primes);
+impl DkgCheckpoint {
+    fn path(&self, eid_bytes: &[u8]) -> PathBuf {
+        self.dir.join(format!("dkg-{}.checkpoint", hex::encode(eid_bytes)))
     }
 
-    // Generate a random EID for this aux_info generation
-    let mut eid_bytes = [0u8; 32];
-    getrandom::getrandom(&mut eid_bytes).expect("getrandom");
+    /// The aux infos saved for this ceremony, if a checkpoint exists.
+    fn load<L: SecurityLevel>(
+        &self,
+        n: u16,
+        threshold: u16,
+        eid_bytes: &[u8],
+        level: Level,
+    ) -> Result<Option<Vec<cggmp24::key_share::AuxInfo<L>>>, String> {
+        let path = self.path(eid_bytes);
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("CHECKPOINT_INVALID: {}: {e}", path.display())),
+        };
+        let plain = match &self.passphrase {
+            Some(passphrase) => share_crypto::decrypt_share(&bytes, passphrase)
+                .map_err(|e| format!("CHECKPOINT_INVALID: {}: {e}", path.display()))?,
+            None => zeroize::Zeroizing::new(bytes),
+        };
+        let file: DkgCheckpointFile = serde_json::from_slice(&plain).map_err(|e| {
+            let hint = if self.passphrase.is_none() { " (encrypted? pass --checkpoint-passphrase-env)" } else { "" };
+            format!("CHECKPOINT_INVALID: {}: {e}{hint}", path.display())
+        })?;
+        if file.version != DKG_CHECKPOINT_VERSION {
+            return Err(format!("CHECKPOINT_INVALID: {}: unsupported version {}", path.display(), file.version));
+        }
+        if file.n != n || file.threshold != threshold || file.eid.0 != eid_bytes {
+            return Err(format!(
+                "CHECKPOINT_MISMATCH: {} is for n={} threshold={} eid={}, this run is n={n} threshold={threshold} eid={}; \
+                 delete it to start over",
+                path.display(),
+                file.n,
+                file.threshold,
+                hex::encode(&file.eid.0),
+                hex::encode(eid_bytes)
+            ));
+        }
+        if file.aux_infos.len() != usize::from(n) {
+            return Err(format!(
+                "CHECKPOINT_INVALID: {}: {} aux infos for {n} parties",
+                path.display(),
+                file.aux_infos.len()
+            ));
+        }
+        file.aux_infos
+            .iter()
+            .enumerate()
+            .map(|(i, blob)| {
+                let (header, payload) = share_format::split_expect(&blob.0, BlobKind::AuxInfo)
+                    .map_err(|e| format!("CHECKPOINT_INVALID: {}: party {i}: {e}", path.display()))?;
+                share_format::ensure_level(&header, Some(level))
+                    .map_err(|e| format!("CHECKPOINT_MISMATCH: {}: {e}", path.display()))?;
+                share_format::deserialize(BlobKind::AuxInfo, payload)
+                    .map_err(|e| format!("CHECKPOINT_INVALID: {}: party {i}: {e}", path.display()))
+            })
+            .collect::<Result<_, String>>()
+            .map(Some)
+    }
 
-    // Run Phase A: aux_info_gen
-    eprintln!("Phase A: aux_info_gen ({n} parties)...");
-    let phase_a_start = std::time::Instant::now();
+    /// Save Phase A's aux infos, returning the file written.
+    fn save<L: SecurityLevel>(
+        &self,
+        n: u16,
+        threshold: u16,
+        eid_bytes: &[u8],
+        level: Level,
+        aux_infos: &[cggmp24::key_share::AuxInfo<L>],
+    ) -> Result<PathBuf, String> {
+        let aux_infos = aux_infos
+            .iter()
+            .map(|aux| share_format::serialize(BlobKind::AuxInfo, None, Some(level), aux).map(B64Bytes))
+            .collect::<Result<_, String>>()?;
+        let file = DkgCheckpointFile {
+            version: DKG_CHECKPOINT_VERSION,
+            n,
+            threshold,
+            eid: HexBytes::from(eid_bytes),
+            aux_infos,
+        };
+        let plain =
+            zeroize::Zeroizing::new(serde_json::to_vec(&file).map_err(|e| format!("serialize checkpoint: {e}"))?);
+        let bytes = match &self.passphrase {
+            Some(passphrase) => share_crypto::encrypt_share(&plain, passphrase, share_crypto::KdfParams::default())?,
+            None => plain.to_vec(),
+        };
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("WRITE_FAILED: {}: {e}", self.dir.display()))?;
+        let path = self.path(eid_bytes);
+        write_atomic(&path, &bytes)?;
+        Ok(path)
+    }
 
-    let mut aux_parties = Vec::new();
-    for (i, primes) in primes_list.into_iter().enumerate() {
-        let i = i as u16;
-        let eid = cggmp24::ExecutionId::new(&eid_bytes);
-        aux_parties.push(round_based::state_machine::wrap_protocol(
-            move |party| async move {
-                let mut rng = OsRng;
-                cggmp24::aux_info_gen(eid, i, n, primes)
-                    .start(&mut rng, party)
-                    .await
-            },
-        ));
+    /// Delete the checkpoint of a completed ceremony.
+    fn remove(&self, eid_bytes: &[u8]) -> Result<(), String> {
+        let path = self.path(eid_bytes);
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("CHECKPOINT_DELETE_FAILED: {}: {e}", path.display())),
+        }
     }
+}
 
-    let aux_results = simulate(aux_parties).map_err(|e| format!("aux_info_gen failed: {e}"))?;
-    let mut aux_info_b64s = Vec::new();
-    for (i, result) in aux_results.into_iter().enumerate() {
-        let aux = result.map_err(|e| format!("aux_info_gen party {i}: {e:?}"))?;
-        let bytes = serde_json::to_vec(&aux)
-            .map_err(|e| format!("serialize aux info {i}: {e}"))?;
-        aux_info_b64s.push(b64.encode(&bytes));
+// ---------------------------------------------------------------------------
+// Trusted dealer (`insecure-dev` feature)
+// ---------------------------------------------------------------------------
+
+/// `dkg --trusted-dealer`: every share minted here, from a key this process
+/// knows in full. Same output as a DKG, but every blob carries the
+/// dealer-generated flag. The eid is unused — no protocol runs.
+#[cfg(feature = "insecure-dev")]
+fn run_dkg_trusted_dealer<E, L>(n: u16, threshold: u16, curve: CurveId, level: Level) -> Result<DkgOutput, String>
+where
+    E: Curve,
+    L: SecurityLevel,
+{
+    progress(Event::PhaseStart { phase: "trusted_dealer", n }, || {
+        format!("Trusted dealer: minting {n} shares, threshold {threshold} (INSECURE, tests only)...")
+    });
+    let key_shares = cggmp24::trusted_dealer::builder::<E, L>(n)
+        .set_threshold(Some(threshold))
+        .generate_shares(&mut MixedRng)
+        .map_err(|e| format!("trusted dealer: {e}"))?;
+
+    let pk_bytes = key_shares[0].shared_public_key().to_bytes(true);
+    let mut shares = Vec::new();
+    for (i, key_share) in key_shares.iter().enumerate() {
+        let core_bytes = share_format::serialize_flagged(
+            BlobKind::CoreShare,
+            Some(curve),
+            None,
+            share_format::FLAG_DEALER,
+            &key_share.core,
+        )
+        .map_err(|e| format!("party {i}: {e}"))?;
+        let aux_bytes = share_format::serialize_flagged(
+            BlobKind::AuxInfo,
+            None,
+            Some(level),
+            share_format::FLAG_DEALER,
+            &key_share.aux,
+        )
+        .map_err(|e| format!("party {i}: {e}"))?;
+        shares.push(DkgShare {
+            share_fingerprint: fingerprint::share_fingerprint(&core_bytes),
+            core_share: core_bytes.into(),
+            aux_info: aux_bytes.into(),
+        });
     }
-    eprintln!("Phase A complete in {:.1}s", phase_a_start.elapsed().as_secs_f64());
 
-    Ok(AuxInfoOutput { aux_infos: aux_info_b64s, n })
+    Ok(DkgOutput {
+        shares,
+        public_key: pk_bytes.as_bytes().into(),
+        wallet_id: fingerprint::wallet_id(pk_bytes.as_bytes())?,
+        primes_fingerprints: Vec::new(),
+    })
 }
 
-/// Run DKG using pre-generated AuxInfo — only runs Phase B (keygen), ~1s.
-fn run_dkg_with_aux(n: u16, threshold: u16, eid_bytes: &[u8], aux_info_json: &str) -> Result<DkgOutput, String> {
-    let b64 = base64::engine::general_purpose::STANDARD;
+/// `manifest.json` of a `gen-vectors` directory.
+#[cfg(feature = "insecure-dev")]
+#[derive(Serialize)]
+struct VectorManifest {
+    format_version: u32,
+    cggmp24_version: &'static str,
+    seed: HexBytes,
+    files: Vec<VectorFile>,
+}
 
-    // Deserialize cached AuxInfo
-    let aux_output: AuxInfoOutput = serde_json::from_str(aux_info_json)
-        .map_err(|e| format!("parse cached aux info: {e}"))?;
-    if (aux_output.n as usize) < n as usize || aux_output.aux_infos.len() < n as usize {
-        return Err(format!("Need {} aux_infos, got {}", n, aux_output.aux_infos.len()));
-    }
+#[cfg(feature = "insecure-dev")]
+#[derive(Serialize)]
+struct VectorFile {
+    file: &'static str,
+    sha256: HexBytes,
+}
 
-    let mut aux_infos = Vec::new();
-    for (i, b64_str) in aux_output.aux_infos.iter().take(n as usize).enumerate() {
-        let bytes = b64.decode(b64_str).map_err(|e| format!("decode aux info {i}: {e}"))?;
-        let aux: cggmp24::key_share::AuxInfo<SecurityLevel128> =
-            serde_json::from_slice(&bytes).map_err(|e| format!("deserialize aux info {i}: {e}"))?;
-        aux_infos.push(aux);
-    }
+/// `gen-vectors`: mint the test-vector key with the seeded dealer, record
+/// both signings and write them to `out` with a manifest of their hashes
+/// (see `test_vectors`). Existing files are replaced.
+#[cfg(feature = "insecure-dev")]
+fn gen_vectors(out: &Path) -> Result<VectorManifest, String> {
+    progress(Event::PhaseStart { phase: "trusted_dealer", n: test_vectors::N }, || {
+        format!("Test vectors: minting a {}-of-{} key (INSECURE, tests only)...", test_vectors::T, test_vectors::N)
+    });
+    let key_shares = test_vectors::dealer_key_shares()?;
 
-    // Phase B only: Key Generation (lightweight, ~1s)
-    eprintln!("Phase B: keygen ({n} parties, threshold {threshold})...");
-    let phase_b_start = std::time::Instant::now();
+    let key = test_vectors::key_vector(&key_shares)?;
+    let reliable = test_vectors::sign_vector(&key_shares, true)?;
+    let unreliable = test_vectors::sign_vector(&key_shares, false)?;
 
-    let mut kg_parties = Vec::new();
-    for i in 0..n {
-        let eid = cggmp24::ExecutionId::new(eid_bytes);
-        kg_parties.push(round_based::state_machine::wrap_protocol(
-            move |party| async move {
-                let mut rng = OsRng;
-                cggmp24::keygen::<Secp256k1>(eid, i, n)
-                    .set_threshold(threshold)
-                    .start(&mut rng, party)
-                    .await
-            },
-        ));
+    std::fs::create_dir_all(out).map_err(|e| format!("WRITE_FAILED: {}: {e}", out.display()))?;
+    let mut files = Vec::new();
+    for (file, bytes) in [
+        ("key.json", pretty_json(&key)?),
+        ("sign-reliable.json", pretty_json(&reliable)?),
+        ("sign-unreliable.json", pretty_json(&unreliable)?),
+    ] {
+        write_atomic(&out.join(file), &bytes)?;
+        files.push(VectorFile { file, sha256: HexBytes(Sha256::digest(&bytes).to_vec()) });
     }
 
-    let kg_results = simulate(kg_parties).map_err(|e| format!("keygen failed: {e}"))?;
-    let mut core_shares = Vec::new();
-    for (i, result) in kg_results.into_iter().enumerate() {
+    let manifest = VectorManifest {
+        format_version: test_vectors::FORMAT_VERSION,
+        cggmp24_version: capabilities::CGGMP24_VERSION,
+        seed: HexBytes(test_vectors::SEED.to_vec()),
+        files,
+    };
+    write_atomic(&out.join("manifest.json"), &pretty_json(&manifest)?)?;
+    Ok(manifest)
+}
+
+/// `value` as indented JSON ending in a newline, for files meant to be
+/// checked in and diffed.
+#[cfg(feature = "insecure-dev")]
+fn pretty_json<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    let mut bytes = serde_json::to_vec_pretty(value).map_err(|e| format!("serialize: {e}"))?;
+    bytes.push(b'\n');
+    Ok(bytes)
+}
+
+// ---------------------------------------------------------------------------
+// Prime generation (original mode)
+// ---------------------------------------------------------------------------
+
+/// Base64 prime lines, as `primes` prints them, decoded.
+fn decode_prime_lines(lines: &[String]) -> Result<Vec<Vec<u8>>, String> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| b64.decode(line.trim()).map_err(|e| format!("decode prime {i}: {e}")))
+        .collect()
+}
+
+/// Sidecar of a `primes --out-dir` file: `prime-<secs>-<rand>.bin` comes
+/// with `prime-<secs>-<rand>.meta.json`.
+#[derive(Serialize, Deserialize)]
+struct PrimesMeta {
+    /// The `.bin` file this describes
+    file: String,
+    /// Unix seconds
+    generated_at: u64,
+    security_level: String,
+    /// Bit length each prime was generated at
+    bit_length: u32,
+    /// hex sha256 of the `.bin` file
+    sha256: String,
+}
+
+const PRIMES_META_SUFFIX: &str = ".meta.json";
+
+/// Write one generated pair into `dir`: the blob, then its sidecar, each
+/// atomically, so a crash leaves at worst a `.bin` with no sidecar, which
+/// [`load_primes_dir`] ignores.
+fn write_primes_file(dir: &Path, bytes: &[u8], level: Level, bit_length: u32) -> Result<PrimesMeta, String> {
+    let generated_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let mut suffix = [0u8; 4];
+    getrandom::getrandom(&mut suffix).map_err(|e| format!("getrandom: {e}"))?;
+    let stem = format!("prime-{generated_at}-{}", hex::encode(suffix));
+
+    let meta = PrimesMeta {
+        file: format!("{stem}.bin"),
+        generated_at,
+        security_level: level.as_str().into(),
+        bit_length,
+        sha256: hex::encode(Sha256::digest(bytes)),
+    };
+    write_atomic(&dir.join(&meta.file), bytes)?;
+    let json = serde_json::to_string_pretty(&meta).expect("serialize primes sidecar");
+    write_atomic(&dir.join(format!("{stem}{PRIMES_META_SUFFIX}")), format!("{json}\n").as_bytes())?;
+    Ok(meta)
+}
+
+/// Every pair in a `primes --out-dir` directory, in file name order (so
+/// oldest first), each checked against its sidecar: `PRIMES_HASH_MISMATCH`
+/// if the file isn't the one generated, and the `primes validate` checks
+/// against the sidecar's level and bit length.
+fn load_primes_dir(dir: &Path) -> Result<Vec<Vec<u8>>, String> {
+    let mut sidecars: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("READ_FAILED: {}: {e}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.to_string_lossy().ends_with(PRIMES_META_SUFFIX))
+        .collect();
+    sidecars.sort();
+
+    let mut blobs = Vec::with_capacity(sidecars.len());
+    for sidecar in sidecars {
+        let text = std::fs::read_to_string(&sidecar)
+            .map_err(|e| format!("READ_FAILED: {}: {e}", sidecar.display()))?;
+        let meta: PrimesMeta = serde_json::from_str(&text)
+            .map_err(|e| format!("INVALID_PRIMES_FORMAT: {}: {e}", sidecar.display()))?;
+        // The sidecar names a file next to it, never a path elsewhere
+        let path = dir.join(Path::new(&meta.file).file_name().unwrap_or_default());
+        let bytes = std::fs::read(&path).map_err(|e| format!("READ_FAILED: {}: {e}", path.display()))?;
+        if hex::encode(Sha256::digest(&bytes)) != meta.sha256 {
+            return Err(format!(
+                "PRIMES_HASH_MISMATCH: {} does not match the sha256 in its sidecar",
+                path.display()
+            ));
+        }
+        let level = Level::parse(&meta.security_level)
+            .map_err(|e| format!("INVALID_PRIMES_FORMAT: {}: {e}", sidecar.display()))?;
+        let report = primes_check::validate(&bytes, Some(level), primes_check::DEFAULT_MR_ROUNDS);
+        if let Some(e) = report.error {
+            return Err(format!("{}: {e}", path.display()));
+        }
+        if report.bit_length != meta.bit_length {
+            return Err(format!(
+                "INVALID_PRIMES_FORMAT: {} has {}-bit primes, its sidecar says {}",
+                path.display(),
+                report.bit_length,
+                meta.bit_length
+            ));
+        }
+        blobs.push(bytes);
+    }
+    Ok(blobs)
+}
+
+fn gen_primes<L: SecurityLevel>(count: usize, level: Level, out_dir: Option<&Path>) {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    if let Some(dir) = out_dir {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            eprintln!("cannot create {}: {e}", dir.display());
+            std::process::exit(1);
+        }
+    }
+    for i in 0..count {
+        // Every line already printed is a complete prime pair
+        if shutting_down() {
+            let detail = format!("{i}/{count} primes written");
+            progress(Event::Shutdown { detail: detail.clone() }, || format!("shutting down: {detail}"));
+            return;
+        }
+        let start = Instant::now();
+        let primes: cggmp24::PregeneratedPrimes<L> =
+            cggmp24::PregeneratedPrimes::generate(&mut MixedRng);
+        let bytes = share_format::serialize(BlobKind::Primes, None, Some(level), &primes)
+            .expect("serialize primes");
+        let secs = secs_since(start);
+        progress(Event::PrimeDone { party: i, secs }, || {
+            format!("prime {}/{}: {secs:.1}s ({} bytes)", i + 1, count, bytes.len())
+        });
+        match out_dir {
+            Some(dir) => match write_primes_file(dir, &bytes, level, L::RSA_PRIME_BITLEN) {
+                Ok(meta) => emit_line(&serde_json::to_string(&meta).expect("serialize primes sidecar")),
+                Err(e) => {
+                    progress(Event::Error { session_id: None, message: &e }, || format!("cannot write primes: {e}"));
+                    std::process::exit(1);
+                }
+            },
+            None => emit_line(&b64.encode(&bytes)),
+        }
+    }
+}
+
+/// `primes validate`: one report per input line, exit 1 if any failed.
+fn validate_primes(requested: Option<Level>, rounds: u32) {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let mut all_valid = true;
+    for line in read_input_lines() {
+        let report = match b64.decode(line.trim()) {
+            Ok(bytes) => primes_check::validate(&bytes, requested, rounds),
+            Err(e) => primes_check::PrimesReport {
+                valid: false,
+                bit_length: 0,
+                security_level: String::new(),
+                error: Some(format!("INVALID_PRIMES_FORMAT: bad base64: {e}")),
+            },
+        };
+        all_valid &= report.valid;
+        emit_line(&serde_json::to_string(&report).expect("serialize primes report"));
+    }
+    if !all_valid {
+        std::process::exit(1);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AuxInfo generation (pre-generate Phase A for fast DKG)
+// ---------------------------------------------------------------------------
+
+/// Run only Phase A (aux_info_gen) and output serialized AuxInfo.
+/// This is the expensive part of DKG. Pre-generating it makes DKG ~1s.
+fn gen_aux_info<L: SecurityLevel>(n: u16, level: Level) -> Result<AuxInfoOutput, String> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+
+    // Generate primes (expensive but unavoidable for fresh aux_info)
+    progress(Event::PhaseStart { phase: "primes", n }, || format!("Generating primes for {n} parties..."));
+    let mut primes_list = Vec::new();
+    let prime_start = Instant::now();
+    for i in 0..n {
+        ensure_running(|| format!("primes generated for {i}/{n} parties"))?;
+        let primes: cggmp24::PregeneratedPrimes<L> =
+            cggmp24::PregeneratedPrimes::generate(&mut MixedRng);
+        let secs = secs_since(prime_start);
+        progress(Event::PrimeDone { party: i.into(), secs }, || format!("  party {i}: primes in {secs:.1}s"));
+        primes_list.push(primes);
+    }
+    progress_json(Event::PhaseDone { phase: "primes", secs: secs_since(prime_start) });
+
+    // Generate a random EID for this aux_info generation
+    let mut eid_bytes = [0u8; 32];
+    getrandom::getrandom(&mut eid_bytes).expect("getrandom");
+
+    // Run Phase A: aux_info_gen
+    progress(Event::PhaseStart { phase: "aux_info", n }, || {
+        format!("Phase A: aux_info_gen ({n} parties)...")
+    });
+    let phase_a_start = Instant::now();
+
+    let mut aux_parties = Vec::new();
+    for (i, primes) in (0..n).zip(primes_list) {
+        let eid = cggmp24::ExecutionId::new(&eid_bytes);
+        let mut rng = protocol_rng()?;
+        aux_parties.push(round_based::state_machine::wrap_protocol(
+            move |party| async move {
+                cggmp24::aux_info_gen(eid, i, n, primes)
+                    .start(&mut rng, party)
+                    .await
+            },
+        ));
+    }
+
+    let aux_results = simulate(aux_parties).map_err(|e| format!("aux_info_gen failed: {e}"))?;
+    let mut aux_info_b64s = Vec::new();
+    for (i, result) in aux_results.into_iter().enumerate() {
+        let aux = result.map_err(|e| format!("aux_info_gen party {i}: {e:?}"))?;
+        let bytes = share_format::serialize(BlobKind::AuxInfo, None, Some(level), &aux)
+            .map_err(|e| format!("party {i}: {e}"))?;
+        aux_info_b64s.push(b64.encode(&bytes));
+    }
+    let secs = secs_since(phase_a_start);
+    progress(Event::PhaseDone { phase: "aux_info", secs }, || format!("Phase A complete in {secs:.1}s"));
+
+    Ok(AuxInfoOutput {
+        aux_infos: aux_info_b64s,
+        n,
+        generated_at: clock::unix_seconds(),
+        security_level: level.as_str().into(),
+        uses: 0,
+    })
+}
+
+/// `gen-aux` / `aux-pool fill`: generate `count` sets, handing each to
+/// `sink` as it completes. Any failure ends the process.
+fn gen_aux_sets(n: u16, count: usize, level: Level, mut sink: impl FnMut(AuxInfoOutput) -> Result<(), String>) {
+    for i in 0..count {
+        let start = Instant::now();
+        let result = with_security_level!(level, L => gen_aux_info::<L>(n, level)).and_then(|output| {
+            let secs = secs_since(start);
+            progress(Event::AuxSetDone { set: i, count, secs }, || {
+                format!("AuxInfo set {}/{} complete in {secs:.1}s", i + 1, count)
+            });
+            sink(output)
+        });
+        if let Err(e) = result {
+            progress(Event::Error { session_id: None, message: &e }, || {
+                format!("AuxInfo generation failed: {e}")
+            });
+            std::process::exit(1);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AuxInfo pool (`aux-pool`): `gen-aux` sets reused across DKGs
+// ---------------------------------------------------------------------------
+//
+// One `aux-<generated_at>-<rand>.json` file per set, holding the
+// `AuxInfoOutput` line itself; `take` rewrites it with `uses` bumped before
+// the set is used, so a DKG that fails afterwards still counts. Takers
+// aren't serialised against each other: run one at a time per directory.
+
+const AUX_POOL_PREFIX: &str = "aux-";
+
+/// `aux-pool status` line for one set.
+#[derive(Serialize)]
+struct AuxSetStatus {
+    file: String,
+    n: u16,
+    security_level: String,
+    generated_at: u64,
+    age_secs: u64,
+    uses: u32,
+    /// `take` would hand it out (for its `n` and level)
+    usable: bool,
+    /// Why not, when not
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refused: Option<String>,
+}
+
+impl AuxSetStatus {
+    fn new(path: &Path, set: &AuxInfoOutput, policy: &AuxPolicy, now: u64) -> Self {
+        let refused = aux_set_refusal(set, policy, now);
+        AuxSetStatus {
+            file: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+            n: set.n,
+            security_level: set.security_level.clone(),
+            generated_at: set.generated_at,
+            age_secs: now.saturating_sub(set.generated_at),
+            uses: set.uses,
+            usable: refused.is_none(),
+            refused,
+        }
+    }
+}
+
+/// Why `policy` refuses `set` at `now`, if it does (see
+/// [`aux_set::refusal`]).
+fn aux_set_refusal(set: &AuxInfoOutput, policy: &AuxPolicy, now: u64) -> Option<String> {
+    aux_set::refusal(set, policy.max_age, policy.max_uses, now)
+}
+
+/// Write `set` into the pool at `dir` atomically.
+fn write_aux_set(dir: &Path, set: &AuxInfoOutput) -> Result<PathBuf, String> {
+    let mut suffix = [0u8; 4];
+    getrandom::getrandom(&mut suffix).map_err(|e| format!("getrandom: {e}"))?;
+    let path = dir.join(format!("{AUX_POOL_PREFIX}{}-{}.json", set.generated_at, hex::encode(suffix)));
+    rewrite_aux_set(&path, set)?;
+    Ok(path)
+}
+
+fn rewrite_aux_set(path: &Path, set: &AuxInfoOutput) -> Result<(), String> {
+    let json = serde_json::to_string(set).expect("serialize aux info output");
+    write_atomic(path, format!("{json}\n").as_bytes())
+}
+
+/// Every set in an `aux-pool` directory, oldest first (file name order).
+fn load_aux_pool(dir: &Path) -> Result<Vec<(PathBuf, AuxInfoOutput)>, String> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("READ_FAILED: {}: {e}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            name.starts_with(AUX_POOL_PREFIX) && name.ends_with(".json")
+        })
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let text = std::fs::read_to_string(&path)
+                .map_err(|e| format!("READ_FAILED: {}: {e}", path.display()))?;
+            let set = serde_json::from_str(&text)
+                .map_err(|e| format!("INVALID_AUX_SET: {}: {e}", path.display()))?;
+            Ok((path, set))
+        })
+        .collect()
+}
+
+/// `aux-pool take`: the oldest set in `dir` for `n` parties at `level` that
+/// `policy` allows, its use recorded. `AUX_POOL_EMPTY` if there is no set
+/// for `n` and `level` at all, `AUX_POOL_EXHAUSTED` (with the first
+/// refusal) if the policy refuses every one.
+fn take_aux_set(dir: &Path, n: u16, level: Level, policy: &AuxPolicy) -> Result<AuxInfoOutput, String> {
+    let now = clock::unix_seconds();
+    let mut refusals = Vec::new();
+    for (path, mut set) in load_aux_pool(dir)? {
+        if set.n != n || set.security_level != level.as_str() {
+            continue;
+        }
+        if let Some(refusal) = aux_set_refusal(&set, policy, now) {
+            refusals.push(refusal);
+            continue;
+        }
+        set.uses += 1;
+        rewrite_aux_set(&path, &set)?;
+        return Ok(set);
+    }
+    match refusals.first() {
+        None => Err(format!(
+            "AUX_POOL_EMPTY: no set for n={n} at level {} in {}",
+            level.as_str(),
+            dir.display()
+        )),
+        Some(first) => Err(format!(
+            "AUX_POOL_EXHAUSTED: all {} sets for n={n} at level {} in {} are refused; first: {first}",
+            refusals.len(),
+            level.as_str(),
+            dir.display()
+        )),
+    }
+}
+
+/// Run DKG using pre-generated AuxInfo — only runs Phase B (keygen), ~1s.
+///
+/// The cached AuxInfo is only validated here (it is passed through verbatim),
+/// so any `--security-level` request is checked against its headers.
+fn run_dkg_with_aux(
+    n: u16,
+    threshold: u16,
+    eid_bytes: &[u8],
+    aux_output: &AuxInfoOutput,
+    curve: CurveId,
+    requested: Option<Level>,
+) -> Result<DkgOutput, String> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+
+    let mut level = match (requested, aux_set::check_for_keygen(aux_output, n)?) {
+        (Some(requested), Some(labelled)) if requested != labelled => {
+            return Err(format!(
+                "SECURITY_LEVEL_MISMATCH: requested {}, the aux set is labelled {}",
+                requested.as_str(),
+                labelled.as_str()
+            ));
+        }
+        (requested, labelled) => requested.or(labelled),
+    };
+    for (i, b64_str) in aux_output.aux_infos.iter().enumerate() {
+        let bytes = b64.decode(b64_str).map_err(|e| format!("decode aux info {i}: {e}"))?;
+        let (header, payload) = share_format::split_expect(&bytes, BlobKind::AuxInfo)
+            .map_err(|e| format!("aux info {i}: {e}"))?;
+        let found = share_format::ensure_level(&header, level).map_err(|e| format!("aux info {i}: {e}"))?;
+        level = Some(found);
+        with_security_level!(found, L => {
+            share_format::deserialize::<cggmp24::key_share::AuxInfo<L>>(BlobKind::AuxInfo, payload)
+                .map_err(|e| format!("aux info {i}: {e}"))?;
+        });
+    }
+
+    with_curve!(curve, E => keygen_with_aux::<E>(n, threshold, eid_bytes, curve, &aux_output.aux_infos))
+}
+
+/// Phase B of `run_dkg_with_aux` on curve `E`; `aux_infos` are passed through.
+fn keygen_with_aux<E: Curve>(
+    n: u16,
+    threshold: u16,
+    eid_bytes: &[u8],
+    curve: CurveId,
+    aux_infos: &[String],
+) -> Result<DkgOutput, String> {
+    // Phase B only: Key Generation (lightweight, ~1s)
+    progress(Event::PhaseStart { phase: "keygen", n }, || {
+        format!("Phase B: keygen ({n} parties, threshold {threshold})...")
+    });
+    let phase_b_start = Instant::now();
+
+    let mut kg_parties = Vec::new();
+    for i in 0..n {
+        let eid = cggmp24::ExecutionId::new(eid_bytes);
+        let mut rng = protocol_rng()?;
+        kg_parties.push(round_based::state_machine::wrap_protocol(
+            move |party| async move {
+                cggmp24::keygen::<E>(eid, i, n)
+                    .set_threshold(threshold)
+                    .start(&mut rng, party)
+                    .await
+            },
+        ));
+    }
+
+    let kg_results = simulate(kg_parties).map_err(|e| format!("keygen failed: {e}"))?;
+    let mut core_shares = Vec::new();
+    for (i, result) in kg_results.into_iter().enumerate() {
         let share = result.map_err(|e| format!("keygen party {i}: {e:?}"))?;
         core_shares.push(share);
     }
-    eprintln!("Phase B complete in {:.1}s", phase_b_start.elapsed().as_secs_f64());
+    let secs = secs_since(phase_b_start);
+    progress(Event::PhaseDone { phase: "keygen", secs }, || format!("Phase B complete in {secs:.1}s"));
 
     // Extract public key
     let pk = core_shares[0].shared_public_key();
     let pk_bytes = pk.to_bytes(true);
-    let pk_hex = hex::encode(pk_bytes.as_bytes());
 
     // Serialize shares (combine core_share + cached aux_info)
     let mut shares = Vec::new();
-    for i in 0..n as usize {
-        let core_bytes = serde_json::to_vec(&core_shares[i])
-            .map_err(|e| format!("serialize core share {i}: {e}"))?;
+    for i in 0..usize::from(n) {
+        let core_bytes = share_format::serialize(BlobKind::CoreShare, Some(curve), None, &core_shares[i])
+            .map_err(|e| format!("party {i}: {e}"))?;
         shares.push(DkgShare {
-            core_share: b64.encode(&core_bytes),
-            aux_info: aux_output.aux_infos[i].clone(),
+            share_fingerprint: fingerprint::share_fingerprint(&core_bytes),
+            core_share: core_bytes.into(),
+            aux_info: B64Bytes::parse(&format!("aux info {i}"), &aux_infos[i])?,
         });
     }
-
-    Ok(DkgOutput {
-        shares,
-        public_key: pk_hex,
-    })
+
+    Ok(DkgOutput {
+        shares,
+        public_key: pk_bytes.as_bytes().into(),
+        wallet_id: fingerprint::wallet_id(pk_bytes.as_bytes())?,
+        primes_fingerprints: Vec::new(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Interactive signing types (wire-compatible with WASM WasmSignMessage)
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+struct SignInit {
+    core_share: B64Bytes,
+    aux_info: B64Bytes,
+    /// 32 bytes; exactly one of this and `message`
+    #[serde(default)]
+    message_hash: Option<HexBytes>,
+    /// Raw message, hashed with SHA-256 by cggmp24 (see WASM `SignData`)
+    #[serde(default)]
+    message: Option<HexBytes>,
+    party_index: u16,
+    parties_at_keygen: Vec<u16>,
+    /// 32 bytes
+    eid: HexBytes,
+    /// Skip self-verification of the final signature (see WASM `SignOptions`)
+    #[serde(default)]
+    skip_verify: bool,
+    /// Expected security level (`"128"` / `"192"`); checked against the aux blob
+    #[serde(default)]
+    security_level: Option<String>,
+    /// Expected curve (`"secp256k1"` / `"secp256r1"`); checked against the core share
+    #[serde(default)]
+    curve: Option<String>,
+    /// Overrides of the default message limits (see WASM `set_message_limits`)
+    #[serde(default)]
+    limits: LimitOverrides,
+    /// Hex-encoded secret shared by the signing group; tags outgoing
+    /// messages and requires valid tags on incoming ones (see WASM `SignOptions`)
+    #[serde(default)]
+    auth_secret: Option<String>,
+    /// Encoding of protocol messages (`"json"` / `"cbor"`); defaults to
+    /// `--encoding` (see WASM `SignOptions`)
+    #[serde(default)]
+    encoding: Option<String>,
+    /// Reliable broadcast check, one relay round more; default true (see
+    /// WASM `SignOptions`)
+    #[serde(default)]
+    reliable_broadcast: Option<bool>,
+    /// Digest the protocol hashes with, `"sha256"` (default) or
+    /// `"keccak256"` (see WASM `SignOptions`)
+    #[serde(default)]
+    digest: Option<String>,
+    /// Most bytes of messages one reply carries; the rest come with
+    /// `more` and go out on the next line, `[]` to fetch them (see WASM
+    /// `SignOptions`)
+    #[serde(default)]
+    max_outgoing_bytes: Option<u32>,
+    /// Bring `s` to its low form, default true; the completing reply's
+    /// `normalized` says whether it flipped (see WASM `SignOptions`)
+    #[serde(default)]
+    normalize_s: Option<bool>,
+}
+
+/// Envelope version (same as the WASM `sign::MESSAGE_VERSION`).
+const MESSAGE_VERSION: u8 = 1;
+
+/// `capabilities` features: everything the default WASM build has.
+const NATIVE_FEATURES: &[&str] = &["dkg", "signing", "primes", "ecdsa", "frost"];
+
+/// Bignum backend cggmp24 was built with (see the `gmp` feature).
+#[cfg(feature = "gmp")]
+const BIGNUM_BACKEND: &str = "rug";
+#[cfg(not(feature = "gmp"))]
+const BIGNUM_BACKEND: &str = "num-bigint";
+
+fn message_version() -> u8 {
+    MESSAGE_VERSION
+}
+
+/// What a wire message carries (same as the WASM `sign::MessageKind`).
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+enum MessageKind {
+    #[default]
+    Protocol,
+    /// The sender abandoned the session; `payload` is its reason
+    Abort,
+}
+
+/// Longest abort reason sent to peers (same as the WASM crate).
+const MAX_ABORT_REASON_BYTES: usize = 256;
+
+/// Most hashes one batch session signs (same as the WASM crate).
+const MAX_BATCH_SIZE: usize = 256;
+
+#[derive(Serialize, Deserialize, Clone)]
+struct WasmSignMessage {
+    /// Defaults for peers predating the field
+    #[serde(default = "message_version")]
+    version: u8,
+    /// Sender's relay round: 0 for the first messages, then +1 per round
+    #[serde(default)]
+    round: u16,
+    /// Session binding tag (see the shared `binding` module)
+    #[serde(default)]
+    binding: Option<String>,
+    /// Sender authentication tag (see the shared `message_auth` module)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auth_tag: Option<String>,
+    #[serde(default)]
+    kind: MessageKind,
+    sender: u16,
+    is_broadcast: bool,
+    recipient: Option<u16>,
+    payload: String,            // base64-encoded serde_json of protocol Msg
+    /// Which hash of a batch session the message belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message_index: Option<u32>,
+    /// Digest of the sender's protocol, absent for SHA-256 (see the shared
+    /// `protocol_digest` module)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    digest: Option<String>,
+}
+
+impl WasmSignMessage {
+    /// The fields an `auth_tag` covers.
+    fn auth_envelope(&self) -> Envelope<'_> {
+        Envelope {
+            sender: self.sender,
+            recipient: self.recipient,
+            round: self.round,
+            abort: self.kind == MessageKind::Abort,
+            payload: &self.payload,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct HexSignature {
+    r: HexBytes,
+    s: HexBytes,
+}
+
+#[derive(Serialize)]
+struct SignOutput {
+    /// Echoes the input line's session id (multi-session mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    messages: Vec<WasmSignMessage>,
+    complete: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    r: Option<HexBytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    s: Option<HexBytes>,
+    /// Incoming messages dropped as stale (sender already past that round)
+    /// or as copies of ones already delivered
+    stale_messages: u32,
+    /// Indices into the line's messages of those taken (see WASM
+    /// `ProcessRoundResult::accepted`)
+    accepted: Vec<u32>,
+    /// The line's messages rejected, with their error code; the others
+    /// were still delivered
+    rejected: Vec<RejectedMessage>,
+    /// Time spent on this line (see WASM `ProcessRoundResult::metrics`);
+    /// absent on the init reply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metrics: Option<RoundMetrics>,
+    /// Set on the reply to an `abort` line; `messages` holds the notice
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    aborted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    /// Batch sessions: one entry per hash, set as each one completes
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    signatures: Vec<Option<HexSignature>>,
+    /// Set once `complete`, for ECDSA sessions (see `--audit-log`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    audit: Option<AuditRecord>,
+    /// Size of `messages` as JSON
+    outgoing_bytes: u64,
+    /// `max_outgoing_bytes` held messages back; a `[]` line fetches them
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    more: bool,
+    /// Set once `complete`, for ECDSA sessions: `s` was flipped to its low
+    /// form (see the init's `normalize_s`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    normalized: Option<bool>,
+}
+
+/// Multi-session reply to a failed line or a `destroy`.
+#[derive(Serialize)]
+struct SessionEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    destroyed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// The structured form of a `PROTOCOL_FAILURE` error, blame included
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<ProtocolFailure>,
+    /// Reply to a `stats` line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<MemoryStats>,
+}
+
+// ---------------------------------------------------------------------------
+// Signing sessions (type-erased, same shape as sign.rs in WASM crate)
+// ---------------------------------------------------------------------------
+
+/// Object-safe view of a signing state machine and its finish step.
+///
+/// Every method adds the time it spends to `times`.
+trait SignDriver {
+    /// Drive until the protocol blocks, appending outgoing messages. Returns
+    /// the 64-byte `r || s` signature once the protocol completes.
+    fn drive(&mut self, out: &mut Vec<WasmSignMessage>, times: &mut PhaseTimes) -> Result<Option<Vec<u8>>, String>;
+    /// Decode an incoming payload without delivering it.
+    fn check_payload(&self, payload: &[u8], times: &mut PhaseTimes) -> Result<(), String>;
+    /// Deliver one incoming wire message.
+    fn deliver(&mut self, msg: &WasmSignMessage, times: &mut PhaseTimes) -> Result<(), String>;
+}
+
+/// `finish` turns the protocol output into the wire signature (verifying
+/// it); it is the only protocol-specific step.
+struct Driver<SM, F> {
+    sm: SM,
+    party_index: u16,
+    /// Encoding of outgoing protocol messages
+    encoding: Encoding,
+    finish: F,
+}
+
+impl<SM, F> Driver<SM, F>
+where
+    SM: StateMachine + 'static,
+    SM::Msg: Serialize + DeserializeOwned,
+    F: FnMut(SM::Output) -> Result<Vec<u8>, String> + 'static,
+{
+    fn boxed(sm: SM, party_index: u16, encoding: Encoding, finish: F) -> Box<dyn SignDriver> {
+        Box::new(Driver { sm, party_index, encoding, finish })
+    }
+}
+
+impl<SM, F> SignDriver for Driver<SM, F>
+where
+    SM: StateMachine,
+    SM::Msg: Serialize + DeserializeOwned,
+    F: FnMut(SM::Output) -> Result<Vec<u8>, String>,
+{
+    fn drive(&mut self, out: &mut Vec<WasmSignMessage>, times: &mut PhaseTimes) -> Result<Option<Vec<u8>>, String> {
+        let mut yields = YieldGuard::default();
+        loop {
+            let start = clock::now_ms();
+            let proceeded = self.sm.proceed();
+            let proceeded_at = clock::now_ms();
+            times.proceed_ms += proceeded_at - start;
+            yields.step(matches!(proceeded, ProceedResult::Yielded))?;
+
+            match proceeded {
+                ProceedResult::SendMsg(outgoing) => {
+                    let payload = message_codec::encode(self.encoding, &outgoing.msg)?;
+                    let (is_broadcast, recipient) = match outgoing.recipient {
+                        MessageDestination::AllParties => (true, None),
+                        MessageDestination::OneParty(p) => (false, Some(p)),
+                    };
+                    out.push(WasmSignMessage {
+                        version: MESSAGE_VERSION,
+                        round: 0, // stamped by SignState
+                        binding: None,
+                        auth_tag: None,
+                        kind: MessageKind::Protocol,
+                        sender: self.party_index,
+                        is_broadcast,
+                        recipient,
+                        payload,
+                        message_index: None,
+                        digest: None, // stamped by SignState
+                    });
+                    times.serialize_ms += clock::now_ms() - proceeded_at;
+                }
+                ProceedResult::NeedsOneMoreMessage => return Ok(None),
+                ProceedResult::Output(result) => {
+                    let sig = (self.finish)(result);
+                    times.proceed_ms += clock::now_ms() - proceeded_at;
+                    return sig.map(Some);
+                }
+                ProceedResult::Yielded => {} // continue
+                ProceedResult::Error(e) => return Err(format!("protocol error: {e}")),
+            }
+        }
+    }
+
+    fn check_payload(&self, payload: &[u8], times: &mut PhaseTimes) -> Result<(), String> {
+        let start = clock::now_ms();
+        let decoded = message_codec::decode::<SM::Msg>(payload).map(drop);
+        times.deserialize_ms += clock::now_ms() - start;
+        decoded
+    }
+
+    fn deliver(&mut self, msg: &WasmSignMessage, times: &mut PhaseTimes) -> Result<(), String> {
+        let start = clock::now_ms();
+        let protocol_msg: SM::Msg = message_codec::decode(msg.payload.as_bytes())?;
+
+        let incoming = Incoming {
+            id: 0,
+            sender: msg.sender,
+            msg_type: if msg.is_broadcast {
+                MessageType::Broadcast
+            } else {
+                MessageType::P2P
+            },
+            msg: protocol_msg,
+        };
+
+        let delivered = self.sm.received_msg(incoming).map_err(|_| {
+            format!(
+                "failed to deliver msg from party {} (broadcast={})",
+                msg.sender, msg.is_broadcast
+            )
+        });
+        times.deserialize_ms += clock::now_ms() - start;
+        delivered
+    }
+}
+
+/// A `Box::into_raw` allocation lent to the state machine as `'static`;
+/// dropping the handle frees it, so the state machine must go first.
+struct Leaked<T>(*mut T);
+
+impl<T> Leaked<T> {
+    fn new(value: T) -> Self {
+        Leaked(Box::into_raw(Box::new(value)))
+    }
+
+    /// SAFETY: the returned reference must not outlive `self`.
+    unsafe fn as_static(&self) -> &'static T {
+        &*self.0
+    }
+
+    /// SAFETY: the returned reference must not outlive `self`, and must be
+    /// the only reference handed out.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn as_static_mut(&self) -> &'static mut T {
+        &mut *self.0
+    }
+}
+
+impl<T> Drop for Leaked<T> {
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(self.0)); }
+    }
+}
+
+/// Type-erased [`Leaked`] so one session struct covers every share type.
+trait LeakedAlloc {}
+impl<T> LeakedAlloc for Leaked<T> {}
+
+/// One live signing session.
+struct SignState {
+    /// Type-erased state machine (dropped first via ManuallyDrop)
+    driver: ManuallyDrop<Box<dyn SignDriver>>,
+    party_index: u16,
+    /// Key share, eid, parties, rng and message borrowed by `driver`
+    _leaked: Vec<Box<dyn LeakedAlloc>>,
+    /// `r || s`, set when the protocol completes
+    signature: Option<Vec<u8>>,
+    /// Session binding tag, stamped on outgoing messages and checked on
+    /// incoming ones
+    binding: String,
+    /// Encoding of protocol messages, both ways (the driver's)
+    encoding: Encoding,
+    /// Digest of the driver's protocol, tagged on outgoing messages and
+    /// required on incoming ones
+    digest: ProtocolDigest,
+    /// MAC key for `auth_tag`s, when the init had an `auth_secret`
+    auth_key: Option<AuthKey>,
+    /// Relay round, stamped on outgoing messages
+    round: u16,
+    /// Messages delivered so far; one from a round its sender has moved
+    /// past, or a second copy of one, is stale
+    seen: SeenMessages,
+    /// Caps on incoming messages, and how many were accepted so far
+    limits: MessageLimits,
+    received: u32,
+    /// Cumulative timings (`setup_ms` is set by `run_interactive`)
+    metrics: SessionMetrics,
+    /// Key material counted in `metrics::memory_stats` while the session lives
+    pinned_bytes: usize,
+    /// What produced the signature (ECDSA sessions)
+    audit: Option<AuditTrail>,
+    /// Cap on the message bytes of one reply, and the stamped messages
+    /// held back by it, oldest first
+    max_outgoing_bytes: Option<u32>,
+    held: VecDeque<WasmSignMessage>,
+    /// Set by the driver's finish step when it flips `s` (ECDSA sessions)
+    normalized: Option<Arc<AtomicBool>>,
+}
+
+impl SignState {
+    /// `pinned_bytes` is the size of the serialised key material the
+    /// session was built from, for `stats`.
+    fn new(
+        driver: Box<dyn SignDriver>,
+        party_index: u16,
+        leaked: Vec<Box<dyn LeakedAlloc>>,
+        binding: String,
+        encoding: Encoding,
+        pinned_bytes: usize,
+    ) -> Self {
+        metrics::session_opened(pinned_bytes);
+        SignState {
+            driver: ManuallyDrop::new(driver),
+            party_index,
+            _leaked: leaked,
+            signature: None,
+            binding,
+            encoding,
+            digest: ProtocolDigest::Sha256,
+            auth_key: None,
+            round: 0,
+            seen: SeenMessages::default(),
+            limits: MessageLimits::default(),
+            received: 0,
+            metrics: SessionMetrics::default(),
+            pinned_bytes,
+            audit: None,
+            max_outgoing_bytes: None,
+            held: VecDeque::new(),
+            normalized: None,
+        }
+    }
+
+    fn with_max_outgoing_bytes(mut self, max: Option<u32>) -> Self {
+        self.max_outgoing_bytes = max;
+        self
+    }
+
+    fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    fn with_normalized(mut self, normalized: Arc<AtomicBool>) -> Self {
+        self.normalized = Some(normalized);
+        self
+    }
+
+    fn with_digest(mut self, digest: ProtocolDigest) -> Self {
+        self.digest = digest;
+        self
+    }
+
+    fn with_limits(mut self, overrides: &LimitOverrides) -> Self {
+        self.limits = self.limits.with_overrides(overrides);
+        self
+    }
+
+    /// Authenticate messages with a key derived from `secret`.
+    fn with_auth(mut self, secret: Option<&str>) -> Result<Self, String> {
+        self.auth_key = secret
+            .map(|secret| AuthKey::derive(secret, &self.binding))
+            .transpose()?;
+        Ok(self)
+    }
+
+    /// Initial drive — produce the first messages.
+    fn start(&mut self) -> Result<SignOutput, String> {
+        let mut messages = Vec::new();
+        let mut times = PhaseTimes::default();
+        self.signature = self
+            .driver
+            .drive(&mut messages, &mut times)
+            .map_err(|e| protocol_failure::stamp_round(e, self.round))?;
+        self.metrics.record_start(&times);
+        self.audit_messages(&messages);
+        self.complete_audit()?;
+        Ok(self.output(messages, 0, None))
+    }
+
+    /// Deliver a round of incoming messages, driving after each one.
+    ///
+    /// Matches the WASM `process_round` behavior: collecting outgoing
+    /// messages before accepting the next incoming one is required for the
+    /// reliable broadcast echo steps.
+    ///
+    /// Every message is checked before anything is delivered. One that
+    /// speaks another envelope version, belongs to another session, is in
+    /// the other encoding, lacks a valid auth tag, breaks the message limits
+    /// or doesn't decode is rejected on its own (`rejected`, with its
+    /// `UNSUPPORTED_MESSAGE_VERSION` / `SESSION_BINDING_MISMATCH` /
+    /// `ENCODING_MISMATCH` / `MESSAGE_AUTH_FAILED` / `MESSAGE_TOO_LARGE` /
+    /// `TOO_MANY_MESSAGES` / `MALFORMED_MESSAGE` code); the others are
+    /// delivered and listed in `accepted`. A line whose messages were all
+    /// rejected isn't a relay round.
+    fn process_round(&mut self, incoming: &[WasmSignMessage]) -> Result<SignOutput, String> {
+        let mut times = PhaseTimes::default();
+        let mut accepted = Vec::new();
+        let mut rejected = Vec::new();
+        for (index, msg) in (0u32..).zip(incoming) {
+            match self.check_incoming(msg, accepted.len(), &mut times) {
+                Ok(()) => {
+                    self.received = self.received.saturating_add(1);
+                    accepted.push(index);
+                }
+                Err(e) => rejected.push(RejectedMessage::new(index, &e)),
+            }
+        }
+        let taken: Vec<&WasmSignMessage> = (0u32..)
+            .zip(incoming)
+            .filter(|(index, _)| accepted.contains(index))
+            .map(|(_, msg)| msg)
+            .collect();
+        if let Some(abort) = taken.iter().find(|m| m.kind == MessageKind::Abort) {
+            return Err(format!(
+                "PEER_ABORTED: party {} aborted the session: {}",
+                abort.sender, abort.payload
+            ));
+        }
+
+        // A `[]` line fetching held messages isn't a relay round, nor is one
+        // whose messages were all rejected
+        let fetch = incoming.is_empty() && !self.held.is_empty();
+        let refused = !incoming.is_empty() && taken.is_empty();
+        if !fetch && !refused {
+            self.round = self.round.saturating_add(1);
+        }
+        let mut messages = Vec::new();
+        let mut stale = 0;
+        if self.signature.is_none() {
+            for msg in taken {
+                // Drop anything from a round the sender has already moved
+                // past, and copies of what was delivered
+                let recipient = msg.recipient.filter(|_| !msg.is_broadcast);
+                if !self.seen.insert(msg.sender, msg.round, recipient, &msg.payload) {
+                    stale += 1;
+                    continue;
+                }
+
+                self.driver.deliver(msg, &mut times)?;
+                self.audit_messages(std::slice::from_ref(msg));
+                times.messages += 1;
+                self.signature = self
+                    .driver
+                    .drive(&mut messages, &mut times)
+                    .map_err(|e| protocol_failure::stamp_round(e, self.round))?;
+                if self.signature.is_some() {
+                    break;
+                }
+            }
+        }
+        let metrics = self.metrics.record_round(&times);
+        self.audit_messages(&messages);
+        self.complete_audit()?;
+        let mut output = self.output(messages, stale, Some(metrics));
+        output.accepted = accepted;
+        output.rejected = rejected;
+        Ok(output)
+    }
+
+    /// Why `msg` can't be taken, if it can't (see `process_round`), with
+    /// `in_round` messages of its line already taken.
+    fn check_incoming(&self, msg: &WasmSignMessage, in_round: usize, times: &mut PhaseTimes) -> Result<(), String> {
+        if msg.version != MESSAGE_VERSION {
+            return Err(format!(
+                "UNSUPPORTED_MESSAGE_VERSION: message from party {} has version {}, this build speaks {MESSAGE_VERSION}",
+                msg.sender, msg.version
+            ));
+        }
+        binding::check_binding(&self.binding, msg.sender, msg.binding.as_deref())?;
+        if msg.kind == MessageKind::Protocol {
+            self.digest.check(msg.sender, msg.digest.as_deref())?;
+            message_codec::check_encoding(self.encoding, msg.sender, &msg.payload)?;
+        }
+        if let Some(key) = &self.auth_key {
+            key.verify(&msg.auth_envelope(), msg.auth_tag.as_deref())?;
+        }
+        self.limits
+            .check_message(self.received, in_round, msg.sender, msg.payload.len())?;
+        if msg.kind == MessageKind::Protocol {
+            self.driver
+                .check_payload(msg.payload.as_bytes(), times)
+                .map_err(|e| format!("MALFORMED_MESSAGE: message from party {}: {e}", msg.sender))?;
+        }
+        Ok(())
+    }
+
+    /// Add messages sent or received to the audit transcript.
+    fn audit_messages(&mut self, messages: &[WasmSignMessage]) {
+        if let Some(audit) = &mut self.audit {
+            for msg in messages {
+                audit.message(msg.sender, msg.recipient.filter(|_| !msg.is_broadcast), &msg.payload);
+            }
+        }
+    }
+
+    /// Once the signature is in, complete the audit record and append it
+    /// to the `--audit-log` file. Fails with `AUDIT_LOG_FAILED` if the
+    /// file can't be written.
+    fn complete_audit(&mut self) -> Result<(), String> {
+        let (Some(audit), Some(signature)) = (&mut self.audit, &self.signature) else {
+            return Ok(());
+        };
+        if audit.record().transcript_hash.is_some() {
+            return Ok(());
+        }
+        audit.complete(signature);
+        let Some(path) = AUDIT_LOG.get() else {
+            return Ok(());
+        };
+        let mut line = serde_json::to_vec(audit.record()).map_err(|e| format!("serialize audit record: {e}"))?;
+        line.push(b'\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(&line))
+            .map_err(|e| format!("AUDIT_LOG_FAILED: {}: {e}", path.display()))
+    }
+
+    /// Abandon the session: the reply carries one `abort` notice for the
+    /// peers. The caller drops the state afterwards.
+    fn abort(&mut self, reason: &str) -> SignOutput {
+        let mut end = reason.len().min(MAX_ABORT_REASON_BYTES);
+        while !reason.is_char_boundary(end) {
+            end -= 1;
+        }
+        let reason = &reason[..end];
+        self.round = self.round.saturating_add(1);
+        // The peers only need the notice now
+        self.held.clear();
+        let notice = WasmSignMessage {
+            version: MESSAGE_VERSION,
+            round: self.round,
+            binding: Some(self.binding.clone()),
+            auth_tag: None,
+            kind: MessageKind::Abort,
+            sender: self.party_index,
+            is_broadcast: true,
+            recipient: None,
+            payload: reason.to_string(),
+            message_index: None,
+            digest: self.digest.tag(),
+        };
+        let mut output = self.output(vec![notice], 0, None);
+        output.complete = false;
+        output.aborted = true;
+        output.reason = Some(reason.to_string());
+        output
+    }
+
+    /// The reply carrying `messages`, stamped, after any held ones — as
+    /// many as `max_outgoing_bytes` lets through.
+    fn output(
+        &mut self,
+        mut messages: Vec<WasmSignMessage>,
+        stale_messages: u32,
+        metrics: Option<RoundMetrics>,
+    ) -> SignOutput {
+        for msg in &mut messages {
+            msg.round = self.round;
+            msg.binding = Some(self.binding.clone());
+            msg.digest = self.digest.tag();
+            if let Some(key) = &self.auth_key {
+                msg.auth_tag = Some(key.tag(&msg.auth_envelope()));
+            }
+        }
+        let queued: Vec<_> = self.held.drain(..).chain(messages).collect();
+        let (messages, rest, outgoing_bytes) = split_outgoing(queued, self.max_outgoing_bytes);
+        self.held = rest.into();
+        SignOutput {
+            session_id: None,
+            messages,
+            complete: self.signature.is_some(),
+            r: self.signature.as_ref().map(|sig| HexBytes::from(&sig[..32])),
+            s: self.signature.as_ref().map(|sig| HexBytes::from(&sig[32..])),
+            stale_messages,
+            accepted: Vec::new(),
+            rejected: Vec::new(),
+            metrics,
+            aborted: false,
+            reason: None,
+            signatures: Vec::new(),
+            audit: self
+                .signature
+                .as_ref()
+                .and(self.audit.as_ref())
+                .map(|audit| audit.record().clone()),
+            outgoing_bytes,
+            more: !self.held.is_empty(),
+            normalized: self
+                .signature
+                .as_ref()
+                .and(self.normalized.as_ref())
+                .map(|normalized| normalized.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Size of a message as the relay forwards it: its JSON.
+fn wire_len(msg: &WasmSignMessage) -> u64 {
+    serde_json::to_vec(msg).map_or(0, |json| json.len() as u64)
+}
+
+/// Split `messages` at `max_bytes`: the longest run from the front that
+/// fits — at least one message, so an oversized one still goes out — and
+/// the rest, plus the size of the first part (same as the WASM `sign`).
+fn split_outgoing(
+    mut messages: Vec<WasmSignMessage>,
+    max_bytes: Option<u32>,
+) -> (Vec<WasmSignMessage>, Vec<WasmSignMessage>, u64) {
+    let mut total = 0u64;
+    let mut fit = messages.len();
+    for (i, msg) in messages.iter().enumerate() {
+        let len = wire_len(msg);
+        if i > 0 && max_bytes.is_some_and(|max| total + len > u64::from(max)) {
+            fit = i;
+            break;
+        }
+        total += len;
+    }
+    let rest = messages.split_off(fit);
+    (messages, rest, total)
+}
+
+impl Drop for SignState {
+    fn drop(&mut self) {
+        // The state machine references the leaked data, so it goes first
+        unsafe {
+            ManuallyDrop::drop(&mut self.driver);
+        }
+        metrics::session_closed(self.pinned_bytes);
+    }
+}
+
+/// A multi-session entry: one session, or a batch of sessions signing
+/// several hashes in lockstep (same as the WASM batch sessions).
+enum Live {
+    Single(SignState),
+    Batch(Vec<SignState>),
+    /// A round failed: the key material is gone, and every later line for
+    /// the session fails with `SESSION_FAILED` and this reason until it is
+    /// destroyed
+    Failed(String),
+}
+
+impl Live {
+    /// Still waiting for input to finish signing.
+    fn awaiting(&self) -> bool {
+        match self {
+            Live::Single(state) => state.signature.is_none(),
+            Live::Batch(members) => members.iter().any(|state| state.signature.is_none()),
+            Live::Failed(_) => false,
+        }
+    }
+
+    fn failed(reason: &str) -> String {
+        format!("SESSION_FAILED: {reason}")
+    }
+
+    /// First messages of every batch member, tagged with their index.
+    fn start_batch(members: &mut [SignState]) -> Result<SignOutput, String> {
+        let mut outputs = Vec::with_capacity(members.len());
+        for (index, state) in members.iter_mut().enumerate() {
+            outputs.push(state.start().map_err(|e| format!("message {index}: {e}"))?);
+        }
+        Ok(batch_output(members, (0u32..).zip(outputs)))
+    }
+
+    fn process_round(&mut self, incoming: &[WasmSignMessage]) -> Result<SignOutput, String> {
+        let members = match self {
+            Live::Single(state) => return state.process_round(incoming),
+            Live::Batch(members) => members,
+            Live::Failed(reason) => return Err(Live::failed(reason)),
+        };
+
+        // Route by message_index, keeping each message's place in the line
+        let mut routed = vec![Vec::new(); members.len()];
+        let mut places = vec![Vec::new(); members.len()];
+        let mut rejected = Vec::new();
+        for (place, msg) in (0u32..).zip(incoming) {
+            let index = msg
+                .message_index
+                .and_then(|i| usize::try_from(i).ok())
+                .filter(|&i| i < members.len());
+            let Some(index) = index else {
+                rejected.push(RejectedMessage::new(
+                    place,
+                    &format!(
+                        "INVALID_MESSAGE_INDEX: message from party {} has message_index {:?}, the batch signs {} hashes",
+                        msg.sender,
+                        msg.message_index,
+                        members.len()
+                    ),
+                ));
+                continue;
+            };
+            routed[index].push(msg.clone());
+            places[index].push(place);
+        }
+
+        let mut outputs = Vec::with_capacity(members.len());
+        let mut late = 0u32;
+        let mut late_accepted = Vec::new();
+        for (index, ((state, batch), places)) in (0u32..).zip(members.iter_mut().zip(routed).zip(places)) {
+            if state.signature.is_some() {
+                late = late.saturating_add(u32::try_from(batch.len()).unwrap_or(u32::MAX));
+                late_accepted.extend(places);
+                // What it holds still goes out
+                if !state.held.is_empty() {
+                    outputs.push((index, state.output(Vec::new(), 0, None)));
+                }
+                continue;
+            }
+            let mut output = state.process_round(&batch).map_err(|e| format!("message {index}: {e}"))?;
+            // Back from the member's messages to the line's
+            let place = |i: u32| places[i as usize];
+            output.accepted = output.accepted.iter().map(|&i| place(i)).collect();
+            for rejection in &mut output.rejected {
+                rejection.index = place(rejection.index);
+            }
+            outputs.push((index, output));
+        }
+        let mut output = batch_output(members, outputs.into_iter());
+        output.stale_messages += late;
+        output.accepted.extend(late_accepted);
+        output.accepted.sort_unstable();
+        output.rejected.extend(rejected);
+        output.rejected.sort_by_key(|rejection| rejection.index);
+        Ok(output)
+    }
+
+    fn abort(&mut self, reason: &str) -> Result<SignOutput, String> {
+        match self {
+            Live::Single(state) => Ok(state.abort(reason)),
+            Live::Batch(members) => {
+                let outputs: Vec<_> = members.iter_mut().map(|state| state.abort(reason)).collect();
+                let mut output = batch_output(members, (0u32..).zip(outputs));
+                output.complete = false;
+                output.aborted = true;
+                Ok(output)
+            }
+            Live::Failed(failure) => Err(Live::failed(failure)),
+        }
+    }
+}
+
+/// Merge the members' outputs of one batch call: messages tagged with their
+/// `message_index`, metrics summed, `accepted` / `rejected` joined (already
+/// indexing the line), `signatures` per hash. The members
+/// share `max_outgoing_bytes`: a message over it goes back to its member,
+/// ahead of what that member already holds.
+fn batch_output(members: &mut [SignState], outputs: impl Iterator<Item = (u32, SignOutput)>) -> SignOutput {
+    let mut messages = Vec::new();
+    let mut stale_messages = 0;
+    let mut accepted = Vec::new();
+    let mut rejected = Vec::new();
+    let mut metrics: Option<RoundMetrics> = None;
+    let mut reason = None;
+    for (index, output) in outputs {
+        messages.extend(output.messages.into_iter().map(|msg| WasmSignMessage {
+            message_index: Some(index),
+            ..msg
+        }));
+        stale_messages += output.stale_messages;
+        accepted.extend(output.accepted);
+        rejected.extend(output.rejected);
+        if let Some(round) = output.metrics {
+            metrics.get_or_insert_with(RoundMetrics::default).absorb(&round);
+        }
+        reason = reason.or(output.reason);
+    }
+    let max_bytes = members.first().and_then(|state| state.max_outgoing_bytes);
+    let (messages, rest, outgoing_bytes) = split_outgoing(messages, max_bytes);
+    for msg in rest.into_iter().rev() {
+        let index = msg.message_index.and_then(|i| usize::try_from(i).ok()).unwrap_or(0);
+        if let Some(state) = members.get_mut(index) {
+            state.held.push_front(msg);
+        }
+    }
+    let signatures: Vec<_> = members
+        .iter()
+        .map(|state| {
+            state.signature.as_ref().map(|sig| HexSignature {
+                r: HexBytes::from(&sig[..32]),
+                s: HexBytes::from(&sig[32..]),
+            })
+        })
+        .collect();
+    SignOutput {
+        session_id: None,
+        messages,
+        complete: signatures.iter().all(Option::is_some),
+        r: None,
+        s: None,
+        stale_messages,
+        accepted,
+        rejected,
+        metrics,
+        aborted: false,
+        reason,
+        signatures,
+        audit: None,
+        outgoing_bytes,
+        more: members.iter().any(|state| !state.held.is_empty()),
+        normalized: None,
+    }
+}
+
+/// Splits a multi-session `init_batch` line into one init line per hash.
+type SplitBatch = fn(&serde_json::Value) -> Result<Vec<serde_json::Value>, String>;
+
+/// ECDSA `init_batch`: each member gets one of `message_hashes` as its
+/// `message_hash` and its own eid derived from `eid`.
+fn split_sign_batch(value: &serde_json::Value) -> Result<Vec<serde_json::Value>, String> {
+    let hashes: Vec<String> = value
+        .get("message_hashes")
+        .cloned()
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| format!("INVALID_INIT: message_hashes: {e}"))?
+        .ok_or("INVALID_INIT: init_batch needs message_hashes")?;
+    if hashes.is_empty() || hashes.len() > MAX_BATCH_SIZE {
+        return Err(format!(
+            "INVALID_BATCH: a batch signs 1 to {MAX_BATCH_SIZE} hashes, got {}",
+            hashes.len()
+        ));
+    }
+    let eid = value
+        .get("eid")
+        .and_then(|v| v.as_str())
+        .ok_or("INVALID_INIT: missing eid")?;
+    let eid = HexBytes::parse("eid", eid)?.0;
+
+    Ok((0u32..)
+        .zip(hashes)
+        .map(|(index, hash)| {
+            let mut init = value.clone();
+            init["message_hash"] = hash.into();
+            init["eid"] = hex::encode(binding::batch_member_eid(&eid, index)).into();
+            init
+        })
+        .collect())
+}
+
+// ---------------------------------------------------------------------------
+// Interactive signing — stdin/stdout JSON lines
+// ---------------------------------------------------------------------------
+//
+// Single-session (the original protocol): the first line is the init
+// object, every following line is a JSON array of incoming messages, and
+// the process exits once the signature is out — or after answering an
+// `{ "type": "abort", "reason": "..." }` line with the abort notice.
+//
+// Multi-session: every line carries `session_id` and `type`:
+//   { "session_id": "a", "type": "init", ...init fields }
+//   { "session_id": "a", "type": "init_batch", "message_hashes": [...], ...init fields }
+//   { "session_id": "a", "type": "round", "messages": [...] }
+//   { "session_id": "a", "type": "destroy" }
+//   { "session_id": "a", "type": "abort", "reason": "..." }
+//   { "session_id": "a", "type": "destroy_key", "key_id": "<hex sha256(core_share || aux_info)>" }
+//   { "type": "stats" }
+// and every output line echoes the `session_id`. `abort` drops the session
+// and replies with an `abort` notice to relay; a peer's notice fails the
+// next round with `PEER_ABORTED`. `init_batch` (`sign` only) starts one
+// session signing every hash in lockstep: its messages carry a
+// `message_index` and its outputs a `signatures` array, one `{ r, s }` or
+// null per hash. `destroy_key` evicts a key share from the
+// share cache (hashed over the decoded blobs) and leaves live sessions
+// alone. `stats` (`session_id` optional; a first line of `stats` picks
+// this mode too) replies `{ "stats": { rss_bytes, live_sessions,
+// pinned_bytes, sessions_created, sessions_destroyed } }`, with the resident
+// set size from /proc/self/status. A failing line reports `{ "session_id", "error" }` (plus `failure`
+// with the blamed parties on a `PROTOCOL_FAILURE`). A failed `round`
+// drops that session's key material at once, and every later `round` or
+// `abort` for it fails with `SESSION_FAILED` and the first error until a
+// `destroy`. The mode is picked by whether the first line has a
+// `session_id`.
+//
+// Relaying between signers (as the Node relay does): every message in an
+// output's `messages` goes to each other signer if `is_broadcast`, else
+// only to the signer whose keygen index is `recipient` — never by position
+// in `parties_at_keygen`, which differs from the index for e.g. [1, 2].
+// A peer gets the messages of one output line as one input line, in
+// order; each is delivered and driven before the next, which the reliable
+// broadcast echo round relies on. Both signers of a finished session
+// print the same `r` / `s`. The WASM `route_messages` implements these
+// rules for relays that run the WASM build; `tests/sign_relay.rs` relays
+// between child processes by them.
+
+fn write_line<W: Write>(writer: &mut W, value: &impl Serialize) {
+    let json = serde_json::to_string(value).expect("serialize sign output");
+    writeln!(writer, "{}", json).expect("write to stdout");
+    writer.flush().expect("flush stdout");
+}
+
+/// Run the stdin/stdout signing protocol, building sessions with `build`.
+/// Without `split_batch`, `init_batch` lines are refused.
+fn run_interactive<I, B>(tag: &str, timeout: Duration, split_batch: Option<SplitBatch>, build: B)
+where
+    I: DeserializeOwned,
+    B: Fn(I) -> Result<SignState, String>,
+{
+    let lines = StdinLines::spawn(timeout);
+    let mut writer = Output;
+
+    let first_line = match lines.next() {
+        StdinLine::Line(line) => line,
+        StdinLine::Rejected(e) => fail_sign(&e),
+        lost => exit_stdin_lost(&mut writer, lost, timeout, Vec::new()),
+    };
+    let first: serde_json::Value = serde_json::from_str(&first_line)
+        .unwrap_or_else(|e| fail_sign(&format!("failed to parse sign init JSON: {e}")));
+
+    if first.get("session_id").is_some() || first.get("type").and_then(|v| v.as_str()) == Some("stats") {
+        let mut sessions = HashMap::new();
+        handle_session_line(tag, &mut sessions, first, split_batch, &build, &mut writer);
+        loop {
+            match lines.next() {
+                StdinLine::Line(line) => {
+                    let value = serde_json::from_str(&line).unwrap_or(serde_json::Value::Null);
+                    handle_session_line(tag, &mut sessions, value, split_batch, &build, &mut writer);
+                }
+                // Can't tell which session it was for; the others carry on
+                StdinLine::Rejected(e) => {
+                    progress(Event::Error { session_id: None, message: &e }, || format!("{tag} {e}"));
+                    write_line(&mut writer, &SessionEvent {
+                        session_id: None,
+                        destroyed: None,
+                        error: Some(e),
+                        failure: None,
+                        stats: None,
+                    });
+                }
+                // Nothing left to sign: a clean shutdown
+                StdinLine::Eof if sessions.is_empty() => return,
+                lost => {
+                    let count = sessions.len();
+                    progress(Event::SessionsDropped { count }, || format!("{tag} dropping {count} live session(s)"));
+                    let awaiting = sessions
+                        .iter()
+                        .filter(|(_, live)| live.awaiting())
+                        .map(|(id, _)| Some(id.clone()))
+                        .collect();
+                    drop(sessions);
+                    exit_stdin_lost(&mut writer, lost, timeout, awaiting);
+                }
+            }
+        }
+    }
+
+    let init: I = serde_json::from_value(first)
+        .unwrap_or_else(|e| fail_sign(&format!("failed to parse sign init JSON: {e}")));
+    let state = build_timed(&build, init).unwrap_or_else(|e| fail_sign(&e));
+
+    let start = Instant::now();
+    let party = state.party_index;
+    progress(Event::SessionCreated { session_id: None, party }, || {
+        format!("{tag} session created for party {party}")
+    });
+
+    if run_round_loop(tag, &lines, timeout, state) {
+        let secs = secs_since(start);
+        progress(Event::SignDone { secs }, || format!("{tag} complete in {secs:.1}s"));
+    }
+}
+
+/// A session the single-session loop drives: a signing session or a
+/// `dkg-party`. `Output` is the reply to each line.
+trait RoundSession {
+    type Output: Serialize;
+
+    /// Initial drive — the first messages.
+    fn start(&mut self) -> Result<Self::Output, String>;
+    /// One line of incoming messages.
+    fn process_round(&mut self, incoming: &[WasmSignMessage]) -> Result<Self::Output, String>;
+    /// Abandon the session; the reply carries the `abort` notice.
+    fn abort(&mut self, reason: &str) -> Self::Output;
+    /// The last reply: the session has its output.
+    fn is_complete(output: &Self::Output) -> bool;
+}
+
+impl RoundSession for SignState {
+    type Output = SignOutput;
+
+    fn start(&mut self) -> Result<SignOutput, String> {
+        SignState::start(self)
+    }
+
+    fn process_round(&mut self, incoming: &[WasmSignMessage]) -> Result<SignOutput, String> {
+        SignState::process_round(self, incoming)
+    }
+
+    fn abort(&mut self, reason: &str) -> SignOutput {
+        SignState::abort(self, reason)
+    }
+
+    fn is_complete(output: &SignOutput) -> bool {
+        output.complete && !output.more
+    }
+}
+
+/// The single-session protocol after the init line: reply to the start,
+/// then to each line of incoming messages, until a reply is complete
+/// (true) or an `abort` line has been answered (false). Any failure ends
+/// the process.
+fn run_round_loop<S: RoundSession>(tag: &str, lines: &StdinLines, timeout: Duration, mut state: S) -> bool {
+    let mut writer = Output;
+    let mut output = state.start().unwrap_or_else(|e| fail_round(tag, &e));
+    write_line(&mut writer, &output);
+    while !S::is_complete(&output) {
+        let line = match lines.next() {
+            StdinLine::Line(line) => line,
+            StdinLine::Rejected(e) => {
+                drop(state);
+                fail_round(tag, &e);
+            }
+            lost => {
+                drop(state);
+                exit_stdin_lost(&mut writer, lost, timeout, vec![None]);
+            }
+        };
+        let value: serde_json::Value = serde_json::from_str(&line)
+            .unwrap_or_else(|e| fail_round(tag, &format!("parse incoming messages JSON: {e}")));
+        if value.get("type").and_then(|v| v.as_str()) == Some("abort") {
+            let reason = value.get("reason").and_then(|v| v.as_str()).unwrap_or_default();
+            write_line(&mut writer, &state.abort(reason));
+            return false;
+        }
+        let incoming: Vec<WasmSignMessage> = serde_json::from_value(value)
+            .unwrap_or_else(|e| fail_round(tag, &format!("parse incoming messages JSON: {e}")));
+        output = state.process_round(&incoming).unwrap_or_else(|e| fail_round(tag, &e));
+        write_line(&mut writer, &output);
+    }
+    true
+}
+
+/// Run `build`, recording how long it took as the session's `setup_ms`.
+fn build_timed<I, B>(build: &B, init: I) -> Result<SignState, String>
+where
+    B: Fn(I) -> Result<SignState, String>,
+{
+    let start = clock::now_ms();
+    let mut state = build(init)?;
+    state.metrics.setup_ms = clock::now_ms() - start;
+    Ok(state)
+}
+
+/// Exit code when stdin times out or closes while a session is live.
+const EXIT_STDIN_LOST: i32 = 3;
+
+/// How often a blocked sign loop checks for a shutdown request.
+const SHUTDOWN_POLL: Duration = Duration::from_millis(200);
+
+/// Longest accepted input line: a full round of maximum-size messages plus
+/// envelope overhead. Anything longer is skipped with `LINE_TOO_LONG`.
+const MAX_LINE_BYTES: usize =
+    limits::DEFAULT_MAX_MESSAGES_PER_ROUND * (limits::DEFAULT_MAX_MESSAGE_BYTES + 1024);
+
+enum StdinLine {
+    Line(String),
+    /// A line that was skipped (`LINE_TOO_LONG` / `INVALID_LINE`)
+    Rejected(String),
+    Eof,
+    Timeout,
+    /// SIGTERM / SIGINT arrived while waiting
+    Shutdown,
+}
+
+/// Non-empty input lines, read on a background thread so that waiting for
+/// the parent can time out instead of blocking forever.
+struct StdinLines {
+    rx: mpsc::Receiver<Result<String, String>>,
+    timeout: Duration,
+}
+
+impl StdinLines {
+    fn spawn(timeout: Duration) -> Self {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(input());
+            while let Ok(Some(line)) = read_bounded_line(&mut reader) {
+                if line.as_ref().is_ok_and(|l| l.trim().is_empty()) {
+                    continue;
+                }
+                if tx.send(line.map(|l| l.trim().to_string())).is_err() {
+                    break;
+                }
+            }
+            // Dropping `tx` reports EOF to the receiver
+        });
+        StdinLines { rx, timeout }
+    }
+
+    fn next(&self) -> StdinLine {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            if shutting_down() {
+                return StdinLine::Shutdown;
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return StdinLine::Timeout;
+            }
+            match self.rx.recv_timeout(left.min(SHUTDOWN_POLL)) {
+                Ok(Ok(line)) => return StdinLine::Line(line),
+                Ok(Err(e)) => return StdinLine::Rejected(e),
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => return StdinLine::Eof,
+            }
+        }
+    }
+}
+
+/// Read one line of at most [`MAX_LINE_BYTES`] without buffering more than
+/// that: an oversize line is consumed up to its newline and reported as
+/// `Err`. `Ok(None)` at EOF.
+fn read_bounded_line<R: BufRead>(reader: &mut R) -> std::io::Result<Option<Result<String, String>>> {
+    let mut buf = Vec::new();
+    let read = reader.by_ref().take(MAX_LINE_BYTES as u64 + 1).read_until(b'\n', &mut buf)?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if buf.len() > MAX_LINE_BYTES && buf.last() != Some(&b'\n') {
+        // Skip the rest of the line
+        loop {
+            let chunk = reader.fill_buf()?;
+            if chunk.is_empty() {
+                break;
+            }
+            match chunk.iter().position(|&b| b == b'\n') {
+                Some(i) => {
+                    reader.consume(i + 1);
+                    break;
+                }
+                None => {
+                    let len = chunk.len();
+                    reader.consume(len);
+                }
+            }
+        }
+        return Ok(Some(Err(format!("LINE_TOO_LONG: input line exceeds {MAX_LINE_BYTES} bytes"))));
+    }
+    Ok(Some(String::from_utf8(buf).map_err(|_| "INVALID_LINE: input line is not UTF-8".to_string())))
+}
+
+#[derive(Serialize)]
+struct FatalOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    error: ErrorReport,
+}
+
+/// Stop waiting for input: report `TIMEOUT` / `EOF` (exit code
+/// [`EXIT_STDIN_LOST`]) or `SHUTTING_DOWN` (exit code 0) once per session
+/// still awaiting input — `awaiting` lists their ids, `None` in
+/// single-session mode.
+///
+/// Callers drop their sessions first: dropping a key share zeroizes its
+/// secret, and `process::exit` runs no destructors.
+fn exit_stdin_lost<W: Write>(
+    writer: &mut W,
+    lost: StdinLine,
+    timeout: Duration,
+    mut awaiting: Vec<Option<String>>,
+) -> ! {
+    let (code, message, exit_code) = match lost {
+        StdinLine::Timeout => ("TIMEOUT", format!("no input for {}s", timeout.as_secs()), EXIT_STDIN_LOST),
+        StdinLine::Shutdown => ("SHUTTING_DOWN", "received SIGTERM / SIGINT".to_string(), 0),
+        _ => ("EOF", "stdin closed before signing completed".to_string(), EXIT_STDIN_LOST),
+    };
+    progress(Event::Error { session_id: None, message: &format!("{code}: {message}") }, || {
+        format!("[native-sign] {code}: {message}")
+    });
+    if awaiting.is_empty() {
+        awaiting.push(None);
+    }
+    for session_id in awaiting {
+        let error = ErrorReport {
+            code: code.into(),
+            message: message.clone(),
+        };
+        write_line(writer, &FatalOutput { session_id, error });
+    }
+    std::process::exit(exit_code);
+}
+
+/// Handle one multi-session line, writing exactly one output line.
+fn handle_session_line<I, B, W>(
+    tag: &str,
+    sessions: &mut HashMap<String, Live>,
+    value: serde_json::Value,
+    split_batch: Option<SplitBatch>,
+    build: &B,
+    writer: &mut W,
+) where
+    I: DeserializeOwned,
+    B: Fn(I) -> Result<SignState, String>,
+    W: Write,
+{
+    let session_id = value.get("session_id").and_then(|v| v.as_str()).map(str::to_string);
+    // Process-wide, so the only line that needs no session
+    if value.get("type").and_then(|v| v.as_str()) == Some("stats") {
+        write_line(writer, &SessionEvent {
+            session_id,
+            destroyed: None,
+            error: None,
+            failure: None,
+            stats: Some(metrics::memory_stats()),
+        });
+        return;
+    }
+    let Some(id) = session_id.clone() else {
+        write_line(writer, &SessionEvent {
+            session_id: None,
+            destroyed: None,
+            error: Some("INVALID_LINE: expected a JSON object with a string session_id".into()),
+            failure: None,
+            stats: None,
+        });
+        return;
+    };
+    let kind = value.get("type").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+    let result = match kind.as_str() {
+        "init" if sessions.contains_key(&id) => Err(format!("SESSION_EXISTS: session {id} is already live")),
+        "init" => serde_json::from_value::<I>(value)
+            .map_err(|e| format!("INVALID_INIT: {e}"))
+            .and_then(|init| build_timed(build, init))
+            .and_then(|mut state| {
+                let output = state.start()?;
+                let party = state.party_index;
+                progress(Event::SessionCreated { session_id: Some(&id), party }, || {
+                    format!("{tag} session {id} created for party {party}")
+                });
+                sessions.insert(id.clone(), Live::Single(state));
+                Ok(output)
+            }),
+        "init_batch" if sessions.contains_key(&id) => Err(format!("SESSION_EXISTS: session {id} is already live")),
+        "init_batch" => split_batch
+            .ok_or_else(|| format!("UNSUPPORTED: {tag} has no batch sessions"))
+            .and_then(|split| split(&value))
+            .and_then(|inits| {
+                inits
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, init)| {
+                        serde_json::from_value::<I>(init)
+                            .map_err(|e| format!("INVALID_INIT: {e}"))
+                            .and_then(|init| build_timed(build, init))
+                            .map_err(|e| format!("message {index}: {e}"))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .and_then(|mut members| {
+                let output = Live::start_batch(&mut members)?;
+                let (party, count) = (members[0].party_index, members.len());
+                progress(Event::SessionCreated { session_id: Some(&id), party }, || {
+                    format!("{tag} batch session {id} created for party {party} ({count} hashes)")
+                });
+                sessions.insert(id.clone(), Live::Batch(members));
+                Ok(output)
+            }),
+        "round" => match sessions.get_mut(&id) {
+            None => Err(format!("SESSION_NOT_FOUND: no live session {id}")),
+            Some(live) => serde_json::from_value::<Vec<WasmSignMessage>>(
+                value.get("messages").cloned().unwrap_or_default(),
+            )
+            .map_err(|e| format!("INVALID_MESSAGES: {e}"))
+            .and_then(|incoming| live.process_round(&incoming)),
+        },
+        "abort" => match sessions.remove(&id) {
+            None => Err(format!("SESSION_NOT_FOUND: no live session {id}")),
+            Some(mut live) => {
+                let reason = value.get("reason").and_then(|v| v.as_str()).unwrap_or_default();
+                let output = live.abort(reason);
+                if let Live::Failed(_) = live {
+                    // Stays failed until destroyed
+                    sessions.insert(id.clone(), live);
+                }
+                output
+            }
+        },
+        "destroy_key" => {
+            let key_id = value.get("key_id").and_then(|v| v.as_str()).unwrap_or_default();
+            let destroyed = hex::decode(key_id)
+                .ok()
+                .and_then(|key| <[u8; 32]>::try_from(key).ok())
+                .map(|key| cached_shares().remove(&key));
+            let error = match destroyed {
+                Some(_) => None,
+                None => Some("INVALID_LINE: key_id must be 64 hex chars".to_string()),
+            };
+            write_line(writer, &SessionEvent {
+                session_id: Some(id),
+                destroyed,
+                error,
+                failure: None,
+                stats: None,
+            });
+            return;
+        }
+        "destroy" => {
+            let destroyed = sessions.remove(&id).is_some();
+            write_line(writer, &SessionEvent {
+                session_id: Some(id),
+                destroyed: Some(destroyed),
+                error: None,
+                failure: None,
+                stats: None,
+            });
+            return;
+        }
+        other => Err(format!(
+            "INVALID_LINE: unknown type {other:?} (expected init, init_batch, round, abort, destroy, destroy_key or stats)"
+        )),
+    };
+
+    match result {
+        Ok(mut output) => {
+            output.session_id = Some(id);
+            write_line(writer, &output);
+        }
+        Err(e) => {
+            // A failed session can't be resumed: free its key material now,
+            // and keep refusing it until it is destroyed
+            if kind == "round" {
+                if let Some(live) = sessions.get_mut(&id) {
+                    if !matches!(live, Live::Failed(_)) {
+                        *live = Live::Failed(e.clone());
+                    }
+                }
+            }
+            progress(Event::Error { session_id: Some(&id), message: &e }, || format!("{tag} session {id}: {e}"));
+            write_line(writer, &SessionEvent {
+                session_id: Some(id),
+                destroyed: None,
+                failure: protocol_failure::parse(&e),
+                error: Some(e),
+                stats: None,
+            });
+        }
+    }
+}
+
+/// Combined ECDSA key shares, shared by every session of this process.
+static SHARE_CACHE: Mutex<ShareCache> = Mutex::new(ShareCache::new(share_cache::DEFAULT_CAPACITY));
+
+fn cached_shares() -> MutexGuard<'static, ShareCache> {
+    // Entries are only ever whole, so a poisoned lock is still consistent
+    SHARE_CACHE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn run_interactive_sign(requested: Option<Level>, timeout: Duration) {
+    run_interactive("[native-sign]", timeout, Some(split_sign_batch), |init: SignInit| {
+        build_sign_state(init, requested)
+    });
+}
+
+fn fail_sign(e: &str) -> ! {
+    fail_round("[native-sign]", e)
+}
+
+fn fail_round(tag: &str, e: &str) -> ! {
+    progress(Event::Error { session_id: None, message: e }, || format!("{tag} {e}"));
+    std::process::exit(1);
+}
+
+/// Decode an ECDSA init line; the share headers decide which curve /
+/// SecurityLevel to run at.
+fn build_sign_state(mut init: SignInit, requested: Option<Level>) -> Result<SignState, String> {
+    // Key material and hashes were decoded with the line; move them out
+    let core_bytes = std::mem::take(&mut init.core_share.0);
+    let aux_bytes = std::mem::take(&mut init.aux_info.0);
+    let (hash_bytes, message) = match (init.message_hash.take(), init.message.take()) {
+        (Some(hash), None) => (hash.0, None),
+        (None, Some(message)) => (Sha256::digest(&message.0).to_vec(), Some(message.0)),
+        _ => return Err("INVALID_INIT: give exactly one of message_hash and message".into()),
+    };
+    let eid_bytes = std::mem::take(&mut init.eid.0);
+
+    if hash_bytes.len() != 32 {
+        return Err(format!("message_hash must be 32 bytes, got {}", hash_bytes.len()));
+    }
+
+    let requested = init.security_level.as_deref().map(Level::parse).transpose()?.or(requested);
+    let requested_curve = init.curve.as_deref().map(CurveId::parse).transpose()?;
+    share_metadata::check_environment(&core_bytes)?;
+    share_metadata::check_environment(&aux_bytes)?;
+    let (core_header, core_payload) = share_format::split_expect(&core_bytes, BlobKind::CoreShare)?;
+    let (aux_header, aux_payload) = share_format::split_expect(&aux_bytes, BlobKind::AuxInfo)?;
+    let curve = share_format::ensure_curve(&core_header, requested_curve)?;
+    let level = share_format::ensure_level(&aux_header, requested)?;
+
+    let cache_key = share_cache::cache_key(&core_bytes, &aux_bytes);
+    with_curve!(curve, E => with_security_level!(level, L => sign_with_level::<E, L>(
+        init,
+        cache_key,
+        core_payload,
+        aux_payload,
+        hash_bytes,
+        message,
+        eid_bytes,
+    )))
+}
+
+/// Build the signing state machine for curve `E` at level `L`.
+fn sign_with_level<E, L>(
+    init: SignInit,
+    cache_key: share_cache::CacheKey,
+    core_payload: &[u8],
+    aux_payload: &[u8],
+    hash_bytes: Vec<u8>,
+    message: Option<Vec<u8>>,
+    eid_bytes: Vec<u8>,
+) -> Result<SignState, String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+{
+    // Deserialize key share, unless the cache has it already
+    let (key_share, cache_hit) = cached_shares().get_or_build(cache_key, || {
+        let core_share: cggmp24::IncompleteKeyShare<E> =
+            share_format::deserialize(BlobKind::CoreShare, core_payload)?;
+        let aux_info: cggmp24::key_share::AuxInfo<L> =
+            share_format::deserialize(BlobKind::AuxInfo, aux_payload)?;
+        cggmp24::KeyShare::from_parts((core_share, aux_info))
+            .map_err(|e| format!("combine key share from parts: {e}"))
+    })?;
+    let public_key = Point::from(key_share.shared_public_key());
+
+    // Map party_index (keygen index) → position within the parties array.
+    // The cggmp24 crate expects `i` to be the 0-based position, not the
+    // keygen party index. For parties=[0,1] the two are identical, but for
+    // parties=[1,2] keygen index 2 is at position 1. A set that could never
+    // finish is refused here, before anything is leaked.
+    let party_position = key_material::check_signer_set(
+        key_share.n(),
+        key_share.min_signers(),
+        init.party_index,
+        &init.parties_at_keygen,
+    )?;
+
+    // The scalar signed; for a raw message its SHA-256, as cggmp24 hashes it
+    let scalar = Scalar::<E>::from_be_bytes_mod_order(&hash_bytes);
+
+    // Leak everything the state machine borrows for 'static (reclaimed on Drop)
+    let key_share: Leaked<Arc<cggmp24::KeyShare<E, L>>> = Leaked::new(key_share);
+    let binding = binding::session_binding(&eid_bytes, &hash_bytes, &init.parties_at_keygen);
+    let audit = AuditTrail::new(
+        &binding,
+        &hash_bytes,
+        &init.parties_at_keygen,
+        init.party_index,
+        clock::unix_seconds(),
+        move |sig| signature::recovery_id(&public_key, &scalar, sig),
+    );
+    let eid_owned = Leaked::new(eid_bytes);
+    let parties_owned = Leaked::new(init.parties_at_keygen);
+    let rng = Leaked::new(protocol_rng()?);
+
+    // SAFETY: the handles live in the SignState next to the state machine and
+    // are dropped after it (see `SignState::drop`).
+    let (key_share_ref, eid_ref, parties_ref, rng_ref) = unsafe {
+        (
+            &**key_share.as_static(),
+            eid_owned.as_static().as_slice(),
+            parties_owned.as_static().as_slice(),
+            rng.as_static_mut(),
+        )
+    };
+
+    // Create the signing state machine (GMP-accelerated)
+    let signing = cggmp24::signing(cggmp24::ExecutionId::new(eid_ref), party_position, parties_ref, key_share_ref)
+        .enforce_reliable_broadcast(init.reliable_broadcast.unwrap_or(true));
+
+    let encoding = Encoding::parse_opt(init.encoding.as_deref())?;
+    let digest = ProtocolDigest::parse_opt(init.digest.as_deref())?;
+
+    // Signature self-check: the produced (r, s) must verify under the wallet key
+    let verify = !init.skip_verify;
+    let normalize_s = init.normalize_s.unwrap_or(true);
+    let normalized = Arc::new(AtomicBool::new(false));
+    let flipped = Arc::clone(&normalized);
+    let finish = move |result: Result<cggmp24::signing::Signature<E>, cggmp24::signing::SigningError>| {
+        let sig = result
+            .map_err(|e| ProtocolFailure::from_signing_error(&e, parties_ref).into_error())?;
+        let mut sig_bytes = vec![0u8; cggmp24::signing::Signature::<E>::serialized_len()];
+        sig.write_to_slice(&mut sig_bytes);
+        // Low s unless the init asked for the raw one; the audit record's
+        // recovery id is found from the bytes returned
+        if normalize_s && signature::normalize_s::<E>(&mut sig_bytes)? {
+            flipped.store(true, Ordering::Relaxed);
+        }
+        if verify {
+            signature::ensure_valid(&public_key, &scalar, &sig_bytes)?;
+        }
+        Ok(sig_bytes)
+    };
+
+    // The data to sign is leaked too; its type depends on the mode, and
+    // the state machine's on the digest
+    let (driver, data): (Box<dyn SignDriver>, Box<dyn LeakedAlloc>) = with_digest!(digest, D => {
+        let signing = signing.set_digest::<D>();
+        match message {
+            Some(message) => {
+                let data = Leaked::new(cggmp24::signing::DataToSign::<E>::digest::<Sha256>(&message));
+                // SAFETY: as above
+                let data_ref = unsafe { data.as_static() };
+                (Driver::boxed(signing.sign_sync(rng_ref, data_ref), init.party_index, encoding, finish), Box::new(data))
+            }
+            None => {
+                let data = Leaked::new(cggmp24::signing::PrehashedDataToSign::from_scalar(scalar));
+                // SAFETY: as above
+                let data_ref = unsafe { data.as_static() };
+                (Driver::boxed(signing.sign_sync(rng_ref, data_ref), init.party_index, encoding, finish), Box::new(data))
+            }
+        }
+    });
+
+    let mut state = SignState::new(
+        driver,
+        init.party_index,
+        vec![
+            Box::new(key_share),
+            data,
+            Box::new(eid_owned),
+            Box::new(parties_owned),
+            Box::new(rng),
+        ],
+        binding,
+        encoding,
+        core_payload.len() + aux_payload.len(),
+    )
+    .with_digest(digest)
+    .with_max_outgoing_bytes(init.max_outgoing_bytes)
+    .with_limits(&init.limits)
+    .with_audit(audit)
+    .with_normalized(normalized)
+    .with_auth(init.auth_secret.as_deref())?;
+    state.metrics.cache_hit = cache_hit;
+    Ok(state)
+}
+
+// ---------------------------------------------------------------------------
+// Threshold Schnorr (FROST, BIP-340) — same envelope as ECDSA signing
+// ---------------------------------------------------------------------------
+
+#[derive(Serialize)]
+struct FrostDkgOutput {
+    /// base64-encoded FROST key shares (headered, kind `frost_key_share`)
+    shares: Vec<String>,
+    /// hex-encoded compressed public key (33 bytes)
+    public_key: String,
+    /// hex-encoded BIP-340 x-only public key (32 bytes)
+    x_only_public_key: String,
+}
+
+#[derive(Deserialize)]
+struct FrostSignInit {
+    key_share: String,          // base64
+    message: String,            // hex, signed as-is
+    party_index: u16,
+    signers: Vec<u16>,
+    /// Skip BIP-340 verification of the final signature
+    #[serde(default)]
+    skip_verify: bool,
+    /// Overrides of the default message limits
+    #[serde(default)]
+    limits: LimitOverrides,
+    /// Shared secret for message authentication (see `SignInit`)
+    #[serde(default)]
+    auth_secret: Option<String>,
+}
+
+fn run_frost_dkg(n: u16, threshold: u16, eid_bytes: &[u8]) -> Result<FrostDkgOutput, String> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+
+    progress(Event::PhaseStart { phase: "frost_keygen", n }, || {
+        format!("FROST keygen ({n} parties, threshold {threshold})...")
+    });
+    let mut parties = Vec::new();
+    for i in 0..n {
+        let eid = givre::keygen::ExecutionId::new(eid_bytes);
+        let mut rng = protocol_rng()?;
+        parties.push(round_based::state_machine::wrap_protocol(
+            move |party| async move {
+                givre::keygen::<generic_ec::curves::Secp256k1>(eid, i, n)
+                    .set_threshold(threshold)
+                    .start(&mut rng, party)
+                    .await
+            },
+        ));
+    }
+
+    let results = simulate(parties).map_err(|e| format!("frost keygen failed: {e}"))?;
+    let mut key_shares = Vec::new();
+    for (i, result) in results.into_iter().enumerate() {
+        key_shares.push(result.map_err(|e| format!("frost keygen party {i}: {e:?}"))?);
+    }
+
+    let pk = Point::from(key_shares[0].shared_public_key());
+    let x_only = pk.x().ok_or("frost keygen produced the point at infinity")?;
+
+    let mut shares = Vec::new();
+    for (i, share) in key_shares.iter().enumerate() {
+        let bytes = share_format::serialize(BlobKind::FrostKeyShare, Some(CurveId::Secp256k1), None, share)
+            .map_err(|e| format!("party {i}: {e}"))?;
+        shares.push(b64.encode(&bytes));
+    }
+
+    Ok(FrostDkgOutput {
+        shares,
+        public_key: hex::encode(pk.to_bytes(true).as_bytes()),
+        x_only_public_key: hex::encode(x_only.as_be_bytes()),
+    })
+}
+
+fn run_interactive_frost_sign(timeout: Duration) {
+    run_interactive("[native-frost-sign]", timeout, None, build_frost_state);
+}
+
+/// Decode a FROST init line and build its signing state machine.
+fn build_frost_state(init: FrostSignInit) -> Result<SignState, String> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+
+    let share_bytes = b64.decode(&init.key_share).map_err(|e| format!("decode key_share base64: {e}"))?;
+    let message = hex::decode(&init.message).map_err(|e| format!("decode message hex: {e}"))?;
+
+    let (_, payload) = share_format::split_expect(&share_bytes, BlobKind::FrostKeyShare)?;
+    let key_share: givre::KeyShare<generic_ec::curves::Secp256k1> =
+        share_format::deserialize(BlobKind::FrostKeyShare, payload)?;
+    let public_key = Point::from(key_share.shared_public_key());
+
+    let position = init
+        .signers
+        .iter()
+        .position(|&p| p == init.party_index)
+        .ok_or_else(|| {
+            format!(
+                "party_index {} not found in signers {:?}",
+                init.party_index, init.signers
+            )
+        })?;
+    let position = party_limits::index(position)?;
+
+    // Leak everything the state machine borrows for 'static (reclaimed on Drop)
+    let key_share = Leaked::new(key_share);
+    let binding = binding::session_binding(&[], &message, &init.signers);
+    let signers_owned = Leaked::new(init.signers);
+    let message_owned = Leaked::new(message);
+
+    // SAFETY: the handles live in the SignState next to the state machine and
+    // are dropped after it (see `SignState::drop`).
+    let (key_share_ref, signers_ref, message_ref) = unsafe {
+        (
+            key_share.as_static(),
+            signers_owned.as_static().as_slice(),
+            message_owned.as_static().as_slice(),
+        )
+    };
+
+    let mut rng = protocol_rng()?;
+    let sm = round_based::state_machine::wrap_protocol(move |party| async move {
+        givre::signing::<givre::ciphersuite::Bip340>(position, key_share_ref, signers_ref, message_ref)
+            .sign(&mut rng, party)
+            .await
+    });
+
+    // Wire signature is BIP-340's `R.x || s`
+    let verify = !init.skip_verify;
+    // FROST messages are JSON, as in the WASM crate
+    let driver = Driver::boxed(sm, init.party_index, Encoding::Json, move |result| {
+        let sig = result.map_err(|e| format!("frost signing protocol error: {e:?}"))?;
+        let r = sig.r.x().ok_or("frost signing produced R at infinity")?;
+        let mut sig_bytes = r.as_be_bytes().to_vec();
+        sig_bytes.extend_from_slice(sig.z.to_be_bytes().as_bytes());
+        if verify {
+            signature::ensure_valid_bip340(&public_key, message_ref, &sig_bytes)?;
+        }
+        Ok(sig_bytes)
+    });
+
+    SignState::new(
+        driver,
+        init.party_index,
+        vec![
+            Box::new(key_share),
+            Box::new(signers_owned),
+            Box::new(message_owned),
+        ],
+        binding,
+        Encoding::Json,
+        share_bytes.len(),
+    )
+    .with_limits(&init.limits)
+    .with_auth(init.auth_secret.as_deref())
+}
+
+// ---------------------------------------------------------------------------
+// Interactive DKG party (`dkg-party`)
+// ---------------------------------------------------------------------------
+//
+// One party of a distributed DKG over the single-session `sign` protocol:
+// the first line is a `DkgPartyInit`, every following line a JSON array of
+// incoming messages (or an `abort` line), and every reply a
+// `DkgPartyOutput`. The party runs aux_info_gen, then keygen. Messages
+// carry the phase they belong to in `message_index` (0 aux_info_gen,
+// 1 keygen) and each phase has its own binding tag; keygen messages from a
+// peer that got there first are held until this party does. The last
+// reply has `complete: true`, the party's share and the public key.
+
+#[derive(Deserialize)]
+struct DkgPartyInit {
+    party_index: u16,
+    n: u16,
+    threshold: u16,
+    eid: String, // hex, 32 bytes, the same for every party
+    /// base64 `primes` blob; generated here when absent
+    #[serde(default)]
+    primes: Option<String>,
+    /// Overrides of the default message limits, per phase
+    #[serde(default)]
+    limits: LimitOverrides,
+    /// Hex-encoded secret shared by the parties (see `SignInit`)
+    #[serde(default)]
+    auth_secret: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DkgPartyOutput {
+    messages: Vec<WasmSignMessage>,
+    complete: bool,
+    /// `aux_info_gen` or `keygen`: where the party is after this line
+    phase: &'static str,
+    /// Incoming messages dropped as stale (earlier round or phase)
+    stale_messages: u32,
+    /// Time spent on this line in the current phase; absent on the init reply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metrics: Option<RoundMetrics>,
+    /// The party's key material, on the last reply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    share: Option<DkgShare>,
+    /// hex-encoded compressed public key, on the last reply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wallet_id: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    aborted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+/// `message_index` of each phase's messages, and the label bound into its
+/// binding tag.
+const DKG_PHASES: [&str; 2] = ["aux_info_gen", "keygen"];
+const PHASE_AUX: u32 = 0;
+const PHASE_KEYGEN: u32 = 1;
+
+/// One party of a distributed DKG.
+struct DkgParty {
+    party_index: u16,
+    n: u16,
+    threshold: u16,
+    eid: Vec<u8>,
+    curve: CurveId,
+    limits: LimitOverrides,
+    auth_secret: Option<String>,
+    passphrase: Option<String>,
+    /// Index into `DKG_PHASES`
+    phase: u32,
+    /// The current phase's state machine; its output is the phase's blob
+    state: SignState,
+    /// Serialised AuxInfo, once aux_info_gen is done
+    aux_info: Option<Vec<u8>>,
+    /// Keygen messages that arrived during aux_info_gen
+    early: Vec<WasmSignMessage>,
+}
+
+impl DkgParty {
+    /// Check the init line and start aux_info_gen. The level comes from
+    /// `requested` or the primes blob; the curve is the `--curve` one.
+    fn new(
+        init: DkgPartyInit,
+        curve: CurveId,
+        requested: Option<Level>,
+        passphrase: Option<String>,
+    ) -> Result<Self, String> {
+        let DkgPartyInit { party_index, n, threshold, eid, primes, limits, auth_secret } = init;
+        if n < 2 || threshold < 2 || threshold > n {
+            return Err(format!("INVALID_INIT: need 2 <= threshold <= n, got threshold {threshold} with n = {n}"));
+        }
+        party_limits::check_key_params(n, threshold)?;
+        if party_index >= n {
+            return Err(format!("INVALID_INIT: party_index {party_index} is not below n = {n}"));
+        }
+        let eid = hex::decode(&eid).map_err(|e| format!("decode eid hex: {e}"))?;
+        if eid.len() != 32 {
+            return Err(format!("INVALID_INIT: eid must be 32 bytes, got {}", eid.len()));
+        }
+
+        let primes = primes
+            .map(|line| base64::engine::general_purpose::STANDARD.decode(line.trim()))
+            .transpose()
+            .map_err(|e| format!("decode primes base64: {e}"))?;
+        let level = match &primes {
+            Some(bytes) => {
+                let (header, _) = share_format::split_expect(bytes, BlobKind::Primes)?;
+                share_format::ensure_level(&header, requested)?
+            }
+            None => requested.unwrap_or_default(),
+        };
+        let state = with_security_level!(level, L => {
+            let primes: cggmp24::PregeneratedPrimes<L> = match &primes {
+                Some(bytes) => share_format::deserialize(BlobKind::Primes, share_format::split(bytes)?.1)?,
+                None => {
+                    let start = Instant::now();
+                    let primes = cggmp24::PregeneratedPrimes::generate(&mut MixedRng);
+                    let secs = secs_since(start);
+                    progress(Event::PrimeDone { party: party_index.into(), secs }, || {
+                        format!("[native-dkg-party] primes generated in {secs:.1}s")
+                    });
+                    primes
+                }
+            };
+            aux_phase_state::<L>(party_index, n, &eid, level, primes)
+        })?
+        .with_limits(&limits)
+        .with_auth(auth_secret.as_deref())?;
+
+        Ok(DkgParty {
+            party_index,
+            n,
+            threshold,
+            eid,
+            curve,
+            limits,
+            auth_secret,
+            passphrase,
+            phase: PHASE_AUX,
+            state,
+            aux_info: None,
+            early: Vec::new(),
+        })
+    }
+
+    /// Sort incoming messages by phase: the current phase's (and untagged
+    /// ones, such as `abort` notices) to `current`, keygen ones received
+    /// during aux_info_gen to `early`. Returns how many were stale.
+    fn route(&mut self, incoming: &[WasmSignMessage], current: &mut Vec<WasmSignMessage>) -> Result<u32, String> {
+        let mut stale = 0;
+        for msg in incoming {
+            match msg.message_index {
+                None => current.push(msg.clone()),
+                Some(phase) if phase == self.phase => current.push(msg.clone()),
+                Some(phase) if phase < self.phase => stale += 1,
+                Some(PHASE_KEYGEN) => {
+                    let held = u32::try_from(self.early.len()).unwrap_or(u32::MAX);
+                    if held >= self.state.limits.max_messages_per_session {
+                        return Err(format!(
+                            "TOO_MANY_MESSAGES: more than {} keygen messages held back during aux_info_gen",
+                            self.state.limits.max_messages_per_session
+                        ));
+                    }
+                    self.early.push(msg.clone());
+                }
+                Some(other) => {
+                    return Err(format!(
+                        "INVALID_MESSAGE_INDEX: message from party {} has message_index {other}, a DKG has phases 0 and 1",
+                        msg.sender
+                    ))
+                }
+            }
+        }
+        Ok(stale)
+    }
+
+    /// Tag a phase's outgoing messages with it.
+    fn stamp(&self, mut output: SignOutput) -> SignOutput {
+        for msg in &mut output.messages {
+            msg.message_index = Some(self.phase);
+        }
+        output
+    }
+
+    /// Hand the current phase `incoming`, stamping its output. A rejected
+    /// message fails the line — a `dkg-party` reply has no `rejected`, and
+    /// a failure ends the party.
+    fn deliver(&mut self, incoming: &[WasmSignMessage]) -> Result<SignOutput, String> {
+        let output = self.state.process_round(incoming)?;
+        if let Some(rejection) = output.rejected.first() {
+            return Err(rejection.error());
+        }
+        Ok(self.stamp(output))
+    }
+
+    /// aux_info_gen is done: start keygen, then hand it the keygen
+    /// messages already received. Returns the messages to send.
+    fn start_keygen(&mut self) -> Result<(Vec<WasmSignMessage>, u32), String> {
+        let state = with_curve!(self.curve, E => {
+            keygen_phase_state::<E>(self.party_index, self.n, self.threshold, &self.eid, self.curve)
+        })?
+        .with_limits(&self.limits)
+        .with_auth(self.auth_secret.as_deref())?;
+        // Dropping the aux_info_gen state machine here
+        self.state = state;
+        self.phase = PHASE_KEYGEN;
+        progress(Event::PhaseStart { phase: "keygen", n: self.n }, || {
+            "[native-dkg-party] aux_info_gen done, starting keygen".to_string()
+        });
+
+        let mut messages = self.stamp(self.state.start()?).messages;
+        let mut stale = 0;
+        if !self.early.is_empty() {
+            let early = std::mem::take(&mut self.early);
+            let output = self.deliver(&early)?;
+            messages.extend(output.messages);
+            stale = output.stale_messages;
+        }
+        Ok((messages, stale))
+    }
+
+    /// The reply for `output`, with the share once keygen is done.
+    fn output(&self, output: SignOutput) -> Result<DkgPartyOutput, String> {
+        let mut reply = DkgPartyOutput {
+            messages: output.messages,
+            complete: false,
+            phase: phase_name(self.phase),
+            stale_messages: output.stale_messages,
+            metrics: output.metrics,
+            share: None,
+            public_key: None,
+            wallet_id: None,
+            aborted: output.aborted,
+            reason: output.reason,
+        };
+        let (Some(core_bytes), Some(aux_bytes)) = (&self.state.signature, &self.aux_info) else {
+            return Ok(reply);
+        };
+        if self.phase != PHASE_KEYGEN {
+            return Ok(reply);
+        }
+
+        let public_key = key_material::extract_public_key(core_bytes)?;
+        let share_fingerprint = fingerprint::share_fingerprint(core_bytes);
+        let (core_share, aux_info) = match self.passphrase.as_deref() {
+            Some(passphrase) => {
+                let params = share_crypto::KdfParams::default();
+                (
+                    share_crypto::encrypt_share(core_bytes, passphrase, params)?,
+                    share_crypto::encrypt_share(aux_bytes, passphrase, params)?,
+                )
+            }
+            None => (core_bytes.clone(), aux_bytes.clone()),
+        };
+        reply.complete = true;
+        reply.share = Some(DkgShare {
+            core_share: core_share.into(),
+            aux_info: aux_info.into(),
+            share_fingerprint,
+        });
+        reply.wallet_id = Some(fingerprint::wallet_id(&public_key)?);
+        reply.public_key = Some(hex::encode(public_key));
+        Ok(reply)
+    }
+}
+
+impl RoundSession for DkgParty {
+    type Output = DkgPartyOutput;
+
+    fn start(&mut self) -> Result<DkgPartyOutput, String> {
+        let output = self.stamp(self.state.start()?);
+        self.output(output)
+    }
+
+    fn process_round(&mut self, incoming: &[WasmSignMessage]) -> Result<DkgPartyOutput, String> {
+        let mut current = Vec::new();
+        let stale = self.route(incoming, &mut current)?;
+        let mut output = self.deliver(&current)?;
+        output.stale_messages += stale;
+
+        if self.phase == PHASE_AUX {
+            if let Some(aux_info) = self.state.signature.take() {
+                self.aux_info = Some(aux_info);
+                let (messages, stale) = self.start_keygen()?;
+                output.messages.extend(messages);
+                output.stale_messages += stale;
+            }
+        }
+        self.output(output)
+    }
+
+    fn abort(&mut self, reason: &str) -> DkgPartyOutput {
+        let output = self.stamp(self.state.abort(reason));
+        let mut reply = self.output(output).expect("an aborted party has no share to encode");
+        reply.complete = false;
+        reply
+    }
+
+    fn is_complete(output: &DkgPartyOutput) -> bool {
+        output.complete
+    }
+}
+
+/// Binding tag of one phase of the DKG over `eid`.
+fn dkg_phase_binding(eid: &[u8], phase: u32, n: u16) -> String {
+    let parties: Vec<u16> = (0..n).collect();
+    binding::session_binding(eid, phase_name(phase).as_bytes(), &parties)
+}
+
+/// `DKG_PHASES` entry of a phase.
+fn phase_name(phase: u32) -> &'static str {
+    usize::try_from(phase)
+        .ok()
+        .and_then(|phase| DKG_PHASES.get(phase))
+        .expect("phase is PHASE_AUX or PHASE_KEYGEN")
+}
+
+/// aux_info_gen for one party; its output is the serialised AuxInfo.
+fn aux_phase_state<L: SecurityLevel>(
+    party_index: u16,
+    n: u16,
+    eid: &[u8],
+    level: Level,
+    primes: cggmp24::PregeneratedPrimes<L>,
+) -> Result<SignState, String> {
+    let eid_owned = Leaked::new(eid.to_vec());
+    // SAFETY: the handle lives in the SignState next to the state machine
+    // and is dropped after it (see `SignState::drop`).
+    let eid_ref = unsafe { eid_owned.as_static().as_slice() };
+
+    let mut rng = protocol_rng()?;
+    let sm = round_based::state_machine::wrap_protocol(move |party| async move {
+        cggmp24::aux_info_gen(cggmp24::ExecutionId::new(eid_ref), party_index, n, primes)
+            .start(&mut rng, party)
+            .await
+    });
+    let driver = Driver::boxed(sm, party_index, share_format::default_encoding(), move |result| {
+        let aux = result.map_err(|e| format!("aux_info_gen failed: {e:?}"))?;
+        share_format::serialize(BlobKind::AuxInfo, None, Some(level), &aux)
+    });
+    Ok(SignState::new(
+        driver,
+        party_index,
+        vec![Box::new(eid_owned)],
+        dkg_phase_binding(eid, PHASE_AUX, n),
+        share_format::default_encoding(),
+        0,
+    ))
+}
+
+/// keygen for one party; its output is the serialised CoreKeyShare.
+fn keygen_phase_state<E>(party_index: u16, n: u16, threshold: u16, eid: &[u8], curve: CurveId) -> Result<SignState, String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+{
+    let eid_owned = Leaked::new(eid.to_vec());
+    // SAFETY: as in `aux_phase_state`
+    let eid_ref = unsafe { eid_owned.as_static().as_slice() };
+
+    let mut rng = protocol_rng()?;
+    let sm = round_based::state_machine::wrap_protocol(move |party| async move {
+        cggmp24::keygen::<E>(cggmp24::ExecutionId::new(eid_ref), party_index, n)
+            .set_threshold(threshold)
+            .start(&mut rng, party)
+            .await
+    });
+    let driver = Driver::boxed(sm, party_index, share_format::default_encoding(), move |result| {
+        let share = result.map_err(|e| format!("keygen failed: {e:?}"))?;
+        share_format::serialize(BlobKind::CoreShare, Some(curve), None, &share)
+    });
+    Ok(SignState::new(
+        driver,
+        party_index,
+        vec![Box::new(eid_owned)],
+        dkg_phase_binding(eid, PHASE_KEYGEN, n),
+        share_format::default_encoding(),
+        0,
+    ))
+}
+
+/// `dkg-party`: read the init line, then run the round loop.
+fn run_dkg_party(curve: CurveId, requested: Option<Level>, timeout: Duration, passphrase: Option<String>) {
+    let tag = "[native-dkg-party]";
+    let lines = StdinLines::spawn(timeout);
+    let init_line = match lines.next() {
+        StdinLine::Line(line) => line,
+        StdinLine::Rejected(e) => fail_round(tag, &e),
+        lost => exit_stdin_lost(&mut Output, lost, timeout, Vec::new()),
+    };
+    let init: DkgPartyInit = serde_json::from_str(&init_line)
+        .unwrap_or_else(|e| fail_round(tag, &format!("INVALID_INIT: {e}")));
+    let party = init.party_index;
+    let state = DkgParty::new(init, curve, requested, passphrase).unwrap_or_else(|e| fail_round(tag, &e));
+
+    let start = Instant::now();
+    progress(Event::SessionCreated { session_id: None, party }, || {
+        format!("{tag} session created for party {party}")
+    });
+    if run_round_loop(tag, &lines, timeout, state) {
+        let secs = secs_since(start);
+        progress(Event::DkgDone { secs }, || format!("{tag} complete in {secs:.1}s"));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Aux info refresh (new Paillier material, same core shares)
+// ---------------------------------------------------------------------------
+
+// ---------------------------------------------------------------------------
+// Aux info refresh (new Paillier material, same core shares)
+// ---------------------------------------------------------------------------
+
+#[derive(Serialize)]
+struct RefreshAuxOutput {
+    /// base64-encoded serialized AuxInfo per party, in keygen index order
+    aux_infos: Vec<String>,
+    /// hex-encoded compressed public key (33 bytes), unchanged
+    public_key: String,
+}
+
+/// Refresh the aux info of the key whose base64 core shares are in
+/// `share_lines`, using the base64 primes in `primes_path` if given.
+fn refresh_aux(
+    share_lines: &[String],
+    eid_bytes: &[u8],
+    primes_path: Option<&Path>,
+    requested: Option<Level>,
+) -> Result<RefreshAuxOutput, String> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let shares = share_lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| b64.decode(line.trim()).map_err(|e| format!("decode core share {i}: {e}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let primes = match primes_path {
+        Some(path) => {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| format!("read {}: {e}", path.display()))?;
+            let primes = text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .enumerate()
+                .map(|(i, line)| b64.decode(line.trim()).map_err(|e| format!("decode primes {i}: {e}")))
+                .collect::<Result<Vec<_>, _>>()?;
+            Some(primes)
+        }
+        None => None,
+    };
+
+    let refreshed = aux_refresh::refresh(eid_bytes, &shares, primes.as_deref(), requested)?;
+    Ok(RefreshAuxOutput {
+        aux_infos: refreshed.aux_infos.iter().map(|aux| b64.encode(aux)).collect(),
+        public_key: hex::encode(&refreshed.public_key),
+    })
+}
+
+/// Replace every aux blob in `output` with its encrypted envelope.
+fn encrypt_refresh_output(output: &mut RefreshAuxOutput, passphrase: &str) -> Result<(), String> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let params = share_crypto::KdfParams::default();
+    for (i, aux) in output.aux_infos.iter_mut().enumerate() {
+        let plain = zeroize::Zeroizing::new(
+            b64.decode(&*aux).map_err(|e| format!("decode aux info {i}: {e}"))?,
+        );
+        *aux = b64.encode(share_crypto::encrypt_share(&plain, passphrase, params)?);
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Private key export (single point of failure — confirmed opt-in only)
+// ---------------------------------------------------------------------------
+
+#[derive(Serialize)]
+struct ExportKeyOutput {
+    /// hex-encoded 32-byte private key
+    private_key: String,
+    /// hex-encoded compressed public key (33 bytes)
+    public_key: String,
+    curve: &'static str,
+}
+
+/// Reconstruct the private key from base64 core shares (one per line).
+fn export_key(share_lines: &[String], confirm: &str) -> Result<ExportKeyOutput, String> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let shares = share_lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| b64.decode(line.trim()).map_err(|e| format!("decode core share {i}: {e}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let exported = key_export::reconstruct_private_key(&shares, confirm)?;
+    Ok(ExportKeyOutput {
+        private_key: hex::encode(&*exported.private_key),
+        public_key: hex::encode(&exported.public_key),
+        curve: exported.curve.as_str(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// tss-lib import (same conversion as the WASM `import_tsslib_share`)
+// ---------------------------------------------------------------------------
+
+#[derive(Serialize)]
+struct TsslibImportOutput {
+    /// base64 serialised CoreKeyShares, in keygen index order; pipe them to
+    /// `refresh-aux` for aux info
+    core_shares: Vec<String>,
+    /// Keygen index of each share
+    party_indices: Vec<u16>,
+    /// Parties of the key; `refresh-aux` needs a share from each
+    n: u16,
+    /// hex compressed shared public key
+    public_key: String,
+    wallet_id: String,
+    share_fingerprints: Vec<String>,
+}
+
+/// `import-tsslib`: convert each party's save file, checking that they are
+/// shares of one key and no party appears twice.
+fn import_tsslib(files: &[PathBuf], threshold: u16) -> Result<TsslibImportOutput, String> {
+    let mut shares = Vec::with_capacity(files.len());
+    for path in files {
+        let bytes = std::fs::read(path).map_err(|e| format!("READ_FAILED: {}: {e}", path.display()))?;
+        let share = interop::import_tsslib_share(&bytes, threshold)
+            .map_err(|e| format!("{e} ({})", path.display()))?;
+        shares.push(share);
+    }
+    shares.sort_by_key(|share| share.party_index);
+
+    let public_key = shares[0].public_key.clone();
+    for (j, share) in shares.iter().enumerate() {
+        if share.public_key != public_key {
+            return Err("PUBLIC_KEY_MISMATCH: the save files belong to different keys".into());
+        }
+        if j > 0 && shares[j - 1].party_index == share.party_index {
+            return Err(format!("SHARE_MISMATCH: party {} given twice", share.party_index));
+        }
+    }
+
+    let b64 = base64::engine::general_purpose::STANDARD;
+    Ok(TsslibImportOutput {
+        core_shares: shares.iter().map(|share| b64.encode(&share.core_share)).collect(),
+        party_indices: shares.iter().map(|share| share.party_index).collect(),
+        n: shares[0].n,
+        wallet_id: fingerprint::wallet_id(&public_key)?,
+        public_key: hex::encode(&public_key),
+        share_fingerprints: shares
+            .iter()
+            .map(|share| fingerprint::share_fingerprint(&share.core_share))
+            .collect(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Key material (`extract-pubkey` / `combine`), same logic as the WASM exports
+// ---------------------------------------------------------------------------
+
+#[derive(Serialize)]
+struct PubkeyOutput {
+    curve: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+}
+
+/// `extract-pubkey`: the share's public key as hex, or its Ethereum address.
+fn extract_pubkey(share: Option<PathBuf>, format: PubkeyFormat) -> Result<PubkeyOutput, String> {
+    let bytes = read_blob(share.as_deref())?;
+    let public_key = key_material::extract_public_key(&bytes)?;
+    let (header, _) = share_format::split(&bytes)?;
+    let curve = header.curve_or_default();
+    let mut output = PubkeyOutput {
+        curve: curve.as_str(),
+        public_key: None,
+        address: None,
+    };
+    match format {
+        PubkeyFormat::Hex => output.public_key = Some(hex::encode(&public_key)),
+        PubkeyFormat::Address => {
+            if curve != CurveId::Secp256k1 {
+                return Err(format!(
+                    "UNSUPPORTED_CURVE: Ethereum addresses need a secp256k1 key, the share is {}",
+                    curve.as_str()
+                ));
+            }
+            output.address = Some(key_material::eth_address(&public_key)?);
+        }
+    }
+    Ok(output)
+}
+
+/// Name of the manifest `migrate` writes into the output directory.
+const MIGRATE_MANIFEST: &str = "manifest.json";
+
+/// `migrate`'s report, also written to `<out-dir>/manifest.json`.
+#[derive(Serialize)]
+struct MigrateManifest {
+    dry_run: bool,
+    migrated: usize,
+    /// Already binary
+    skipped: usize,
+    failed: usize,
+    files: Vec<MigrateEntry>,
+}
+
+#[derive(Serialize)]
+struct MigrateEntry {
+    /// File name, the same in both directories
+    file: String,
+    /// `migrated` (`would_migrate` in a dry run), `skipped` or `failed`
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<&'static str>,
+    /// `share_fingerprint` of the input blob and of its binary form
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_fingerprint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_fingerprint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorReport>,
+}
+
+/// `migrate`: convert each file of `in_dir` (not recursing) into `out_dir`.
+/// A file that fails is recorded and the rest carry on; only an unreadable
+/// input directory or an unwritable output one fails the whole run.
+fn migrate_dir(in_dir: &Path, out_dir: &Path, dry_run: bool) -> Result<MigrateManifest, String> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(in_dir)
+        .map_err(|e| format!("READ_FAILED: {}: {e}", in_dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    let manifest_path = out_dir.join(MIGRATE_MANIFEST);
+    if !dry_run {
+        std::fs::create_dir_all(out_dir).map_err(|e| format!("WRITE_FAILED: {}: {e}", out_dir.display()))?;
+        if manifest_path.exists() {
+            return Err(format!("OUTPUT_EXISTS: {} already exists", manifest_path.display()));
+        }
+    }
+
+    let files: Vec<MigrateEntry> = paths.iter().map(|path| migrate_file(path, out_dir, dry_run)).collect();
+    let count = |status: &str| files.iter().filter(|f| f.status == status).count();
+    let manifest = MigrateManifest {
+        dry_run,
+        migrated: count("migrated") + count("would_migrate"),
+        skipped: count("skipped"),
+        failed: count("failed"),
+        files,
+    };
+    if !dry_run {
+        let json = serde_json::to_string_pretty(&manifest).expect("serialize migrate manifest");
+        write_new(&manifest_path, format!("{json}\n").as_bytes())?;
+    }
+    Ok(manifest)
+}
+
+/// Convert one file; never fails, problems land in the entry.
+fn migrate_file(path: &Path, out_dir: &Path, dry_run: bool) -> MigrateEntry {
+    let file = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let mut entry = MigrateEntry {
+        file,
+        status: "failed",
+        kind: None,
+        old_fingerprint: None,
+        new_fingerprint: None,
+        error: None,
+    };
+    let raw = match std::fs::read(path) {
+        Ok(raw) => raw,
+        Err(e) => {
+            entry.error = Some(ErrorReport::new("READ_FAILED", &e.to_string()));
+            return entry;
+        }
+    };
+    let blob = decode_blob(raw.clone());
+    let was_base64 = blob != raw;
+    entry.old_fingerprint = Some(fingerprint::share_fingerprint(&blob));
+
+    let migrated = match migrate::migrate_share(&blob) {
+        Ok(migrated) => migrated,
+        Err(e) => {
+            if e.starts_with("ALREADY_MIGRATED") {
+                entry.status = "skipped";
+            }
+            entry.error = Some(ErrorReport::new("INVALID_SHARE", &e));
+            return entry;
+        }
+    };
+    entry.kind = Some(migrated.kind.as_str());
+    entry.new_fingerprint = Some(fingerprint::share_fingerprint(&migrated.blob));
+    if dry_run {
+        entry.status = "would_migrate";
+        return entry;
+    }
+
+    // Keep the container: base64 text in, base64 text out
+    let out = if was_base64 {
+        format!("{}\n", base64::engine::general_purpose::STANDARD.encode(&migrated.blob)).into_bytes()
+    } else {
+        migrated.blob
+    };
+    match write_new(&out_dir.join(&entry.file), &out) {
+        Ok(()) => entry.status = "migrated",
+        Err(e) => entry.error = Some(ErrorReport::new("WRITE_FAILED", &e)),
+    }
+    entry
+}
+
+/// Write `bytes` to a temporary file next to `path` and rename it into
+/// place, so `path` is either absent or complete.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let written = File::create(&tmp)
+        .and_then(|mut file| file.write_all(bytes).and_then(|()| file.sync_all()))
+        .and_then(|()| std::fs::rename(&tmp, path));
+    written.map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("WRITE_FAILED: {}: {e}", path.display())
+    })
+}
+
+/// Write `bytes` to `path`, failing with `OUTPUT_EXISTS` rather than
+/// replacing a file that is already there.
+fn write_new(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => format!("OUTPUT_EXISTS: {} already exists", path.display()),
+            _ => format!("WRITE_FAILED: {}: {e}", path.display()),
+        })?;
+    file.write_all(bytes).map_err(|e| format!("WRITE_FAILED: {}: {e}", path.display()))
+}
+
+#[derive(Serialize)]
+struct AddressOutput {
+    address: String,
+}
+
+/// `address`: the key's P2WPKH address, as the WASM `bitcoin_p2wpkh_address`.
+fn p2wpkh_address(pubkey: String, network: &str) -> Result<AddressOutput, String> {
+    let public_key = decode_hex_arg("pubkey", Some(pubkey), Some(33))?;
+    let network = bitcoin::Network::parse(network)?;
+    Ok(AddressOutput {
+        address: bitcoin::p2wpkh_address(&public_key, network)?,
+    })
+}
+
+/// `backup create`: bundle the share, as the WASM `create_backup_bundle`.
+fn backup_create(
+    share: &Path,
+    out: &Path,
+    label: Option<&str>,
+    passphrase: Option<&str>,
+) -> Result<backup::BackupMetadata, String> {
+    let share = zeroize::Zeroizing::new(read_blob_file(share)?);
+    let created_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let bundle = backup::create_backup_bundle(&share, created_at, label, passphrase)?;
+    write_new(out, &bundle)?;
+    backup::inspect(&bundle)
+}
+
+/// `backup restore`: the share inside a bundle, as the WASM `read_backup_bundle`.
+fn backup_restore(bundle: &Path, out: &Path, passphrase: Option<&str>) -> Result<backup::BackupMetadata, String> {
+    let bytes = std::fs::read(bundle).map_err(|e| format!("READ_FAILED: {}: {e}", bundle.display()))?;
+    let opened = backup::read_backup_bundle(&bytes, passphrase)?;
+    write_new(out, &opened.share)?;
+    Ok(opened.metadata)
+}
+
+#[derive(Serialize)]
+struct EncodedSigOutput {
+    /// hex DER signature with the sighash byte appended
+    script_sig: String,
+}
+
+/// `encode-sig`: the signature in Bitcoin form, as the WASM `bitcoin_script_sig`.
+fn encode_sig(r: String, s: String, sighash_type: u8) -> Result<EncodedSigOutput, String> {
+    let r = decode_hex_arg("r", Some(r), Some(32))?;
+    let s = decode_hex_arg("s", Some(s), Some(32))?;
+    Ok(EncodedSigOutput {
+        script_sig: hex::encode(bitcoin::script_sig(&r, &s, sighash_type)?),
+    })
+}
+
+#[derive(Serialize)]
+struct CommitmentOutput {
+    /// hex 69-byte share commitment
+    commitment: String,
+}
+
+/// `commitment`: the share's commitment, as the WASM `share_commitment`.
+fn share_commitment(share: Option<PathBuf>) -> Result<CommitmentOutput, String> {
+    let bytes = read_blob(share.as_deref())?;
+    Ok(CommitmentOutput {
+        commitment: hex::encode(commitment::share_commitment(&bytes)?),
+    })
+}
+
+/// `commitment --verify`: check the hex commitments on the input.
+fn verify_commitments(expect: Option<String>) -> Result<commitment::CommitmentReport, String> {
+    let commitments = read_input_lines()
+        .iter()
+        .enumerate()
+        .map(|(j, line)| {
+            hex::decode(line.trim().trim_start_matches("0x"))
+                .map_err(|e| format!("INVALID_HEX: commitment {j}: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let expected = expect
+        .map(|pk| decode_hex_arg("expect-pubkey", Some(pk), None))
+        .transpose()?;
+    Ok(commitment::verify_commitments(&commitments, expected.as_deref()))
+}
+
+#[derive(Serialize)]
+struct CombineOutput {
+    /// base64 serialised KeyShare
+    key_share: String,
+    /// hex compressed shared public key
+    public_key: String,
+}
+
+/// `combine`: merge a core share and its aux info into a KeyShare.
+fn combine(
+    core: Option<PathBuf>,
+    aux: Option<PathBuf>,
+    level: Option<Level>,
+) -> Result<CombineOutput, String> {
+    let (core, aux) = match (core, aux) {
+        (Some(core), Some(aux)) => (read_blob_file(&core)?, read_blob_file(&aux)?),
+        (None, None) => {
+            let lines = read_input_lines();
+            let [core, aux] = <[String; 2]>::try_from(lines).map_err(|lines| {
+                format!(
+                    "INVALID_INPUT: expected a core share line and an aux info line, got {} lines",
+                    lines.len()
+                )
+            })?;
+            (decode_blob(core.into_bytes()), decode_blob(aux.into_bytes()))
+        }
+        _ => return Err("MISSING_ARGUMENT: give both --core and --aux, or neither".into()),
+    };
+    let key_share = key_material::combine_key_share(&core, &aux, level, None, share_format::default_encoding())?;
+    let public_key = key_material::extract_public_key(&key_share)?;
+    Ok(CombineOutput {
+        key_share: base64::engine::general_purpose::STANDARD.encode(&key_share),
+        public_key: hex::encode(public_key),
+    })
+}
+
+/// Print a key material result as one JSON line — to `out` if given — or
+/// `{"error":{code,message}}` and exit 1.
+fn emit_result<T: Serialize>(result: Result<T, String>, default_code: &str, out: Option<&Path>) {
+    match result {
+        Ok(value) => {
+            let line = serde_json::to_string(&value).expect("serialize output");
+            match out {
+                Some(path) => {
+                    if let Err(e) = std::fs::write(path, format!("{line}\n")) {
+                        eprintln!("cannot write {}: {e}", path.display());
+                        std::process::exit(1);
+                    }
+                }
+                None => emit_line(&line),
+            }
+        }
+        Err(e) => {
+            let error = ErrorReport::new(default_code, &e);
+            emit_line(&serde_json::to_string(&FatalOutput { session_id: None, error }).expect("serialize error"));
+            std::process::exit(1);
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
-// Interactive signing types (wire-compatible with WASM WasmSignMessage)
+// Offline auditing (`verify share` / `verify signature`)
 // ---------------------------------------------------------------------------
 
-#[derive(Deserialize)]
-struct SignInit {
-    core_share: String,         // base64
-    aux_info: String,           // base64
-    message_hash: String,       // hex, 32 bytes
-    party_index: u16,
-    parties_at_keygen: Vec<u16>,
-    eid: String,                // hex, 32 bytes
+/// One step of a verify run.
+#[derive(Serialize)]
+struct VerifyCheck {
+    check: &'static str,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-struct WasmSignMessage {
-    sender: u16,
-    is_broadcast: bool,
-    recipient: Option<u16>,
-    payload: String,            // base64-encoded serde_json of protocol Msg
+/// Machine-readable error: `code` is the `CODE:` prefix of the underlying
+/// error string when it has one.
+#[derive(Serialize)]
+struct ErrorReport {
+    code: String,
+    message: String,
+}
+
+impl ErrorReport {
+    fn new(default_code: &str, message: &str) -> Self {
+        let (code, rest) = match message.split_once(": ") {
+            Some((code, rest))
+                if !code.is_empty()
+                    && code.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_') =>
+            {
+                (code, rest)
+            }
+            _ => (default_code, message),
+        };
+        ErrorReport {
+            code: code.to_string(),
+            message: rest.to_string(),
+        }
+    }
 }
 
+/// JSON report printed by `verify`; the process exits 0 iff `ok`.
 #[derive(Serialize)]
-struct SignOutput {
-    messages: Vec<WasmSignMessage>,
-    complete: bool,
+struct VerifyReport {
+    command: &'static str,
+    ok: bool,
+    checks: Vec<VerifyCheck>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    r: Option<String>,
+    public_key: Option<String>,
+    /// The core share's label and environment, if it carries any
     #[serde(skip_serializing_if = "Option::is_none")]
-    s: Option<String>,
+    metadata: Option<share_metadata::ShareMetadata>,
+    /// First failure, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorReport>,
 }
 
-// ---------------------------------------------------------------------------
-// Interactive signing — one process per session, stdin/stdout JSON lines
-// ---------------------------------------------------------------------------
+impl VerifyReport {
+    fn new(command: &'static str) -> Self {
+        VerifyReport {
+            command,
+            ok: true,
+            checks: Vec::new(),
+            public_key: None,
+            metadata: None,
+            error: None,
+        }
+    }
 
-fn run_interactive_sign() {
-    let b64 = base64::engine::general_purpose::STANDARD;
+    /// Record `result` as check `name`, returning its value on success.
+    fn check<T>(&mut self, name: &'static str, code: &str, result: Result<T, String>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                self.checks.push(VerifyCheck { check: name, ok: true, detail: None });
+                Some(value)
+            }
+            Err(e) => {
+                self.checks.push(VerifyCheck { check: name, ok: false, detail: Some(e.clone()) });
+                if self.ok {
+                    self.error = Some(ErrorReport::new(code, &e));
+                }
+                self.ok = false;
+                None
+            }
+        }
+    }
 
-    // Read init line from stdin
-    let stdin = std::io::stdin();
-    let mut reader = BufReader::new(stdin.lock());
-    let stdout = std::io::stdout();
-    let mut writer = BufWriter::new(stdout.lock());
+    fn emit_and_exit(self) -> ! {
+        emit_line(&serde_json::to_string(&self).expect("serialize verify report"));
+        std::process::exit(if self.ok { 0 } else { 1 });
+    }
+}
 
-    let mut init_line = String::new();
-    reader.read_line(&mut init_line).expect("failed to read init line from stdin");
-    let init: SignInit = serde_json::from_str(init_line.trim())
-        .expect("failed to parse sign init JSON");
+/// Read a share file holding either base64 text or the raw blob.
+fn read_blob_file(path: &Path) -> Result<Vec<u8>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("READ_FAILED: {}: {e}", path.display()))?;
+    Ok(decode_blob(bytes))
+}
 
-    // Decode key material
-    let core_bytes = b64.decode(&init.core_share).expect("decode core_share base64");
-    let aux_bytes = b64.decode(&init.aux_info).expect("decode aux_info base64");
-    let hash_bytes = hex::decode(&init.message_hash).expect("decode message_hash hex");
-    let eid_bytes = hex::decode(&init.eid).expect("decode eid hex");
+/// [`read_blob_file`] on `path`, or on the whole input.
+fn read_blob(path: Option<&Path>) -> Result<Vec<u8>, String> {
+    match path {
+        Some(path) => read_blob_file(path),
+        None => {
+            let mut bytes = Vec::new();
+            input()
+                .read_to_end(&mut bytes)
+                .map_err(|e| format!("READ_FAILED: input: {e}"))?;
+            Ok(decode_blob(bytes))
+        }
+    }
+}
 
-    if hash_bytes.len() != 32 {
-        eprintln!("message_hash must be 32 bytes, got {}", hash_bytes.len());
-        std::process::exit(1);
+/// Base64 text decoded, anything else taken as the raw blob.
+fn decode_blob(bytes: Vec<u8>) -> Vec<u8> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let decoded = std::str::from_utf8(&bytes)
+        .ok()
+        .and_then(|text| b64.decode(text.trim()).ok());
+    decoded.unwrap_or(bytes)
+}
+
+fn decode_hex_arg(name: &str, value: Option<String>, len: Option<usize>) -> Result<Vec<u8>, String> {
+    let value = value.ok_or_else(|| format!("MISSING_ARGUMENT: --{name} is required"))?;
+    let bytes = HexBytes::parse(&format!("--{name}"), &value)?.0;
+    match len {
+        Some(len) if bytes.len() != len => Err(format!(
+            "INVALID_LENGTH: --{name} must be {len} bytes, got {}",
+            bytes.len()
+        )),
+        _ => Ok(bytes),
     }
+}
 
-    // Deserialize key share
-    let core_share: cggmp24::IncompleteKeyShare<Secp256k1> =
-        serde_json::from_slice(&core_bytes).expect("deserialize CoreKeyShare");
-    let aux_info: cggmp24::key_share::AuxInfo<SecurityLevel128> =
-        serde_json::from_slice(&aux_bytes).expect("deserialize AuxInfo");
-    let key_share = cggmp24::KeyShare::from_parts((core_share, aux_info))
-        .expect("combine key share from parts");
+/// `verify share`: does core + aux combine, and does it carry the expected key?
+fn verify_share(
+    core_path: Option<PathBuf>,
+    aux_path: Option<PathBuf>,
+    expect: Option<String>,
+    level: Option<Level>,
+) -> VerifyReport {
+    let mut report = VerifyReport::new("verify share");
 
-    // Leak for 'static lifetime — process exits after signing, so leak is harmless
-    let key_share_ptr = Box::into_raw(Box::new(key_share));
-    let key_share_ref: &'static cggmp24::KeyShare<Secp256k1, SecurityLevel128> =
-        unsafe { &*key_share_ptr };
+    let core = report.check(
+        "read_core",
+        "INVALID_SHARE",
+        core_path
+            .ok_or_else(|| "MISSING_ARGUMENT: --core is required".to_string())
+            .and_then(|p| read_blob_file(&p)),
+    );
+    let aux = report.check(
+        "read_aux",
+        "INVALID_SHARE",
+        aux_path
+            .ok_or_else(|| "MISSING_ARGUMENT: --aux is required".to_string())
+            .and_then(|p| read_blob_file(&p)),
+    );
+    let (Some(core), Some(aux)) = (core, aux) else {
+        return report;
+    };
+    // A damaged envelope fails `combine` below
+    report.metadata = share_metadata::read_metadata(&core).ok().flatten();
 
-    // Build prehashed data to sign
-    let scalar = Scalar::<Secp256k1>::from_be_bytes_mod_order(&hash_bytes);
-    let prehashed_ptr = Box::into_raw(Box::new(
-        cggmp24::signing::PrehashedDataToSign::from_scalar(scalar),
-    ));
-    let prehashed_ref: &'static cggmp24::signing::PrehashedDataToSign<Secp256k1> =
-        unsafe { &*prehashed_ptr };
+    let Some(key_share) = report.check(
+        "combine",
+        "COMBINE_FAILED",
+        key_material::combine_key_share(&core, &aux, level, None, share_format::default_encoding()),
+    ) else {
+        return report;
+    };
+    let Some(public_key) = report.check(
+        "extract_public_key",
+        "INVALID_SHARE",
+        key_material::extract_public_key(&key_share),
+    ) else {
+        return report;
+    };
+    report.public_key = Some(hex::encode(&public_key));
 
-    // EID and parties — leak for 'static
-    let eid_static: &'static [u8] = Box::leak(eid_bytes.into_boxed_slice());
-    let eid = cggmp24::ExecutionId::new(eid_static);
-    let parties_static: &'static [u16] = Box::leak(init.parties_at_keygen.into_boxed_slice());
+    if expect.is_some() {
+        let matches = decode_hex_arg("expect-pubkey", expect, None).and_then(|expected| {
+            // Either SEC1 form; compare compressed
+            let (header, _) = share_format::split(&key_share)?;
+            let expected = key_material::recode_public_key(header.curve_or_default(), &expected, true)?;
+            if expected == public_key {
+                Ok(())
+            } else {
+                Err(format!(
+                    "PUBKEY_MISMATCH: share has {}, expected {}",
+                    hex::encode(&public_key),
+                    hex::encode(&expected)
+                ))
+            }
+        });
+        report.check("public_key_matches", "PUBKEY_MISMATCH", matches);
+    }
+    report
+}
 
-    let rng_ptr = Box::into_raw(Box::new(OsRng));
-    let rng_ref: &'static mut OsRng = unsafe { &mut *rng_ptr };
+/// `verify signature`: does (r, s) verify over the hash under the key?
+fn verify_signature(
+    pubkey: Option<String>,
+    hash: Option<String>,
+    r: Option<String>,
+    s: Option<String>,
+    curve: CurveId,
+) -> VerifyReport {
+    let mut report = VerifyReport::new("verify signature");
 
-    // Map party_index (keygen index) → position within the parties array.
-    // The cggmp24 crate expects `i` to be the 0-based position, not the
-    // keygen party index. For parties=[0,1] the two are identical, but for
-    // parties=[1,2] keygen index 2 is at position 1.
-    let party_position = parties_static
-        .iter()
-        .position(|&p| p == init.party_index)
-        .expect(&format!(
-            "party_index {} not found in parties {:?}",
-            init.party_index, parties_static
-        )) as u16;
+    with_curve!(curve, E => {
+        let public_key = report.check(
+            "parse_public_key",
+            "INVALID_PUBLIC_KEY",
+            decode_hex_arg("pubkey", pubkey, None).and_then(|bytes| {
+                Point::<E>::from_bytes(&bytes).map_err(|e| format!("INVALID_PUBLIC_KEY: {e}"))
+            }),
+        );
+        let message = report.check(
+            "parse_hash",
+            "INVALID_HASH",
+            decode_hex_arg("hash", hash, Some(32)).map(|h| Scalar::<E>::from_be_bytes_mod_order(&h)),
+        );
+        let signature = report.check(
+            "parse_signature",
+            "INVALID_SIGNATURE",
+            decode_hex_arg("r", r, Some(32)).and_then(|mut r| {
+                r.extend(decode_hex_arg("s", s, Some(32))?);
+                Ok(r)
+            }),
+        );
+        if let (Some(public_key), Some(message), Some(signature)) = (public_key, message, signature) {
+            report.public_key = Some(hex::encode(public_key.to_bytes(true).as_bytes()));
+            report.check(
+                "signature_valid",
+                "SIGNATURE_VERIFY_FAILED",
+                signature::ensure_valid(&public_key, &message, &signature),
+            );
+        }
+    });
+    report
+}
 
-    // Create the signing state machine (GMP-accelerated)
-    let sm = cggmp24::signing(eid, party_position, parties_static, key_share_ref)
-        .enforce_reliable_broadcast(true)
-        .sign_sync(rng_ref, prehashed_ref);
+// ---------------------------------------------------------------------------
+// Environment self-check (`doctor`)
+// ---------------------------------------------------------------------------
 
-    let start = std::time::Instant::now();
-    eprintln!("[native-sign] session created for party {}", init.party_index);
+/// Outcome of a doctor check; the run's status is its worst check.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
 
-    run_sign_loop(sm, init.party_index, &mut reader, &mut writer);
+impl CheckStatus {
+    fn exit_code(self) -> i32 {
+        match self {
+            CheckStatus::Pass => 0,
+            CheckStatus::Warn => 2,
+            CheckStatus::Fail => 1,
+        }
+    }
+}
 
-    eprintln!("[native-sign] complete in {:.1}s", start.elapsed().as_secs_f64());
+/// One step of a doctor run.
+#[derive(Serialize)]
+struct DoctorCheck {
+    check: &'static str,
+    status: CheckStatus,
+    detail: String,
 }
 
-/// Drive the signing state machine via stdin/stdout JSON lines.
-///
-/// Matches the WASM `process_round` behavior: after each incoming message
-/// delivery, immediately drive the state machine to collect any outgoing
-/// messages before accepting the next incoming message. This is required
-/// for reliable broadcast echo steps.
-fn run_sign_loop<SM, R, W>(mut sm: SM, party_index: u16, reader: &mut R, writer: &mut W)
-where
-    SM: StateMachine<
-        Output = Result<cggmp24::signing::Signature<Secp256k1>, cggmp24::signing::SigningError>,
-    >,
-    SM::Msg: Serialize + for<'de> Deserialize<'de> + Clone,
-    R: BufRead,
-    W: Write,
-{
-    let b64 = base64::engine::general_purpose::STANDARD;
+impl DoctorCheck {
+    fn new(check: &'static str, status: CheckStatus, detail: String) -> Self {
+        DoctorCheck { check, status, detail }
+    }
 
-    /// Helper: drive sm until it blocks, collecting messages and checking for completion.
-    fn drive_batch<SM2>(
-        sm: &mut SM2,
-        party_index: u16,
-        b64: &base64::engine::general_purpose::GeneralPurpose,
-        messages: &mut Vec<WasmSignMessage>,
-    ) -> Option<(String, String)>
-    where
-        SM2: StateMachine<
-            Output = Result<cggmp24::signing::Signature<Secp256k1>, cggmp24::signing::SigningError>,
-        >,
-        SM2::Msg: Serialize,
-    {
-        loop {
-            match sm.proceed() {
-                ProceedResult::SendMsg(outgoing) => {
-                    let json_bytes = serde_json::to_vec(&outgoing.msg)
-                        .expect("serialize outgoing protocol message");
-                    let payload = b64.encode(&json_bytes);
-                    let (is_broadcast, recipient) = match outgoing.recipient {
-                        MessageDestination::AllParties => (true, None),
-                        MessageDestination::OneParty(p) => (false, Some(p)),
-                    };
-                    messages.push(WasmSignMessage {
-                        sender: party_index,
-                        is_broadcast,
-                        recipient,
-                        payload,
-                    });
-                }
-                ProceedResult::NeedsOneMoreMessage => return None,
-                ProceedResult::Output(result) => {
-                    let sig = result.expect("signing protocol produced an error");
-                    let sig = sig.normalize_s();
-                    let mut sig_bytes =
-                        vec![0u8; cggmp24::signing::Signature::<Secp256k1>::serialized_len()];
-                    sig.write_to_slice(&mut sig_bytes);
-                    return Some((hex::encode(&sig_bytes[..32]), hex::encode(&sig_bytes[32..])));
-                }
-                ProceedResult::Yielded => {} // continue
-                ProceedResult::Error(e) => {
-                    eprintln!("[native-sign] protocol error: {e}");
-                    std::process::exit(1);
-                }
-            }
+    /// Pass with `detail`, or fail with the error.
+    fn from_result(check: &'static str, result: Result<String, String>) -> Self {
+        match result {
+            Ok(detail) => DoctorCheck::new(check, CheckStatus::Pass, detail),
+            Err(e) => DoctorCheck::new(check, CheckStatus::Fail, e),
         }
     }
+}
 
-    // Phase 1: Initial drive — produce first messages
-    let mut messages = Vec::new();
-    let mut sig = drive_batch(&mut sm, party_index, &b64, &mut messages);
+/// JSON report printed by `doctor`.
+#[derive(Serialize)]
+struct DoctorReport {
+    status: CheckStatus,
+    bignum_backend: &'static str,
+    checks: Vec<DoctorCheck>,
+}
 
-    // Output first messages
-    let output = SignOutput {
-        messages,
-        complete: sig.is_some(),
-        r: sig.as_ref().map(|(r, _)| r.clone()),
-        s: sig.as_ref().map(|(_, s)| s.clone()),
-    };
-    let json = serde_json::to_string(&output).expect("serialize sign output");
-    writeln!(writer, "{}", json).expect("write to stdout");
-    writer.flush().expect("flush stdout");
+/// Slowest 1536-bit modexp `doctor` expects of a signer on this backend;
+/// slower ones warn. Generous: a few years old laptop CPU is well under.
+#[cfg(feature = "gmp")]
+const DOCTOR_MAX_MODEXP_MS: f64 = 5.0;
+#[cfg(not(feature = "gmp"))]
+const DOCTOR_MAX_MODEXP_MS: f64 = 50.0;
 
-    if sig.is_some() {
-        return;
+type DoctorCurve = cggmp24::supported_curves::Secp256k1;
+type DoctorKeyShare = cggmp24::KeyShare<DoctorCurve, cggmp24::security_level::SecurityLevel128>;
+
+/// Message hash and eid of the `doctor` round trip.
+const DOCTOR_MESSAGE_HASH: [u8; 32] = [0x42; 32];
+const DOCTOR_EID: &[u8] = b"guardian-wallet doctor";
+
+/// Run every check (the directory ones for the directories given), print
+/// each as it finishes and return the report.
+fn doctor(aux_pool: Option<&Path>, checkpoint_dir: Option<&Path>) -> DoctorReport {
+    let mut checks = Vec::new();
+    let mut run = |check: &dyn Fn() -> DoctorCheck| {
+        let check = check();
+        let status = check.status;
+        progress(
+            Event::CheckDone { check: check.check, status, detail: &check.detail },
+            || {
+                let tag = match status {
+                    CheckStatus::Pass => "pass",
+                    CheckStatus::Warn => "WARN",
+                    CheckStatus::Fail => "FAIL",
+                };
+                format!("[{tag}] {}: {}", check.check, check.detail)
+            },
+        );
+        checks.push(check);
+    };
+    run(&check_entropy);
+    run(&check_prime_speed);
+    run(&check_bignum_backend);
+    if let Some(dir) = aux_pool {
+        run(&|| DoctorCheck::from_result("aux_pool_writable", check_dir_writable(dir)));
     }
+    if let Some(dir) = checkpoint_dir {
+        run(&|| DoctorCheck::from_result("checkpoint_dir_writable", check_dir_writable(dir)));
+    }
+    run(&check_sign_roundtrip);
 
-    // Phase 2: Round loop — read incoming, deliver + drive after each, output
-    loop {
-        let mut line = String::new();
-        reader.read_line(&mut line).expect("read incoming messages from stdin");
-        let incoming: Vec<WasmSignMessage> = serde_json::from_str(line.trim())
-            .expect("parse incoming messages JSON");
-
-        let mut all_outgoing = Vec::new();
-
-        // Deliver each message, driving after each (matches WASM process_round)
-        for msg in &incoming {
-            let payload_bytes = b64
-                .decode(msg.payload.as_bytes())
-                .expect("base64 decode incoming message payload");
-            let protocol_msg: SM::Msg = serde_json::from_slice(&payload_bytes)
-                .expect("deserialize incoming protocol message");
-
-            let incoming_msg = Incoming {
-                id: 0,
-                sender: msg.sender,
-                msg_type: if msg.is_broadcast {
-                    MessageType::Broadcast
-                } else {
-                    MessageType::P2P
-                },
-                msg: protocol_msg,
-            };
+    DoctorReport {
+        status: checks.iter().map(|c| c.status).max().unwrap_or(CheckStatus::Pass),
+        bignum_backend: BIGNUM_BACKEND,
+        checks,
+    }
+}
 
-            if sm.received_msg(incoming_msg).is_err() {
-                eprintln!("[native-sign] failed to deliver msg from party {} (broadcast={})",
-                    msg.sender, msg.is_broadcast);
-                std::process::exit(1);
-            }
+/// The OS rng answers, and doesn't hand out the same bytes twice. Warns
+/// when `/dev/urandom` can't be read: getrandom doesn't need it, but
+/// dependencies that open it do.
+fn check_entropy() -> DoctorCheck {
+    let mut first = [0u8; 32];
+    let mut second = [0u8; 32];
+    let drawn = getrandom::getrandom(&mut first).and_then(|()| getrandom::getrandom(&mut second));
+    if let Err(e) = drawn {
+        return DoctorCheck::new("entropy", CheckStatus::Fail, format!("ENTROPY_UNAVAILABLE: getrandom failed: {e}"));
+    }
+    if first == second || first.iter().all(|&b| b == first[0]) {
+        return DoctorCheck::new(
+            "entropy",
+            CheckStatus::Fail,
+            "ENTROPY_UNAVAILABLE: getrandom returned repeating output".into(),
+        );
+    }
+    let source = if rng::has_extra() { ", --entropy-source mixed in" } else { "" };
+    let mut urandom = [0u8; 32];
+    match File::open("/dev/urandom").and_then(|mut file| file.read_exact(&mut urandom)) {
+        Ok(()) => DoctorCheck::new("entropy", CheckStatus::Pass, format!("getrandom and /dev/urandom readable{source}")),
+        Err(e) => DoctorCheck::new(
+            "entropy",
+            CheckStatus::Warn,
+            format!("getrandom works{source}, but /dev/urandom can't be read: {e}"),
+        ),
+    }
+}
 
-            // Drive after each delivery to process relay/echo steps
-            sig = drive_batch(&mut sm, party_index, &b64, &mut all_outgoing);
-            if sig.is_some() {
-                break;
-            }
-        }
+/// At most a second of the modexps prime search is made of (the `estimate`
+/// calibration), against what this backend should manage.
+fn check_prime_speed() -> DoctorCheck {
+    let modexp_ms = match estimate::modexp_ms(&|_: &str, _: usize, _: usize| ensure_running(|| "doctor stopped".into())) {
+        Ok(ms) => ms,
+        Err(e) => return DoctorCheck::new("prime_speed", CheckStatus::Fail, e),
+    };
+    // Two safe primes per party
+    let primes_secs = 2.0 * estimate::SAFE_PRIME_MODEXPS * modexp_ms / 1000.0;
+    let detail = format!(
+        "{modexp_ms:.2} ms per 1536-bit modexp (expected at most {DOCTOR_MAX_MODEXP_MS} on {BIGNUM_BACKEND}), \
+         about {primes_secs:.0}s of prime search per party"
+    );
+    let status = if modexp_ms <= DOCTOR_MAX_MODEXP_MS { CheckStatus::Pass } else { CheckStatus::Warn };
+    DoctorCheck::new("prime_speed", status, detail)
+}
 
-        // Output this round's results
-        let output = SignOutput {
-            messages: all_outgoing,
-            complete: sig.is_some(),
-            r: sig.as_ref().map(|(r, _)| r.clone()),
-            s: sig.as_ref().map(|(_, s)| s.clone()),
-        };
-        let json = serde_json::to_string(&output).expect("serialize sign output");
-        writeln!(writer, "{}", json).expect("write to stdout");
-        writer.flush().expect("flush stdout");
+/// Which bignum backend this binary has; num-bigint warns, as prime
+/// generation and the Paillier proofs run several times slower on it.
+fn check_bignum_backend() -> DoctorCheck {
+    if cfg!(feature = "gmp") {
+        DoctorCheck::new("bignum_backend", CheckStatus::Pass, format!("{BIGNUM_BACKEND} (GMP)"))
+    } else {
+        DoctorCheck::new(
+            "bignum_backend",
+            CheckStatus::Warn,
+            format!("{BIGNUM_BACKEND}: built without the gmp feature, prime generation and proofs are slower"),
+        )
+    }
+}
 
-        if sig.is_some() {
-            break;
-        }
+/// `dir` exists and takes a new file, synced to disk and removed again.
+fn check_dir_writable(dir: &Path) -> Result<String, String> {
+    if !dir.is_dir() {
+        return Err(format!("WRITE_FAILED: {} is not a directory", dir.display()));
     }
+    let probe = dir.join(format!(".doctor-{}.tmp", std::process::id()));
+    let written = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .and_then(|mut file| file.write_all(b"doctor").and_then(|()| file.sync_all()));
+    let removed = std::fs::remove_file(&probe);
+    written
+        .and(removed)
+        .map(|()| format!("{} is writable", dir.display()))
+        .map_err(|e| format!("WRITE_FAILED: {}: {e}", dir.display()))
+}
+
+/// Sign a fixed hash with a throwaway 2-of-2 key through
+/// `simulate::sign_local`, then verify the signature.
+fn check_sign_roundtrip() -> DoctorCheck {
+    let start = Instant::now();
+    let result = doctor_key().and_then(|key_shares| {
+        let (signature, _) =
+            simulate::sign_local(&key_shares[..2], &[0, 1], &DOCTOR_MESSAGE_HASH, DOCTOR_EID, |_| MixedRng)?;
+        let mut sig_bytes = vec![0u8; cggmp24::signing::Signature::<DoctorCurve>::serialized_len()];
+        signature.write_to_slice(&mut sig_bytes);
+        let public_key = Point::from(key_shares[0].shared_public_key());
+        let message = Scalar::from_be_bytes_mod_order(DOCTOR_MESSAGE_HASH);
+        signature::ensure_valid(&public_key, &message, &sig_bytes)?;
+        Ok(format!("signed and verified in {:.1}s", secs_since(start)))
+    });
+    DoctorCheck::from_result("sign_roundtrip", result)
+}
+
+/// The round trip's key: dealt in `insecure-dev` builds, elsewhere the
+/// fixed-seed `bench` key (the dealer only exists in `insecure-dev`
+/// builds). Either way Paillier primes are generated, which takes the
+/// check most of its time.
+#[cfg(feature = "insecure-dev")]
+fn doctor_key() -> Result<Vec<DoctorKeyShare>, String> {
+    cggmp24::trusted_dealer::builder::<DoctorCurve, cggmp24::security_level::SecurityLevel128>(2)
+        .set_threshold(Some(2))
+        .generate_shares(&mut MixedRng)
+        .map_err(|e| format!("trusted dealer: {e}"))
+}
+
+#[cfg(not(feature = "insecure-dev"))]
+fn doctor_key() -> Result<Vec<DoctorKeyShare>, String> {
+    bench::sign_keys(2)
 }
 
 // ---------------------------------------------------------------------------
@@ -661,128 +4823,403 @@ where
 // ---------------------------------------------------------------------------
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
+    install_signal_handlers();
+    let Cli { global, command } = Cli::parse_args();
+    let passphrase = passphrase_from_env(global.passphrase_env);
+    let requested_level = global.security_level;
+    let level = requested_level.unwrap_or_default();
+    let curve = global.curve;
+    let timeout = Duration::from_secs(global.timeout);
+    share_format::set_default_encoding(global.encoding);
+    cached_shares().set_capacity(global.share_cache);
+    party_limits::set_max_parties(global.max_parties).expect("--max-parties is checked when parsed");
+    share_metadata::set_expected_environment(global.expected_environment);
+    if let Some(path) = &global.entropy_source {
+        if let Err(e) = load_entropy_source(path) {
+            progress(Event::Error { session_id: None, message: &e }, || format!("cannot start: {e}"));
+            std::process::exit(1);
+        }
+    }
+    if global.progress == ProgressFormat::Json {
+        PROGRESS_JSON.store(true, Ordering::Relaxed);
+    }
+    open_input_output(global.input, global.output.as_deref());
+    if let Some(path) = global.audit_log {
+        AUDIT_LOG.set(path).expect("audit log set once");
+    }
 
-    match args.get(1).map(|s| s.as_str()) {
-        Some("dkg") => {
-            let n: u16 = args.get(2).and_then(|s| s.parse().ok()).unwrap_or(3);
-            let threshold: u16 = args.get(3).and_then(|s| s.parse().ok()).unwrap_or(2);
-            let eid_hex = args.get(4).cloned().unwrap_or_else(|| {
-                let mut eid = [0u8; 32];
-                getrandom::getrandom(&mut eid).expect("getrandom");
-                hex::encode(eid)
+    match command {
+        #[cfg(feature = "insecure-dev")]
+        Command::Dkg { args, trusted_dealer: true, .. } => {
+            let (n, threshold) = (args.n, args.threshold);
+
+            let start = Instant::now();
+            let result = with_curve!(curve, E => with_security_level!(level, L => {
+                run_dkg_trusted_dealer::<E, L>(n, threshold, curve, level)
+            }));
+            match result {
+                Ok(output) => {
+                    let secs = secs_since(start);
+                    progress(Event::DkgDone { secs }, || format!("Trusted dealer done in {secs:.1}s"));
+                    emit_dkg_output(output, passphrase.as_deref());
+                }
+                Err(e) => {
+                    progress(Event::Error { session_id: None, message: &e }, || format!("DKG failed: {e}"));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Dkg { args, checkpoint_dir, checkpoint_passphrase_env, .. } => {
+            let (n, threshold, eid_bytes) = (args.n, args.threshold, args.eid());
+            let checkpoint = checkpoint_dir.map(|dir| DkgCheckpoint {
+                dir,
+                passphrase: passphrase_from_env(checkpoint_passphrase_env),
+            });
+
+            let start = Instant::now();
+            let result = with_curve!(curve, E => with_security_level!(level, L => {
+                run_dkg::<E, L>(n, threshold, &eid_bytes, curve, level, checkpoint.as_ref())
+            }));
+            match result {
+                Ok(output) => {
+                    let secs = secs_since(start);
+                    progress(Event::DkgDone { secs }, || format!("DKG complete in {secs:.1}s"));
+                    emit_dkg_output(output, passphrase.as_deref());
+                }
+                Err(e) => {
+                    progress(Event::Error { session_id: None, message: &e }, || format!("DKG failed: {e}"));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::DkgWithPrimes { args, primes_dir, used_primes, allow_unsafe_prime_reuse } => {
+            // Fast DKG: reads pre-generated primes (one base64 line per
+            // party, or one file each from --primes-dir)
+            let (n, threshold, eid_bytes) = (args.n, args.threshold, args.eid());
+            let prime_blobs = match &primes_dir {
+                Some(dir) => load_primes_dir(dir),
+                None => decode_prime_lines(&read_input_lines()),
+            };
+            let prime_blobs = prime_blobs.unwrap_or_else(|e| {
+                progress(Event::Error { session_id: None, message: &e }, || format!("cannot read primes: {e}"));
+                std::process::exit(1);
             });
-            let eid_bytes = hex::decode(&eid_hex).expect("invalid eid hex");
 
-            let start = std::time::Instant::now();
-            match run_dkg(n, threshold, &eid_bytes) {
+            let count = prime_blobs.len();
+            progress(Event::InputRead { what: "primes", count }, || format!("Read {count} prime sets"));
+            let used_primes: Vec<String> = match &used_primes {
+                Some(path) => std::fs::read_to_string(path)
+                    .unwrap_or_else(|e| {
+                        eprintln!("READ_FAILED: {}: {e}", path.display());
+                        std::process::exit(1);
+                    })
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            let start = Instant::now();
+            let result = run_dkg_with_primes(
+                n,
+                threshold,
+                &eid_bytes,
+                &prime_blobs,
+                curve,
+                requested_level,
+                &used_primes,
+                allow_unsafe_prime_reuse,
+            );
+            match result {
                 Ok(output) => {
-                    eprintln!("DKG complete in {:.1}s", start.elapsed().as_secs_f64());
-                    println!("{}", serde_json::to_string(&output).expect("serialize output"));
+                    let secs = secs_since(start);
+                    progress(Event::DkgDone { secs }, || format!("DKG complete in {secs:.1}s"));
+                    emit_dkg_output(output, passphrase.as_deref());
                 }
                 Err(e) => {
-                    eprintln!("DKG failed: {e}");
+                    progress(Event::Error { session_id: None, message: &e }, || format!("DKG failed: {e}"));
                     std::process::exit(1);
                 }
             }
         }
-        Some("dkg-with-primes") => {
-            // Fast DKG: reads pre-generated primes from stdin (one base64 line per party)
-            let n: u16 = args.get(2).and_then(|s| s.parse().ok()).unwrap_or(3);
-            let threshold: u16 = args.get(3).and_then(|s| s.parse().ok()).unwrap_or(2);
-            let eid_hex = args.get(4).cloned().unwrap_or_else(|| {
+        Command::RefreshAux { eid, derive_eid, primes } => {
+            let derived = derive_eid.map(|seed| seed.derive(eid::EidPurpose::Refresh));
+            let eid_bytes = eid.or(derived).unwrap_or_else(|| {
                 let mut eid = [0u8; 32];
                 getrandom::getrandom(&mut eid).expect("getrandom");
-                hex::encode(eid)
+                eid
+            });
+            let share_lines = read_input_lines();
+            let count = share_lines.len();
+            progress(Event::InputRead { what: "core shares", count }, || {
+                format!("Read {count} core shares from stdin")
             });
-            let eid_bytes = hex::decode(&eid_hex).expect("invalid eid hex");
 
-            // Read primes from stdin
-            let mut input = String::new();
-            std::io::Read::read_to_string(&mut std::io::stdin(), &mut input)
-                .expect("failed to read stdin");
-            let prime_lines: Vec<String> = input
-                .lines()
-                .filter(|l| !l.trim().is_empty())
-                .map(|l| l.to_string())
-                .collect();
+            let start = Instant::now();
+            match refresh_aux(&share_lines, &eid_bytes, primes.as_deref(), requested_level) {
+                Ok(mut output) => {
+                    let secs = secs_since(start);
+                    progress(Event::DkgDone { secs }, || format!("Aux info refresh complete in {secs:.1}s"));
+                    if let Some(passphrase) = passphrase.as_deref() {
+                        if let Err(e) = encrypt_refresh_output(&mut output, passphrase) {
+                            eprintln!("share encryption failed: {e}");
+                            std::process::exit(1);
+                        }
+                    }
+                    emit_line(&serde_json::to_string(&output).expect("serialize output"));
+                }
+                Err(e) => {
+                    progress(Event::Error { session_id: None, message: &e }, || {
+                        format!("aux info refresh failed: {e}")
+                    });
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Sign => {
+            run_interactive_sign(requested_level, timeout);
+        }
+        Command::DkgParty => {
+            run_dkg_party(curve, requested_level, timeout, passphrase);
+        }
+        Command::FrostDkg(args) => {
+            let (n, threshold, eid_bytes) = (args.n, args.threshold, args.eid());
 
-            eprintln!("Read {} prime sets from stdin", prime_lines.len());
+            let start = Instant::now();
+            match run_frost_dkg(n, threshold, &eid_bytes) {
+                Ok(mut output) => {
+                    let secs = secs_since(start);
+                    progress(Event::DkgDone { secs }, || format!("FROST DKG complete in {secs:.1}s"));
+                    if let Some(passphrase) = passphrase.as_deref() {
+                        if let Err(e) = encrypt_frost_shares(&mut output, passphrase) {
+                            eprintln!("share encryption failed: {e}");
+                            std::process::exit(1);
+                        }
+                    }
+                    emit_line(&serde_json::to_string(&output).expect("serialize output"));
+                }
+                Err(e) => {
+                    progress(Event::Error { session_id: None, message: &e }, || format!("FROST DKG failed: {e}"));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::FrostSign => {
+            run_interactive_frost_sign(timeout);
+        }
+        Command::Capabilities => {
+            let caps = capabilities::capabilities(Some(MESSAGE_VERSION), BIGNUM_BACKEND, NATIVE_FEATURES);
+            emit_line(&serde_json::to_string(&caps).expect("serialize capabilities"));
+        }
+        Command::Migrate { in_dir, out_dir, dry_run } => match migrate_dir(&in_dir, &out_dir, dry_run) {
+            Ok(manifest) => {
+                emit_line(&serde_json::to_string(&manifest).expect("serialize migrate manifest"));
+                std::process::exit(if manifest.failed == 0 { 0 } else { 1 });
+            }
+            Err(e) => emit_result::<()>(Err(e), "MIGRATE_FAILED", None),
+        },
+        Command::Address { pubkey, network } => {
+            emit_result(p2wpkh_address(pubkey, &network), "INVALID_PUBLIC_KEY", None);
+        }
+        Command::Backup(BackupCommand::Create { share, out, label, passphrase_env }) => {
+            let passphrase = passphrase_from_env(passphrase_env);
+            let result = backup_create(&share, &out, label.as_deref(), passphrase.as_deref());
+            emit_result(result, "BACKUP_FAILED", None);
+        }
+        Command::Backup(BackupCommand::Restore { bundle, out, passphrase_env }) => {
+            let passphrase = passphrase_from_env(passphrase_env);
+            emit_result(backup_restore(&bundle, &out, passphrase.as_deref()), "BACKUP_FAILED", None);
+        }
+        Command::EncodeSig { r, s, sighash_type } => {
+            emit_result(encode_sig(r, s, sighash_type), "INVALID_SIGNATURE", None);
+        }
+        Command::ImportTsslib { threshold, files } => {
+            emit_result(import_tsslib(&files, threshold), "INVALID_TSSLIB_SHARE", None);
+        }
+        Command::Commitment { share, verify: false, .. } => {
+            emit_result(share_commitment(share), "INVALID_SHARE", None);
+        }
+        Command::Commitment { verify: true, expect_pubkey, .. } => match verify_commitments(expect_pubkey) {
+            Ok(report) => {
+                emit_line(&serde_json::to_string(&report).expect("serialize commitment report"));
+                std::process::exit(if report.consistent { 0 } else { 1 });
+            }
+            Err(e) => emit_result::<()>(Err(e), "INVALID_COMMITMENT", None),
+        },
+        Command::ExtractPubkey { share, format } => {
+            emit_result(extract_pubkey(share, format), "INVALID_SHARE", None);
+        }
+        Command::Combine { core, aux, out } => {
+            emit_result(combine(core, aux, requested_level), "INVALID_SHARE", out.as_deref());
+        }
+        Command::Verify(VerifyCommand::Share { core, aux, expect_pubkey }) => {
+            verify_share(core, aux, expect_pubkey, requested_level).emit_and_exit();
+        }
+        Command::Verify(VerifyCommand::Signature { pubkey, hash, r, s }) => {
+            verify_signature(pubkey, hash, r, s, curve).emit_and_exit();
+        }
+        Command::Check { core, aux } => {
+            let blobs = read_blob_file(&core).and_then(|core| Ok((core, read_blob_file(&aux)?)));
+            let (core, aux) = blobs.unwrap_or_else(|e| {
+                progress(Event::Error { session_id: None, message: &e }, || format!("check failed: {e}"));
+                std::process::exit(1);
+            });
+            let report = share_compat::check_share_compat(&core, &aux);
+            emit_line(&serde_json::to_string(&report).expect("serialize compat report"));
+            std::process::exit(if report.compatible { 0 } else { 1 });
+        }
+        #[cfg(feature = "insecure-dev")]
+        Command::GenVectors { out } => match gen_vectors(&out) {
+            Ok(manifest) => emit_line(&serde_json::to_string(&manifest).expect("serialize vector manifest")),
+            Err(e) => {
+                progress(Event::Error { session_id: None, message: &e }, || format!("gen-vectors failed: {e}"));
+                std::process::exit(1);
+            }
+        },
+        Command::Bench(which) => {
+            let report = match which {
+                BenchCommand::Sign { iterations, parties, presign, concurrency } => {
+                    bench::bench_sign(bench::SignBenchOptions { iterations, signers: parties, presign, concurrency })
+                }
+                BenchCommand::DkgKeygenOnly { n, threshold, iterations } => {
+                    bench::bench_dkg_keygen_only(n, threshold, iterations)
+                }
+            };
+            match report {
+                Ok(report) => emit_line(&serde_json::to_string(&report).expect("serialize bench report")),
+                Err(e) => {
+                    progress(Event::Error { session_id: None, message: &e }, || format!("bench failed: {e}"));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Estimate(which) => {
+            let cancel = |stage: &str, done: usize, total: usize| {
+                ensure_running(|| format!("estimate stopped at {stage} {done}/{total}"))
+            };
+            let estimate = match which {
+                EstimateCommand::Dkg { n, threshold, with_primes } => {
+                    estimate::estimate_dkg(n, threshold, with_primes, &cancel)
+                }
+                EstimateCommand::Sign { parties } => estimate::estimate_sign(parties, &cancel),
+            };
+            match estimate {
+                Ok(estimate) => emit_line(&serde_json::to_string(&estimate).expect("serialize estimate")),
+                Err(e) => {
+                    progress(Event::Error { session_id: None, message: &e }, || format!("estimate failed: {e}"));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Doctor { aux_pool, checkpoint_dir } => {
+            let report = doctor(aux_pool.as_deref(), checkpoint_dir.as_deref());
+            emit_line(&serde_json::to_string(&report).expect("serialize doctor report"));
+            std::process::exit(report.status.exit_code());
+        }
+        Command::ExportKey { confirm } => {
+            // Refuses to run without `--confirm EXPORT_PRIVATE_KEY`
+            let confirm = confirm.as_deref().unwrap_or_default();
+            let share_lines = read_input_lines();
 
-            let start = std::time::Instant::now();
-            match run_dkg_with_primes(n, threshold, &eid_bytes, &prime_lines) {
+            match export_key(&share_lines, confirm) {
                 Ok(output) => {
-                    eprintln!("DKG complete in {:.1}s", start.elapsed().as_secs_f64());
-                    println!("{}", serde_json::to_string(&output).expect("serialize output"));
+                    eprintln!("WARNING: the private key below bypasses all threshold protection");
+                    emit_line(&serde_json::to_string(&output).expect("serialize output"));
                 }
                 Err(e) => {
-                    eprintln!("DKG failed: {e}");
+                    eprintln!("export-key failed: {e}");
                     std::process::exit(1);
                 }
             }
         }
-        Some("sign") => {
-            run_interactive_sign();
+        Command::Primes { count, count_flag, out_dir, action: None } => {
+            let count = count_flag.unwrap_or(count);
+            with_security_level!(level, L => gen_primes::<L>(count, level, out_dir.as_deref()));
         }
-        Some("primes") => {
-            let count: usize = args.get(2).and_then(|s| s.parse().ok()).unwrap_or(3);
-            gen_primes(count);
+        Command::Primes { action: Some(PrimesCommand::Validate { rounds }), .. } => {
+            validate_primes(requested_level, rounds);
         }
-        Some("gen-aux") => {
+        Command::GenAux { n, count } => {
             // Pre-generate AuxInfo (Phase A only) for fast DKG later.
-            // Output: one JSON line per set to stdout.
-            let n: u16 = args.get(2).and_then(|s| s.parse().ok()).unwrap_or(3);
-            let count: usize = args.get(3).and_then(|s| s.parse().ok()).unwrap_or(1);
-            for i in 0..count {
-                let start = std::time::Instant::now();
-                match gen_aux_info(n) {
-                    Ok(output) => {
-                        eprintln!("AuxInfo set {}/{} complete in {:.1}s",
-                            i + 1, count, start.elapsed().as_secs_f64());
-                        println!("{}", serde_json::to_string(&output).expect("serialize aux info output"));
-                    }
-                    Err(e) => {
-                        eprintln!("AuxInfo generation failed: {e}");
-                        std::process::exit(1);
+            // Output: one JSON line per set.
+            gen_aux_sets(n, count, level, |output| {
+                emit_line(&serde_json::to_string(&output).expect("serialize aux info output"));
+                Ok(())
+            });
+        }
+        Command::AuxPool { action: AuxPoolCommand::Fill { dir, n, count } } => {
+            if let Err(e) = std::fs::create_dir_all(&dir) {
+                eprintln!("cannot create {}: {e}", dir.display());
+                std::process::exit(1);
+            }
+            let policy = AuxPolicy { max_age: None, max_uses: None };
+            gen_aux_sets(n, count, level, |output| {
+                let path = write_aux_set(&dir, &output)?;
+                let status = AuxSetStatus::new(&path, &output, &policy, output.generated_at);
+                emit_line(&serde_json::to_string(&status).expect("serialize aux set status"));
+                Ok(())
+            });
+        }
+        Command::AuxPool { action: AuxPoolCommand::Status { dir, policy } } => {
+            let now = clock::unix_seconds();
+            match load_aux_pool(&dir) {
+                Ok(sets) => {
+                    for (path, set) in &sets {
+                        let status = AuxSetStatus::new(path, set, &policy, now);
+                        emit_line(&serde_json::to_string(&status).expect("serialize aux set status"));
                     }
                 }
+                Err(e) => {
+                    progress(Event::Error { session_id: None, message: &e }, || format!("aux-pool status failed: {e}"));
+                    std::process::exit(1);
+                }
             }
         }
-        Some("dkg-with-aux") => {
-            // Fast DKG: reads pre-generated AuxInfo from stdin (one JSON line),
-            // runs only Phase B (keygen) — ~1s.
-            let n: u16 = args.get(2).and_then(|s| s.parse().ok()).unwrap_or(3);
-            let threshold: u16 = args.get(3).and_then(|s| s.parse().ok()).unwrap_or(2);
-            let eid_hex = args.get(4).cloned().unwrap_or_else(|| {
-                let mut eid = [0u8; 32];
-                getrandom::getrandom(&mut eid).expect("getrandom");
-                hex::encode(eid)
-            });
-            let eid_bytes = hex::decode(&eid_hex).expect("invalid eid hex");
-
-            // Read one line of AuxInfo JSON from stdin
-            let mut input = String::new();
-            std::io::Read::read_to_string(&mut std::io::stdin(), &mut input)
-                .expect("failed to read stdin");
-            let aux_line = input.lines().find(|l| !l.trim().is_empty())
-                .expect("no aux info line on stdin");
+        Command::AuxPool { action: AuxPoolCommand::Take { dir, n, policy } } => {
+            match take_aux_set(&dir, n, level, &policy) {
+                Ok(set) => emit_line(&serde_json::to_string(&set).expect("serialize aux info output")),
+                Err(e) => {
+                    progress(Event::Error { session_id: None, message: &e }, || format!("aux-pool take failed: {e}"));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::DkgWithAux { args, aux_pool, policy } => {
+            // Fast DKG: reads pre-generated AuxInfo (one JSON line, or a
+            // set taken from `--aux-pool`), runs only Phase B (keygen) — ~1s.
+            let (n, threshold, eid_bytes) = (args.n, args.threshold, args.eid());
+            let aux_output = match aux_pool {
+                Some(dir) => take_aux_set(&dir, n, level, &policy),
+                None => {
+                    let aux_line = read_input_lines().into_iter().next().unwrap_or_else(|| {
+                        eprintln!("no aux info line on the input");
+                        std::process::exit(1);
+                    });
+                    serde_json::from_str::<AuxInfoOutput>(&aux_line)
+                        .map_err(|e| format!("parse cached aux info: {e}"))
+                        .and_then(|set| match aux_set_refusal(&set, &policy, clock::unix_seconds()) {
+                            Some(refusal) => Err(refusal),
+                            None => Ok(set),
+                        })
+                }
+            };
 
-            let start = std::time::Instant::now();
-            match run_dkg_with_aux(n, threshold, &eid_bytes, aux_line) {
+            let start = Instant::now();
+            match aux_output.and_then(|aux_output| {
+                run_dkg_with_aux(n, threshold, &eid_bytes, &aux_output, curve, requested_level)
+            }) {
                 Ok(output) => {
-                    eprintln!("DKG (keygen only) complete in {:.1}s", start.elapsed().as_secs_f64());
-                    println!("{}", serde_json::to_string(&output).expect("serialize output"));
+                    let secs = secs_since(start);
+                    progress(Event::DkgDone { secs }, || format!("DKG (keygen only) complete in {secs:.1}s"));
+                    emit_dkg_output(output, passphrase.as_deref());
                 }
                 Err(e) => {
-                    eprintln!("DKG failed: {e}");
+                    progress(Event::Error { session_id: None, message: &e }, || format!("DKG failed: {e}"));
                     std::process::exit(1);
                 }
             }
         }
-        _ => {
-            // Default: backward compatible — generate primes
-            let count: usize = args.get(1).and_then(|s| s.parse().ok()).unwrap_or(3);
-            gen_primes(count);
-        }
     }
 }
@@ -0,0 +1,156 @@
+//! `sign` between child processes, relayed line by line as the Node relay
+//! does, on the vendored 2-of-3 key (`../fixtures/key.json`): both signers
+//! must print the same `r` / `s`, and `verify signature` must accept it.
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use serde_json::{json, Value};
+
+const BIN: &str = env!("CARGO_BIN_EXE_guardian-gen-primes");
+const KEY_JSON: &str = include_str!("../../fixtures/key.json");
+
+/// `test_vectors::MESSAGE_HASH`
+const MESSAGE_HASH: &str = "4242424242424242424242424242424242424242424242424242424242424242";
+
+/// Relay rounds a signing may take.
+const MAX_ROUNDS: usize = 16;
+
+/// One `sign` child in single-session mode.
+struct Signer {
+    party: u16,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Signer {
+    /// Spawn a signer for `party` among `signers` and send it its init line.
+    /// Returns the signer and its reply.
+    fn start(key: &Value, party: u16, signers: &[u16], eid: &str) -> (Self, Value) {
+        let mut child = Command::new(BIN)
+            .arg("sign")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("spawn sign");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut signer = Signer { party, child, stdin, stdout };
+        let share = &key["shares"][usize::from(party)];
+        assert_eq!(share["index"], party);
+        let reply = signer.send(&json!({
+            "core_share": share["core_share"],
+            "aux_info": share["aux_info"],
+            "message_hash": MESSAGE_HASH,
+            "party_index": party,
+            "parties_at_keygen": signers,
+            "eid": eid,
+        }));
+        (signer, reply)
+    }
+
+    /// Write `line`, read the reply.
+    fn send(&mut self, line: &Value) -> Value {
+        writeln!(self.stdin, "{line}").unwrap();
+        self.stdin.flush().unwrap();
+        let mut reply = String::new();
+        self.stdout.read_line(&mut reply).unwrap();
+        let reply: Value = serde_json::from_str(&reply).unwrap_or_else(|e| panic!("party {}: {e}: {reply}", self.party));
+        assert!(reply.get("error").is_none(), "party {}: {reply}", self.party);
+        assert_eq!(reply["rejected"], json!([]), "party {}: {reply}", self.party);
+        reply
+    }
+
+    /// What a relay hands this signer of a peer's `messages`: broadcasts,
+    /// and P2P messages whose `recipient` is its keygen index.
+    fn inbox(&self, messages: &[Value]) -> Value {
+        messages
+            .iter()
+            .filter(|msg| msg["is_broadcast"] == true || msg["recipient"] == self.party)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Sign with `signers`, one child each, until every one completes. Returns
+/// the `(r, s)` all of them printed.
+fn sign(signers: &[u16], eid: &str) -> (String, String) {
+    let key: Value = serde_json::from_str(KEY_JSON).unwrap();
+    let (mut children, mut replies): (Vec<Signer>, Vec<Value>) =
+        signers.iter().map(|&party| Signer::start(&key, party, signers, eid)).unzip();
+    let mut signatures: Vec<Option<(String, String)>> = vec![None; signers.len()];
+
+    for _ in 0..MAX_ROUNDS {
+        let sent: Vec<Vec<Value>> = replies
+            .iter()
+            .map(|reply| reply["messages"].as_array().cloned().unwrap_or_default())
+            .collect();
+        for (i, reply) in replies.iter().enumerate() {
+            if reply["complete"] == true {
+                let field = |name: &str| reply[name].as_str().unwrap().to_owned();
+                signatures[i].get_or_insert_with(|| (field("r"), field("s")));
+            }
+        }
+        if signatures.iter().all(Option::is_some) {
+            break;
+        }
+        // A finished signer's last messages go out once; it reads no more
+        for (i, reply) in replies.iter_mut().enumerate() {
+            if signatures[i].is_some() {
+                reply["messages"] = json!([]);
+            }
+        }
+        // Every peer's messages of the last line, as one line
+        for (i, signer) in children.iter_mut().enumerate() {
+            if signatures[i].is_some() {
+                continue;
+            }
+            let incoming: Vec<Value> = sent
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .flat_map(|(_, messages)| messages.iter().cloned())
+                .collect();
+            let inbox = signer.inbox(&incoming);
+            replies[i] = signer.send(&inbox);
+        }
+    }
+
+    for signer in &mut children {
+        assert!(signer.child.wait().unwrap().success(), "party {} failed", signer.party);
+    }
+    let signatures: Vec<(String, String)> = signatures
+        .into_iter()
+        .map(|signature| signature.unwrap_or_else(|| panic!("signing did not complete in {MAX_ROUNDS} relay rounds")))
+        .collect();
+    assert!(signatures.windows(2).all(|pair| pair[0] == pair[1]), "signers disagree: {signatures:?}");
+    signatures.into_iter().next().unwrap()
+}
+
+/// Whether `verify signature` accepts `(r, s)` under the fixture key.
+fn verifies((r, s): &(String, String)) -> bool {
+    let key: Value = serde_json::from_str(KEY_JSON).unwrap();
+    let public_key = key["public_key"].as_str().unwrap();
+    Command::new(BIN)
+        .args(["verify", "signature", "--pubkey", public_key, "--hash", MESSAGE_HASH, "--r", r, "--s", s])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap()
+        .success()
+}
+
+#[test]
+fn parties_0_and_1_sign_over_the_relay() {
+    let eid = "01".repeat(32);
+    assert!(verifies(&sign(&[0, 1], &eid)));
+}
+
+/// Party 2 is at position 1: P2P messages must go by keygen index.
+#[test]
+fn parties_1_and_2_sign_over_the_relay() {
+    let eid = "02".repeat(32);
+    assert!(verifies(&sign(&[1, 2], &eid)));
+}
@@ -0,0 +1,208 @@
+//! Host approval of an ECDSA signature before it is released
+//! (`SignOptions::approval_callback`).
+//!
+//! Defense in depth against a compromised orchestration layer: once the
+//! protocol has produced the signature, the session holds it back and
+//! calls the host's policy function with what is being signed. Only a
+//! truthy answer releases it into the result; anything else — falsy, a
+//! throw, a rejected Promise — zeroizes it and fails the session with
+//! `POLICY_REJECTED`.
+//!
+//! A callback may answer with a Promise. The session then reports status
+//! `computing` with no signature; once the Promise has settled, the next
+//! `process_round` with no messages (or `poll`) releases or rejects.
+//!
+//! The callback runs with no session store borrowed, so it may call back
+//! into the module: status, heartbeat, audit and memory calls see the
+//! session `computing` with no signature; a `process_round` or `poll` of it
+//! drives nothing and reports the same; aborting or destroying it discards
+//! the signature, and the call that asked fails with `no sign session
+//! found`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use js_sys::{Function, Promise, Reflect};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use zeroize::Zeroize;
+
+use crate::types::SignatureResult;
+
+#[wasm_bindgen(typescript_custom_section)]
+const APPROVAL_REQUEST_TS: &str = r#"
+export interface ApprovalRequest {
+    message_hash_hex: string;
+    parties: number[];
+    party_index: number;
+    created_at: number;
+}
+"#;
+
+/// What the callback is asked to approve.
+#[derive(Serialize)]
+pub struct ApprovalRequest {
+    pub message_hash_hex: String,
+    /// Keygen indices of the signers
+    pub parties: Vec<u16>,
+    pub party_index: u16,
+    /// Unix seconds the session was created at
+    pub created_at: u64,
+}
+
+/// The host's answer, once there is one.
+pub enum Verdict {
+    Approved(SignatureResult),
+    /// A Promise that hasn't settled yet
+    Pending,
+    /// `POLICY_REJECTED: ...`; the signature is already zeroized
+    Rejected(String),
+}
+
+/// Outcome of a Promise, filled in by its handlers.
+type Settled = Rc<RefCell<Option<Result<bool, String>>>>;
+
+/// What the callback answered: a verdict, or a Promise of one.
+pub enum Answer {
+    Now(Result<bool, String>),
+    Later(Settled),
+}
+
+/// A session's approval callback, and the signature it withholds.
+pub struct Approval {
+    callback: Function,
+    withheld: Option<SignatureResult>,
+    /// The callback has been handed the withheld signature's request
+    asked: bool,
+    settled: Option<Settled>,
+}
+
+impl Approval {
+    pub fn new(callback: Function) -> Self {
+        Approval {
+            callback,
+            withheld: None,
+            asked: false,
+            settled: None,
+        }
+    }
+
+    /// A signature is withheld, waiting on the callback.
+    pub fn is_pending(&self) -> bool {
+        self.withheld.is_some()
+    }
+
+    /// Withhold `signature` until the callback has approved it.
+    pub fn withhold(&mut self, signature: SignatureResult) {
+        self.withheld = Some(signature);
+        self.asked = false;
+    }
+
+    /// The callback to [`ask`] about the withheld signature, once; `None`
+    /// if there is nothing to ask or it has been asked already.
+    pub fn question(&mut self) -> Option<Function> {
+        if self.withheld.is_none() || self.asked {
+            return None;
+        }
+        self.asked = true;
+        Some(self.callback.clone())
+    }
+
+    /// The verdict on the withheld signature, given the callback's answer.
+    pub fn answer(&mut self, answer: Answer) -> Verdict {
+        match answer {
+            Answer::Now(outcome) => self.settle(outcome),
+            Answer::Later(settled) => {
+                self.settled = Some(settled);
+                self.poll()
+            }
+        }
+    }
+
+    /// The verdict on the withheld signature, once the callback's Promise
+    /// has settled.
+    pub fn poll(&mut self) -> Verdict {
+        let outcome = self.settled.as_ref().and_then(|settled| settled.borrow_mut().take());
+        match outcome {
+            Some(outcome) => {
+                self.settled = None;
+                self.settle(outcome)
+            }
+            None => Verdict::Pending,
+        }
+    }
+
+    fn settle(&mut self, outcome: Result<bool, String>) -> Verdict {
+        match outcome {
+            Ok(true) => match self.withheld.take() {
+                Some(signature) => Verdict::Approved(signature),
+                None => Verdict::Pending,
+            },
+            Ok(false) => self.reject("approval_callback declined the signature".into()),
+            Err(e) => self.reject(e),
+        }
+    }
+
+    fn reject(&mut self, reason: String) -> Verdict {
+        self.wipe();
+        Verdict::Rejected(format!("POLICY_REJECTED: {reason}"))
+    }
+
+    fn wipe(&mut self) {
+        if let Some(mut signature) = self.withheld.take() {
+            signature.r.zeroize();
+            signature.s.zeroize();
+        }
+    }
+}
+
+impl Drop for Approval {
+    fn drop(&mut self) {
+        self.wipe();
+    }
+}
+
+/// Call `callback` about `request`. No session may be borrowed meanwhile:
+/// the callback may call back into the module.
+pub fn ask(callback: &Function, request: &ApprovalRequest) -> Answer {
+    let request = match serde_wasm_bindgen::to_value(request) {
+        Ok(request) => request,
+        Err(e) => return Answer::Now(Err(format!("serialize approval request: {e}"))),
+    };
+    let answer = match callback.call1(&JsValue::NULL, &request) {
+        Ok(answer) => answer,
+        Err(e) => return Answer::Now(Err(format!("approval_callback threw: {e:?}"))),
+    };
+    let Some(promise) = answer.dyn_ref::<Promise>() else {
+        return Answer::Now(Ok(answer.is_truthy()));
+    };
+    let settled = Settled::default();
+    match on_settled(promise, &settled) {
+        Ok(()) => Answer::Later(settled),
+        Err(e) => Answer::Now(Err(e)),
+    }
+}
+
+/// Record `promise`'s outcome in `settled` when it settles.
+fn on_settled(promise: &Promise, settled: &Settled) -> Result<(), String> {
+    let then = Reflect::get(promise, &JsValue::from_str("then"))
+        .ok()
+        .and_then(|then| then.dyn_into::<Function>().ok())
+        .ok_or("approval_callback returned a Promise without then()")?;
+    let resolved = {
+        let settled = Rc::clone(settled);
+        Closure::once_into_js(move |value: JsValue| {
+            *settled.borrow_mut() = Some(Ok(value.is_truthy()));
+        })
+    };
+    let rejected = {
+        let settled = Rc::clone(settled);
+        Closure::once_into_js(move |e: JsValue| {
+            *settled.borrow_mut() = Some(Err(format!("approval_callback failed: {e:?}")));
+        })
+    };
+    then.call2(promise, &resolved, &rejected)
+        .map(drop)
+        .map_err(|e| format!("approval_callback's Promise: {e:?}"))
+}
@@ -0,0 +1,137 @@
+//! Audit records: which session, parties and message hash produced a
+//! signature, for dispute resolution.
+//!
+//! An ECDSA session starts its [`AuditRecord`] when it is created — binding
+//! tag, message hash, signer set, this party, creation time — and fills in
+//! `r`, `s`, the recovery id `v` and a transcript hash when it completes.
+//! Nothing in it is secret: it can be handed to a compliance team as is.
+//!
+//! The transcript hash commits to every protocol message the party sent
+//! or received. Each message is digested as SHA-256 over a domain label,
+//! sender, recipient and the base64 payload as it travelled; the digests
+//! are sorted and folded into a hash chain, so the result doesn't depend on
+//! the order messages arrived in. Parties that saw the same messages get
+//! the same hash: every party of a two-party signing, or of any signing
+//! whose messages are all broadcast. A P2P message between two other
+//! parties is only in their transcripts.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::hex_codec;
+
+const MESSAGE_DOMAIN: &[u8] = b"guardian-wallet transcript message v1";
+const CHAIN_DOMAIN: &[u8] = b"guardian-wallet transcript v1";
+
+/// What produced a signature.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct AuditRecord {
+    /// The session's binding tag (see `binding`)
+    pub session_binding_tag: String,
+    /// Hex hash signed
+    pub message_hash: String,
+    /// Keygen indices of the signers
+    pub parties: Vec<u16>,
+    /// This party's keygen index
+    pub party_index: u16,
+    /// Unix seconds
+    pub created_at: u64,
+    /// Hex 32-byte `r`, once complete
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub r: Option<String>,
+    /// Hex 32-byte low `s`, once complete
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub s: Option<String>,
+    /// Recovery id (0 or 1), once complete
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub v: Option<u8>,
+    /// Hex transcript hash (see the module docs), once complete
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub transcript_hash: Option<String>,
+}
+
+/// A session's audit record in the making.
+pub struct AuditTrail {
+    record: AuditRecord,
+    /// Digests of the messages seen so far
+    digests: Vec<[u8; 32]>,
+    /// Recovery id of an `r || s` signature over the session's hash
+    recovery_id: Box<dyn Fn(&[u8]) -> Option<u8>>,
+}
+
+impl AuditTrail {
+    /// Start the record of a session signing `message_hash`.
+    /// `recovery_id` finds `v` for the finished signature.
+    pub fn new(
+        binding: &str,
+        message_hash: &[u8],
+        parties: &[u16],
+        party_index: u16,
+        created_at: u64,
+        recovery_id: impl Fn(&[u8]) -> Option<u8> + 'static,
+    ) -> Self {
+        AuditTrail {
+            record: AuditRecord {
+                session_binding_tag: binding.to_string(),
+                message_hash: hex_codec::encode(message_hash),
+                parties: parties.to_vec(),
+                party_index,
+                created_at,
+                r: None,
+                s: None,
+                v: None,
+                transcript_hash: None,
+            },
+            digests: Vec::new(),
+            recovery_id: Box::new(recovery_id),
+        }
+    }
+
+    /// Add a protocol message sent or received; `recipient` is `None` for
+    /// a broadcast.
+    pub fn message(&mut self, sender: u16, recipient: Option<u16>, payload: &str) {
+        let recipient = match recipient {
+            None => [0, 0, 0],
+            Some(index) => {
+                let [hi, lo] = index.to_be_bytes();
+                [1, hi, lo]
+            }
+        };
+        self.digests.push(
+            Sha256::new()
+                .chain_update(MESSAGE_DOMAIN)
+                .chain_update(sender.to_be_bytes())
+                .chain_update(recipient)
+                .chain_update(payload.as_bytes())
+                .finalize()
+                .into(),
+        );
+    }
+
+    /// Fill in the finished `r || s` signature and the transcript hash.
+    pub fn complete(&mut self, signature: &[u8]) {
+        let (r, s) = signature.split_at(signature.len() / 2);
+        self.record.r = Some(hex_codec::encode(r));
+        self.record.s = Some(hex_codec::encode(s));
+        self.record.v = (self.recovery_id)(signature);
+
+        let mut digests = self.digests.clone();
+        digests.sort_unstable();
+        let mut chain: [u8; 32] = Sha256::digest(CHAIN_DOMAIN).into();
+        for digest in &digests {
+            chain = Sha256::new().chain_update(chain).chain_update(digest).finalize().into();
+        }
+        self.record.transcript_hash = Some(hex_codec::encode(&chain));
+    }
+
+    pub fn record(&self) -> &AuditRecord {
+        &self.record
+    }
+}
@@ -0,0 +1,229 @@
+//! Aux info refresh: fresh Paillier material for an existing key.
+//!
+//! Reruns aux_info_gen for all parties and leaves the ECDSA core shares
+//! untouched, so the shared public key (and any address derived from it)
+//! stays the same. The new aux blobs pair with the old core shares through
+//! `combine_key_share`. Before anything is returned, the combined shares
+//! co-sign a fixed hash locally and the signature is verified, so a refresh
+//! that doesn't pair up fails here rather than at the next real signing.
+//!
+//! Errors:
+//! - `INVALID_SHARE_SET`: not one core share per party of a single key
+//! - `SECURITY_LEVEL_MISMATCH` / `INVALID_SHARE_HEADER`: from the blob headers
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use cggmp24::key_share::AnyKeyShare;
+use cggmp24::security_level::SecurityLevel;
+use generic_ec::coords::HasAffineX;
+use generic_ec::{Curve, Point, Scalar};
+use sha2::{Digest, Sha256};
+
+use crate::rng::MixedRng;
+use crate::share_format::{self, with_curve, with_security_level, BlobKind, Level};
+use crate::signature;
+use crate::simulate;
+
+/// Hash the refreshed shares co-sign before they are handed out.
+const CHECK_HASH: [u8; 32] = *b"guardian-wallet aux refresh chk.";
+const CHECK_EID_DOMAIN: &[u8] = b"guardian-wallet aux refresh check v1";
+
+/// Result of [`refresh`].
+pub struct AuxRefresh {
+    /// Serialised AuxInfo per party, in keygen index order
+    pub aux_infos: Vec<Vec<u8>>,
+    /// 33-byte compressed shared public key (unchanged by the refresh)
+    pub public_key: Vec<u8>,
+}
+
+/// Generate new AuxInfo for every party of the key the serialised
+/// CoreKeyShares belong to.
+///
+/// `core_shares` must hold exactly one share per party, in any order.
+/// `primes`, if given, is one pregenerated primes blob per party and fixes
+/// the level; otherwise primes are generated here (slow) at `requested`,
+/// defaulting to 128.
+pub fn refresh(
+    eid: &[u8],
+    core_shares: &[impl AsRef<[u8]>],
+    primes: Option<&[impl AsRef<[u8]>]>,
+    requested: Option<Level>,
+) -> Result<AuxRefresh, String> {
+    let first = core_shares
+        .first()
+        .ok_or("INVALID_SHARE_SET: no core shares given")?;
+    let (header, _) = share_format::split_expect(first.as_ref(), BlobKind::CoreShare)?;
+    let curve = header.curve_or_default();
+
+    let mut core_payloads = Vec::with_capacity(core_shares.len());
+    for (i, bytes) in core_shares.iter().enumerate() {
+        let (header, payload) = share_format::split_expect(bytes.as_ref(), BlobKind::CoreShare)
+            .map_err(|e| format!("share {i}: {e}"))?;
+        share_format::ensure_curve(&header, Some(curve)).map_err(|e| format!("share {i}: {e}"))?;
+        core_payloads.push(payload);
+    }
+
+    let mut level = requested;
+    let mut primes_payloads = Vec::new();
+    for (i, bytes) in primes.unwrap_or_default().iter().enumerate() {
+        let (header, payload) = share_format::split_expect(bytes.as_ref(), BlobKind::Primes)
+            .map_err(|e| format!("primes for party {i}: {e}"))?;
+        let found = share_format::ensure_level(&header, level)
+            .map_err(|e| format!("primes for party {i}: {e}"))?;
+        level = Some(found);
+        primes_payloads.push(payload);
+    }
+    let level = level.unwrap_or_default();
+    let primes_payloads = primes.map(|_| primes_payloads);
+
+    with_curve!(curve, E => with_security_level!(level, L => {
+        refresh_inner::<E, L>(eid, &core_payloads, primes_payloads.as_deref(), level)
+    }))
+}
+
+fn refresh_inner<E, L>(
+    eid: &[u8],
+    core_payloads: &[&[u8]],
+    primes_payloads: Option<&[&[u8]]>,
+    level: Level,
+) -> Result<AuxRefresh, String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+{
+    let mut cores = core_payloads
+        .iter()
+        .enumerate()
+        .map(|(i, payload)| {
+            share_format::deserialize::<cggmp24::IncompleteKeyShare<E>>(BlobKind::CoreShare, payload)
+                .map_err(|e| format!("share {i}: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    cores.sort_by_key(|core| core.i);
+    check_share_set(&cores)?;
+    let n = cores[0].n();
+
+    let primes = match primes_payloads {
+        Some(payloads) => {
+            if payloads.len() != usize::from(n) {
+                return Err(format!(
+                    "INVALID_SHARE_SET: need {n} sets of primes, got {}",
+                    payloads.len()
+                ));
+            }
+            payloads
+                .iter()
+                .enumerate()
+                .map(|(i, payload)| {
+                    share_format::deserialize(BlobKind::Primes, payload)
+                        .map_err(|e| format!("primes for party {i}: {e}"))
+                })
+                .collect::<Result<Vec<cggmp24::PregeneratedPrimes<L>>, _>>()?
+        }
+        None => (0..n)
+            .map(|_| cggmp24::PregeneratedPrimes::<L>::generate(&mut MixedRng))
+            .collect(),
+    };
+
+    let aux_parties = primes
+        .into_iter()
+        .enumerate()
+        .map(|(i, primes)| {
+            let i = i as u16;
+            let eid = cggmp24::ExecutionId::new(eid);
+            let mut rng = MixedRng;
+            round_based::state_machine::wrap_protocol(move |party| async move {
+                cggmp24::aux_info_gen(eid, i, n, primes)
+                    .start(&mut rng, party)
+                    .await
+            })
+        })
+        .collect();
+    let aux_infos = simulate::run(aux_parties)
+        .map_err(|e| format!("aux_info_gen failed: {e}"))?
+        .into_iter()
+        .enumerate()
+        .map(|(i, result)| result.map_err(|e| format!("aux_info_gen party {i} failed: {e:?}")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let key_shares = cores
+        .iter()
+        .zip(&aux_infos)
+        .enumerate()
+        .map(|(i, (core, aux))| {
+            cggmp24::KeyShare::from_parts((core.clone(), aux.clone()))
+                .map_err(|e| format!("combine key share {i}: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let public_key: Point<E> = cores[0].shared_public_key().into();
+    check_signs(eid, &key_shares, &public_key)?;
+
+    let aux_infos = aux_infos
+        .iter()
+        .enumerate()
+        .map(|(i, aux)| {
+            share_format::serialize(BlobKind::AuxInfo, None, Some(level), aux)
+                .map_err(|e| format!("party {i}: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(AuxRefresh {
+        aux_infos,
+        public_key: public_key.to_bytes(true).as_bytes().to_vec(),
+    })
+}
+
+/// One share per party 0..n of a single key, sorted by index.
+fn check_share_set<E: Curve>(cores: &[cggmp24::IncompleteKeyShare<E>]) -> Result<(), String> {
+    let first = &cores[0];
+    let n = first.n();
+    if cores.len() != usize::from(n) {
+        return Err(format!(
+            "INVALID_SHARE_SET: the key has {n} parties, got {} core shares",
+            cores.len()
+        ));
+    }
+    for (expected, core) in cores.iter().enumerate() {
+        if Point::from(core.shared_public_key()) != Point::from(first.shared_public_key()) {
+            return Err("INVALID_SHARE_SET: shares belong to different keys".into());
+        }
+        if usize::from(core.i) != expected {
+            return Err(format!("INVALID_SHARE_SET: no share for party {expected}"));
+        }
+    }
+    Ok(())
+}
+
+/// Co-sign [`CHECK_HASH`] with the first `min_signers` refreshed shares and
+/// verify the result against the unchanged public key.
+fn check_signs<E, L>(
+    eid: &[u8],
+    key_shares: &[cggmp24::KeyShare<E, L>],
+    public_key: &Point<E>,
+) -> Result<(), String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+{
+    let t = key_shares[0].min_signers();
+    let parties: Vec<u16> = (0..t).collect();
+    let check_eid = Sha256::new()
+        .chain_update(CHECK_EID_DOMAIN)
+        .chain_update(eid)
+        .finalize();
+
+    let (sig, _) = simulate::sign_local(
+        &key_shares[..usize::from(t)],
+        &parties,
+        &CHECK_HASH,
+        &check_eid,
+        |_| MixedRng,
+    )
+    .map_err(|e| format!("AUX_REFRESH_CHECK_FAILED: {e}"))?;
+    let mut sig_bytes = vec![0u8; cggmp24::signing::Signature::<E>::serialized_len()];
+    sig.write_to_slice(&mut sig_bytes);
+    signature::ensure_valid(public_key, &Scalar::from_be_bytes_mod_order(CHECK_HASH), &sig_bytes)
+        .map_err(|e| format!("AUX_REFRESH_CHECK_FAILED: {e}"))
+}
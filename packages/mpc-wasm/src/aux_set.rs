@@ -0,0 +1,118 @@
+//! Pregenerated aux info sets (native-gen `gen-aux` output) and the
+//! metadata a pool keeps on them: when a set was generated, at which
+//! level, and how many DKGs it has been handed to. [`refusal`] applies
+//! the max-age / max-uses policy, [`check_for_keygen`] matches a set to a
+//! keygen before any of it is parsed.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use serde::{Deserialize, Serialize};
+
+use crate::share_format::Level;
+
+/// One set: a serialised AuxInfo per party, all from one aux_info_gen run.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AuxInfoOutput {
+    /// base64-encoded serialized AuxInfo, one per party
+    pub aux_infos: Vec<String>,
+    pub n: u16,
+    /// Unix seconds; 0 for sets generated before the field existed
+    #[serde(default)]
+    pub generated_at: u64,
+    /// `"128"` / `"192"`; empty for sets generated before the field
+    /// existed (the blob headers still say)
+    #[serde(default)]
+    pub security_level: String,
+    /// DKGs the set has been handed to
+    #[serde(default)]
+    pub uses: u32,
+}
+
+/// Why a policy of `max_age` seconds and `max_uses` DKGs (`None`: no
+/// limit) refuses `set` at `now`, if it does: `AUX_SET_EXHAUSTED` or
+/// `AUX_SET_EXPIRED`. A set of unknown age fails any `max_age`.
+pub fn refusal(set: &AuxInfoOutput, max_age: Option<u64>, max_uses: Option<u32>, now: u64) -> Option<String> {
+    if let Some(max_uses) = max_uses.filter(|&max_uses| set.uses >= max_uses) {
+        return Some(format!(
+            "AUX_SET_EXHAUSTED: handed to {} DKGs already, the limit is {max_uses}",
+            set.uses
+        ));
+    }
+    let max_age = max_age?;
+    if set.generated_at == 0 {
+        return Some("AUX_SET_EXPIRED: generation time unknown (set predates the field)".into());
+    }
+    let age = now.saturating_sub(set.generated_at);
+    (age > max_age).then(|| format!("AUX_SET_EXPIRED: generated {age}s ago, the limit is {max_age}s"))
+}
+
+/// Check `set` for a keygen of `n` parties and return the level it says
+/// it is at, if it says. The set must be for exactly `n` parties, with a
+/// blob each (`AUX_PARTY_COUNT_MISMATCH`): every blob holds all its
+/// parties' Paillier moduli, so a set for more parties can't be cut down.
+pub fn check_for_keygen(set: &AuxInfoOutput, n: u16) -> Result<Option<Level>, String> {
+    if set.n != n || set.aux_infos.len() != usize::from(n) {
+        return Err(format!(
+            "AUX_PARTY_COUNT_MISMATCH: keygen for {n} parties, the aux set is for {} with {} blobs",
+            set.n,
+            set.aux_infos.len()
+        ));
+    }
+    if set.security_level.is_empty() {
+        return Ok(None);
+    }
+    Level::parse(&set.security_level)
+        .map(Some)
+        .map_err(|e| format!("INVALID_AUX_SET: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_800_000_000;
+
+    fn set(generated_at: u64, uses: u32) -> AuxInfoOutput {
+        AuxInfoOutput {
+            aux_infos: vec![String::new(); 3],
+            n: 3,
+            generated_at,
+            security_level: "128".into(),
+            uses,
+        }
+    }
+
+    #[test]
+    fn without_limits_every_set_is_usable() {
+        assert_eq!(refusal(&set(0, 1000), None, None, NOW), None);
+    }
+
+    #[test]
+    fn a_set_past_max_age_or_of_unknown_age_is_expired() {
+        assert_eq!(refusal(&set(NOW - 60, 0), Some(60), None, NOW), None);
+        let expired = refusal(&set(NOW - 61, 0), Some(60), None, NOW).unwrap();
+        assert!(expired.starts_with("AUX_SET_EXPIRED: generated 61s ago"), "{expired}");
+        let unknown = refusal(&set(0, 0), Some(60), None, NOW).unwrap();
+        assert!(unknown.starts_with("AUX_SET_EXPIRED: generation time unknown"), "{unknown}");
+    }
+
+    #[test]
+    fn a_set_at_max_uses_is_exhausted() {
+        assert_eq!(refusal(&set(NOW, 2), None, Some(3), NOW), None);
+        let exhausted = refusal(&set(NOW, 3), None, Some(3), NOW).unwrap();
+        assert!(exhausted.starts_with("AUX_SET_EXHAUSTED: handed to 3 DKGs already"), "{exhausted}");
+    }
+
+    #[test]
+    fn a_set_must_be_for_exactly_the_keygen_parties() {
+        assert_eq!(check_for_keygen(&set(NOW, 0), 3), Ok(Some(Level::L128)));
+        let fewer = check_for_keygen(&set(NOW, 0), 2).unwrap_err();
+        assert!(fewer.starts_with("AUX_PARTY_COUNT_MISMATCH: "), "{fewer}");
+        let mut short = set(NOW, 0);
+        short.aux_infos.pop();
+        let short = check_for_keygen(&short, 3).unwrap_err();
+        assert!(short.starts_with("AUX_PARTY_COUNT_MISMATCH: "), "{short}");
+        let legacy = AuxInfoOutput { security_level: String::new(), ..set(0, 0) };
+        assert_eq!(check_for_keygen(&legacy, 3), Ok(None));
+    }
+}
@@ -0,0 +1,495 @@
+//! Backup bundles: one self-describing file per share for cold storage.
+//!
+//! A bundle carries the share (in clear, or in a `share_crypto` passphrase
+//! envelope) together with what a user needs to tell backups apart without
+//! opening them: wallet id, party index, threshold and `n`, curve, public
+//! key, creation time and an optional label. The metadata is always in
+//! clear, so [`inspect`] needs no passphrase.
+//!
+//! Layout (integers little-endian):
+//!
+//! ```text
+//! magic      4 bytes   "GWBK"
+//! version    1 byte    1
+//! meta_len   4 bytes
+//! metadata   meta_len bytes   JSON `BackupMetadata`
+//! share_len  4 bytes
+//! share      share_len bytes  share blob, or a "GWSE" envelope of it
+//! checksum   32 bytes  SHA-256 of everything above
+//! ```
+//!
+//! The checksum catches corruption, not forgery: anyone can recompute it.
+//! An encrypted share is authenticated by its envelope, and [`read_backup_bundle`]
+//! checks the share it returns carries the party index and public key the
+//! metadata names.
+//!
+//! The layout is frozen for version 1; a change needs a new version.
+//!
+//! # Fragments
+//!
+//! [`split_share`] spreads a share (or a whole bundle) over `m` fragments
+//! for different custodians, any `k` of which [`recover_share`] puts back
+//! together: Shamir secret sharing over GF(256), byte by byte, each byte
+//! the constant term of its own random degree-`k - 1` polynomial. This is
+//! at-rest redundancy for one party's share only; it has nothing to do
+//! with the threshold of the key. Fewer than `k` fragments say nothing
+//! about the share.
+//!
+//! ```text
+//! magic      4 bytes   "GWSF"
+//! version    1 byte    1
+//! split_id   16 bytes  random, the same in every fragment of one split
+//! k          1 byte
+//! m          1 byte
+//! index      1 byte    1..=m, the fragment's evaluation point
+//! digest     32 bytes  SHA-256 of the share
+//! data_len   4 bytes
+//! data       data_len bytes  the share's length in evaluations
+//! checksum   32 bytes  SHA-256 of everything above
+//! ```
+//!
+//! As with bundles the checksum catches corruption, not forgery: a damaged
+//! fragment is named (`FRAGMENT_CORRUPTED`) before anything is combined.
+//! Fragments of another split (`FRAGMENT_MISMATCH`) are refused by their
+//! split id, `k`, `m` and digest, and a recovered share must hash to the
+//! digest, so a forged fragment fails recovery rather than yielding a wrong
+//! share. Every fragment given must also lie on the polynomials the share
+//! was recovered through, so one that doesn't is named too; with more than
+//! `k` fragments a single forged one is found even among the first `k`.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::fingerprint;
+use crate::hex_codec;
+use crate::key_material;
+use crate::rng::MixedRng;
+use crate::share_crypto::{self, KdfParams};
+
+const MAGIC: &[u8; 4] = b"GWBK";
+const VERSION: u8 = 1;
+const CHECKSUM_LEN: usize = 32;
+/// Magic, version and the two length fields.
+const FIXED_LEN: usize = 4 + 1 + 4 + 4 + CHECKSUM_LEN;
+
+const FRAGMENT_MAGIC: &[u8; 4] = b"GWSF";
+const FRAGMENT_VERSION: u8 = 1;
+const SPLIT_ID_LEN: usize = 16;
+/// Everything before the data: magic, version, split id, k, m, index,
+/// digest and the data length.
+const FRAGMENT_HEADER_LEN: usize = 4 + 1 + SPLIT_ID_LEN + 3 + 32 + 4;
+
+/// Longest label accepted, in bytes.
+pub const MAX_LABEL_BYTES: usize = 256;
+
+/// What a bundle says about the share it holds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct BackupMetadata {
+    /// `fingerprint::wallet_id` of the public key
+    pub wallet_id: String,
+    pub party_index: u16,
+    pub threshold: u16,
+    pub n: u16,
+    pub curve: String,
+    /// Hex compressed shared public key
+    pub public_key: String,
+    /// Unix seconds, as given when the bundle was created
+    pub created_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub label: Option<String>,
+    /// The share is in a passphrase envelope
+    pub encrypted: bool,
+}
+
+/// A bundle's contents.
+pub struct Backup {
+    pub metadata: BackupMetadata,
+    pub share: Zeroizing<Vec<u8>>,
+}
+
+/// Whether `bytes` starts like a bundle (nothing else is checked).
+pub fn is_bundle(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Bundle a KeyShare or CoreKeyShare, encrypting it under `passphrase` if
+/// given. Fails with `INVALID_SHARE` for anything else and `INVALID_LABEL`
+/// for a label over [`MAX_LABEL_BYTES`].
+pub fn create_backup_bundle(
+    share: &[u8],
+    created_at: u64,
+    label: Option<&str>,
+    passphrase: Option<&str>,
+) -> Result<Vec<u8>, String> {
+    if label.is_some_and(|label| label.len() > MAX_LABEL_BYTES) {
+        return Err(format!("INVALID_LABEL: labels are at most {MAX_LABEL_BYTES} bytes"));
+    }
+    let core = key_material::core_public_data(share)?;
+    let public_key = hex_codec::decode(&core.public_key, "public_key")?;
+    let metadata = BackupMetadata {
+        wallet_id: fingerprint::wallet_id(&public_key)?,
+        party_index: core.party_index,
+        threshold: core.threshold,
+        n: core.n,
+        curve: core.curve,
+        public_key: core.public_key,
+        created_at,
+        label: label.map(str::to_string),
+        encrypted: passphrase.is_some(),
+    };
+    let stored = match passphrase {
+        Some(passphrase) => share_crypto::encrypt_share(share, passphrase, KdfParams::default())?,
+        None => share.to_vec(),
+    };
+    let stored = Zeroizing::new(stored);
+    let meta = serde_json::to_vec(&metadata).map_err(|e| format!("serialize backup metadata: {e}"))?;
+
+    let mut out = Vec::with_capacity(FIXED_LEN + meta.len() + stored.len());
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    out.extend_from_slice(&(meta.len() as u32).to_le_bytes());
+    out.extend_from_slice(&meta);
+    out.extend_from_slice(&(stored.len() as u32).to_le_bytes());
+    out.extend_from_slice(&stored);
+    let checksum = Sha256::digest(&out);
+    out.extend_from_slice(&checksum);
+    Ok(out)
+}
+
+/// Open a bundle. `passphrase` is required for an encrypted one
+/// (`PASSPHRASE_REQUIRED` otherwise) and ignored for one in clear.
+///
+/// Fails with `INVALID_BACKUP` / `UNSUPPORTED_BACKUP_VERSION` for something
+/// that isn't a bundle this build reads, `BACKUP_CORRUPTED` if the checksum
+/// or the share doesn't match, and `DECRYPT_AUTH_FAILED` for a wrong
+/// passphrase.
+pub fn read_backup_bundle(bytes: &[u8], passphrase: Option<&str>) -> Result<Backup, String> {
+    let (metadata, stored) = parse(bytes)?;
+    let share = match (metadata.encrypted, passphrase) {
+        (true, Some(passphrase)) => share_crypto::decrypt_share(stored, passphrase)?,
+        (true, None) => return Err("PASSPHRASE_REQUIRED: the share in this backup is encrypted".into()),
+        (false, _) => Zeroizing::new(stored.to_vec()),
+    };
+
+    let core = key_material::core_public_data(&share)
+        .map_err(|e| format!("BACKUP_CORRUPTED: share unreadable: {e}"))?;
+    if core.party_index != metadata.party_index || core.public_key != metadata.public_key {
+        return Err("BACKUP_CORRUPTED: share does not match the backup's metadata".into());
+    }
+    Ok(Backup { metadata, share })
+}
+
+/// A bundle's metadata, checksum verified; no passphrase needed.
+pub fn inspect(bytes: &[u8]) -> Result<BackupMetadata, String> {
+    parse(bytes).map(|(metadata, _)| metadata)
+}
+
+/// Metadata and stored share of a bundle whose checksum holds.
+fn parse(bytes: &[u8]) -> Result<(BackupMetadata, &[u8]), String> {
+    if !is_bundle(bytes) {
+        return Err("INVALID_BACKUP: not a backup bundle (bad magic)".into());
+    }
+    if bytes.len() < FIXED_LEN {
+        return Err(format!("INVALID_BACKUP: {} bytes is too short for a backup bundle", bytes.len()));
+    }
+    if bytes[4] != VERSION {
+        return Err(format!("UNSUPPORTED_BACKUP_VERSION: {}", bytes[4]));
+    }
+    let (body, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+    if Sha256::digest(body).as_slice() != checksum {
+        return Err("BACKUP_CORRUPTED: checksum mismatch".into());
+    }
+
+    // A length-prefixed field off the front of `rest`
+    fn take<'a>(rest: &mut &'a [u8]) -> Result<&'a [u8], String> {
+        let truncated = || "BACKUP_CORRUPTED: length fields overrun the bundle".to_string();
+        let len_bytes: [u8; 4] = rest.get(..4).ok_or_else(truncated)?.try_into().expect("4 bytes");
+        let len = u32::from_le_bytes(len_bytes) as usize;
+        let end = len.checked_add(4).ok_or_else(truncated)?;
+        let field = rest.get(4..end).ok_or_else(truncated)?;
+        *rest = &rest[end..];
+        Ok(field)
+    }
+    let mut rest = &body[5..];
+    let meta = take(&mut rest)?;
+    let stored = take(&mut rest)?;
+    if !rest.is_empty() {
+        return Err("BACKUP_CORRUPTED: trailing bytes after the share".into());
+    }
+    let metadata: BackupMetadata =
+        serde_json::from_slice(meta).map_err(|e| format!("BACKUP_CORRUPTED: metadata: {e}"))?;
+    Ok((metadata, stored))
+}
+
+/// Split `share` into `m` fragments, any `k` of which recover it (see the
+/// module docs). `share` may be any blob — a share, or a bundle of one.
+/// Fails with `INVALID_SPLIT` unless 2 ≤ `k` ≤ `m` ≤ 255 and `share` is
+/// non-empty.
+pub fn split_share(share: &[u8], k: u8, m: u8) -> Result<Vec<Vec<u8>>, String> {
+    if k < 2 || k > m {
+        return Err(format!("INVALID_SPLIT: need 2 <= k <= m <= 255, got k = {k}, m = {m}"));
+    }
+    if share.is_empty() {
+        return Err("INVALID_SPLIT: the share is empty".into());
+    }
+    let mut split_id = [0u8; SPLIT_ID_LEN];
+    MixedRng.fill_bytes(&mut split_id);
+    let digest = Sha256::digest(share);
+
+    // Coefficients 1..k of every byte's polynomial, byte-major
+    let mut coefficients = Zeroizing::new(vec![0u8; share.len() * usize::from(k - 1)]);
+    MixedRng.fill_bytes(&mut coefficients);
+
+    let fragments = (1..=m)
+        .map(|x| {
+            let mut out = Vec::with_capacity(FRAGMENT_HEADER_LEN + share.len() + CHECKSUM_LEN);
+            out.extend_from_slice(FRAGMENT_MAGIC);
+            out.push(FRAGMENT_VERSION);
+            out.extend_from_slice(&split_id);
+            out.extend_from_slice(&[k, m, x]);
+            out.extend_from_slice(&digest);
+            out.extend_from_slice(&(share.len() as u32).to_le_bytes());
+            for (secret, coefficients) in share.iter().zip(coefficients.chunks(usize::from(k - 1))) {
+                // Horner, highest coefficient first
+                let y = coefficients
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, &c| gf_mul(acc, x) ^ c);
+                out.push(gf_mul(y, x) ^ secret);
+            }
+            let checksum = Sha256::digest(&out);
+            out.extend_from_slice(&checksum);
+            out
+        })
+        .collect();
+    Ok(fragments)
+}
+
+/// Put a share back together from at least `k` fragments of one split, in
+/// any order. Every fragment given is checked against the share's
+/// polynomials, not only the `k` interpolated.
+///
+/// Fails with `INVALID_FRAGMENT` for something that isn't a fragment this
+/// build reads, `FRAGMENT_CORRUPTED` naming the first damaged or forged
+/// one, `FRAGMENT_MISMATCH` for one of another split, `DUPLICATE_FRAGMENT`
+/// for the same fragment twice, `TOO_FEW_FRAGMENTS` and, when no `k` of
+/// them combine to the share split (a forged one among exactly `k`, or
+/// several forged), `BACKUP_CORRUPTED`.
+pub fn recover_share(fragments: &[impl AsRef<[u8]>]) -> Result<Zeroizing<Vec<u8>>, String> {
+    let parsed = fragments
+        .iter()
+        .enumerate()
+        .map(|(i, fragment)| parse_fragment(i, fragment.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some(first) = parsed.first() else {
+        return Err("TOO_FEW_FRAGMENTS: no fragments given".into());
+    };
+    for (i, fragment) in parsed.iter().enumerate().skip(1) {
+        if fragment.split_id != first.split_id
+            || (fragment.k, fragment.m) != (first.k, first.m)
+            || fragment.digest != first.digest
+            || fragment.data.len() != first.data.len()
+        {
+            return Err(format!("FRAGMENT_MISMATCH: fragment {i} is from another split than fragment 0"));
+        }
+        if let Some(j) = parsed[..i].iter().position(|earlier| earlier.index == fragment.index) {
+            return Err(format!("DUPLICATE_FRAGMENT: fragments {j} and {i} are both fragment {}", fragment.index));
+        }
+    }
+    let k = usize::from(first.k);
+    if parsed.len() < k {
+        return Err(format!(
+            "TOO_FEW_FRAGMENTS: {} given, this split needs {} of its {}",
+            parsed.len(),
+            first.k,
+            first.m
+        ));
+    }
+
+    // Through the first `k`, then — should that miss the digest and there
+    // be a spare — through the first `k` leaving out each fragment in turn
+    let spare = parsed.len() > k;
+    for left_out in std::iter::once(None).chain((0..parsed.len()).filter(|_| spare).map(Some)) {
+        let used: Vec<&Fragment> = (0..parsed.len())
+            .filter(|&i| Some(i) != left_out)
+            .map(|i| &parsed[i])
+            .take(k)
+            .collect();
+        let share = interpolate(&used, 0);
+        if Sha256::digest(&share[..]).as_slice() != first.digest {
+            continue;
+        }
+        if let Some(i) = parsed.iter().position(|fragment| *interpolate(&used, fragment.index) != fragment.data) {
+            return Err(format!("FRAGMENT_CORRUPTED: fragment {i} doesn't agree with the others"));
+        }
+        return Ok(share);
+    }
+    Err(if spare {
+        "BACKUP_CORRUPTED: more than one fragment is forged; no k of them combine to the share they were split from"
+    } else {
+        "BACKUP_CORRUPTED: the fragments don't combine to the share they were split from; \
+         one more fragment would tell which is forged"
+    }
+    .into())
+}
+
+/// The evaluations at `x` of the polynomials through `used` — the share
+/// itself at 0.
+fn interpolate(used: &[&Fragment], x: u8) -> Zeroizing<Vec<u8>> {
+    // Lagrange basis at `x` of each fragment used
+    let basis: Vec<u8> = used
+        .iter()
+        .enumerate()
+        .map(|(j, fragment)| {
+            let (num, den) = used
+                .iter()
+                .enumerate()
+                .filter(|&(l, _)| l != j)
+                .fold((1, 1), |(num, den), (_, other)| {
+                    (gf_mul(num, x ^ other.index), gf_mul(den, other.index ^ fragment.index))
+                });
+            gf_mul(num, gf_inv(den))
+        })
+        .collect();
+    let len = used.first().map_or(0, |fragment| fragment.data.len());
+    Zeroizing::new(
+        (0..len)
+            .map(|b| used.iter().zip(&basis).fold(0, |acc, (f, &l)| acc ^ gf_mul(f.data[b], l)))
+            .collect(),
+    )
+}
+
+/// A fragment whose checksum holds.
+struct Fragment<'a> {
+    split_id: &'a [u8],
+    k: u8,
+    m: u8,
+    index: u8,
+    digest: &'a [u8],
+    data: &'a [u8],
+}
+
+/// Fragment `i` of [`recover_share`]'s input, checked on its own.
+fn parse_fragment(i: usize, bytes: &[u8]) -> Result<Fragment<'_>, String> {
+    if !bytes.starts_with(FRAGMENT_MAGIC) {
+        return Err(format!("INVALID_FRAGMENT: fragment {i} is not a share fragment (bad magic)"));
+    }
+    if bytes.len() < FRAGMENT_HEADER_LEN + CHECKSUM_LEN {
+        return Err(format!("INVALID_FRAGMENT: fragment {i} is too short ({} bytes)", bytes.len()));
+    }
+    if bytes[4] != FRAGMENT_VERSION {
+        return Err(format!("INVALID_FRAGMENT: fragment {i} has unsupported version {}", bytes[4]));
+    }
+    let (body, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+    if Sha256::digest(body).as_slice() != checksum {
+        return Err(format!("FRAGMENT_CORRUPTED: fragment {i}: checksum mismatch"));
+    }
+    let (header, data) = body.split_at(FRAGMENT_HEADER_LEN);
+    let split_id = &header[5..5 + SPLIT_ID_LEN];
+    let (k, m, index) = (header[5 + SPLIT_ID_LEN], header[6 + SPLIT_ID_LEN], header[7 + SPLIT_ID_LEN]);
+    let digest = &header[8 + SPLIT_ID_LEN..40 + SPLIT_ID_LEN];
+    let data_len = u32::from_le_bytes(header[40 + SPLIT_ID_LEN..].try_into().expect("4 bytes")) as usize;
+    if data.len() != data_len {
+        return Err(format!("FRAGMENT_CORRUPTED: fragment {i}: data length field doesn't match"));
+    }
+    if k < 2 || k > m || index == 0 || index > m {
+        return Err(format!("FRAGMENT_CORRUPTED: fragment {i} claims to be number {index} of a {k}-of-{m} split"));
+    }
+    Ok(Fragment { split_id, k, m, index, digest, data })
+}
+
+/// Product in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1 (the AES field),
+/// without secret-dependent branches or tables.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    product
+}
+
+/// Inverse in GF(2^8): `a^254`. `a` is never 0 here — evaluation points
+/// are distinct and non-zero.
+fn gf_inv(a: u8) -> u8 {
+    let mut result = 1;
+    let mut power = a;
+    let mut exponent = 254u8;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = gf_mul(result, power);
+        }
+        power = gf_mul(power, power);
+        exponent >>= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHARE: &[u8] = b"a share, or anything else worth splitting";
+
+    /// `fragment` with its data changed and its checksum made to match.
+    fn forged(fragment: &[u8]) -> Vec<u8> {
+        let mut body = fragment[..fragment.len() - CHECKSUM_LEN].to_vec();
+        body[FRAGMENT_HEADER_LEN] ^= 1;
+        let checksum = Sha256::digest(&body);
+        body.extend_from_slice(&checksum);
+        body
+    }
+
+    #[test]
+    fn any_k_fragments_in_any_order_recover_the_share() {
+        let fragments = split_share(SHARE, 3, 5).unwrap();
+        for picked in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let some: Vec<Vec<u8>> = picked.iter().map(|&i| fragments[i].clone()).collect();
+            assert_eq!(&recover_share(&some).unwrap()[..], SHARE);
+        }
+        assert_eq!(&recover_share(&fragments).unwrap()[..], SHARE);
+    }
+
+    #[test]
+    fn k_minus_1_fragments_recover_nothing() {
+        let fragments = split_share(SHARE, 3, 5).unwrap();
+        let err = recover_share(&fragments[..2]).unwrap_err();
+        assert!(err.starts_with("TOO_FEW_FRAGMENTS"), "{err}");
+    }
+
+    #[test]
+    fn a_damaged_fragment_is_named() {
+        let mut fragments = split_share(SHARE, 3, 5).unwrap();
+        fragments[1][FRAGMENT_HEADER_LEN] ^= 1;
+        let err = recover_share(&fragments).unwrap_err();
+        assert!(err.starts_with("FRAGMENT_CORRUPTED: fragment 1:"), "{err}");
+    }
+
+    #[test]
+    fn a_forged_fragment_is_named_wherever_it_is() {
+        let fragments = split_share(SHARE, 3, 5).unwrap();
+        for bad in [0, 3] {
+            let mut given = fragments[..4].to_vec();
+            given[bad] = forged(&given[bad]);
+            let err = recover_share(&given).unwrap_err();
+            assert_eq!(err, format!("FRAGMENT_CORRUPTED: fragment {bad} doesn't agree with the others"));
+        }
+    }
+
+    #[test]
+    fn a_forged_fragment_among_exactly_k_fails_recovery() {
+        let mut fragments = split_share(SHARE, 3, 5).unwrap();
+        fragments.truncate(3);
+        fragments[2] = forged(&fragments[2]);
+        let err = recover_share(&fragments).unwrap_err();
+        assert!(err.starts_with("BACKUP_CORRUPTED"), "{err}");
+    }
+}
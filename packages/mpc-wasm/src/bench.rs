@@ -0,0 +1,286 @@
+//! Fixed-seed local ceremonies, timed, for before/after numbers on
+//! performance changes.
+//!
+//! Every iteration replays the same transcript (fixed seed, eid and
+//! message), so runs differ only in how fast this build executes them. The
+//! WASM exports and native-gen's `bench` subcommand report the same
+//! [`BenchReport`], which keeps WASM-vs-native comparisons like for like,
+//! and is stable JSON for CI to diff between builds.
+//!
+//! Native runs may spread iterations over threads ([`SignBenchOptions`]
+//! `concurrency`) to measure how signing scales across cores; latencies
+//! are per ceremony, `per_second` is over the whole run. The WASM build is
+//! single-threaded and always runs one at a time.
+//!
+//! The signing key is generated by a fixed-seed DKG on first use, not by a
+//! trusted dealer: the dealer only exists in `insecure-dev` builds.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+use cggmp24::security_level::SecurityLevel128;
+use cggmp24::supported_curves::Secp256k1;
+use serde::Serialize;
+
+use crate::clock;
+use crate::party_limits;
+use crate::simulate::{self, Traffic};
+
+const SEED: [u8; 32] = *b"guardian-wallet bench seed v1...";
+const EID: &[u8] = b"guardian-wallet bench";
+const MESSAGE_HASH: [u8; 32] = [0x42; 32];
+
+/// Signers `bench_sign` uses by default: the 2-of-3 flow, parties 0 and 1
+/// sign. With `signers` s the key is s-of-(s + 1).
+pub const DEFAULT_SIGNERS: u16 = 2;
+
+type BenchKeyShare = cggmp24::KeyShare<Secp256k1, SecurityLevel128>;
+
+thread_local! {
+    /// Key shares for `bench_sign` by signer count, generated on first use
+    /// (Paillier primes included, so the first call is slow; it isn't timed).
+    static SIGN_KEYS: RefCell<HashMap<u16, Vec<BenchKeyShare>>> = RefCell::new(HashMap::new());
+}
+
+/// What `bench_sign` runs.
+#[derive(Clone, Copy, Debug)]
+pub struct SignBenchOptions {
+    pub iterations: u32,
+    /// Parties co-signing, at least 2
+    pub signers: u16,
+    /// Sign from presignatures: presigning rounds, then the local partial
+    /// signatures (see `simulate::presign_local`)
+    pub presign: bool,
+    /// Ceremonies run at once, each on its own thread (native only)
+    pub concurrency: u32,
+}
+
+impl Default for SignBenchOptions {
+    fn default() -> Self {
+        SignBenchOptions {
+            iterations: 5,
+            signers: DEFAULT_SIGNERS,
+            presign: false,
+            concurrency: 1,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct BenchReport {
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    pub bench: &'static str,
+    pub n: u16,
+    pub t: u16,
+    pub iterations: u32,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub max_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    /// Ceremonies completed per second of wall-clock time
+    pub per_second: f64,
+    /// Ceremonies run at once
+    pub concurrency: u32,
+    /// Per iteration (identical across iterations)
+    pub messages: u64,
+    pub bytes: u64,
+    /// One-off preparation outside the timed loop (`bench_sign`: key
+    /// generation, 0 once cached)
+    pub setup_ms: f64,
+}
+
+/// Time `options.iterations` fixed-seed signing ceremonies on secp256k1
+/// (see [`SignBenchOptions`]). The report's bench is `"sign"` or
+/// `"presign"`.
+pub fn bench_sign(options: SignBenchOptions) -> Result<BenchReport, String> {
+    check_iterations(options.iterations)?;
+    let (t, n) = (options.signers, options.signers.saturating_add(1));
+    party_limits::check_key_params(n, t)?;
+    let start = clock::now_ms();
+    let key_shares = sign_keys(t)?;
+    let setup_ms = clock::now_ms() - start;
+
+    let signers = &key_shares[..usize::from(t)];
+    let parties: Vec<u16> = (0..t).collect();
+    let bench = if options.presign { "presign" } else { "sign" };
+    let mut report = timed(bench, n, t, options.iterations, options.concurrency, || {
+        let party_rng = |i: usize| simulate::party_rng(SEED, i);
+        let signed = if options.presign {
+            simulate::presign_local(signers, &parties, &MESSAGE_HASH, EID, party_rng)
+        } else {
+            simulate::sign_local(signers, &parties, &MESSAGE_HASH, EID, party_rng)
+        };
+        signed.map(|(_, traffic)| traffic)
+    })?;
+    report.setup_ms = setup_ms;
+    Ok(report)
+}
+
+/// Time `iterations` fixed-seed keygen ceremonies (no aux info phase) for
+/// `n` parties with threshold `t` on secp256k1.
+pub fn bench_dkg_keygen_only(n: u16, t: u16, iterations: u32) -> Result<BenchReport, String> {
+    check_iterations(iterations)?;
+    party_limits::check_key_params(n, t)?;
+    timed("dkg_keygen_only", n, t, iterations, 1, || {
+        let (_, traffic) = keygen::<Secp256k1>(n, t)?;
+        Ok(traffic)
+    })
+}
+
+fn check_iterations(iterations: u32) -> Result<(), String> {
+    if iterations == 0 {
+        return Err("iterations must be at least 1".into());
+    }
+    Ok(())
+}
+
+/// Run `ceremony` `iterations` times, `concurrency` at once, and summarise
+/// the wall-clock times.
+fn timed(
+    bench: &'static str,
+    n: u16,
+    t: u16,
+    iterations: u32,
+    concurrency: u32,
+    ceremony: impl Fn() -> Result<Traffic, String> + Sync,
+) -> Result<BenchReport, String> {
+    if concurrency == 0 {
+        return Err("concurrency must be at least 1".into());
+    }
+    let samples = Mutex::new(Vec::with_capacity(iterations as usize));
+    let traffic = Mutex::new(Traffic::default());
+    let next = AtomicU32::new(0);
+    let worker = || -> Result<(), String> {
+        while next.fetch_add(1, Ordering::Relaxed) < iterations {
+            let start = clock::now_ms();
+            let run = ceremony()?;
+            let ms = clock::now_ms() - start;
+            samples.lock().unwrap_or_else(|e| e.into_inner()).push(ms);
+            *traffic.lock().unwrap_or_else(|e| e.into_inner()) = run;
+        }
+        Ok(())
+    };
+
+    let start = clock::now_ms();
+    if concurrency == 1 {
+        worker()?;
+    } else {
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..concurrency.min(iterations)).map(|_| scope.spawn(&worker)).collect();
+            workers
+                .into_iter()
+                .try_for_each(|handle| handle.join().map_err(|_| "bench worker panicked".to_string())?)
+        })?;
+    }
+    let wall_ms = clock::now_ms() - start;
+
+    let mut samples = samples.into_inner().unwrap_or_else(|e| e.into_inner());
+    let traffic = traffic.into_inner().unwrap_or_else(|e| e.into_inner());
+    samples.sort_by(f64::total_cmp);
+    let mid = samples.len() / 2;
+    let median_ms = if samples.len() % 2 == 0 {
+        (samples[mid - 1] + samples[mid]) / 2.0
+    } else {
+        samples[mid]
+    };
+    Ok(BenchReport {
+        bench,
+        n,
+        t,
+        iterations,
+        min_ms: samples[0],
+        median_ms,
+        max_ms: samples[samples.len() - 1],
+        p50_ms: percentile(&samples, 50),
+        p95_ms: percentile(&samples, 95),
+        per_second: f64::from(iterations) * 1000.0 / wall_ms.max(f64::MIN_POSITIVE),
+        concurrency,
+        messages: traffic.messages,
+        bytes: traffic.bytes,
+        setup_ms: 0.0,
+    })
+}
+
+/// Fixed-seed keygen for `n` parties.
+pub(crate) fn keygen<E: generic_ec::Curve>(
+    n: u16,
+    t: u16,
+) -> Result<(Vec<cggmp24::IncompleteKeyShare<E>>, Traffic), String> {
+    let parties = (0..n)
+        .map(|i| {
+            let eid = cggmp24::ExecutionId::new(EID);
+            let mut rng = simulate::party_rng(SEED, i as usize);
+            round_based::state_machine::wrap_protocol(move |party| async move {
+                cggmp24::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .start(&mut rng, party)
+                    .await
+            })
+        })
+        .collect();
+
+    let (results, traffic) = simulate::run_counted(parties).map_err(|e| format!("keygen failed: {e}"))?;
+    let shares = results
+        .into_iter()
+        .enumerate()
+        .map(|(i, result)| result.map_err(|e| format!("keygen party {i} failed: {e:?}")))
+        .collect::<Result<_, _>>()?;
+    Ok((shares, traffic))
+}
+
+/// Nearest-rank `p`th percentile of sorted, non-empty `samples`.
+fn percentile(samples: &[f64], p: usize) -> f64 {
+    let rank = (samples.len() * p).div_ceil(100).max(1);
+    samples[rank - 1]
+}
+
+/// `bench_sign`'s key shares for `t` signers (a t-of-(t + 1) key),
+/// generating them on first use.
+pub(crate) fn sign_keys(t: u16) -> Result<Vec<BenchKeyShare>, String> {
+    if let Some(keys) = SIGN_KEYS.with(|keys| keys.borrow().get(&t).cloned()) {
+        return Ok(keys);
+    }
+    let n = t + 1;
+
+    let primes = (0..n)
+        .map(|i| {
+            let mut rng = simulate::party_rng(SEED, usize::from(n + i));
+            cggmp24::PregeneratedPrimes::<SecurityLevel128>::generate(&mut rng)
+        })
+        .collect::<Vec<_>>();
+    let aux_parties = primes
+        .into_iter()
+        .enumerate()
+        .map(|(i, primes)| {
+            let i = i as u16;
+            let eid = cggmp24::ExecutionId::new(EID);
+            let mut rng = simulate::party_rng(SEED, usize::from(2 * n + i));
+            round_based::state_machine::wrap_protocol(move |party| async move {
+                cggmp24::aux_info_gen(eid, i, n, primes)
+                    .start(&mut rng, party)
+                    .await
+            })
+        })
+        .collect();
+    let aux_infos = simulate::run(aux_parties).map_err(|e| format!("aux_info_gen failed: {e}"))?;
+
+    let (core_shares, _) = keygen::<Secp256k1>(n, t)?;
+    let keys = core_shares
+        .into_iter()
+        .zip(aux_infos)
+        .enumerate()
+        .map(|(i, (core, aux))| {
+            let aux = aux.map_err(|e| format!("aux_info_gen party {i} failed: {e:?}"))?;
+            cggmp24::KeyShare::from_parts((core, aux)).map_err(|e| format!("combine key share {i}: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    SIGN_KEYS.with(|cached| cached.borrow_mut().insert(t, keys.clone()));
+    Ok(keys)
+}
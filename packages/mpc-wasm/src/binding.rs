@@ -0,0 +1,55 @@
+//! Session binding tag carried by every signing message.
+//!
+//! The tag is the first 8 bytes of sha256(eid || message || sorted parties),
+//! hex-encoded, so a relay that feeds one session's messages into another is
+//! caught on arrival (`SESSION_BINDING_MISMATCH`) instead of surfacing as a
+//! protocol error rounds later. FROST sessions have no eid and bind the
+//! message itself.
+//!
+//! Members of a batch session each sign under their own eid, derived from
+//! the batch's with [`batch_member_eid`], so no two ever share a transcript.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use sha2::{Digest, Sha256};
+
+use crate::hex_codec;
+
+const BATCH_EID_DOMAIN: &[u8] = b"guardian-wallet batch member eid v1";
+
+/// Eid of the `index`-th member of a batch session over `eid`.
+pub fn batch_member_eid(eid: &[u8], index: u32) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(BATCH_EID_DOMAIN)
+        .chain_update((eid.len() as u64).to_be_bytes())
+        .chain_update(eid)
+        .chain_update(index.to_be_bytes())
+        .finalize()
+        .into()
+}
+
+/// Binding tag for a session over `eid`, `message` and the signing `parties`
+/// (keygen indices, any order).
+pub fn session_binding(eid: &[u8], message: &[u8], parties: &[u16]) -> String {
+    let mut sorted = parties.to_vec();
+    sorted.sort_unstable();
+
+    let mut hasher = Sha256::new();
+    hasher.update(eid);
+    hasher.update(message);
+    for party in sorted {
+        hasher.update(party.to_be_bytes());
+    }
+    hex_codec::encode(&hasher.finalize()[..8])
+}
+
+/// Check an incoming message's tag. Messages without one come from peers
+/// predating the tag and are let through.
+pub fn check_binding(expected: &str, sender: u16, got: Option<&str>) -> Result<(), String> {
+    match got {
+        Some(tag) if tag != expected => Err(format!(
+            "SESSION_BINDING_MISMATCH: message from party {sender} is bound to session {tag}, expected {expected}"
+        )),
+        _ => Ok(()),
+    }
+}
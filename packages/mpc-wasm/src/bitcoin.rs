@@ -0,0 +1,163 @@
+//! Bitcoin encodings of threshold ECDSA output, so a host can build
+//! transactions without another library.
+//!
+//! - [`script_sig`]: DER signature plus sighash byte, as pushed in a
+//!   scriptSig or segwit witness. `s` is brought to the low half of the
+//!   order first (BIP-62 / BIP-146), since relays refuse high-s signatures.
+//! - [`p2wpkh_address`]: native segwit v0 address (BIP-173 bech32) of a
+//!   compressed key. BIP-173's vector: key
+//!   `0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798`
+//!   is `bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4` on mainnet.
+//!
+//! secp256k1 only. No wasm-bindgen dependency — native-gen compiles this
+//! module too.
+
+use generic_ec::curves::Secp256k1;
+use generic_ec::{Point, Scalar};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+/// `(n - 1) / 2` for secp256k1: the largest low `s`.
+const HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Network an address is for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    /// Parse `"mainnet"` / `"testnet"`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "mainnet" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            other => Err(format!(
+                "INVALID_NETWORK: unknown network {other:?} (expected mainnet or testnet)"
+            )),
+        }
+    }
+
+    /// Bech32 human-readable part.
+    fn hrp(self) -> &'static str {
+        match self {
+            Network::Mainnet => "bc",
+            Network::Testnet => "tb",
+        }
+    }
+}
+
+/// DER encoding of a 32-byte `r` and `s` with `sighash_type` appended.
+///
+/// A high `s` is replaced by `n - s`, which verifies the same. Fails with
+/// `INVALID_SIGNATURE` if `r` or `s` is zero or not below the order, and
+/// with `INVALID_SIGHASH_TYPE` unless the type is ALL, NONE or SINGLE
+/// (`0x01`–`0x03`), optionally with ANYONECANPAY (`0x80`).
+pub fn script_sig(r: &[u8], s: &[u8], sighash_type: u8) -> Result<Vec<u8>, String> {
+    if !matches!(sighash_type & !0x80, 0x01..=0x03) {
+        return Err(format!(
+            "INVALID_SIGHASH_TYPE: 0x{sighash_type:02x} is not ALL, NONE or SINGLE (| ANYONECANPAY)"
+        ));
+    }
+    let scalar = |name: &str, bytes: &[u8]| {
+        Scalar::<Secp256k1>::from_be_bytes(bytes)
+            .ok()
+            .filter(|x| bytes.len() == 32 && !x.is_zero())
+            .ok_or_else(|| format!("INVALID_SIGNATURE: {name} must be 32 bytes, nonzero and below the curve order"))
+    };
+    let r = scalar("r", r)?;
+    let mut s = scalar("s", s)?;
+    if s.to_be_bytes().as_bytes() > &HALF_ORDER[..] {
+        s = -s;
+    }
+
+    let r = der_integer(r.to_be_bytes().as_bytes());
+    let s = der_integer(s.to_be_bytes().as_bytes());
+    let mut out = Vec::with_capacity(7 + r.len() + s.len());
+    out.extend_from_slice(&[0x30, (4 + r.len() + s.len()) as u8, 0x02, r.len() as u8]);
+    out.extend_from_slice(&r);
+    out.extend_from_slice(&[0x02, s.len() as u8]);
+    out.extend_from_slice(&s);
+    out.push(sighash_type);
+    Ok(out)
+}
+
+/// Minimal DER INTEGER content of an unsigned big-endian value: leading
+/// zeros dropped, one put back if the top bit would read as a sign.
+fn der_integer(bytes: &[u8]) -> Vec<u8> {
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len() - 1);
+    let mut out = Vec::with_capacity(bytes.len() + 1);
+    if bytes[start] & 0x80 != 0 {
+        out.push(0);
+    }
+    out.extend_from_slice(&bytes[start..]);
+    out
+}
+
+/// Bech32 P2WPKH address of a 33-byte compressed secp256k1 key. Fails with
+/// `INVALID_PUBLIC_KEY` on anything else: segwit outputs only commit to
+/// compressed keys.
+pub fn p2wpkh_address(public_key: &[u8], network: Network) -> Result<String, String> {
+    if public_key.len() != 33 || Point::<Secp256k1>::from_bytes(public_key).is_err() {
+        return Err(format!(
+            "INVALID_PUBLIC_KEY: expected a 33-byte compressed secp256k1 key, got {} bytes",
+            public_key.len()
+        ));
+    }
+    let hash160 = Ripemd160::digest(Sha256::digest(public_key));
+
+    // Witness version 0, then the program regrouped into 5-bit words
+    let mut data = vec![0u8];
+    let (mut acc, mut bits) = (0u32, 0u32);
+    for &byte in hash160.iter() {
+        acc = (acc << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            data.push(((acc >> bits) & 0x1f) as u8);
+        }
+    }
+    if bits > 0 {
+        data.push(((acc << (5 - bits)) & 0x1f) as u8);
+    }
+
+    let hrp = network.hrp();
+    let checksum = bech32_checksum(hrp, &data);
+    let mut address = format!("{hrp}1");
+    address.extend(data.iter().chain(&checksum).map(|&d| BECH32_CHARSET[usize::from(d)] as char));
+    Ok(address)
+}
+
+/// BIP-173 checksum (bech32 constant 1, as segwit v0 uses).
+fn bech32_checksum(hrp: &str, data: &[u8]) -> [u8; 6] {
+    fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+        const GEN: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+        let mut chk = 1u32;
+        for v in values {
+            let top = chk >> 25;
+            chk = ((chk & 0x1ffffff) << 5) ^ u32::from(v);
+            for (i, g) in GEN.iter().enumerate() {
+                if (top >> i) & 1 == 1 {
+                    chk ^= g;
+                }
+            }
+        }
+        chk
+    }
+
+    let expanded = hrp
+        .bytes()
+        .map(|b| b >> 5)
+        .chain([0])
+        .chain(hrp.bytes().map(|b| b & 0x1f))
+        .chain(data.iter().copied())
+        .chain([0; 6]);
+    let modulus = polymod(expanded) ^ 1;
+    std::array::from_fn(|i| ((modulus >> (5 * (5 - i))) & 0x1f) as u8)
+}
@@ -0,0 +1,94 @@
+//! Cancellation tokens for the long-running exports.
+//!
+//! A token is a number from [`create`]. The DKG exports (`cancel_token` in
+//! their options) and `pregenerate_paillier_primes` look at it at safe
+//! points — between parties, between passes of the local simulation — and
+//! fail with `CANCELLED` once it has been cancelled, dropping their partial
+//! output. `sign_process_round` checks on entry and aborts its session
+//! instead, since the peers need telling.
+//!
+//! The module is single-threaded, so a running export only notices a
+//! cancellation made before the call or from a callback it invokes (the
+//! `on_share` of `run_dkg_streaming`); a cancel posted to a busy worker
+//! lands once the call has returned. Cancelling a token that a queued call
+//! will use still saves that call.
+//!
+//! The error is `CANCELLED: {"stage":...,"completed":...,"total":...}`:
+//! the step that was interrupted and how far it got.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+/// Live tokens kept; creating one more fails with `TOO_MANY_TOKENS`.
+const MAX_TOKENS: usize = 1024;
+
+thread_local! {
+    /// Live tokens and whether each was cancelled
+    static TOKENS: RefCell<HashMap<u32, bool>> = RefCell::new(HashMap::new());
+    static NEXT_TOKEN: Cell<u32> = Cell::new(1);
+}
+
+/// Where a cancelled call stopped.
+#[derive(Serialize)]
+struct Cancelled<'a> {
+    /// `primes`, `aux_info_gen`, `keygen` or `serialize`
+    stage: &'a str,
+    /// Parties (or items) finished in that stage
+    completed: usize,
+    total: usize,
+}
+
+/// A fresh, uncancelled token.
+pub fn create() -> Result<u32, String> {
+    TOKENS.with(|tokens| {
+        let mut tokens = tokens.borrow_mut();
+        if tokens.len() >= MAX_TOKENS {
+            return Err(format!(
+                "TOO_MANY_TOKENS: {MAX_TOKENS} cancel tokens are live; release some first"
+            ));
+        }
+        let token = NEXT_TOKEN.with(|next| next.replace(next.get().wrapping_add(1).max(1)));
+        tokens.insert(token, false);
+        Ok(token)
+    })
+}
+
+/// Cancel `token`; false if it doesn't exist (or was released).
+pub fn cancel(token: u32) -> bool {
+    TOKENS.with(|tokens| match tokens.borrow_mut().get_mut(&token) {
+        Some(cancelled) => {
+            *cancelled = true;
+            true
+        }
+        None => false,
+    })
+}
+
+/// Forget `token`; false if it doesn't exist.
+pub fn release(token: u32) -> bool {
+    TOKENS.with(|tokens| tokens.borrow_mut().remove(&token).is_some())
+}
+
+/// Whether `token` was cancelled. Unknown tokens never are.
+pub fn is_cancelled(token: Option<u32>) -> bool {
+    token.is_some_and(|token| TOKENS.with(|tokens| tokens.borrow().get(&token).copied().unwrap_or(false)))
+}
+
+/// `CANCELLED` if `token` was cancelled, reporting `completed` of `total`
+/// done in `stage`.
+pub fn check(token: Option<u32>, stage: &str, completed: usize, total: usize) -> Result<(), String> {
+    if !is_cancelled(token) {
+        return Ok(());
+    }
+    let progress = Cancelled {
+        stage,
+        completed,
+        total,
+    };
+    Err(format!(
+        "CANCELLED: {}",
+        serde_json::to_string(&progress).expect("serialize cancel progress")
+    ))
+}
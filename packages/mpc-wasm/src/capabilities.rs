@@ -0,0 +1,60 @@
+//! What this build supports, for hosts rolling out across versions.
+//!
+//! The JS SDK loads whichever WASM build the page was served with, and a
+//! `signing`-only browser build has no DKG exports at all. [`capabilities`]
+//! reports versions and feature names so the host can check before calling
+//! instead of catching a missing export. native-gen's `capabilities`
+//! subcommand prints the same shape.
+//!
+//! Feature names are the build's cargo features plus protocol names
+//! (`ecdsa`, `frost`); callers pass them in, since this module can't test
+//! the WASM crate's features. The bignum backend is passed in the same
+//! way: the WASM build is always on num-bigint, native-gen on rug/GMP
+//! unless built without its `gmp` feature.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use serde::Serialize;
+
+use crate::protocol_digest::ProtocolDigest;
+use crate::share_format::{self, CurveId};
+
+/// cggmp24 release this build is pinned to (see Cargo.lock).
+pub const CGGMP24_VERSION: &str = "0.7.0-alpha.3";
+
+#[derive(Serialize, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct Capabilities {
+    /// Version of the crate that built this module
+    pub crate_version: String,
+    pub cggmp24_version: String,
+    /// Bignum backend of the Paillier arithmetic, `"rug"` (GMP) or
+    /// `"num-bigint"` (pure Rust)
+    pub bignum_backend: String,
+    /// Share blob header versions read; 0 is the headerless legacy form
+    pub share_format_versions: Vec<u8>,
+    /// Signing envelope version spoken; absent without signing support
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub message_version: Option<u8>,
+    /// Curves key material may be on
+    pub curves: Vec<String>,
+    /// Digests signing can hash its protocol with (`SignOptions::digest`);
+    /// the first is the default
+    pub digests: Vec<String>,
+    /// Compiled-in features
+    pub features: Vec<String>,
+}
+
+pub fn capabilities(message_version: Option<u8>, bignum_backend: &str, features: &[&str]) -> Capabilities {
+    Capabilities {
+        crate_version: env!("CARGO_PKG_VERSION").into(),
+        cggmp24_version: CGGMP24_VERSION.into(),
+        bignum_backend: bignum_backend.into(),
+        share_format_versions: share_format::SUPPORTED_VERSIONS.to_vec(),
+        message_version,
+        curves: CurveId::ALL.iter().map(|c| c.as_str().to_string()).collect(),
+        digests: ProtocolDigest::ALL.iter().map(|d| d.as_str().to_string()).collect(),
+        features: features.iter().map(|f| f.to_string()).collect(),
+    }
+}
@@ -0,0 +1,22 @@
+//! Wall-clock time: `Date::now()` on wasm, `SystemTime` natively.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+/// Milliseconds since the Unix epoch.
+#[cfg(target_arch = "wasm32")]
+pub fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+/// Milliseconds since the Unix epoch.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now_ms() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0)
+}
+
+/// Unix seconds now.
+pub fn unix_seconds() -> u64 {
+    (now_ms() / 1000.0) as u64
+}
@@ -0,0 +1,237 @@
+//! Share commitments: attest that copies held apart belong to one wallet.
+//!
+//! Each holder computes [`share_commitment`] over its own share and sends
+//! only that; [`verify_commitments`] checks the set describes one key with
+//! no party repeated. No share ever moves, and a commitment reveals nothing
+//! the public key shares don't already.
+//!
+//! A commitment is 69 bytes:
+//!
+//! | bytes  | field                                               |
+//! |--------|-----------------------------------------------------|
+//! | 0      | version (1)                                         |
+//! | 1      | curve (`share_format` curve tag)                    |
+//! | 2..4   | party index, big-endian                             |
+//! | 4..37  | shared public key, compressed SEC1                  |
+//! | 37..69 | key digest                                          |
+//!
+//! The key digest is SHA-256 over a domain label, the curve, `n`,
+//! `min_signers`, the evaluation points (if any), every party's public
+//! share and the shared public key — the parts all holders have in common.
+//! It is computed from the decoded share, never its bytes, so it doesn't
+//! change with the share's encoding, its aux info or its header. The
+//! secret scalar is never read.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::key_material;
+use crate::share_format::{self, with_curve, with_security_level, BlobKind, CurveId};
+
+const VERSION: u8 = 1;
+const COMMITMENT_LEN: usize = 69;
+const DIGEST_DOMAIN: &[u8] = b"guardian-wallet share commitment v1";
+
+/// The public parts of a share that are committed to.
+struct KeyParts {
+    i: u16,
+    n: u16,
+    min_signers: u16,
+    /// Evaluation points, 32-byte big-endian; empty for an additive key
+    points: Vec<Vec<u8>>,
+    /// Compressed
+    public_shares: Vec<Vec<u8>>,
+    public_key: Vec<u8>,
+}
+
+/// The `KeyParts` of anything that derefs to a key-share crate
+/// `DirtyCoreKeyShare` (cggmp24 core shares, FROST key shares).
+macro_rules! key_parts {
+    ($core:expr) => {{
+        let core = $core;
+        let points: Vec<Vec<u8>> = core
+            .vss_setup
+            .as_ref()
+            .map(|vss| vss.I.iter().map(|x| x.to_be_bytes().as_bytes().to_vec()).collect())
+            .unwrap_or_default();
+        let public_shares: Vec<Vec<u8>> = core
+            .public_shares
+            .iter()
+            .map(|p| p.to_bytes(true).as_bytes().to_vec())
+            .collect();
+        KeyParts {
+            i: core.i,
+            n: public_shares.len() as u16,
+            min_signers: core
+                .vss_setup
+                .as_ref()
+                .map_or(public_shares.len() as u16, |vss| vss.min_signers),
+            points,
+            public_shares,
+            public_key: core.shared_public_key.to_bytes(true).as_bytes().to_vec(),
+        }
+    }};
+}
+
+/// Commitment to the public parts of a serialised KeyShare, CoreKeyShare or
+/// FROST key share. Fails with `INVALID_SHARE` on anything else.
+pub fn share_commitment(share: &[u8]) -> Result<Vec<u8>, String> {
+    let (header, payload) = share_format::split(share)?;
+    let curve = header.curve_or_default();
+
+    let parts = match header.kind {
+        Some(BlobKind::FrostKeyShare) => {
+            let ks: givre::KeyShare<cggmp24::supported_curves::Secp256k1> =
+                share_format::deserialize(BlobKind::FrostKeyShare, payload)?;
+            Some(key_parts!(&ks))
+        }
+        Some(BlobKind::AuxInfo | BlobKind::Primes) => None,
+        kind => with_curve!(curve, E => {
+            let mut parts = None;
+            if kind.map_or(true, |k| k == BlobKind::KeyShare) {
+                parts = with_security_level!(header.level_or_default(), L => {
+                    share_format::deserialize::<cggmp24::KeyShare<E, L>>(BlobKind::KeyShare, payload)
+                        .ok()
+                        .map(|ks| key_parts!(&ks.core))
+                });
+            }
+            if parts.is_none() && kind.map_or(true, |k| k == BlobKind::CoreShare) {
+                parts = share_format::deserialize::<cggmp24::IncompleteKeyShare<E>>(BlobKind::CoreShare, payload)
+                    .ok()
+                    .map(|iks| key_parts!(&iks));
+            }
+            parts
+        }),
+    };
+    let parts = parts.ok_or("INVALID_SHARE: not a KeyShare, CoreKeyShare or FROST key share")?;
+
+    let mut digest = Sha256::new();
+    digest.update(DIGEST_DOMAIN);
+    digest.update([curve.tag()]);
+    digest.update(parts.n.to_be_bytes());
+    digest.update(parts.min_signers.to_be_bytes());
+    digest.update((parts.points.len() as u16).to_be_bytes());
+    for point in &parts.points {
+        digest.update(point);
+    }
+    for public_share in &parts.public_shares {
+        digest.update(public_share);
+    }
+    digest.update(&parts.public_key);
+
+    let mut out = Vec::with_capacity(COMMITMENT_LEN);
+    out.push(VERSION);
+    out.push(curve.tag());
+    out.extend_from_slice(&parts.i.to_be_bytes());
+    out.extend_from_slice(&parts.public_key);
+    out.extend_from_slice(&digest.finalize());
+    Ok(out)
+}
+
+/// Result of [`verify_commitments`].
+#[derive(Serialize, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct CommitmentReport {
+    /// Every commitment is for the same key (the expected one, if given)
+    /// and no party index repeats
+    pub consistent: bool,
+    /// Positions of the commitments that don't fit: malformed, for another
+    /// key, or repeating an earlier commitment's party
+    pub mismatched: Vec<usize>,
+    /// Hex compressed public key the set agrees on, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub public_key: Option<String>,
+    /// What is wrong with each mismatched commitment
+    pub errors: Vec<String>,
+}
+
+/// A decoded commitment.
+struct Parsed<'a> {
+    curve: CurveId,
+    party_index: u16,
+    public_key: &'a [u8],
+    /// Key digest
+    key: &'a [u8],
+}
+
+fn parse(commitment: &[u8]) -> Result<Parsed<'_>, String> {
+    if commitment.len() != COMMITMENT_LEN || commitment[0] != VERSION {
+        return Err(format!(
+            "not a version {VERSION} share commitment ({} bytes)",
+            commitment.len()
+        ));
+    }
+    let curve = CurveId::from_tag(commitment[1])?.ok_or("commitment names no curve")?;
+    Ok(Parsed {
+        curve,
+        party_index: u16::from_be_bytes([commitment[2], commitment[3]]),
+        public_key: &commitment[4..37],
+        key: &commitment[37..],
+    })
+}
+
+/// Check that `commitments` are all for one key — `expected_public_key`
+/// (either SEC1 form) if given, else the one most of them are for — and
+/// name distinct parties. Never fails; problems are reported per
+/// commitment.
+pub fn verify_commitments(commitments: &[impl AsRef<[u8]>], expected_public_key: Option<&[u8]>) -> CommitmentReport {
+    let mut mismatched = Vec::new();
+    let mut errors = Vec::new();
+    let mut flag = |j: usize, error: String| {
+        mismatched.push(j);
+        errors.push(format!("commitment {j}: {error}"));
+    };
+
+    let mut parsed = Vec::with_capacity(commitments.len());
+    for (j, commitment) in commitments.iter().enumerate() {
+        match parse(commitment.as_ref()) {
+            Ok(p) => parsed.push((j, p)),
+            Err(e) => flag(j, e),
+        }
+    }
+
+    // Candidates for the reference key: those on the expected key, if any
+    let on_expected = |p: &Parsed<'_>| {
+        expected_public_key.map_or(true, |expected| {
+            key_material::recode_public_key(p.curve, expected, true).is_ok_and(|pk| pk == p.public_key)
+        })
+    };
+    let reference = parsed
+        .iter()
+        .filter(|(_, p)| on_expected(p))
+        .max_by_key(|(_, p)| {
+            parsed
+                .iter()
+                .filter(|(_, q)| q.curve == p.curve && q.key == p.key)
+                .count()
+        })
+        .map(|(_, p)| (p.curve, p.key, p.public_key));
+
+    let mut seen = Vec::new();
+    for (j, p) in &parsed {
+        match reference {
+            None => flag(*j, "not for the expected public key".into()),
+            Some((curve, key, _)) if p.curve != curve || p.key != key => {
+                flag(*j, "for a different key".into())
+            }
+            Some(_) if seen.contains(&p.party_index) => {
+                flag(*j, format!("party {} appears twice", p.party_index))
+            }
+            Some(_) => seen.push(p.party_index),
+        }
+    }
+    if commitments.is_empty() {
+        errors.push("no commitments given".into());
+    }
+    mismatched.sort_unstable();
+
+    CommitmentReport {
+        consistent: !commitments.is_empty() && mismatched.is_empty(),
+        mismatched,
+        public_key: reference.map(|(_, _, pk)| pk.iter().map(|b| format!("{b:02x}")).collect()),
+        errors,
+    }
+}
@@ -0,0 +1,126 @@
+//! Runtime configuration: the module's tunable limits as one object.
+//!
+//! `init` takes a [`ConfigUpdate`] alongside its other options,
+//! `update_config` applies one later (e.g. a lower session cap under memory
+//! pressure) and `get_config` returns the [`GuardianConfig`] in effect.
+//! Fields left out of an update keep their current value; an update with
+//! any invalid field changes nothing.
+//!
+//! Each value lives where it is enforced — message limits and the share
+//! cache in `sign`, the party cap in `party_limits` — so the single-purpose
+//! setters (`set_message_limits`, `set_share_cache_capacity`,
+//! `set_max_parties`) and the config never disagree. Only the session cap
+//! is kept here.
+
+use std::cell::Cell;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::limits::LimitOverrides;
+use crate::party_limits;
+use crate::sign;
+
+/// Live signing sessions allowed when the host doesn't configure a cap.
+pub const DEFAULT_MAX_SESSIONS: u32 = 256;
+
+thread_local! {
+    static MAX_SESSIONS: Cell<u32> = const { Cell::new(DEFAULT_MAX_SESSIONS) };
+}
+
+/// The configuration in effect.
+#[derive(Serialize, Tsify, Clone, Debug)]
+pub struct GuardianConfig {
+    /// Live signing sessions, each member of a batch counting as one;
+    /// creating more fails with `TOO_MANY_SESSIONS`
+    pub max_sessions: u32,
+    /// Message limits of new signing sessions (see `set_message_limits`)
+    pub max_message_bytes: usize,
+    pub max_messages_per_round: usize,
+    pub max_messages_per_session: u32,
+    /// Combined key shares kept cached for signing; 0 disables the cache
+    pub share_cache_capacity: usize,
+    /// Largest `n` accepted for new keys
+    pub max_parties: u16,
+}
+
+/// Changes to the configuration. Numbers are taken as JS passes them and
+/// range-checked here, so a bad one is reported by field name.
+#[derive(Deserialize, Tsify, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ConfigUpdate {
+    /// At least 1
+    pub max_sessions: Option<f64>,
+    /// At least 1
+    pub max_message_bytes: Option<f64>,
+    /// At least 1
+    pub max_messages_per_round: Option<f64>,
+    /// At least 1
+    pub max_messages_per_session: Option<f64>,
+    pub share_cache_capacity: Option<f64>,
+    /// 2 to 65535
+    pub max_parties: Option<f64>,
+}
+
+/// Apply `update` once every field in it checks out. Fails with
+/// `INVALID_CONFIG` naming the first bad field, changing nothing.
+pub fn update(update: &ConfigUpdate) -> Result<GuardianConfig, String> {
+    let max_sessions = whole("max_sessions", update.max_sessions, 1, u32::MAX)?;
+    let limits = LimitOverrides {
+        max_message_bytes: whole("max_message_bytes", update.max_message_bytes, 1, u32::MAX)?.map(to_usize),
+        max_messages_per_round: whole("max_messages_per_round", update.max_messages_per_round, 1, u32::MAX)?
+            .map(to_usize),
+        max_messages_per_session: whole("max_messages_per_session", update.max_messages_per_session, 1, u32::MAX)?,
+    };
+    let share_cache_capacity = whole("share_cache_capacity", update.share_cache_capacity, 0, u32::MAX)?;
+    let max_parties = whole("max_parties", update.max_parties, 2, u16::MAX.into())?
+        .map(|max| u16::try_from(max).expect("checked against u16::MAX"));
+
+    if let Some(max) = max_sessions {
+        MAX_SESSIONS.with(|cap| cap.set(max));
+    }
+    sign::set_default_limits(sign::default_limits().with_overrides(&limits));
+    if let Some(capacity) = share_cache_capacity {
+        sign::set_share_cache_capacity(to_usize(capacity));
+    }
+    if let Some(max) = max_parties {
+        party_limits::set_max_parties(max)?;
+    }
+    Ok(current())
+}
+
+/// The configuration in effect.
+pub fn current() -> GuardianConfig {
+    let limits = sign::default_limits();
+    GuardianConfig {
+        max_sessions: max_sessions(),
+        max_message_bytes: limits.max_message_bytes,
+        max_messages_per_round: limits.max_messages_per_round,
+        max_messages_per_session: limits.max_messages_per_session,
+        share_cache_capacity: sign::share_cache_capacity(),
+        max_parties: party_limits::max_parties(),
+    }
+}
+
+/// Live signing sessions allowed.
+pub fn max_sessions() -> u32 {
+    MAX_SESSIONS.with(Cell::get)
+}
+
+/// `value` as a whole number in `[min, max]`.
+fn whole(field: &str, value: Option<f64>, min: u32, max: u32) -> Result<Option<u32>, String> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    // NaN and the infinities have no zero fraction, so they fail here too
+    if value.fract() != 0.0 || value < f64::from(min) || value > f64::from(max) {
+        return Err(format!(
+            "INVALID_CONFIG: {field} must be a whole number in [{min}, {max}], got {value}"
+        ));
+    }
+    Ok(Some(value as u32))
+}
+
+fn to_usize(value: u32) -> usize {
+    usize::try_from(value).unwrap_or(usize::MAX)
+}
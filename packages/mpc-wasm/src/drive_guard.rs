@@ -0,0 +1,40 @@
+//! Catching a state machine that yields forever.
+//!
+//! `ProceedResult::Yielded` means "call `proceed()` again", and every drive
+//! loop does. A state machine that keeps yielding without sending, asking
+//! for a message or finishing — a cggmp24 bug, a corrupted state — would
+//! spin its loop for good: a frozen browser tab, a hung native process.
+//! Drive loops count consecutive yields with a [`YieldGuard`] and give up
+//! with `DRIVE_LOOP_STALLED` after [`MAX_CONSECUTIVE_YIELDS`]. A healthy
+//! protocol yields a handful of times between messages.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+/// `Yielded` steps in a row after which a state machine is taken to be stuck.
+pub const MAX_CONSECUTIVE_YIELDS: u32 = 10_000;
+
+/// Consecutive `Yielded` steps of one state machine.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct YieldGuard {
+    yields: u32,
+}
+
+impl YieldGuard {
+    /// Count a `proceed()` step, `yielded` if it returned `Yielded`; any
+    /// other result starts the count over. Fails with `DRIVE_LOOP_STALLED`
+    /// once the count reaches [`MAX_CONSECUTIVE_YIELDS`].
+    pub fn step(&mut self, yielded: bool) -> Result<(), String> {
+        if !yielded {
+            self.yields = 0;
+            return Ok(());
+        }
+        self.yields += 1;
+        if self.yields >= MAX_CONSECUTIVE_YIELDS {
+            return Err(format!(
+                "DRIVE_LOOP_STALLED: the state machine yielded {} times in a row without progressing",
+                self.yields
+            ));
+        }
+        Ok(())
+    }
+}
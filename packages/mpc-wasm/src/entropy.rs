@@ -0,0 +1,70 @@
+//! OS entropy health check and host-supplied extra entropy.
+//!
+//! Some locked-down WebViews have no working `crypto.getRandomValues`, and
+//! the first symptom used to be a panic deep inside a ceremony. Every draw
+//! here fails with `ENTROPY_UNAVAILABLE` instead, and [`check`] lets the host
+//! find out before it starts anything.
+//!
+//! Extra entropy from the host goes to `rng::add_extra`, whose stream is
+//! XORed into every draw of `rng::MixedRng` and into the session seeds
+//! drawn here, as native-gen does for `--entropy-source`.
+
+use zeroize::Zeroize;
+
+use crate::rng;
+
+/// Fill `dest` from the OS rng.
+pub fn os_fill(dest: &mut [u8]) -> Result<(), String> {
+    getrandom::getrandom(dest).map_err(|e| format!("ENTROPY_UNAVAILABLE: getrandom failed: {e}"))
+}
+
+/// Draw 32 bytes from the OS rng and sanity-check them.
+pub fn check() -> Result<(), String> {
+    let mut sample = [0u8; 32];
+    os_fill(&mut sample)?;
+    let checked = sanity_check(&sample);
+    sample.zeroize();
+    checked
+}
+
+/// Reject what a working CSPRNG doesn't produce in practice: all zeros (a
+/// stub that never wrote the buffer) or one byte repeated.
+fn sanity_check(sample: &[u8]) -> Result<(), String> {
+    if sample.iter().all(|&b| b == 0) {
+        return Err("ENTROPY_UNAVAILABLE: getrandom returned all zeros".into());
+    }
+    if sample.windows(2).all(|w| w[0] == w[1]) {
+        return Err("ENTROPY_UNAVAILABLE: getrandom returned a single repeated byte".into());
+    }
+    Ok(())
+}
+
+/// Fill `dest` with sanity-checked OS randomness, XORed with the extra
+/// stream when the host supplied one.
+pub fn fill_mixed(dest: &mut [u8]) -> Result<(), String> {
+    os_fill(dest)?;
+    sanity_check(dest)?;
+    rng::mix_extra(dest);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_os_rng_passes_the_check() {
+        check().unwrap();
+    }
+
+    #[test]
+    fn all_zeros_or_one_repeated_byte_is_refused() {
+        for sample in [[0u8; 32], [0xa5; 32]] {
+            let err = sanity_check(&sample).unwrap_err();
+            assert!(err.starts_with("ENTROPY_UNAVAILABLE"), "{err}");
+        }
+        let mut sample = [0xa5u8; 32];
+        sample[31] = 0x5a;
+        sanity_check(&sample).unwrap();
+    }
+}
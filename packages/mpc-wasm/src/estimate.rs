@@ -0,0 +1,185 @@
+//! Dry-run estimates of how long a DKG or signing ceremony takes on this
+//! device, and how much memory it needs, before committing to one.
+//!
+//! A short calibration measures the device: a bounded burst of the 1536-bit
+//! modular exponentiations that prime search and the Paillier proofs are
+//! made of, and (DKG) one fixed-seed keygen on at most
+//! [`CALIBRATION_PARTIES`] parties. The estimate scales those by how much of
+//! each a ceremony of the requested size does. The operation counts are
+//! approximate and prime search is luck — one safe prime takes anywhere
+//! from a few hundred to several thousand candidates — so every estimate is
+//! a `low`–`high` range rather than a prediction. Estimates grow
+//! monotonically with `n` (DKG) and `parties` (signing).
+//!
+//! Calibration takes under two seconds — the burst stops after
+//! [`MODEXP_BUDGET_MS`], and the keygen is small — and calls `cancel`
+//! between steps; its error is returned as is.
+//!
+//! DKG estimates are for the local ceremony (`run_dkg`): every party on this
+//! device, one after the other. Signing estimates are for one party's share
+//! of a session; the others do theirs on their own devices.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use cggmp24::fast_paillier::backend::Integer;
+use cggmp24::security_level::{SecurityLevel, SecurityLevel128};
+use cggmp24::supported_curves::Secp256k1;
+use serde::Serialize;
+
+use crate::bench;
+use crate::clock;
+use crate::party_limits;
+use crate::rng::MixedRng;
+
+/// Wall-clock cap on the modexp burst.
+pub const MODEXP_BUDGET_MS: f64 = 1000.0;
+/// Modexps in the burst, when the budget allows that many.
+const CALIBRATION_MODEXPS: usize = 32;
+/// The calibration keygen runs on at most this many parties.
+pub const CALIBRATION_PARTIES: u16 = 3;
+
+// Work per ceremony step, in calibrated 1536-bit modexps. Rough figures for
+// level 128; the ranges below absorb the error.
+
+/// Expected candidates tested to find one 1536-bit safe prime after sieving
+pub const SAFE_PRIME_MODEXPS: f64 = 1500.0;
+/// A modexp modulo the 3072-bit Paillier modulus
+const PAILLIER_MODEXP: f64 = 7.0;
+/// aux_info_gen: proving one party's Paillier and ring-Pedersen parameters
+const AUX_OWN_MODEXPS: f64 = 150.0 * PAILLIER_MODEXP;
+/// aux_info_gen: checking one peer's proofs, and the proof made for it
+const AUX_PEER_MODEXPS: f64 = 250.0 * PAILLIER_MODEXP;
+/// Presigning: one party's own Paillier encryptions
+const PRESIGN_OWN_MODEXPS: f64 = 10.0 * PAILLIER_MODEXP;
+/// Presigning: the range and affine proofs made for, and checked from, one peer
+const PRESIGN_PEER_MODEXPS: f64 = 40.0 * PAILLIER_MODEXP;
+
+/// (low, high) multipliers on the expected time of a step
+const PRIMES_RANGE: (f64, f64) = (0.3, 2.5);
+const PROOFS_RANGE: (f64, f64) = (0.5, 2.0);
+const MEASURED_RANGE: (f64, f64) = (0.8, 1.5);
+
+/// Baseline heap of a ceremony, before any messages are held
+const BASE_MEMORY_MB: f64 = 4.0;
+/// aux_info_gen messages held for one (sender, recipient) pair
+const AUX_MESSAGE_MB: f64 = 0.1;
+/// Presigning messages held from one peer
+const PRESIGN_MESSAGE_MB: f64 = 0.02;
+
+/// What a ceremony is expected to cost on this device.
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct Estimate {
+    pub est_seconds_low: f64,
+    pub est_seconds_high: f64,
+    pub est_peak_memory_mb: f64,
+    /// In the order they run; their bounds sum to the totals
+    pub phases: Vec<PhaseEstimate>,
+    /// Time the calibration took
+    pub calibration_ms: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct PhaseEstimate {
+    /// `primes`, `aux_info` or `keygen` (DKG); `presign` or `sign`
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    pub phase: &'static str,
+    pub est_seconds_low: f64,
+    pub est_seconds_high: f64,
+}
+
+/// Estimate a local DKG of `n` parties with threshold `threshold`.
+/// `with_primes`: the primes are pregenerated (`run_dkg_with_primes`), so
+/// there is no prime search.
+pub fn estimate_dkg(
+    n: u16,
+    threshold: u16,
+    with_primes: bool,
+    cancel: &dyn Fn(&str, usize, usize) -> Result<(), String>,
+) -> Result<Estimate, String> {
+    party_limits::check_key_params(n, threshold)?;
+    let start = clock::now_ms();
+    let modexp_ms = modexp_ms(cancel)?;
+
+    let (cal_n, cal_t) = (n.min(CALIBRATION_PARTIES), threshold.min(CALIBRATION_PARTIES));
+    cancel("calibrate_keygen", 0, 1)?;
+    let keygen_start = clock::now_ms();
+    bench::keygen::<Secp256k1>(cal_n, cal_t)?;
+    let keygen_ms = clock::now_ms() - keygen_start;
+
+    // Keygen work grows with every party checking every other's degree-t
+    // commitments
+    let (n_f, t_f) = (f64::from(n), f64::from(threshold));
+    let keygen_scale = (n_f * n_f * t_f) / (f64::from(cal_n) * f64::from(cal_n) * f64::from(cal_t));
+
+    let mut phases = Vec::with_capacity(3);
+    if !with_primes {
+        // Two safe primes (p, q) per party
+        let ms = n_f * 2.0 * SAFE_PRIME_MODEXPS * modexp_ms;
+        phases.push(phase("primes", ms, PRIMES_RANGE));
+    }
+    let aux_modexps = n_f * (AUX_OWN_MODEXPS + (n_f - 1.0) * AUX_PEER_MODEXPS);
+    phases.push(phase("aux_info", aux_modexps * modexp_ms, PROOFS_RANGE));
+    phases.push(phase("keygen", keygen_ms * keygen_scale, MEASURED_RANGE));
+
+    let memory_mb = BASE_MEMORY_MB + n_f * (n_f - 1.0) * AUX_MESSAGE_MB;
+    Ok(total(phases, memory_mb, clock::now_ms() - start))
+}
+
+/// Estimate one party's work in a signing session of `parties` co-signers.
+pub fn estimate_sign(
+    parties: u16,
+    cancel: &dyn Fn(&str, usize, usize) -> Result<(), String>,
+) -> Result<Estimate, String> {
+    party_limits::check_key_params(parties, parties)?;
+    let start = clock::now_ms();
+    let modexp_ms = modexp_ms(cancel)?;
+
+    let peers = f64::from(parties - 1);
+    let presign_modexps = PRESIGN_OWN_MODEXPS + peers * PRESIGN_PEER_MODEXPS;
+    let phases = vec![
+        phase("presign", presign_modexps * modexp_ms, PROOFS_RANGE),
+        // Curve arithmetic only: a handful of modexps' worth
+        phase("sign", modexp_ms * (1.0 + peers), MEASURED_RANGE),
+    ];
+
+    let memory_mb = BASE_MEMORY_MB + peers * PRESIGN_MESSAGE_MB;
+    Ok(total(phases, memory_mb, clock::now_ms() - start))
+}
+
+/// Milliseconds per 1536-bit modexp (the level 128 prime size), from a
+/// burst capped by count and time.
+pub fn modexp_ms(cancel: &dyn Fn(&str, usize, usize) -> Result<(), String>) -> Result<f64, String> {
+    let bits = SecurityLevel128::RSA_PRIME_BITLEN as usize;
+    let modulus = &(Integer::one() << bits) - &Integer::one();
+    let exponent = Integer::sample_in_mult_group_of(&mut MixedRng, &modulus);
+
+    let start = clock::now_ms();
+    let mut done = 0;
+    while done < CALIBRATION_MODEXPS && (done == 0 || clock::now_ms() - start < MODEXP_BUDGET_MS) {
+        cancel("calibrate_modexp", done, CALIBRATION_MODEXPS)?;
+        let base = Integer::sample_in_mult_group_of(&mut MixedRng, &modulus);
+        base.pow_mod_ref(&exponent, &modulus).ok_or("modexp calibration failed")?;
+        done += 1;
+    }
+    Ok((clock::now_ms() - start) / done as f64)
+}
+
+fn phase(phase: &'static str, expected_ms: f64, (low, high): (f64, f64)) -> PhaseEstimate {
+    PhaseEstimate {
+        phase,
+        est_seconds_low: expected_ms * low / 1000.0,
+        est_seconds_high: expected_ms * high / 1000.0,
+    }
+}
+
+fn total(phases: Vec<PhaseEstimate>, memory_mb: f64, calibration_ms: f64) -> Estimate {
+    Estimate {
+        est_seconds_low: phases.iter().map(|p| p.est_seconds_low).sum(),
+        est_seconds_high: phases.iter().map(|p| p.est_seconds_high).sum(),
+        est_peak_memory_mb: memory_mb,
+        phases,
+        calibration_ms,
+    }
+}
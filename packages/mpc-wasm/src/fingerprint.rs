@@ -0,0 +1,53 @@
+//! Short, log-safe identifiers for a key and its shares.
+//!
+//! Hosts used to invent their own wallet ids and share labels — sometimes
+//! by hashing the whole share blob into a log line, sometimes by logging a
+//! prefix of the blob itself. Both values here are public: they identify
+//! material without revealing any of it.
+//!
+//! - wallet id: the first 16 hex characters of `sha256(public_key)` over
+//!   the 33-byte compressed SEC1 key.
+//! - share fingerprint: the first 16 hex characters of `sha256(blob)` over
+//!   the serialised CoreKeyShare exactly as `run_dkg` returns it (headered
+//!   JSON). Re-encoding a share changes its fingerprint; attaching
+//!   metadata (`share_metadata`) doesn't.
+//!
+//! Both formats are relied on by hosts to match records across runs; they
+//! must not change.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use sha2::{Digest, Sha256};
+
+use crate::share_metadata;
+
+/// Hex characters kept from the digest (64 bits).
+const ID_LEN: usize = 16;
+
+/// Wallet id of a 33-byte compressed public key. Fails with
+/// `INVALID_PUBLIC_KEY` on any other length; recode uncompressed keys first.
+pub fn wallet_id(compressed_public_key: &[u8]) -> Result<String, String> {
+    if compressed_public_key.len() != 33 {
+        return Err(format!(
+            "INVALID_PUBLIC_KEY: wallet ids are derived from the 33-byte compressed key, got {} bytes",
+            compressed_public_key.len()
+        ));
+    }
+    Ok(short_digest(compressed_public_key))
+}
+
+/// Fingerprint of a serialised core share; a `share_metadata` envelope
+/// around it doesn't count.
+pub fn share_fingerprint(core_share: &[u8]) -> String {
+    let blob = share_metadata::unwrap(core_share).map_or(core_share, |(_, blob)| blob);
+    short_digest(blob)
+}
+
+fn short_digest(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    let mut out = String::with_capacity(ID_LEN);
+    for b in &digest[..ID_LEN / 2] {
+        out.push_str(&format!("{b:02x}"));
+    }
+    out
+}
@@ -0,0 +1,122 @@
+//! Vendored fixtures (`fixtures` feature): the 2-of-3 secp256k1 key and
+//! signing transcript of the test vectors, embedded at build time so the
+//! SDK, relay and e2e suites share one key instead of each minting its own.
+//!
+//! The files under `fixtures/` are native-gen `gen-vectors` output: a
+//! trusted-dealer key from [`test_vectors::SEED`] and a signing of
+//! [`test_vectors::MESSAGE_HASH`] by [`test_vectors::SIGNERS`]. Reruns are
+//! byte-identical, so refreshing them after a format change is
+//!
+//! ```text
+//! cargo run --manifest-path native-gen/Cargo.toml --features insecure-dev -- gen-vectors --out fixtures
+//! ```
+//!
+//! from `packages/mpc-wasm`, and `verify_vectors` replays the result under
+//! the current build; `cargo test --features fixtures,insecure-dev` fails
+//! while the files differ from what this build would write. A known seed is
+//! a known key: never enable the feature for a production build.
+
+use base64::Engine;
+
+use crate::hex_codec;
+use crate::key_material;
+use crate::test_vectors::{self, KeyVector, SignVector};
+
+const KEY_JSON: &[u8] = include_bytes!("../fixtures/key.json");
+const SIGN_JSON: &[u8] = include_bytes!("../fixtures/sign-reliable.json");
+
+/// The vendored key, decoded.
+pub struct FixtureKey {
+    /// One per party, in index order
+    pub shares: Vec<FixtureShare>,
+    /// Compressed SEC1
+    pub public_key: Vec<u8>,
+    /// EIP-55 Ethereum address of `public_key`
+    pub address: String,
+}
+
+/// One party's blobs, as `run_dkg` returns them.
+pub struct FixtureShare {
+    pub index: u16,
+    pub core_share: Vec<u8>,
+    pub aux_info: Vec<u8>,
+}
+
+/// `fixtures/key.json`.
+pub fn key_vector() -> Result<KeyVector, String> {
+    serde_json::from_slice(KEY_JSON).map_err(|e| format!("VECTOR_INVALID: fixtures/key.json: {e}"))
+}
+
+/// `fixtures/sign-reliable.json`: the signature and the transcript that
+/// produced it.
+pub fn signature() -> Result<SignVector, String> {
+    serde_json::from_slice(SIGN_JSON).map_err(|e| format!("VECTOR_INVALID: fixtures/sign-reliable.json: {e}"))
+}
+
+/// The 2-of-3 key.
+pub fn two_of_three() -> Result<FixtureKey, String> {
+    let key = key_vector()?;
+    if (key.n, key.t) != (test_vectors::N, test_vectors::T) {
+        return Err(format!(
+            "VECTOR_INVALID: fixtures/key.json is {}-of-{}, expected {}-of-{}",
+            key.t,
+            key.n,
+            test_vectors::T,
+            test_vectors::N
+        ));
+    }
+    let public_key = hex_codec::decode(&key.public_key, "fixtures/key.json public_key")
+        .map_err(|e| format!("VECTOR_INVALID: {e}"))?;
+    let base64 = |index: u16, field: &str, text: &str| {
+        base64::engine::general_purpose::STANDARD
+            .decode(text)
+            .map_err(|e| format!("VECTOR_INVALID: fixtures/key.json: share {index} {field}: {e}"))
+    };
+    let shares = key
+        .shares
+        .iter()
+        .map(|share| {
+            Ok(FixtureShare {
+                index: share.index,
+                core_share: base64(share.index, "core_share", &share.core_share)?,
+                aux_info: base64(share.index, "aux_info", &share.aux_info)?,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(FixtureKey {
+        shares,
+        address: key_material::eth_address(&public_key)?,
+        public_key,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_of_three_decodes_every_share() {
+        let key = two_of_three().unwrap();
+        let indices: Vec<u16> = key.shares.iter().map(|share| share.index).collect();
+        assert_eq!(indices, [0, 1, 2]);
+        assert_eq!(key.public_key.len(), 33);
+        assert_eq!(key.address, key_material::eth_address(&key.public_key).unwrap());
+    }
+
+    #[test]
+    fn signature_is_by_the_vector_signers() {
+        let sig = signature().unwrap();
+        assert!(sig.reliable_broadcast);
+        assert_eq!(sig.signers, test_vectors::SIGNERS);
+        assert_eq!(sig.message_hash, hex_codec::encode(&test_vectors::MESSAGE_HASH));
+    }
+
+    /// The vendored files are what `gen-vectors` writes under this build.
+    #[cfg(feature = "insecure-dev")]
+    #[test]
+    fn vendored_files_match_a_regeneration() {
+        let key_shares = test_vectors::dealer_key_shares().unwrap();
+        assert_eq!(key_vector().unwrap(), test_vectors::key_vector(&key_shares).unwrap());
+        assert_eq!(signature().unwrap(), test_vectors::sign_vector(&key_shares, true).unwrap());
+    }
+}
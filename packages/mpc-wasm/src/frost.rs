@@ -0,0 +1,273 @@
+//! Threshold Schnorr (FROST) via `givre`, BIP-340 ciphersuite.
+//!
+//! DKG runs all parties locally like the ECDSA `run_dkg`. Signing sessions
+//! are registered with the `sign` module's session store and speak the same
+//! `WasmSignMessage` envelope, so the relay layer doesn't care which
+//! protocol a session runs.
+//!
+//! The DKG half is gated on the `dkg` feature, the signing half on
+//! `signing`.
+//!
+//! FROST key shares are always written with a `share_format` header of kind
+//! `FrostKeyShare`; the ECDSA entry points reject them and vice versa.
+
+use generic_ec::coords::HasAffineX;
+use generic_ec::Point;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "dkg")]
+use serde_bytes::ByteBuf;
+use tsify::Tsify;
+#[cfg(feature = "signing")]
+use zeroize::Zeroize;
+
+use cggmp24::supported_curves::Secp256k1;
+#[cfg(feature = "signing")]
+use givre::ciphersuite::Bip340;
+
+#[cfg(feature = "signing")]
+use crate::binding;
+#[cfg(feature = "signing")]
+use crate::limits::LimitOverrides;
+#[cfg(feature = "dkg")]
+use crate::rng::MixedRng;
+#[cfg(feature = "dkg")]
+use crate::share_format::CurveId;
+use crate::share_format::{self, BlobKind, Encoding};
+#[cfg(feature = "signing")]
+use crate::share_metadata;
+#[cfg(feature = "signing")]
+use crate::sign::{self, CreateSessionResult, Leaked, OnMisrouted, Protocol, SessionRecipe, SignSession, SmWrapper};
+#[cfg(feature = "signing")]
+use crate::signature;
+#[cfg(feature = "dkg")]
+use crate::simulate;
+#[cfg(feature = "signing")]
+use crate::types::SignatureResult;
+
+/// Output of [`run_dkg`].
+#[cfg(feature = "dkg")]
+#[derive(Serialize, Deserialize, Tsify)]
+pub struct FrostDkgResult {
+    /// Serialised givre KeyShare per party (headered, kind `frost_key_share`)
+    #[tsify(type = "Uint8Array[]")]
+    pub shares: Vec<ByteBuf>,
+    /// Shared public key (33 bytes, compressed)
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
+    pub public_key: Vec<u8>,
+    /// BIP-340 x-only public key (32 bytes)
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
+    pub x_only_public_key: Vec<u8>,
+}
+
+/// Per-session options for FROST signing (all fields optional).
+#[cfg(feature = "signing")]
+#[derive(Serialize, Deserialize, Default, Clone, Tsify)]
+#[serde(default)]
+pub struct FrostSignOptions {
+    /// Skip BIP-340 verification of the final signature.
+    pub skip_verify: bool,
+    /// Overrides of the configured message limits for this session.
+    pub limits: LimitOverrides,
+    /// Shared secret for message authentication (see `SignOptions`).
+    pub auth_secret: Option<String>,
+    /// Messages not for this party (see `SignOptions`).
+    pub on_misrouted: OnMisrouted,
+}
+
+/// Inputs of a FROST session (see [`create_session`]); the FROST half of
+/// `sign::SessionRecipe`.
+#[cfg(feature = "signing")]
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct FrostRecipe {
+    key_share: Vec<u8>,
+    message: Vec<u8>,
+    party_index: u16,
+    signers: Vec<u16>,
+    pub(crate) options: FrostSignOptions,
+    /// Seed of the session's rng
+    seed: [u8; 32],
+}
+
+#[cfg(feature = "signing")]
+impl FrostRecipe {
+    pub(crate) fn key_share_len(&self) -> usize {
+        self.key_share.len()
+    }
+
+    pub(crate) fn signer_count(&self) -> usize {
+        self.signers.len()
+    }
+
+    pub(crate) fn wipe(&mut self) {
+        self.key_share.zeroize();
+        self.seed.zeroize();
+        self.options.auth_secret.zeroize();
+    }
+}
+
+#[cfg(feature = "signing")]
+impl Drop for FrostRecipe {
+    fn drop(&mut self) {
+        self.wipe();
+    }
+}
+
+/// Run a complete FROST DKG for `n` parties with threshold `t`.
+#[cfg(feature = "dkg")]
+pub fn run_dkg(eid_bytes: &[u8], n: u16, threshold: u16) -> Result<FrostDkgResult, String> {
+    let mut parties = Vec::new();
+    for i in 0..n {
+        let eid = givre::keygen::ExecutionId::new(eid_bytes);
+        parties.push(round_based::state_machine::wrap_protocol(
+            move |party| async move {
+                let mut rng = MixedRng;
+                givre::keygen::<Secp256k1>(eid, i, n)
+                    .set_threshold(threshold)
+                    .start(&mut rng, party)
+                    .await
+            },
+        ));
+    }
+
+    let results = simulate::run(parties).map_err(|e| format!("frost keygen failed: {e}"))?;
+
+    let mut key_shares = Vec::new();
+    for (i, result) in results.into_iter().enumerate() {
+        let share = result.map_err(|e| format!("frost keygen party {i} failed: {e:?}"))?;
+        key_shares.push(share);
+    }
+
+    let pk = Point::from(key_shares[0].shared_public_key());
+    let x_only = pk
+        .x()
+        .ok_or("frost keygen produced the point at infinity")?
+        .as_be_bytes()
+        .to_vec();
+
+    let shares = key_shares
+        .iter()
+        .enumerate()
+        .map(|(i, share)| {
+            share_format::serialize(BlobKind::FrostKeyShare, Some(CurveId::Secp256k1), None, share)
+                .map(ByteBuf::from)
+                .map_err(|e| format!("party {i}: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(FrostDkgResult {
+        shares,
+        public_key: pk.to_bytes(true).as_bytes().to_vec(),
+        x_only_public_key: x_only,
+    })
+}
+
+/// Create a FROST signing session for one party.
+///
+/// `message` is signed as-is (BIP-340 takes the message, not a prehash).
+/// `signers` are keygen indices, with the same meaning as ECDSA's
+/// `parties_at_keygen`.
+#[cfg(feature = "signing")]
+pub fn create_session(
+    key_share_bytes: &[u8],
+    message: &[u8],
+    party_index: u16,
+    signers: &[u16],
+    options: &FrostSignOptions,
+) -> Result<CreateSessionResult, String> {
+    let recipe = FrostRecipe {
+        key_share: key_share_bytes.to_vec(),
+        message: message.to_vec(),
+        party_index,
+        signers: signers.to_vec(),
+        options: options.clone(),
+        seed: sign::fresh_seed()?,
+    };
+    sign::register_session(|| build_session(&recipe))
+}
+
+/// Build a FROST session's state machine from its recipe.
+#[cfg(feature = "signing")]
+pub(crate) fn build_session(recipe: &FrostRecipe) -> Result<SignSession, String> {
+    let party_index = recipe.party_index;
+    let signers = recipe.signers.as_slice();
+    let message = recipe.message.as_slice();
+    share_metadata::check_environment(&recipe.key_share)?;
+    let (_, payload) = share_format::split_expect(&recipe.key_share, BlobKind::FrostKeyShare)?;
+    let key_share: givre::KeyShare<Secp256k1> =
+        share_format::deserialize(BlobKind::FrostKeyShare, payload)?;
+    let public_key = Point::from(key_share.shared_public_key());
+
+    let position = signers
+        .iter()
+        .position(|&p| p == party_index)
+        .ok_or_else(|| format!("party_index {party_index} not found in signers {signers:?}"))?
+        as u16;
+
+    // Leak everything the state machine borrows for 'static (reclaimed on Drop)
+    let key_share = Leaked::new(key_share);
+    let signers_owned = Leaked::new(signers.to_vec());
+    let message_owned = Leaked::new(message.to_vec());
+
+    // SAFETY: the handles live in the session next to `sm` and are dropped
+    // after it (see `SignSession::drop`).
+    let (key_share_ref, signers_ref, message_ref) = unsafe {
+        (
+            key_share.as_static(),
+            signers_owned.as_static().as_slice(),
+            message_owned.as_static().as_slice(),
+        )
+    };
+
+    let seed = recipe.seed;
+    let sm = round_based::state_machine::wrap_protocol(move |party| async move {
+        let mut rng = sign::session_rng(seed);
+        givre::signing::<Bip340>(position, key_share_ref, signers_ref, message_ref)
+            .sign(&mut rng, party)
+            .await
+    });
+
+    let verify = !recipe.options.skip_verify;
+    // Wire signature is BIP-340's `R.x || s`
+    let dyn_sm = SmWrapper::boxed(sm, Encoding::Json, move |result| {
+        let sig = result.map_err(|e| format!("frost signing protocol error: {e:?}"))?;
+        let r = sig.r.x().ok_or("frost signing produced R at infinity")?;
+        let mut sig_bytes = r.as_be_bytes().to_vec();
+        sig_bytes.extend_from_slice(sig.z.to_be_bytes().as_bytes());
+
+        if verify {
+            signature::ensure_valid_bip340(&public_key, message_ref, &sig_bytes)?;
+        }
+
+        Ok(SignatureResult {
+            r: sig_bytes[..32].to_vec(),
+            s: sig_bytes[32..].to_vec(),
+            normalized: false,
+            v_eip155: None,
+            recovered_pubkey: None,
+        })
+    });
+
+    SignSession::new(
+        dyn_sm,
+        party_index,
+        signers.to_vec(),
+        vec![
+            Box::new(key_share),
+            Box::new(signers_owned),
+            Box::new(message_owned),
+        ],
+        SessionRecipe::Frost(recipe.clone()),
+        binding::session_binding(&[], message, signers),
+    )
+}
+
+/// Process a round of incoming messages for a FROST session.
+#[cfg(feature = "signing")]
+pub fn process_round(
+    session_id: &str,
+    incoming: &[sign::WasmSignMessage],
+) -> Result<sign::ProcessRoundResult, String> {
+    sign::process_round_for(Protocol::Frost, session_id, incoming, None)
+}
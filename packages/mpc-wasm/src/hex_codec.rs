@@ -0,0 +1,47 @@
+//! Hex encoding and decoding, without pulling in the hex crate.
+//!
+//! Encoding is lowercase; decoding takes either case and an optional
+//! `0x` / `0X` prefix, and names the offending input in its
+//! `INVALID_HEX` error.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use std::fmt::Write;
+
+/// Lowercase hex of `bytes`.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(out, "{b:02x}");
+    }
+    out
+}
+
+/// Decode `hex`; `name` says what it is for error messages.
+pub fn decode(hex: &str, name: &str) -> Result<Vec<u8>, String> {
+    let digits = hex
+        .strip_prefix("0x")
+        .or_else(|| hex.strip_prefix("0X"))
+        .unwrap_or(hex);
+    let offset = hex.len() - digits.len();
+    if digits.len() % 2 != 0 {
+        return Err(format!(
+            "INVALID_HEX: {name} has an odd number of hex digits ({})",
+            digits.len()
+        ));
+    }
+    let nibble = |c: u8, at: usize| {
+        (c as char).to_digit(16).map(|d| d as u8).ok_or_else(|| {
+            format!("INVALID_HEX: {name} has invalid hex character {:?} at position {at}", c as char)
+        })
+    };
+    digits
+        .as_bytes()
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            let at = offset + 2 * i;
+            Ok(nibble(pair[0], at)? << 4 | nibble(pair[1], at + 1)?)
+        })
+        .collect()
+}
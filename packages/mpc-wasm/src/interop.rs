@@ -0,0 +1,285 @@
+//! Key shares from other threshold-ECDSA stacks.
+//!
+//! Supported: Binance tss-lib (GG18/GG20), from the `LocalPartySaveData`
+//! JSON its ECDSA keygen writes. A tss-lib key is a Shamir sharing like a
+//! CGGMP24 one, so the core share converts field by field; the Paillier and
+//! ring-Pedersen material is GG20's and can't be reused. Migrating a key is:
+//! convert every party's save data with [`import_tsslib_share`], then mint
+//! aux info for the converted core shares with `run_aux_refresh` (native:
+//! `refresh-aux`). The shared public key — and every address derived from
+//! it — stays the same.
+//!
+//! Field mapping, tss-lib → `IncompleteKeyShare`:
+//!
+//! | tss-lib    | IncompleteKeyShare                                    |
+//! |------------|-------------------------------------------------------|
+//! | `Xi`       | `x`                                                   |
+//! | `ShareID`  | `i`: the position of `ShareID` in `Ks`                |
+//! | `Ks`       | `vss_setup.I`, in the same order                      |
+//! | `BigXj`    | `public_shares`                                       |
+//! | `ECDSAPub` | `shared_public_key`                                   |
+//! | (none)     | `vss_setup.min_signers`: the caller's `threshold`     |
+//!
+//! The save data doesn't record the threshold; pass tss-lib's `threshold`
+//! plus one (the number of signers it needs). `PaillierSK`, `NTildei`,
+//! `H1i`, `H2i`, `Alpha`, `Beta`, `P`, `Q`, `NTildej`, `H1j`, `H2j` and
+//! `PaillierPKs` are dropped.
+//!
+//! Before a share is returned, the public shares are checked to lie on one
+//! polynomial of degree `threshold - 1` (`THRESHOLD_MISMATCH`) whose value
+//! at zero is `ECDSAPub` (`PUBLIC_KEY_MISMATCH`), and `Xi` against the
+//! party's own public share (`SHARE_MISMATCH`). Save data on another curve,
+//! EdDSA keygen output included, fails with `UNSUPPORTED_CURVE`; anything
+//! unreadable with `INVALID_TSSLIB_SHARE`.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use generic_ec::curves::Secp256k1;
+use generic_ec::serde::CurveName;
+use generic_ec::{NonZero, Point, Scalar, SecretScalar};
+use key_share::{DirtyCoreKeyShare, DirtyKeyInfo, VssSetup};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::share_format::{self, BlobKind, CurveId};
+
+/// tss-lib's name for the only curve imported.
+const TSSLIB_SECP256K1: &str = "secp256k1";
+
+/// A converted share.
+pub struct ImportedShare {
+    /// Serialised CoreKeyShare, as `run_dkg` returns it
+    pub core_share: Vec<u8>,
+    /// Keygen index of the party (its position in tss-lib's `Ks`)
+    pub party_index: u16,
+    pub n: u16,
+    /// 33-byte compressed shared public key
+    pub public_key: Vec<u8>,
+}
+
+/// The parts of `LocalPartySaveData` that are read. Big integers are JSON
+/// numbers (Go's `big.Int`) too long for f64, so they stay raw.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SaveData {
+    xi: Option<Box<RawValue>>,
+    #[serde(rename = "ShareID")]
+    share_id: Option<Box<RawValue>>,
+    ks: Option<Vec<Box<RawValue>>>,
+    big_xj: Option<Vec<TsslibPoint>>,
+    #[serde(rename = "ECDSAPub")]
+    ecdsa_pub: Option<TsslibPoint>,
+    #[serde(rename = "EDDSAPub")]
+    eddsa_pub: Option<serde::de::IgnoredAny>,
+}
+
+/// tss-lib `ECPoint`; releases before v2 leave out `Curve` (always secp256k1).
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TsslibPoint {
+    curve: Option<String>,
+    coords: [Box<RawValue>; 2],
+}
+
+/// Convert one party's tss-lib ECDSA save data into a CoreKeyShare of a key
+/// `threshold` parties sign with.
+pub fn import_tsslib_share(save_data: &[u8], threshold: u16) -> Result<ImportedShare, String> {
+    let data: SaveData = serde_json::from_slice(save_data)
+        .map_err(|e| format!("INVALID_TSSLIB_SHARE: not tss-lib save data: {e}"))?;
+    if data.eddsa_pub.is_some() {
+        return Err("UNSUPPORTED_CURVE: tss-lib EdDSA (ed25519) keys can't be imported".into());
+    }
+    let (Some(xi), Some(share_id), Some(ks), Some(big_xj), Some(ecdsa_pub)) =
+        (data.xi, data.share_id, data.ks, data.big_xj, data.ecdsa_pub)
+    else {
+        return Err(
+            "INVALID_TSSLIB_SHARE: save data lacks Xi, ShareID, Ks, BigXj or ECDSAPub (not from ECDSA keygen?)"
+                .into(),
+        );
+    };
+
+    let n = u16::try_from(ks.len())
+        .ok()
+        .filter(|&n| n >= 2)
+        .ok_or_else(|| format!("INVALID_TSSLIB_SHARE: {} parties in Ks, expected 2 to 65535", ks.len()))?;
+    if threshold < 2 || threshold > n {
+        return Err(format!(
+            "INVALID_THRESHOLD: threshold must be in [2, {n}], got {threshold} (pass tss-lib's threshold + 1)"
+        ));
+    }
+    if big_xj.len() != ks.len() {
+        return Err(format!(
+            "INVALID_TSSLIB_SHARE: {} public shares in BigXj for {n} parties in Ks",
+            big_xj.len()
+        ));
+    }
+
+    let ids = ks
+        .iter()
+        .map(|k| nonzero_scalar(k, "Ks"))
+        .collect::<Result<Vec<_>, _>>()?;
+    for (j, id) in ids.iter().enumerate() {
+        if ids[..j].contains(id) {
+            return Err("INVALID_TSSLIB_SHARE: Ks repeats a share id".into());
+        }
+    }
+    let own_id = nonzero_scalar(&share_id, "ShareID")?;
+    let i = ids
+        .iter()
+        .position(|id| *id == own_id)
+        .ok_or("INVALID_TSSLIB_SHARE: ShareID is not in Ks")? as u16;
+
+    let shared_public_key = point(&ecdsa_pub, "ECDSAPub")?;
+    let public_shares = big_xj
+        .iter()
+        .map(|p| point(p, "BigXj"))
+        .collect::<Result<Vec<_>, _>>()?;
+    check_polynomial(&ids, &public_shares, &shared_public_key, threshold)?;
+
+    let mut x = secret_scalar(&xi)?;
+    if Point::generator() * &x != *public_shares[usize::from(i)] {
+        return Err(format!("SHARE_MISMATCH: Xi does not match party {i}'s public share in BigXj"));
+    }
+    let x = NonZero::from_secret_scalar(SecretScalar::new(&mut x))
+        .ok_or("INVALID_TSSLIB_SHARE: Xi is zero")?;
+    let public_key = shared_public_key.to_bytes(true).as_bytes().to_vec();
+
+    let dirty = DirtyCoreKeyShare {
+        i,
+        key_info: DirtyKeyInfo {
+            curve: CurveName::<Secp256k1>::new(),
+            shared_public_key,
+            public_shares,
+            vss_setup: Some(VssSetup {
+                min_signers: threshold,
+                I: ids,
+            }),
+        },
+        x,
+    };
+    // Round-trip through the validating deserialiser, so a share that
+    // cggmp24 would refuse later is refused here
+    let bytes = share_format::serialize(BlobKind::CoreShare, Some(CurveId::Secp256k1), None, &dirty)?;
+    let (_, payload) = share_format::split_expect(&bytes, BlobKind::CoreShare)?;
+    share_format::deserialize::<cggmp24::IncompleteKeyShare<Secp256k1>>(BlobKind::CoreShare, payload)
+        .map_err(|e| format!("INVALID_TSSLIB_SHARE: converted share is invalid: {e}"))?;
+
+    Ok(ImportedShare {
+        core_share: bytes,
+        party_index: i,
+        n,
+        public_key,
+    })
+}
+
+/// Every public share must be the value at its id of the polynomial the
+/// first `threshold` of them define, and that polynomial's value at zero
+/// the shared public key.
+fn check_polynomial(
+    ids: &[NonZero<Scalar<Secp256k1>>],
+    public_shares: &[NonZero<Point<Secp256k1>>],
+    shared_public_key: &NonZero<Point<Secp256k1>>,
+    threshold: u16,
+) -> Result<(), String> {
+    let t = usize::from(threshold);
+    let (base_ids, base_shares) = (&ids[..t], &public_shares[..t]);
+    let interpolate = |at: Scalar<Secp256k1>| {
+        base_shares
+            .iter()
+            .enumerate()
+            .fold(Point::<Secp256k1>::zero(), |acc, (j, share)| {
+                acc + **share * lagrange(base_ids, j, at)
+            })
+    };
+
+    for (id, share) in ids.iter().zip(public_shares).skip(t) {
+        if interpolate(**id) != **share {
+            return Err(format!(
+                "THRESHOLD_MISMATCH: the public shares don't fit a {threshold}-of-{} key; check the threshold",
+                ids.len()
+            ));
+        }
+    }
+    if interpolate(Scalar::zero()) != **shared_public_key {
+        return Err("PUBLIC_KEY_MISMATCH: the public shares don't interpolate to ECDSAPub".into());
+    }
+    Ok(())
+}
+
+/// λ_j(at) = Π_{m≠j} (at − x_m) / (x_j − x_m); ids are distinct, so the
+/// denominator is invertible.
+fn lagrange(ids: &[NonZero<Scalar<Secp256k1>>], j: usize, at: Scalar<Secp256k1>) -> Scalar<Secp256k1> {
+    let x_j = *ids[j];
+    let mut num = Scalar::<Secp256k1>::one();
+    let mut den = Scalar::<Secp256k1>::one();
+    for (m, x_m) in ids.iter().enumerate() {
+        if m == j {
+            continue;
+        }
+        num = num * (at - **x_m);
+        den = den * (x_j - **x_m);
+    }
+    num * den.invert().expect("distinct ids")
+}
+
+fn nonzero_scalar(raw: &RawValue, field: &str) -> Result<NonZero<Scalar<Secp256k1>>, String> {
+    let bytes = big_int(raw, field)?;
+    Scalar::from_be_bytes(bytes)
+        .ok()
+        .and_then(NonZero::from_scalar)
+        .ok_or_else(|| format!("INVALID_TSSLIB_SHARE: {field} is not a nonzero secp256k1 scalar"))
+}
+
+fn secret_scalar(raw: &RawValue) -> Result<Scalar<Secp256k1>, String> {
+    let mut bytes = big_int(raw, "Xi")?;
+    let scalar = Scalar::from_be_bytes(bytes)
+        .map_err(|_| "INVALID_TSSLIB_SHARE: Xi is not a secp256k1 scalar".to_string());
+    bytes.zeroize();
+    scalar
+}
+
+fn point(p: &TsslibPoint, field: &str) -> Result<NonZero<Point<Secp256k1>>, String> {
+    let curve = p.curve.as_deref().unwrap_or(TSSLIB_SECP256K1);
+    if curve != TSSLIB_SECP256K1 {
+        return Err(format!(
+            "UNSUPPORTED_CURVE: {field} is on {curve}; only secp256k1 keys can be imported"
+        ));
+    }
+    let mut sec1 = [0u8; 65];
+    sec1[0] = 0x04;
+    sec1[1..33].copy_from_slice(&big_int(&p.coords[0], field)?);
+    sec1[33..].copy_from_slice(&big_int(&p.coords[1], field)?);
+    Point::from_bytes(sec1)
+        .ok()
+        .and_then(NonZero::from_point)
+        .ok_or_else(|| format!("INVALID_TSSLIB_SHARE: {field} is not a secp256k1 point"))
+}
+
+/// A non-negative decimal big integer (a JSON number, or a string holding
+/// one) as 32 big-endian bytes.
+fn big_int(raw: &RawValue, field: &str) -> Result<[u8; 32], String> {
+    let text = raw.get();
+    let digits = text
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .unwrap_or(text);
+    let invalid = || format!("INVALID_TSSLIB_SHARE: {field} is not a 256-bit unsigned integer");
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let mut out = Zeroizing::new([0u8; 32]);
+    for digit in digits.bytes() {
+        let mut carry = u32::from(digit - b'0');
+        for byte in out.iter_mut().rev() {
+            let v = u32::from(*byte) * 10 + carry;
+            *byte = v as u8;
+            carry = v >> 8;
+        }
+        if carry != 0 {
+            return Err(invalid());
+        }
+    }
+    Ok(*out)
+}
@@ -0,0 +1,205 @@
+//! Full private-key export from threshold core shares.
+//!
+//! This deliberately defeats the threshold model: it exists so a customer
+//! can leave the platform with a plain key. Every entry point requires the
+//! caller to pass [`EXPORT_CONFIRMATION`] verbatim.
+//!
+//! The secret is Lagrange-interpolated at zero over ≥ threshold shares and
+//! checked against the shared public key before it is returned. Scalar
+//! intermediates are zeroized; the accumulator is a `SecretScalar`.
+//!
+//! This module has no wasm-bindgen dependency — native-gen compiles it too.
+
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use zeroize::{Zeroize, Zeroizing};
+
+use cggmp24::key_share::AnyKeyShare;
+
+use crate::share_format::{self, with_curve, BlobKind, CurveId};
+
+/// The exact string callers must pass to confirm a key export.
+pub const EXPORT_CONFIRMATION: &str = "EXPORT_PRIVATE_KEY";
+
+/// Result of [`reconstruct_private_key`].
+pub struct ExportedKey {
+    /// 32-byte big-endian private key
+    pub private_key: Zeroizing<Vec<u8>>,
+    /// 33-byte compressed public key it corresponds to
+    pub public_key: Vec<u8>,
+    pub curve: CurveId,
+}
+
+/// Reconstruct the full private key from serialised CoreKeyShares.
+///
+/// Fails with `EXPORT_NOT_CONFIRMED` unless `confirm` is
+/// [`EXPORT_CONFIRMATION`], `INSUFFICIENT_SHARES` below the threshold, and
+/// `SHARE_MISMATCH` if the shares belong to different keys or repeat a party.
+pub fn reconstruct_private_key(core_shares: &[impl AsRef<[u8]>], confirm: &str) -> Result<ExportedKey, String> {
+    if confirm != EXPORT_CONFIRMATION {
+        return Err(format!(
+            "EXPORT_NOT_CONFIRMED: pass confirm = {EXPORT_CONFIRMATION:?} to export the private key"
+        ));
+    }
+    let first = core_shares
+        .first()
+        .ok_or("INSUFFICIENT_SHARES: no core shares given")?;
+    let (header, _) = share_format::split_expect(first.as_ref(), BlobKind::CoreShare)?;
+    let curve = header.curve_or_default();
+
+    let mut payloads = Vec::with_capacity(core_shares.len());
+    for (i, bytes) in core_shares.iter().enumerate() {
+        let (header, payload) = share_format::split_expect(bytes.as_ref(), BlobKind::CoreShare)
+            .map_err(|e| format!("share {i}: {e}"))?;
+        share_format::ensure_curve(&header, Some(curve)).map_err(|e| format!("share {i}: {e}"))?;
+        payloads.push(payload);
+    }
+
+    with_curve!(curve, E => {
+        let shares = payloads
+            .iter()
+            .enumerate()
+            .map(|(i, payload)| {
+                share_format::deserialize::<cggmp24::IncompleteKeyShare<E>>(BlobKind::CoreShare, payload)
+                    .map_err(|e| format!("share {i}: {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let (secret, public_key) = interpolate::<E>(&shares)?;
+        Ok(ExportedKey {
+            private_key: Zeroizing::new(secret.as_ref().to_be_bytes().as_bytes().to_vec()),
+            public_key,
+            curve,
+        })
+    })
+}
+
+/// Lagrange-interpolate the shared secret and check it against the shared
+/// public key. Returns the secret and the compressed public key.
+fn interpolate<E: Curve>(
+    shares: &[cggmp24::IncompleteKeyShare<E>],
+) -> Result<(SecretScalar<E>, Vec<u8>), String> {
+    let first = &shares[0];
+    let public_key: Point<E> = first.shared_public_key().into();
+    let threshold = first.min_signers();
+    if shares.len() < usize::from(threshold) {
+        return Err(format!(
+            "INSUFFICIENT_SHARES: need at least {threshold} shares, got {}",
+            shares.len()
+        ));
+    }
+
+    let mut seen = Vec::with_capacity(shares.len());
+    for share in shares {
+        if Point::from(share.shared_public_key()) != public_key {
+            return Err("SHARE_MISMATCH: shares belong to different keys".into());
+        }
+        if seen.contains(&share.i) {
+            return Err(format!("SHARE_MISMATCH: party {} given twice", share.i));
+        }
+        seen.push(share.i);
+    }
+
+    // Evaluation points; an additive (n-of-n) key has none and just sums
+    let points: Option<Vec<NonZero<Scalar<E>>>> = first
+        .vss_setup
+        .as_ref()
+        .map(|vss| shares.iter().map(|s| vss.I[usize::from(s.i)]).collect());
+
+    let mut secret = SecretScalar::<E>::zero();
+    for (j, share) in shares.iter().enumerate() {
+        let lambda = match &points {
+            Some(points) => lagrange_at_zero(points, j)
+                .ok_or("SHARE_MISMATCH: duplicate evaluation points")?,
+            None => Scalar::one(),
+        };
+        let mut term = lambda * &share.x;
+        let mut sum = secret.as_ref() + &term;
+        term.zeroize();
+        secret = SecretScalar::new(&mut sum);
+    }
+
+    if Point::generator() * &secret != public_key {
+        return Err("RECONSTRUCTION_FAILED: interpolated key does not match the shared public key".into());
+    }
+
+    Ok((secret, public_key.to_bytes(true).as_bytes().to_vec()))
+}
+
+/// λ_j(0) = Π_{m≠j} x_m / (x_m − x_j)
+fn lagrange_at_zero<E: Curve>(points: &[NonZero<Scalar<E>>], j: usize) -> Option<Scalar<E>> {
+    let x_j = *points[j];
+    let mut num = Scalar::<E>::one();
+    let mut den = Scalar::<E>::one();
+    for (m, x_m) in points.iter().enumerate() {
+        if m == j {
+            continue;
+        }
+        num = num * **x_m;
+        den = den * (**x_m - x_j);
+    }
+    Some(num * den.invert()?)
+}
+
+#[cfg(all(test, feature = "insecure-dev"))]
+mod tests {
+    use cggmp24::security_level::SecurityLevel128;
+    use cggmp24::supported_curves::Secp256k1;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+
+    /// The core shares of a 2-of-3 dealer key, as blobs, and its public key.
+    fn two_of_three(seed: u64) -> (Vec<Vec<u8>>, Point<Secp256k1>) {
+        let shares = cggmp24::trusted_dealer::builder::<Secp256k1, SecurityLevel128>(3)
+            .set_threshold(Some(2))
+            .generate_core_shares(&mut ChaCha20Rng::seed_from_u64(seed))
+            .unwrap();
+        let public_key = shares[0].shared_public_key().into();
+        let blobs = shares
+            .iter()
+            .map(|share| share_format::serialize(BlobKind::CoreShare, Some(CurveId::Secp256k1), None, share).unwrap())
+            .collect();
+        (blobs, public_key)
+    }
+
+    fn export(shares: &[Vec<u8>], signers: &[usize]) -> Result<ExportedKey, String> {
+        let picked: Vec<Vec<u8>> = signers.iter().map(|&i| shares[i].clone()).collect();
+        reconstruct_private_key(&picked, EXPORT_CONFIRMATION)
+    }
+
+    #[test]
+    fn every_signer_set_exports_the_key_of_the_group_public_key() {
+        let (shares, public_key) = two_of_three(1);
+        let first = export(&shares, &[0, 1]).unwrap();
+        for signers in [&[1, 2][..], &[2, 0], &[0, 1, 2]] {
+            assert_eq!(export(&shares, signers).unwrap().private_key, first.private_key);
+        }
+        let secret = Scalar::<Secp256k1>::from_be_bytes(&first.private_key).unwrap();
+        assert_eq!(Point::generator() * secret, public_key);
+        assert_eq!(first.public_key, public_key.to_bytes(true).as_bytes());
+    }
+
+    #[test]
+    fn a_share_of_another_key_is_rejected() {
+        let (shares, _) = two_of_three(1);
+        let (others, _) = two_of_three(2);
+        let err = reconstruct_private_key(&[shares[0].clone(), others[1].clone()], EXPORT_CONFIRMATION)
+            .err()
+            .unwrap();
+        assert!(err.starts_with("SHARE_MISMATCH"), "{err}");
+    }
+
+    #[test]
+    fn a_party_given_twice_is_rejected() {
+        let (shares, _) = two_of_three(1);
+        let err = export(&shares, &[1, 1]).err().unwrap();
+        assert!(err.starts_with("SHARE_MISMATCH"), "{err}");
+    }
+
+    #[test]
+    fn fewer_shares_than_the_threshold_are_rejected() {
+        let (shares, _) = two_of_three(1);
+        let err = export(&shares, &[2]).err().unwrap();
+        assert!(err.starts_with("INSUFFICIENT_SHARES"), "{err}");
+    }
+}
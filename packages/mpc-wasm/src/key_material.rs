@@ -0,0 +1,593 @@
+//! Combining and inspecting serialised key material.
+//!
+//! Shared by the WASM exports (`combine_key_share`, `extract_public_key`)
+//! and native-gen's `verify` subcommand. No wasm-bindgen dependency.
+//!
+//! Public keys come out SEC1-compressed (33 bytes) unless a [`PointFormat`]
+//! asks for the 65-byte uncompressed form; keys coming in may be either.
+//!
+//! Combining checks the two halves against each other before cggmp24 sees
+//! them, so a stale blob is named in the error rather than hidden behind
+//! the library's generic one:
+//! - `AUX_PARTY_COUNT_MISMATCH`: core share and aux info are for different
+//!   party counts
+//! - `AUX_PARTY_MISMATCH`: same count, but the aux info was generated for
+//!   another party or in another ceremony
+//! - `PUBLIC_KEY_MISMATCH`: the core share isn't for the expected key
+//!
+//! [`aux_public_data`] / [`core_public_data`] read the public half of a
+//! blob for audit displays. Their result types have no field a secret could
+//! land in, so nothing secret can leak through them.
+//!
+//! [`check_signer_set`] vets a signing session's parties against the share
+//! before any state machine is built, so a set that can never finish fails
+//! at once instead of at the session timeout. [`signer_sets`] lists the
+//! sets a share could sign with, for a scheduler picking a quorum, and
+//! [`can_sign_with`] applies the same check to one set up front.
+
+use generic_ec::{Curve, Point};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use cggmp24::key_share::AnyKeyShare;
+use cggmp24::security_level::SecurityLevel;
+
+use crate::hex_codec;
+use crate::party_limits;
+use crate::share_format::{self, with_curve, with_security_level, BlobKind, CurveId, Encoding, Level};
+use crate::share_metadata;
+
+/// Encoding of a public key handed back to the caller.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PointFormat {
+    /// 33 bytes, `02`/`03` prefix
+    #[default]
+    Compressed,
+    /// 65 bytes, `04` prefix (Ethereum address derivation, HSM imports)
+    Uncompressed,
+    /// Both of the above
+    Both,
+}
+
+impl PointFormat {
+    /// Parse `"compressed"` / `"uncompressed"` / `"both"`; `None` is
+    /// compressed.
+    pub fn parse_opt(s: Option<&str>) -> Result<Self, String> {
+        match s {
+            None | Some("compressed") => Ok(PointFormat::Compressed),
+            Some("uncompressed") => Ok(PointFormat::Uncompressed),
+            Some("both") => Ok(PointFormat::Both),
+            Some(other) => Err(format!(
+                "INVALID_FORMAT: unknown public key format {other:?} \
+                 (expected compressed, uncompressed or both)"
+            )),
+        }
+    }
+}
+
+/// Re-encode a public key on `curve`, given in either SEC1 form, as
+/// compressed or uncompressed.
+pub fn recode_public_key(curve: CurveId, public_key: &[u8], compressed: bool) -> Result<Vec<u8>, String> {
+    if public_key.len() != 33 && public_key.len() != 65 {
+        return Err(format!(
+            "INVALID_PUBLIC_KEY: expected 33 or 65 bytes, got {}",
+            public_key.len()
+        ));
+    }
+    with_curve!(curve, E => {
+        let point = Point::<E>::from_bytes(public_key)
+            .map_err(|_| format!("INVALID_PUBLIC_KEY: not a valid {} point", curve.as_str()))?;
+        Ok(point.to_bytes(compressed).as_bytes().to_vec())
+    })
+}
+
+/// EIP-55 checksummed Ethereum address of a secp256k1 public key in
+/// either SEC1 form.
+pub fn eth_address(public_key: &[u8]) -> Result<String, String> {
+    use sha3::Keccak256;
+
+    let uncompressed = recode_public_key(CurveId::Secp256k1, public_key, false)?;
+    let hash = Keccak256::digest(&uncompressed[1..]);
+    let lower: String = hash[12..].iter().map(|b| format!("{b:02x}")).collect();
+    let checksum = Keccak256::digest(lower.as_bytes());
+    let mixed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (checksum[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if c.is_ascii_alphabetic() && nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    Ok(format!("0x{mixed}"))
+}
+
+/// Public parameters of an aux info blob.
+#[derive(Serialize, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct AuxPublicData {
+    pub security_level: String,
+    /// One entry per party, by keygen index
+    pub parties: Vec<AuxPartyPublic>,
+}
+
+/// One party's Paillier modulus and ring-Pedersen parameters. Fingerprints
+/// are the first 16 hex characters of `sha256` over the big-endian integer.
+#[derive(Serialize, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct AuxPartyPublic {
+    pub index: u16,
+    /// Bit length of the Paillier modulus `N`
+    pub modulus_bits: u64,
+    pub modulus_fingerprint: String,
+    pub s_fingerprint: String,
+    pub t_fingerprint: String,
+}
+
+/// Public parts of a core share (or of a full KeyShare's core).
+#[derive(Serialize, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct CorePublicData {
+    pub curve: String,
+    pub party_index: u16,
+    pub threshold: u16,
+    pub n: u16,
+    /// Every party's public share, compressed SEC1 hex, by keygen index
+    pub public_shares: Vec<String>,
+    /// Compressed SEC1 hex
+    pub public_key: String,
+}
+
+/// The public parameters of every party in a serialised AuxInfo (JSON or
+/// binary). The Paillier primes are never read out.
+pub fn aux_public_data(aux_info: &[u8]) -> Result<AuxPublicData, String> {
+    let (header, payload) = share_format::split_expect(aux_info, BlobKind::AuxInfo)?;
+    let level = header.level_or_default();
+    let fingerprint = |bytes: &[u8]| -> String { Sha256::digest(bytes)[..8].iter().map(|b| format!("{b:02x}")).collect() };
+    let parties = with_security_level!(level, L => {
+        let aux: cggmp24::key_share::AuxInfo<L> = share_format::deserialize(BlobKind::AuxInfo, payload)
+            .map_err(|e| format!("INVALID_SHARE: {e}"))?;
+        aux.parties
+            .iter()
+            .enumerate()
+            .map(|(index, party)| {
+                Ok(AuxPartyPublic {
+                    index: party_limits::index(index)?,
+                    modulus_bits: party.N.significant_bits(),
+                    modulus_fingerprint: fingerprint(&party.N.to_bytes_msf()),
+                    s_fingerprint: fingerprint(&party.s.to_bytes_msf()),
+                    t_fingerprint: fingerprint(&party.t.to_bytes_msf()),
+                })
+            })
+            .collect::<Result<_, String>>()?
+    });
+    Ok(AuxPublicData {
+        security_level: level.as_str().into(),
+        parties,
+    })
+}
+
+/// Index, threshold, party count, public shares and shared key of a
+/// serialised CoreKeyShare or KeyShare (JSON or binary). The secret share
+/// is never read out.
+pub fn core_public_data(share: &[u8]) -> Result<CorePublicData, String> {
+    let (header, payload) = share_format::split(share)?;
+    let curve = header.curve_or_default();
+    let invalid = |e: String| format!("INVALID_SHARE: {e}");
+    with_curve!(curve, E => match header.kind {
+        Some(BlobKind::KeyShare) => with_security_level!(header.level_or_default(), L => {
+            let ks: cggmp24::KeyShare<E, L> =
+                share_format::deserialize(BlobKind::KeyShare, payload).map_err(invalid)?;
+            public_core(curve, &ks.core)
+        }),
+        // Legacy blobs too: a KeyShare's JSON parses as its core
+        Some(BlobKind::CoreShare) | None => {
+            let iks: cggmp24::IncompleteKeyShare<E> =
+                share_format::deserialize(BlobKind::CoreShare, payload).map_err(invalid)?;
+            public_core(curve, &iks)
+        }
+        Some(other) => Err(format!(
+            "WRONG_BLOB_KIND: expected core_share or key_share, found {}",
+            other.as_str()
+        )),
+    })
+}
+
+fn public_core<E: Curve>(curve: CurveId, core: &key_share::DirtyCoreKeyShare<E>) -> Result<CorePublicData, String> {
+    let hex = |point: &Point<E>| hex_codec::encode(point.to_bytes(true).as_bytes());
+    let n = party_limits::index(core.public_shares.len())?;
+    Ok(CorePublicData {
+        curve: curve.as_str().into(),
+        party_index: core.i,
+        threshold: core.vss_setup.as_ref().map_or(n, |vss| vss.min_signers),
+        n,
+        public_shares: core.public_shares.iter().map(hex).collect(),
+        public_key: hex(&core.shared_public_key),
+    })
+}
+
+/// Check a signer set against a share with `n` parties and threshold
+/// `threshold`, returning `party_index`'s position in `parties`.
+///
+/// Fails with:
+/// - `TOO_FEW_SIGNERS`: fewer parties than the threshold
+/// - `PARTY_INDEX_OUT_OF_RANGE`: an index (or `party_index`) is `n` or more
+/// - `DUPLICATE_PARTY`: an index appears twice
+/// - `PARTY_NOT_IN_SIGNERS`: `party_index` isn't one of `parties`
+pub fn check_signer_set(n: u16, threshold: u16, party_index: u16, parties: &[u16]) -> Result<u16, String> {
+    if parties.len() < usize::from(threshold) {
+        return Err(format!(
+            "TOO_FEW_SIGNERS: the share needs {threshold} signers, got {} ({parties:?})",
+            parties.len()
+        ));
+    }
+    if let Some(&p) = parties.iter().chain([&party_index]).find(|&&p| p >= n) {
+        return Err(format!(
+            "PARTY_INDEX_OUT_OF_RANGE: party {p} is not below the share's n = {n}"
+        ));
+    }
+    if let Some((j, &p)) = parties.iter().enumerate().find(|&(j, p)| parties[..j].contains(p)) {
+        return Err(format!("DUPLICATE_PARTY: party {p} appears twice in {parties:?} (position {j})"));
+    }
+    let position = parties
+        .iter()
+        .position(|&p| p == party_index)
+        .ok_or_else(|| format!("PARTY_NOT_IN_SIGNERS: party_index {party_index} not found in parties {parties:?}"))?;
+    party_limits::index(position)
+}
+
+/// Signer sets [`signer_sets`] lists at most, unless told otherwise.
+pub const DEFAULT_MAX_SIGNER_SETS: u32 = 1024;
+
+/// Paging and the cap for [`signer_sets`]; every field optional.
+#[derive(Deserialize, Clone, Copy, Debug, Default)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[serde(default)]
+pub struct SignerSetOptions {
+    /// Sets to skip (default 0)
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub offset: Option<u32>,
+    /// Sets to return at most (default: all that remain)
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub limit: Option<u32>,
+    /// Refuse to enumerate more sets than this (default
+    /// [`DEFAULT_MAX_SIGNER_SETS`])
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub max_sets: Option<u32>,
+}
+
+/// One page of [`signer_sets`].
+#[derive(Serialize, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct SignerSets {
+    /// Keygen indices, ascending within each set; smallest sets first
+    pub sets: Vec<Vec<u16>>,
+    /// Sets there are in all
+    pub total: u32,
+    /// `offset` of the next page; `None` on the last one
+    pub next_offset: Option<u32>,
+}
+
+/// Every signer set the share `share` (a core share or KeyShare) can sign
+/// with out of `available`: each has at least the threshold's parties and
+/// includes the share's own index, which counts as available whether
+/// listed or not. Sets pass [`check_signer_set`] as a signing session
+/// would run it.
+///
+/// Fails with `PARTY_INDEX_OUT_OF_RANGE` for an index that is `n` or more,
+/// and `TOO_MANY_SIGNER_SETS` if there are more than `max_sets` in all.
+pub fn signer_sets(share: &[u8], available: &[u16], options: SignerSetOptions) -> Result<SignerSets, String> {
+    let core = core_public_data(share)?;
+    if let Some(&p) = available.iter().find(|&&p| p >= core.n) {
+        return Err(format!("PARTY_INDEX_OUT_OF_RANGE: party {p} is not below the share's n = {}", core.n));
+    }
+    let mut others: Vec<u16> = available.iter().copied().filter(|&p| p != core.party_index).collect();
+    others.sort_unstable();
+    others.dedup();
+
+    // Each set is the share's own index plus `size - 1` of the others
+    let smallest = usize::from(core.threshold.max(1));
+    let sizes = smallest..=others.len() + 1;
+    let total = sizes
+        .clone()
+        .map(|size| binomial(others.len(), size - 1))
+        .fold(0u64, u64::saturating_add);
+    let max_sets = options.max_sets.unwrap_or(DEFAULT_MAX_SIGNER_SETS);
+    if total > u64::from(max_sets) {
+        return Err(format!(
+            "TOO_MANY_SIGNER_SETS: {total} signer sets, more than the maximum of {max_sets};              list fewer available parties or raise max_sets"
+        ));
+    }
+    let total = total as u32;
+
+    let offset = options.offset.unwrap_or(0);
+    let limit = options.limit.unwrap_or(u32::MAX);
+    let mut sets = Vec::new();
+    let mut seen = 0u32;
+    for size in sizes {
+        for_each_combination(&others, size - 1, &mut |picked| {
+            if seen >= offset && (sets.len() as u32) < limit {
+                let mut set = picked.to_vec();
+                set.push(core.party_index);
+                set.sort_unstable();
+                sets.push(set);
+            }
+            seen += 1;
+        });
+    }
+    let end = offset.saturating_add(sets.len() as u32);
+    Ok(SignerSets {
+        sets,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+/// Whether a signing session for `share` (a core share or KeyShare) would
+/// accept `parties` as its signer set under [`check_signer_set`]. Fails
+/// only for a share that can't be read.
+pub fn can_sign_with(share: &[u8], parties: &[u16]) -> Result<bool, String> {
+    let core = core_public_data(share)?;
+    Ok(check_signer_set(core.n, core.threshold, core.party_index, parties).is_ok())
+}
+
+/// `n` choose `k`, saturating.
+fn binomial(n: usize, k: usize) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k) as u64;
+    let n = n as u64;
+    (0..k).fold(1u64, |c, i| c.saturating_mul(n - i) / (i + 1))
+}
+
+/// Call `f` with every `k`-element combination of `items`, in
+/// lexicographic order.
+fn for_each_combination(items: &[u16], k: usize, f: &mut impl FnMut(&[u16])) {
+    fn go(items: &[u16], k: usize, picked: &mut Vec<u16>, f: &mut impl FnMut(&[u16])) {
+        if picked.len() == k {
+            f(picked);
+            return;
+        }
+        let needed = k - picked.len();
+        for start in 0..=items.len().saturating_sub(needed) {
+            if items.len() - start < needed {
+                break;
+            }
+            picked.push(items[start]);
+            go(&items[start + 1..], k, picked, f);
+            picked.pop();
+        }
+    }
+    go(items, k, &mut Vec::with_capacity(k), f);
+}
+
+/// Combine a serialised CoreKeyShare with a serialised AuxInfo into a
+/// serialised KeyShare.
+///
+/// The level comes from the aux header and the curve from the core share's;
+/// a `requested` level that disagrees fails with `SECURITY_LEVEL_MISMATCH`.
+/// Material that doesn't pair up fails with one of the codes in the module
+/// docs, as does a core share for a key other than `expected_public_key`
+/// (either SEC1 form) when one is given, and a half tagged for another
+/// environment with `ENVIRONMENT_MISMATCH`. The KeyShare is written in
+/// `encoding`, whatever the halves were written in.
+pub fn combine_key_share(
+    core_key_share: &[u8],
+    aux_info: &[u8],
+    requested: Option<Level>,
+    expected_public_key: Option<&[u8]>,
+    encoding: Encoding,
+) -> Result<Vec<u8>, String> {
+    share_metadata::check_environment(core_key_share)?;
+    share_metadata::check_environment(aux_info)?;
+    let (core_header, core_payload) = share_format::split_expect(core_key_share, BlobKind::CoreShare)?;
+    let (aux_header, aux_payload) = share_format::split_expect(aux_info, BlobKind::AuxInfo)?;
+    let level = share_format::ensure_level(&aux_header, requested)?;
+    let curve = core_header.curve_or_default();
+    let expected = expected_public_key
+        .map(|pk| recode_public_key(curve, pk, true))
+        .transpose()?;
+
+    with_curve!(curve, E => with_security_level!(level, L => {
+        let iks: cggmp24::IncompleteKeyShare<E> =
+            share_format::deserialize(BlobKind::CoreShare, core_payload)?;
+        let aux: cggmp24::key_share::AuxInfo<L> =
+            share_format::deserialize(BlobKind::AuxInfo, aux_payload)?;
+        let key_share = pair_up(iks, aux, expected.as_deref())
+            .map_err(|mut errors| errors.remove(0))?;
+        // A dealer-minted half makes the whole share dealer-minted
+        let flags = core_header.flags | aux_header.flags;
+        share_format::serialize_encoded(BlobKind::KeyShare, Some(curve), Some(level), flags, encoding, &key_share)
+    }))
+}
+
+/// What [`check_key_material`] found out about a core share / aux info
+/// pair. Fields stay `None` when the blob they come from couldn't be read.
+#[derive(Serialize, Default, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct KeyMaterialReport {
+    /// True when the pair combines into a KeyShare (and matches the
+    /// expected key, if one was given)
+    pub ok: bool,
+    /// Core share's curve
+    pub curve: Option<String>,
+    /// Aux info's security level
+    pub security_level: Option<String>,
+    /// Core share's party index
+    pub core_party_index: Option<u16>,
+    /// Parties in the key, per the core share
+    pub core_n: Option<u16>,
+    /// Signing threshold, per the core share
+    pub core_threshold: Option<u16>,
+    /// Parties the aux info was generated for
+    pub aux_n: Option<u16>,
+    /// Whether the core share is for the expected key; `None` when no key
+    /// was given
+    pub public_key_matches: Option<bool>,
+    /// Every problem found, each `CODE: detail`
+    pub errors: Vec<String>,
+}
+
+/// Inspect a core share / aux info pair without failing: every check
+/// [`combine_key_share`] makes, with all problems found rather than the
+/// first.
+pub fn check_key_material(
+    core_key_share: &[u8],
+    aux_info: &[u8],
+    expected_public_key: Option<&[u8]>,
+) -> KeyMaterialReport {
+    let mut report = KeyMaterialReport::default();
+    if let Err(e) = inspect_pair(&mut report, core_key_share, aux_info, expected_public_key) {
+        report.errors.push(e);
+    }
+    report.ok = report.errors.is_empty();
+    report
+}
+
+fn inspect_pair(
+    report: &mut KeyMaterialReport,
+    core_key_share: &[u8],
+    aux_info: &[u8],
+    expected_public_key: Option<&[u8]>,
+) -> Result<(), String> {
+    let (core_header, core_payload) = share_format::split_expect(core_key_share, BlobKind::CoreShare)?;
+    let (aux_header, aux_payload) = share_format::split_expect(aux_info, BlobKind::AuxInfo)?;
+    let level = share_format::ensure_level(&aux_header, None)?;
+    let curve = core_header.curve_or_default();
+    report.curve = Some(curve.as_str().into());
+    report.security_level = Some(level.as_str().into());
+    let expected = expected_public_key
+        .map(|pk| recode_public_key(curve, pk, true))
+        .transpose()?;
+
+    with_curve!(curve, E => with_security_level!(level, L => {
+        let iks: cggmp24::IncompleteKeyShare<E> =
+            share_format::deserialize(BlobKind::CoreShare, core_payload)?;
+        report.core_party_index = Some(iks.i);
+        report.core_n = Some(iks.n());
+        report.core_threshold = Some(iks.min_signers());
+        if let Some(expected) = &expected {
+            report.public_key_matches = Some(iks.shared_public_key().to_bytes(true).as_bytes() == &expected[..]);
+        }
+        let aux: cggmp24::key_share::AuxInfo<L> =
+            share_format::deserialize(BlobKind::AuxInfo, aux_payload)?;
+        report.aux_n = u16::try_from(aux.parties.len()).ok();
+        if let Err(errors) = pair_up(iks, aux, expected.as_deref()) {
+            report.errors.extend(errors);
+        }
+        Ok(())
+    }))
+}
+
+/// Check the halves against each other, then combine. On failure, every
+/// mismatch found, most specific first.
+pub(crate) fn pair_up<E: Curve, L: SecurityLevel>(
+    iks: cggmp24::IncompleteKeyShare<E>,
+    aux: cggmp24::key_share::AuxInfo<L>,
+    expected_public_key: Option<&[u8]>,
+) -> Result<cggmp24::KeyShare<E, L>, Vec<String>> {
+    let mut errors = Vec::new();
+    let (core_n, aux_n) = (iks.n(), aux.parties.len());
+    if usize::from(core_n) != aux_n {
+        errors.push(format!(
+            "AUX_PARTY_COUNT_MISMATCH: core share is for {core_n} parties, aux info for {aux_n}"
+        ));
+    }
+    if let Some(expected) = expected_public_key {
+        if iks.shared_public_key().to_bytes(true).as_bytes() != expected {
+            errors.push("PUBLIC_KEY_MISMATCH: core share belongs to a different key".into());
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let i = iks.i;
+    cggmp24::KeyShare::from_parts((iks, aux)).map_err(|e| {
+        vec![format!(
+            "AUX_PARTY_MISMATCH: aux info wasn't generated for party {i} of this key's \
+             ceremony (stale or from another wallet): {e}"
+        )]
+    })
+}
+
+/// Extract the 33-byte compressed shared public key from a serialised
+/// KeyShare, CoreKeyShare or FROST key share.
+pub fn extract_public_key(key_share_bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let (header, payload) = share_format::split(key_share_bytes)?;
+    let level = header.level_or_default();
+
+    // FROST shares (givre, secp256k1 only)
+    if header.kind == Some(BlobKind::FrostKeyShare) {
+        let ks: givre::KeyShare<cggmp24::supported_curves::Secp256k1> =
+            share_format::deserialize(BlobKind::FrostKeyShare, payload)?;
+        return Ok(Point::from(ks.shared_public_key()).to_bytes(true).as_bytes().to_vec());
+    }
+
+    with_curve!(header.curve_or_default(), E => {
+        // Try as full KeyShare first
+        if header.kind.map_or(true, |k| k == BlobKind::KeyShare) {
+            let pk = with_security_level!(level, L => {
+                share_format::deserialize::<cggmp24::KeyShare<E, L>>(BlobKind::KeyShare, payload)
+                    .map(|ks| ks.shared_public_key().to_bytes(true).as_bytes().to_vec())
+            });
+            if let Ok(pk) = pk {
+                return Ok(pk);
+            }
+        }
+
+        // Try as CoreKeyShare (IncompleteKeyShare)
+        if header.kind.map_or(true, |k| k == BlobKind::CoreShare) {
+            if let Ok(iks) = share_format::deserialize::<cggmp24::IncompleteKeyShare<E>>(BlobKind::CoreShare, payload) {
+                let pk = iks.shared_public_key();
+                let encoded = pk.to_bytes(true);
+                return Ok(encoded.as_bytes().to_vec());
+            }
+        }
+    });
+
+    Err("failed to deserialize as KeyShare or CoreKeyShare".into())
+}
+
+/// [`extract_public_key`], 65-byte uncompressed unless `compressed`.
+pub fn extract_public_key_as(key_share_bytes: &[u8], compressed: bool) -> Result<Vec<u8>, String> {
+    let public_key = extract_public_key(key_share_bytes)?;
+    if compressed {
+        return Ok(public_key);
+    }
+    let (header, _) = share_format::split(key_share_bytes)?;
+    recode_public_key(header.curve_or_default(), &public_key, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn signer_sets_are_checked_without_panicking(
+            n in 0u16..20,
+            threshold in 0u16..20,
+            party_index in prop_oneof![0u16..24, any::<u16>()],
+            parties in proptest::collection::vec(prop_oneof![0u16..24, any::<u16>()], 0..24),
+        ) {
+            let unique = parties.iter().enumerate().all(|(j, p)| !parties[..j].contains(p));
+            let valid = parties.len() >= usize::from(threshold)
+                && parties.iter().chain([&party_index]).all(|&p| p < n)
+                && unique
+                && parties.contains(&party_index);
+            match check_signer_set(n, threshold, party_index, &parties) {
+                Ok(position) => {
+                    prop_assert!(valid);
+                    prop_assert_eq!(parties[usize::from(position)], party_index);
+                }
+                Err(_) => prop_assert!(!valid),
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Keys registered once and signed with by id.
+//!
+//! A host juggling several wallets would otherwise hand the same core share
+//! and aux info over for every session, and re-derive the public key and
+//! address to show which wallet is which. [`register_key`] checks the pair
+//! combines, records its public parameters and keeps both blobs; sessions
+//! created with the key's id read them from here.
+//!
+//! The combined share itself lives in the signing share cache like any
+//! other: registering warms it, so the first session by id skips
+//! deserialising. The blobs are wiped when the key is unregistered, and
+//! counted in `memory_stats` while registered.
+//!
+//! A key's id is its share fingerprint (see `fingerprint`), so registering
+//! the same core share again returns the same id.
+
+use std::cell::RefCell;
+
+use serde::Serialize;
+use tsify::Tsify;
+use zeroize::Zeroizing;
+
+use crate::fingerprint;
+use crate::key_material;
+use crate::metrics;
+use crate::share_format::CurveId;
+
+/// Longest label accepted, in bytes.
+const MAX_LABEL_BYTES: usize = 256;
+
+/// A registered key, as [`list_keys`] reports it.
+#[derive(Serialize, Tsify, Clone, Debug)]
+pub struct KeyInfo {
+    pub key_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub label: Option<String>,
+    pub curve: String,
+    /// Hex compressed shared public key
+    pub public_key: String,
+    /// EIP-55 Ethereum address; secp256k1 keys only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub address: Option<String>,
+    pub threshold: u16,
+    pub n: u16,
+    pub party_index: u16,
+}
+
+struct Entry {
+    info: KeyInfo,
+    core_share: Zeroizing<Vec<u8>>,
+    aux_info: Zeroizing<Vec<u8>>,
+}
+
+impl Entry {
+    fn bytes(&self) -> usize {
+        self.core_share.len() + self.aux_info.len()
+    }
+}
+
+thread_local! {
+    /// In registration order
+    static KEYS: RefCell<Vec<Entry>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Register a core share / aux info pair under an optional label and
+/// return its id. Fails if the pair doesn't combine (see
+/// `key_material::combine_key_share`) or the label is over 256 bytes
+/// (`INVALID_LABEL`). Registering a key again replaces its label.
+pub fn register_key(core_share: &[u8], aux_info: &[u8], label: Option<&str>) -> Result<String, String> {
+    if label.is_some_and(|label| label.len() > MAX_LABEL_BYTES) {
+        return Err(format!("INVALID_LABEL: labels are at most {MAX_LABEL_BYTES} bytes"));
+    }
+    // Checks the pair and leaves the combined share cached for sessions
+    crate::sign::warm_share_cache(core_share, aux_info)?;
+
+    let core = key_material::core_public_data(core_share)?;
+    let address = match CurveId::parse(&core.curve)? {
+        CurveId::Secp256k1 => Some(key_material::eth_address(&key_material::extract_public_key(core_share)?)?),
+        _ => None,
+    };
+    let info = KeyInfo {
+        key_id: fingerprint::share_fingerprint(core_share),
+        label: label.map(str::to_string),
+        curve: core.curve,
+        public_key: core.public_key,
+        address,
+        threshold: core.threshold,
+        n: core.n,
+        party_index: core.party_index,
+    };
+    let key_id = info.key_id.clone();
+
+    KEYS.with(|keys| {
+        let mut keys = keys.borrow_mut();
+        if let Some(entry) = keys.iter_mut().find(|entry| entry.info.key_id == key_id) {
+            entry.info.label = info.label;
+            return;
+        }
+        let entry = Entry {
+            info,
+            core_share: Zeroizing::new(core_share.to_vec()),
+            aux_info: Zeroizing::new(aux_info.to_vec()),
+        };
+        metrics::key_registered(entry.bytes());
+        keys.push(entry);
+    });
+    Ok(key_id)
+}
+
+/// Every registered key, oldest first.
+pub fn list_keys() -> Vec<KeyInfo> {
+    KEYS.with(|keys| keys.borrow().iter().map(|entry| entry.info.clone()).collect())
+}
+
+/// Forget a key and wipe its blobs. Sessions already created with it keep
+/// running. Returns false if no such key is registered.
+pub fn unregister_key(key_id: &str) -> bool {
+    KEYS.with(|keys| {
+        let mut keys = keys.borrow_mut();
+        let Some(position) = keys.iter().position(|entry| entry.info.key_id == key_id) else {
+            return false;
+        };
+        let entry = keys.remove(position);
+        metrics::key_unregistered(entry.bytes());
+        true
+    })
+}
+
+/// Copies of a registered key's core share and aux info. Fails with
+/// `KEY_NOT_FOUND` for an id that isn't registered.
+pub fn key_material(key_id: &str) -> Result<(Zeroizing<Vec<u8>>, Zeroizing<Vec<u8>>), String> {
+    KEYS.with(|keys| {
+        keys.borrow()
+            .iter()
+            .find(|entry| entry.info.key_id == key_id)
+            .map(|entry| (entry.core_share.clone(), entry.aux_info.clone()))
+            .ok_or_else(|| format!("KEY_NOT_FOUND: no key registered as {key_id}"))
+    })
+}
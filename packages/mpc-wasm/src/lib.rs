@@ -1,13 +1,68 @@
 //! WASM wrapper for CGGMP24 threshold ECDSA.
 //!
 //! Provides:
+//! - `init` / `check_entropy`: entropy health check, optional host entropy, default encoding, configuration
+//!   and a host store that keeps signing sessions across worker restarts
+//! - `get_config` / `update_config`: the module's tunable limits, adjustable at runtime
+//! - `capabilities` / `is_share_compatible`: what this build supports
+//! - `get_share_info`: what a blob's header says (kind, curve, level, dealer flag)
+//! - `migrate_share`: re-encode a JSON blob in the binary (CBOR) share encoding
+//! - `attach_metadata` / `read_metadata`: a label and environment tag carried with a share
+//! - `create_backup_bundle` / `read_backup_bundle`: one self-describing file per share for cold storage
+//! - `split_share` / `recover_share`: k-of-m Shamir fragments of a share for separate custodians
+//! - `create_cancel_token` / `cancel`: stop a long-running export at its next safe point
 //! - `run_dkg`: Full DKG ceremony (aux_info_gen + keygen) for all parties locally
+//! - `run_dkg_streaming`: `run_dkg` handing out shares one party at a time
+//! - `run_dkg_trusted_dealer`: instant dealer-minted shares for tests (`insecure-dev` builds only)
+//! - `set_max_parties`: largest `n` the DKG exports accept (default 16)
+//! - `run_aux_for_parties` / `run_keygen_for_parties`: the two DKG phases on their own, for any party indices
+//! - `run_dkg_with_aux`: keygen over a native-gen `gen-aux` set, its metadata checked
+//! - `run_aux_refresh`: New Paillier material for existing core shares
+//! - `import_tsslib_share`: Core share from a tss-lib (GG20) keygen save file
 //! - `combine_key_share`: Merge CoreKeyShare + AuxInfo into full KeyShare
+//! - `check_key_material`: Diagnose a CoreKeyShare / AuxInfo pair that won't combine
+//! - `check_share_compat`: Check a CoreKeyShare / AuxInfo pair still signs under this build (signing builds)
 //! - `extract_public_key`: Get shared public key from serialised key share
+//! - `aux_public_data` / `core_public_data`: public parameters of aux info / a core share, for audit
+//! - `signer_sets` / `can_sign_with`: the signer sets a share can co-sign with
 //! - `pregenerate_paillier_primes`: Pre-generate expensive Paillier primes
+//! - `validate_pregenerated_primes`: Check a cached primes blob before DKG uses it
+//! - `encrypt_share` / `decrypt_share`: Passphrase-based share-at-rest encryption
+//! - `wrap_share_to_recipient` / `unwrap_share`: ECIES share delivery to a recipient key
+//! - `SigningSession`: one party's interactive ECDSA signing session
+//! - `sign_create_batch_session`: one session signing several hashes in lockstep
+//! - `sign_create_session_deferred` / `sign_warmup`: create a session at once, compute its first messages in idle time
+//! - `sign_feed_message` / `sign_poll`: `sign_process_round` one message / a few steps at a time
+//! - `route_messages` / `validate_routing`: which party a relay hands each signing message to
+//! - `sign_heartbeat`: a session's liveness, status and estimated progress
+//! - `signing_protocol_info`: a session's rounds and message counts, ahead of creating it
+//! - `sign_session_status`: a session's status, and its error if it failed
+//! - `sign_audit_record`: what produced an ECDSA session's signature, for dispute resolution
+//! - `frost_run_dkg` / `frost_sign_*`: threshold Schnorr (FROST, BIP-340) via givre
+//! - `reconstruct_private_key`: confirmed full-key export from ≥ threshold core shares
+//! - `memory_stats`: WASM memory and key material held by live signing sessions
+//! - `register_key` / `list_keys` / `unregister_key`: keys held in the module and signed with by id
+//! - `sign_export_session` / `sign_import_session`: resume a signing session after a reload
+//! - `set_share_cache_capacity` / `evict_cached_share`: cache of combined key shares for repeated signings
+//! - `eip155_v`: legacy-transaction `v` from a recovery id and chain id
+//! - `recover_public_key`: the public key an ECDSA signature recovers to
+//! - `normalize_signature`: an ECDSA signature's low-s form, and whether `s` was flipped
+//! - `bitcoin_script_sig` / `bitcoin_p2wpkh_address`: Bitcoin signature and address encodings
+//! - `derive_eid` / `eid_reuse_count`: purpose-bound execution ids, and a reuse counter
+//! - `share_fingerprint` / `wallet_id_from_pubkey`: short public ids for shares and keys
+//! - `share_commitment` / `verify_commitments`: check shares held apart belong to one wallet
+//! - `bench_sign` / `bench_dkg_keygen_only`: fixed-seed local ceremonies, timed
+//! - `estimate_dkg` / `estimate_sign`: how long and how much memory a ceremony needs on this device
+//! - `verify_vectors`: replay the frozen wire-format test vectors (native-gen `gen-vectors`)
+//! - `fixture_dkg_result` / `fixture_address` / `fixture_signature`: the vendored 2-of-3 test key
+//!   and a signature by it (`fixtures` builds)
 //!
-//! DKG runs all parties locally (server-side). Signing uses per-party
-//! state machines driven by HTTP round-trips (not yet implemented).
+//! Byte arguments take a `Uint8Array` or a hex string (`0x` optional);
+//! malformed hex fails with `INVALID_HEX` naming the parameter.
+//!
+//! DKG runs all parties locally (server-side). Signing runs one state
+//! machine per party (`SigningSession`, or `sign_create_session` /
+//! `sign_process_round`); the host relays their messages, e.g. over HTTP.
 
 // ─── Critical-section implementation for WASM ────────────────────────────────
 // WASM is single-threaded so a no-op critical section is safe.
@@ -27,389 +82,3810 @@ unsafe impl critical_section::Impl for WasmCriticalSection {
     }
 }
 
+// ─── Features ───────────────────────────────────────────────────────────────
+// `dkg`, `signing` and `primes` (all on by default) each gate their exports
+// and the modules only they use; the browser ships a `signing`-only build.
+// Modules in native-gen's `#[path]` list must not test these features —
+// native-gen has none — so the gating stays on the `mod` lines here.
+
+#[cfg(feature = "signing")]
+mod approval;
+#[cfg(feature = "signing")]
+mod audit;
+#[cfg(feature = "dkg")]
+mod aux_refresh;
+#[cfg(feature = "dkg")]
+mod aux_set;
+mod backup;
+#[cfg(all(feature = "dkg", feature = "signing"))]
+mod bench;
+#[cfg(feature = "signing")]
+mod binding;
+#[cfg(feature = "signing")]
+mod bitcoin;
+mod cancel;
+mod capabilities;
+#[cfg(any(feature = "dkg", feature = "signing"))]
+mod clock;
+mod commitment;
+#[cfg(feature = "signing")]
+mod config;
+#[cfg(any(feature = "dkg", feature = "signing", feature = "test-fixtures"))]
+mod drive_guard;
+#[cfg_attr(not(feature = "signing"), allow(dead_code))]
+mod entropy;
+mod eid;
+#[cfg(all(feature = "dkg", feature = "signing"))]
+mod estimate;
+mod fingerprint;
+#[cfg(feature = "fixtures")]
+mod fixtures;
+#[cfg(any(feature = "dkg", feature = "signing"))]
+mod frost;
+mod hex_codec;
+mod interop;
+mod key_export;
+// `check_signer_set` only serves signing sessions
+#[cfg_attr(not(feature = "signing"), allow(dead_code))]
+mod key_material;
+#[cfg(feature = "signing")]
+mod key_registry;
+#[cfg(feature = "signing")]
+mod limits;
+#[cfg(feature = "signing")]
+mod message_auth;
+#[cfg(feature = "signing")]
+mod message_codec;
+mod migrate;
+// Without `signing` only the primes counters are used
+#[cfg(any(feature = "dkg", feature = "signing"))]
+#[cfg_attr(not(feature = "signing"), allow(dead_code))]
+mod metrics;
+// `check_key_params` only serves new keys
+#[cfg_attr(not(feature = "dkg"), allow(dead_code))]
+mod party_limits;
+#[cfg(feature = "primes")]
+mod primes_check;
+#[cfg(feature = "dkg")]
+mod primes_reuse;
+// Only signing runs the protocol with it; `capabilities` lists the names
+#[cfg_attr(not(feature = "signing"), allow(dead_code, unused_imports, unused_macros))]
+mod protocol_digest;
+#[cfg(feature = "signing")]
+mod protocol_failure;
+#[cfg(feature = "signing")]
+mod relay;
+// `has_extra` and `MIN_EXTRA_ENTROPY` only serve native-gen
+#[allow(dead_code)]
+mod rng;
+#[cfg(feature = "signing")]
+mod session_store;
+#[cfg(feature = "signing")]
+mod share_cache;
+#[cfg(feature = "signing")]
+mod share_compat;
+mod share_crypto;
+#[cfg_attr(not(all(feature = "dkg", feature = "signing", feature = "primes")), allow(dead_code))]
+mod share_format;
+mod share_metadata;
+#[cfg(feature = "signing")]
 mod sign;
+// `dkg` only needs `ensure_valid`, for the aux refresh check
+#[cfg(any(feature = "dkg", feature = "signing"))]
+#[cfg_attr(not(feature = "signing"), allow(dead_code))]
+mod signature;
+// Without both `dkg` and `signing` only part of it is reachable
+#[cfg(any(feature = "dkg", feature = "test-fixtures"))]
+#[cfg_attr(not(all(feature = "dkg", feature = "signing")), allow(dead_code))]
 mod simulate;
+#[cfg(all(test, feature = "insecure-dev", feature = "signing"))]
+mod test_support;
+#[cfg(all(feature = "dkg", feature = "signing"))]
+mod test_vectors;
+#[cfg(feature = "signing")]
 mod types;
 
-use rand::rngs::OsRng;
+#[cfg(any(feature = "dkg", feature = "primes"))]
+use rng::MixedRng;
+#[cfg(feature = "dkg")]
+use rand_core::{CryptoRng, RngCore};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use tsify::Tsify;
 use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
 
+#[cfg(feature = "dkg")]
 use cggmp24::key_share::AnyKeyShare;
-use cggmp24::security_level::SecurityLevel128;
-use cggmp24::supported_curves::Secp256k1;
+#[cfg(any(feature = "dkg", all(feature = "test-fixtures", feature = "signing")))]
+use cggmp24::security_level::SecurityLevel;
+#[cfg(feature = "dkg")]
+use generic_ec::coords::HasAffineX;
+#[cfg(any(feature = "dkg", all(feature = "test-fixtures", feature = "signing")))]
+use generic_ec::Curve;
+#[cfg(feature = "dkg")]
+use generic_ec::Point;
 
-/// Initialise the WASM module (called once from JS).
+#[cfg(any(feature = "dkg", feature = "primes", all(feature = "test-fixtures", feature = "signing")))]
+use share_format::{with_security_level, BlobKind};
+#[cfg(any(feature = "dkg", all(feature = "test-fixtures", feature = "signing")))]
+use share_format::with_curve;
+use key_material::PointFormat;
+use share_format::{CurveId, Encoding, Level};
+
+/// Runs when the WASM module is instantiated.
 #[wasm_bindgen(start)]
-pub fn init() {
+pub fn start() {
     // No-op for now. Panic hook can be added later if needed.
 }
 
-// ─── DKG Result Types ───────────────────────────────────────────────────────
+// ─── TypeScript types ───────────────────────────────────────────────────────
+//
+// The boundary structs derive `Tsify`, which emits their interfaces into the
+// generated .d.ts; these handles give the exports those types instead of
+// `any`. Values still go through `to_js` / serde-wasm-bindgen, so the TS
+// types only describe what serde produces.
 
-/// A single party's key material from DKG.
-#[derive(Serialize, Deserialize)]
-struct DkgShare {
-    /// Serialised CoreKeyShare (serde_json bytes)
-    core_share: Vec<u8>,
-    /// Serialised AuxInfo (serde_json bytes)
-    aux_info: Vec<u8>,
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "InitOptions & { session_store?: SessionStoreCallbacks | null }")]
+    pub type JsInitOptions;
+    #[wasm_bindgen(typescript_type = "GuardianConfig")]
+    pub type JsGuardianConfig;
+    #[wasm_bindgen(typescript_type = "ConfigUpdate")]
+    pub type JsConfigUpdate;
+    #[wasm_bindgen(typescript_type = "DkgResult")]
+    pub type JsDkgResult;
+    #[wasm_bindgen(typescript_type = "DkgSummary")]
+    pub type JsDkgSummary;
+    #[wasm_bindgen(typescript_type = "DkgOptions")]
+    pub type JsDkgOptions;
+    #[wasm_bindgen(typescript_type = "AuxRefreshResult")]
+    pub type JsAuxRefreshResult;
+    #[wasm_bindgen(typescript_type = "AuxForPartiesResult")]
+    pub type JsAuxForPartiesResult;
+    #[wasm_bindgen(typescript_type = "TsslibImportResult")]
+    pub type JsTsslibImportResult;
+    #[wasm_bindgen(typescript_type = "Uint8Array | PublicKeyForms")]
+    pub type JsPublicKey;
+    #[wasm_bindgen(typescript_type = "Capabilities")]
+    pub type JsCapabilities;
+    #[wasm_bindgen(typescript_type = "ShareInfo")]
+    pub type JsShareInfo;
+    #[wasm_bindgen(typescript_type = "ShareMetadata")]
+    pub type JsShareMetadata;
+    #[wasm_bindgen(typescript_type = "SignerSetOptions")]
+    pub type JsSignerSetOptions;
+    #[wasm_bindgen(typescript_type = "SignerSets")]
+    pub type JsSignerSets;
+    #[wasm_bindgen(typescript_type = "ShareMetadata | undefined")]
+    pub type JsShareMetadataOrUndefined;
+    #[wasm_bindgen(typescript_type = "BackupOptions")]
+    pub type JsBackupOptions;
+    #[wasm_bindgen(typescript_type = "BackupContents")]
+    pub type JsBackupContents;
+    #[wasm_bindgen(typescript_type = "Uint8Array[]")]
+    pub type JsShareFragments;
+    #[wasm_bindgen(typescript_type = "KeyMaterialReport")]
+    pub type JsKeyMaterialReport;
+    #[wasm_bindgen(typescript_type = "ShareCompatReport")]
+    pub type JsShareCompatReport;
+    #[wasm_bindgen(typescript_type = "AuxPublicData")]
+    pub type JsAuxPublicData;
+    #[wasm_bindgen(typescript_type = "CorePublicData")]
+    pub type JsCorePublicData;
+    #[wasm_bindgen(typescript_type = "CommitmentReport")]
+    pub type JsCommitmentReport;
+    #[wasm_bindgen(typescript_type = "PrimesReport")]
+    pub type JsPrimesReport;
+    #[wasm_bindgen(typescript_type = "PrimesCheckOptions")]
+    pub type JsPrimesCheckOptions;
+    #[wasm_bindgen(typescript_type = "KdfParams")]
+    pub type JsKdfParams;
+    #[wasm_bindgen(typescript_type = "Uint8Array | string")]
+    pub type JsBytes;
+    #[wasm_bindgen(typescript_type = "(Uint8Array | string)[]")]
+    pub type JsByteArrays;
+    #[wasm_bindgen(typescript_type = "Uint8Array | string | EidSpec")]
+    pub type JsEid;
+    #[wasm_bindgen(
+        typescript_type = "SignOptions & { approval_callback?: (request: ApprovalRequest) => boolean | Promise<boolean> }"
+    )]
+    pub type JsSignOptions;
+    #[wasm_bindgen(typescript_type = "WasmSignMessage")]
+    pub type JsSignMessage;
+    #[wasm_bindgen(typescript_type = "WasmSignMessage[]")]
+    pub type JsSignMessages;
+    #[wasm_bindgen(typescript_type = "Record<number, WasmSignMessage[]>")]
+    pub type JsRoutedMessages;
+    #[wasm_bindgen(typescript_type = "RoutingReport")]
+    pub type JsRoutingReport;
+    #[wasm_bindgen(typescript_type = "CreateSessionResult")]
+    pub type JsCreateSessionResult;
+    #[wasm_bindgen(typescript_type = "ProcessRoundResult")]
+    pub type JsProcessRoundResult;
+    #[wasm_bindgen(typescript_type = "SessionMetrics")]
+    pub type JsSessionMetrics;
+    #[wasm_bindgen(typescript_type = "AuditRecord")]
+    pub type JsAuditRecord;
+    #[wasm_bindgen(typescript_type = "Heartbeat")]
+    pub type JsHeartbeat;
+    #[wasm_bindgen(typescript_type = "SigningProtocolOptions")]
+    pub type JsSigningProtocolOptions;
+    #[wasm_bindgen(typescript_type = "SigningProtocolInfo")]
+    pub type JsSigningProtocolInfo;
+    #[wasm_bindgen(typescript_type = "SessionState")]
+    pub type JsSessionState;
+    #[wasm_bindgen(typescript_type = "MemoryStats")]
+    pub type JsMemoryStats;
+    #[wasm_bindgen(typescript_type = "Uint8Array | string | KeyRef")]
+    pub type JsSigningKey;
+    #[wasm_bindgen(typescript_type = "KeyInfo[]")]
+    pub type JsKeyInfos;
+    #[wasm_bindgen(typescript_type = "Uint8Array | string | RawMessage")]
+    pub type JsSignData;
+    #[wasm_bindgen(typescript_type = "LimitOverrides")]
+    pub type JsLimitOverrides;
+    #[wasm_bindgen(typescript_type = "MessageLimits")]
+    pub type JsMessageLimits;
+    #[wasm_bindgen(typescript_type = "FrostDkgResult")]
+    pub type JsFrostDkgResult;
+    #[wasm_bindgen(typescript_type = "FrostSignOptions")]
+    pub type JsFrostSignOptions;
+    #[wasm_bindgen(typescript_type = "SignatureResult")]
+    pub type JsSignatureResult;
+    #[wasm_bindgen(typescript_type = "NormalizedSignature")]
+    pub type JsNormalizedSignature;
+    #[wasm_bindgen(typescript_type = "BenchReport")]
+    pub type JsBenchReport;
+    #[wasm_bindgen(typescript_type = "Estimate")]
+    pub type JsEstimate;
+    #[wasm_bindgen(typescript_type = "VectorReport")]
+    pub type JsVectorReport;
+    #[wasm_bindgen(typescript_type = "SignVector")]
+    pub type JsSignVector;
 }
 
-/// Complete DKG result: key shares for all parties + shared public key.
-#[derive(Serialize, Deserialize)]
-struct DkgResult {
-    /// One DkgShare per party (index 0..n)
-    shares: Vec<DkgShare>,
-    /// 33-byte compressed secp256k1 shared public key
-    public_key: Vec<u8>,
+/// Deserialize an optional options object from JS (`undefined`/`null` → defaults).
+fn options_from_js<T: DeserializeOwned + Default>(
+    value: Option<impl Into<JsValue>>,
+    what: &str,
+) -> Result<T, JsError> {
+    let Some(value) = value.map(Into::into) else {
+        return Ok(T::default());
+    };
+    if value.is_undefined() || value.is_null() {
+        return Ok(T::default());
+    }
+    serde_wasm_bindgen::from_value(value)
+        .map_err(|e| JsError::new(&format!("deserialize {what}: {e}")))
 }
 
-// ─── Full DKG (all parties local) ────────────────────────────────────────────
+/// `SignOptions` from JS. `approval_callback` is a function, which serde
+/// can't carry, so it is read off the object directly.
+#[cfg(feature = "signing")]
+fn sign_options(options: Option<JsSignOptions>) -> Result<sign::SignOptions, JsError> {
+    let options: Option<JsValue> = options.map(Into::into);
+    let callback = options
+        .as_ref()
+        .filter(|options| options.is_object())
+        .and_then(|options| js_sys::Reflect::get(options, &JsValue::from_str("approval_callback")).ok())
+        .filter(|callback| !callback.is_undefined() && !callback.is_null());
+    let mut parsed: sign::SignOptions = options_from_js(options, "sign options")?;
+    parsed.approval_callback = callback
+        .map(|callback| {
+            callback
+                .dyn_into::<js_sys::Function>()
+                .map_err(|_| JsError::new("deserialize sign options: approval_callback must be a function"))
+        })
+        .transpose()?;
+    Ok(parsed)
+}
 
-/// Run a complete two-phase DKG ceremony for `n` parties with threshold `t`.
-///
-/// Phase A: Auxiliary info generation (Paillier primes — computationally expensive)
-/// Phase B: Key generation (lightweight ECDSA key shares)
-///
-/// All parties run locally via protocol simulation. Returns a JSON object
-/// containing key shares for each party and the shared public key.
+/// Serialise a result for JS: structs and maps become plain objects, byte
+/// fields marked `serde_bytes` become `Uint8Array`.
+fn to_js<T: Serialize + ?Sized, R: JsCast>(value: &T) -> Result<R, JsError> {
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+    value
+        .serialize(&serializer)
+        .map(JsCast::unchecked_into)
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// Bytes from a byte-accepting export argument: a `Uint8Array` (or plain
+/// `Array<number>`) as-is, or a hex string with an optional `0x` prefix.
+/// `name` is the parameter name for error messages.
 ///
-/// The caller (server) distributes shares:
-/// - Share[0] → signer (encrypted .share.enc file)
-/// - Share[1] → server (stored in Vault)
-/// - Share[2] → user (wallet-encrypted, returned to browser)
-#[wasm_bindgen]
-pub fn run_dkg(eid_bytes: &[u8], n: u16, threshold: u16) -> Result<JsValue, JsError> {
-    if n < 2 {
-        return Err(JsError::new("n must be at least 2"));
+/// Wiped on drop — most of these arguments are key material.
+fn js_bytes(value: impl Into<JsValue>, name: &str) -> Result<Zeroizing<Vec<u8>>, JsError> {
+    let value = value.into();
+    if let Some(hex) = value.as_string() {
+        let hex = Zeroizing::new(hex);
+        return hex_codec::decode(&hex, name).map(Zeroizing::new).map_err(|e| JsError::new(&e));
     }
-    if threshold < 2 || threshold > n {
-        return Err(JsError::new(&format!(
-            "threshold must be in [2, {n}], got {threshold}"
-        )));
+    if let Some(array) = value.dyn_ref::<js_sys::Uint8Array>() {
+        return Ok(Zeroizing::new(array.to_vec()));
     }
+    serde_wasm_bindgen::from_value::<ByteBuf>(value)
+        .map(|bytes| Zeroizing::new(bytes.into_vec()))
+        .map_err(|_| JsError::new(&format!("INVALID_BYTES: {name} must be a Uint8Array or a hex string")))
+}
 
-    // Phase A: Auxiliary Info Generation
-    // Generates Paillier key pairs for each party (expensive: ~30-60s per party)
-    let mut aux_parties = Vec::new();
-    for i in 0..n {
-        let eid = cggmp24::ExecutionId::new(eid_bytes);
-        let primes: cggmp24::PregeneratedPrimes<SecurityLevel128> =
-            cggmp24::PregeneratedPrimes::generate(&mut OsRng);
-        aux_parties.push(round_based::state_machine::wrap_protocol(
-            move |party| async move {
-                let mut rng = OsRng;
-                cggmp24::aux_info_gen(eid, i, n, primes)
-                    .start(&mut rng, party)
-                    .await
-            },
-        ));
+/// What `derive_eid` takes, accepted in place of raw bytes by the exports
+/// that take an eid.
+#[derive(Deserialize, Tsify)]
+struct EidSpec {
+    wallet_id: String,
+    /// `"dkg"`, `"sign"` or `"refresh"`; must be what the export runs
+    purpose: String,
+    #[serde(with = "serde_bytes", default)]
+    #[tsify(optional, type = "Uint8Array")]
+    nonce: Vec<u8>,
+}
+
+/// Eid from an eid argument: bytes as [`js_bytes`] takes them, or an
+/// [`EidSpec`] derived with `derive_eid`. A spec for another purpose than
+/// `purpose` fails with `EID_PURPOSE_MISMATCH`.
+fn js_eid(value: JsEid, name: &str, purpose: eid::EidPurpose) -> Result<Zeroizing<Vec<u8>>, JsError> {
+    let value: JsValue = value.into();
+    if !value.is_object() || value.is_instance_of::<js_sys::Uint8Array>() || js_sys::Array::is_array(&value) {
+        return js_bytes(value, name);
     }
+    let spec: EidSpec = serde_wasm_bindgen::from_value(value)
+        .map_err(|e| JsError::new(&format!("INVALID_EID: {name}: {e}")))?;
+    let requested = eid::EidPurpose::parse(&spec.purpose).map_err(|e| JsError::new(&e))?;
+    if requested != purpose {
+        return Err(JsError::new(&format!(
+            "EID_PURPOSE_MISMATCH: {name} is derived for {}, this call needs {}",
+            requested.as_str(),
+            purpose.as_str()
+        )));
+    }
+    eid::derive_eid(&spec.wallet_id, purpose, &spec.nonce)
+        .map(|eid| Zeroizing::new(eid.to_vec()))
+        .map_err(|e| JsError::new(&e))
+}
 
-    let aux_results = simulate::run(aux_parties)
-        .map_err(|e| JsError::new(&format!("aux_info_gen failed: {e}")))?;
+/// A registered key (`register_key`), accepted in place of a core share by
+/// the exports that create signing sessions.
+#[cfg(feature = "signing")]
+#[derive(Deserialize, Tsify)]
+struct KeyRef {
+    key_id: String,
+}
 
-    let mut aux_infos = Vec::new();
-    for (i, result) in aux_results.into_iter().enumerate() {
-        let aux = result
-            .map_err(|e| JsError::new(&format!("aux_info_gen party {i} failed: {e:?}")))?;
-        aux_infos.push(aux);
+/// Core share and aux info from a session export's first two arguments:
+/// bytes as [`js_bytes`] takes them, or a [`KeyRef`] with `aux_info`
+/// omitted. An unknown key fails with `KEY_NOT_FOUND`.
+#[cfg(feature = "signing")]
+fn signing_key(
+    core_share: JsSigningKey,
+    aux_info: Option<JsBytes>,
+) -> Result<(Zeroizing<Vec<u8>>, Zeroizing<Vec<u8>>), JsError> {
+    let value: JsValue = core_share.into();
+    if !value.is_object() || value.is_instance_of::<js_sys::Uint8Array>() || js_sys::Array::is_array(&value) {
+        let aux_info = aux_info
+            .ok_or_else(|| JsError::new("MISSING_ARGUMENT: aux_info is required unless core_share is a { key_id }"))?;
+        return Ok((js_bytes(value, "core_share")?, js_bytes(aux_info, "aux_info")?));
     }
-
-    // Phase B: Key Generation
-    // Generates threshold ECDSA key shares (lightweight: ~2-5s)
-    let mut kg_parties = Vec::new();
-    for i in 0..n {
-        let eid = cggmp24::ExecutionId::new(eid_bytes);
-        kg_parties.push(round_based::state_machine::wrap_protocol(
-            move |party| async move {
-                let mut rng = OsRng;
-                cggmp24::keygen::<Secp256k1>(eid, i, n)
-                    .set_threshold(threshold)
-                    .start(&mut rng, party)
-                    .await
-            },
+    let key: KeyRef = serde_wasm_bindgen::from_value(value)
+        .map_err(|e| JsError::new(&format!("INVALID_KEY_REF: core_share: {e}")))?;
+    if aux_info.is_some() {
+        return Err(JsError::new(
+            "INVALID_ARGUMENTS: aux_info must be omitted when core_share is a { key_id }",
         ));
     }
+    key_registry::key_material(&key.key_id).map_err(|e| JsError::new(&e))
+}
 
-    let kg_results = simulate::run(kg_parties)
-        .map_err(|e| JsError::new(&format!("keygen failed: {e}")))?;
-
-    let mut core_shares = Vec::new();
-    for (i, result) in kg_results.into_iter().enumerate() {
-        let share = result
-            .map_err(|e| JsError::new(&format!("keygen party {i} failed: {e:?}")))?;
-        core_shares.push(share);
-    }
+/// Message bytes to sign as they are, accepted in place of a hash by
+/// `sign_create_session`: cggmp24 hashes them with SHA-256.
+#[cfg(feature = "signing")]
+#[derive(Deserialize, Tsify)]
+struct RawMessage {
+    #[tsify(type = "Uint8Array")]
+    message: ByteBuf,
+}
 
-    // Extract shared public key (same for all parties)
-    let pk = core_shares[0].shared_public_key();
-    let pk_bytes = pk.to_bytes(true); // 33-byte compressed
+/// A decoded `message_hash` argument.
+#[cfg(feature = "signing")]
+enum SignInput {
+    Hash(Zeroizing<Vec<u8>>),
+    Message(ByteBuf),
+}
 
-    // Serialize each party's key material
-    let mut shares = Vec::new();
-    for i in 0..n as usize {
-        let core_bytes = serde_json::to_vec(&core_shares[i])
-            .map_err(|e| JsError::new(&format!("serialize core share {i}: {e}")))?;
-        let aux_bytes = serde_json::to_vec(&aux_infos[i])
-            .map_err(|e| JsError::new(&format!("serialize aux info {i}: {e}")))?;
-        shares.push(DkgShare {
-            core_share: core_bytes,
-            aux_info: aux_bytes,
-        });
+#[cfg(feature = "signing")]
+impl SignInput {
+    fn data(&self) -> sign::SignData<'_> {
+        match self {
+            SignInput::Hash(hash) => sign::SignData::Prehashed(hash),
+            SignInput::Message(message) => sign::SignData::Message(message),
+        }
     }
+}
 
-    let result = DkgResult {
-        shares,
-        public_key: pk_bytes.as_bytes().to_vec(),
-    };
+/// What to sign: a hash as [`js_bytes`] takes it, or a [`RawMessage`].
+#[cfg(feature = "signing")]
+fn sign_input(value: JsSignData) -> Result<SignInput, JsError> {
+    let value: JsValue = value.into();
+    if !value.is_object() || value.is_instance_of::<js_sys::Uint8Array>() || js_sys::Array::is_array(&value) {
+        return js_bytes(value, "message_hash").map(SignInput::Hash);
+    }
+    let raw: RawMessage = serde_wasm_bindgen::from_value(value)
+        .map_err(|e| JsError::new(&format!("INVALID_MESSAGE: message_hash: {e}")))?;
+    Ok(SignInput::Message(raw.message))
+}
 
-    serde_wasm_bindgen::to_value(&result).map_err(|e| JsError::new(&e.to_string()))
+/// A JS array of byte blobs, each in any form [`js_bytes`] accepts and,
+/// like its result, wiped on drop.
+fn byte_arrays_from_js(value: JsByteArrays, what: &str) -> Result<Vec<Zeroizing<Vec<u8>>>, JsError> {
+    let value: JsValue = value.into();
+    let array = value
+        .dyn_ref::<js_sys::Array>()
+        .ok_or_else(|| JsError::new(&format!("{what} must be an array")))?;
+    array
+        .iter()
+        .enumerate()
+        .map(|(i, item)| js_bytes(item, &format!("{what}[{i}]")))
+        .collect()
 }
 
-// ─── DKG with Pre-generated Primes (fast path) ──────────────────────────────
+// ─── Initialisation ─────────────────────────────────────────────────────────
 
-/// Run a complete two-phase DKG ceremony using pre-generated Paillier primes.
+/// Options for `init`; every field optional.
+#[derive(Deserialize, Default, Tsify)]
+#[serde(default)]
+struct InitOptions {
+    /// Host-supplied entropy mixed into signing nonce seeds
+    #[tsify(type = "Uint8Array")]
+    extra_entropy: Option<ByteBuf>,
+    /// `"json"` (default) or `"cbor"`: encoding of new blobs and signing
+    /// messages when a call doesn't name one
+    encoding: Option<String>,
+    /// Refuse to sign or combine shares tagged for another environment
+    expected_environment: Option<String>,
+    /// Limits to start with (see `update_config`)
+    #[cfg(feature = "signing")]
+    #[serde(flatten)]
+    config: config::ConfigUpdate,
+}
+
+/// Check the OS entropy source and take optional extra entropy from the
+/// host; call before starting any ceremony.
 ///
-/// This is the FAST path — Paillier prime generation (~30-60s per party) is
-/// skipped because primes were generated ahead of time (e.g. during server
-/// startup in a background worker thread).
+/// `options`: `{ extra_entropy?: Uint8Array, encoding?: "json" | "cbor",
+/// expected_environment?: string, ...ConfigUpdate }`. Extra entropy is
+/// XORed into every signing session's nonce seed on top of OS randomness,
+/// never in place of it; calling `init` again adds to it. `encoding` becomes the default of every call
+/// that takes one (DKG and `combine_key_share` blobs, signing messages);
+/// blobs are read in either encoding regardless. With `expected_environment`,
+/// signing and `combine_key_share` fail with `ENVIRONMENT_MISMATCH` on a
+/// share whose metadata names another environment. The configuration fields
+/// (signing builds) are applied as `update_config` would; left out, the
+/// defaults hold.
+///
+/// `session_store` (signing builds) lets the host keep ECDSA signing
+/// sessions across a worker restart: `sign_process_round` saves each
+/// session's export through `save(session_id, bytes)` after every round
+/// (and on creation), deletes it through `delete(session_id)` once the
+/// session is over, and for an id it doesn't hold first asks
+/// `load(session_id)` for one to import. Exports carry no key material
+/// unless `include_key_material: true` — restoring one then needs its key
+/// registered (`register_key`) — but do carry the session's nonce seed.
+/// Callbacks are synchronous; one that throws is logged to the console and
+/// ignored. `null` removes the store.
 ///
-/// `serialized_primes` is a JS array of `Uint8Array`, one per party,
-/// each being the serde_json serialization of `PregeneratedPrimes`.
+/// Fails with `ENTROPY_UNAVAILABLE` if `crypto.getRandomValues` is missing
+/// or returns obviously broken output, `INVALID_ENCODING` for an unknown
+/// encoding, `INVALID_CONFIG` for a bad configuration field and
+/// `INVALID_SESSION_STORE` for a store missing a callback, before anything
+/// is applied.
 #[wasm_bindgen]
-pub fn run_dkg_with_primes(
-    eid_bytes: &[u8],
-    n: u16,
-    threshold: u16,
-    serialized_primes: JsValue,
-) -> Result<JsValue, JsError> {
-    if n < 2 {
-        return Err(JsError::new("n must be at least 2"));
-    }
-    if threshold < 2 || threshold > n {
-        return Err(JsError::new(&format!(
-            "threshold must be in [2, {n}], got {threshold}"
-        )));
-    }
-
-    // Deserialize the pre-generated primes from JS
-    let primes_bytes: Vec<Vec<u8>> = serde_wasm_bindgen::from_value(serialized_primes)
-        .map_err(|e| JsError::new(&format!("deserialize primes array: {e}")))?;
-
-    if primes_bytes.len() < n as usize {
-        return Err(JsError::new(&format!(
-            "need {} sets of primes, got {}",
-            n,
-            primes_bytes.len()
-        )));
+pub fn init(options: Option<JsInitOptions>) -> Result<(), JsError> {
+    let options: Option<JsValue> = options.map(Into::into);
+    #[cfg(feature = "signing")]
+    let (options, store) = session_store::take(options).map_err(|e| JsError::new(&e))?;
+    let options: InitOptions = options_from_js(options, "init options")?;
+    let encoding = options
+        .encoding
+        .as_deref()
+        .map(Encoding::parse)
+        .transpose()
+        .map_err(|e| JsError::new(&e))?;
+    entropy::check().map_err(|e| JsError::new(&e))?;
+    #[cfg(feature = "signing")]
+    config::update(&options.config).map_err(|e| JsError::new(&e))?;
+    #[cfg(feature = "signing")]
+    if let Some(store) = store {
+        session_store::install(store);
     }
-
-    // Phase A: Auxiliary Info Generation (using pre-generated primes — FAST)
-    let mut aux_parties = Vec::new();
-    for i in 0..n {
-        let eid = cggmp24::ExecutionId::new(eid_bytes);
-        let primes: cggmp24::PregeneratedPrimes<SecurityLevel128> =
-            serde_json::from_slice(&primes_bytes[i as usize])
-                .map_err(|e| JsError::new(&format!("deserialize primes for party {i}: {e}")))?;
-        aux_parties.push(round_based::state_machine::wrap_protocol(
-            move |party| async move {
-                let mut rng = OsRng;
-                cggmp24::aux_info_gen(eid, i, n, primes)
-                    .start(&mut rng, party)
-                    .await
-            },
-        ));
+    if let Some(encoding) = encoding {
+        share_format::set_default_encoding(encoding);
     }
-
-    let aux_results = simulate::run(aux_parties)
-        .map_err(|e| JsError::new(&format!("aux_info_gen failed: {e}")))?;
-
-    let mut aux_infos = Vec::new();
-    for (i, result) in aux_results.into_iter().enumerate() {
-        let aux = result
-            .map_err(|e| JsError::new(&format!("aux_info_gen party {i} failed: {e:?}")))?;
-        aux_infos.push(aux);
+    if let Some(extra) = options.extra_entropy {
+        rng::add_extra(&extra);
     }
-
-    // Phase B: Key Generation (lightweight: ~2-5s)
-    let mut kg_parties = Vec::new();
-    for i in 0..n {
-        let eid = cggmp24::ExecutionId::new(eid_bytes);
-        kg_parties.push(round_based::state_machine::wrap_protocol(
-            move |party| async move {
-                let mut rng = OsRng;
-                cggmp24::keygen::<Secp256k1>(eid, i, n)
-                    .set_threshold(threshold)
-                    .start(&mut rng, party)
-                    .await
-            },
-        ));
+    if options.expected_environment.is_some() {
+        share_metadata::set_expected_environment(options.expected_environment);
     }
+    Ok(())
+}
 
-    let kg_results = simulate::run(kg_parties)
-        .map_err(|e| JsError::new(&format!("keygen failed: {e}")))?;
-
-    let mut core_shares = Vec::new();
-    for (i, result) in kg_results.into_iter().enumerate() {
-        let share = result
-            .map_err(|e| JsError::new(&format!("keygen party {i} failed: {e:?}")))?;
-        core_shares.push(share);
-    }
+/// The configuration in effect.
+///
+/// # Returns
+/// JS object: `{ max_sessions, max_message_bytes, max_messages_per_round, max_messages_per_session,
+/// share_cache_capacity, max_parties }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn get_config() -> Result<JsGuardianConfig, JsError> {
+    to_js(&config::current())
+}
 
-    // Extract shared public key (same for all parties)
-    let pk = core_shares[0].shared_public_key();
-    let pk_bytes = pk.to_bytes(true); // 33-byte compressed
+/// Change the configuration at runtime, e.g. lower `max_sessions` under
+/// memory pressure. Fields left out keep their value; lowering a limit
+/// ends no live session, but new sessions are held to it.
+///
+/// `partial`: any of `{ max_sessions, max_message_bytes, max_messages_per_round,
+/// max_messages_per_session, share_cache_capacity, max_parties }`. Fails
+/// with `INVALID_CONFIG` naming the field for a value that isn't a whole
+/// number in its range (negative, fractional, zero where a cap must be
+/// positive), applying none of the update.
+///
+/// # Returns
+/// The configuration now in effect.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn update_config(partial: Option<JsConfigUpdate>) -> Result<JsGuardianConfig, JsError> {
+    let update: config::ConfigUpdate = options_from_js(partial, "config")?;
+    let config = config::update(&update).map_err(|e| JsError::new(&e))?;
+    to_js(&config)
+}
 
-    // Serialize each party's key material
-    let mut shares = Vec::new();
-    for i in 0..n as usize {
-        let core_bytes = serde_json::to_vec(&core_shares[i])
-            .map_err(|e| JsError::new(&format!("serialize core share {i}: {e}")))?;
-        let aux_bytes = serde_json::to_vec(&aux_infos[i])
-            .map_err(|e| JsError::new(&format!("serialize aux info {i}: {e}")))?;
-        shares.push(DkgShare {
-            core_share: core_bytes,
-            aux_info: aux_bytes,
-        });
-    }
+/// Draw 32 bytes from the OS rng and sanity-check them (not all zeros, not
+/// one repeated byte). Fails with `ENTROPY_UNAVAILABLE`.
+#[wasm_bindgen]
+pub fn check_entropy() -> Result<(), JsError> {
+    entropy::check().map_err(|e| JsError::new(&e))
+}
 
-    let result = DkgResult {
-        shares,
-        public_key: pk_bytes.as_bytes().to_vec(),
-    };
+/// Features reported by `capabilities`: the cargo features this build was
+/// compiled with, then the protocols it carries.
+const FEATURES: &[(&str, bool)] = &[
+    ("dkg", cfg!(feature = "dkg")),
+    ("signing", cfg!(feature = "signing")),
+    ("primes", cfg!(feature = "primes")),
+    ("test-fixtures", cfg!(feature = "test-fixtures")),
+    ("fixtures", cfg!(feature = "fixtures")),
+    ("insecure-dev", cfg!(feature = "insecure-dev")),
+    ("ecdsa", cfg!(any(feature = "dkg", feature = "signing"))),
+    ("frost", cfg!(any(feature = "dkg", feature = "signing"))),
+];
 
-    serde_wasm_bindgen::to_value(&result).map_err(|e| JsError::new(&e.to_string()))
+/// A new cancellation token for the long-running exports (`cancel_token`
+/// in the DKG options, `pregenerate_paillier_primes`,
+/// `sign_process_round`). Fails with `TOO_MANY_TOKENS` when 1024 are live;
+/// `release_cancel_token` the ones you are done with.
+///
+/// WASM runs one call at a time, so a running export only sees a cancel
+/// made before it started or from a callback it invokes (`on_share` of
+/// `run_dkg_streaming`).
+#[wasm_bindgen]
+pub fn create_cancel_token() -> Result<u32, JsError> {
+    cancel::create().map_err(|e| JsError::new(&e))
 }
 
-// ─── Utility Functions ───────────────────────────────────────────────────────
+/// Cancel `token`: calls using it stop at their next safe point with
+/// `CANCELLED: {"stage","completed","total"}`. Returns false for an unknown
+/// token.
+#[wasm_bindgen]
+pub fn cancel(token: u32) -> bool {
+    cancel::cancel(token)
+}
 
-/// Combine a CoreKeyShare (from keygen) with AuxInfo (from aux_info_gen)
-/// into a full KeyShare suitable for signing.
-///
-/// Returns the serialised KeyShare bytes.
+/// Forget `token`. Returns false for an unknown token.
 #[wasm_bindgen]
-pub fn combine_key_share(
-    core_key_share: &[u8],
-    aux_info: &[u8],
-) -> Result<Vec<u8>, JsError> {
-    let iks: cggmp24::IncompleteKeyShare<Secp256k1> = serde_json::from_slice(core_key_share)
-        .map_err(|e| JsError::new(&format!("deserialize CoreKeyShare: {e}")))?;
+pub fn release_cancel_token(token: u32) -> bool {
+    cancel::release(token)
+}
 
-    let aux: cggmp24::key_share::AuxInfo<SecurityLevel128> = serde_json::from_slice(aux_info)
-        .map_err(|e| JsError::new(&format!("deserialize AuxInfo: {e}")))?;
+/// What this build supports: `{ crate_version, cggmp24_version,
+/// bignum_backend, share_format_versions, message_version?, curves,
+/// digests, features }`; `bignum_backend` is always `"num-bigint"` here.
+/// `message_version` is absent from builds without `signing`.
+#[wasm_bindgen]
+pub fn capabilities() -> Result<JsCapabilities, JsError> {
+    #[cfg(feature = "signing")]
+    let message_version = Some(sign::MESSAGE_VERSION);
+    #[cfg(not(feature = "signing"))]
+    let message_version = None;
+    let features: Vec<&str> = FEATURES.iter().filter(|(_, on)| *on).map(|(name, _)| *name).collect();
+    to_js(&capabilities::capabilities(message_version, "num-bigint", &features))
+}
 
-    let key_share = cggmp24::KeyShare::from_parts((iks, aux))
-        .map_err(|e| JsError::new(&format!("combine key share: {e}")))?;
+/// Whether this build can read the blob's header (version, encoding and
+/// kind / curve / level tags); false means a newer writer produced it.
+/// The payload itself isn't checked.
+#[wasm_bindgen]
+pub fn is_share_compatible(bytes: JsBytes) -> Result<bool, JsError> {
+    let bytes = js_bytes(bytes, "bytes")?;
+    Ok(share_format::is_compatible(&bytes))
+}
 
-    serde_json::to_vec(&key_share)
-        .map_err(|e| JsError::new(&format!("serialize KeyShare: {e}")))
+/// What a blob's header says about it. Headerless (legacy) blobs report
+/// version 0 and nothing else.
+#[derive(Serialize, Tsify)]
+struct ShareInfo {
+    /// Header version; 0 for legacy blobs. Backup bundles: the bundle
+    /// format version
+    version: u8,
+    /// `"core_share"`, `"aux_info"`, `"key_share"`, `"primes"`,
+    /// `"frost_key_share"` or `"backup_bundle"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    kind: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    curve: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    security_level: Option<&'static str>,
+    /// Minted by `run_dkg_trusted_dealer`: never hold real funds with it
+    dealer_generated: bool,
+    /// Part of a single-party key (`allow_single_party`): development only,
+    /// never hold real funds with it
+    single_party: bool,
+    /// Payload encoding: `"json"`, or `"cbor"` after `migrate_share`;
+    /// `"backup"` for a backup bundle
+    encoding: &'static str,
+    /// What a backup bundle says about its share; open it with
+    /// `read_backup_bundle` for the share's own header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    backup: Option<backup::BackupMetadata>,
 }
 
-/// Extract the shared public key from a serialised KeyShare or CoreKeyShare.
+/// Read a share, aux info or primes blob's header. Fails like
+/// `is_share_compatible` would return false; the payload isn't checked.
+/// For a legacy (headerless) blob, `curve` / `security_level` are what its
+/// fields show, when they show it; signing and `combine_key_share` check
+/// the same values (`CURVE_MISMATCH`, `SECURITY_LEVEL_MISMATCH`).
 ///
-/// Returns 33-byte compressed secp256k1 public key.
+/// A backup bundle reports kind `"backup_bundle"` and its metadata under
+/// `backup`, checksum verified; an encrypted one needs no passphrase here.
+///
+/// # Returns
+/// JS object: `{ version, kind?, curve?, security_level?, dealer_generated, single_party, encoding, backup? }`
 #[wasm_bindgen]
-pub fn extract_public_key(key_share_bytes: &[u8]) -> Result<Vec<u8>, JsError> {
-    // Try as full KeyShare first
-    if let Ok(ks) =
-        serde_json::from_slice::<cggmp24::KeyShare<Secp256k1, SecurityLevel128>>(key_share_bytes)
-    {
-        let pk = ks.shared_public_key();
-        let encoded = pk.to_bytes(true);
-        return Ok(encoded.as_bytes().to_vec());
+pub fn get_share_info(bytes: JsBytes) -> Result<JsShareInfo, JsError> {
+    let bytes = js_bytes(bytes, "bytes")?;
+    if backup::is_bundle(&bytes) {
+        let metadata = backup::inspect(&bytes).map_err(|e| JsError::new(&e))?;
+        return to_js(&ShareInfo {
+            version: bytes[4],
+            kind: Some("backup_bundle"),
+            curve: CurveId::parse(&metadata.curve).ok().map(CurveId::as_str),
+            security_level: None,
+            dealer_generated: false,
+            single_party: false,
+            encoding: "backup",
+            backup: Some(metadata),
+        });
     }
+    let (header, _) = share_format::split(&bytes).map_err(|e| JsError::new(&e))?;
+    to_js(&ShareInfo {
+        version: header.version,
+        kind: header.kind.map(|kind| kind.as_str()),
+        curve: header.curve.map(|curve| curve.as_str()),
+        security_level: header.level.map(|level| level.as_str()),
+        dealer_generated: header.dealer_generated(),
+        single_party: header.single_party(),
+        encoding: header.encoding.as_str(),
+        backup: None,
+    })
+}
 
-    // Try as CoreKeyShare (IncompleteKeyShare)
-    if let Ok(iks) =
-        serde_json::from_slice::<cggmp24::IncompleteKeyShare<Secp256k1>>(key_share_bytes)
-    {
-        let pk = iks.shared_public_key();
-        let encoded = pk.to_bytes(true);
-        return Ok(encoded.as_bytes().to_vec());
-    }
+/// `create_backup_bundle` options.
+#[derive(Deserialize, Default, Tsify)]
+#[serde(default)]
+struct BackupOptions {
+    /// Unix seconds to record (default: now)
+    #[tsify(optional)]
+    created_at: Option<u64>,
+    /// Free text to tell backups apart, at most 256 bytes
+    #[tsify(optional)]
+    label: Option<String>,
+}
 
-    Err(JsError::new(
-        "failed to deserialize as KeyShare or CoreKeyShare",
-    ))
+/// An opened backup bundle.
+#[derive(Serialize, Tsify)]
+struct BackupContents {
+    metadata: backup::BackupMetadata,
+    /// The share exactly as it was bundled
+    #[tsify(type = "Uint8Array")]
+    share: ByteBuf,
 }
 
-/// Pre-generate Paillier primes for aux_info_gen.
+/// Bundle a KeyShare or CoreKeyShare for cold storage: one file carrying
+/// the share and its wallet id, party index, threshold, `n`, curve, public
+/// key, creation time and label, with a SHA-256 checksum over it all.
 ///
-/// This is the expensive part (~30-60s). Call this ahead of time
-/// and store the result. Pass serialised primes to speed up DKG.
+/// With a `passphrase`, the share (not the metadata) is encrypted as
+/// `encrypt_share` would, at the default KDF cost.
 ///
-/// Returns serialised PregeneratedPrimes.
+/// `metadata` is an optional `{ created_at?, label? }`. Fails with
+/// `INVALID_SHARE` or `INVALID_LABEL`.
 #[wasm_bindgen]
-pub fn pregenerate_paillier_primes() -> Result<Vec<u8>, JsError> {
-    let primes: cggmp24::PregeneratedPrimes<SecurityLevel128> =
-        cggmp24::PregeneratedPrimes::generate(&mut OsRng);
-    serde_json::to_vec(&primes).map_err(|e| JsError::new(&format!("serialize primes: {e}")))
+pub fn create_backup_bundle(
+    share: JsBytes,
+    metadata: Option<JsBackupOptions>,
+    passphrase: Option<String>,
+) -> Result<Vec<u8>, JsError> {
+    let share = js_bytes(share, "share")?;
+    let options: BackupOptions = options_from_js(metadata, "backup options")?;
+    let created_at = options
+        .created_at
+        .unwrap_or_else(|| (js_sys::Date::now() / 1000.0) as u64);
+    backup::create_backup_bundle(&share, created_at, options.label.as_deref(), passphrase.as_deref())
+        .map_err(|e| JsError::new(&e))
 }
 
-// ─── Interactive Signing ────────────────────────────────────────────────────
-
-/// Create an interactive signing session for one party.
+/// Open a bundle made by `create_backup_bundle`, checking its checksum and
+/// that the share inside matches the metadata.
 ///
-/// # Arguments
-/// - `core_share`: serialised CoreKeyShare (serde_json bytes)
-/// - `aux_info`: serialised AuxInfo (serde_json bytes)
-/// - `message_hash`: 32-byte hash to sign
-/// - `party_index`: this party's index at keygen time (0-based)
-/// - `parties_at_keygen`: array of party indices participating in signing
-/// - `eid`: execution ID bytes (32 bytes)
+/// `passphrase` is required if the share is encrypted
+/// (`PASSPHRASE_REQUIRED`); a wrong one fails with `DECRYPT_AUTH_FAILED`.
+/// A damaged bundle fails with `BACKUP_CORRUPTED`.
 ///
 /// # Returns
-/// JS object: `{ session_id: string, messages: WasmSignMessage[] }`
+/// JS object: `{ metadata, share }`
 #[wasm_bindgen]
-pub fn sign_create_session(
-    core_share: &[u8],
-    aux_info: &[u8],
-    message_hash: &[u8],
-    party_index: u16,
-    parties_at_keygen: &[u16],
-    eid: &[u8],
-) -> Result<JsValue, JsError> {
-    let result = sign::create_session(
-        core_share,
-        aux_info,
-        message_hash,
-        party_index,
-        parties_at_keygen,
-        eid,
-    )
-    .map_err(|e| JsError::new(&e))?;
-
-    serde_wasm_bindgen::to_value(&result).map_err(|e| JsError::new(&e.to_string()))
+pub fn read_backup_bundle(bytes: JsBytes, passphrase: Option<String>) -> Result<JsBackupContents, JsError> {
+    let bytes = js_bytes(bytes, "bytes")?;
+    let opened = backup::read_backup_bundle(&bytes, passphrase.as_deref()).map_err(|e| JsError::new(&e))?;
+    let mut contents = BackupContents {
+        metadata: opened.metadata,
+        share: ByteBuf::from(opened.share.to_vec()),
+    };
+    let result = to_js(&contents);
+    // Copied out to JS; wipe the Rust-side buffer
+    contents.share.zeroize();
+    result
 }
 
-/// Process a round of incoming messages for an existing signing session.
+/// Split a share (or a backup bundle) into `m` fragments, any `k` of which
+/// `recover_share` puts back together: Shamir secret sharing over GF(256),
+/// for redundancy at rest only — it doesn't touch the key's threshold.
+/// Each fragment carries a split id, `k`, `m`, the share's SHA-256 and a
+/// checksum, so damaged or mismatched fragments are named on recovery.
 ///
-/// # Arguments
-/// - `session_id`: the session ID returned by `sign_create_session`
-/// - `incoming_messages`: JS array of `WasmSignMessage` objects
+/// Fails with `INVALID_SPLIT` unless 2 ≤ `k` ≤ `m` ≤ 255.
+#[wasm_bindgen]
+pub fn split_share(share: JsBytes, k: u8, m: u8) -> Result<JsShareFragments, JsError> {
+    let share = js_bytes(share, "share")?;
+    let fragments = backup::split_share(&share, k, m).map_err(|e| JsError::new(&e))?;
+    let fragments: Vec<ByteBuf> = fragments.into_iter().map(ByteBuf::from).collect();
+    to_js(&fragments)
+}
+
+/// Recover a share from at least `k` fragments of one `split_share`, in any
+/// order.
 ///
-/// # Returns
-/// JS object: `{ messages: WasmSignMessage[], complete: bool, signature?: { r, s } }`
+/// Fails with `FRAGMENT_CORRUPTED` naming a damaged fragment,
+/// `FRAGMENT_MISMATCH` for one of another split, `DUPLICATE_FRAGMENT`,
+/// `TOO_FEW_FRAGMENTS`, `INVALID_FRAGMENT` for something that isn't a
+/// fragment, and `BACKUP_CORRUPTED` if the fragments don't combine to the
+/// share they were split from.
 #[wasm_bindgen]
-pub fn sign_process_round(
-    session_id: &str,
-    incoming_messages: JsValue,
-) -> Result<JsValue, JsError> {
-    let incoming: Vec<sign::WasmSignMessage> = serde_wasm_bindgen::from_value(incoming_messages)
-        .map_err(|e| JsError::new(&format!("deserialize incoming messages: {e}")))?;
+pub fn recover_share(fragments: JsByteArrays) -> Result<Vec<u8>, JsError> {
+    let fragments = byte_arrays_from_js(fragments, "fragments")?;
+    let share = backup::recover_share(&fragments).map_err(|e| JsError::new(&e))?;
+    // Copy out to JS; the Rust-side buffer is wiped when `share` drops.
+    Ok(share.to_vec())
+}
 
-    let result = sign::process_round(session_id, &incoming)
-        .map_err(|e| JsError::new(&e))?;
+/// Convert a JSON share, aux info or primes blob (legacy or headered) to
+/// the binary (CBOR) encoding, about half the size. Kind, curve, level and
+/// flags carry over; the result is checked to decode to the same value and,
+/// for shares, the same public key before it is returned.
+///
+/// Fails with `ALREADY_MIGRATED`, `INVALID_SHARE` or
+/// `MIGRATION_VERIFY_FAILED`. Binary blobs need a reader from this release
+/// on; keep the JSON original until every peer has upgraded.
+#[wasm_bindgen]
+pub fn migrate_share(bytes: JsBytes) -> Result<Vec<u8>, JsError> {
+    let bytes = js_bytes(bytes, "bytes")?;
+    migrate::migrate_share(&bytes)
+        .map(|migrated| migrated.blob)
+        .map_err(|e| JsError::new(&e))
+}
 
-    serde_wasm_bindgen::to_value(&result).map_err(|e| JsError::new(&e.to_string()))
+/// Wrap a share (any blob `get_share_info` reads) with a label, an
+/// environment tag, a wallet id and a creation time, replacing metadata it
+/// already carries. The wrapped share signs, combines and migrates like the
+/// bare one and keeps its fingerprint.
+///
+/// `metadata`: `{ label?, environment?, wallet_id?, created_at? }`. The
+/// metadata is stored in the clear and unauthenticated.
+///
+/// Fails with `INVALID_METADATA` for more than 4 KiB of metadata or a
+/// damaged envelope.
+#[wasm_bindgen]
+pub fn attach_metadata(share: JsBytes, metadata: JsShareMetadata) -> Result<Vec<u8>, JsError> {
+    let share = js_bytes(share, "share")?;
+    let metadata: share_metadata::ShareMetadata = serde_wasm_bindgen::from_value(metadata.into())
+        .map_err(|e| JsError::new(&format!("deserialize metadata: {e}")))?;
+    share_metadata::attach_metadata(&share, &metadata).map_err(|e| JsError::new(&e))
 }
 
-/// Destroy a signing session and free all resources.
+/// The metadata `attach_metadata` put on a share; `undefined` for a share
+/// without any.
 ///
-/// Returns `true` if the session existed and was destroyed.
+/// Fails with `INVALID_METADATA` for a damaged envelope.
 #[wasm_bindgen]
-pub fn sign_destroy_session(session_id: &str) -> bool {
-    sign::destroy_session(session_id)
+pub fn read_metadata(bytes: JsBytes) -> Result<JsShareMetadataOrUndefined, JsError> {
+    let bytes = js_bytes(bytes, "bytes")?;
+    to_js(&share_metadata::read_metadata(&bytes).map_err(|e| JsError::new(&e))?)
+}
+
+// ─── DKG Result Types ───────────────────────────────────────────────────────
+
+/// A single party's key material from DKG.
+#[cfg(feature = "dkg")]
+#[derive(Serialize, Deserialize, Tsify)]
+struct DkgShare {
+    /// Serialised CoreKeyShare (serde_json bytes)
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
+    core_share: Vec<u8>,
+    /// Serialised AuxInfo (serde_json bytes, headered unless SecurityLevel128)
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
+    aux_info: Vec<u8>,
+}
+
+/// Complete DKG result: key shares for all parties + shared public key.
+#[cfg(feature = "dkg")]
+#[derive(Serialize, Deserialize, Tsify)]
+struct DkgResult {
+    /// One DkgShare per party (index 0..n)
+    shares: Vec<DkgShare>,
+    #[serde(flatten)]
+    summary: DkgSummary,
+}
+
+/// Everything in a [`DkgResult`] but the shares; all `run_dkg_streaming`
+/// returns.
+#[cfg(feature = "dkg")]
+#[derive(Serialize, Deserialize, Tsify)]
+struct DkgSummary {
+    /// Shared public key: 33-byte compressed unless `public_key_format`
+    /// asked for `"uncompressed"` (65 bytes)
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
+    public_key: Vec<u8>,
+    /// 65-byte uncompressed key, with `public_key_format: "both"`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[tsify(optional, type = "Uint8Array")]
+    public_key_uncompressed: Option<ByteBuf>,
+    /// `wallet_id_from_pubkey(compressed public key)`
+    wallet_id: String,
+    /// `share_fingerprint(core_share)` per party, in `shares` order
+    share_fingerprints: Vec<String>,
+    /// Label of each party, in `shares` order: the `indices` given to
+    /// `run_keygen_for_parties`, `0..n` otherwise. Signing names the
+    /// parties by these
+    #[serde(default)]
+    party_labels: Vec<u16>,
+    /// `run_dkg_with_primes`: fingerprint of each party's primes, in
+    /// `shares` order, for the caller's `used_primes` list
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[tsify(optional)]
+    primes_fingerprints: Vec<String>,
+    /// `run_dkg_with_aux`: the aux set's metadata, as passed in
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[tsify(optional)]
+    aux_set: Option<AuxSetInfo>,
+}
+
+/// A pregenerated aux set's metadata (see `run_dkg_with_aux`).
+#[cfg(feature = "dkg")]
+#[derive(Serialize, Deserialize, Tsify)]
+struct AuxSetInfo {
+    /// Unix seconds; 0 for sets generated before the field existed
+    generated_at: u64,
+    /// Seconds since `generated_at`, at the call
+    age_secs: u64,
+    /// `"128"` / `"192"`; empty for sets generated before the field existed
+    security_level: String,
+    /// DKGs the set had been handed to before this one
+    uses: u32,
+}
+
+#[cfg(feature = "dkg")]
+impl DkgSummary {
+    /// Re-encode `public_key` (compressed out of the ceremony) as asked.
+    fn with_public_key_format(mut self, curve: CurveId, format: PointFormat) -> Result<Self, String> {
+        match format {
+            PointFormat::Compressed => {}
+            PointFormat::Uncompressed => {
+                self.public_key = key_material::recode_public_key(curve, &self.public_key, false)?;
+            }
+            PointFormat::Both => {
+                let uncompressed = key_material::recode_public_key(curve, &self.public_key, false)?;
+                self.public_key_uncompressed = Some(ByteBuf::from(uncompressed));
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// Options accepted by the DKG exports (all fields optional).
+#[cfg(feature = "dkg")]
+#[derive(Deserialize, Default, Tsify)]
+#[serde(default)]
+struct DkgOptions {
+    /// `"128"` (default) or `"192"`
+    security_level: Option<String>,
+    /// `"secp256k1"` (default) or `"secp256r1"`
+    curve: Option<String>,
+    /// `"compressed"` (default), `"uncompressed"` or `"both"` — the
+    /// encoding of `DkgResult.public_key`; see `extract_public_key`
+    public_key_format: Option<String>,
+    /// `"json"` or `"cbor"`: encoding of the share and aux blobs; defaults
+    /// to the one set at `init`
+    encoding: Option<String>,
+    /// Token from `create_cancel_token`; the ceremony stops with
+    /// `CANCELLED` at the next safe point once it is cancelled
+    cancel_token: Option<u32>,
+    /// `run_dkg_with_primes`: fingerprints of primes consumed by earlier
+    /// ceremonies (their `primes_fingerprints`), refused with
+    /// `PRIMES_ALREADY_USED`
+    used_primes: Option<Vec<String>>,
+    /// `run_dkg_with_primes`: skip the `DUPLICATE_PRIMES` /
+    /// `PRIMES_ALREADY_USED` checks. Test environments only — parties
+    /// sharing primes share a Paillier modulus
+    allow_unsafe_prime_reuse: Option<bool>,
+    /// `run_dkg` / `run_dkg_with_primes`: accept `n = 1, threshold = 1`, a
+    /// development key whose one share holds the whole secret. Its blobs
+    /// are flagged `single_party` (see `get_share_info`)
+    allow_single_party: Option<bool>,
+    /// `run_dkg_with_aux`: refuse an aux set generated more than this many
+    /// seconds ago, or of unknown age (`AUX_SET_EXPIRED`)
+    max_aux_age_secs: Option<u64>,
+    /// `run_dkg_with_aux`: refuse an aux set already handed to this many
+    /// DKGs (`AUX_SET_EXHAUSTED`)
+    max_aux_uses: Option<u32>,
+}
+
+#[cfg(feature = "dkg")]
+fn validate_dkg_params(n: u16, threshold: u16) -> Result<(), JsError> {
+    party_limits::check_key_params(n, threshold).map_err(|e| JsError::new(&e))
+}
+
+/// [`validate_dkg_params`], also accepting the single-party key when the
+/// caller opted in.
+#[cfg(feature = "dkg")]
+fn validate_dkg_params_for(n: u16, threshold: u16, options: &DkgOptions) -> Result<(), JsError> {
+    if options.allow_single_party.unwrap_or(false) && n == 1 {
+        if threshold != 1 {
+            return Err(JsError::new(&format!(
+                "threshold must be 1 for a single-party key, got {threshold}"
+            )));
+        }
+        return Ok(());
+    }
+    validate_dkg_params(n, threshold)
+}
+
+/// Set the largest `n` the DKG exports accept (default 16). Ceremonies for
+/// more parties fail with `N_TOO_LARGE` before any work starts: aux info
+/// generation grows quadratically with `n`. Fails with `INVALID_LIMIT`
+/// below 2.
+#[cfg(feature = "dkg")]
+#[wasm_bindgen]
+pub fn set_max_parties(max: u16) -> Result<(), JsError> {
+    party_limits::set_max_parties(max).map_err(|e| JsError::new(&e))
+}
+
+// ─── Full DKG (all parties local) ────────────────────────────────────────────
+
+/// Run a complete two-phase DKG ceremony for `n` parties with threshold `t`.
+///
+/// Phase A: Auxiliary info generation (Paillier primes — computationally expensive)
+/// Phase B: Key generation (lightweight ECDSA key shares)
+///
+/// All parties run locally via protocol simulation. Returns a JSON object
+/// containing key shares for each party and the shared public key.
+///
+/// `options` may select `{ security_level: "192", curve: "secp256r1" }`;
+/// the defaults are 128 and secp256k1. `eid_bytes` may be an `EidSpec`
+/// (`{ wallet_id, purpose: "dkg", nonce? }`) instead of bytes.
+///
+/// The caller (server) distributes shares:
+/// - Share[0] → signer (encrypted .share.enc file)
+/// - Share[1] → server (stored in Vault)
+/// - Share[2] → user (wallet-encrypted, returned to browser)
+///
+/// This is `run_aux_for_parties` over freshly generated primes, then
+/// `run_keygen_for_parties`, for the parties `0..n`; call those two for
+/// other party indices ("the signer is always 7") or to run the phases
+/// apart.
+///
+/// `n` is at least 2 unless `options.allow_single_party`: then `n = 1,
+/// threshold = 1` gives a development key of one share, flagged as such,
+/// whose signing sessions complete in `sign_create_session`.
+///
+/// If converting the result to JS objects fails, this and the other
+/// `run_dkg*` exports return it as a JSON string marked
+/// `"__fallback_json": true` rather than throwing; `run_dkg_streaming`
+/// avoids the whole-result conversion altogether.
+#[cfg(feature = "dkg")]
+#[wasm_bindgen]
+pub fn run_dkg(
+    eid_bytes: JsEid,
+    n: u16,
+    threshold: u16,
+    options: Option<JsDkgOptions>,
+) -> Result<JsDkgResult, JsError> {
+    let eid_bytes = &js_eid(eid_bytes, "eid_bytes", eid::EidPurpose::Dkg)?;
+    collect_dkg(|on_share| run_dkg_with_rng(eid_bytes, n, threshold, options, &|_| MixedRng, on_share))
+}
+
+/// [`run_dkg`] that hands each party's `DkgShare` to
+/// `on_share(share, index)` as soon as it is serialised and keeps no copy,
+/// so only one party's blobs are ever held at once. Returns the rest of a
+/// `DkgResult` as a `DkgSummary`: public key, wallet id and fingerprints.
+///
+/// Use this where memory is tight (mobile browsers): a materialised n=5
+/// result holds every share twice over during the JS conversion. If
+/// `on_share` throws, the ceremony stops with `SHARE_CALLBACK_FAILED`;
+/// shares already delivered belong to a key that will never be completed
+/// and should be discarded.
+#[cfg(feature = "dkg")]
+#[wasm_bindgen]
+pub fn run_dkg_streaming(
+    eid_bytes: JsEid,
+    n: u16,
+    threshold: u16,
+    on_share: js_sys::Function,
+    options: Option<JsDkgOptions>,
+) -> Result<JsDkgSummary, JsError> {
+    let eid_bytes = &js_eid(eid_bytes, "eid_bytes", eid::EidPurpose::Dkg)?;
+    let mut deliver = |i: u16, share: DkgShare| {
+        // Copied into the JS heap here; the Rust buffers go with `share`
+        let share = share
+            .serialize(&serde_wasm_bindgen::Serializer::new())
+            .map_err(|e| format!("party {i}: {e}"))?;
+        on_share
+            .call2(&JsValue::NULL, &share, &JsValue::from(i))
+            .map(drop)
+            .map_err(|e| format!("SHARE_CALLBACK_FAILED: party {i}: {e:?}"))
+    };
+    let summary = run_dkg_with_rng(eid_bytes, n, threshold, options, &|_| MixedRng, &mut deliver)?;
+    to_js(&summary)
+}
+
+/// Where a DKG hands each party's share: `(label, share)`, in keygen
+/// index order (see `DkgSummary::party_labels`).
+#[cfg(feature = "dkg")]
+type ShareSink<'a> = dyn FnMut(u16, DkgShare) -> Result<(), String> + 'a;
+
+/// Run a DKG into a fully materialised [`DkgResult`].
+///
+/// Should converting it to JS objects fail (large `n` can exhaust the JS
+/// heap part-way), the result comes back as a JSON string instead: the
+/// `DkgResult` with its byte fields as number arrays, plus
+/// `"__fallback_json": true` and `"__fallback_reason"` naming the share
+/// that failed. The ceremony's output isn't lost to a conversion error.
+#[cfg(feature = "dkg")]
+fn collect_dkg(
+    run: impl FnOnce(&mut ShareSink<'_>) -> Result<DkgSummary, JsError>,
+) -> Result<JsDkgResult, JsError> {
+    let mut shares = Vec::new();
+    let summary = run(&mut |_, share: DkgShare| {
+        shares.push(share);
+        Ok(())
+    })?;
+    let result = DkgResult { shares, summary };
+    dkg_result_to_js(&result).or_else(|reason| dkg_result_fallback(&result, &reason))
+}
+
+/// [`to_js`] for a [`DkgResult`], one share at a time, so a failure names
+/// the share and no single conversion holds more than one share's buffers.
+#[cfg(feature = "dkg")]
+fn dkg_result_to_js(result: &DkgResult) -> Result<JsDkgResult, String> {
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+    let shares = js_sys::Array::new();
+    for (i, share) in result.shares.iter().enumerate() {
+        let share = share.serialize(&serializer).map_err(|e| format!("share {i}: {e}"))?;
+        shares.push(&share);
+    }
+    let object = result.summary.serialize(&serializer).map_err(|e| format!("summary: {e}"))?;
+    js_sys::Reflect::set(&object, &JsValue::from_str("shares"), &shares)
+        .map_err(|e| format!("shares: {e:?}"))?;
+    Ok(object.unchecked_into())
+}
+
+/// What [`collect_dkg`] returns when [`dkg_result_to_js`] fails.
+#[cfg(feature = "dkg")]
+#[derive(Serialize)]
+struct DkgResultFallback<'a> {
+    #[serde(rename = "__fallback_json")]
+    fallback_json: bool,
+    #[serde(rename = "__fallback_reason")]
+    fallback_reason: &'a str,
+    #[serde(flatten)]
+    result: &'a DkgResult,
+}
+
+#[cfg(feature = "dkg")]
+fn dkg_result_fallback(result: &DkgResult, reason: &str) -> Result<JsDkgResult, JsError> {
+    let fallback = DkgResultFallback { fallback_json: true, fallback_reason: reason, result };
+    let json = serde_json::to_string(&fallback)
+        .map_err(|e| JsError::new(&format!("serialize dkg result: {e} (JS conversion failed at {reason})")))?;
+    Ok(JsValue::from_str(&json).unchecked_into())
+}
+
+/// [`run_dkg`] with party `i` drawing its (non-prime) randomness from
+/// `party_rng(i)`, its shares going to `on_share`.
+#[cfg(feature = "dkg")]
+fn run_dkg_with_rng<R: RngCore + CryptoRng + 'static>(
+    eid_bytes: &[u8],
+    n: u16,
+    threshold: u16,
+    options: Option<JsDkgOptions>,
+    party_rng: &impl Fn(usize) -> R,
+    on_share: &mut ShareSink<'_>,
+) -> Result<DkgSummary, JsError> {
+    let options: DkgOptions = options_from_js(options, "dkg options")?;
+    validate_dkg_params_for(n, threshold, &options)?;
+    let level = Level::parse_opt(options.security_level.as_deref()).map_err(|e| JsError::new(&e))?;
+    let curve = CurveId::parse_opt(options.curve.as_deref()).map_err(|e| JsError::new(&e))?;
+    let format = PointFormat::parse_opt(options.public_key_format.as_deref())
+        .map_err(|e| JsError::new(&e))?;
+    let encoding = Encoding::parse_opt(options.encoding.as_deref()).map_err(|e| JsError::new(&e))?;
+
+    let token = options.cancel_token;
+    with_curve!(curve, E => with_security_level!(level, L => {
+        // Phase A primes: generates Paillier key pairs for each party
+        // (expensive: ~30-60s per party)
+        (0..n)
+            .map(|i| {
+                cancel::check(token, "primes", usize::from(i), usize::from(n))?;
+                Ok(cggmp24::PregeneratedPrimes::<L>::generate(&mut MixedRng))
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .and_then(|primes| {
+                run_dkg_inner::<E, L, R>(
+                    eid_bytes,
+                    &positions(n),
+                    threshold,
+                    curve,
+                    level,
+                    encoding,
+                    primes,
+                    party_rng,
+                    on_share,
+                    token,
+                )
+            })
+    }))
+    .and_then(|summary| summary.with_public_key_format(curve, format))
+    .map_err(|e| JsError::new(&e))
+}
+
+// ─── DKG with Pre-generated Primes (fast path) ──────────────────────────────
+
+/// Run a complete two-phase DKG ceremony using pre-generated Paillier primes.
+///
+/// This is the FAST path — Paillier prime generation (~30-60s per party) is
+/// skipped because primes were generated ahead of time (e.g. during server
+/// startup in a background worker thread).
+///
+/// `serialized_primes` is a JS array of `Uint8Array`, one per party, each
+/// being a blob from `pregenerate_paillier_primes`. The blobs are copied in
+/// and parsed one at a time, so the call holds one raw blob at most next
+/// to the parsed sets (`memory_stats` reports `primes_peak_bytes`). The
+/// security level is taken from `options.security_level` or, failing that,
+/// from the blobs; every set must be of the same level.
+///
+/// Fails with `DUPLICATE_PRIMES: {"parties":[i,j]}` if two parties got the
+/// same primes, and `PRIMES_ALREADY_USED` for a set listed in
+/// `options.used_primes`, unless `options.allow_unsafe_prime_reuse`. The
+/// result's `primes_fingerprints` are what to add to that list.
+#[cfg(feature = "dkg")]
+#[wasm_bindgen]
+pub fn run_dkg_with_primes(
+    eid_bytes: JsEid,
+    n: u16,
+    threshold: u16,
+    serialized_primes: JsByteArrays,
+    options: Option<JsDkgOptions>,
+) -> Result<JsDkgResult, JsError> {
+    let eid_bytes = &js_eid(eid_bytes, "eid_bytes", eid::EidPurpose::Dkg)?;
+    collect_dkg(|on_share| {
+        run_dkg_with_primes_and_rng(eid_bytes, n, threshold, serialized_primes, options, &|_| MixedRng, on_share)
+    })
+}
+
+/// [`run_dkg_with_primes`] with party `i` drawing its randomness from
+/// `party_rng(i)`, its shares going to `on_share`.
+#[cfg(feature = "dkg")]
+fn run_dkg_with_primes_and_rng<R: RngCore + CryptoRng + 'static>(
+    eid_bytes: &[u8],
+    n: u16,
+    threshold: u16,
+    serialized_primes: JsByteArrays,
+    options: Option<JsDkgOptions>,
+    party_rng: &impl Fn(usize) -> R,
+    on_share: &mut ShareSink<'_>,
+) -> Result<DkgSummary, JsError> {
+    let options: DkgOptions = options_from_js(options, "dkg options")?;
+    validate_dkg_params_for(n, threshold, &options)?;
+    let requested = options
+        .security_level
+        .as_deref()
+        .map(Level::parse)
+        .transpose()
+        .map_err(|e| JsError::new(&e))?;
+    let curve = CurveId::parse_opt(options.curve.as_deref()).map_err(|e| JsError::new(&e))?;
+    let format = PointFormat::parse_opt(options.public_key_format.as_deref())
+        .map_err(|e| JsError::new(&e))?;
+    let encoding = Encoding::parse_opt(options.encoding.as_deref()).map_err(|e| JsError::new(&e))?;
+
+    // Read from JS one blob at a time; see `PrimeBlobs`
+    let primes_blobs = PrimeBlobs::new(serialized_primes)?;
+    let level = primes_level(&primes_blobs, n, requested)?;
+    let mut held = metrics::HeldPrimes::default();
+
+    with_curve!(curve, E => with_security_level!(level, L => {
+        parse_primes::<L>(&primes_blobs, n, &mut held)
+            .and_then(|primes| {
+                let fingerprints = checked_primes_fingerprints(&primes, &options)?;
+                run_dkg_inner::<E, L, R>(
+                    eid_bytes,
+                    &positions(n),
+                    threshold,
+                    curve,
+                    level,
+                    encoding,
+                    primes,
+                    party_rng,
+                    on_share,
+                    options.cancel_token,
+                )
+                .map(|summary| DkgSummary { primes_fingerprints: fingerprints, ..summary })
+            })
+    }))
+    .and_then(|summary| summary.with_public_key_format(curve, format))
+    .map_err(|e| JsError::new(&e))
+}
+
+/// The primes array passed from JS, left there and copied in one blob at
+/// a time: a prime set is over a megabyte as JSON, and copying them all up
+/// front held every raw blob next to every parsed set right before the
+/// aux info phase.
+#[cfg(feature = "dkg")]
+struct PrimeBlobs(js_sys::Array);
+
+#[cfg(feature = "dkg")]
+impl PrimeBlobs {
+    fn new(value: JsByteArrays) -> Result<Self, JsError> {
+        let value: JsValue = value.into();
+        value
+            .dyn_into::<js_sys::Array>()
+            .map(PrimeBlobs)
+            .map_err(|_| JsError::new("primes array must be an array"))
+    }
+
+    fn len(&self) -> usize {
+        self.0.length() as usize
+    }
+
+    /// A copy of blob `i`, wiped on drop.
+    fn get(&self, i: u16) -> Result<Zeroizing<Vec<u8>>, String> {
+        js_bytes(self.0.get(u32::from(i)), &format!("primes array[{i}]"))
+            .map_err(|_| format!("INVALID_BYTES: primes array[{i}] must be a Uint8Array or a hex string"))
+    }
+}
+
+/// The security level of the first `n` prime blobs, which must all agree
+/// (and match `requested`, if given).
+#[cfg(feature = "dkg")]
+fn primes_level(blobs: &PrimeBlobs, n: u16, requested: Option<Level>) -> Result<Level, JsError> {
+    if blobs.len() < usize::from(n) {
+        return Err(JsError::new(&format!("need {} sets of primes, got {}", n, blobs.len())));
+    }
+    let mut level = requested;
+    for i in 0..n {
+        let bytes = blobs.get(i).map_err(|e| JsError::new(&e))?;
+        let (header, _) = share_format::split_expect(&bytes, BlobKind::Primes)
+            .map_err(|e| JsError::new(&format!("primes for party {i}: {e}")))?;
+        let found = share_format::ensure_level(&header, level)
+            .map_err(|e| JsError::new(&format!("primes for party {i}: {e}")))?;
+        level = Some(found);
+    }
+    Ok(level.unwrap_or_default())
+}
+
+/// Parse the first `n` prime blobs, one at a time: each raw blob is copied
+/// in, parsed and wiped before the next is read, so at most one is held
+/// next to the parsed sets. Everything held is counted in `held`.
+#[cfg(feature = "dkg")]
+fn parse_primes<L: SecurityLevel>(
+    blobs: &PrimeBlobs,
+    n: u16,
+    held: &mut metrics::HeldPrimes,
+) -> Result<Vec<cggmp24::PregeneratedPrimes<L>>, String> {
+    let mut primes = Vec::with_capacity(usize::from(n));
+    for i in 0..n {
+        let bytes = blobs.get(i)?;
+        // The raw blob, then the parsed set next to it until the blob goes
+        held.add(bytes.len());
+        let parsed = share_format::split(&bytes)
+            .and_then(|(_, payload)| share_format::deserialize(BlobKind::Primes, payload))
+            .map_err(|e| format!("primes for party {i}: {e}"))?;
+        held.add(bytes.len());
+        held.release(bytes.len());
+        drop(bytes);
+        primes.push(parsed);
+    }
+    Ok(primes)
+}
+
+/// Fingerprints of `primes`, refused as `run_dkg_with_primes` documents
+/// unless `options.allow_unsafe_prime_reuse`.
+#[cfg(feature = "dkg")]
+fn checked_primes_fingerprints<L: SecurityLevel>(
+    primes: &[cggmp24::PregeneratedPrimes<L>],
+    options: &DkgOptions,
+) -> Result<Vec<String>, String> {
+    let fingerprints = primes
+        .iter()
+        .map(primes_reuse::fingerprint)
+        .collect::<Result<Vec<_>, _>>()?;
+    if !options.allow_unsafe_prime_reuse.unwrap_or(false) {
+        primes_reuse::check_reuse(&fingerprints, options.used_primes.as_deref().unwrap_or_default())?;
+    }
+    Ok(fingerprints)
+}
+
+// ─── DKG one phase at a time ─────────────────────────────────────────────────
+
+/// What `run_aux_for_parties` returns.
+#[cfg(feature = "dkg")]
+#[derive(Serialize, Tsify)]
+struct AuxForPartiesResult {
+    /// Serialised AuxInfo per party, in `indices` order
+    #[tsify(type = "Uint8Array[]")]
+    aux_infos: Vec<ByteBuf>,
+    /// Fingerprint of each party's primes, in `indices` order, for the
+    /// caller's `used_primes` list
+    primes_fingerprints: Vec<String>,
+}
+
+/// Phase A of [`run_dkg_with_primes`] alone: aux info generation for the
+/// parties `indices`, from one prime blob per party in `indices` order.
+/// Pass the result to `run_keygen_for_parties` with the same indices, now
+/// or later: aux info is the slow half and doesn't depend on the key.
+///
+/// `indices` are checked as for `run_keygen_for_parties`; the aux infos
+/// come back in their order. Of `options`,
+/// `security_level`, `encoding`, `cancel_token`, `used_primes` and
+/// `allow_unsafe_prime_reuse` apply, as for `run_dkg_with_primes`.
+#[cfg(feature = "dkg")]
+#[wasm_bindgen]
+pub fn run_aux_for_parties(
+    eid_bytes: JsEid,
+    indices: Vec<u16>,
+    serialized_primes: JsByteArrays,
+    options: Option<JsDkgOptions>,
+) -> Result<JsAuxForPartiesResult, JsError> {
+    let eid_bytes = &js_eid(eid_bytes, "eid_bytes", eid::EidPurpose::Dkg)?;
+    let options: DkgOptions = options_from_js(options, "dkg options")?;
+    let n = party_limits::check_party_indices(&indices)
+        .and_then(|n| party_limits::check_party_count(n).map(|()| n))
+        .map_err(|e| JsError::new(&e))?;
+    let requested = options
+        .security_level
+        .as_deref()
+        .map(Level::parse)
+        .transpose()
+        .map_err(|e| JsError::new(&e))?;
+    let encoding = Encoding::parse_opt(options.encoding.as_deref()).map_err(|e| JsError::new(&e))?;
+
+    let primes_blobs = PrimeBlobs::new(serialized_primes)?;
+    let level = primes_level(&primes_blobs, n, requested)?;
+    let mut held = metrics::HeldPrimes::default();
+
+    let result = with_security_level!(level, L => {
+        parse_primes::<L>(&primes_blobs, n, &mut held).and_then(|primes| {
+            let primes_fingerprints = checked_primes_fingerprints(&primes, &options)?;
+            let aux_infos = aux_info_phase(eid_bytes, &indices, primes, &|_| MixedRng, options.cancel_token)?
+                .iter()
+                .enumerate()
+                .map(|(i, aux_info)| {
+                    share_format::serialize_encoded(BlobKind::AuxInfo, None, Some(level), 0, encoding, aux_info)
+                        .map(ByteBuf::from)
+                        .map_err(|e| format!("party {i}: {e}"))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(AuxForPartiesResult { aux_infos, primes_fingerprints })
+        })
+    })
+    .map_err(|e| JsError::new(&e))?;
+    to_js(&result)
+}
+
+/// Phase B of [`run_dkg`] alone: keygen for the parties `indices` with
+/// threshold `threshold`, each party's share paired with `aux_infos[i]`
+/// from `run_aux_for_parties` (one blob per party, in `indices` order).
+/// Returns a `DkgResult`, as `run_dkg` does, its shares in `indices`
+/// order and `party_labels` set to `indices`.
+///
+/// `indices` are those of `run_aux_for_parties`: any distinct values, so a
+/// key of parties 3, 7 and 9 is one where party 7 signs as 7. CGGMP24
+/// numbers parties `0..n`, so the party `indices[i]` is keygen index `i`
+/// underneath; unless `indices` is `0..n` itself, every core share carries
+/// the labels in its metadata (see `attach_metadata`), and signing sessions
+/// take `party_index` and `parties_at_keygen` as labels and relay under
+/// them. A repeated index fails with `DUPLICATE_INDEX`. Aux info for
+/// another number of parties fails with `AUX_PARTY_COUNT_MISMATCH`, aux
+/// info from different aux_info_gen runs or in the wrong order with
+/// `AUX_PARTY_MISMATCH`. Of `options`, all but `allow_single_party`, the
+/// primes ones and `security_level` (taken from the blobs, and checked
+/// against it if given) apply.
+#[cfg(feature = "dkg")]
+#[wasm_bindgen]
+pub fn run_keygen_for_parties(
+    eid_bytes: JsEid,
+    indices: Vec<u16>,
+    threshold: u16,
+    aux_infos: JsByteArrays,
+    options: Option<JsDkgOptions>,
+) -> Result<JsDkgResult, JsError> {
+    let eid_bytes = &js_eid(eid_bytes, "eid_bytes", eid::EidPurpose::Dkg)?;
+    let aux_infos = byte_arrays_from_js(aux_infos, "aux_infos")?;
+    let options: DkgOptions = options_from_js(options, "dkg options")?;
+    collect_dkg(|on_share| keygen_for_parties(eid_bytes, &indices, threshold, &aux_infos, &options, on_share))
+}
+
+/// [`run_keygen_for_parties`] with its arguments decoded.
+#[cfg(feature = "dkg")]
+fn keygen_for_parties(
+    eid_bytes: &[u8],
+    indices: &[u16],
+    threshold: u16,
+    aux_blobs: &[impl AsRef<[u8]>],
+    options: &DkgOptions,
+    on_share: &mut ShareSink<'_>,
+) -> Result<DkgSummary, JsError> {
+    let n = party_limits::check_party_indices(indices).map_err(|e| JsError::new(&e))?;
+    validate_dkg_params(n, threshold)?;
+    let requested = options
+        .security_level
+        .as_deref()
+        .map(Level::parse)
+        .transpose()
+        .map_err(|e| JsError::new(&e))?;
+    let curve = CurveId::parse_opt(options.curve.as_deref()).map_err(|e| JsError::new(&e))?;
+    let format = PointFormat::parse_opt(options.public_key_format.as_deref())
+        .map_err(|e| JsError::new(&e))?;
+    let encoding = Encoding::parse_opt(options.encoding.as_deref()).map_err(|e| JsError::new(&e))?;
+    let level = aux_level(aux_blobs, n, requested).map_err(|e| JsError::new(&e))?;
+
+    with_curve!(curve, E => with_security_level!(level, L => {
+        parse_aux_infos::<L>(aux_blobs, n).and_then(|aux_infos| {
+            keygen_phase::<E, L, _>(
+                eid_bytes,
+                indices,
+                threshold,
+                curve,
+                level,
+                encoding,
+                aux_infos,
+                &|_| MixedRng,
+                on_share,
+                options.cancel_token,
+            )
+        })
+    }))
+    .and_then(|summary| summary.with_public_key_format(curve, format))
+    .map_err(|e| JsError::new(&e))
+}
+
+/// Keygen for parties `0..n` over a native-gen `gen-aux` set, passed as
+/// its JSON line (`{ aux_infos, n, generated_at, security_level, uses }`,
+/// as `aux-pool take` prints it). Returns a `DkgResult`, as
+/// `run_keygen_for_parties` does, whose `aux_set` reports the metadata.
+///
+/// The set must be for exactly `n` parties (`AUX_PARTY_COUNT_MISMATCH`),
+/// its blobs from one aux_info_gen run and in party order
+/// (`AUX_PARTY_MISMATCH`), and at the level it is labelled with, which
+/// `options.security_level` must match if given
+/// (`SECURITY_LEVEL_MISMATCH`). `options.max_aux_age_secs` and
+/// `max_aux_uses` refuse it with `AUX_SET_EXPIRED` / `AUX_SET_EXHAUSTED`.
+/// The set is only read: recording the use is up to the pool.
+#[cfg(feature = "dkg")]
+#[wasm_bindgen]
+pub fn run_dkg_with_aux(
+    eid_bytes: JsEid,
+    n: u16,
+    threshold: u16,
+    aux_set_json: &str,
+    options: Option<JsDkgOptions>,
+) -> Result<JsDkgResult, JsError> {
+    let eid_bytes = &js_eid(eid_bytes, "eid_bytes", eid::EidPurpose::Dkg)?;
+    let mut options: DkgOptions = options_from_js(options, "dkg options")?;
+    let (aux_blobs, aux_set) =
+        open_aux_set(aux_set_json, n, &mut options, clock::unix_seconds()).map_err(|e| JsError::new(&e))?;
+    collect_dkg(|on_share| {
+        keygen_for_parties(eid_bytes, &positions(n), threshold, &aux_blobs, &options, on_share)
+            .map(|summary| DkgSummary { aux_set: Some(aux_set), ..summary })
+    })
+}
+
+/// Check an aux set for [`run_dkg_with_aux`] at `now` and decode its
+/// blobs. Its level label becomes `options.security_level`, for
+/// [`aux_level`] to hold the blobs to.
+#[cfg(feature = "dkg")]
+fn open_aux_set(
+    json: &str,
+    n: u16,
+    options: &mut DkgOptions,
+    now: u64,
+) -> Result<(Vec<Vec<u8>>, AuxSetInfo), String> {
+    let set: aux_set::AuxInfoOutput =
+        serde_json::from_str(json).map_err(|e| format!("INVALID_AUX_SET: {e}"))?;
+    let labelled = aux_set::check_for_keygen(&set, n)?;
+    if let Some(refusal) = aux_set::refusal(&set, options.max_aux_age_secs, options.max_aux_uses, now) {
+        return Err(refusal);
+    }
+    if let Some(labelled) = labelled {
+        let requested = options.security_level.get_or_insert_with(|| labelled.as_str().into());
+        if requested.as_str() != labelled.as_str() {
+            return Err(format!(
+                "SECURITY_LEVEL_MISMATCH: requested {requested}, the aux set is labelled {}",
+                labelled.as_str()
+            ));
+        }
+    }
+    let aux_blobs = set
+        .aux_infos
+        .iter()
+        .enumerate()
+        .map(|(i, text)| {
+            use base64::Engine;
+            base64::engine::general_purpose::STANDARD
+                .decode(text)
+                .map_err(|e| format!("INVALID_AUX_SET: aux info {i}: {e}"))
+        })
+        .collect::<Result<_, String>>()?;
+    let info = AuxSetInfo {
+        generated_at: set.generated_at,
+        age_secs: now.saturating_sub(set.generated_at),
+        security_level: set.security_level,
+        uses: set.uses,
+    };
+    Ok((aux_blobs, info))
+}
+
+/// The security level of `n` aux blobs, which must all agree (and match
+/// `requested`, if given).
+#[cfg(feature = "dkg")]
+fn aux_level(blobs: &[impl AsRef<[u8]>], n: u16, requested: Option<Level>) -> Result<Level, String> {
+    if blobs.len() != usize::from(n) {
+        return Err(format!(
+            "AUX_PARTY_COUNT_MISMATCH: {n} parties need {n} aux infos, got {}",
+            blobs.len()
+        ));
+    }
+    let mut level = requested;
+    for (i, bytes) in blobs.iter().enumerate() {
+        let (header, _) = share_format::split_expect(bytes.as_ref(), BlobKind::AuxInfo)
+            .map_err(|e| format!("aux info {i}: {e}"))?;
+        let found = share_format::ensure_level(&header, level).map_err(|e| format!("aux info {i}: {e}"))?;
+        level = Some(found);
+    }
+    Ok(level.unwrap_or_default())
+}
+
+/// Parse aux blobs for a keygen of `n` parties: each must be for `n`
+/// parties, and all from the same aux_info_gen run (the same Paillier
+/// moduli throughout). Whether blob `i` is party `i`'s is only known once
+/// there is a core share to pair it with, in [`keygen_phase`].
+#[cfg(feature = "dkg")]
+fn parse_aux_infos<L: SecurityLevel>(
+    blobs: &[impl AsRef<[u8]>],
+    n: u16,
+) -> Result<Vec<cggmp24::key_share::AuxInfo<L>>, String> {
+    let moduli = |aux: &cggmp24::key_share::AuxInfo<L>| -> Vec<Vec<u8>> {
+        aux.parties.iter().map(|party| party.N.to_bytes_msf()).collect()
+    };
+    let mut aux_infos: Vec<cggmp24::key_share::AuxInfo<L>> = Vec::with_capacity(blobs.len());
+    for (i, bytes) in blobs.iter().enumerate() {
+        let aux = share_format::split(bytes.as_ref())
+            .and_then(|(_, payload)| share_format::deserialize(BlobKind::AuxInfo, payload))
+            .map_err(|e| format!("aux info {i}: {e}"))?;
+        let aux_n = aux.parties.len();
+        if aux_n != usize::from(n) {
+            return Err(format!(
+                "AUX_PARTY_COUNT_MISMATCH: aux info {i} is for {aux_n} parties, keygen for {n}"
+            ));
+        }
+        if aux_infos.first().is_some_and(|first| moduli(first) != moduli(&aux)) {
+            return Err(format!(
+                "AUX_PARTY_MISMATCH: aux info {i} is from another aux_info_gen run than aux info 0"
+            ));
+        }
+        aux_infos.push(aux);
+    }
+    Ok(aux_infos)
+}
+
+// ─── DKG inner logic (shared by both modes) ─────────────────────────────────
+
+/// `error` with `context` in front, unless it is a `CANCELLED` error, which
+/// the host matches on as is.
+#[cfg(feature = "dkg")]
+fn cancelled_or(error: String, context: &str) -> String {
+    if error.starts_with("CANCELLED: ") {
+        error
+    } else {
+        format!("{context}: {error}")
+    }
+}
+
+/// The parties `0..n` of a key, the labels of a DKG without any.
+#[cfg(feature = "dkg")]
+fn positions(n: u16) -> Vec<u16> {
+    (0..n).collect()
+}
+
+/// Both phases, all parties local, for the parties `indices`: what
+/// `run_aux_for_parties`, then `run_keygen_for_parties` run.
+#[cfg(feature = "dkg")]
+#[allow(clippy::too_many_arguments)]
+fn run_dkg_inner<E, L, R>(
+    eid_bytes: &[u8],
+    indices: &[u16],
+    threshold: u16,
+    curve: CurveId,
+    level: Level,
+    encoding: Encoding,
+    primes_list: Vec<cggmp24::PregeneratedPrimes<L>>,
+    party_rng: &impl Fn(usize) -> R,
+    on_share: &mut ShareSink<'_>,
+    cancel_token: Option<u32>,
+) -> Result<DkgSummary, String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+    R: RngCore + CryptoRng + 'static,
+{
+    let aux_infos = aux_info_phase(eid_bytes, indices, primes_list, party_rng, cancel_token)?;
+    keygen_phase::<E, L, R>(
+        eid_bytes, indices, threshold, curve, level, encoding, aux_infos, party_rng, on_share, cancel_token,
+    )
+}
+
+/// Phase A, all parties local: aux info generation for the parties
+/// `indices` from `primes_list`, party `i` (`indices[i]`) drawing from
+/// `party_rng(i)`. Returns the aux info by keygen index.
+#[cfg(feature = "dkg")]
+fn aux_info_phase<L, R>(
+    eid_bytes: &[u8],
+    indices: &[u16],
+    primes_list: Vec<cggmp24::PregeneratedPrimes<L>>,
+    party_rng: &impl Fn(usize) -> R,
+    cancel_token: Option<u32>,
+) -> Result<Vec<cggmp24::key_share::AuxInfo<L>>, String>
+where
+    L: SecurityLevel,
+    R: RngCore + CryptoRng + 'static,
+{
+    let n = party_limits::check_party_indices(indices)?;
+    let mut aux_parties = Vec::new();
+    for (i, primes) in (0..n).zip(primes_list) {
+        let mut rng = party_rng(usize::from(i));
+        let eid = cggmp24::ExecutionId::new(eid_bytes);
+        aux_parties.push(round_based::state_machine::wrap_protocol(
+            move |party| async move {
+                cggmp24::aux_info_gen(eid, i, n, primes)
+                    .start(&mut rng, party)
+                    .await
+            },
+        ));
+    }
+
+    let aux_results = simulate::run_checked(aux_parties, |done, total| {
+        cancel::check(cancel_token, "aux_info_gen", done, total)
+    })
+    .map_err(|e| cancelled_or(e, "aux_info_gen failed"))?;
+
+    let mut aux_infos = Vec::new();
+    for (i, result) in aux_results.into_iter().enumerate() {
+        let aux = result.map_err(|e| format!("aux_info_gen party {i} failed: {e:?}"))?;
+        aux_infos.push(aux);
+    }
+    Ok(aux_infos)
+}
+
+/// Phase B, all parties local: keygen for the parties `indices`, party `i`
+/// (`indices[i]`) drawing from `party_rng(n + i)`, each share paired with
+/// `aux_infos[i]`. Unless `indices` is `0..n`, each core share carries them
+/// as its `party_labels`.
+///
+/// Each party's material is serialised, handed to `on_share` and dropped
+/// before the next party's is serialised. `cancel_token` is checked before
+/// every simulation pass and every party's serialisation.
+///
+/// `n = 1` (the caller checked `allow_single_party`) runs keygen without a
+/// threshold and flags both blobs `FLAG_SINGLE_PARTY`.
+#[cfg(feature = "dkg")]
+#[allow(clippy::too_many_arguments)]
+fn keygen_phase<E, L, R>(
+    eid_bytes: &[u8],
+    indices: &[u16],
+    threshold: u16,
+    curve: CurveId,
+    level: Level,
+    encoding: Encoding,
+    aux_infos: Vec<cggmp24::key_share::AuxInfo<L>>,
+    party_rng: &impl Fn(usize) -> R,
+    on_share: &mut ShareSink<'_>,
+    cancel_token: Option<u32>,
+) -> Result<DkgSummary, String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+    R: RngCore + CryptoRng + 'static,
+{
+    let n = party_limits::check_party_indices(indices)?;
+    // Generates threshold ECDSA key shares (lightweight: ~2-5s)
+    let kg_results = if n == 1 {
+        // A single-party key has no threshold to share: plain 1-of-1 keygen
+        let mut rng = party_rng(1);
+        let eid = cggmp24::ExecutionId::new(eid_bytes);
+        let party = round_based::state_machine::wrap_protocol(move |party| async move {
+            cggmp24::keygen::<E>(eid, 0, 1).start(&mut rng, party).await
+        });
+        simulate::run_checked(vec![party], |done, total| {
+            cancel::check(cancel_token, "keygen", done, total)
+        })
+    } else {
+        let mut kg_parties = Vec::new();
+        for i in 0..n {
+            let mut rng = party_rng(usize::from(n) + usize::from(i));
+            let eid = cggmp24::ExecutionId::new(eid_bytes);
+            kg_parties.push(round_based::state_machine::wrap_protocol(
+                move |party| async move {
+                    cggmp24::keygen::<E>(eid, i, n)
+                        .set_threshold(threshold)
+                        .start(&mut rng, party)
+                        .await
+                },
+            ));
+        }
+        simulate::run_checked(kg_parties, |done, total| {
+            cancel::check(cancel_token, "keygen", done, total)
+        })
+    }
+    .map_err(|e| cancelled_or(e, "keygen failed"))?;
+
+    let mut core_shares = Vec::new();
+    for (i, result) in kg_results.into_iter().enumerate() {
+        let share = result.map_err(|e| format!("keygen party {i} failed: {e:?}"))?;
+        core_shares.push(share);
+    }
+
+    // Extract shared public key (same for all parties)
+    let pk = core_shares[0].shared_public_key();
+    let pk_bytes = pk.to_bytes(true); // 33-byte compressed
+
+    // Serialize each party's key material, consuming the protocol outputs
+    // so each party's are freed once handed on. A single-party key is
+    // flagged as such in both blobs.
+    let flags = if n == 1 { share_format::FLAG_SINGLE_PARTY } else { 0 };
+    let labels = (!party_limits::is_positional(indices)).then(|| share_metadata::ShareMetadata {
+        party_labels: Some(indices.to_vec()),
+        ..share_metadata::ShareMetadata::default()
+    });
+    let mut share_fingerprints = Vec::with_capacity(usize::from(n));
+    for ((i, &label), (core_share, aux_info)) in (0..n).zip(indices).zip(core_shares.into_iter().zip(aux_infos)) {
+        cancel::check(cancel_token, "serialize", usize::from(i), usize::from(n))?;
+        // Aux info from `run_keygen_for_parties`' caller may be another
+        // party's or another ceremony's: pairing is what catches that
+        let key_share = key_material::pair_up(core_share, aux_info, None)
+            .map_err(|errors| format!("party {label}: {}", errors.join("; ")))?;
+        let core_bytes = share_format::serialize_encoded(
+            BlobKind::CoreShare,
+            Some(curve),
+            None,
+            flags,
+            encoding,
+            &key_share.core,
+        )
+        .and_then(|bytes| match &labels {
+            Some(labels) => share_metadata::attach_metadata(&bytes, labels),
+            None => Ok(bytes),
+        })
+        .map_err(|e| format!("party {label}: {e}"))?;
+        let aux_bytes = share_format::serialize_encoded(
+            BlobKind::AuxInfo,
+            None,
+            Some(level),
+            flags,
+            encoding,
+            &key_share.aux,
+        )
+        .map_err(|e| format!("party {label}: {e}"))?;
+        drop(key_share);
+        share_fingerprints.push(fingerprint::share_fingerprint(&core_bytes));
+        on_share(
+            label,
+            DkgShare {
+                core_share: core_bytes,
+                aux_info: aux_bytes,
+            },
+        )?;
+    }
+
+    Ok(DkgSummary {
+        public_key: pk_bytes.as_bytes().to_vec(),
+        public_key_uncompressed: None,
+        wallet_id: fingerprint::wallet_id(pk_bytes.as_bytes())?,
+        share_fingerprints,
+        party_labels: indices.to_vec(),
+        primes_fingerprints: Vec::new(),
+        aux_set: None,
+    })
+}
+
+// ─── Trusted dealer (`insecure-dev` feature) ─────────────────────────────────
+//
+// One process mints every share of a key it knows in full: no MPC rounds,
+// so a "DKG" takes milliseconds once primes are at hand. For test suites
+// only — the dealer is a single point of failure by construction, and
+// every blob it writes says so in its header.
+
+/// A `DkgResult` minted by a trusted dealer instead of a DKG ceremony.
+/// The shares and aux info sign exactly like `run_dkg`'s, but every blob
+/// is flagged as dealer-generated (see `get_share_info`) and carries a
+/// version 2 header, which older readers refuse.
+///
+/// `eid_bytes` is checked as for `run_dkg` but otherwise unused: no
+/// protocol runs. `serialized_primes` (one `pregenerate_paillier_primes`
+/// blob per party, as for `run_dkg_with_primes`) makes the call instant;
+/// without it the dealer generates the Paillier primes itself, which is as
+/// slow as in `run_dkg`. `options.cancel_token` is ignored.
+#[cfg(feature = "insecure-dev")]
+#[wasm_bindgen]
+pub fn run_dkg_trusted_dealer(
+    eid_bytes: JsEid,
+    n: u16,
+    threshold: u16,
+    serialized_primes: Option<JsByteArrays>,
+    options: Option<JsDkgOptions>,
+) -> Result<JsDkgResult, JsError> {
+    js_eid(eid_bytes, "eid_bytes", eid::EidPurpose::Dkg)?;
+    validate_dkg_params(n, threshold)?;
+    let options: DkgOptions = options_from_js(options, "dkg options")?;
+    let requested = options
+        .security_level
+        .as_deref()
+        .map(Level::parse)
+        .transpose()
+        .map_err(|e| JsError::new(&e))?;
+    let curve = CurveId::parse_opt(options.curve.as_deref()).map_err(|e| JsError::new(&e))?;
+    let format = PointFormat::parse_opt(options.public_key_format.as_deref())
+        .map_err(|e| JsError::new(&e))?;
+    let encoding = Encoding::parse_opt(options.encoding.as_deref()).map_err(|e| JsError::new(&e))?;
+
+    let primes_blobs = serialized_primes.map(PrimeBlobs::new).transpose()?;
+    let level = match &primes_blobs {
+        Some(blobs) => primes_level(blobs, n, requested)?,
+        None => requested.unwrap_or_default(),
+    };
+    let mut held = metrics::HeldPrimes::default();
+
+    collect_dkg(|on_share| {
+        with_curve!(curve, E => with_security_level!(level, L => {
+            primes_blobs
+                .as_ref()
+                .map(|blobs| parse_primes::<L>(blobs, n, &mut held))
+                .transpose()
+                .and_then(|primes| trusted_dealer_inner::<E, L>(n, threshold, curve, level, encoding, primes, on_share))
+        }))
+        .and_then(|summary| summary.with_public_key_format(curve, format))
+        .map_err(|e| JsError::new(&e))
+    })
+}
+
+/// Mint and serialise every party's material, flagged as dealer-generated.
+#[cfg(feature = "insecure-dev")]
+fn trusted_dealer_inner<E, L>(
+    n: u16,
+    threshold: u16,
+    curve: CurveId,
+    level: Level,
+    encoding: Encoding,
+    primes: Option<Vec<cggmp24::PregeneratedPrimes<L>>>,
+    on_share: &mut ShareSink<'_>,
+) -> Result<DkgSummary, String>
+where
+    E: Curve,
+    L: SecurityLevel,
+{
+    let mut builder = cggmp24::trusted_dealer::builder::<E, L>(n).set_threshold(Some(threshold));
+    if let Some(primes) = primes {
+        builder = builder.set_pregenerated_primes(primes);
+    }
+    let key_shares = builder
+        .generate_shares(&mut MixedRng)
+        .map_err(|e| format!("trusted dealer: {e}"))?;
+    let pk_bytes = key_shares[0].shared_public_key().to_bytes(true);
+
+    let mut share_fingerprints = Vec::with_capacity(usize::from(n));
+    for (i, key_share) in (0..n).zip(key_shares) {
+        let core_bytes = share_format::serialize_encoded(
+            BlobKind::CoreShare,
+            Some(curve),
+            None,
+            share_format::FLAG_DEALER,
+            encoding,
+            &key_share.core,
+        )
+        .map_err(|e| format!("party {i}: {e}"))?;
+        let aux_bytes = share_format::serialize_encoded(
+            BlobKind::AuxInfo,
+            None,
+            Some(level),
+            share_format::FLAG_DEALER,
+            encoding,
+            &key_share.aux,
+        )
+        .map_err(|e| format!("party {i}: {e}"))?;
+        drop(key_share);
+        share_fingerprints.push(fingerprint::share_fingerprint(&core_bytes));
+        on_share(
+            i,
+            DkgShare {
+                core_share: core_bytes,
+                aux_info: aux_bytes,
+            },
+        )?;
+    }
+
+    Ok(DkgSummary {
+        public_key: pk_bytes.as_bytes().to_vec(),
+        public_key_uncompressed: None,
+        wallet_id: fingerprint::wallet_id(pk_bytes.as_bytes())?,
+        share_fingerprints,
+        party_labels: positions(n),
+        primes_fingerprints: Vec::new(),
+        aux_set: None,
+    })
+}
+
+// ─── Aux info refresh ────────────────────────────────────────────────────────
+
+/// New aux info for every party, pairing with the unchanged core shares.
+#[cfg(feature = "dkg")]
+#[derive(Serialize, Tsify)]
+struct AuxRefreshResult {
+    /// Serialised AuxInfo per party, in keygen index order
+    #[tsify(type = "Uint8Array[]")]
+    aux_infos: Vec<ByteBuf>,
+    /// 33-byte compressed shared public key, same as before the refresh
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
+    public_key: Vec<u8>,
+}
+
+/// Regenerate the Paillier material (aux_info_gen) for an existing key
+/// without touching its ECDSA shares, e.g. after an aux blob leaked or to
+/// move a key to security level 192.
+///
+/// `core_shares` holds every party's CoreKeyShare, in any order. If
+/// `serialized_primes` is given (one blob per party) it fixes the level;
+/// otherwise primes are generated inline at `security_level` (default 128).
+/// The refreshed shares co-sign a check hash before anything is returned;
+/// a failure there is `AUX_REFRESH_CHECK_FAILED`. Pair each new aux blob
+/// with its party's core share via `combine_key_share`.
+#[cfg(feature = "dkg")]
+#[wasm_bindgen]
+pub fn run_aux_refresh(
+    eid_bytes: JsEid,
+    core_shares: JsByteArrays,
+    serialized_primes: Option<JsByteArrays>,
+    security_level: Option<String>,
+) -> Result<JsAuxRefreshResult, JsError> {
+    let eid_bytes = &js_eid(eid_bytes, "eid_bytes", eid::EidPurpose::Refresh)?;
+    let core_shares = byte_arrays_from_js(core_shares, "core shares")?;
+    let primes = serialized_primes
+        .map(|primes| byte_arrays_from_js(primes, "primes array"))
+        .transpose()?;
+    let requested = security_level
+        .as_deref()
+        .map(Level::parse)
+        .transpose()
+        .map_err(|e| JsError::new(&e))?;
+
+    let refreshed = aux_refresh::refresh(eid_bytes, &core_shares, primes.as_deref(), requested)
+        .map_err(|e| JsError::new(&e))?;
+    to_js(&AuxRefreshResult {
+        aux_infos: refreshed.aux_infos.into_iter().map(ByteBuf::from).collect(),
+        public_key: refreshed.public_key,
+    })
+}
+
+// ─── tss-lib import ──────────────────────────────────────────────────────────
+
+/// One party's core share, converted from tss-lib save data.
+#[derive(Serialize, Tsify)]
+struct TsslibImportResult {
+    /// Serialised CoreKeyShare, the same as `run_dkg` returns; it has no
+    /// aux info yet
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
+    core_share: Vec<u8>,
+    /// Keygen index of the party
+    party_index: u16,
+    /// Number of parties of the key
+    n: u16,
+    /// 33-byte compressed shared public key, tss-lib's `ECDSAPub`
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
+    public_key: Vec<u8>,
+    /// See `share_fingerprint`
+    share_fingerprint: String,
+}
+
+/// Convert one party's Binance tss-lib (GG18/GG20) ECDSA keygen save data
+/// (`LocalPartySaveData` JSON) into a CoreKeyShare of the same key.
+/// `threshold` is the number of signers, tss-lib's `threshold` + 1.
+///
+/// GG20's Paillier material can't be reused: convert every party's save
+/// data, then pass all the core shares to `run_aux_refresh` for fresh aux
+/// info. Fails with `UNSUPPORTED_CURVE` for anything but secp256k1,
+/// `INVALID_THRESHOLD` / `THRESHOLD_MISMATCH` when the threshold doesn't
+/// fit the public shares, `PUBLIC_KEY_MISMATCH` / `SHARE_MISMATCH` when the
+/// shares don't add up to `ECDSAPub`, and `INVALID_TSSLIB_SHARE` otherwise.
+#[wasm_bindgen]
+pub fn import_tsslib_share(save_data: JsBytes, threshold: u16) -> Result<JsTsslibImportResult, JsError> {
+    let save_data = js_bytes(save_data, "save_data")?;
+    let imported = interop::import_tsslib_share(&save_data, threshold).map_err(|e| JsError::new(&e))?;
+    to_js(&TsslibImportResult {
+        share_fingerprint: fingerprint::share_fingerprint(&imported.core_share),
+        core_share: imported.core_share,
+        party_index: imported.party_index,
+        n: imported.n,
+        public_key: imported.public_key,
+    })
+}
+
+// ─── Utility Functions ───────────────────────────────────────────────────────
+
+/// Derive a 32-byte execution id for `purpose` (`"dkg"`, `"sign"` or
+/// `"refresh"`) on wallet `wallet_id`: HKDF-SHA256 with a fixed label per
+/// purpose, so a DKG eid never doubles as a signing eid. `nonce` (empty by
+/// default) tells ceremonies of one purpose apart — use a fresh one per
+/// signing. Every party derives the same eid from the same inputs.
+///
+/// The eid-taking exports also accept `{ wallet_id, purpose, nonce }`
+/// directly, and refuse a spec derived for another purpose
+/// (`EID_PURPOSE_MISMATCH`).
+#[wasm_bindgen]
+pub fn derive_eid(wallet_id: &str, purpose: &str, nonce: Option<JsBytes>) -> Result<Vec<u8>, JsError> {
+    let purpose = eid::EidPurpose::parse(purpose).map_err(|e| JsError::new(&e))?;
+    let nonce = match nonce {
+        Some(nonce) => js_bytes(nonce, "nonce")?,
+        None => Zeroizing::new(Vec::new()),
+    };
+    eid::derive_eid(wallet_id, purpose, &nonce)
+        .map(|eid| eid.to_vec())
+        .map_err(|e| JsError::new(&e))
+}
+
+/// Combine a CoreKeyShare (from keygen) with AuxInfo (from aux_info_gen)
+/// into a full KeyShare suitable for signing.
+///
+/// The security level is read from the aux blob's header (legacy blobs are
+/// 128) and the curve from the core share's. If `security_level` is given
+/// and disagrees, this fails with `SECURITY_LEVEL_MISMATCH` instead of a
+/// deserialize error.
+///
+/// Halves that don't belong together fail with a specific code rather than
+/// the library's generic error: `AUX_PARTY_COUNT_MISMATCH` (different party
+/// counts), `AUX_PARTY_MISMATCH` (aux info from another party or ceremony),
+/// or `PUBLIC_KEY_MISMATCH` when `expected_public_key` is given and the
+/// core share is for another key. `check_key_material` reports all of them
+/// at once.
+///
+/// `encoding` (`"json"` / `"cbor"`) picks the KeyShare's encoding; it
+/// defaults to the one set at `init`. The halves may be in either.
+///
+/// Returns the serialised KeyShare bytes.
+#[wasm_bindgen]
+pub fn combine_key_share(
+    core_key_share: JsBytes,
+    aux_info: JsBytes,
+    security_level: Option<String>,
+    expected_public_key: Option<JsBytes>,
+    encoding: Option<String>,
+) -> Result<Vec<u8>, JsError> {
+    let core_key_share = &js_bytes(core_key_share, "core_key_share")?;
+    let aux_info = &js_bytes(aux_info, "aux_info")?;
+    let requested = security_level
+        .as_deref()
+        .map(Level::parse)
+        .transpose()
+        .map_err(|e| JsError::new(&e))?;
+    let expected = expected_public_key
+        .map(|pk| js_bytes(pk, "expected_public_key"))
+        .transpose()?;
+    let encoding = Encoding::parse_opt(encoding.as_deref()).map_err(|e| JsError::new(&e))?;
+
+    key_material::combine_key_share(
+        core_key_share,
+        aux_info,
+        requested,
+        expected.as_deref().map(|pk| &pk[..]),
+        encoding,
+    )
+    .map_err(|e| JsError::new(&e))
+}
+
+/// Diagnose a core share / aux info pair without combining it: curve,
+/// level, party index, both party counts, whether the core share is for
+/// `expected_public_key`, and every problem `combine_key_share` would
+/// report. Problems with the material land in `errors`; only arguments
+/// that aren't bytes at all throw.
+#[wasm_bindgen]
+pub fn check_key_material(
+    core_key_share: JsBytes,
+    aux_info: JsBytes,
+    expected_public_key: Option<JsBytes>,
+) -> Result<JsKeyMaterialReport, JsError> {
+    let core_key_share = &js_bytes(core_key_share, "core_key_share")?;
+    let aux_info = &js_bytes(aux_info, "aux_info")?;
+    let expected = expected_public_key
+        .map(|pk| js_bytes(pk, "expected_public_key"))
+        .transpose()?;
+
+    to_js(&key_material::check_key_material(
+        core_key_share,
+        aux_info,
+        expected.as_deref().map(|pk| &pk[..]),
+    ))
+}
+
+/// Check that a core share / aux info pair still signs under this build's
+/// cggmp24, e.g. after an upgrade: the blobs' formats, whether they combine,
+/// and a local dry run of this party's first signing round. The dry run
+/// contacts no one and needs no co-signer; see `share_compat`.
+///
+/// Incompatible material is reported in `issues` (`AUX_SCHEMA_INCOMPATIBLE`,
+/// `SIGNING_DRY_RUN_FAILED`, ...) with `compatible: false`; only arguments
+/// that aren't bytes at all throw.
+///
+/// # Returns
+/// JS object: `{ compatible, cggmp24_version, core_schema, aux_schema, issues }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn check_share_compat(core_key_share: JsBytes, aux_info: JsBytes) -> Result<JsShareCompatReport, JsError> {
+    let core_key_share = &js_bytes(core_key_share, "core_key_share")?;
+    let aux_info = &js_bytes(aux_info, "aux_info")?;
+    to_js(&share_compat::check_share_compat(core_key_share, aux_info))
+}
+
+/// Public parameters of a serialised AuxInfo (legacy, JSON or binary), for
+/// audit displays: per party, the Paillier modulus bit length and
+/// fingerprints of `N` and the ring-Pedersen `s` / `t`. No Paillier prime
+/// is ever part of the result.
+///
+/// # Returns
+/// JS object: `{ security_level, parties: [{ index, modulus_bits, modulus_fingerprint, s_fingerprint, t_fingerprint }] }`
+#[wasm_bindgen]
+pub fn aux_public_data(aux_info: JsBytes) -> Result<JsAuxPublicData, JsError> {
+    let aux_info = js_bytes(aux_info, "aux_info")?;
+    let data = key_material::aux_public_data(&aux_info).map_err(|e| JsError::new(&e))?;
+    to_js(&data)
+}
+
+/// Public parts of a serialised CoreKeyShare or KeyShare (legacy, JSON or
+/// binary). The secret share is never part of the result.
+///
+/// # Returns
+/// JS object: `{ curve, party_index, threshold, n, public_shares, public_key }`,
+/// points as compressed SEC1 hex
+#[wasm_bindgen]
+pub fn core_public_data(core_share: JsBytes) -> Result<JsCorePublicData, JsError> {
+    let core_share = js_bytes(core_share, "core_share")?;
+    let data = key_material::core_public_data(&core_share).map_err(|e| JsError::new(&e))?;
+    to_js(&data)
+}
+
+/// Every signer set `share` (a core share or KeyShare) can sign with out of
+/// `available_parties`: each includes the share's own index and at least
+/// the threshold's parties, smallest sets first. The share's own index
+/// counts as available whether listed or not.
+///
+/// `options`: `{ offset?, limit?, max_sets? }` pages through the sets;
+/// enumerating more than `max_sets` (default 1024) in all fails with
+/// `TOO_MANY_SIGNER_SETS`. An index that is `n` or more fails with
+/// `PARTY_INDEX_OUT_OF_RANGE`.
+///
+/// # Returns
+/// JS object: `{ sets, total, next_offset }`, `next_offset` undefined on
+/// the last page
+#[wasm_bindgen]
+pub fn signer_sets(
+    share: JsBytes,
+    available_parties: Vec<u16>,
+    options: Option<JsSignerSetOptions>,
+) -> Result<JsSignerSets, JsError> {
+    let share = js_bytes(share, "share")?;
+    let options: key_material::SignerSetOptions = options_from_js(options, "signer set options")?;
+    let sets = key_material::signer_sets(&share, &available_parties, options).map_err(|e| JsError::new(&e))?;
+    to_js(&sets)
+}
+
+/// Whether `sign_create_session` would accept `parties` as the signer set
+/// of `share` (a core share or KeyShare): at least the threshold's
+/// parties, all below `n`, none twice, the share's own index among them.
+#[wasm_bindgen]
+pub fn can_sign_with(share: JsBytes, parties: Vec<u16>) -> Result<bool, JsError> {
+    let share = js_bytes(share, "share")?;
+    key_material::can_sign_with(&share, &parties).map_err(|e| JsError::new(&e))
+}
+
+/// Short public identifier of a serialised CoreKeyShare: the first 16 hex
+/// characters of its SHA-256, as in `DkgResult.share_fingerprints`. Safe to
+/// log; changes if the share is re-encoded.
+#[wasm_bindgen]
+pub fn share_fingerprint(core_share: JsBytes) -> Result<String, JsError> {
+    let core_share = js_bytes(core_share, "core_share")?;
+    Ok(fingerprint::share_fingerprint(&core_share))
+}
+
+/// Wallet id of a public key: the first 16 hex characters of the SHA-256
+/// of its compressed form, as in `DkgResult.wallet_id`. A 65-byte
+/// uncompressed key is compressed on `curve` (default `"secp256k1"`) first.
+///
+/// Fails with `INVALID_PUBLIC_KEY` on anything that isn't a point.
+#[wasm_bindgen]
+pub fn wallet_id_from_pubkey(public_key: JsBytes, curve: Option<String>) -> Result<String, JsError> {
+    let public_key = js_bytes(public_key, "public_key")?;
+    let curve = share_format::CurveId::parse_opt(curve.as_deref()).map_err(|e| JsError::new(&e))?;
+    key_material::recode_public_key(curve, &public_key, true)
+        .and_then(|compressed| fingerprint::wallet_id(&compressed))
+        .map_err(|e| JsError::new(&e))
+}
+
+/// 69-byte commitment to the public parts of a KeyShare, CoreKeyShare or
+/// FROST key share: party index, every party's public share and the shared
+/// public key, never the secret. Send it instead of the share to attest
+/// that copies held apart belong to one wallet (`verify_commitments`).
+/// Doesn't change when the share is re-encoded or gains its aux info.
+#[wasm_bindgen]
+pub fn share_commitment(share: JsBytes) -> Result<Vec<u8>, JsError> {
+    let share = js_bytes(share, "share")?;
+    commitment::share_commitment(&share).map_err(|e| JsError::new(&e))
+}
+
+/// Check that `share_commitment`s describe one key — `expected_public_key`
+/// if given — with no party index repeated. `mismatched` lists the
+/// positions that don't fit, with a reason each in `errors`.
+#[wasm_bindgen]
+pub fn verify_commitments(
+    commitments: JsByteArrays,
+    expected_public_key: Option<JsBytes>,
+) -> Result<JsCommitmentReport, JsError> {
+    let commitments = byte_arrays_from_js(commitments, "commitments")?;
+    let expected = expected_public_key
+        .map(|pk| js_bytes(pk, "expected_public_key"))
+        .transpose()?;
+    to_js(&commitment::verify_commitments(
+        &commitments,
+        expected.as_deref().map(|pk| &pk[..]),
+    ))
+}
+
+/// Both encodings of a public key (`extract_public_key(.., "both")`).
+#[derive(Serialize, Tsify)]
+struct PublicKeyForms {
+    /// 33 bytes, `02`/`03` prefix
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
+    compressed: Vec<u8>,
+    /// 65 bytes, `04` prefix
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
+    uncompressed: Vec<u8>,
+}
+
+/// Extract the shared public key from a serialised KeyShare or CoreKeyShare.
+///
+/// Returns the public key on the share's curve (secp256k1 for legacy
+/// blobs) in `format`: `"compressed"` (default, 33 bytes),
+/// `"uncompressed"` (65 bytes) or `"both"`, which returns
+/// `{ compressed, uncompressed }`.
+#[wasm_bindgen]
+pub fn extract_public_key(
+    key_share_bytes: JsBytes,
+    format: Option<String>,
+) -> Result<JsPublicKey, JsError> {
+    let key_share_bytes = &js_bytes(key_share_bytes, "key_share_bytes")?;
+    let format = PointFormat::parse_opt(format.as_deref()).map_err(|e| JsError::new(&e))?;
+    let compressed = key_material::extract_public_key(key_share_bytes).map_err(|e| JsError::new(&e))?;
+    match format {
+        PointFormat::Compressed => to_js(&ByteBuf::from(compressed)),
+        PointFormat::Uncompressed | PointFormat::Both => {
+            let uncompressed = key_material::extract_public_key_as(key_share_bytes, false)
+                .map_err(|e| JsError::new(&e))?;
+            if format == PointFormat::Uncompressed {
+                return to_js(&ByteBuf::from(uncompressed));
+            }
+            to_js(&PublicKeyForms { compressed, uncompressed })
+        }
+    }
+}
+
+/// Pre-generate Paillier primes for aux_info_gen.
+///
+/// This is the expensive part (~30-60s at level 128, considerably more at
+/// 192). Call this ahead of time and store the result. Pass serialised
+/// primes to speed up DKG.
+///
+/// Returns serialised PregeneratedPrimes (headered unless level 128).
+///
+/// A cancelled `cancel_token` fails with `CANCELLED` before any work; the
+/// search itself runs inside cggmp24 and can't be interrupted.
+#[cfg(feature = "primes")]
+#[wasm_bindgen]
+pub fn pregenerate_paillier_primes(
+    security_level: Option<String>,
+    cancel_token: Option<u32>,
+) -> Result<Vec<u8>, JsError> {
+    let level = Level::parse_opt(security_level.as_deref()).map_err(|e| JsError::new(&e))?;
+    cancel::check(cancel_token, "primes", 0, 1).map_err(|e| JsError::new(&e))?;
+    with_security_level!(level, L => {
+        let primes = cggmp24::PregeneratedPrimes::<L>::generate(&mut MixedRng);
+        share_format::serialize(BlobKind::Primes, None, Some(level), &primes)
+    })
+    .map_err(|e| JsError::new(&format!("serialize primes: {e}")))
+}
+
+/// Options for `validate_pregenerated_primes`; every field optional.
+#[cfg(feature = "primes")]
+#[derive(Deserialize, Default, Tsify)]
+#[serde(default)]
+struct PrimesCheckOptions {
+    /// Expected level (`"128"` / `"192"`); a blob for the other level fails
+    security_level: Option<String>,
+    /// Miller-Rabin rounds per prime (default 32)
+    rounds: Option<u32>,
+}
+
+/// Check a pregenerated primes blob: header, deserialisation, bit lengths
+/// and probable primality of both primes.
+///
+/// Bad blobs are reported, not thrown. Returns
+/// `{ valid, bit_length, security_level, error? }` where `error` is
+/// `CODE: detail` with one of `INVALID_PRIMES_FORMAT`,
+/// `SECURITY_LEVEL_MISMATCH`, `PRIMES_TOO_SHORT`, `PRIMES_NOT_PRIME`.
+#[cfg(feature = "primes")]
+#[wasm_bindgen]
+pub fn validate_pregenerated_primes(
+    primes: JsBytes,
+    options: Option<JsPrimesCheckOptions>,
+) -> Result<JsPrimesReport, JsError> {
+    let primes = &js_bytes(primes, "primes")?;
+    let options: PrimesCheckOptions = options_from_js(options, "primes check options")?;
+    let requested = options
+        .security_level
+        .as_deref()
+        .map(Level::parse)
+        .transpose()
+        .map_err(|e| JsError::new(&e))?;
+    let rounds = options.rounds.unwrap_or(primes_check::DEFAULT_MR_ROUNDS);
+
+    let report = primes_check::validate(primes, requested, rounds);
+    to_js(&report)
+}
+
+/// Reconstruct the full private key from ≥ threshold CoreKeyShares.
+///
+/// This is the platform-exit escape hatch and removes all threshold
+/// protection — the caller owns the returned 32-byte secret. `core_shares`
+/// is a JS array of `Uint8Array`; `confirm` must be exactly
+/// `"EXPORT_PRIVATE_KEY"`. The reconstructed key is checked against the
+/// shared public key before it is returned.
+#[wasm_bindgen]
+pub fn reconstruct_private_key(core_shares: JsByteArrays, confirm: &str) -> Result<Vec<u8>, JsError> {
+    let core_shares = byte_arrays_from_js(core_shares, "core shares")?;
+
+    let exported = key_export::reconstruct_private_key(&core_shares, confirm)
+        .map_err(|e| JsError::new(&e))?;
+    // Copy out to JS; the Rust-side buffer is wiped when `exported` drops.
+    Ok(exported.private_key.to_vec())
+}
+
+// ─── Share Encryption ───────────────────────────────────────────────────────
+
+/// Encrypt a serialised share under a passphrase (Argon2id + AES-256-GCM).
+///
+/// `params` is an optional `{ m_cost, t_cost, p_cost }` object overriding the
+/// default Argon2id cost; `m_cost` is at most 262144 KiB (256 MiB), the
+/// most `decrypt_share` will allocate. The returned blob is self-describing (salt, nonce,
+/// KDF parameters and format version are embedded).
+#[wasm_bindgen]
+pub fn encrypt_share(
+    share_bytes: JsBytes,
+    passphrase: &str,
+    params: Option<JsKdfParams>,
+) -> Result<Vec<u8>, JsError> {
+    let share_bytes = &js_bytes(share_bytes, "share_bytes")?;
+    let params: share_crypto::KdfParams = options_from_js(params, "kdf params")?;
+
+    share_crypto::encrypt_share(share_bytes, passphrase, params).map_err(|e| JsError::new(&e))
+}
+
+/// Decrypt a blob produced by `encrypt_share`.
+///
+/// A wrong passphrase or a tampered blob fails with `DECRYPT_AUTH_FAILED`;
+/// KDF costs above what `encrypt_share` accepts fail with
+/// `INVALID_KDF_PARAMS` before any memory is allocated for them.
+#[wasm_bindgen]
+pub fn decrypt_share(blob: JsBytes, passphrase: &str) -> Result<Vec<u8>, JsError> {
+    let blob = &js_bytes(blob, "blob")?;
+    let plaintext =
+        share_crypto::decrypt_share(blob, passphrase).map_err(|e| JsError::new(&e))?;
+    // Copy out to JS; the Rust-side buffer is wiped when `plaintext` drops.
+    Ok(plaintext.to_vec())
+}
+
+/// Encrypt a share to a recipient's secp256k1 public key, 33-byte
+/// compressed or 65-byte uncompressed (ECIES: ephemeral ECDH + HKDF-SHA256 + AES-256-GCM).
+///
+/// Used to deliver the user's share from DKG to a key the browser generated
+/// before the ceremony, so it never crosses the wire in plaintext.
+#[wasm_bindgen]
+pub fn wrap_share_to_recipient(
+    share_bytes: JsBytes,
+    recipient_pubkey: JsBytes,
+) -> Result<Vec<u8>, JsError> {
+    let share_bytes = &js_bytes(share_bytes, "share_bytes")?;
+    let recipient_pubkey = &js_bytes(recipient_pubkey, "recipient_pubkey")?;
+    share_crypto::wrap_share_to_recipient(share_bytes, recipient_pubkey)
+        .map_err(|e| JsError::new(&e))
+}
+
+/// Decrypt a blob produced by `wrap_share_to_recipient` with the recipient's
+/// 32-byte secret key. Fails with `DECRYPT_AUTH_FAILED` on a wrong key or a
+/// tampered envelope.
+#[wasm_bindgen]
+pub fn unwrap_share(blob: JsBytes, recipient_secret: JsBytes) -> Result<Vec<u8>, JsError> {
+    let blob = &js_bytes(blob, "blob")?;
+    let recipient_secret = &js_bytes(recipient_secret, "recipient_secret")?;
+    let plaintext =
+        share_crypto::unwrap_share(blob, recipient_secret).map_err(|e| JsError::new(&e))?;
+    Ok(plaintext.to_vec())
+}
+
+// ─── Interactive Signing ────────────────────────────────────────────────────
+
+/// Create an interactive signing session for one party.
+///
+/// # Arguments
+/// - `core_share`: serialised CoreKeyShare (serde_json bytes), or
+///   `{ key_id }` naming a key from `register_key`
+/// - `aux_info`: serialised AuxInfo (serde_json bytes); omitted with a
+///   `key_id`
+/// - `message_hash`: 32-byte hash to sign, or `{ message }` to sign raw
+///   bytes that cggmp24 hashes with SHA-256 (the signature verifies
+///   against SHA-256(message))
+/// - `party_index`: this party's index at keygen time (0-based), or its
+///   label for a key from `run_keygen_for_parties` with other indices
+/// - `parties_at_keygen`: array of party indices participating in signing,
+///   labels likewise; messages name parties by the same
+/// - `eid`: execution ID bytes (32 bytes), or `{ wallet_id, purpose: "sign", nonce }`
+///   to derive it as `derive_eid` does. Fails with `EID_REUSE` while
+///   another live session of this party signs under it with the same key,
+///   unless `options.allow_eid_reuse`
+/// - `options`: optional `SignOptions` object (e.g. `{ skip_verify: true }`).
+///   `approval_callback` is called with `{ message_hash_hex, parties,
+///   party_index, created_at }` once the signature is ready, and the
+///   signature is only released if it returns (or resolves to) a truthy
+///   value; otherwise the session fails with `POLICY_REJECTED`. While a
+///   returned Promise is pending the session reports `computing`: call
+///   `sign_process_round` with `[]` after it settles
+///
+/// # Returns
+/// JS object: `{ session_id: string, messages: WasmSignMessage[], complete, signature?, protocol_info }`.
+/// A single-party key (`allow_single_party`) signs right here: `complete`
+/// is true and `signature` set, with no messages to relay.
+///
+/// @deprecated Use the `SigningSession` class; removed in the next release.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_create_session(
+    core_share: JsSigningKey,
+    aux_info: Option<JsBytes>,
+    message_hash: JsSignData,
+    party_index: u16,
+    parties_at_keygen: &[u16],
+    eid: JsEid,
+    options: Option<JsSignOptions>,
+) -> Result<JsCreateSessionResult, JsError> {
+    let (core_share, aux_info) = &signing_key(core_share, aux_info)?;
+    let input = sign_input(message_hash)?;
+    let eid = &js_eid(eid, "eid", eid::EidPurpose::Sign)?;
+    let options = sign_options(options)?;
+
+    let result = sign::create_session(
+        core_share,
+        aux_info,
+        input.data(),
+        party_index,
+        parties_at_keygen,
+        eid,
+        &options,
+    )
+    .map_err(|e| JsError::new(&e))?;
+    session_store::sync(&result.session_id);
+
+    to_js(&result)
+}
+
+/// `sign_create_session` that returns at once: the session is built but
+/// its first messages aren't computed yet, which is most of round 1's
+/// cost. Same arguments; the result has no messages and `complete` false.
+///
+/// Compute them with `sign_warmup` in idle time — e.g. from the moment the
+/// user clicks "send", before the co-signer is contacted — and forward what
+/// it returns. `sign_process_round` works on the session whether or not
+/// the warm-up finished: it first completes what is left. The signature is
+/// the same either way.
+///
+/// # Returns
+/// JS object: `{ session_id: string, messages: [], complete: false, protocol_info }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_create_session_deferred(
+    core_share: JsSigningKey,
+    aux_info: Option<JsBytes>,
+    message_hash: JsSignData,
+    party_index: u16,
+    parties_at_keygen: &[u16],
+    eid: JsEid,
+    options: Option<JsSignOptions>,
+) -> Result<JsCreateSessionResult, JsError> {
+    let (core_share, aux_info) = &signing_key(core_share, aux_info)?;
+    let input = sign_input(message_hash)?;
+    let eid = &js_eid(eid, "eid", eid::EidPurpose::Sign)?;
+    let options = sign_options(options)?;
+
+    let result = sign::create_session_deferred(
+        core_share,
+        aux_info,
+        input.data(),
+        party_index,
+        parties_at_keygen,
+        eid,
+        &options,
+    )
+    .map_err(|e| JsError::new(&e))?;
+    to_js(&result)
+}
+
+/// Compute a `sign_create_session_deferred` session's first messages for
+/// about `budget_ms` milliseconds (at least one protocol step), e.g. from
+/// `requestIdleCallback` with the deadline's `timeRemaining()`. Same result
+/// shape as `sign_process_round`: forward `messages`; `status` stays
+/// `"computing"` until the first messages are all out, then `"waiting"`.
+///
+/// Once the session is past its first messages this drives nothing and
+/// returns no messages, so it is safe to keep calling.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_warmup(session_id: &str, budget_ms: f64) -> Result<JsProcessRoundResult, JsError> {
+    let result = sign::warmup(session_id, budget_ms);
+    session_store::sync(session_id);
+    to_js(&result.map_err(|e| JsError::new(&e))?)
+}
+
+/// Create one signing session covering several hashes signed with the same
+/// key share by the same parties — e.g. a payout run — so they share setup
+/// and relay round trips.
+///
+/// Arguments are those of `sign_create_session`, with `message_hashes` (an
+/// array of 32-byte hashes, 1 to 256, the same order at every party) in
+/// place of `message_hash`. Every message in and out carries a
+/// `message_index`; drive the batch with `sign_process_round`, whose result
+/// lists `signatures` (one per hash, `undefined` until that one completes).
+/// `sign_abort_session` / `sign_destroy_session` take the batch's id too;
+/// `sign_export_session` doesn't.
+///
+/// # Returns
+/// JS object: `{ session_id: string, messages: WasmSignMessage[] }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_create_batch_session(
+    core_share: JsSigningKey,
+    aux_info: Option<JsBytes>,
+    message_hashes: JsByteArrays,
+    party_index: u16,
+    parties_at_keygen: &[u16],
+    eid: JsEid,
+    options: Option<JsSignOptions>,
+) -> Result<JsCreateSessionResult, JsError> {
+    let (core_share, aux_info) = &signing_key(core_share, aux_info)?;
+    let message_hashes = byte_arrays_from_js(message_hashes, "message_hashes")?;
+    let eid = &js_eid(eid, "eid", eid::EidPurpose::Sign)?;
+    let options = sign_options(options)?;
+
+    let result = sign::create_batch_session(
+        core_share,
+        aux_info,
+        &message_hashes,
+        party_index,
+        parties_at_keygen,
+        eid,
+        &options,
+    )
+    .map_err(|e| JsError::new(&e))?;
+
+    to_js(&result)
+}
+
+/// Process a round of incoming messages for an existing signing session.
+/// With a session store (see `init`), an unknown `session_id` is first
+/// looked up there, and the session's export saved after the round.
+///
+/// # Arguments
+/// - `session_id`: the session ID returned by `sign_create_session`
+/// - `incoming_messages`: JS array of `WasmSignMessage` objects
+/// - `cancel_token`: once cancelled, the session is aborted as by
+///   `sign_abort_session` with reason `CANCELLED`, and that result returned
+/// - `max_millis`: time budget for the call, to keep UI frames responsive.
+///   Once spent, the call returns the messages so far with status
+///   `computing`; forward them and call again with `[]` to continue. Omit
+///   to run the round to the end
+///
+/// # Returns
+/// JS object: `{ messages: WasmSignMessage[], complete: bool, signature?: { r, s },
+/// stale_messages, misrouted_skipped, accepted: number[], rejected: { index, code, detail }[],
+/// metrics: { deserialize_ms, proceed_ms, serialize_ms, messages, total }, outgoing_bytes, more }`
+///
+/// `accepted` and `rejected` index into `incoming_messages`, for a relay
+/// that retries until acknowledged: resend neither. A message that fails
+/// its checks (wrong version, binding, encoding or auth tag, an unknown
+/// sender, over the limits, a payload that doesn't decode) is rejected
+/// with the error code, and the rest of the call goes through without it.
+///
+/// `outgoing_bytes` is the size of `messages` as JSON. A session created
+/// with `max_outgoing_bytes` returns no more than that per call (one
+/// message at least) and sets `more` when it held some back: forward these
+/// and call again with `[]` for the rest, before destroying a completed
+/// session too.
+///
+/// Messages not for this party (P2P to another, or under this party's own
+/// index) are dropped and counted in `misrouted_skipped`, or rejected with
+/// `MISROUTED_MESSAGE` under `on_misrouted: "error"`.
+///
+/// An `abort` message from a peer (see `sign_abort_session`) fails the call
+/// with `PEER_ABORTED` and destroys the session. If the protocol itself
+/// fails, the error is `PROTOCOL_FAILURE: ` followed by a JSON
+/// `ProtocolFailure` (`{ kind, blamed_parties, round, detail }`) whose
+/// `blamed_parties` are the keygen indices identifiable abort pins it on.
+///
+/// @deprecated Use `SigningSession.processRound`; removed in the next
+/// release. Sessions from `sign_import_session` still go through here.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_process_round(
+    session_id: &str,
+    incoming_messages: JsSignMessages,
+    cancel_token: Option<u32>,
+    max_millis: Option<f64>,
+) -> Result<JsProcessRoundResult, JsError> {
+    session_store::restore(session_id);
+    if cancel::is_cancelled(cancel_token) {
+        // Tell the peers rather than leave them waiting on this party
+        let result = sign::abort_session(session_id, "CANCELLED");
+        session_store::sync(session_id);
+        return to_js(&result.map_err(|e| JsError::new(&e))?);
+    }
+    let incoming: Vec<sign::WasmSignMessage> = serde_wasm_bindgen::from_value(incoming_messages.into())
+        .map_err(|e| JsError::new(&format!("deserialize incoming messages: {e}")))?;
+
+    let result = sign::process_round(session_id, &incoming, max_millis);
+    session_store::sync(session_id);
+
+    to_js(&result.map_err(|e| JsError::new(&e))?)
+}
+
+/// Queue one incoming message on a (non-batch) signing session without
+/// driving it; `sign_poll` delivers and drives. The message gets the
+/// checks `sign_process_round` makes, failing with the code it would be
+/// rejected with, and an `abort` from a peer fails with `PEER_ABORTED` and
+/// destroys the session.
+///
+/// Returns false if the message was dropped as stale or addressed to
+/// another party.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_feed_message(session_id: &str, msg: JsSignMessage) -> Result<bool, JsError> {
+    let msg: sign::WasmSignMessage = serde_wasm_bindgen::from_value(msg.into())
+        .map_err(|e| JsError::new(&format!("deserialize incoming message: {e}")))?;
+    sign::feed_message(session_id, &msg).map_err(|e| JsError::new(&e))
+}
+
+/// Drive a (non-batch) signing session for at most `max_steps` protocol
+/// steps (unbounded if omitted), delivering fed messages as it asks for
+/// them. Same result shape as `sign_process_round`; forward `messages` at
+/// once. Poll again while it returns messages, or after the next feed.
+///
+/// Feeding a round's messages one by one and polling until the session
+/// stops asking gives exactly what one `sign_process_round` with the same
+/// messages would. A session can only be exported between rounds, once
+/// the polls of one ran to the end.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_poll(session_id: &str, max_steps: Option<u32>) -> Result<JsProcessRoundResult, JsError> {
+    let result = sign::poll(session_id, max_steps).map_err(|e| JsError::new(&e))?;
+    to_js(&result)
+}
+
+/// Sort the signing messages a relay received into what each of
+/// `all_parties` (keygen indices) should be handed: broadcasts to every
+/// party but the sender, P2P messages to their `recipient` only, each
+/// party's in the order sent. Every party has an entry, empty if nothing
+/// is for it.
+///
+/// Fails on the first message that can't be routed, with the code
+/// `validate_routing` reports for it.
+///
+/// # Returns
+/// JS object: `{ [party]: WasmSignMessage[] }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn route_messages(messages: JsSignMessages, all_parties: Vec<u16>) -> Result<JsRoutedMessages, JsError> {
+    let messages: Vec<sign::WasmSignMessage> = serde_wasm_bindgen::from_value(messages.into())
+        .map_err(|e| JsError::new(&format!("deserialize messages: {e}")))?;
+    let routed = relay::route_messages(&messages, &all_parties, sign::WasmSignMessage::route)
+        .map_err(|e| JsError::new(&e))?;
+    to_js(&routed)
+}
+
+/// Every signing message `route_messages` would refuse among `messages`
+/// for `parties`: a sender or recipient not among them (`UNKNOWN_SENDER`,
+/// `UNKNOWN_RECIPIENT`), a message addressed to its sender
+/// (`SELF_ADDRESSED`), or `is_broadcast` disagreeing with `recipient`
+/// (`INCONSISTENT_ROUTE`).
+///
+/// # Returns
+/// JS object: `{ valid, issues }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn validate_routing(messages: JsSignMessages, parties: Vec<u16>) -> Result<JsRoutingReport, JsError> {
+    let messages: Vec<sign::WasmSignMessage> = serde_wasm_bindgen::from_value(messages.into())
+        .map_err(|e| JsError::new(&format!("deserialize messages: {e}")))?;
+    to_js(&relay::validate_routing(&messages, &parties, sign::WasmSignMessage::route))
+}
+
+/// Destroy a signing session and free all resources.
+///
+/// Returns `true` if the session existed and was destroyed.
+///
+/// @deprecated A `SigningSession` is destroyed by `free()`; removed in the
+/// next release.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_destroy_session(session_id: &str) -> bool {
+    session_store::delete(session_id);
+    sign::destroy_session(session_id)
+}
+
+/// Abandon a signing session (ECDSA or FROST), e.g. when policy rejects the
+/// transaction mid-ceremony. The session is destroyed; relay the returned
+/// `messages` (one `abort` notice carrying `reason`) so the other parties'
+/// next `process_round` fails with `PEER_ABORTED` rather than hanging.
+///
+/// # Returns
+/// `{ messages, complete: false, aborted: true, reason }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_abort_session(session_id: &str, reason: &str) -> Result<JsProcessRoundResult, JsError> {
+    session_store::delete(session_id);
+    let result = sign::abort_session(session_id, reason).map_err(|e| JsError::new(&e))?;
+    to_js(&result)
+}
+
+/// One party's interactive ECDSA signing session.
+///
+/// Owns its entry in the session store: `free()` — or the garbage
+/// collector, where wasm-bindgen's weak references are enabled — destroys
+/// it, key material included. `sessionId` works with the session-id
+/// exports (`sign_session_metrics`, `sign_export_session`).
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub struct SigningSession {
+    id: String,
+    first_messages: Vec<sign::WasmSignMessage>,
+    more_first_messages: bool,
+    signature: Option<types::SignatureResult>,
+}
+
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+impl SigningSession {
+    /// Create the session; arguments as `sign_create_session`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        core_share: JsSigningKey,
+        aux_info: Option<JsBytes>,
+        message_hash: JsSignData,
+        party_index: u16,
+        parties_at_keygen: &[u16],
+        eid: JsEid,
+        options: Option<JsSignOptions>,
+    ) -> Result<SigningSession, JsError> {
+        let (core_share, aux_info) = &signing_key(core_share, aux_info)?;
+        let input = sign_input(message_hash)?;
+        let eid = &js_eid(eid, "eid", eid::EidPurpose::Sign)?;
+        let options = sign_options(options)?;
+        let created = sign::create_session(
+            core_share,
+            aux_info,
+            input.data(),
+            party_index,
+            parties_at_keygen,
+            eid,
+            &options,
+        )
+        .map_err(|e| JsError::new(&e))?;
+        session_store::sync(&created.session_id);
+
+        Ok(SigningSession {
+            id: created.session_id,
+            first_messages: created.messages,
+            more_first_messages: created.more,
+            signature: None,
+        })
+    }
+
+    #[wasm_bindgen(getter, js_name = sessionId)]
+    pub fn session_id(&self) -> String {
+        self.id.clone()
+    }
+
+    /// Messages to send before the first `processRound`.
+    #[wasm_bindgen(js_name = firstMessages)]
+    pub fn first_messages(&self) -> Result<JsSignMessages, JsError> {
+        to_js(&self.first_messages)
+    }
+
+    /// `max_outgoing_bytes` held back some of the first messages:
+    /// `processRound([])` returns the rest.
+    #[wasm_bindgen(getter, js_name = moreFirstMessages)]
+    pub fn more_first_messages(&self) -> bool {
+        self.more_first_messages
+    }
+
+    /// Deliver one round of incoming messages; same result as
+    /// `sign_process_round`, including its `max_millis` budget.
+    #[wasm_bindgen(js_name = processRound)]
+    pub fn process_round(
+        &mut self,
+        incoming_messages: JsSignMessages,
+        max_millis: Option<f64>,
+    ) -> Result<JsProcessRoundResult, JsError> {
+        let incoming: Vec<sign::WasmSignMessage> =
+            serde_wasm_bindgen::from_value(incoming_messages.into())
+                .map_err(|e| JsError::new(&format!("deserialize incoming messages: {e}")))?;
+
+        let result = sign::process_round(&self.id, &incoming, max_millis);
+        session_store::sync(&self.id);
+        let result = result.map_err(|e| JsError::new(&e))?;
+        if result.signature.is_some() {
+            self.signature = result.signature.clone();
+        }
+        to_js(&result)
+    }
+
+    /// Queue one incoming message without driving; see `sign_feed_message`.
+    #[wasm_bindgen(js_name = feedMessage)]
+    pub fn feed_message(&mut self, msg: JsSignMessage) -> Result<bool, JsError> {
+        sign_feed_message(&self.id, msg)
+    }
+
+    /// Drive for at most `max_steps` steps; see `sign_poll`.
+    pub fn poll(&mut self, max_steps: Option<u32>) -> Result<JsProcessRoundResult, JsError> {
+        let result = sign::poll(&self.id, max_steps).map_err(|e| JsError::new(&e))?;
+        if result.signature.is_some() {
+            self.signature = result.signature.clone();
+        }
+        to_js(&result)
+    }
+
+    #[wasm_bindgen(js_name = isComplete)]
+    pub fn is_complete(&self) -> bool {
+        self.signature.is_some()
+    }
+
+    /// The signature once complete, `undefined` before.
+    pub fn signature(&self) -> Result<Option<JsSignatureResult>, JsError> {
+        self.signature.as_ref().map(to_js).transpose()
+    }
+
+    /// Abandon the session; same result as `sign_abort_session`. Relay the
+    /// returned abort notice to the other parties.
+    pub fn abort(&mut self, reason: &str) -> Result<JsProcessRoundResult, JsError> {
+        session_store::delete(&self.id);
+        let result = sign::abort_session(&self.id, reason).map_err(|e| JsError::new(&e))?;
+        to_js(&result)
+    }
+}
+
+#[cfg(feature = "signing")]
+impl Drop for SigningSession {
+    fn drop(&mut self) {
+        session_store::delete(&self.id);
+        sign::destroy_session(&self.id);
+    }
+}
+
+/// Cumulative timings of a live signing session (ECDSA or FROST).
+///
+/// # Returns
+/// JS object: `{ setup_ms, rounds, deserialize_ms, proceed_ms, serialize_ms, messages, cache_hit }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_session_metrics(session_id: &str) -> Result<JsSessionMetrics, JsError> {
+    let metrics = sign::session_metrics(session_id).map_err(|e| JsError::new(&e))?;
+    to_js(&metrics)
+}
+
+/// Audit record of an ECDSA signing session: binding tag, message hash,
+/// signers, this party and creation time, plus `r`, `s`, `v` and the
+/// transcript hash once complete. The completing `sign_process_round`
+/// result carries the same record. Fails with `UNSUPPORTED` for FROST and
+/// batch sessions.
+///
+/// # Returns
+/// JS object: `{ session_binding_tag, message_hash, parties, party_index, created_at, r?, s?, v?, transcript_hash? }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_audit_record(session_id: &str) -> Result<JsAuditRecord, JsError> {
+    let record = sign::audit_record(session_id).map_err(|e| JsError::new(&e))?;
+    to_js(&record)
+}
+
+/// Liveness of a signing session (ECDSA, FROST or batch), for a host timer
+/// telling a party that is computing from one that is stuck.
+///
+/// Steps only happen inside `sign_process_round` / `sign_poll`, so drive a
+/// heavy round with `sign_poll(id, maxSteps)` to let heartbeats in between.
+/// An unknown or destroyed session reports `alive: false`.
+///
+/// # Returns
+/// JS object: `{ alive, status, round, steps, progress, last_progress_ms_ago }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_heartbeat(session_id: &str) -> Result<JsHeartbeat, JsError> {
+    to_js(&sign::heartbeat(session_id))
+}
+
+/// Rounds and per-round message counts of a signing session, before
+/// creating one, for relays that allocate per round.
+///
+/// `options`: `{ signers, protocol?: "ecdsa" | "frost", reliable_broadcast? }`;
+/// `reliable_broadcast` (default true) adds an ECDSA round and must match
+/// the sessions' `SignOptions`. `sign_create_session` results carry the same
+/// as `protocol_info`.
+///
+/// # Returns
+/// JS object: `{ rounds, broadcasts_per_round, p2p_per_round, with_reliable_broadcast }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn signing_protocol_info(options: JsSigningProtocolOptions) -> Result<JsSigningProtocolInfo, JsError> {
+    let options: sign::SigningProtocolOptions = options_from_js(Some(options), "signing protocol options")?;
+    let info = sign::signing_protocol_info(&options).map_err(|e| JsError::new(&e))?;
+    to_js(&info)
+}
+
+/// Status of a signing session (ECDSA, FROST or batch).
+///
+/// A session whose `sign_process_round` / `sign_poll` / `sign_feed_message`
+/// failed part-way is `failed`: its key material is already freed and
+/// every other call on it throws `SESSION_FAILED` with the first error,
+/// which is returned here as `reason`. Destroy it and start a new one.
+///
+/// # Returns
+/// JS object: `{ status, reason? }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_session_status(session_id: &str) -> Result<JsSessionState, JsError> {
+    let state = sign::session_status(session_id).map_err(|e| JsError::new(&e))?;
+    to_js(&state)
+}
+
+/// Memory in use: the WASM linear memory, and what the live signing
+/// sessions hold.
+///
+/// `pinned_bytes` is the serialised key material the live sessions were
+/// built from; it returns to its old value once a session is destroyed, so
+/// a figure that only grows points at sessions never destroyed.
+///
+/// `registered_keys` / `registered_key_bytes` count what `register_key`
+/// holds until `unregister_key`.
+///
+/// `primes_bytes` is what `run_dkg_with_primes` holds of its primes (raw
+/// blobs being parsed, and parsed sets) while it runs; `primes_peak_bytes`
+/// the most it ever held at once.
+///
+/// `eid_reservations` counts the ECDSA sessions holding their eid against
+/// reuse: live ones that haven't completed or failed.
+///
+/// # Returns
+/// JS object: `{ wasm_memory_bytes, live_sessions, pinned_bytes, sessions_created, sessions_destroyed,
+/// registered_keys, registered_key_bytes, primes_bytes, primes_peak_bytes, eid_reservations }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn memory_stats() -> Result<JsMemoryStats, JsError> {
+    to_js(&metrics::memory_stats())
+}
+
+/// Keep a core share / aux info pair in the module under an optional
+/// label, so signing sessions can name it as `{ key_id }` instead of
+/// passing the blobs each time. Checks the pair combines, as a signing
+/// session would, and caches the combined share.
+///
+/// Returns the key id: the share's fingerprint (`share_fingerprint`), so
+/// registering the same share again returns the same id and updates the
+/// label. Fails with `INVALID_LABEL` for a label over 256 bytes.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn register_key(core_share: JsBytes, aux_info: JsBytes, label: Option<String>) -> Result<String, JsError> {
+    let core_share = js_bytes(core_share, "core_share")?;
+    let aux_info = js_bytes(aux_info, "aux_info")?;
+    key_registry::register_key(&core_share, &aux_info, label.as_deref()).map_err(|e| JsError::new(&e))
+}
+
+/// The registered keys, oldest first, with what a wallet picker shows:
+/// label, public key, Ethereum address (secp256k1), threshold, `n` and
+/// this party's index.
+///
+/// # Returns
+/// JS array: `{ key_id, label?, curve, public_key, address?, threshold, n, party_index }[]`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn list_keys() -> Result<JsKeyInfos, JsError> {
+    to_js(&key_registry::list_keys())
+}
+
+/// Forget a registered key, wiping its blobs; sessions already created
+/// with it run on. Later uses of the id fail with `KEY_NOT_FOUND`. Returns
+/// false if no such key was registered.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn unregister_key(key_id: &str) -> bool {
+    key_registry::unregister_key(key_id)
+}
+
+/// Serialise a live signing session (ECDSA or FROST) so it survives a page
+/// reload; pass the bytes to `sign_import_session` to resume it. The
+/// session stays live.
+///
+/// The export contains the key share and the session's nonce seed — store
+/// it like a share (e.g. through `encrypt_share`) and import it only once.
+/// A session with an `approval_callback` fails with `UNSUPPORTED`.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_export_session(session_id: &str) -> Result<Vec<u8>, JsError> {
+    sign::export_session(session_id, true).map_err(|e| JsError::new(&e))
+}
+
+/// Resume a session exported with `sign_export_session`.
+///
+/// Replays the recorded rounds and registers the session under its original
+/// ID, which is returned; continue with `sign_process_round` (or
+/// `frost_sign_process_round`) from the next round. Fails with
+/// `SESSION_EXISTS` if that session is still live here, or
+/// `INVALID_SESSION_EXPORT` on a malformed export.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn sign_import_session(bytes: JsBytes) -> Result<String, JsError> {
+    let bytes = &js_bytes(bytes, "bytes")?;
+    sign::import_session(bytes).map_err(|e| JsError::new(&e))
+}
+
+/// Set the message limits for signing sessions created from now on (ECDSA
+/// and FROST). Fields left out keep their current value; a session can
+/// override them again through the `limits` field of its options.
+///
+/// `limits`: `{ max_message_bytes?, max_messages_per_round?, max_messages_per_session? }`
+/// — defaults 512 KiB, 64 and 10 000.
+///
+/// # Returns
+/// The limits now in effect.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn set_message_limits(limits: Option<JsLimitOverrides>) -> Result<JsMessageLimits, JsError> {
+    let overrides: limits::LimitOverrides = options_from_js(limits, "message limits")?;
+    let limits = sign::default_limits().with_overrides(&overrides);
+    sign::set_default_limits(limits);
+    to_js(&limits)
+}
+
+/// Set how many combined key shares signing keeps cached (default 8; 0
+/// disables the cache). Sessions whose core share and aux info were seen
+/// recently skip deserialising them; `metrics.cache_hit` reports it.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn set_share_cache_capacity(capacity: u32) {
+    sign::set_share_cache_capacity(usize::try_from(capacity).unwrap_or(usize::MAX));
+}
+
+/// Drop the cached key share for this core share / aux info pair, e.g.
+/// when the wallet is removed. Returns whether one was cached; live
+/// sessions keep their own reference.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn evict_cached_share(core_share: JsBytes, aux_info: JsBytes) -> Result<bool, JsError> {
+    let core_share = &js_bytes(core_share, "core_share")?;
+    let aux_info = &js_bytes(aux_info, "aux_info")?;
+    Ok(sign::evict_cached_share(core_share, aux_info))
+}
+
+/// How many signing sessions reused the eid of an earlier one in this
+/// instance (among the last 1024). Anything above 0 means a caller isn't
+/// deriving a fresh eid per signing; see `derive_eid`. Reuse while the
+/// first session is still live fails outright with `EID_REUSE`.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn eid_reuse_count() -> u32 {
+    sign::eid_reuse_count()
+}
+
+/// EIP-155 `v` for a legacy transaction: `chain_id * 2 + 35 + recovery_id`.
+///
+/// `recovery_id` must be 0 or 1 (`INVALID_RECOVERY_ID`); a chain id too
+/// large for the formula fails with `CHAIN_ID_OVERFLOW`. Signing sessions
+/// created with `SignOptions.chain_id` return this as `v_eip155`.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn eip155_v(recovery_id: u8, chain_id: u64) -> Result<u64, JsError> {
+    signature::eip155_v(recovery_id, chain_id).map_err(|e| JsError::new(&e))
+}
+
+/// Bitcoin signature encoding: the DER form of a 32-byte `r` / `s` with
+/// the `sighash_type` byte appended, ready for a scriptSig or witness.
+/// A high `s` is normalised to `n - s` (BIP-62 low-s).
+///
+/// Fails with `INVALID_SIGNATURE` or `INVALID_SIGHASH_TYPE`.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn bitcoin_script_sig(r: JsBytes, s: JsBytes, sighash_type: u8) -> Result<Vec<u8>, JsError> {
+    let r = js_bytes(r, "r")?;
+    let s = js_bytes(s, "s")?;
+    bitcoin::script_sig(&r, &s, sighash_type).map_err(|e| JsError::new(&e))
+}
+
+/// Native segwit (P2WPKH, bech32) address of a 33-byte compressed
+/// secp256k1 key on `network` (`"mainnet"` / `"testnet"`).
+///
+/// Fails with `INVALID_PUBLIC_KEY` or `INVALID_NETWORK`.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn bitcoin_p2wpkh_address(public_key: JsBytes, network: &str) -> Result<String, JsError> {
+    let public_key = js_bytes(public_key, "public_key")?;
+    let network = bitcoin::Network::parse(network).map_err(|e| JsError::new(&e))?;
+    bitcoin::p2wpkh_address(&public_key, network).map_err(|e| JsError::new(&e))
+}
+
+/// Recover the 33-byte compressed public key an ECDSA signature over the
+/// 32-byte `message_hash` was made with, as `ecrecover` does.
+///
+/// `v` is the recovery id (0 / 1), its Ethereum form (27 / 28) or an
+/// EIP-155 `v`; anything else fails with `INVALID_RECOVERY_ID`. `r` / `s`
+/// are 32 bytes each; `curve` defaults to secp256k1. Fails with
+/// `RECOVERY_FAILED` when the signature recovers no key.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn recover_public_key(
+    message_hash: JsBytes,
+    r: JsBytes,
+    s: JsBytes,
+    v: u64,
+    curve: Option<String>,
+) -> Result<Vec<u8>, JsError> {
+    let message_hash = js_bytes(message_hash, "message_hash")?;
+    let r = js_bytes(r, "r")?;
+    let s = js_bytes(s, "s")?;
+    for (name, bytes) in [("message_hash", &message_hash), ("r", &r), ("s", &s)] {
+        if bytes.len() != 32 {
+            return Err(JsError::new(&format!("{name} must be 32 bytes, got {}", bytes.len())));
+        }
+    }
+    let curve = share_format::CurveId::parse_opt(curve.as_deref()).map_err(|e| JsError::new(&e))?;
+    let recovery_id = signature::recovery_id_from_v(v).map_err(|e| JsError::new(&e))?;
+    let sig_bytes = [&r[..], &s[..]].concat();
+
+    share_format::with_curve!(curve, E => {
+        let message = generic_ec::Scalar::<E>::from_be_bytes_mod_order(&message_hash);
+        signature::recover_public_key(&message, &sig_bytes, recovery_id)
+            .map(|key| key.to_bytes(true).as_bytes().to_vec())
+    })
+    .ok_or_else(|| JsError::new("RECOVERY_FAILED: the signature recovers no public key"))
+}
+
+/// `normalize_signature` result.
+#[cfg(feature = "signing")]
+#[derive(Serialize, Tsify)]
+struct NormalizedSignature {
+    #[tsify(type = "Uint8Array")]
+    r: ByteBuf,
+    #[tsify(type = "Uint8Array")]
+    s: ByteBuf,
+    /// `s` was above `n / 2` and is now `n - s`
+    flipped: bool,
+}
+
+/// The low-s form of an ECDSA signature, for signatures made with
+/// `SignOptions.normalize_s: false` or elsewhere: `s` becomes `n - s` when
+/// it is above `n / 2`. The flip also flips the recovery id (`v`), so
+/// recover it from the signature returned here.
+///
+/// `r` / `s` are 32 bytes each; `curve` defaults to secp256k1. Fails with
+/// `INVALID_SIGNATURE` for a zero or out-of-range `r` or `s`.
+///
+/// # Returns
+/// JS object: `{ r, s, flipped }`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn normalize_signature(r: JsBytes, s: JsBytes, curve: Option<String>) -> Result<JsNormalizedSignature, JsError> {
+    let r = js_bytes(r, "r")?;
+    let s = js_bytes(s, "s")?;
+    for (name, bytes) in [("r", &r), ("s", &s)] {
+        if bytes.len() != 32 {
+            return Err(JsError::new(&format!("{name} must be 32 bytes, got {}", bytes.len())));
+        }
+    }
+    let curve = share_format::CurveId::parse_opt(curve.as_deref()).map_err(|e| JsError::new(&e))?;
+    let mut sig_bytes = [&r[..], &s[..]].concat();
+    let flipped = share_format::with_curve!(curve, E => signature::normalize_s::<E>(&mut sig_bytes))
+        .map_err(|e| JsError::new(&e))?;
+    to_js(&NormalizedSignature {
+        r: ByteBuf::from(sig_bytes[..32].to_vec()),
+        s: ByteBuf::from(sig_bytes[32..].to_vec()),
+        flipped,
+    })
+}
+
+// ─── Threshold Schnorr (FROST) ──────────────────────────────────────────────
+
+/// Run a complete FROST DKG (BIP-340 ciphersuite) for `n` parties with
+/// threshold `t`, all parties local.
+///
+/// # Returns
+/// JS object: `{ shares: Uint8Array[], public_key: Uint8Array(33), x_only_public_key: Uint8Array(32) }`
+#[cfg(feature = "dkg")]
+#[wasm_bindgen]
+pub fn frost_run_dkg(eid_bytes: JsBytes, n: u16, threshold: u16) -> Result<JsFrostDkgResult, JsError> {
+    let eid_bytes = &js_bytes(eid_bytes, "eid_bytes")?;
+    validate_dkg_params(n, threshold)?;
+    let result = frost::run_dkg(eid_bytes, n, threshold).map_err(|e| JsError::new(&e))?;
+    to_js(&result)
+}
+
+/// Create a FROST signing session for one party.
+///
+/// # Arguments
+/// - `key_share`: FROST key share from `frost_run_dkg`
+/// - `message`: message to sign (BIP-340 signs the message itself)
+/// - `party_index`: this party's index at keygen time (0-based)
+/// - `signers`: keygen indices of all signing parties
+/// - `options`: optional `{ skip_verify: true }`
+///
+/// # Returns
+/// JS object: `{ session_id: string, messages: WasmSignMessage[] }`, as
+/// `sign_create_session`
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn frost_sign_create_session(
+    key_share: JsBytes,
+    message: JsBytes,
+    party_index: u16,
+    signers: &[u16],
+    options: Option<JsFrostSignOptions>,
+) -> Result<JsCreateSessionResult, JsError> {
+    let key_share = &js_bytes(key_share, "key_share")?;
+    let message = &js_bytes(message, "message")?;
+    let options: frost::FrostSignOptions = options_from_js(options, "frost sign options")?;
+
+    let result = frost::create_session(key_share, message, party_index, signers, &options)
+        .map_err(|e| JsError::new(&e))?;
+
+    to_js(&result)
+}
+
+/// Process a round of incoming messages for a FROST signing session.
+///
+/// Same envelope as `sign_process_round`; on completion `signature` is the
+/// BIP-340 signature split as `{ r: R.x, s }`. Destroy with
+/// `sign_destroy_session`.
+#[cfg(feature = "signing")]
+#[wasm_bindgen]
+pub fn frost_sign_process_round(
+    session_id: &str,
+    incoming_messages: JsSignMessages,
+) -> Result<JsProcessRoundResult, JsError> {
+    let incoming: Vec<sign::WasmSignMessage> = serde_wasm_bindgen::from_value(incoming_messages.into())
+        .map_err(|e| JsError::new(&format!("deserialize incoming messages: {e}")))?;
+
+    let result = frost::process_round(session_id, &incoming)
+        .map_err(|e| JsError::new(&e))?;
+
+    to_js(&result)
+}
+
+// ─── Deterministic fixtures (`test-fixtures` feature) ───────────────────────
+//
+// Seeded twins of the local ceremonies for reproducible transcripts and
+// stable SDK fixtures. Never in a production build: a known seed is a known
+// key. Paillier primes still come from the OS rng (`MixedRng`) — pass the same primes to
+// `run_dkg_with_primes_deterministic` for byte-identical output.
+
+/// The 32-byte seed argument of the `*_deterministic` exports.
+#[cfg(all(feature = "test-fixtures", any(feature = "dkg", feature = "signing")))]
+fn fixture_seed(seed: &[u8]) -> Result<[u8; 32], JsError> {
+    seed.try_into()
+        .map_err(|_| JsError::new(&format!("seed must be 32 bytes, got {}", seed.len())))
+}
+
+/// [`run_dkg`] with every party's keygen / aux randomness derived from
+/// `seed`. The primes are fresh, so only the core shares repeat.
+#[cfg(all(feature = "test-fixtures", feature = "dkg"))]
+#[wasm_bindgen]
+pub fn run_dkg_deterministic(
+    eid_bytes: JsBytes,
+    n: u16,
+    threshold: u16,
+    seed: JsBytes,
+    options: Option<JsDkgOptions>,
+) -> Result<JsDkgResult, JsError> {
+    let eid_bytes = &js_bytes(eid_bytes, "eid_bytes")?;
+    let seed = &js_bytes(seed, "seed")?;
+    let seed = fixture_seed(seed)?;
+    collect_dkg(|on_share| {
+        run_dkg_with_rng(eid_bytes, n, threshold, options, &|i| simulate::party_rng(seed, i), on_share)
+    })
+}
+
+/// [`run_dkg_with_primes`] with every party's randomness derived from
+/// `seed`: the same seed and primes give byte-identical shares.
+#[cfg(all(feature = "test-fixtures", feature = "dkg"))]
+#[wasm_bindgen]
+pub fn run_dkg_with_primes_deterministic(
+    eid_bytes: JsBytes,
+    n: u16,
+    threshold: u16,
+    serialized_primes: JsByteArrays,
+    seed: JsBytes,
+    options: Option<JsDkgOptions>,
+) -> Result<JsDkgResult, JsError> {
+    let eid_bytes = &js_bytes(eid_bytes, "eid_bytes")?;
+    let seed = &js_bytes(seed, "seed")?;
+    let seed = fixture_seed(seed)?;
+    collect_dkg(|on_share| {
+        run_dkg_with_primes_and_rng(
+            eid_bytes,
+            n,
+            threshold,
+            serialized_primes,
+            options,
+            &|i| simulate::party_rng(seed, i),
+            on_share,
+        )
+    })
+}
+
+/// Sign `message_hash` with all signers local and nonces derived from
+/// `seed`: the same shares, seed and inputs give the same signature.
+///
+/// `core_shares` / `aux_infos` are JS arrays of `Uint8Array`, one per entry
+/// of `parties_at_keygen`, in the same order.
+///
+/// # Returns
+/// JS object: `{ r: Uint8Array(32), s: Uint8Array(32) }`
+#[cfg(all(feature = "test-fixtures", feature = "signing"))]
+#[wasm_bindgen]
+pub fn sign_local_deterministic(
+    core_shares: JsByteArrays,
+    aux_infos: JsByteArrays,
+    parties_at_keygen: &[u16],
+    message_hash: JsBytes,
+    eid: JsBytes,
+    seed: JsBytes,
+) -> Result<JsSignatureResult, JsError> {
+    let message_hash = &js_bytes(message_hash, "message_hash")?;
+    let eid = &js_bytes(eid, "eid")?;
+    let seed = &js_bytes(seed, "seed")?;
+    let seed = fixture_seed(seed)?;
+    let core_shares = byte_arrays_from_js(core_shares, "core shares array")?;
+    let aux_infos = byte_arrays_from_js(aux_infos, "aux infos array")?;
+    if message_hash.len() != 32 {
+        return Err(JsError::new(&format!(
+            "message_hash must be 32 bytes, got {}",
+            message_hash.len()
+        )));
+    }
+    if core_shares.len() != parties_at_keygen.len() || aux_infos.len() != parties_at_keygen.len() {
+        return Err(JsError::new(&format!(
+            "need one core share and aux info per party: {} parties, {} core shares, {} aux infos",
+            parties_at_keygen.len(),
+            core_shares.len(),
+            aux_infos.len()
+        )));
+    }
+    let first_core = core_shares.first().ok_or_else(|| JsError::new("no signers"))?;
+    let first_aux = &aux_infos[0];
+    let (core_header, _) =
+        share_format::split_expect(first_core, BlobKind::CoreShare).map_err(|e| JsError::new(&e))?;
+    let (aux_header, _) =
+        share_format::split_expect(first_aux, BlobKind::AuxInfo).map_err(|e| JsError::new(&e))?;
+    let curve = share_format::ensure_curve(&core_header, None).map_err(|e| JsError::new(&e))?;
+    let level = share_format::ensure_level(&aux_header, None).map_err(|e| JsError::new(&e))?;
+
+    let result = with_curve!(curve, E => with_security_level!(level, L => {
+        fixture_key_shares::<E, L>(&core_shares, &aux_infos, curve, level).and_then(|key_shares| {
+            simulate::sign_local(&key_shares, parties_at_keygen, message_hash, eid, |i| {
+                simulate::party_rng(seed, i)
+            })
+            .map(|(sig, _)| {
+                let mut bytes = vec![0u8; cggmp24::signing::Signature::<E>::serialized_len()];
+                sig.write_to_slice(&mut bytes);
+                types::SignatureResult {
+                    r: bytes[..32].to_vec(),
+                    s: bytes[32..].to_vec(),
+                    // `sign_local` always normalises, and doesn't say whether it flipped
+                    normalized: false,
+                    v_eip155: None,
+                    recovered_pubkey: None,
+                }
+            })
+        })
+    }))
+    .map_err(|e| JsError::new(&e))?;
+
+    to_js(&result)
+}
+
+/// Combine each core share with its aux info; all must be on `curve` at
+/// `level`.
+#[cfg(all(feature = "test-fixtures", feature = "signing"))]
+fn fixture_key_shares<E, L>(
+    core_shares: &[impl AsRef<[u8]>],
+    aux_infos: &[impl AsRef<[u8]>],
+    curve: CurveId,
+    level: Level,
+) -> Result<Vec<cggmp24::KeyShare<E, L>>, String>
+where
+    E: Curve,
+    L: SecurityLevel,
+{
+    core_shares
+        .iter()
+        .zip(aux_infos)
+        .enumerate()
+        .map(|(i, (core, aux))| {
+            let (core_header, core_payload) = share_format::split_expect(core.as_ref(), BlobKind::CoreShare)?;
+            let (aux_header, aux_payload) = share_format::split_expect(aux.as_ref(), BlobKind::AuxInfo)?;
+            share_format::ensure_curve(&core_header, Some(curve))?;
+            share_format::ensure_level(&aux_header, Some(level))?;
+            let core: cggmp24::IncompleteKeyShare<E> =
+                share_format::deserialize(BlobKind::CoreShare, core_payload)?;
+            let aux: cggmp24::key_share::AuxInfo<L> =
+                share_format::deserialize(BlobKind::AuxInfo, aux_payload)?;
+            cggmp24::KeyShare::from_parts((core, aux)).map_err(|e| format!("combine key share {i}: {e}"))
+        })
+        .collect()
+}
+
+// ─── Benchmarks ─────────────────────────────────────────────────────────────
+
+/// Time `iterations` fixed-seed 2-of-3 secp256k1 signing ceremonies, all
+/// parties local, one at a time. The first call generates the key shares
+/// (Paillier primes included) outside the timed loop and reports it as
+/// `setup_ms`. Signer count, presigning and concurrency are native-gen
+/// `bench sign` options.
+///
+/// # Returns
+/// JS object: `{ bench, n, t, iterations, min_ms, median_ms, max_ms, p50_ms, p95_ms, per_second,
+/// concurrency, messages, bytes, setup_ms }` — `messages` / `bytes` are per iteration.
+#[cfg(all(feature = "dkg", feature = "signing"))]
+#[wasm_bindgen]
+pub fn bench_sign(iterations: u32) -> Result<JsBenchReport, JsError> {
+    let options = bench::SignBenchOptions { iterations, ..Default::default() };
+    let report = bench::bench_sign(options).map_err(|e| JsError::new(&e))?;
+    to_js(&report)
+}
+
+/// Time `iterations` fixed-seed keygen ceremonies (no aux info phase) for
+/// `n` parties with threshold `t`. Same report as `bench_sign`.
+#[cfg(all(feature = "dkg", feature = "signing"))]
+#[wasm_bindgen]
+pub fn bench_dkg_keygen_only(n: u16, t: u16, iterations: u32) -> Result<JsBenchReport, JsError> {
+    let report = bench::bench_dkg_keygen_only(n, t, iterations).map_err(|e| JsError::new(&e))?;
+    to_js(&report)
+}
+
+/// Estimate how long `run_dkg` (or, `with_primes`, `run_dkg_with_primes`)
+/// takes on this device for `n` parties with threshold `threshold`, and its
+/// peak memory, from a calibration of under two seconds. Fails with
+/// `CANCELLED` once `cancel_token` is cancelled.
+///
+/// # Returns
+/// JS object: `{ est_seconds_low, est_seconds_high, est_peak_memory_mb, phases, calibration_ms }`
+/// — `phases` are `{ phase, est_seconds_low, est_seconds_high }` for `primes` (unless
+/// `with_primes`), `aux_info` and `keygen`.
+#[cfg(all(feature = "dkg", feature = "signing"))]
+#[wasm_bindgen]
+pub fn estimate_dkg(
+    n: u16,
+    threshold: u16,
+    with_primes: bool,
+    cancel_token: Option<u32>,
+) -> Result<JsEstimate, JsError> {
+    let cancel = |stage: &str, done, total| cancel::check(cancel_token, stage, done, total);
+    let estimate = estimate::estimate_dkg(n, threshold, with_primes, &cancel).map_err(|e| JsError::new(&e))?;
+    to_js(&estimate)
+}
+
+/// Estimate one party's time and memory in an ECDSA signing session of
+/// `parties` co-signers on this device; same result as `estimate_dkg`, with
+/// phases `presign` and `sign`.
+#[cfg(all(feature = "dkg", feature = "signing"))]
+#[wasm_bindgen]
+pub fn estimate_sign(parties: u16, cancel_token: Option<u32>) -> Result<JsEstimate, JsError> {
+    let cancel = |stage: &str, done, total| cancel::check(cancel_token, stage, done, total);
+    let estimate = estimate::estimate_sign(parties, &cancel).map_err(|e| JsError::new(&e))?;
+    to_js(&estimate)
+}
+
+// ─── Test vectors ───────────────────────────────────────────────────────────
+
+/// Replay the test vectors written by native-gen `gen-vectors` under this
+/// build: `json` is `{ "key": <key.json>, "sign": [<sign-*.json>, ...] }`.
+/// Fails with `VECTOR_MISMATCH` naming the first message (or blob) that
+/// this build encodes differently, `VECTOR_INVALID` for malformed input.
+///
+/// # Returns
+/// JS object: `{ key_shares, transcripts, messages }` — what was replayed.
+#[cfg(all(feature = "dkg", feature = "signing"))]
+#[wasm_bindgen]
+pub fn verify_vectors(json: &str) -> Result<JsVectorReport, JsError> {
+    let set: test_vectors::VectorSet =
+        serde_json::from_str(json).map_err(|e| JsError::new(&format!("VECTOR_INVALID: {e}")))?;
+    let report = test_vectors::verify_vectors(&set).map_err(|e| JsError::new(&e))?;
+    to_js(&report)
+}
+
+// ─── Vendored fixtures (`fixtures` feature) ─────────────────────────────────
+//
+// The test-vector key, embedded so downstream suites share it rather than
+// generating their own. Never in a production build: its seed is public.
+
+/// The vendored 2-of-3 secp256k1 key (see `fixtures`), as `run_dkg` would
+/// return it.
+///
+/// # Returns
+/// JS object: same shape as `run_dkg`
+#[cfg(feature = "fixtures")]
+#[wasm_bindgen]
+pub fn fixture_dkg_result() -> Result<JsDkgResult, JsError> {
+    let key = fixtures::two_of_three().map_err(|e| JsError::new(&e))?;
+    let share_fingerprints = key
+        .shares
+        .iter()
+        .map(|share| fingerprint::share_fingerprint(&share.core_share))
+        .collect();
+    let summary = DkgSummary {
+        wallet_id: fingerprint::wallet_id(&key.public_key).map_err(|e| JsError::new(&e))?,
+        public_key: key.public_key,
+        public_key_uncompressed: None,
+        share_fingerprints,
+        party_labels: key.shares.iter().map(|share| share.index).collect(),
+        primes_fingerprints: Vec::new(),
+        aux_set: None,
+    };
+    collect_dkg(|on_share| {
+        for share in key.shares {
+            let index = share.index;
+            let share = DkgShare {
+                core_share: share.core_share,
+                aux_info: share.aux_info,
+            };
+            on_share(index, share).map_err(|e| JsError::new(&e))?;
+        }
+        Ok(summary)
+    })
+}
+
+/// EIP-55 Ethereum address of the vendored key.
+#[cfg(feature = "fixtures")]
+#[wasm_bindgen]
+pub fn fixture_address() -> Result<String, JsError> {
+    Ok(fixtures::two_of_three().map_err(|e| JsError::new(&e))?.address)
+}
+
+/// The vendored key's signature of `[0x42; 32]` by parties `[0, 1]`, with
+/// the messages that produced it.
+///
+/// # Returns
+/// JS object: `{ reliable_broadcast, seed, eid, message_hash, signers, messages, r, s }` — hex
+/// strings; `messages` are `{ round, sender, recipient, payload }`, in the order they were sent.
+#[cfg(feature = "fixtures")]
+#[wasm_bindgen]
+pub fn fixture_signature() -> Result<JsSignVector, JsError> {
+    let signature = fixtures::signature().map_err(|e| JsError::new(&e))?;
+    to_js(&signature)
+}
+
+#[cfg(all(test, feature = "dkg", feature = "insecure-dev", feature = "signing"))]
+mod tests {
+    use super::*;
+    use cggmp24::security_level::SecurityLevel128;
+    use generic_ec::curves::Secp256k1;
+    use generic_ec::Scalar;
+
+    /// The test key's aux info, by index: one aux_info_gen run for 3 parties.
+    fn aux_blobs() -> Vec<Vec<u8>> {
+        test_support::key().shares.iter().map(|share| share.aux_info.clone()).collect()
+    }
+
+    /// `run_keygen_for_parties` for the parties `indices`, threshold 2.
+    fn keygen(aux_blobs: &[Vec<u8>], indices: &[u16]) -> Result<(Vec<DkgShare>, DkgSummary), String> {
+        let aux_infos = parse_aux_infos::<SecurityLevel128>(aux_blobs, 3)?;
+        let mut shares = Vec::new();
+        let summary = keygen_phase::<Secp256k1, SecurityLevel128, _>(
+            &test_support::eid("keygen_phase"),
+            indices,
+            2,
+            CurveId::Secp256k1,
+            Level::L128,
+            Encoding::Json,
+            aux_infos,
+            &|_| MixedRng,
+            &mut |_, share| {
+                shares.push(share);
+                Ok(())
+            },
+            None,
+        )?;
+        Ok((shares, summary))
+    }
+
+    #[test]
+    fn keygen_pairs_each_share_with_its_aux_info() {
+        let blobs = aux_blobs();
+        let (shares, summary) = keygen(&blobs, &[0, 1, 2]).unwrap();
+        assert_eq!(summary.party_labels, [0, 1, 2]);
+        assert_eq!(shares.len(), 3);
+        for share in &shares {
+            let report = key_material::check_key_material(&share.core_share, &share.aux_info, None);
+            assert!(report.ok, "{:?}", report.errors);
+        }
+    }
+
+    #[test]
+    fn aux_info_in_the_wrong_order_is_refused() {
+        let mut blobs = aux_blobs();
+        blobs.swap(0, 1);
+        let error = keygen(&blobs, &[0, 1, 2]).err().unwrap();
+        assert!(error.contains("AUX_PARTY_MISMATCH: "), "{error}");
+    }
+
+    #[test]
+    fn aux_info_for_another_party_count_is_refused() {
+        let error = parse_aux_infos::<SecurityLevel128>(&aux_blobs(), 2).unwrap_err();
+        assert!(error.starts_with("AUX_PARTY_COUNT_MISMATCH: aux info 0 is for 3 parties"), "{error}");
+        let error = aux_level(&aux_blobs()[..2], 3, None).unwrap_err();
+        assert!(error.starts_with("AUX_PARTY_COUNT_MISMATCH: "), "{error}");
+    }
+
+    /// Sign the test vectors' hash with `shares` as the parties `signers`,
+    /// and check the signatures against `public_key`.
+    fn sign_verifies(shares: &[&DkgShare], signers: &[u16], public_key: &[u8]) -> bool {
+        let eid = test_support::eid(&format!("labelled signers {signers:?}"));
+        let mut ids = Vec::new();
+        let mut messages = Vec::new();
+        for (&party, share) in signers.iter().zip(shares) {
+            let created = sign::create_session(
+                &share.core_share,
+                &share.aux_info,
+                sign::SignData::Prehashed(&test_vectors::MESSAGE_HASH),
+                party,
+                signers,
+                &eid,
+                &sign::SignOptions::default(),
+            )
+            .unwrap();
+            ids.push(created.session_id);
+            messages.extend(created.messages);
+        }
+        let public_key = Point::<Secp256k1>::from_bytes(public_key).unwrap();
+        let message = Scalar::<Secp256k1>::from_be_bytes_mod_order(test_vectors::MESSAGE_HASH);
+        test_support::run(signers, &ids, messages).iter().all(|sig| {
+            let (r, s) = (Scalar::from_be_bytes(&sig.r).unwrap(), Scalar::from_be_bytes(&sig.s).unwrap());
+            signature::verify_prehashed(&public_key, &message, &r, &s)
+        })
+    }
+
+    #[test]
+    fn a_key_of_parties_3_7_and_9_signs_with_parties_3_and_9() {
+        let (shares, summary) = keygen(&aux_blobs(), &[3, 7, 9]).unwrap();
+        assert_eq!(summary.party_labels, [3, 7, 9]);
+        let labels = share_metadata::read_metadata(&shares[1].core_share).unwrap().unwrap().party_labels;
+        assert_eq!(labels.as_deref(), Some(&[3, 7, 9][..]));
+
+        assert!(sign_verifies(&[&shares[0], &shares[2]], &[3, 9], &summary.public_key));
+        assert!(sign_verifies(&[&shares[2], &shares[1]], &[9, 7], &summary.public_key));
+
+        // The key has no party 0 any more
+        let error = sign::create_session(
+            &shares[0].core_share,
+            &shares[0].aux_info,
+            sign::SignData::Prehashed(&test_vectors::MESSAGE_HASH),
+            0,
+            &[0, 2],
+            &test_support::eid("unlabelled signers"),
+            &sign::SignOptions::default(),
+        )
+        .unwrap_err();
+        assert!(error.starts_with("UNKNOWN_PARTY: party 0 "), "{error}");
+    }
+
+    /// The test key's aux info as a `gen-aux` line.
+    fn aux_set_json(generated_at: u64, uses: u32) -> String {
+        use base64::Engine;
+        let aux_infos = aux_blobs()
+            .iter()
+            .map(|blob| base64::engine::general_purpose::STANDARD.encode(blob))
+            .collect();
+        let set = aux_set::AuxInfoOutput { aux_infos, n: 3, generated_at, security_level: "128".into(), uses };
+        serde_json::to_string(&set).unwrap()
+    }
+
+    fn open_refused(json: &str, n: u16, options: &mut DkgOptions) -> String {
+        open_aux_set(json, n, options, 1030).map(drop).unwrap_err()
+    }
+
+    #[test]
+    fn an_aux_set_is_checked_before_keygen() {
+        let mut options = DkgOptions { max_aux_age_secs: Some(60), max_aux_uses: Some(3), ..DkgOptions::default() };
+        let (blobs, info) = open_aux_set(&aux_set_json(1000, 2), 3, &mut options, 1030).unwrap();
+        assert_eq!(blobs, aux_blobs());
+        assert_eq!((info.generated_at, info.age_secs, info.uses), (1000, 30, 2));
+        assert_eq!(options.security_level.as_deref(), Some("128"));
+
+        let count = open_refused(&aux_set_json(1000, 2), 2, &mut options);
+        assert!(count.starts_with("AUX_PARTY_COUNT_MISMATCH: "), "{count}");
+        let expired = open_refused(&aux_set_json(900, 0), 3, &mut options);
+        assert!(expired.starts_with("AUX_SET_EXPIRED: "), "{expired}");
+        let exhausted = open_refused(&aux_set_json(1000, 3), 3, &mut options);
+        assert!(exhausted.starts_with("AUX_SET_EXHAUSTED: "), "{exhausted}");
+        let mut at_192 = DkgOptions { security_level: Some("192".into()), ..DkgOptions::default() };
+        let level = open_refused(&aux_set_json(1000, 0), 3, &mut at_192);
+        assert!(level.starts_with("SECURITY_LEVEL_MISMATCH: "), "{level}");
+    }
 }
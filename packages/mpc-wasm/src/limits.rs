@@ -0,0 +1,89 @@
+//! Caps on what a peer can make a signing session decode.
+//!
+//! Checked on the encoded (base64) payloads before anything is decoded, so a
+//! hostile peer can't make us allocate an arbitrarily large buffer. A
+//! message that breaks a limit is rejected and never counted; the rest of
+//! its batch still goes through.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use serde::{Deserialize, Serialize};
+
+/// Per-message payload cap (encoded length), in bytes.
+pub const DEFAULT_MAX_MESSAGE_BYTES: usize = 512 * 1024;
+/// Messages accepted in one `process_round` call.
+pub const DEFAULT_MAX_MESSAGES_PER_ROUND: usize = 64;
+/// Messages accepted over a session's lifetime.
+pub const DEFAULT_MAX_MESSAGES_PER_SESSION: u32 = 10_000;
+
+// TypeScript definitions only exist for the WASM build; native-gen never
+// targets wasm32, so it doesn't need tsify.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct MessageLimits {
+    pub max_message_bytes: usize,
+    pub max_messages_per_round: usize,
+    pub max_messages_per_session: u32,
+}
+
+impl Default for MessageLimits {
+    fn default() -> Self {
+        MessageLimits {
+            max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES,
+            max_messages_per_round: DEFAULT_MAX_MESSAGES_PER_ROUND,
+            max_messages_per_session: DEFAULT_MAX_MESSAGES_PER_SESSION,
+        }
+    }
+}
+
+/// Per-session overrides; unset fields keep the configured default.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[serde(default)]
+pub struct LimitOverrides {
+    pub max_message_bytes: Option<usize>,
+    pub max_messages_per_round: Option<usize>,
+    pub max_messages_per_session: Option<u32>,
+}
+
+impl MessageLimits {
+    pub fn with_overrides(self, overrides: &LimitOverrides) -> Self {
+        MessageLimits {
+            max_message_bytes: overrides.max_message_bytes.unwrap_or(self.max_message_bytes),
+            max_messages_per_round: overrides
+                .max_messages_per_round
+                .unwrap_or(self.max_messages_per_round),
+            max_messages_per_session: overrides
+                .max_messages_per_session
+                .unwrap_or(self.max_messages_per_session),
+        }
+    }
+
+    /// Check one incoming message from `sender` with an encoded payload of
+    /// `len` bytes, for a session that has already accepted `received`
+    /// messages, `in_round` of them in this call.
+    ///
+    /// Fails with `MESSAGE_TOO_LARGE` or `TOO_MANY_MESSAGES`, naming the
+    /// sender.
+    pub fn check_message(&self, received: u32, in_round: usize, sender: u16, len: usize) -> Result<(), String> {
+        if in_round >= self.max_messages_per_round {
+            return Err(format!(
+                "TOO_MANY_MESSAGES: message from party {sender} exceeds {} messages per round",
+                self.max_messages_per_round
+            ));
+        }
+        if received >= self.max_messages_per_session {
+            return Err(format!(
+                "TOO_MANY_MESSAGES: message from party {sender} exceeds {} messages per session",
+                self.max_messages_per_session
+            ));
+        }
+        if len > self.max_message_bytes {
+            return Err(format!(
+                "MESSAGE_TOO_LARGE: message from party {sender} is {len} bytes, limit {}",
+                self.max_message_bytes
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+//! Encoding of protocol messages inside the signing envelope.
+//!
+//! A message's `payload` is base64 over the protocol message, serialised
+//! as serde_json or CBOR (`share_format::Encoding`). A session uses one
+//! encoding for everything it sends, picked when it is created. Decoding
+//! sniffs the payload's first byte the way `share_format` does for blobs,
+//! but a session refuses a peer speaking the other encoding up front
+//! (`ENCODING_MISMATCH`) rather than feeding the state machine messages
+//! its peer can't read back: both sides of a signing must agree, and a
+//! mixed pair should fail on its first message, not rounds later.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::share_format::Encoding;
+
+/// `msg` serialised in `encoding`, base64-encoded.
+pub fn encode<T: Serialize>(encoding: Encoding, msg: &T) -> Result<String, String> {
+    let bytes = match encoding {
+        Encoding::Json => serde_json::to_vec(msg).map_err(|e| format!("serialize outgoing msg: {e}"))?,
+        Encoding::Cbor => {
+            let mut bytes = Vec::new();
+            ciborium::into_writer(msg, &mut bytes).map_err(|e| format!("serialize outgoing msg: {e}"))?;
+            bytes
+        }
+    };
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// Decode a base64 `payload` in whichever encoding it is in.
+pub fn decode<T: DeserializeOwned>(payload: &[u8]) -> Result<T, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .map_err(|e| format!("base64 decode incoming msg: {e}"))?;
+    match Encoding::sniff(&bytes) {
+        Encoding::Json => {
+            serde_json::from_slice(&bytes).map_err(|e| format!("deserialize incoming msg: {e}"))
+        }
+        Encoding::Cbor => {
+            ciborium::from_reader(bytes.as_slice()).map_err(|e| format!("deserialize incoming msg: {e}"))
+        }
+    }
+}
+
+/// Check that `sender`'s base64 `payload` is in the session's `expected`
+/// encoding. Only the first base64 quantum is decoded; a payload that
+/// isn't base64 at all is left for [`decode`] to report.
+pub fn check_encoding(expected: Encoding, sender: u16, payload: &str) -> Result<(), String> {
+    let Some(head) = payload.get(..4) else {
+        return Ok(());
+    };
+    let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(head) else {
+        return Ok(());
+    };
+    let found = Encoding::sniff(&bytes);
+    if found != expected {
+        return Err(format!(
+            "ENCODING_MISMATCH: message from party {sender} is {}-encoded, this session uses {}",
+            found.as_str(),
+            expected.as_str()
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,255 @@
+//! Wall-clock timings of a signing session, per round and cumulative.
+//!
+//! Each step takes two timestamps: one around `proceed()` / message decode,
+//! one more after serialising an outgoing message (`clock::now_ms`).
+//!
+//! Also process-wide session counters for [`memory_stats`]: sessions
+//! created and destroyed, and the bytes of serialised key material the live
+//! ones were built from — a stand-in for what they pin, since the decoded
+//! shares are leaked for the state machine and only reclaimed on drop.
+//! Likewise the pre-generated primes a DKG holds ([`HeldPrimes`]), with the
+//! highest figure seen, since they peak just before the aux info phase.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+static SESSIONS_CREATED: AtomicU64 = AtomicU64::new(0);
+static SESSIONS_DESTROYED: AtomicU64 = AtomicU64::new(0);
+static PINNED_BYTES: AtomicU64 = AtomicU64::new(0);
+static REGISTERED_KEYS: AtomicU64 = AtomicU64::new(0);
+static REGISTERED_KEY_BYTES: AtomicU64 = AtomicU64::new(0);
+static PRIMES_BYTES: AtomicU64 = AtomicU64::new(0);
+static PRIMES_PEAK_BYTES: AtomicU64 = AtomicU64::new(0);
+static EID_RESERVATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Time spent in each phase of driving a state machine.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PhaseTimes {
+    /// Decoding incoming messages and handing them to the state machine
+    pub deserialize_ms: f64,
+    /// Inside `proceed()` — protocol work, ZK proofs included — plus the
+    /// final signature check
+    pub proceed_ms: f64,
+    /// Encoding outgoing messages
+    pub serialize_ms: f64,
+    /// Incoming messages delivered
+    pub messages: u32,
+}
+
+/// Cumulative timings over a session's lifetime.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct SessionMetrics {
+    /// Key share deserialization and state machine construction
+    pub setup_ms: f64,
+    /// `process_round` calls
+    pub rounds: u32,
+    pub deserialize_ms: f64,
+    pub proceed_ms: f64,
+    pub serialize_ms: f64,
+    /// Incoming messages delivered
+    pub messages: u32,
+    /// The key share came from the share cache, skipping deserialisation
+    #[serde(default)]
+    pub cache_hit: bool,
+}
+
+impl SessionMetrics {
+    /// Fold in the initial drive done at session creation.
+    pub fn record_start(&mut self, times: &PhaseTimes) {
+        self.deserialize_ms += times.deserialize_ms;
+        self.proceed_ms += times.proceed_ms;
+        self.serialize_ms += times.serialize_ms;
+        self.messages += times.messages;
+    }
+
+    /// Fold in one `process_round` call and report it.
+    pub fn record_round(&mut self, times: &PhaseTimes) -> RoundMetrics {
+        self.rounds += 1;
+        self.record_start(times);
+        RoundMetrics {
+            deserialize_ms: times.deserialize_ms,
+            proceed_ms: times.proceed_ms,
+            serialize_ms: times.serialize_ms,
+            messages: times.messages,
+            total: *self,
+        }
+    }
+}
+
+/// Timings of one `process_round` call, with the session totals so far.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct RoundMetrics {
+    pub deserialize_ms: f64,
+    pub proceed_ms: f64,
+    pub serialize_ms: f64,
+    pub messages: u32,
+    pub total: SessionMetrics,
+}
+
+impl RoundMetrics {
+    /// Add another session's call into this one; batch sessions report the
+    /// sum over their members. `total.rounds` is the most any member ran,
+    /// `total.cache_hit` whether any member hit.
+    pub fn absorb(&mut self, other: &RoundMetrics) {
+        self.deserialize_ms += other.deserialize_ms;
+        self.proceed_ms += other.proceed_ms;
+        self.serialize_ms += other.serialize_ms;
+        self.messages += other.messages;
+
+        let (total, more) = (&mut self.total, &other.total);
+        total.setup_ms += more.setup_ms;
+        total.rounds = total.rounds.max(more.rounds);
+        total.deserialize_ms += more.deserialize_ms;
+        total.proceed_ms += more.proceed_ms;
+        total.serialize_ms += more.serialize_ms;
+        total.messages += more.messages;
+        total.cache_hit |= more.cache_hit;
+    }
+}
+
+/// Memory held by the process and its signing sessions.
+#[derive(Serialize, Clone, Copy, Debug, Default)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct MemoryStats {
+    /// Size of the WASM linear memory (what `memory.buffer.byteLength`
+    /// reports); wasm only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub wasm_memory_bytes: Option<u64>,
+    /// Resident set size from `/proc/self/status`; native, where available
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub rss_bytes: Option<u64>,
+    /// Sessions created and not yet destroyed (batch members count singly)
+    pub live_sessions: u64,
+    /// Serialised key material the live sessions were built from
+    pub pinned_bytes: u64,
+    pub sessions_created: u64,
+    pub sessions_destroyed: u64,
+    /// Keys in the WASM key registry (`register_key`)
+    pub registered_keys: u64,
+    /// Serialised key material those keys hold
+    pub registered_key_bytes: u64,
+    /// Pre-generated primes held by DKGs in progress, raw and parsed
+    pub primes_bytes: u64,
+    /// Highest `primes_bytes` seen since startup
+    pub primes_peak_bytes: u64,
+    /// Live signing sessions holding their eid against reuse (see `sign`)
+    pub eid_reservations: u64,
+}
+
+/// Count a new session built from `pinned_bytes` of key material.
+pub fn session_opened(pinned_bytes: usize) {
+    SESSIONS_CREATED.fetch_add(1, Ordering::Relaxed);
+    PINNED_BYTES.fetch_add(pinned_bytes as u64, Ordering::Relaxed);
+}
+
+/// Count a session dropped; `pinned_bytes` as passed to [`session_opened`].
+pub fn session_closed(pinned_bytes: usize) {
+    SESSIONS_DESTROYED.fetch_add(1, Ordering::Relaxed);
+    PINNED_BYTES.fetch_sub(pinned_bytes as u64, Ordering::Relaxed);
+}
+
+/// Stop counting `pinned_bytes` of a session that freed its key material
+/// early; it still counts as live until [`session_closed`] (with 0 bytes).
+pub fn release_pinned(pinned_bytes: usize) {
+    PINNED_BYTES.fetch_sub(pinned_bytes as u64, Ordering::Relaxed);
+}
+
+/// Count a session's eid reservation taken.
+pub fn eid_reserved() {
+    EID_RESERVATIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count an eid reservation given back.
+pub fn eid_released() {
+    EID_RESERVATIONS.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// Count a key registered with `bytes` of key material.
+pub fn key_registered(bytes: usize) {
+    REGISTERED_KEYS.fetch_add(1, Ordering::Relaxed);
+    REGISTERED_KEY_BYTES.fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+/// Count a key unregistered; `bytes` as passed to [`key_registered`].
+pub fn key_unregistered(bytes: usize) {
+    REGISTERED_KEYS.fetch_sub(1, Ordering::Relaxed);
+    REGISTERED_KEY_BYTES.fetch_sub(bytes as u64, Ordering::Relaxed);
+}
+
+/// Prime material counted in [`memory_stats`] while this lives. The
+/// byte length of a primes blob stands in for what it holds, raw or
+/// parsed.
+#[derive(Default)]
+pub struct HeldPrimes(u64);
+
+impl HeldPrimes {
+    pub fn add(&mut self, bytes: usize) {
+        let bytes = bytes as u64;
+        self.0 += bytes;
+        let now = PRIMES_BYTES.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        PRIMES_PEAK_BYTES.fetch_max(now, Ordering::Relaxed);
+    }
+
+    pub fn release(&mut self, bytes: usize) {
+        let bytes = (bytes as u64).min(self.0);
+        self.0 -= bytes;
+        PRIMES_BYTES.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+impl Drop for HeldPrimes {
+    fn drop(&mut self) {
+        PRIMES_BYTES.fetch_sub(self.0, Ordering::Relaxed);
+    }
+}
+
+/// Current counters and process memory.
+pub fn memory_stats() -> MemoryStats {
+    let created = SESSIONS_CREATED.load(Ordering::Relaxed);
+    let destroyed = SESSIONS_DESTROYED.load(Ordering::Relaxed);
+    MemoryStats {
+        wasm_memory_bytes: wasm_memory_bytes(),
+        rss_bytes: rss_bytes(),
+        live_sessions: created.saturating_sub(destroyed),
+        pinned_bytes: PINNED_BYTES.load(Ordering::Relaxed),
+        sessions_created: created,
+        sessions_destroyed: destroyed,
+        registered_keys: REGISTERED_KEYS.load(Ordering::Relaxed),
+        registered_key_bytes: REGISTERED_KEY_BYTES.load(Ordering::Relaxed),
+        primes_bytes: PRIMES_BYTES.load(Ordering::Relaxed),
+        primes_peak_bytes: PRIMES_PEAK_BYTES.load(Ordering::Relaxed),
+        eid_reservations: EID_RESERVATIONS.load(Ordering::Relaxed),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn wasm_memory_bytes() -> Option<u64> {
+    // Pages of 64 KiB
+    Some(core::arch::wasm32::memory_size(0) as u64 * 65536)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn wasm_memory_bytes() -> Option<u64> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+fn rss_bytes() -> Option<u64> {
+    None
+}
+
+/// `VmRSS` from `/proc/self/status`; `None` off Linux.
+#[cfg(not(target_arch = "wasm32"))]
+fn rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line["VmRSS:".len()..].trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kb * 1024)
+}
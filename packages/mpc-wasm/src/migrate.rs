@@ -0,0 +1,123 @@
+//! Converting JSON key material to the binary (CBOR) share encoding.
+//!
+//! [`migrate_share`] decodes a blob — legacy headerless or headered JSON —
+//! as the type its header names (legacy blobs: whichever ECDSA kind it
+//! parses as), re-encodes it as CBOR under a header with the same kind,
+//! curve, level and flags, and refuses to hand back anything that doesn't
+//! decode to the same value or, for shares, to the same public key. A
+//! `share_metadata` envelope is carried over to the binary blob.
+//!
+//! Shared by the WASM `migrate_share` export and native-gen's `migrate`
+//! subcommand. No wasm-bindgen dependency.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::key_material;
+use crate::share_format::{self, with_curve, with_security_level, BlobHeader, BlobKind, Encoding};
+use crate::share_metadata;
+
+/// Kinds a headerless blob may hold, in the order they are tried: a
+/// KeyShare also parses as a CoreKeyShare, so it goes first.
+const LEGACY_KINDS: [BlobKind; 4] = [BlobKind::KeyShare, BlobKind::CoreShare, BlobKind::AuxInfo, BlobKind::Primes];
+
+/// A converted blob.
+pub struct Migrated {
+    pub blob: Vec<u8>,
+    pub kind: BlobKind,
+}
+
+/// Re-encode `bytes` as CBOR, checking the result. Fails with
+/// `ALREADY_MIGRATED` for a blob that is binary already, `INVALID_SHARE`
+/// for one that decodes as nothing it claims to be, and
+/// `MIGRATION_VERIFY_FAILED` if the binary form doesn't read back the same.
+pub fn migrate_share(bytes: &[u8]) -> Result<Migrated, String> {
+    let metadata = share_metadata::read_metadata(bytes)?;
+    let (header, payload) = share_format::split(bytes)?;
+    if header.encoding == Encoding::Cbor {
+        return Err("ALREADY_MIGRATED: blob is already in the binary encoding".into());
+    }
+
+    let kinds = match header.kind {
+        Some(kind) => vec![kind],
+        None => LEGACY_KINDS.to_vec(),
+    };
+    for kind in kinds {
+        let Some(blob) = reencode_as(kind, &header, payload)? else {
+            continue;
+        };
+        if matches!(kind, BlobKind::KeyShare | BlobKind::CoreShare | BlobKind::FrostKeyShare) {
+            let before = key_material::extract_public_key(bytes)?;
+            let after = key_material::extract_public_key(&blob)
+                .map_err(|e| format!("MIGRATION_VERIFY_FAILED: binary {} unreadable: {e}", kind.as_str()))?;
+            if before != after {
+                return Err(format!(
+                    "MIGRATION_VERIFY_FAILED: binary {} carries a different public key",
+                    kind.as_str()
+                ));
+            }
+        }
+        let blob = match &metadata {
+            Some(metadata) => share_metadata::attach_metadata(&blob, metadata)?,
+            None => blob,
+        };
+        return Ok(Migrated { blob, kind });
+    }
+    Err(match header.kind {
+        Some(kind) => format!("INVALID_SHARE: payload is not a valid {}", kind.as_str()),
+        None => "INVALID_SHARE: not a KeyShare, CoreKeyShare, AuxInfo or primes blob".into(),
+    })
+}
+
+/// The CBOR blob of `payload` read as `kind`; `None` if it isn't one.
+fn reencode_as(kind: BlobKind, header: &BlobHeader, payload: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    let curve = header.curve_or_default();
+    let level = header.level_or_default();
+    match kind {
+        BlobKind::KeyShare => with_curve!(curve, E => with_security_level!(level, L => {
+            reencode::<cggmp24::KeyShare<E, L>>(kind, header, Some(curve), Some(level), payload)
+        })),
+        BlobKind::CoreShare => with_curve!(curve, E => {
+            reencode::<cggmp24::IncompleteKeyShare<E>>(kind, header, Some(curve), None, payload)
+        }),
+        BlobKind::AuxInfo => with_security_level!(level, L => {
+            reencode::<cggmp24::key_share::AuxInfo<L>>(kind, header, None, Some(level), payload)
+        }),
+        BlobKind::Primes => with_security_level!(level, L => {
+            reencode::<cggmp24::PregeneratedPrimes<L>>(kind, header, None, Some(level), payload)
+        }),
+        BlobKind::FrostKeyShare => reencode::<givre::KeyShare<cggmp24::supported_curves::Secp256k1>>(
+            kind,
+            header,
+            header.curve,
+            None,
+            payload,
+        ),
+    }
+}
+
+fn reencode<T: Serialize + DeserializeOwned>(
+    kind: BlobKind,
+    header: &BlobHeader,
+    curve: Option<share_format::CurveId>,
+    level: Option<share_format::Level>,
+    payload: &[u8],
+) -> Result<Option<Vec<u8>>, String> {
+    let Ok(value) = share_format::deserialize::<T>(kind, payload) else {
+        return Ok(None);
+    };
+    let blob = share_format::serialize_encoded(kind, curve, level, header.flags, Encoding::Cbor, &value)?;
+
+    // The binary blob must decode to the very value the JSON one did
+    let (_, binary_payload) = share_format::split_expect(&blob, kind)?;
+    let back: T = share_format::deserialize(kind, binary_payload)
+        .map_err(|e| format!("MIGRATION_VERIFY_FAILED: {e}"))?;
+    let same = serde_json::to_vec(&back).ok().is_some_and(|a| serde_json::to_vec(&value).is_ok_and(|b| a == b));
+    if !same {
+        return Err(format!(
+            "MIGRATION_VERIFY_FAILED: binary {} does not round-trip",
+            kind.as_str()
+        ));
+    }
+    Ok(Some(blob))
+}
@@ -0,0 +1,166 @@
+//! How many parties a key may have, and checked party index conversions.
+//!
+//! Aux info generation is quadratic in `n` and every party proves its
+//! Paillier key to every other, so a ceremony for a few thousand parties
+//! would run for hours and the local simulation's queues would exhaust
+//! memory long before it finished. New keys are therefore capped at
+//! [`max_parties`] (default 16, see [`set_max_parties`]); over it,
+//! ceremonies fail up front with `N_TOO_LARGE`.
+//!
+//! Party indices are `u16` on the wire and in cggmp24, positions in a party
+//! list are `usize`: [`index`] converts the one to the other without
+//! truncating. cggmp24 numbers a key's parties `0..n`; a caller may label
+//! them otherwise (see [`check_party_indices`]).
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use std::sync::atomic::{AtomicU16, Ordering};
+
+/// Largest `n` accepted unless configured otherwise.
+pub const DEFAULT_MAX_PARTIES: u16 = 16;
+
+static MAX_PARTIES: AtomicU16 = AtomicU16::new(DEFAULT_MAX_PARTIES);
+
+/// Largest `n` a new key may have.
+pub fn max_parties() -> u16 {
+    MAX_PARTIES.load(Ordering::Relaxed)
+}
+
+/// Set the largest `n` a new key may have. Fails with `INVALID_LIMIT`
+/// below 2, the smallest key there is.
+pub fn set_max_parties(max: u16) -> Result<(), String> {
+    if max < 2 {
+        return Err(format!("INVALID_LIMIT: max parties must be at least 2, got {max}"));
+    }
+    MAX_PARTIES.store(max, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Check `n` and `threshold` for a new key: `2 <= threshold <= n`, and `n`
+/// at most [`max_parties`] (`N_TOO_LARGE` otherwise).
+pub fn check_key_params(n: u16, threshold: u16) -> Result<(), String> {
+    check_key_params_within(n, threshold, max_parties())
+}
+
+/// [`check_key_params`] for a phase with no threshold (aux info
+/// generation): `2 <= n <= max_parties()`.
+pub fn check_party_count(n: u16) -> Result<(), String> {
+    check_party_count_within(n, max_parties())
+}
+
+/// [`check_key_params`] against an explicit maximum.
+pub fn check_key_params_within(n: u16, threshold: u16, max: u16) -> Result<(), String> {
+    check_party_count_within(n, max)?;
+    if threshold < 2 || threshold > n {
+        return Err(format!("threshold must be in [2, {n}], got {threshold}"));
+    }
+    Ok(())
+}
+
+fn check_party_count_within(n: u16, max: u16) -> Result<(), String> {
+    if n < 2 {
+        return Err("n must be at least 2".into());
+    }
+    if n > max {
+        return Err(format!("N_TOO_LARGE: n = {n} exceeds the supported maximum of {max} parties"));
+    }
+    Ok(())
+}
+
+/// Position `i` in a party list as a party index. Fails with
+/// `N_TOO_LARGE` past `u16::MAX`.
+pub fn index(i: usize) -> Result<u16, String> {
+    u16::try_from(i).map_err(|_| format!("N_TOO_LARGE: party position {i} does not fit a u16 index"))
+}
+
+/// The `n` of a new key whose parties are labelled `indices`: any distinct
+/// values, `[3, 7, 9]` as well as `[0, 1, 2]`. A repeat fails with
+/// `DUPLICATE_INDEX`.
+///
+/// cggmp24 takes party `i` of `n` with `i < n`, so party `i` runs as
+/// position `i` in the list and is labelled `indices[i]`.
+pub fn check_party_indices(indices: &[u16]) -> Result<u16, String> {
+    let n = index(indices.len())?;
+    let mut sorted = indices.to_vec();
+    sorted.sort_unstable();
+    if let Some(pair) = sorted.windows(2).find(|pair| pair[0] == pair[1]) {
+        return Err(format!("DUPLICATE_INDEX: party {} is listed twice", pair[0]));
+    }
+    Ok(n)
+}
+
+/// Whether `indices` are the positions themselves, `0..n` in order: the
+/// labels of a key generated without any.
+pub fn is_positional(indices: &[u16]) -> bool {
+    indices.iter().enumerate().all(|(i, &label)| usize::from(label) == i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn distinct_indices_in_any_order_are_accepted() {
+        assert_eq!(check_party_indices(&[0, 1, 2]), Ok(3));
+        assert_eq!(check_party_indices(&[2, 0, 1]), Ok(3));
+        assert_eq!(check_party_indices(&[3, 7, 9]), Ok(3));
+        assert!(is_positional(&[0, 1, 2]));
+        assert!(!is_positional(&[2, 0, 1]));
+        assert!(!is_positional(&[3, 7, 9]));
+    }
+
+    #[test]
+    fn repeats_are_refused() {
+        let repeat = check_party_indices(&[0, 1, 1]).unwrap_err();
+        assert!(repeat.starts_with("DUPLICATE_INDEX: party 1 "), "{repeat}");
+        let repeat = check_party_indices(&[7, 3, 7]).unwrap_err();
+        assert!(repeat.starts_with("DUPLICATE_INDEX: party 7 "), "{repeat}");
+    }
+
+    /// Around the bounds as often as anywhere else.
+    fn small_or_any() -> impl Strategy<Value = u16> {
+        prop_oneof![0u16..40, any::<u16>()]
+    }
+
+    proptest! {
+        #[test]
+        fn key_params_are_accepted_exactly_within_bounds(
+            n in small_or_any(),
+            threshold in small_or_any(),
+            max in 2u16..40,
+        ) {
+            let result = check_key_params_within(n, threshold, max);
+            prop_assert_eq!(result.is_ok(), (2..=max).contains(&n) && (2..=n).contains(&threshold));
+            if n >= 2 && n > max {
+                prop_assert!(result.unwrap_err().starts_with("N_TOO_LARGE: "));
+            }
+        }
+
+        #[test]
+        fn every_position_that_fits_a_u16_converts(i in prop_oneof![0usize..70_000, any::<usize>()]) {
+            match index(i) {
+                Ok(converted) => prop_assert_eq!(usize::from(converted), i),
+                Err(e) => {
+                    prop_assert!(i > usize::from(u16::MAX));
+                    prop_assert!(e.starts_with("N_TOO_LARGE: "));
+                }
+            }
+        }
+
+        #[test]
+        fn any_order_of_zero_to_n_is_a_key_of_n(
+            indices in (0u16..24).prop_flat_map(|n| Just((0..n).collect::<Vec<u16>>()).prop_shuffle()),
+        ) {
+            prop_assert_eq!(check_party_indices(&indices), Ok(index(indices.len()).unwrap()));
+        }
+
+        #[test]
+        fn party_indices_are_accepted_exactly_when_distinct(
+            indices in proptest::collection::vec(prop_oneof![0u16..24, any::<u16>()], 0..24),
+        ) {
+            let distinct: std::collections::BTreeSet<u16> = indices.iter().copied().collect();
+            prop_assert_eq!(check_party_indices(&indices).is_ok(), distinct.len() == indices.len());
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Checks on a cached `PregeneratedPrimes` blob before a DKG commits to it.
+//!
+//! A corrupt blob otherwise fails deep inside aux_info_gen with an opaque
+//! protocol error. Failures are reported by code so "wrong format" and
+//! "right format, wrong numbers" can't be confused:
+//! - `INVALID_PRIMES_FORMAT`: not a primes blob, or it doesn't deserialise
+//! - `SECURITY_LEVEL_MISMATCH`: a well-formed blob of another level
+//! - `PRIMES_TOO_SHORT`: a prime below the level's bit length
+//! - `PRIMES_NOT_PRIME`: a composite, or p == q
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use cggmp24::fast_paillier::backend::IsPrime;
+use cggmp24::security_level::SecurityLevel;
+use serde::Serialize;
+
+use crate::rng::MixedRng;
+use crate::share_format::{self, with_security_level, BlobKind, Level};
+
+/// Miller-Rabin rounds when the caller doesn't pick a count.
+pub const DEFAULT_MR_ROUNDS: u32 = 32;
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct PrimesReport {
+    pub valid: bool,
+    /// Bit length of the shorter prime; 0 if the blob didn't deserialise
+    pub bit_length: u32,
+    /// Level the blob is for (`"128"` / `"192"`), from its header
+    pub security_level: String,
+    /// `CODE: detail` when `valid` is false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Validate a primes blob, optionally against an expected level, running
+/// `rounds` Miller-Rabin rounds per prime.
+pub fn validate(bytes: &[u8], requested: Option<Level>, rounds: u32) -> PrimesReport {
+    let mut report = PrimesReport {
+        valid: false,
+        bit_length: 0,
+        security_level: String::new(),
+        error: None,
+    };
+    let (header, payload) = match share_format::split_expect(bytes, BlobKind::Primes) {
+        Ok(split) => split,
+        Err(e) => {
+            report.error = Some(format!("INVALID_PRIMES_FORMAT: {e}"));
+            return report;
+        }
+    };
+    report.security_level = header.level_or_default().as_str().into();
+    let level = match share_format::ensure_level(&header, requested) {
+        Ok(level) => level,
+        Err(e) => {
+            report.error = Some(e);
+            return report;
+        }
+    };
+
+    let checked = with_security_level!(level, L => check::<L>(payload, rounds, &mut report.bit_length));
+    match checked {
+        Ok(()) => report.valid = true,
+        Err(e) => report.error = Some(e),
+    }
+    report
+}
+
+fn check<L: SecurityLevel>(payload: &[u8], rounds: u32, bit_length: &mut u32) -> Result<(), String> {
+    let primes: cggmp24::PregeneratedPrimes<L> = share_format::deserialize(BlobKind::Primes, payload)
+        .map_err(|e| format!("INVALID_PRIMES_FORMAT: {e}"))?;
+    let (p, q) = primes.split();
+
+    let bits = p.significant_bits().min(q.significant_bits());
+    *bit_length = u32::try_from(bits).unwrap_or(u32::MAX);
+    if bits < u64::from(L::RSA_PRIME_BITLEN) {
+        return Err(format!(
+            "PRIMES_TOO_SHORT: {bits}-bit prime, level needs {} bits",
+            L::RSA_PRIME_BITLEN
+        ));
+    }
+    if p == q {
+        return Err("PRIMES_NOT_PRIME: p and q are equal".into());
+    }
+    for (name, x) in [("p", &p), ("q", &q)] {
+        if x.is_probably_prime(rounds, &mut MixedRng) == IsPrime::No {
+            return Err(format!("PRIMES_NOT_PRIME: {name} is composite"));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! Refusing Paillier primes a DKG must not use twice.
+//!
+//! Two parties given the same primes end up with the same Paillier modulus;
+//! the ceremony still succeeds, so a pool that hands one set out twice goes
+//! unnoticed. `run_dkg_with_primes` (WASM and native) fingerprints every
+//! party's set and refuses:
+//! - `DUPLICATE_PRIMES: {"parties":[i,j]}`: parties `i` and `j` got the same set
+//! - `PRIMES_ALREADY_USED`: a set whose fingerprint the caller lists as
+//!   consumed by an earlier ceremony
+//!
+//! A fingerprint is SHA-256 over the decoded primes, so a set matches
+//! however its blob is encoded or headered. The ceremony reports the
+//! fingerprints it consumed for the caller to add to its list. Both checks
+//! can be turned off (`allow_unsafe_prime_reuse`) for test environments
+//! that recycle fixtures.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use cggmp24::security_level::SecurityLevel;
+use sha2::{Digest, Sha256};
+
+const FINGERPRINT_DOMAIN: &[u8] = b"guardian-wallet primes fingerprint v1";
+
+/// Hex fingerprint of a primes set.
+pub fn fingerprint<L: SecurityLevel>(primes: &cggmp24::PregeneratedPrimes<L>) -> Result<String, String> {
+    let encoded = serde_json::to_vec(primes).map_err(|e| format!("serialize primes: {e}"))?;
+    let digest = Sha256::new()
+        .chain_update(FINGERPRINT_DOMAIN)
+        .chain_update(encoded)
+        .finalize();
+    Ok(digest.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Check the per-party `fingerprints` (party `i` at position `i`) against
+/// each other and against `used`, compared case-insensitively.
+pub fn check_reuse(fingerprints: &[String], used: &[String]) -> Result<(), String> {
+    for (j, fingerprint) in fingerprints.iter().enumerate() {
+        if let Some(i) = fingerprints[..j].iter().position(|earlier| earlier == fingerprint) {
+            return Err(format!("DUPLICATE_PRIMES: {{\"parties\":[{i},{j}]}}"));
+        }
+        if used.iter().any(|used| used.eq_ignore_ascii_case(fingerprint)) {
+            return Err(format!(
+                "PRIMES_ALREADY_USED: primes for party {j} ({fingerprint}) were used by an earlier DKG"
+            ));
+        }
+    }
+    Ok(())
+}
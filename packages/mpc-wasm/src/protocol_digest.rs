@@ -0,0 +1,87 @@
+//! The hash cggmp24 signing uses inside the protocol.
+//!
+//! Commitments, proof challenges and the reliable-broadcast echo are hashed
+//! with SHA-256 unless a session picks Keccak-256 (`SignOptions::digest`),
+//! for deployments whose tooling expects Keccak transcripts. It doesn't
+//! touch the data signed: a raw message is still hashed with SHA-256, and a
+//! prehashed one is used as is. Nor does it touch key shares, which are the
+//! same whichever digest signs with them.
+//!
+//! Every party of a session must use the same digest, and the state
+//! machine only notices a mix-up rounds in, as a proof that fails. So a
+//! session tags its protocol messages with its digest (`digest`, absent for
+//! SHA-256, which keeps the default wire format unchanged) and refuses a
+//! peer's message tagged otherwise with `DIGEST_MISMATCH`, on the first
+//! one.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+/// A digest cggmp24 signing can run with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProtocolDigest {
+    #[default]
+    Sha256,
+    Keccak256,
+}
+
+impl ProtocolDigest {
+    pub const ALL: [ProtocolDigest; 2] = [ProtocolDigest::Sha256, ProtocolDigest::Keccak256];
+
+    /// Parse `"sha256"` / `"keccak256"`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "sha256" => Ok(ProtocolDigest::Sha256),
+            "keccak256" => Ok(ProtocolDigest::Keccak256),
+            other => Err(format!(
+                "INVALID_DIGEST: unknown digest {other:?}, expected \"sha256\" or \"keccak256\""
+            )),
+        }
+    }
+
+    /// Parse an optional digest, SHA-256 if absent.
+    pub fn parse_opt(s: Option<&str>) -> Result<Self, String> {
+        s.map(ProtocolDigest::parse).transpose().map(Option::unwrap_or_default)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProtocolDigest::Sha256 => "sha256",
+            ProtocolDigest::Keccak256 => "keccak256",
+        }
+    }
+
+    /// The `digest` tag of this session's messages: none for SHA-256.
+    pub fn tag(self) -> Option<String> {
+        (self != ProtocolDigest::Sha256).then(|| self.as_str().to_string())
+    }
+
+    /// Check the `digest` tag of a message from `sender` against this
+    /// session's digest.
+    pub fn check(self, sender: u16, tag: Option<&str>) -> Result<(), String> {
+        let found = tag.unwrap_or(ProtocolDigest::Sha256.as_str());
+        if found != self.as_str() {
+            return Err(format!(
+                "DIGEST_MISMATCH: message from party {sender} was hashed with {found}, this session uses {}",
+                self.as_str()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Run `$body` with `$D` bound to the hash type for `$digest`.
+macro_rules! with_digest {
+    ($digest:expr, $D:ident => $body:expr) => {
+        match $digest {
+            $crate::protocol_digest::ProtocolDigest::Sha256 => {
+                type $D = sha2::Sha256;
+                $body
+            }
+            $crate::protocol_digest::ProtocolDigest::Keccak256 => {
+                type $D = sha3::Keccak256;
+                $body
+            }
+        }
+    };
+}
+pub(crate) use with_digest;
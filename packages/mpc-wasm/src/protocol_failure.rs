@@ -0,0 +1,119 @@
+//! Structured signing failures with identifiable-abort blame.
+//!
+//! When a signer misbehaves, CGGMP24's identifiable abort names it, but the
+//! crate keeps its error variants private: the blame is only visible in the
+//! `Debug` form of `SigningError`. This module reads it from there and
+//! reports a [`ProtocolFailure`] whose `blamed_parties` are keygen indices,
+//! so an orchestrator can quarantine the right signer.
+//!
+//! Failures travel in the usual error string as
+//! `PROTOCOL_FAILURE: {"kind":...,"blamed_parties":[...],"round":...,"detail":...}`;
+//! [`parse`] reads one back.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+const CODE: &str = "PROTOCOL_FAILURE: ";
+
+/// cggmp24 `SigningAborted` variants, as they appear in `Debug` output, and
+/// the `kind` each is reported as.
+const ABORT_KINDS: &[(&str, &str)] = &[
+    // Longest first: `InvalidPsi` is a prefix of `InvalidPsiPrimePrime`
+    ("InvalidPsiPrimePrime", "invalid_psi_prime_prime"),
+    ("InvalidPsi", "invalid_psi"),
+    ("EncProofOfK", "enc_proof_of_k"),
+    ("MismatchedDelta", "mismatched_delta"),
+    ("SignatureInvalid", "signature_invalid"),
+    ("Round1NotReliable", "round1_not_reliable"),
+];
+
+/// Other `SigningError` reasons.
+const OTHER_KINDS: &[(&str, &str)] = &[
+    ("InvalidArgs", "invalid_args"),
+    ("IoError", "io_error"),
+    ("Bug", "bug"),
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct ProtocolFailure {
+    /// Which check failed: one of the identifiable-abort kinds
+    /// (`enc_proof_of_k`, `invalid_psi`, `invalid_psi_prime_prime`,
+    /// `mismatched_delta`, `signature_invalid`, `round1_not_reliable`),
+    /// `invalid_args`, `io_error`, `bug`, or `unknown`
+    pub kind: String,
+    /// Keygen indices of the parties the protocol blames; empty when it
+    /// couldn't tell (`mismatched_delta`, `signature_invalid`, ...)
+    pub blamed_parties: Vec<u16>,
+    /// Relay round the failure surfaced in
+    pub round: u16,
+    /// The protocol's own description
+    pub detail: String,
+}
+
+impl ProtocolFailure {
+    /// Describe a cggmp24 `SigningError`; `parties` are the signers' keygen
+    /// indices, which the blamed signing positions are mapped through.
+    pub fn from_signing_error(error: &impl Debug, parties: &[u16]) -> Self {
+        let detail = format!("{error:?}");
+        let kind = ABORT_KINDS
+            .iter()
+            .chain(OTHER_KINDS)
+            .find(|(variant, _)| detail.contains(variant))
+            .map_or("unknown", |(_, kind)| kind);
+
+        let mut blamed_parties = Vec::new();
+        for position in blamed_positions(&detail, kind == "round1_not_reliable") {
+            let index = parties.get(usize::from(position)).copied().unwrap_or(position);
+            if !blamed_parties.contains(&index) {
+                blamed_parties.push(index);
+            }
+        }
+
+        ProtocolFailure {
+            kind: kind.into(),
+            blamed_parties,
+            round: 0,
+            detail,
+        }
+    }
+
+    /// The error string carrying this failure.
+    pub fn into_error(self) -> String {
+        format!("{CODE}{}", serde_json::to_string(&self).expect("serialize protocol failure"))
+    }
+}
+
+/// The failure in an error string from [`ProtocolFailure::into_error`].
+pub fn parse(error: &str) -> Option<ProtocolFailure> {
+    serde_json::from_str(error.strip_prefix(CODE)?).ok()
+}
+
+/// Record the relay round on a failure error; other errors pass through.
+pub fn stamp_round(error: String, round: u16) -> String {
+    match parse(&error) {
+        Some(failure) => ProtocolFailure { round, ..failure }.into_error(),
+        None => error,
+    }
+}
+
+/// Signing positions named in a `SigningError`'s `Debug` form: the
+/// `faulty_party` of each `AbortBlame`, or for `Round1NotReliable` the
+/// first element of each `(party, msg_id)` pair.
+fn blamed_positions(detail: &str, pairs: bool) -> Vec<u16> {
+    let marker = if pairs { "(" } else { "faulty_party: " };
+    detail
+        .match_indices(marker)
+        .filter_map(|(at, _)| {
+            let rest = &detail[at + marker.len()..];
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            if digits == 0 || (pairs && !rest[digits..].starts_with(',')) {
+                return None;
+            }
+            rest[..digits].parse().ok()
+        })
+        .collect()
+}
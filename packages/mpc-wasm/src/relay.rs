@@ -0,0 +1,193 @@
+//! Routing signing messages between the parties of a session.
+//!
+//! A relay hands every party the messages addressed to it, and nothing
+//! else. The rules, which every relay so far has re-implemented:
+//! - a broadcast (`is_broadcast`) goes to every party but its sender;
+//! - a P2P message goes to the one party whose keygen index is its
+//!   `recipient` — never by position in the signer list, which differs
+//!   from the index for e.g. `[1, 2]`;
+//! - nobody gets their own messages back;
+//! - each party gets its messages in the order they were sent, which the
+//!   reliable broadcast echo round relies on.
+//!
+//! [`route_messages`] applies them and fails on the first message it can't
+//! route; [`validate_routing`] lists every such message instead, for a
+//! relay that would rather log and drop. A message can't be routed when
+//! its sender isn't one of the parties (`UNKNOWN_SENDER`), its recipient
+//! isn't (`UNKNOWN_RECIPIENT`), it is addressed to its own sender
+//! (`SELF_ADDRESSED`), or `is_broadcast` and `recipient` disagree
+//! (`INCONSISTENT_ROUTE`). A party listed twice fails with
+//! `INVALID_PARTIES`.
+//!
+//! For at-least-once delivery, a session acknowledges what each call took:
+//! `accepted` lists the incoming messages it queued, or dropped as stale or
+//! not for it, and `rejected` ([`RejectedMessage`]) the ones it refused,
+//! the rest of the call going through regardless. Resend neither; a
+//! rejected message would only be rejected again.
+//!
+//! Sessions drop as stale ([`SeenMessages`]) a message from a round its
+//! sender has already moved past, and a second copy of one already
+//! delivered; both count in `stale_messages`. The state machine holds
+//! messages that arrive ahead of their round.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Who a message is from and for.
+pub struct Route {
+    pub sender: u16,
+    pub is_broadcast: bool,
+    /// Set iff `is_broadcast` is false
+    pub recipient: Option<u16>,
+}
+
+/// What [`validate_routing`] found.
+#[derive(Serialize, Clone, Debug, Default)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct RoutingReport {
+    /// True when every message can be routed
+    pub valid: bool,
+    /// One per message that can't, each `CODE: detail`
+    pub issues: Vec<String>,
+}
+
+/// `messages` sorted into one list per party (see the module docs). Every
+/// party has an entry, empty if nothing is addressed to it.
+pub fn route_messages<M: Clone>(
+    messages: &[M],
+    parties: &[u16],
+    route: impl Fn(&M) -> Route,
+) -> Result<BTreeMap<u16, Vec<M>>, String> {
+    check_parties(parties)?;
+    let mut routed: BTreeMap<u16, Vec<M>> = parties.iter().map(|&p| (p, Vec::new())).collect();
+    for (i, msg) in messages.iter().enumerate() {
+        let route = route(msg);
+        if let Some(issue) = issue(i, &route, parties) {
+            return Err(issue);
+        }
+        match route.recipient {
+            Some(recipient) => routed.entry(recipient).or_default().push(msg.clone()),
+            None => {
+                for (_, inbox) in routed.iter_mut().filter(|(&p, _)| p != route.sender) {
+                    inbox.push(msg.clone());
+                }
+            }
+        }
+    }
+    Ok(routed)
+}
+
+/// Every message of `messages` [`route_messages`] would refuse.
+pub fn validate_routing<M>(messages: &[M], parties: &[u16], route: impl Fn(&M) -> Route) -> RoutingReport {
+    let mut issues: Vec<String> = check_parties(parties).err().into_iter().collect();
+    issues.extend(
+        messages
+            .iter()
+            .enumerate()
+            .filter_map(|(i, msg)| issue(i, &route(msg), parties)),
+    );
+    RoutingReport {
+        valid: issues.is_empty(),
+        issues,
+    }
+}
+
+/// An incoming message a session refused.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct RejectedMessage {
+    /// Index into the call's incoming messages
+    pub index: u32,
+    /// E.g. `MESSAGE_AUTH_FAILED`
+    pub code: String,
+    pub detail: String,
+}
+
+impl RejectedMessage {
+    /// Message `index`, refused with `error` (`CODE: detail`).
+    pub fn new(index: u32, error: &str) -> Self {
+        let (code, detail) = error.split_once(": ").unwrap_or(("INVALID_MESSAGE", error));
+        RejectedMessage {
+            index,
+            code: code.to_string(),
+            detail: detail.to_string(),
+        }
+    }
+
+    /// The error it was refused with, `CODE: detail`.
+    pub fn error(&self) -> String {
+        format!("{}: {}", self.code, self.detail)
+    }
+}
+
+/// The messages a session has delivered, to drop stale ones: from a round
+/// below the highest delivered from their sender, or a second copy of one.
+/// Two messages are the same when their sender, relay round, recipient and
+/// payload are.
+#[derive(Default)]
+pub struct SeenMessages {
+    /// Highest round delivered from each sender
+    last_round_from: HashMap<u16, u16>,
+    delivered: HashSet<[u8; 32]>,
+}
+
+impl SeenMessages {
+    /// Note a message; false if it is stale.
+    pub fn insert(&mut self, sender: u16, round: u16, recipient: Option<u16>, payload: &str) -> bool {
+        let last = self.last_round_from.entry(sender).or_default();
+        if round < *last {
+            return false;
+        }
+        *last = round;
+
+        let mut hasher = Sha256::new();
+        hasher.update(sender.to_be_bytes());
+        hasher.update(round.to_be_bytes());
+        match recipient {
+            Some(recipient) => {
+                hasher.update([1]);
+                hasher.update(recipient.to_be_bytes());
+            }
+            None => hasher.update([0]),
+        }
+        hasher.update(payload.as_bytes());
+        self.delivered.insert(hasher.finalize().into())
+    }
+}
+
+fn check_parties(parties: &[u16]) -> Result<(), String> {
+    for (i, p) in parties.iter().enumerate() {
+        if parties[..i].contains(p) {
+            return Err(format!("INVALID_PARTIES: party {p} is listed twice"));
+        }
+    }
+    Ok(())
+}
+
+/// Why message `i` can't be routed, if it can't.
+fn issue(i: usize, route: &Route, parties: &[u16]) -> Option<String> {
+    let sender = route.sender;
+    if !parties.contains(&sender) {
+        return Some(format!("UNKNOWN_SENDER: message {i} is from party {sender}, not one of {parties:?}"));
+    }
+    match (route.is_broadcast, route.recipient) {
+        (true, None) => None,
+        (true, Some(recipient)) => Some(format!(
+            "INCONSISTENT_ROUTE: message {i} from party {sender} is a broadcast with recipient {recipient}"
+        )),
+        (false, None) => Some(format!(
+            "INCONSISTENT_ROUTE: message {i} from party {sender} is neither a broadcast nor has a recipient"
+        )),
+        (false, Some(recipient)) if recipient == sender => Some(format!(
+            "SELF_ADDRESSED: message {i} from party {sender} is addressed to its sender"
+        )),
+        (false, Some(recipient)) if !parties.contains(&recipient) => Some(format!(
+            "UNKNOWN_RECIPIENT: message {i} from party {sender} is for party {recipient}, not one of {parties:?}"
+        )),
+        (false, Some(_)) => None,
+    }
+}
@@ -0,0 +1,186 @@
+//! Mixing extra entropy into protocol randomness.
+//!
+//! Extra entropy — the host's `init({ extra_entropy })` in WASM, a hardware
+//! TRNG behind native-gen's `--entropy-source` — seeds an [`ExtraEntropy`]
+//! ChaCha20 stream: `SHA-256(domain ‖ previous ‖ extra)`, where `previous`
+//! is 32 bytes drawn from the stream so far, if any. There is one stream
+//! per process ([`add_extra`]), and every draw of [`MixedRng`] — the rng
+//! for key material, nonces and the protocol — is OS output XORed with it.
+//! XOR with an independent stream can't make the OS output any less random,
+//! so a weak or hostile source costs nothing; a good one covers a broken OS
+//! rng.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use std::sync::Mutex;
+
+use rand::rngs::OsRng;
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+const EXTRA_DOMAIN: &[u8] = b"guardian-wallet extra entropy v1";
+
+/// Least extra entropy, in bytes, a source must yield to be used at all.
+pub const MIN_EXTRA_ENTROPY: usize = 32;
+
+/// The process's stream, once extra entropy was supplied.
+static EXTRA: Mutex<Option<ExtraEntropy>> = Mutex::new(None);
+
+/// Fold `extra` into the process's stream. Repeated calls chain onto what
+/// was supplied before rather than replacing it.
+pub fn add_extra(extra: &[u8]) {
+    if extra.is_empty() {
+        return;
+    }
+    let mut stream = EXTRA.lock().unwrap_or_else(|e| e.into_inner());
+    match stream.as_mut() {
+        Some(stream) => stream.add(extra),
+        None => *stream = Some(ExtraEntropy::new(extra)),
+    }
+}
+
+/// Whether any extra entropy was supplied.
+pub fn has_extra() -> bool {
+    EXTRA.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+/// XOR the process's stream into `dest`; nothing without one.
+pub fn mix_extra(dest: &mut [u8]) {
+    if let Some(stream) = EXTRA.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        stream.mix(dest);
+    }
+}
+
+/// `OsRng` with the process's stream mixed into every draw: use it wherever
+/// `OsRng` would do. Like `OsRng`, the infallible draws panic if the OS
+/// rng fails.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MixedRng;
+
+impl RngCore for MixedRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if let Err(e) = self.try_fill_bytes(dest) {
+            panic!("ENTROPY_UNAVAILABLE: {e}");
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        OsRng.try_fill_bytes(dest)?;
+        mix_extra(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for MixedRng {}
+
+/// The stream extra entropy is mixed in with.
+pub struct ExtraEntropy(ChaCha20Rng);
+
+impl ExtraEntropy {
+    /// A stream seeded from `extra` alone.
+    pub fn new(extra: &[u8]) -> Self {
+        ExtraEntropy(Self::seeded(None, extra))
+    }
+
+    /// Chain `extra` onto what the stream was seeded with so far.
+    pub fn add(&mut self, extra: &[u8]) {
+        let mut previous = [0u8; 32];
+        self.0.fill_bytes(&mut previous);
+        self.0 = Self::seeded(Some(&previous), extra);
+        previous.zeroize();
+    }
+
+    /// XOR the next `dest.len()` bytes of the stream into `dest`.
+    pub fn mix(&mut self, dest: &mut [u8]) {
+        let mut pad = vec![0u8; dest.len()];
+        self.0.fill_bytes(&mut pad);
+        for (d, p) in dest.iter_mut().zip(&pad) {
+            *d ^= p;
+        }
+        pad.zeroize();
+    }
+
+    fn seeded(previous: Option<&[u8; 32]>, extra: &[u8]) -> ChaCha20Rng {
+        let mut hasher = Sha256::new();
+        hasher.update(EXTRA_DOMAIN);
+        if let Some(previous) = previous {
+            hasher.update(previous);
+        }
+        hasher.update(extra);
+        let mut seed: [u8; 32] = hasher.finalize().into();
+        let rng = ChaCha20Rng::from_seed(seed);
+        seed.zeroize();
+        rng
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixing_twice_with_the_same_stream_gives_the_input_back() {
+        let input: Vec<u8> = (0..100).collect();
+        let mut dest = input.clone();
+        ExtraEntropy::new(b"extra").mix(&mut dest);
+        assert_ne!(dest, input);
+        ExtraEntropy::new(b"extra").mix(&mut dest);
+        assert_eq!(dest, input);
+    }
+
+    #[test]
+    fn extra_entropy_reaches_mixed_rng() {
+        add_extra(b"host entropy");
+        assert!(has_extra());
+        let mut drawn = [0u8; 32];
+        MixedRng.try_fill_bytes(&mut drawn).unwrap();
+        assert_ne!(drawn, [0; 32]);
+    }
+
+    #[test]
+    fn a_fixed_source_mixes_in_a_fixed_stream() {
+        // ChaCha20 keyed by SHA-256(EXTRA_DOMAIN || "hardware trng")
+        let mut dest = [0u8; 32];
+        ExtraEntropy::new(b"hardware trng").mix(&mut dest);
+        assert_eq!(
+            crate::hex_codec::encode(&dest),
+            "cbeb55df88e3ea943f89733ad655625d07e1752765d2ce55018b3d1d8dadd941"
+        );
+    }
+
+    #[test]
+    fn extra_entropy_changes_the_protocol_rng() {
+        let os_seed = [7u8; 32];
+        let seeded = |extra: Option<&[u8]>| {
+            let mut seed = os_seed;
+            if let Some(extra) = extra {
+                ExtraEntropy::new(extra).mix(&mut seed);
+            }
+            ChaCha20Rng::from_seed(seed).next_u64()
+        };
+        let plain = seeded(None);
+        assert_ne!(seeded(Some(b"trng a")), plain);
+        assert_ne!(seeded(Some(b"trng a")), seeded(Some(b"trng b")));
+        assert_eq!(seeded(Some(b"trng a")), seeded(Some(b"trng a")));
+    }
+
+    #[test]
+    fn added_entropy_chains_onto_what_came_before() {
+        let mut chained = ExtraEntropy::new(b"first");
+        chained.add(b"second");
+        let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+        chained.mix(&mut a);
+        ExtraEntropy::new(b"second").mix(&mut b);
+        assert_ne!(a, b);
+    }
+}
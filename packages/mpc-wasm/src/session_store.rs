@@ -0,0 +1,190 @@
+//! Host storage for signing sessions, so they outlive the module.
+//!
+//! A browser extension's background worker can be killed between two rounds
+//! of a signing session, taking the session map with it. With a store
+//! installed (`init({ session_store })`), every ECDSA `sign_process_round`
+//! ends by handing the session's export (`sign::export_session`, the replay
+//! log) to `save(session_id, bytes)`, or `delete(session_id)` once the
+//! session completed, failed or is gone. A `sign_process_round` for an id
+//! this module doesn't hold first asks `load(session_id)` for an export to
+//! import, then carries on as usual.
+//!
+//! Exports leave the key share and aux info out unless the store was
+//! installed with `include_key_material: true`: they name the key by id,
+//! so a restore needs that key registered again (`register_key`) first.
+//! They always hold the session's rng seed — keep them out of storage a
+//! third party can read.
+//!
+//! The callbacks run synchronously: `load` returns a `Uint8Array`, or
+//! `null` / `undefined` when it holds nothing for the id, never a Promise.
+//! A callback that throws or returns something unusable is logged to the
+//! console and otherwise ignored; the sessions in memory are never touched
+//! by a store failure, and a failed `load` leaves the call failing as it
+//! would have without a store. Batch and FROST sessions aren't stored.
+
+use std::cell::RefCell;
+
+use js_sys::{Function, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::sign::{self, SessionStatus};
+
+#[wasm_bindgen(typescript_custom_section)]
+const SESSION_STORE_TS: &str = r#"
+export interface SessionStoreCallbacks {
+    save: (session_id: string, bytes: Uint8Array) => void;
+    load: (session_id: string) => Uint8Array | null | undefined;
+    delete: (session_id: string) => void;
+    include_key_material?: boolean;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console)]
+    fn warn(message: &str);
+}
+
+/// The host's callbacks.
+#[derive(Clone)]
+pub struct SessionStore {
+    save: Function,
+    load: Function,
+    delete: Function,
+    include_key_material: bool,
+}
+
+thread_local! {
+    static STORE: RefCell<Option<SessionStore>> = const { RefCell::new(None) };
+}
+
+/// Split `init`'s options into the rest and their `session_store`, parsed;
+/// `None` if they have none. Functions don't survive serde, so the store
+/// is read off the object before the other fields are deserialised.
+pub fn take(options: Option<JsValue>) -> Result<(Option<JsValue>, Option<Option<SessionStore>>), String> {
+    let key = JsValue::from_str("session_store");
+    let Some(object) = options.as_ref().and_then(|o| o.dyn_ref::<Object>()).cloned() else {
+        return Ok((options, None));
+    };
+    let value = Reflect::get(&object, &key).unwrap_or(JsValue::UNDEFINED);
+    if value.is_undefined() {
+        return Ok((options, None));
+    }
+    let store = parse(&value)?;
+    let rest = Object::assign(&Object::new(), &object);
+    Reflect::delete_property(&rest, &key).map_err(|e| format!("INVALID_SESSION_STORE: {e:?}"))?;
+    Ok((Some(rest.into()), Some(store)))
+}
+
+/// Read a `session_store`: `{ save, load, delete, include_key_material? }`,
+/// or `null` for none. Fails with `INVALID_SESSION_STORE` naming the field
+/// that isn't a function (or boolean).
+fn parse(value: &JsValue) -> Result<Option<SessionStore>, String> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let field = |name: &str| Reflect::get(value, &JsValue::from_str(name)).unwrap_or(JsValue::UNDEFINED);
+    let function = |name: &str| {
+        field(name)
+            .dyn_into::<Function>()
+            .map_err(|_| format!("INVALID_SESSION_STORE: {name} must be a function"))
+    };
+    let include_key_material = match field("include_key_material") {
+        flag if flag.is_undefined() => false,
+        flag => flag
+            .as_bool()
+            .ok_or("INVALID_SESSION_STORE: include_key_material must be a boolean")?,
+    };
+    Ok(Some(SessionStore {
+        save: function("save")?,
+        load: function("load")?,
+        delete: function("delete")?,
+        include_key_material,
+    }))
+}
+
+/// Use `store` from now on, replacing any earlier one.
+pub fn install(store: Option<SessionStore>) {
+    STORE.with(|current| *current.borrow_mut() = store);
+}
+
+/// A copy of the installed store, so callbacks run with `STORE` unborrowed
+/// and may call back into the module.
+fn current() -> Option<SessionStore> {
+    STORE.with(|store| store.borrow().clone())
+}
+
+/// Before a call on `session_id`: if no such session is live, import the
+/// store's export of it. Returns whether one was imported.
+pub fn restore(session_id: &str) -> bool {
+    if sign::is_live(session_id) {
+        return false;
+    }
+    let Some(store) = current() else {
+        return false;
+    };
+    let loaded = match store.load.call1(&JsValue::NULL, &JsValue::from_str(session_id)) {
+        Ok(loaded) if loaded.is_null() || loaded.is_undefined() => return false,
+        Ok(loaded) => loaded,
+        Err(e) => {
+            warn(&format!("session_store.load({session_id}) threw: {e:?}"));
+            return false;
+        }
+    };
+    let Some(bytes) = loaded.dyn_ref::<Uint8Array>() else {
+        warn(&format!("session_store.load({session_id}) returned neither a Uint8Array nor null"));
+        return false;
+    };
+    match sign::import_session(&bytes.to_vec()) {
+        Ok(_) => true,
+        Err(e) => {
+            warn(&format!("session_store: can't restore {session_id}: {e}"));
+            false
+        }
+    }
+}
+
+/// After a call on `session_id`: save its export while it is waiting for
+/// messages, delete it once it is over. A session computing under a time
+/// budget keeps its last save.
+pub fn sync(session_id: &str) {
+    if sign::is_batch(session_id) {
+        return;
+    }
+    let Some(store) = current() else {
+        return;
+    };
+    match sign::session_status(session_id).map(|state| state.status) {
+        Ok(SessionStatus::Waiting) => {
+            let bytes = match sign::export_session(session_id, store.include_key_material) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    warn(&format!("session_store: can't export {session_id}: {e}"));
+                    return;
+                }
+            };
+            let id = JsValue::from_str(session_id);
+            if let Err(e) = store.save.call2(&JsValue::NULL, &id, &Uint8Array::from(&bytes[..])) {
+                warn(&format!("session_store.save({session_id}) threw: {e:?}"));
+            }
+        }
+        Ok(SessionStatus::Computing) => {}
+        Ok(SessionStatus::Complete | SessionStatus::Aborted | SessionStatus::Failed) | Err(_) => {
+            forget(&store, session_id);
+        }
+    }
+}
+
+/// Delete `session_id` from the store, e.g. when it is destroyed.
+pub fn delete(session_id: &str) {
+    if let Some(store) = current() {
+        forget(&store, session_id);
+    }
+}
+
+fn forget(store: &SessionStore, session_id: &str) {
+    if let Err(e) = store.delete.call1(&JsValue::NULL, &JsValue::from_str(session_id)) {
+        warn(&format!("session_store.delete({session_id}) threw: {e:?}"));
+    }
+}
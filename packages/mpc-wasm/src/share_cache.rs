@@ -0,0 +1,98 @@
+//! LRU cache of combined ECDSA key shares for repeated signings.
+//!
+//! Deserialising a KeyShare and validating it in `from_parts` is a large
+//! share of per-signature latency, and a wallet signs with the same blobs
+//! over and over. Entries are keyed by `sha256(core_share || aux_info)` over
+//! the serialised blobs and hold the combined share behind an `Arc`:
+//! concurrent sessions on the same share each keep their own reference to
+//! an immutable value, so they can't interfere.
+//!
+//! Evicting or removing an entry drops the cache's reference; the share is
+//! freed — its secret scalar wiped — once the last session using it ends.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too. The
+//! WASM crate keeps its cache in a thread-local, native-gen behind a mutex.
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+
+/// Entries kept when the host doesn't configure a capacity.
+pub const DEFAULT_CAPACITY: usize = 8;
+
+/// `sha256(core_share || aux_info)`
+pub type CacheKey = [u8; 32];
+
+/// Cache key of a serialised core share / aux info pair.
+pub fn cache_key(core_share: &[u8], aux_info: &[u8]) -> CacheKey {
+    Sha256::new()
+        .chain_update(core_share)
+        .chain_update(aux_info)
+        .finalize()
+        .into()
+}
+
+/// Least recently used first. Values are type-erased so one cache covers
+/// every curve / security level; the blob headers fix the type, so a key
+/// always maps to the same one.
+pub struct ShareCache {
+    capacity: usize,
+    entries: VecDeque<(CacheKey, Arc<dyn Any + Send + Sync>)>,
+}
+
+impl ShareCache {
+    pub const fn new(capacity: usize) -> Self {
+        ShareCache {
+            capacity,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Change the capacity, evicting the oldest entries if it shrank. 0
+    /// disables caching.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The cached value for `key`, or `build()`'s, cached. The flag is
+    /// true on a cache hit.
+    pub fn get_or_build<T: Any + Send + Sync>(
+        &mut self,
+        key: CacheKey,
+        build: impl FnOnce() -> Result<T, String>,
+    ) -> Result<(Arc<T>, bool), String> {
+        if let Some(pos) = self.entries.iter().position(|(k, _)| *k == key) {
+            let entry = self.entries.remove(pos).expect("position is in range");
+            if let Ok(value) = Arc::clone(&entry.1).downcast::<T>() {
+                self.entries.push_back(entry);
+                return Ok((value, true));
+            }
+        }
+        let value = Arc::new(build()?);
+        if self.capacity > 0 {
+            self.entries.push_back((key, value.clone()));
+            self.evict();
+        }
+        Ok((value, false))
+    }
+
+    /// Drop the entry for `key`; true if there was one.
+    pub fn remove(&mut self, key: &CacheKey) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|(k, _)| k != key);
+        self.entries.len() != before
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Checking that stored key material still signs under this build's
+//! cggmp24.
+//!
+//! A cggmp24 upgrade can change how aux info is read without making old
+//! blobs fail to parse, and then the first sign to notice is a proof error
+//! rounds into a session. [`check_share_compat`] finds that out up front,
+//! locally:
+//! 1. both blobs' headers are read and their format reported
+//!    ([`BlobSchema`]);
+//! 2. both payloads are deserialised and combined with
+//!    `KeyShare::from_parts` (the checks of `key_material::combine_key_share`);
+//! 3. the combined share runs the first round of a signing as this party,
+//!    which encrypts under its own Paillier key and proves against the
+//!    ring-Pedersen parameters the aux info holds for the other parties.
+//!
+//! The dry run stays in this process: its signer set is made up from the
+//! key's own party count, its messages are dropped as they come out and
+//! its eid and hash are fixed dummies, so no co-signer is contacted or
+//! needs to exist. It stops before any round that would need a peer's
+//! reply.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use generic_ec::coords::HasAffineX;
+use generic_ec::{Curve, Point, Scalar};
+use round_based::state_machine::{ProceedResult, StateMachine};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use cggmp24::key_share::AnyKeyShare;
+use cggmp24::security_level::SecurityLevel;
+use cggmp24::signing::PrehashedDataToSign;
+
+use crate::capabilities::CGGMP24_VERSION;
+use crate::drive_guard::YieldGuard;
+use crate::key_material;
+use crate::rng::MixedRng;
+use crate::share_format::{self, with_curve, with_security_level, BlobHeader, BlobKind};
+
+const DRY_RUN_EID_DOMAIN: &[u8] = b"guardian-wallet share compat dry run eid v1";
+const DRY_RUN_HASH_DOMAIN: &[u8] = b"guardian-wallet share compat dry run hash v1";
+
+/// How a blob was written.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct BlobSchema {
+    /// Header version; 0 for legacy (headerless) blobs
+    pub version: u8,
+    /// `"json"` or `"cbor"`
+    pub encoding: String,
+}
+
+impl From<&BlobHeader> for BlobSchema {
+    fn from(header: &BlobHeader) -> Self {
+        BlobSchema {
+            version: header.version,
+            encoding: header.encoding.as_str().into(),
+        }
+    }
+}
+
+/// What [`check_share_compat`] found.
+#[derive(Serialize, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct ShareCompatReport {
+    /// True when the pair combines and its first signing round runs
+    pub compatible: bool,
+    /// The cggmp24 release checked against
+    pub cggmp24_version: String,
+    /// Format of the core share; `None` if its header couldn't be read
+    pub core_schema: Option<BlobSchema>,
+    /// Format of the aux info; `None` if its header couldn't be read
+    pub aux_schema: Option<BlobSchema>,
+    /// Every problem found, each `CODE: detail`
+    pub issues: Vec<String>,
+}
+
+/// Check a core share / aux info pair against this build (see the module
+/// docs). Never fails: problems land in `issues`.
+pub fn check_share_compat(core_key_share: &[u8], aux_info: &[u8]) -> ShareCompatReport {
+    let mut report = ShareCompatReport {
+        compatible: false,
+        cggmp24_version: CGGMP24_VERSION.into(),
+        core_schema: None,
+        aux_schema: None,
+        issues: Vec::new(),
+    };
+    if let Err(e) = check_pair(&mut report, core_key_share, aux_info) {
+        report.issues.push(e);
+    }
+    report.compatible = report.issues.is_empty();
+    report
+}
+
+fn check_pair(report: &mut ShareCompatReport, core_key_share: &[u8], aux_info: &[u8]) -> Result<(), String> {
+    let core = share_format::split_expect(core_key_share, BlobKind::CoreShare);
+    let aux = share_format::split_expect(aux_info, BlobKind::AuxInfo);
+    if let Ok((header, _)) = &core {
+        report.core_schema = Some(header.into());
+    }
+    if let Ok((header, _)) = &aux {
+        report.aux_schema = Some(header.into());
+    }
+    let (core_header, core_payload) = core.map_err(|e| format!("core share: {e}"))?;
+    let (aux_header, aux_payload) = aux.map_err(|e| format!("aux info: {e}"))?;
+    let curve = core_header.curve_or_default();
+    let level = aux_header.level_or_default();
+
+    with_curve!(curve, E => with_security_level!(level, L => {
+        let iks = share_format::deserialize::<cggmp24::IncompleteKeyShare<E>>(BlobKind::CoreShare, core_payload)
+            .map_err(|e| schema_issue("CORE_SCHEMA_INCOMPATIBLE", "core share", &core_header, &e));
+        let aux = share_format::deserialize::<cggmp24::key_share::AuxInfo<L>>(BlobKind::AuxInfo, aux_payload)
+            .map_err(|e| schema_issue("AUX_SCHEMA_INCOMPATIBLE", "aux info", &aux_header, &e));
+        let (iks, aux) = match (iks, aux) {
+            (Ok(iks), Ok(aux)) => (iks, aux),
+            (iks, aux) => {
+                report.issues.extend(iks.err());
+                report.issues.extend(aux.err());
+                return Ok(());
+            }
+        };
+        let key_share = match key_material::pair_up(iks, aux, None) {
+            Ok(key_share) => key_share,
+            Err(errors) => {
+                report.issues.extend(errors);
+                return Ok(());
+            }
+        };
+        dry_run_first_round(&key_share)
+    }))
+}
+
+/// A blob that parses as a header but not as this release's type.
+fn schema_issue(code: &str, what: &str, header: &BlobHeader, error: &str) -> String {
+    format!(
+        "{code}: {what} (format v{}, {}) doesn't deserialize under cggmp24 {CGGMP24_VERSION}; \
+         it was probably written by another release: {error}",
+        header.version,
+        header.encoding.as_str()
+    )
+}
+
+/// Run this party's first signing round with dummy inputs, dropping its
+/// messages.
+fn dry_run_first_round<E, L>(key_share: &cggmp24::KeyShare<E, L>) -> Result<(), String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+{
+    let i = key_share.core.i;
+    // The lowest other indices make up the rest of a minimal signer set
+    let mut parties: Vec<u16> = (0..key_share.n())
+        .filter(|&p| p != i)
+        .take(usize::from(key_share.min_signers().saturating_sub(1)))
+        .collect();
+    parties.push(i);
+    parties.sort_unstable();
+    let position = key_material::check_signer_set(key_share.n(), key_share.min_signers(), i, &parties)?;
+
+    let eid_bytes = Sha256::digest(DRY_RUN_EID_DOMAIN);
+    let scalar = Scalar::<E>::from_be_bytes_mod_order(Sha256::digest(DRY_RUN_HASH_DOMAIN).as_slice());
+    let data = PrehashedDataToSign::from_scalar(scalar);
+    let mut rng = MixedRng;
+    let mut sm = cggmp24::signing(cggmp24::ExecutionId::new(eid_bytes.as_slice()), position, &parties, key_share)
+        .enforce_reliable_broadcast(true)
+        .sign_sync(&mut rng, &data);
+
+    let mut sent = 0usize;
+    let mut yields = YieldGuard::default();
+    loop {
+        let proceeded = sm.proceed();
+        yields.step(matches!(proceeded, ProceedResult::Yielded))?;
+        match proceeded {
+            ProceedResult::SendMsg(_) => sent += 1,
+            ProceedResult::Yielded => {}
+            // Waiting on peers: round 1 is out
+            ProceedResult::NeedsOneMoreMessage if sent > 0 => return Ok(()),
+            ProceedResult::NeedsOneMoreMessage => {
+                return Err("SIGNING_DRY_RUN_FAILED: first round produced no messages".into())
+            }
+            ProceedResult::Output(_) => return Ok(()),
+            ProceedResult::Error(e) => {
+                return Err(format!(
+                    "SIGNING_DRY_RUN_FAILED: the first signing round fails with this key material: {e}"
+                ))
+            }
+        }
+    }
+}
@@ -0,0 +1,412 @@
+//! Share-at-rest and in-transit encryption.
+//!
+//! Two envelope formats, both versioned and self-describing:
+//!
+//! - **Passphrase envelope** (`encrypt_share` / `decrypt_share`): Argon2id
+//!   derives a 256-bit key, AES-256-GCM encrypts the share.
+//! - **Recipient envelope** (`wrap_share_to_recipient` / `unwrap_share`):
+//!   ECIES over secp256k1 — ephemeral ECDH, HKDF-SHA256, AES-256-GCM — so a
+//!   share can be encrypted to a key the recipient generated beforehand.
+//!
+//! Passphrase envelope layout (all integers little-endian):
+//!
+//! ```text
+//! magic      4 bytes   "GWSE"
+//! version    1 byte    1
+//! kdf        1 byte    1 = Argon2id (v0x13)
+//! m_cost     4 bytes   memory in KiB
+//! t_cost     4 bytes   iterations
+//! p_cost     4 bytes   lanes
+//! salt       16 bytes
+//! nonce      12 bytes
+//! ciphertext N bytes   AES-256-GCM output (includes 16-byte tag)
+//! ```
+//!
+//! The whole header (everything before the ciphertext) is bound as AAD, so
+//! tampering with the KDF parameters fails authentication like a wrong
+//! passphrase would.
+//!
+//! Recipient envelope layout:
+//!
+//! ```text
+//! magic      4 bytes   "GWSW"
+//! version    1 byte    1
+//! eph_pub    33 bytes  compressed secp256k1 ephemeral public key
+//! nonce      12 bytes
+//! ciphertext N bytes   AES-256-GCM output (16-byte tag is the MAC)
+//! ```
+//!
+//! The AEAD key is `HKDF-SHA256(ikm = compressed ECDH point,
+//! salt = eph_pub || recipient_pub, info = "guardian-wallet/share-wrap/v1")`
+//! and the header is bound as AAD.
+//!
+//! This module has no wasm-bindgen dependency — native-gen compiles it too.
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use generic_ec::curves::Secp256k1;
+use generic_ec::{Point, SecretScalar};
+use hkdf::Hkdf;
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::rng::MixedRng;
+
+const MAGIC: &[u8; 4] = b"GWSE";
+const FORMAT_VERSION: u8 = 1;
+const KDF_ARGON2ID: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = 4 + 1 + 1 + 4 + 4 + 4 + SALT_LEN + NONCE_LEN;
+
+const WRAP_MAGIC: &[u8; 4] = b"GWSW";
+const WRAP_VERSION: u8 = 1;
+const WRAP_INFO: &[u8] = b"guardian-wallet/share-wrap/v1";
+const PUBKEY_LEN: usize = 33;
+const WRAP_HEADER_LEN: usize = 4 + 1 + PUBKEY_LEN + NONCE_LEN;
+
+/// Upper bounds on the cost parameters, for encrypting and decrypting
+/// alike: an envelope never asks for more than `encrypt_share` could have
+/// written, so a hostile header cannot make a browser worker allocate
+/// beyond 256 MiB or spin for minutes before failing. Checked before the
+/// KDF allocates anything.
+const MAX_M_COST_KIB: u32 = 256 * 1024;
+const MAX_T_COST: u32 = 64;
+const MAX_P_COST: u32 = 16;
+
+/// Argon2id cost parameters.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[serde(default)]
+pub struct KdfParams {
+    /// Memory cost in KiB
+    pub m_cost: u32,
+    /// Number of iterations
+    pub t_cost: u32,
+    /// Degree of parallelism
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        // OWASP-recommended baseline; fits comfortably in WASM memory.
+        Self {
+            m_cost: 64 * 1024,
+            t_cost: 3,
+            p_cost: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    fn validate(&self) -> Result<(), String> {
+        if self.m_cost < 8 * self.p_cost || self.m_cost > MAX_M_COST_KIB {
+            return Err(format!("INVALID_KDF_PARAMS: m_cost {} out of range", self.m_cost));
+        }
+        if self.t_cost == 0 || self.t_cost > MAX_T_COST {
+            return Err(format!("INVALID_KDF_PARAMS: t_cost {} out of range", self.t_cost));
+        }
+        if self.p_cost == 0 || self.p_cost > MAX_P_COST {
+            return Err(format!("INVALID_KDF_PARAMS: p_cost {} out of range", self.p_cost));
+        }
+        Ok(())
+    }
+}
+
+/// Encrypt `share` under `passphrase`, returning a self-describing envelope.
+pub fn encrypt_share(share: &[u8], passphrase: &str, params: KdfParams) -> Result<Vec<u8>, String> {
+    params.validate()?;
+    if passphrase.is_empty() {
+        return Err("INVALID_PASSPHRASE: passphrase must not be empty".into());
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    MixedRng.try_fill_bytes(&mut salt).map_err(|e| format!("getrandom salt: {e}"))?;
+    MixedRng.try_fill_bytes(&mut nonce).map_err(|e| format!("getrandom nonce: {e}"))?;
+
+    let mut out = Vec::with_capacity(HEADER_LEN + share.len() + 16);
+    out.extend_from_slice(MAGIC);
+    out.push(FORMAT_VERSION);
+    out.push(KDF_ARGON2ID);
+    out.extend_from_slice(&params.m_cost.to_le_bytes());
+    out.extend_from_slice(&params.t_cost.to_le_bytes());
+    out.extend_from_slice(&params.p_cost.to_le_bytes());
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+
+    let key = derive_key(passphrase, &salt, params)?;
+    let cipher = Aes256Gcm::new_from_slice(key.as_slice())
+        .map_err(|e| format!("init cipher: {e}"))?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: share, aad: &out })
+        .map_err(|_| "encrypt share: AEAD failure".to_string())?;
+
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Decrypt an envelope produced by [`encrypt_share`].
+///
+/// The plaintext is returned in a `Zeroizing` buffer so callers that fail to
+/// parse it afterwards wipe it on drop.
+pub fn decrypt_share(blob: &[u8], passphrase: &str) -> Result<Zeroizing<Vec<u8>>, String> {
+    if blob.len() < HEADER_LEN + 16 {
+        return Err(format!(
+            "INVALID_ENVELOPE: {} bytes is too short for an encrypted share",
+            blob.len()
+        ));
+    }
+    if &blob[..4] != MAGIC {
+        return Err("INVALID_ENVELOPE: not an encrypted share (bad magic)".into());
+    }
+    if blob[4] != FORMAT_VERSION {
+        return Err(format!("UNSUPPORTED_ENVELOPE_VERSION: {}", blob[4]));
+    }
+    if blob[5] != KDF_ARGON2ID {
+        return Err(format!("UNSUPPORTED_KDF: {}", blob[5]));
+    }
+
+    let read_u32 = |at: usize| u32::from_le_bytes([blob[at], blob[at + 1], blob[at + 2], blob[at + 3]]);
+    let params = KdfParams {
+        m_cost: read_u32(6),
+        t_cost: read_u32(10),
+        p_cost: read_u32(14),
+    };
+    params.validate()?;
+
+    let salt = &blob[18..18 + SALT_LEN];
+    let nonce = &blob[18 + SALT_LEN..HEADER_LEN];
+    let (header, ciphertext) = blob.split_at(HEADER_LEN);
+
+    let key = derive_key(passphrase, salt, params)?;
+    let cipher = Aes256Gcm::new_from_slice(key.as_slice())
+        .map_err(|e| format!("init cipher: {e}"))?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: header })
+        .map_err(|_| {
+            "DECRYPT_AUTH_FAILED: wrong passphrase or corrupted envelope".to_string()
+        })?;
+
+    Ok(Zeroizing::new(plaintext))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: KdfParams) -> Result<Zeroizing<[u8; 32]>, String> {
+    let argon_params = Params::new(params.m_cost, params.t_cost, params.p_cost, Some(32))
+        .map_err(|e| format!("INVALID_KDF_PARAMS: {e}"))?;
+    let argon = Argon2::new(Algorithm::Argon2id, Version::V0x13, argon_params);
+    let mut key = Zeroizing::new([0u8; 32]);
+    argon
+        .hash_password_into(passphrase.as_bytes(), salt, &mut *key)
+        .map_err(|e| format!("derive key: {e}"))?;
+    Ok(key)
+}
+
+// ---------------------------------------------------------------------------
+// Recipient envelope (ECIES)
+// ---------------------------------------------------------------------------
+
+/// Encrypt `share` to a recipient's secp256k1 public key (33-byte
+/// compressed or 65-byte uncompressed; the envelope binds the compressed
+/// form either way, so `unwrap_share` doesn't care which was used).
+pub fn wrap_share_to_recipient(share: &[u8], recipient_pubkey: &[u8]) -> Result<Vec<u8>, String> {
+    if recipient_pubkey.len() != PUBKEY_LEN && recipient_pubkey.len() != 65 {
+        return Err(format!(
+            "INVALID_RECIPIENT_KEY: expected a {PUBKEY_LEN}- or 65-byte key, got {} bytes",
+            recipient_pubkey.len()
+        ));
+    }
+    let recipient = Point::<Secp256k1>::from_bytes(recipient_pubkey)
+        .map_err(|_| "INVALID_RECIPIENT_KEY: not a valid secp256k1 point".to_string())?;
+    if recipient.is_zero() {
+        return Err("INVALID_RECIPIENT_KEY: point at infinity".into());
+    }
+
+    let ephemeral = SecretScalar::<Secp256k1>::random(&mut MixedRng);
+    let eph_pub = Point::generator() * &ephemeral;
+    let shared = recipient * &ephemeral;
+
+    let mut nonce = [0u8; NONCE_LEN];
+    MixedRng.try_fill_bytes(&mut nonce).map_err(|e| format!("getrandom nonce: {e}"))?;
+
+    let mut out = Vec::with_capacity(WRAP_HEADER_LEN + share.len() + 16);
+    out.extend_from_slice(WRAP_MAGIC);
+    out.push(WRAP_VERSION);
+    out.extend_from_slice(eph_pub.to_bytes(true).as_bytes());
+    out.extend_from_slice(&nonce);
+
+    let key = wrap_key(&shared, &out[5..5 + PUBKEY_LEN], recipient.to_bytes(true).as_bytes())?;
+    let cipher = Aes256Gcm::new_from_slice(key.as_slice())
+        .map_err(|e| format!("init cipher: {e}"))?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: share, aad: &out })
+        .map_err(|_| "wrap share: AEAD failure".to_string())?;
+
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Decrypt a recipient envelope with the recipient's 32-byte secret key.
+pub fn unwrap_share(blob: &[u8], recipient_secret: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+    if blob.len() < WRAP_HEADER_LEN + 16 {
+        return Err(format!(
+            "INVALID_ENVELOPE: {} bytes is too short for a wrapped share",
+            blob.len()
+        ));
+    }
+    if &blob[..4] != WRAP_MAGIC {
+        return Err("INVALID_ENVELOPE: not a wrapped share (bad magic)".into());
+    }
+    if blob[4] != WRAP_VERSION {
+        return Err(format!("UNSUPPORTED_ENVELOPE_VERSION: {}", blob[4]));
+    }
+    if recipient_secret.len() != 32 {
+        return Err(format!(
+            "INVALID_RECIPIENT_KEY: expected 32-byte secret, got {} bytes",
+            recipient_secret.len()
+        ));
+    }
+    let secret = SecretScalar::<Secp256k1>::from_be_bytes(recipient_secret)
+        .map_err(|_| "INVALID_RECIPIENT_KEY: secret is not a valid scalar".to_string())?;
+
+    let eph_bytes = &blob[5..5 + PUBKEY_LEN];
+    let nonce = &blob[5 + PUBKEY_LEN..WRAP_HEADER_LEN];
+    let (header, ciphertext) = blob.split_at(WRAP_HEADER_LEN);
+
+    let eph_pub = Point::<Secp256k1>::from_bytes(eph_bytes)
+        .map_err(|_| "DECRYPT_AUTH_FAILED: corrupted ephemeral key".to_string())?;
+    if eph_pub.is_zero() {
+        return Err("DECRYPT_AUTH_FAILED: corrupted ephemeral key".into());
+    }
+    let shared = eph_pub * &secret;
+    let recipient_pub = Point::generator() * &secret;
+
+    let key = wrap_key(&shared, eph_bytes, recipient_pub.to_bytes(true).as_bytes())?;
+    let cipher = Aes256Gcm::new_from_slice(key.as_slice())
+        .map_err(|e| format!("init cipher: {e}"))?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: header })
+        .map_err(|_| {
+            "DECRYPT_AUTH_FAILED: wrong recipient key or corrupted envelope".to_string()
+        })?;
+
+    Ok(Zeroizing::new(plaintext))
+}
+
+fn wrap_key(
+    shared: &Point<Secp256k1>,
+    eph_pub: &[u8],
+    recipient_pub: &[u8],
+) -> Result<Zeroizing<[u8; 32]>, String> {
+    let ikm = Zeroizing::new(shared.to_bytes(true).as_bytes().to_vec());
+    let mut salt = Vec::with_capacity(2 * PUBKEY_LEN);
+    salt.extend_from_slice(eph_pub);
+    salt.extend_from_slice(recipient_pub);
+
+    let mut key = Zeroizing::new([0u8; 32]);
+    Hkdf::<Sha256>::new(Some(&salt), &ikm)
+        .expand(WRAP_INFO, &mut *key)
+        .map_err(|e| format!("derive wrap key: {e}"))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery staple";
+    /// Cheapest parameters `validate` takes, to keep the tests quick.
+    const FAST: KdfParams = KdfParams { m_cost: 64, t_cost: 1, p_cost: 1 };
+
+    /// A version 1 envelope written by another implementation (OpenSSL's
+    /// Argon2id and AES-256-GCM) under [`PASSPHRASE`] with `m_cost` 64,
+    /// `t_cost` 1, `p_cost` 1. Envelopes already at rest must keep opening.
+    const ENVELOPE_V1: &[u8] = include_bytes!("../testdata/share-envelope-v1.bin");
+
+    #[test]
+    fn a_stored_version_1_envelope_still_decrypts() {
+        let share = decrypt_share(ENVELOPE_V1, PASSPHRASE).unwrap();
+        assert_eq!(&share[..], b"guardian-wallet share envelope v1 fixture");
+    }
+
+    #[test]
+    fn an_envelope_decrypts_to_the_share() {
+        let envelope = encrypt_share(b"share", PASSPHRASE, FAST).unwrap();
+        assert!(envelope.starts_with(MAGIC));
+        assert_eq!(&decrypt_share(&envelope, PASSPHRASE).unwrap()[..], b"share");
+    }
+
+    #[test]
+    fn a_wrong_passphrase_fails_authentication() {
+        let err = decrypt_share(ENVELOPE_V1, "incorrect horse battery staple").unwrap_err();
+        assert!(err.starts_with("DECRYPT_AUTH_FAILED"), "{err}");
+    }
+
+    #[test]
+    fn a_tampered_header_or_ciphertext_fails_authentication() {
+        // m_cost (64 to 65), a salt byte, a nonce byte and the last
+        // ciphertext byte
+        for at in [6, 18, 18 + SALT_LEN, ENVELOPE_V1.len() - 1] {
+            let mut envelope = ENVELOPE_V1.to_vec();
+            envelope[at] ^= 1;
+            let err = decrypt_share(&envelope, PASSPHRASE).unwrap_err();
+            assert!(err.starts_with("DECRYPT_AUTH_FAILED"), "byte {at}: {err}");
+        }
+    }
+
+    #[test]
+    fn a_header_asking_for_more_memory_than_encrypt_writes_is_refused() {
+        let mut envelope = ENVELOPE_V1.to_vec();
+        envelope[6..10].copy_from_slice(&(MAX_M_COST_KIB + 1).to_le_bytes());
+        let err = decrypt_share(&envelope, PASSPHRASE).unwrap_err();
+        assert!(err.starts_with("INVALID_KDF_PARAMS: m_cost "), "{err}");
+
+        let too_costly = KdfParams { m_cost: MAX_M_COST_KIB + 1, ..FAST };
+        let err = encrypt_share(b"share", PASSPHRASE, too_costly).unwrap_err();
+        assert!(err.starts_with("INVALID_KDF_PARAMS: m_cost "), "{err}");
+    }
+
+    /// A recipient key pair: the 32-byte secret and the compressed public key.
+    fn recipient() -> (Vec<u8>, Vec<u8>) {
+        let secret = SecretScalar::<Secp256k1>::random(&mut MixedRng);
+        let public = Point::generator() * &secret;
+        (secret.as_ref().to_be_bytes().as_bytes().to_vec(), public.to_bytes(true).as_bytes().to_vec())
+    }
+
+    #[test]
+    fn a_wrapped_share_unwraps_with_the_recipient_key() {
+        let (secret, public) = recipient();
+        let blob = wrap_share_to_recipient(b"share", &public).unwrap();
+        assert!(blob.starts_with(WRAP_MAGIC));
+        assert_eq!(&unwrap_share(&blob, &secret).unwrap()[..], b"share");
+
+        let uncompressed = Point::<Secp256k1>::from_bytes(&public).unwrap().to_bytes(false).as_bytes().to_vec();
+        let blob = wrap_share_to_recipient(b"share", &uncompressed).unwrap();
+        assert_eq!(&unwrap_share(&blob, &secret).unwrap()[..], b"share");
+    }
+
+    #[test]
+    fn another_recipient_key_fails_authentication() {
+        let (_, public) = recipient();
+        let (other_secret, _) = recipient();
+        let blob = wrap_share_to_recipient(b"share", &public).unwrap();
+        let err = unwrap_share(&blob, &other_secret).unwrap_err();
+        assert!(err.starts_with("DECRYPT_AUTH_FAILED"), "{err}");
+    }
+
+    #[test]
+    fn a_tampered_wrapped_share_fails_authentication() {
+        let (secret, public) = recipient();
+        let blob = wrap_share_to_recipient(b"share", &public).unwrap();
+        // The ephemeral key's x, a nonce byte and the last ciphertext byte
+        for at in [PUBKEY_LEN, 5 + PUBKEY_LEN, blob.len() - 1] {
+            let mut tampered = blob.clone();
+            tampered[at] ^= 1;
+            let err = unwrap_share(&tampered, &secret).unwrap_err();
+            assert!(err.starts_with("DECRYPT_AUTH_FAILED"), "byte {at}: {err}");
+        }
+    }
+}
@@ -0,0 +1,559 @@
+//! Versioned header for serialised key material.
+//!
+//! Legacy blobs are bare serde_json (first byte `{`) and are implicitly
+//! secp256k1 / `SecurityLevel128`, unless their fields say otherwise (a
+//! point's curve name, the size of a Paillier modulus — see [`split`]).
+//! Blobs that need to say anything else carry an 8-byte header in front of
+//! the serde_json payload:
+//!
+//! ```text
+//! magic     3 bytes  "GWK"
+//! version   1 byte   1
+//! kind      1 byte   1 = CoreKeyShare, 2 = AuxInfo, 3 = KeyShare, 4 = PregeneratedPrimes,
+//!                    5 = FROST KeyShare
+//! curve     1 byte   0 = n/a (aux info, primes), 1 = secp256k1, 2 = secp256r1
+//! level     1 byte   0 = n/a (core shares), 1 = SecurityLevel128, 2 = SecurityLevel192
+//! encoding  1 byte   0 = serde_json, 1 = CBOR (binary)
+//! ```
+//!
+//! Version 2 adds a ninth byte of flags — bit 0: minted by a trusted dealer
+//! (`run_dkg_trusted_dealer`, dev builds only). It is written only when a
+//! flag is set, so ordinary blobs stay readable by version 1 peers.
+//!
+//! Writers emit the legacy form whenever it is unambiguous (secp256k1 or
+//! curve-independent, and SecurityLevel128) so already-deployed peers keep reading our output.
+//! Readers accept both forms. FROST shares are always headered, so an
+//! ECDSA blob can never be fed into a FROST session or vice versa.
+//!
+//! Binary (CBOR) payloads are about half the size of JSON ones and are
+//! written only on request — see `migrate`, and [`set_default_encoding`]
+//! for hosts that want every new blob in CBOR; they are always headered,
+//! since the legacy form is JSON by definition. [`deserialize`] reads
+//! either: a JSON payload never starts with a byte ≥ 0x80 and a
+//! CBOR-encoded struct always does, so the payload names its own encoding.
+//! Signing messages use the same rule (see `message_codec`).
+//!
+//! A blob may sit inside a `share_metadata` envelope (labels, environment
+//! tag); [`split`] looks through it, so every reader accepts both.
+//!
+//! This module has no wasm-bindgen dependency — native-gen compiles it too.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::share_metadata;
+
+const MAGIC: &[u8; 3] = b"GWK";
+pub const FORMAT_VERSION: u8 = 1;
+/// Header version carrying the flags byte.
+const FLAGS_VERSION: u8 = 2;
+/// Header versions [`split`] reads; 0 is the headerless legacy form.
+pub const SUPPORTED_VERSIONS: &[u8] = &[0, FORMAT_VERSION, FLAGS_VERSION];
+const HEADER_LEN: usize = 8;
+
+/// Flag bit: the blob was minted by a trusted dealer, not a DKG.
+pub const FLAG_DEALER: u8 = 1;
+/// Flag bit: the blob belongs to a single-party (`n = 1`) development key.
+pub const FLAG_SINGLE_PARTY: u8 = 2;
+const KNOWN_FLAGS: u8 = FLAG_DEALER | FLAG_SINGLE_PARTY;
+
+const ENCODING_JSON: u8 = 0;
+const ENCODING_CBOR: u8 = 1;
+
+/// Tag of the encoding new blobs and signing messages use when the caller
+/// names none.
+static DEFAULT_ENCODING: AtomicU8 = AtomicU8::new(ENCODING_JSON);
+
+/// Payload encoding.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Encoding {
+    #[default]
+    Json,
+    Cbor,
+}
+
+impl Encoding {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "json" => Ok(Encoding::Json),
+            "cbor" => Ok(Encoding::Cbor),
+            other => Err(format!("INVALID_ENCODING: expected \"json\" or \"cbor\", got {other:?}")),
+        }
+    }
+
+    /// Parse an optional encoding, defaulting to [`default_encoding`].
+    pub fn parse_opt(s: Option<&str>) -> Result<Self, String> {
+        s.map(Encoding::parse).transpose().map(|e| e.unwrap_or_else(default_encoding))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Encoding::Json => "json",
+            Encoding::Cbor => "cbor",
+        }
+    }
+
+    fn tag(self) -> u8 {
+        match self {
+            Encoding::Json => ENCODING_JSON,
+            Encoding::Cbor => ENCODING_CBOR,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self, String> {
+        match tag {
+            ENCODING_JSON => Ok(Encoding::Json),
+            ENCODING_CBOR => Ok(Encoding::Cbor),
+            other => Err(format!("UNSUPPORTED_SHARE_ENCODING: {other}")),
+        }
+    }
+
+    /// The encoding a payload is in, from its first byte.
+    pub fn sniff(payload: &[u8]) -> Self {
+        match payload.first() {
+            Some(&b) if b >= 0x80 => Encoding::Cbor,
+            _ => Encoding::Json,
+        }
+    }
+}
+
+/// Encoding of new blobs and signing messages when the caller names none;
+/// JSON unless [`set_default_encoding`] said otherwise.
+pub fn default_encoding() -> Encoding {
+    Encoding::from_tag(DEFAULT_ENCODING.load(Ordering::Relaxed)).unwrap_or_default()
+}
+
+/// Make `encoding` the default for blobs and signing sessions created from
+/// now on. CBOR blobs are always headered, so peers older than the binary
+/// encoding can't read them.
+pub fn set_default_encoding(encoding: Encoding) {
+    DEFAULT_ENCODING.store(encoding.tag(), Ordering::Relaxed);
+}
+
+/// What a blob contains.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlobKind {
+    CoreShare,
+    AuxInfo,
+    KeyShare,
+    Primes,
+    /// givre (FROST / threshold Schnorr) key share
+    FrostKeyShare,
+}
+
+impl BlobKind {
+    fn tag(self) -> u8 {
+        match self {
+            BlobKind::CoreShare => 1,
+            BlobKind::AuxInfo => 2,
+            BlobKind::KeyShare => 3,
+            BlobKind::Primes => 4,
+            BlobKind::FrostKeyShare => 5,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self, String> {
+        match tag {
+            1 => Ok(BlobKind::CoreShare),
+            2 => Ok(BlobKind::AuxInfo),
+            3 => Ok(BlobKind::KeyShare),
+            4 => Ok(BlobKind::Primes),
+            5 => Ok(BlobKind::FrostKeyShare),
+            other => Err(format!("INVALID_SHARE_HEADER: unknown blob kind {other}")),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BlobKind::CoreShare => "core_share",
+            BlobKind::AuxInfo => "aux_info",
+            BlobKind::KeyShare => "key_share",
+            BlobKind::Primes => "primes",
+            BlobKind::FrostKeyShare => "frost_key_share",
+        }
+    }
+
+    /// Whether headerless (legacy) blobs may hold this kind. Only the
+    /// original ECDSA blobs predate the header.
+    fn has_legacy_form(self) -> bool {
+        !matches!(self, BlobKind::FrostKeyShare)
+    }
+}
+
+/// Paillier / ZK security level (selects the cggmp24 `SecurityLevel` type).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Level {
+    #[default]
+    L128,
+    L192,
+}
+
+impl Level {
+    /// Parse `"128"` / `"192"`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "128" => Ok(Level::L128),
+            "192" => Ok(Level::L192),
+            other => Err(format!(
+                "INVALID_SECURITY_LEVEL: expected \"128\" or \"192\", got {other:?}"
+            )),
+        }
+    }
+
+    /// Parse an optional level, defaulting to 128.
+    pub fn parse_opt(s: Option<&str>) -> Result<Self, String> {
+        s.map(Level::parse).transpose().map(Option::unwrap_or_default)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::L128 => "128",
+            Level::L192 => "192",
+        }
+    }
+
+    fn tag(self) -> u8 {
+        match self {
+            Level::L128 => 1,
+            Level::L192 => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Option<Self>, String> {
+        match tag {
+            0 => Ok(None),
+            1 => Ok(Some(Level::L128)),
+            2 => Ok(Some(Level::L192)),
+            other => Err(format!("INVALID_SHARE_HEADER: unknown security level tag {other}")),
+        }
+    }
+}
+
+/// Curve a blob belongs to (selects the generic-ec curve type).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CurveId {
+    #[default]
+    Secp256k1,
+    /// NIST P-256 (WebAuthn / passkey-style signatures)
+    Secp256r1,
+}
+
+impl CurveId {
+    pub const ALL: [CurveId; 2] = [CurveId::Secp256k1, CurveId::Secp256r1];
+
+    /// Parse `"secp256k1"` / `"secp256r1"`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "secp256k1" => Ok(CurveId::Secp256k1),
+            "secp256r1" => Ok(CurveId::Secp256r1),
+            other => Err(format!(
+                "INVALID_CURVE: expected \"secp256k1\" or \"secp256r1\", got {other:?}"
+            )),
+        }
+    }
+
+    /// Parse an optional curve, defaulting to secp256k1.
+    pub fn parse_opt(s: Option<&str>) -> Result<Self, String> {
+        s.map(CurveId::parse).transpose().map(Option::unwrap_or_default)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CurveId::Secp256k1 => "secp256k1",
+            CurveId::Secp256r1 => "secp256r1",
+        }
+    }
+
+    /// Header byte for the curve; share commitments use it too.
+    pub fn tag(self) -> u8 {
+        match self {
+            CurveId::Secp256k1 => 1,
+            CurveId::Secp256r1 => 2,
+        }
+    }
+
+    pub fn from_tag(tag: u8) -> Result<Option<Self>, String> {
+        match tag {
+            0 => Ok(None),
+            1 => Ok(Some(CurveId::Secp256k1)),
+            2 => Ok(Some(CurveId::Secp256r1)),
+            other => Err(format!("INVALID_SHARE_HEADER: unknown curve tag {other}")),
+        }
+    }
+}
+
+/// Parsed blob header. Legacy blobs report `version: 0` and `kind: None`.
+#[derive(Clone, Copy, Debug)]
+pub struct BlobHeader {
+    pub version: u8,
+    pub kind: Option<BlobKind>,
+    /// `None` for blobs that don't depend on the curve
+    pub curve: Option<CurveId>,
+    /// `None` for blobs that don't depend on the security level
+    pub level: Option<Level>,
+    /// `FLAG_*` bits; always 0 below version 2
+    pub flags: u8,
+    pub encoding: Encoding,
+}
+
+impl BlobHeader {
+    /// Curve, falling back to secp256k1 for legacy / curve-less blobs.
+    pub fn curve_or_default(&self) -> CurveId {
+        self.curve.unwrap_or_default()
+    }
+
+    /// Security level, falling back to 128 for legacy / level-less blobs.
+    pub fn level_or_default(&self) -> Level {
+        self.level.unwrap_or_default()
+    }
+
+    /// Minted by a trusted dealer: fine for tests, never for real funds.
+    pub fn dealer_generated(&self) -> bool {
+        self.flags & FLAG_DEALER != 0
+    }
+
+    /// Part of a single-party key: one device holds the whole secret, so
+    /// it is for development only.
+    pub fn single_party(&self) -> bool {
+        self.flags & FLAG_SINGLE_PARTY != 0
+    }
+}
+
+/// Split a blob into its header and serde_json payload, looking through a
+/// `share_metadata` envelope. A legacy blob's header carries the curve and
+/// level its fields show, if any, so [`ensure_curve`] / [`ensure_level`]
+/// catch a mismatch before deserialisation does, opaquely.
+pub fn split(bytes: &[u8]) -> Result<(BlobHeader, &[u8]), String> {
+    if share_metadata::is_wrapped(bytes) {
+        let (_, blob) = share_metadata::unwrap(bytes)?;
+        return split(blob);
+    }
+    if bytes.len() >= 3 && &bytes[..3] == MAGIC {
+        let version = bytes.get(3).copied().unwrap_or(FORMAT_VERSION);
+        let header_len = match version {
+            FORMAT_VERSION => HEADER_LEN,
+            FLAGS_VERSION => HEADER_LEN + 1,
+            other => return Err(format!("UNSUPPORTED_SHARE_VERSION: {other}")),
+        };
+        if bytes.len() < header_len {
+            return Err("INVALID_SHARE_HEADER: truncated header".into());
+        }
+        let encoding = Encoding::from_tag(bytes[7])?;
+        let flags = if version == FLAGS_VERSION { bytes[8] } else { 0 };
+        if flags & !KNOWN_FLAGS != 0 {
+            return Err(format!("INVALID_SHARE_HEADER: unknown flags {flags:#04x}"));
+        }
+        let header = BlobHeader {
+            version,
+            kind: Some(BlobKind::from_tag(bytes[4])?),
+            curve: CurveId::from_tag(bytes[5])?,
+            level: Level::from_tag(bytes[6])?,
+            flags,
+            encoding,
+        };
+        return Ok((header, &bytes[header_len..]));
+    }
+
+    let (curve, level) = sniff_legacy(bytes);
+    let legacy = BlobHeader {
+        version: 0,
+        kind: None,
+        curve,
+        level,
+        flags: 0,
+        encoding: Encoding::Json,
+    };
+    Ok((legacy, bytes))
+}
+
+/// Whether this build reads `bytes`' header: a supported version and
+/// encoding, and known kind / curve / level tags. Says nothing about the
+/// payload.
+pub fn is_compatible(bytes: &[u8]) -> bool {
+    split(bytes).is_ok()
+}
+
+/// Split a blob and check it holds `expected` (legacy blobs are trusted to,
+/// unless `expected` has no legacy form).
+pub fn split_expect(bytes: &[u8], expected: BlobKind) -> Result<(BlobHeader, &[u8]), String> {
+    let (header, payload) = split(bytes)?;
+    match header.kind {
+        Some(kind) if kind != expected => Err(format!(
+            "WRONG_BLOB_KIND: expected {}, found {}",
+            expected.as_str(),
+            kind.as_str()
+        )),
+        None if !expected.has_legacy_form() => Err(format!(
+            "WRONG_BLOB_KIND: expected {}, found headerless ECDSA blob",
+            expected.as_str()
+        )),
+        _ => Ok((header, payload)),
+    }
+}
+
+/// Check a blob's level against the one the caller asked for.
+pub fn ensure_level(header: &BlobHeader, requested: Option<Level>) -> Result<Level, String> {
+    let found = header.level_or_default();
+    match requested {
+        Some(requested) if requested != found => Err(format!(
+            "SECURITY_LEVEL_MISMATCH: expected {}, found {}",
+            requested.as_str(),
+            found.as_str()
+        )),
+        _ => Ok(found),
+    }
+}
+
+/// Check a blob's curve against the one the caller asked for.
+pub fn ensure_curve(header: &BlobHeader, requested: Option<CurveId>) -> Result<CurveId, String> {
+    let found = header.curve_or_default();
+    match requested {
+        Some(requested) if requested != found => Err(format!(
+            "CURVE_MISMATCH: expected {}, found {}",
+            requested.as_str(),
+            found.as_str()
+        )),
+        _ => Ok(found),
+    }
+}
+
+/// Serialise `value` in [`default_encoding`], prefixed with a header unless
+/// the legacy (headerless) form already implies the same parameters.
+pub fn serialize<T: Serialize>(
+    kind: BlobKind,
+    curve: Option<CurveId>,
+    level: Option<Level>,
+    value: &T,
+) -> Result<Vec<u8>, String> {
+    serialize_flagged(kind, curve, level, 0, value)
+}
+
+/// [`serialize`] with `FLAG_*` bits; any flag forces a version 2 header.
+pub fn serialize_flagged<T: Serialize>(
+    kind: BlobKind,
+    curve: Option<CurveId>,
+    level: Option<Level>,
+    flags: u8,
+    value: &T,
+) -> Result<Vec<u8>, String> {
+    serialize_encoded(kind, curve, level, flags, default_encoding(), value)
+}
+
+/// [`serialize_flagged`] in `encoding`; CBOR always gets a header.
+pub fn serialize_encoded<T: Serialize>(
+    kind: BlobKind,
+    curve: Option<CurveId>,
+    level: Option<Level>,
+    flags: u8,
+    encoding: Encoding,
+    value: &T,
+) -> Result<Vec<u8>, String> {
+    let legacy = flags == 0
+        && encoding == Encoding::Json
+        && kind.has_legacy_form()
+        && curve.unwrap_or_default() == CurveId::Secp256k1
+        && level.unwrap_or_default() == Level::L128;
+    if legacy {
+        return serde_json::to_vec(value).map_err(|e| format!("serialize {}: {e}", kind.as_str()));
+    }
+
+    let mut out = Vec::with_capacity(HEADER_LEN + 1 + 1024);
+    out.extend_from_slice(MAGIC);
+    out.push(if flags == 0 { FORMAT_VERSION } else { FLAGS_VERSION });
+    out.push(kind.tag());
+    out.push(curve.map_or(0, CurveId::tag));
+    out.push(level.map_or(0, Level::tag));
+    out.push(encoding.tag());
+    if flags != 0 {
+        out.push(flags);
+    }
+    match encoding {
+        Encoding::Json => serde_json::to_writer(&mut out, value).map_err(|e| e.to_string()),
+        Encoding::Cbor => ciborium::ser::into_writer(value, &mut out).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| format!("serialize {}: {e}", kind.as_str()))?;
+    Ok(out)
+}
+
+/// Deserialise a payload returned by [`split`], JSON or CBOR.
+pub fn deserialize<T: DeserializeOwned>(kind: BlobKind, payload: &[u8]) -> Result<T, String> {
+    match Encoding::sniff(payload) {
+        Encoding::Json => serde_json::from_slice(payload).map_err(|e| e.to_string()),
+        Encoding::Cbor => ciborium::de::from_reader(payload).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| format!("deserialize {}: {e}", kind.as_str()))
+}
+
+/// Run `$body` with `$L` bound to the cggmp24 security level type for `$level`.
+macro_rules! with_security_level {
+    ($level:expr, $L:ident => $body:expr) => {
+        match $level {
+            $crate::share_format::Level::L128 => {
+                type $L = cggmp24::security_level::SecurityLevel128;
+                $body
+            }
+            $crate::share_format::Level::L192 => {
+                type $L = cggmp24::security_level::SecurityLevel192;
+                $body
+            }
+        }
+    };
+}
+pub(crate) use with_security_level;
+
+/// Run `$body` with `$E` bound to the cggmp24 curve type for `$curve`.
+macro_rules! with_curve {
+    ($curve:expr, $E:ident => $body:expr) => {
+        match $curve {
+            $crate::share_format::CurveId::Secp256k1 => {
+                type $E = cggmp24::supported_curves::Secp256k1;
+                $body
+            }
+            $crate::share_format::CurveId::Secp256r1 => {
+                type $E = cggmp24::supported_curves::Secp256r1;
+                $body
+            }
+        }
+    };
+}
+pub(crate) use with_curve;
+
+/// Curve and level a legacy JSON blob shows in its fields, where it shows
+/// them: a point's `"curve"` name, and the size of the first Paillier
+/// modulus `"N"` (hex or decimal string). Only the first occurrence of each
+/// is read, without parsing the JSON; anything unrecognised stays `None`
+/// and the legacy defaults apply.
+fn sniff_legacy(payload: &[u8]) -> (Option<CurveId>, Option<Level>) {
+    let curve = string_field(payload, b"\"curve\":\"").and_then(|name| {
+        std::str::from_utf8(name).ok().and_then(|name| CurveId::parse(name).ok())
+    });
+    let level = string_field(payload, b"\"N\":\"").and_then(|digits| {
+        let bits = if digits.iter().all(u8::is_ascii_digit) {
+            // log2(10) ≈ 3.32
+            digits.len() as u64 * 332 / 100
+        } else if digits.iter().all(u8::is_ascii_hexdigit) {
+            digits.len() as u64 * 4
+        } else {
+            return None;
+        };
+        [Level::L128, Level::L192]
+            .into_iter()
+            .find(|&level| modulus_bits(level).abs_diff(bits) <= 16)
+    });
+    (curve, level)
+}
+
+/// The string value following the first `key` (`"name":"` included) in
+/// `payload`.
+fn string_field<'a>(payload: &'a [u8], key: &[u8]) -> Option<&'a [u8]> {
+    let start = payload.windows(key.len()).position(|window| window == key)? + key.len();
+    let len = payload[start..].iter().position(|&b| b == b'"')?;
+    Some(&payload[start..start + len])
+}
+
+/// Bits of a Paillier modulus at `level`.
+fn modulus_bits(level: Level) -> u64 {
+    with_security_level!(level, L => 2 * u64::from(<L as cggmp24::security_level::SecurityLevel>::RSA_PRIME_BITLEN))
+}
@@ -0,0 +1,174 @@
+//! Labels and an environment tag carried with a share.
+//!
+//! [`attach_metadata`] wraps any share blob (headered or legacy) in a thin
+//! envelope; the blob inside is untouched. Layout (integers
+//! little-endian, as in `backup`):
+//!
+//! ```text
+//! magic      4 bytes   "GWMD"
+//! version    1 byte    1
+//! meta_len   4 bytes
+//! metadata   meta_len bytes   JSON `ShareMetadata`
+//! blob       the rest         the share blob as it was
+//! ```
+//!
+//! Everything that reads blobs looks through the envelope (`share_format::split`
+//! skips it), so a wrapped share signs, combines and exports like the bare
+//! one, and its fingerprint doesn't change. Signing and combining also
+//! compare the share's `environment` with the one the host expects
+//! ([`set_expected_environment`]) and fail with `ENVIRONMENT_MISMATCH` on
+//! a difference: a staging share pasted into a prod signer is refused
+//! rather than used. Shares without an environment pass.
+//!
+//! A key generated with party labels (`run_keygen_for_parties` given
+//! indices other than `0..n`) carries them in every core share's
+//! `party_labels`, and signing names parties by label ([`keygen_indices`]).
+//! `attach_metadata` replaces the whole record, so keep them when
+//! relabelling such a share.
+//!
+//! The metadata is neither encrypted nor authenticated; it guards against
+//! mix-ups, not against someone editing it.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+
+const MAGIC: &[u8; 4] = b"GWMD";
+const VERSION: u8 = 1;
+/// Magic, version and the length field.
+const FIXED_LEN: usize = 4 + 1 + 4;
+
+/// Longest metadata accepted, in bytes of JSON.
+pub const MAX_METADATA_BYTES: usize = 4096;
+
+/// Environment signing and combining insist on; `None` checks nothing.
+static EXPECTED_ENVIRONMENT: RwLock<Option<String>> = RwLock::new(None);
+
+/// What a share is labelled with. Every field is optional.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[serde(default)]
+pub struct ShareMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub label: Option<String>,
+    /// e.g. `"prod"`, `"staging"`; checked against the expected environment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub environment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub wallet_id: Option<String>,
+    /// Unix seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub created_at: Option<u64>,
+    /// Label of each party of the key, by keygen index
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub party_labels: Option<Vec<u16>>,
+}
+
+/// Whether `bytes` starts like a metadata envelope.
+pub fn is_wrapped(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// `blob` wrapped with `metadata`, replacing any metadata it already has.
+/// Fails with `INVALID_METADATA` when the metadata is too large.
+pub fn attach_metadata(blob: &[u8], metadata: &ShareMetadata) -> Result<Vec<u8>, String> {
+    let (_, blob) = unwrap(blob)?;
+    let json = serde_json::to_vec(metadata).map_err(|e| format!("serialize metadata: {e}"))?;
+    if json.len() > MAX_METADATA_BYTES {
+        return Err(format!(
+            "INVALID_METADATA: {} bytes of metadata, at most {MAX_METADATA_BYTES}",
+            json.len()
+        ));
+    }
+    let mut out = Vec::with_capacity(FIXED_LEN + json.len() + blob.len());
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    out.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    out.extend_from_slice(&json);
+    out.extend_from_slice(blob);
+    Ok(out)
+}
+
+/// The metadata of a wrapped blob; `None` for a bare one.
+pub fn read_metadata(bytes: &[u8]) -> Result<Option<ShareMetadata>, String> {
+    unwrap(bytes).map(|(metadata, _)| metadata)
+}
+
+/// Split `bytes` into its metadata and the blob inside; a bare blob comes
+/// back as is. Fails with `INVALID_METADATA` for a damaged envelope.
+pub fn unwrap(bytes: &[u8]) -> Result<(Option<ShareMetadata>, &[u8]), String> {
+    if !is_wrapped(bytes) {
+        return Ok((None, bytes));
+    }
+    if bytes.len() < FIXED_LEN {
+        return Err("INVALID_METADATA: truncated envelope".into());
+    }
+    if bytes[4] != VERSION {
+        return Err(format!("INVALID_METADATA: unsupported envelope version {}", bytes[4]));
+    }
+    let len = u32::from_le_bytes(bytes[5..9].try_into().expect("4 bytes")) as usize;
+    let end = FIXED_LEN.saturating_add(len);
+    let Some(json) = bytes.get(FIXED_LEN..end) else {
+        return Err("INVALID_METADATA: truncated metadata".into());
+    };
+    let metadata = serde_json::from_slice(json).map_err(|e| format!("INVALID_METADATA: {e}"))?;
+    let blob = &bytes[end..];
+    // attach_metadata never nests envelopes; refusing them keeps readers flat
+    if is_wrapped(blob) {
+        return Err("INVALID_METADATA: nested envelope".into());
+    }
+    Ok((Some(metadata), blob))
+}
+
+/// The keygen indices of the parties `labels` names, under the party
+/// labels of the share `bytes`; a share without labels names its parties
+/// by index. Fails with `UNKNOWN_PARTY` for a label the key doesn't have.
+pub fn keygen_indices(bytes: &[u8], labels: &[u16]) -> Result<Vec<u16>, String> {
+    let Some(party_labels) = read_metadata(bytes)?.and_then(|metadata| metadata.party_labels) else {
+        return Ok(labels.to_vec());
+    };
+    labels
+        .iter()
+        .map(|label| {
+            party_labels
+                .iter()
+                .position(|known| known == label)
+                .and_then(|i| u16::try_from(i).ok())
+                .ok_or_else(|| format!("UNKNOWN_PARTY: party {label} is not one of this key's, {party_labels:?}"))
+        })
+        .collect()
+}
+
+/// Make signing and combining refuse shares tagged with any environment
+/// but `environment` (`None` turns the check off).
+pub fn set_expected_environment(environment: Option<String>) {
+    *EXPECTED_ENVIRONMENT.write().unwrap_or_else(|e| e.into_inner()) = environment;
+}
+
+pub fn expected_environment() -> Option<String> {
+    EXPECTED_ENVIRONMENT.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Fail with `ENVIRONMENT_MISMATCH` if `bytes` is tagged with an
+/// environment other than the expected one.
+pub fn check_environment(bytes: &[u8]) -> Result<(), String> {
+    let Some(expected) = expected_environment() else {
+        return Ok(());
+    };
+    let Some(found) = read_metadata(bytes)?.and_then(|metadata| metadata.environment) else {
+        return Ok(());
+    };
+    if found != expected {
+        return Err(format!(
+            "ENVIRONMENT_MISMATCH: share is tagged {found:?}, this signer expects {expected:?}"
+        ));
+    }
+    Ok(())
+}
@@ -1,31 +1,58 @@
-//! Per-party interactive signing state machine for CGGMP24.
+//! Per-party interactive signing sessions, ECDSA (CGGMP24) and FROST (see
+//! `frost`), sharing one wire envelope ([`WasmSignMessage`]).
 //!
 //! Each party holds one [`SignSession`] that wraps the unnameable
-//! `StateMachine` type behind a type-erased `DynSignSM` trait object.
-//! Sessions are stored in a thread-local `HashMap<String, SignSession>`.
-//!
-//! The WASM boundary exposes three functions:
-//! - `create_session`  → initialise state machine, return first messages
-//! - `process_round`   → feed incoming messages, drive until NeedsOneMoreMessage or Output
-//! - `destroy_session` → drop and reclaim memory
-//!
-//! WASM is single-threaded, so leaked heap pointers for `'static` storage
-//! are safe — `Drop` reclaims them in a defined order.
+//! `StateMachine` type behind a type-erased `DynSignSM` trait object, in a
+//! thread-local `HashMap<String, SignSession>`; a batch session is several
+//! of them driven in lockstep. WASM is single-threaded, so leaked heap
+//! pointers for `'static` storage are safe — `Drop` reclaims them in a
+//! defined order.
 
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
 use std::mem::ManuallyDrop;
+use std::sync::Arc;
 
-use generic_ec::Scalar;
-use rand::rngs::OsRng;
+use generic_ec::coords::HasAffineX;
+use generic_ec::{Curve, Point, Scalar};
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
 use round_based::state_machine::{ProceedResult, StateMachine};
 use round_based::{Incoming, MessageDestination, MessageType};
 use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use tsify::Tsify;
+use zeroize::Zeroize;
 
-use cggmp24::security_level::SecurityLevel128;
-use cggmp24::signing::PrehashedDataToSign;
-use cggmp24::supported_curves::Secp256k1;
+use cggmp24::key_share::AnyKeyShare;
+use cggmp24::security_level::SecurityLevel;
+use cggmp24::signing::{DataToSign, PrehashedDataToSign};
+use sha2::{Digest, Sha256};
 
+use crate::approval::{self, Answer, Approval, ApprovalRequest, Verdict};
+use crate::audit::{AuditRecord, AuditTrail};
+use crate::binding;
+use crate::clock;
+use crate::message_codec;
+use crate::config;
+use crate::drive_guard::YieldGuard;
+use crate::entropy;
+use crate::key_material;
+use crate::fingerprint;
+use crate::frost::{self, FrostRecipe};
+use crate::hex_codec;
+use crate::key_registry;
+use crate::limits::MessageLimits;
+use crate::message_auth::{AuthKey, Envelope};
+use crate::metrics::{self, PhaseTimes, RoundMetrics, SessionMetrics};
+use crate::party_limits;
+use crate::protocol_digest::{with_digest, ProtocolDigest};
+use crate::protocol_failure::{self, ProtocolFailure};
+use crate::relay::{RejectedMessage, Route, SeenMessages};
+use crate::share_cache::{self, ShareCache};
+use crate::share_format::{self, with_curve, with_security_level, BlobKind, CurveId, Encoding, Level};
+use crate::share_metadata;
+use crate::signature;
 use crate::types::{MpcMessage, MpcRecipient, SignatureResult};
 
 // ---------------------------------------------------------------------------
@@ -33,7 +60,7 @@ use crate::types::{MpcMessage, MpcRecipient, SignatureResult};
 // ---------------------------------------------------------------------------
 
 /// Result from driving the state machine one step.
-enum DriveOneResult {
+pub(crate) enum DriveOneResult {
     /// Protocol emitted an outgoing message.
     SendMsg(MpcMessage),
     /// Protocol needs one more incoming message before it can continue.
@@ -45,38 +72,69 @@ enum DriveOneResult {
 }
 
 /// Object-safe trait wrapping the unnameable `StateMachine` concrete type.
-trait DynSignSM {
+///
+/// Every method adds the time it spends to `times`.
+pub(crate) trait DynSignSM {
     /// Drive the state machine one step (call `proceed()`).
-    fn drive_one(&mut self, party_index: u16) -> Result<DriveOneResult, String>;
+    fn drive_one(&mut self, party_index: u16, times: &mut PhaseTimes) -> Result<DriveOneResult, String>;
+
+    /// Decode an incoming payload without delivering it: whether
+    /// `receive_msg` will get past decoding it.
+    fn check_payload(&self, payload: &[u8], times: &mut PhaseTimes) -> Result<(), String>;
 
     /// Feed a single incoming message from a remote party.
-    fn receive_msg(&mut self, sender: u16, msg_type: u8, payload: &[u8]) -> Result<(), String>;
+    fn receive_msg(
+        &mut self,
+        sender: u16,
+        msg_type: u8,
+        payload: &[u8],
+        times: &mut PhaseTimes,
+    ) -> Result<(), String>;
 }
 
 /// Wrapper that implements `DynSignSM` for a concrete signing `StateMachine`.
-struct SmWrapper<SM: StateMachine> {
+///
+/// `finish` turns the protocol output into the wire signature (and verifies
+/// it), which is the only protocol-specific step.
+pub(crate) struct SmWrapper<SM, F> {
     sm: SM,
+    /// Encoding of outgoing protocol messages
+    encoding: Encoding,
+    finish: F,
+}
+
+impl<SM, F> SmWrapper<SM, F>
+where
+    SM: StateMachine + 'static,
+    SM::Msg: Serialize + for<'de> Deserialize<'de> + Clone,
+    F: FnMut(SM::Output) -> Result<SignatureResult, String> + 'static,
+{
+    pub(crate) fn boxed(sm: SM, encoding: Encoding, finish: F) -> Box<dyn DynSignSM> {
+        Box::new(SmWrapper { sm, encoding, finish })
+    }
 }
 
-impl<SM> DynSignSM for SmWrapper<SM>
+impl<SM, F> DynSignSM for SmWrapper<SM, F>
 where
-    SM: StateMachine<Output = Result<cggmp24::signing::Signature<Secp256k1>, cggmp24::signing::SigningError>>,
+    SM: StateMachine,
     SM::Msg: Serialize + for<'de> Deserialize<'de> + Clone,
+    F: FnMut(SM::Output) -> Result<SignatureResult, String>,
 {
-    fn drive_one(&mut self, party_index: u16) -> Result<DriveOneResult, String> {
-        match self.sm.proceed() {
+    fn drive_one(&mut self, party_index: u16, times: &mut PhaseTimes) -> Result<DriveOneResult, String> {
+        let start = clock::now_ms();
+        let proceeded = self.sm.proceed();
+        let proceeded_at = clock::now_ms();
+        times.proceed_ms += proceeded_at - start;
+
+        match proceeded {
             ProceedResult::SendMsg(outgoing) => {
-                // Serialize the protocol message to JSON, then base64
-                let json_bytes = serde_json::to_vec(&outgoing.msg)
-                    .map_err(|e| format!("serialize outgoing msg: {e}"))?;
-                let payload = base64::engine::general_purpose::STANDARD.encode(&json_bytes);
+                let payload = message_codec::encode(self.encoding, &outgoing.msg)?;
 
                 let recipient = match outgoing.recipient {
-                    MessageDestination::AllParties => {
-                        MpcRecipient::Broadcast("all".into())
-                    }
-                    MessageDestination::OneParty(p) => MpcRecipient::Party(p),
+                    MessageDestination::AllParties => MpcRecipient::Broadcast,
+                    MessageDestination::OneParty(p) => MpcRecipient::Party { index: p },
                 };
+                times.serialize_ms += clock::now_ms() - proceeded_at;
 
                 Ok(DriveOneResult::SendMsg(MpcMessage {
                     sender: party_index,
@@ -86,32 +144,31 @@ where
             }
             ProceedResult::NeedsOneMoreMessage => Ok(DriveOneResult::NeedsInput),
             ProceedResult::Output(result) => {
-                // Output is Result<Signature<Secp256k1>, SigningError>
-                let sig = result.map_err(|e| format!("signing protocol error: {e:?}"))?;
-                // Normalize s to low-s form (required for Ethereum)
-                let sig = sig.normalize_s();
-                // Extract r, s as 32-byte big-endian arrays
-                let mut sig_bytes = vec![0u8; cggmp24::signing::Signature::<Secp256k1>::serialized_len()];
-                sig.write_to_slice(&mut sig_bytes);
-
-                Ok(DriveOneResult::Finished(SignatureResult {
-                    r: sig_bytes[..32].to_vec(),
-                    s: sig_bytes[32..].to_vec(),
-                }))
+                let sig = (self.finish)(result);
+                times.proceed_ms += clock::now_ms() - proceeded_at;
+                Ok(DriveOneResult::Finished(sig?))
             }
             ProceedResult::Yielded => Ok(DriveOneResult::Yielded),
             ProceedResult::Error(e) => Err(format!("protocol error: {e}")),
         }
     }
 
-    fn receive_msg(&mut self, sender: u16, msg_type: u8, payload: &[u8]) -> Result<(), String> {
-        use base64::Engine;
-        // payload is base64-encoded JSON of the protocol message
-        let json_bytes = base64::engine::general_purpose::STANDARD
-            .decode(payload)
-            .map_err(|e| format!("base64 decode incoming msg: {e}"))?;
-        let msg: SM::Msg = serde_json::from_slice(&json_bytes)
-            .map_err(|e| format!("deserialize incoming msg: {e}"))?;
+    fn check_payload(&self, payload: &[u8], times: &mut PhaseTimes) -> Result<(), String> {
+        let start = clock::now_ms();
+        let decoded = message_codec::decode::<SM::Msg>(payload).map(drop);
+        times.deserialize_ms += clock::now_ms() - start;
+        decoded
+    }
+
+    fn receive_msg(
+        &mut self,
+        sender: u16,
+        msg_type: u8,
+        payload: &[u8],
+        times: &mut PhaseTimes,
+    ) -> Result<(), String> {
+        let start = clock::now_ms();
+        let msg: SM::Msg = message_codec::decode(payload)?;
 
         let incoming = Incoming {
             id: 0, // ID is not used by the protocol implementation
@@ -124,9 +181,12 @@ where
             msg,
         };
 
-        self.sm
+        let delivered = self
+            .sm
             .received_msg(incoming)
-            .map_err(|_| "failed to deliver message to state machine".to_string())
+            .map_err(|_| "failed to deliver message to state machine".to_string());
+        times.deserialize_ms += clock::now_ms() - start;
+        delivered
     }
 }
 
@@ -134,6 +194,311 @@ where
 // Sign Session
 // ---------------------------------------------------------------------------
 
+/// A `Box::into_raw` allocation lent to the state machine as `'static`.
+///
+/// The state machine borrows key material for `'static`, so we leak it and
+/// keep this handle to reclaim it. Dropping the handle frees the allocation —
+/// callers must drop the state machine first.
+pub(crate) struct Leaked<T>(*mut T);
+
+impl<T> Leaked<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Leaked(Box::into_raw(Box::new(value)))
+    }
+
+    /// SAFETY: the returned reference must not outlive `self`.
+    pub(crate) unsafe fn as_static(&self) -> &'static T {
+        &*self.0
+    }
+
+    /// SAFETY: the returned reference must not outlive `self`, and must be
+    /// the only reference handed out.
+    #[allow(clippy::mut_from_ref)]
+    pub(crate) unsafe fn as_static_mut(&self) -> &'static mut T {
+        &mut *self.0
+    }
+}
+
+impl<T> Drop for Leaked<T> {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { drop(Box::from_raw(self.0)); }
+        }
+    }
+}
+
+/// Type-erased [`Leaked`] so one session struct covers every KeyShare type.
+pub(crate) trait LeakedAlloc {}
+impl<T> LeakedAlloc for Leaked<T> {}
+
+/// Signing protocol a session runs; sessions can't be driven by the other
+/// protocol's exports.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) enum Protocol {
+    Ecdsa,
+    Frost,
+}
+
+impl Protocol {
+    fn as_str(self) -> &'static str {
+        match self {
+            Protocol::Ecdsa => "ecdsa",
+            Protocol::Frost => "frost",
+        }
+    }
+
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "ecdsa" => Ok(Protocol::Ecdsa),
+            "frost" => Ok(Protocol::Frost),
+            other => Err(format!("INVALID_PROTOCOL: expected \"ecdsa\" or \"frost\", got {other:?}")),
+        }
+    }
+
+    /// What each of `signers` sends per relay round. cggmp24 presigning
+    /// sends a broadcast and a p2p message to every peer, then (reliable
+    /// broadcast only) a broadcast echoing a hash of the first round's
+    /// broadcasts, then two rounds of p2p messages; signing adds one
+    /// broadcast of the partial signatures. FROST broadcasts commitments,
+    /// then signature shares. A lone signer sends nothing.
+    fn info(self, signers: usize, reliable_broadcast: bool) -> SigningProtocolInfo {
+        let with_reliable_broadcast = self == Protocol::Ecdsa && reliable_broadcast;
+        let peers = u16::try_from(signers.saturating_sub(1)).unwrap_or(u16::MAX);
+        let rounds: Vec<(u16, u16)> = match self {
+            _ if peers == 0 => Vec::new(),
+            Protocol::Ecdsa => [(1, peers)]
+                .into_iter()
+                .chain(with_reliable_broadcast.then_some((1, 0)))
+                .chain([(0, peers), (0, peers), (1, 0)])
+                .collect(),
+            Protocol::Frost => vec![(1, 0), (1, 0)],
+        };
+        SigningProtocolInfo {
+            rounds: rounds.len() as u16,
+            broadcasts_per_round: rounds.iter().map(|&(broadcasts, _)| broadcasts).collect(),
+            p2p_per_round: rounds.iter().map(|&(_, p2p)| p2p).collect(),
+            with_reliable_broadcast,
+        }
+    }
+}
+
+/// The message pattern of a signing session, for relays that size their
+/// plumbing per round. Counts are per signer: what each one sends in that
+/// round.
+#[derive(Serialize, Deserialize, Tsify, Clone, Debug, Default, PartialEq)]
+pub struct SigningProtocolInfo {
+    /// Relay rounds that carry messages: `create_session` sends the first,
+    /// then each `process_round` the next; the call taking the last
+    /// round's messages completes the session
+    pub rounds: u16,
+    /// Broadcast messages each signer sends, per round
+    pub broadcasts_per_round: Vec<u16>,
+    /// Point-to-point messages each signer sends, per round (one per peer)
+    pub p2p_per_round: Vec<u16>,
+    /// The extra echo round of reliable broadcast is in `rounds`
+    pub with_reliable_broadcast: bool,
+}
+
+/// Parameters of [`signing_protocol_info`].
+#[derive(Serialize, Deserialize, Default, Tsify)]
+#[serde(default)]
+pub struct SigningProtocolOptions {
+    /// Signers in the session (`parties_at_keygen.len()`)
+    pub signers: u16,
+    /// `"ecdsa"` (default) or `"frost"`
+    pub protocol: Option<String>,
+    /// As [`SignOptions::reliable_broadcast`]; ignored for FROST
+    pub reliable_broadcast: Option<bool>,
+}
+
+/// The round and message counts of a session with these parameters,
+/// before creating one. `CreateSessionResult::protocol_info` carries the
+/// same for a session that exists.
+pub fn signing_protocol_info(options: &SigningProtocolOptions) -> Result<SigningProtocolInfo, String> {
+    let protocol = options.protocol.as_deref().map(Protocol::parse).transpose()?.unwrap_or(Protocol::Ecdsa);
+    Ok(protocol.info(usize::from(options.signers), options.reliable_broadcast.unwrap_or(true)))
+}
+
+// ---------------------------------------------------------------------------
+// Session recipes (what a session is rebuilt from on import)
+// ---------------------------------------------------------------------------
+
+/// Everything needed to rebuild a session's state machine from scratch.
+///
+/// Holds the key share and the rng seed, so it is as secret as the share
+/// itself; secret fields are zeroized on drop.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "protocol", rename_all = "snake_case")]
+pub(crate) enum SessionRecipe {
+    Ecdsa(EcdsaRecipe),
+    Frost(FrostRecipe),
+}
+
+impl SessionRecipe {
+    fn protocol(&self) -> Protocol {
+        match self {
+            SessionRecipe::Ecdsa(_) => Protocol::Ecdsa,
+            SessionRecipe::Frost(_) => Protocol::Frost,
+        }
+    }
+
+    /// The shared secret of `SignOptions::auth_secret`, if any.
+    fn auth_secret(&self) -> Option<&str> {
+        match self {
+            SessionRecipe::Ecdsa(r) => r.options.auth_secret.as_deref(),
+            SessionRecipe::Frost(r) => r.options.auth_secret.as_deref(),
+        }
+    }
+
+    /// Bytes of serialised key material the session is built from.
+    fn key_material_len(&self) -> usize {
+        match self {
+            SessionRecipe::Ecdsa(r) => r.core_share.len() + r.aux_info.len(),
+            SessionRecipe::Frost(r) => r.key_share_len(),
+        }
+    }
+
+    /// Replace the key share and aux info with the id of the registered key
+    /// they are (see [`export_session`]). FROST keys aren't registered, so
+    /// their recipes can't drop them.
+    fn strip_key_material(&mut self) -> Result<(), String> {
+        match self {
+            SessionRecipe::Ecdsa(r) => {
+                r.key_id = Some(fingerprint::share_fingerprint(&r.core_share));
+                r.core_share.zeroize();
+                r.aux_info.zeroize();
+                Ok(())
+            }
+            SessionRecipe::Frost(_) => {
+                Err("UNSUPPORTED: FROST sessions are exported with their key material only".into())
+            }
+        }
+    }
+
+    /// Undo [`Self::strip_key_material`] from the key registry. Fails with
+    /// `KEY_NOT_FOUND` unless the key is registered.
+    fn restore_key_material(&mut self) -> Result<(), String> {
+        if let SessionRecipe::Ecdsa(r) = self {
+            if let Some(key_id) = r.key_id.take() {
+                let (core_share, aux_info) = key_registry::key_material(&key_id)?;
+                r.core_share = core_share.to_vec();
+                r.aux_info = aux_info.to_vec();
+            }
+        }
+        Ok(())
+    }
+
+    /// Zeroize the secrets now; the recipe can't rebuild a session after.
+    fn wipe(&mut self) {
+        match self {
+            SessionRecipe::Ecdsa(r) => r.wipe(),
+            SessionRecipe::Frost(r) => r.wipe(),
+        }
+    }
+
+    /// Encoding of the session's protocol messages. ECDSA sessions fix it
+    /// at creation (see [`with_resolved_encoding`]); FROST ones speak JSON.
+    fn encoding(&self) -> Result<Encoding, String> {
+        match self {
+            SessionRecipe::Ecdsa(r) => Encoding::parse_opt(r.options.encoding.as_deref()),
+            SessionRecipe::Frost(_) => Ok(Encoding::Json),
+        }
+    }
+
+    /// Round and message counts of the session (see [`Protocol::info`]).
+    fn protocol_info(&self) -> SigningProtocolInfo {
+        match self {
+            SessionRecipe::Ecdsa(r) => Protocol::Ecdsa.info(r.parties_at_keygen.len(), r.options.reliable_broadcast()),
+            SessionRecipe::Frost(r) => Protocol::Frost.info(r.signer_count(), false),
+        }
+    }
+
+    fn digest(&self) -> Result<ProtocolDigest, String> {
+        match self {
+            SessionRecipe::Ecdsa(r) => ProtocolDigest::parse_opt(r.options.digest.as_deref()),
+            SessionRecipe::Frost(_) => Ok(ProtocolDigest::Sha256),
+        }
+    }
+
+    fn on_misrouted(&self) -> OnMisrouted {
+        match self {
+            SessionRecipe::Ecdsa(r) => r.options.on_misrouted,
+            SessionRecipe::Frost(r) => r.options.on_misrouted,
+        }
+    }
+
+    fn max_outgoing_bytes(&self) -> Option<u32> {
+        match self {
+            SessionRecipe::Ecdsa(r) => r.options.max_outgoing_bytes,
+            // FROST messages are a few hundred bytes
+            SessionRecipe::Frost(_) => None,
+        }
+    }
+
+    fn limits(&self) -> MessageLimits {
+        let overrides = match self {
+            SessionRecipe::Ecdsa(r) => &r.options.limits,
+            SessionRecipe::Frost(r) => &r.options.limits,
+        };
+        default_limits().with_overrides(overrides)
+    }
+}
+
+/// Inputs of an ECDSA session (see [`create_session`]).
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct EcdsaRecipe {
+    core_share: Vec<u8>,
+    aux_info: Vec<u8>,
+    /// For a raw message, its SHA-256
+    message_hash: Vec<u8>,
+    /// The raw message, when the session signs one ([`SignData::Message`])
+    #[serde(default)]
+    message: Option<Vec<u8>>,
+    party_index: u16,
+    parties_at_keygen: Vec<u16>,
+    eid: Vec<u8>,
+    options: SignOptions,
+    /// Seed of the session's rng
+    seed: [u8; 32],
+    /// Unix seconds, for the audit record
+    #[serde(default)]
+    created_at: u64,
+    /// Set by an export without key material, whose `core_share` and
+    /// `aux_info` are empty: the registered key to take them from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key_id: Option<String>,
+}
+
+impl EcdsaRecipe {
+    fn wipe(&mut self) {
+        self.core_share.zeroize();
+        // The Paillier primes
+        self.aux_info.zeroize();
+        self.seed.zeroize();
+        self.options.auth_secret.zeroize();
+    }
+}
+
+impl Drop for EcdsaRecipe {
+    fn drop(&mut self) {
+        self.wipe();
+    }
+}
+
+/// Fresh seed for a new session's rng (OS entropy, plus the host's extra
+/// entropy if it supplied any).
+pub(crate) fn fresh_seed() -> Result<[u8; 32], String> {
+    let mut seed = [0u8; 32];
+    entropy::fill_mixed(&mut seed)?;
+    Ok(seed)
+}
+
+/// The session rng: deterministic in `seed` so an imported session can
+/// retrace the original one.
+pub(crate) fn session_rng(seed: [u8; 32]) -> ChaCha20Rng {
+    ChaCha20Rng::from_seed(seed)
+}
+
 /// A signing session owning the type-erased state machine and leaked memory.
 pub struct SignSession {
     /// Type-erased state machine (dropped first via ManuallyDrop)
@@ -144,32 +509,272 @@ pub struct SignSession {
     /// Used to map between keygen indices (wire format) and 0-based
     /// positions (what the round_based state machine expects).
     parties_at_keygen: Vec<u16>,
-    /// Leaked KeyShare pointer (reclaimed on Drop)
-    _key_share_ptr: *mut cggmp24::KeyShare<Secp256k1, SecurityLevel128>,
-    /// Leaked OsRng pointer (reclaimed on Drop)
-    _rng_ptr: *mut OsRng,
-    /// Leaked PrehashedDataToSign pointer (reclaimed on Drop)
-    _prehashed_ptr: *mut PrehashedDataToSign<Secp256k1>,
+    /// Leaked KeyShare, eid, parties, rng and prehashed message — all
+    /// borrowed by `sm` and reclaimed on Drop after it.
+    _leaked: Vec<Box<dyn LeakedAlloc>>,
+    /// Protocol the state machine runs
+    protocol: Protocol,
+    /// Its rounds and message counts for this signer set
+    protocol_info: SigningProtocolInfo,
+    /// Session binding tag (see `binding`), stamped on outgoing messages
+    /// and required on incoming ones
+    binding: String,
+    /// Encoding of protocol messages, both ways
+    encoding: Encoding,
+    /// Digest the protocol hashes with, tagged on outgoing messages and
+    /// required on incoming ones
+    digest: ProtocolDigest,
+    /// MAC key tagging outgoing messages and checking incoming ones, when
+    /// the session was created with an `auth_secret` (see `message_auth`)
+    auth_key: Option<AuthKey>,
+    /// Relay round: 0 for `create_session`'s messages, then one more per
+    /// `process_round` call. Stamped on every outgoing message.
+    round: u16,
+    /// Messages queued so far; one from a round its sender has moved past,
+    /// or a second copy of one, is stale
+    seen: SeenMessages,
+    /// Caps on incoming messages, and how many were accepted so far
+    limits: MessageLimits,
+    received: u32,
+    /// What to do with messages not meant for this party, and how many
+    /// were dropped and not yet reported
+    on_misrouted: OnMisrouted,
+    misrouted_skipped: u32,
+    /// Cap on the outgoing bytes of one result, and the messages held back
+    /// by it, oldest first
+    max_outgoing_bytes: Option<u32>,
+    held: VecDeque<WasmSignMessage>,
+    /// What the state machine was built from, and every incoming batch it
+    /// accepted since — together they are the session export
+    recipe: SessionRecipe,
+    log: Vec<Vec<WasmSignMessage>>,
+    /// Fed messages not yet delivered, with their sender's position in the
+    /// signing group
+    pending: VecDeque<(u16, WasmSignMessage)>,
+    /// `feed_message` opened a relay round that no poll has closed yet:
+    /// further fed messages join its log entry
+    feeding: bool,
+    /// The state machine's last step asked for a message
+    awaiting_input: bool,
+    /// Timings of the call in progress, and the session totals
+    times: PhaseTimes,
+    metrics: SessionMetrics,
+    /// A peer's `abort` message arrived; the session is dropped after the call
+    aborted: bool,
+    /// Key material counted in `metrics::memory_stats` while the session lives
+    pinned_bytes: usize,
+    /// `proceed()` steps taken, and when the last one ran (`clock::now_ms`)
+    steps: u64,
+    last_step_ms: f64,
+    /// `Yielded` steps since the last step that did anything else
+    yields: YieldGuard,
+    /// Why the session failed; `sm` and the key material are gone once set
+    failed: Option<String>,
+    /// What produced the signature (ECDSA sessions)
+    audit: Option<AuditTrail>,
+    /// Hash of the (eid, public key, party) this ECDSA session holds
+    /// against reuse, until it completes, fails or is dropped
+    eid_reservation: Option<[u8; 32]>,
+    /// The host's approval callback, and the signature it withholds until
+    /// approved (`SignOptions::approval_callback`)
+    approval: Option<Approval>,
     /// Signature output (set when protocol completes)
     pub signature: Option<SignatureResult>,
 }
 
-impl Drop for SignSession {
-    fn drop(&mut self) {
-        // 1. Drop the state machine first (it references the leaked data)
+impl SignSession {
+    pub(crate) fn new(
+        sm: Box<dyn DynSignSM>,
+        party_index: u16,
+        parties_at_keygen: Vec<u16>,
+        leaked: Vec<Box<dyn LeakedAlloc>>,
+        recipe: SessionRecipe,
+        binding: String,
+    ) -> Result<Self, String> {
+        let auth_key = recipe
+            .auth_secret()
+            .map(|secret| AuthKey::derive(secret, &binding))
+            .transpose()?;
+        let encoding = recipe.encoding()?;
+        let digest = recipe.digest()?;
+        let pinned_bytes = recipe.key_material_len();
+        metrics::session_opened(pinned_bytes);
+        Ok(SignSession {
+            sm: ManuallyDrop::new(sm),
+            party_index,
+            parties_at_keygen,
+            _leaked: leaked,
+            protocol: recipe.protocol(),
+            protocol_info: recipe.protocol_info(),
+            binding,
+            encoding,
+            digest,
+            auth_key,
+            round: 0,
+            seen: SeenMessages::default(),
+            limits: recipe.limits(),
+            received: 0,
+            on_misrouted: recipe.on_misrouted(),
+            misrouted_skipped: 0,
+            max_outgoing_bytes: recipe.max_outgoing_bytes(),
+            held: VecDeque::new(),
+            recipe,
+            log: Vec::new(),
+            pending: VecDeque::new(),
+            feeding: false,
+            awaiting_input: false,
+            times: PhaseTimes::default(),
+            metrics: SessionMetrics::default(),
+            aborted: false,
+            pinned_bytes,
+            steps: 0,
+            last_step_ms: clock::now_ms(),
+            yields: YieldGuard::default(),
+            failed: None,
+            audit: None,
+            eid_reservation: None,
+            approval: None,
+            signature: None,
+        })
+    }
+
+    /// Hold `reservation` until the session completes, fails or is dropped.
+    fn reserve_eid(&mut self, reservation: [u8; 32]) {
+        self.release_eid();
+        self.eid_reservation = Some(reservation);
+        metrics::eid_reserved();
+    }
+
+    fn release_eid(&mut self) {
+        if self.eid_reservation.take().is_some() {
+            metrics::eid_released();
+        }
+    }
+
+    /// Mark the session failed and free what it holds now rather than at
+    /// destroy: the state machine, the leaked key share and the recipe's
+    /// secrets.
+    fn fail(&mut self, reason: &str) {
+        if self.failed.is_some() {
+            return;
+        }
+        // SAFETY: `sm` is never touched again once `failed` is set (see
+        // `ensure_usable` and `Drop`); it goes before the data it borrows.
         unsafe {
             ManuallyDrop::drop(&mut self.sm);
         }
-        // 2. Reclaim leaked memory
-        if !self._key_share_ptr.is_null() {
-            unsafe { drop(Box::from_raw(self._key_share_ptr)); }
+        self._leaked.clear();
+        self.recipe.wipe();
+        self.log.clear();
+        self.pending.clear();
+        metrics::release_pinned(self.pinned_bytes);
+        self.pinned_bytes = 0;
+        self.release_eid();
+        self.failed = Some(reason.to_string());
+    }
+
+    /// `SESSION_FAILED` if an earlier call failed the session.
+    fn ensure_usable(&self) -> Result<(), String> {
+        match &self.failed {
+            Some(reason) => Err(format!("SESSION_FAILED: {reason}")),
+            None => Ok(()),
+        }
+    }
+
+    /// The protocol is done: the signature is out, or withheld for approval.
+    fn finished(&self) -> bool {
+        self.signature.is_some() || self.approval.as_ref().is_some_and(Approval::is_pending)
+    }
+
+    /// Release a finished protocol's signature, the audit record with it.
+    fn release_signature(&mut self, sig: SignatureResult) {
+        if let Some(audit) = &mut self.audit {
+            audit.complete(&[sig.r.as_slice(), sig.s.as_slice()].concat());
+        }
+        self.signature = Some(sig);
+    }
+
+    /// What `approval_callback` is asked about.
+    fn approval_request(&self) -> ApprovalRequest {
+        let (message_hash, created_at) = match &self.recipe {
+            SessionRecipe::Ecdsa(r) => (r.message_hash.as_slice(), r.created_at),
+            SessionRecipe::Frost(_) => (&[][..], 0),
+        };
+        ApprovalRequest {
+            message_hash_hex: hex_codec::encode(message_hash),
+            parties: self.parties_at_keygen.clone(),
+            party_index: self.party_index,
+            created_at,
+        }
+    }
+
+    /// The approval callback and what to ask it, if the session withholds
+    /// a signature it hasn't asked about yet. Ask with [`approval::ask`]
+    /// once the session is out of any store borrow.
+    fn approval_question(&mut self) -> Option<(js_sys::Function, ApprovalRequest)> {
+        let callback = self.approval.as_mut()?.question()?;
+        Some((callback, self.approval_request()))
+    }
+
+    /// Act on the approval callback's `answer`, failing the session if it
+    /// rejected the signature.
+    fn answer_approval(&mut self, answer: Answer) -> Result<(), String> {
+        let verdict = self
+            .approval
+            .as_mut()
+            .map_or(Verdict::Pending, |approval| approval.answer(answer));
+        failing(self, |session| apply_verdict(session, verdict))
+    }
+
+    /// Where the session stands after a call.
+    fn status(&self) -> SessionStatus {
+        if self.failed.is_some() {
+            SessionStatus::Failed
+        } else if self.signature.is_some() {
+            SessionStatus::Complete
+        } else if self.awaiting_input && self.pending.is_empty() {
+            SessionStatus::Waiting
+        } else {
+            SessionStatus::Computing
+        }
+    }
+
+    /// Estimated fraction of the protocol done: steps taken against the
+    /// last finished session of the same protocol, or relay rounds against
+    /// [`SigningProtocolInfo::rounds`] before one has finished. 1 once
+    /// complete, never 1 before.
+    fn progress(&self) -> f32 {
+        if self.signature.is_some() {
+            return 1.0;
         }
-        if !self._rng_ptr.is_null() {
-            unsafe { drop(Box::from_raw(self._rng_ptr)); }
+        let estimate = match TYPICAL_STEPS.with(|typical| typical.borrow().get(&self.protocol).copied()) {
+            Some(total) => self.steps as f32 / total.max(1) as f32,
+            None => f32::from(self.round) / f32::from(self.protocol_info.rounds.max(1)),
+        };
+        estimate.min(0.99)
+    }
+
+    /// Tag `msg` for the peers, when the session authenticates messages.
+    fn stamp_auth(&self, mut msg: WasmSignMessage) -> WasmSignMessage {
+        if let Some(key) = &self.auth_key {
+            msg.auth_tag = Some(key.tag(&msg.auth_envelope()));
         }
-        if !self._prehashed_ptr.is_null() {
-            unsafe { drop(Box::from_raw(self._prehashed_ptr)); }
+        msg
+    }
+}
+
+impl Drop for SignSession {
+    fn drop(&mut self) {
+        // 1. Drop the state machine first (it references the leaked data),
+        //    unless `fail` already did
+        if self.failed.is_none() {
+            unsafe {
+                ManuallyDrop::drop(&mut self.sm);
+            }
         }
+        // 2. Leaked memory is reclaimed when `_leaked` drops right after
+        metrics::session_closed(self.pinned_bytes);
+        self.release_eid();
     }
 }
 
@@ -182,308 +787,2267 @@ unsafe impl Send for SignSession {}
 
 thread_local! {
     static SESSIONS: RefCell<HashMap<String, SignSession>> = RefCell::new(HashMap::new());
+    /// Batch sessions: one member session per hash, in `message_index` order
+    static BATCHES: RefCell<HashMap<String, Vec<SignSession>>> = RefCell::new(HashMap::new());
+    static DEFAULT_LIMITS: Cell<MessageLimits> = Cell::new(MessageLimits::default());
+    static SHARE_CACHE: RefCell<ShareCache> = RefCell::new(ShareCache::new(share_cache::DEFAULT_CAPACITY));
+    /// Eids of the last [`RECENT_EIDS_KEPT`] sessions created, and how many
+    /// sessions reused one of them
+    static RECENT_EIDS: RefCell<VecDeque<Vec<u8>>> = RefCell::new(VecDeque::new());
+    static EID_REUSES: Cell<u32> = Cell::new(0);
+    /// Steps the last finished session of each protocol took, for `progress`
+    static TYPICAL_STEPS: RefCell<HashMap<Protocol, u64>> = RefCell::new(HashMap::new());
+}
+
+const RECENT_EIDS_KEPT: usize = 1024;
+
+/// Sessions created with an eid an earlier session already used; signing
+/// must run under a fresh eid every time.
+pub fn eid_reuse_count() -> u32 {
+    EID_REUSES.with(Cell::get)
+}
+
+/// Remember a new session's eid, counting it if it was seen before.
+fn note_eid(eid: &[u8]) {
+    RECENT_EIDS.with(|recent| {
+        let mut recent = recent.borrow_mut();
+        if recent.iter().any(|seen| seen == eid) {
+            EID_REUSES.with(|reuses| reuses.set(reuses.get().saturating_add(1)));
+            return;
+        }
+        if recent.len() == RECENT_EIDS_KEPT {
+            recent.pop_front();
+        }
+        recent.push_back(eid.to_vec());
+    });
+}
+
+/// What an ECDSA session reserves against reuse until it completes, fails
+/// or is destroyed. The party is part of it so a host running several
+/// parties of one signing in the same module isn't refused.
+fn eid_reservation(eid: &[u8], public_key: &[u8], party_index: u16) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"guardian-wallet eid reservation v1");
+    hasher.update((eid.len() as u64).to_be_bytes());
+    hasher.update(eid);
+    hasher.update(public_key);
+    hasher.update(party_index.to_be_bytes());
+    hasher.finalize().into()
+}
+
+/// Fail with `EID_REUSE` if a live session holds `reservation`.
+fn check_eid_reuse(reservation: &[u8; 32]) -> Result<(), String> {
+    let holds = |session: &SignSession| session.eid_reservation.as_ref() == Some(reservation);
+    let held = SESSIONS.with(|sessions| sessions.borrow().values().any(holds))
+        || BATCHES.with(|batches| batches.borrow().values().flatten().any(holds));
+    if held {
+        return Err("EID_REUSE: a live signing session already signs under this eid with this key share; \
+                    use a fresh eid per signing (or set allow_eid_reuse)"
+            .into());
+    }
+    Ok(())
+}
+
+/// Message limits for sessions created from now on.
+pub fn set_default_limits(limits: MessageLimits) {
+    DEFAULT_LIMITS.with(|l| l.set(limits));
+}
+
+pub fn default_limits() -> MessageLimits {
+    DEFAULT_LIMITS.with(Cell::get)
+}
+
+/// The combined key share of a core share / aux info pair (and their
+/// payloads), from the share cache or freshly combined into it; the flag
+/// is true on a cache hit, which skips deserialising the blobs.
+fn cached_key_share<E: Curve, L: SecurityLevel>(
+    core_share: &[u8],
+    aux_info: &[u8],
+    core_payload: &[u8],
+    aux_payload: &[u8],
+) -> Result<(Arc<cggmp24::KeyShare<E, L>>, bool), String> {
+    let key = share_cache::cache_key(core_share, aux_info);
+    SHARE_CACHE.with(|cache| {
+        cache.borrow_mut().get_or_build(key, || {
+            let core_share: cggmp24::IncompleteKeyShare<E> =
+                share_format::deserialize(BlobKind::CoreShare, core_payload)?;
+            let aux_info: cggmp24::key_share::AuxInfo<L> =
+                share_format::deserialize(BlobKind::AuxInfo, aux_payload)?;
+            cggmp24::KeyShare::from_parts((core_share, aux_info))
+                .map_err(|e| format!("combine key share: {e}"))
+        })
+    })
+}
+
+/// Combine a core share / aux info pair into the share cache without
+/// starting a session; fails where creating a session would on the pair.
+pub(crate) fn warm_share_cache(core_share: &[u8], aux_info: &[u8]) -> Result<(), String> {
+    let (core_header, core_payload) = share_format::split_expect(core_share, BlobKind::CoreShare)?;
+    let (aux_header, aux_payload) = share_format::split_expect(aux_info, BlobKind::AuxInfo)?;
+    let curve = share_format::ensure_curve(&core_header, None)?;
+    let level = share_format::ensure_level(&aux_header, None)?;
+    with_curve!(curve, E => with_security_level!(level, L => {
+        cached_key_share::<E, L>(core_share, aux_info, core_payload, aux_payload).map(|_| ())
+    }))
+}
+
+/// Number of combined key shares the share cache keeps (0 disables it).
+pub fn set_share_cache_capacity(capacity: usize) {
+    SHARE_CACHE.with(|cache| cache.borrow_mut().set_capacity(capacity));
+}
+
+pub fn share_cache_capacity() -> usize {
+    SHARE_CACHE.with(|cache| cache.borrow().capacity())
+}
+
+/// Drop the cached key share for this core share / aux info pair; true if
+/// one was cached. Live sessions using it are unaffected.
+pub fn evict_cached_share(core_share: &[u8], aux_info: &[u8]) -> bool {
+    let key = share_cache::cache_key(core_share, aux_info);
+    SHARE_CACHE.with(|cache| cache.borrow_mut().remove(&key))
 }
 
 // ---------------------------------------------------------------------------
 // Message type for WASM boundary
 // ---------------------------------------------------------------------------
 
-#[derive(Serialize, Deserialize)]
+/// Envelope version this build speaks. Bumped on incompatible changes;
+/// messages carrying any other version are refused.
+pub const MESSAGE_VERSION: u8 = 1;
+
+fn message_version() -> u8 {
+    MESSAGE_VERSION
+}
+
+/// What a wire message carries.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default, Tsify)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKind {
+    /// A protocol message for the state machine
+    #[default]
+    Protocol,
+    /// The sender abandoned the session; `payload` is its reason
+    Abort,
+}
+
+/// Longest abort reason sent to peers, in bytes; longer ones are cut.
+pub const MAX_ABORT_REASON_BYTES: usize = 256;
+
+/// Most hashes one batch session signs.
+pub const MAX_BATCH_SIZE: usize = 256;
+
+/// `version`, `round` and `kind` default when absent, so peers predating
+/// them still interoperate.
+#[derive(Serialize, Deserialize, Clone, Tsify)]
 pub struct WasmSignMessage {
+    #[serde(default = "message_version")]
+    pub version: u8,
+    /// Sender's relay round (see `SignSession::round`)
+    #[serde(default)]
+    pub round: u16,
+    /// Session binding tag (see `binding`)
+    #[serde(default)]
+    pub binding: Option<String>,
+    /// Sender authentication tag (see `message_auth`); absent unless the
+    /// session was created with an `auth_secret`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub auth_tag: Option<String>,
+    #[serde(default)]
+    #[tsify(optional)]
+    pub kind: MessageKind,
     pub sender: u16,
     pub is_broadcast: bool,
+    /// Set iff `is_broadcast` is false
+    #[tsify(optional)]
     pub recipient: Option<u16>,
-    pub payload: String, // base64-encoded serde_json of Msg<Secp256k1, Sha256>
+    pub payload: String, // base64 of the protocol Msg (see `message_codec`)
+    /// Which hash of a batch session the message belongs to; absent
+    /// outside batches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub message_index: Option<u32>,
+    /// Digest the sender's protocol hashes with (see `protocol_digest`);
+    /// absent for SHA-256
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub digest: Option<String>,
 }
 
-#[derive(Serialize, Deserialize)]
+impl WasmSignMessage {
+    /// Who the message is from and for, as `relay` routes it.
+    pub fn route(&self) -> Route {
+        Route {
+            sender: self.sender,
+            is_broadcast: self.is_broadcast,
+            recipient: self.recipient,
+        }
+    }
+
+    /// The fields an `auth_tag` covers.
+    fn auth_envelope(&self) -> Envelope<'_> {
+        Envelope {
+            sender: self.sender,
+            recipient: self.recipient,
+            round: self.round,
+            abort: self.kind == MessageKind::Abort,
+            payload: &self.payload,
+        }
+    }
+}
+
+/// Per-session options passed from JS (all fields optional).
+#[derive(Serialize, Deserialize, Default, Clone, Tsify)]
+#[serde(default)]
+pub struct SignOptions {
+    /// Skip verifying the final signature against the shared public key.
+    /// Saves one scalar multiplication pair; only for performance-sensitive
+    /// callers that verify elsewhere.
+    pub skip_verify: bool,
+    /// Expected security level (`"128"` / `"192"`). When set, an aux blob
+    /// of a different level fails with `SECURITY_LEVEL_MISMATCH`.
+    pub security_level: Option<String>,
+    /// Expected curve (`"secp256k1"` / `"secp256r1"`). When set, a core
+    /// share on a different curve fails with `CURVE_MISMATCH`.
+    pub curve: Option<String>,
+    /// Overrides of the configured message limits for this session.
+    pub limits: LimitOverrides,
+    /// EIP-155 chain id. When set, the signature comes back with
+    /// `v_eip155` for a legacy transaction on that chain.
+    pub chain_id: Option<u64>,
+    /// Return the public key the signature recovers to as
+    /// `recovered_pubkey`, for callers that match keys rather than r / s.
+    pub include_recovered_pubkey: bool,
+    /// Hex-encoded secret shared by the signing group (16 bytes or more).
+    /// When set, outgoing messages carry an `auth_tag` and incoming ones
+    /// without a valid tag fail with `MESSAGE_AUTH_FAILED`.
+    pub auth_secret: Option<String>,
+    /// Encoding of protocol messages, `"json"` or `"cbor"`; defaults to the
+    /// one set at `init`. Peers on the other encoding fail with
+    /// `ENCODING_MISMATCH`.
+    pub encoding: Option<String>,
+    /// Check that every signer saw the same first-round broadcasts, at the
+    /// cost of one more relay round (default true). All signers of a
+    /// session must agree; see [`signing_protocol_info`].
+    pub reliable_broadcast: Option<bool>,
+    /// What to do with incoming messages not meant for this party (see
+    /// [`OnMisrouted`]); default `"warn"`.
+    pub on_misrouted: OnMisrouted,
+    /// Digest the protocol hashes with, `"sha256"` (default) or
+    /// `"keccak256"`. All signers of a session must agree; a peer on the
+    /// other one fails with `DIGEST_MISMATCH`.
+    pub digest: Option<String>,
+    /// Most bytes of outgoing messages (their JSON) one result carries,
+    /// e.g. under a relay's request size limit. The rest are held and the
+    /// result has `more` set; `process_round` with no messages fetches
+    /// them. A single message over the cap still goes out, alone.
+    pub max_outgoing_bytes: Option<u32>,
+    /// Create the session even if a live one for the same key share and
+    /// party already signs under this eid, rather than failing with
+    /// `EID_REUSE`.
+    pub allow_eid_reuse: bool,
+    /// Bring `s` to its low form, `n - s` when it is above `n / 2`, as
+    /// Ethereum and Bitcoin require (default true); the result's
+    /// `normalized` says whether it flipped. Off, the protocol's raw `s` is
+    /// returned — it verifies too. Recovery ids follow the `s` returned.
+    pub normalize_s: Option<bool>,
+    /// Host policy asked to approve the signature before it is released
+    /// (see `approval`). Not part of an export: a session with one can't
+    /// be exported.
+    #[serde(skip)]
+    pub approval_callback: Option<js_sys::Function>,
+}
+
+/// Handling of an incoming message that isn't for this party: P2P to
+/// another party, or sent under this party's own index (a relay
+/// reflecting our messages back). It is never delivered either way.
+#[derive(Serialize, Deserialize, Tsify, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OnMisrouted {
+    /// Drop it
+    Skip,
+    /// Drop it and count it in `misrouted_skipped`
+    #[default]
+    Warn,
+    /// Reject the message with `MISROUTED_MESSAGE`
+    Error,
+}
+
+impl SignOptions {
+    fn reliable_broadcast(&self) -> bool {
+        self.reliable_broadcast.unwrap_or(true)
+    }
+
+    fn normalize_s(&self) -> bool {
+        self.normalize_s.unwrap_or(true)
+    }
+}
+
+/// `options` with the encoding the session will use spelled out, so an
+/// exported session keeps it whatever the default is on import.
+fn with_resolved_encoding(options: &SignOptions) -> Result<SignOptions, String> {
+    let encoding = Encoding::parse_opt(options.encoding.as_deref())?;
+    Ok(SignOptions {
+        encoding: Some(encoding.as_str().to_string()),
+        ..options.clone()
+    })
+}
+
+#[derive(Serialize, Deserialize, Tsify)]
 pub struct CreateSessionResult {
     pub session_id: String,
     pub messages: Vec<WasmSignMessage>,
+    /// The session finished without a round trip: a single-party key has
+    /// no one to wait for. `signature` is set; a batch's signatures come
+    /// from a `process_round` with no messages.
+    #[serde(default)]
+    pub complete: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub signature: Option<SignatureResult>,
+    /// Rounds and messages to expect (each batch member's, for a batch)
+    #[serde(default)]
+    pub protocol_info: SigningProtocolInfo,
+    /// Size of `messages` as JSON
+    #[serde(default)]
+    pub outgoing_bytes: u64,
+    /// `max_outgoing_bytes` held messages back; fetch them with
+    /// `process_round` and no messages
+    #[serde(default)]
+    pub more: bool,
 }
 
-#[derive(Serialize, Deserialize)]
+/// Where a session stands after a call.
+#[derive(Serialize, Deserialize, Tsify, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    /// Needs the peers' next messages
+    #[default]
+    Waiting,
+    /// Has work left that needs no messages: `poll` stopped at its step
+    /// limit, or `process_round` at its time budget. Poll again, or
+    /// `process_round` with no messages.
+    Computing,
+    Complete,
+    Aborted,
+    /// A call failed part-way; every later call fails with `SESSION_FAILED`
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Tsify)]
 pub struct ProcessRoundResult {
     pub messages: Vec<WasmSignMessage>,
     pub complete: bool,
+    #[serde(default)]
+    pub status: SessionStatus,
+    /// Estimated fraction of the protocol done, 0 to 1 (batches: the
+    /// average over members)
+    #[serde(default)]
+    pub progress: f32,
+    /// Set once `complete`
+    #[tsify(optional)]
     pub signature: Option<SignatureResult>,
+    /// Incoming messages dropped because their sender had already moved
+    /// past that round, or as copies of ones already delivered (replays /
+    /// duplicates)
+    #[serde(default)]
+    pub stale_messages: u32,
+    /// Incoming messages dropped because they weren't for this party (see
+    /// [`OnMisrouted`]), since the last result; always 0 under `"skip"`
+    #[serde(default)]
+    pub misrouted_skipped: u32,
+    /// Indices into the incoming messages of those this call took: queued
+    /// for delivery, or dropped as stale or not for this party
+    #[serde(default)]
+    pub accepted: Vec<u32>,
+    /// The incoming messages this call refused, in order; the others were
+    /// still processed
+    #[serde(default)]
+    pub rejected: Vec<RejectedMessage>,
+    /// Time spent in this call, with the session totals
+    #[serde(default)]
+    pub metrics: RoundMetrics,
+    /// Set by `abort_session`: the session is gone and `messages` holds the
+    /// abort notice for the peers
+    #[serde(default)]
+    pub aborted: bool,
+    /// The abort reason, when `aborted`
+    #[serde(default)]
+    #[tsify(optional)]
+    pub reason: Option<String>,
+    /// Batch sessions: one entry per hash, set as each signature completes
+    /// (`complete` once all are). Empty otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[tsify(optional)]
+    pub signatures: Vec<Option<SignatureResult>>,
+    /// Set once `complete`, for ECDSA sessions: what produced the signature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub audit: Option<AuditRecord>,
+    /// Size of `messages` as JSON
+    #[serde(default)]
+    pub outgoing_bytes: u64,
+    /// `max_outgoing_bytes` held messages back; fetch them with another
+    /// `process_round` and no messages
+    #[serde(default)]
+    pub more: bool,
 }
 
 // ---------------------------------------------------------------------------
 // Public API (called from lib.rs WASM exports)
 // ---------------------------------------------------------------------------
 
-use base64::Engine;
+/// What an ECDSA session signs. The session binding covers the hash
+/// either way, so peers may mix the two for the same SHA-256 digest.
+#[derive(Clone, Copy)]
+pub enum SignData<'a> {
+    /// A 32-byte hash, signed as the scalar it encodes
+    Prehashed(&'a [u8]),
+    /// Message bytes, hashed with SHA-256 by cggmp24
+    Message(&'a [u8]),
+}
 
 /// Create a new signing session for one party.
 ///
 /// # Arguments
 /// - `core_share_bytes`: serialized CoreKeyShare (serde_json)
-/// - `aux_info_bytes`: serialized AuxInfo (serde_json)
-/// - `message_hash`: 32-byte hash to sign
+/// - `aux_info_bytes`: serialized AuxInfo (serde_json, optionally headered)
+/// - `data`: 32-byte hash or raw message to sign
 /// - `party_index`: this party's index at keygen time (0-based)
 /// - `parties_at_keygen`: indices of all parties participating in signing
 /// - `eid_bytes`: execution ID (32 bytes)
+/// - `options`: per-session options
 ///
 /// # Returns
 /// `CreateSessionResult` with session ID and initial outgoing messages.
 pub fn create_session(
     core_share_bytes: &[u8],
     aux_info_bytes: &[u8],
-    message_hash: &[u8],
+    data: SignData<'_>,
     party_index: u16,
     parties_at_keygen: &[u16],
     eid_bytes: &[u8],
+    options: &SignOptions,
 ) -> Result<CreateSessionResult, String> {
-    // Deserialize key material
-    let core_share: cggmp24::IncompleteKeyShare<Secp256k1> =
-        serde_json::from_slice(core_share_bytes)
-            .map_err(|e| format!("deserialize CoreKeyShare: {e}"))?;
-
-    let aux_info: cggmp24::key_share::AuxInfo<SecurityLevel128> =
-        serde_json::from_slice(aux_info_bytes)
-            .map_err(|e| format!("deserialize AuxInfo: {e}"))?;
-
-    let key_share = cggmp24::KeyShare::from_parts((core_share, aux_info))
-        .map_err(|e| format!("combine key share: {e}"))?;
-
-    // Leak the key share to get a 'static reference (reclaimed on Drop)
-    let key_share_ptr = Box::into_raw(Box::new(key_share));
-    let key_share_ref: &'static cggmp24::KeyShare<Secp256k1, SecurityLevel128> =
-        unsafe { &*key_share_ptr };
-
-    // Build the prehashed data to sign
-    if message_hash.len() != 32 {
-        // Clean up leaked memory on error
-        unsafe { drop(Box::from_raw(key_share_ptr)); }
-        return Err(format!(
-            "message_hash must be 32 bytes, got {}",
-            message_hash.len()
-        ));
-    }
-    let scalar = Scalar::<Secp256k1>::from_be_bytes_mod_order(message_hash);
-    let prehashed_ptr = Box::into_raw(Box::new(PrehashedDataToSign::from_scalar(scalar)));
-    let prehashed_ref: &'static PrehashedDataToSign<Secp256k1> =
-        unsafe { &*prehashed_ptr };
-
-    // Build execution ID — leak eid bytes for 'static lifetime
-    let eid_owned: Box<[u8]> = eid_bytes.to_vec().into_boxed_slice();
-    let eid_static: &'static [u8] = Box::leak(eid_owned);
-    let eid = cggmp24::ExecutionId::new(eid_static);
-
-    // Build parties list — leak for 'static lifetime
-    let parties_owned: Box<[u16]> = parties_at_keygen.to_vec().into_boxed_slice();
-    let parties_static: &'static [u16] = Box::leak(parties_owned);
-
-    // Leak rng for 'static lifetime
-    let rng_ptr = Box::into_raw(Box::new(OsRng));
-    let rng_ref: &'static mut OsRng = unsafe { &mut *rng_ptr };
-
-    // Map party_index (keygen index) → position within the parties array.
-    // The cggmp24 crate expects `i` to be the 0-based position, not the
-    // keygen party index. For parties=[0,1] the two are identical, but for
-    // parties=[1,2] keygen index 2 is at position 1.
-    let party_position = parties_at_keygen
-        .iter()
-        .position(|&p| p == party_index)
-        .ok_or_else(|| {
-            // Clean up leaked memory on error
-            unsafe {
-                drop(Box::from_raw(key_share_ptr));
-                drop(Box::from_raw(prehashed_ptr));
-                drop(Box::from_raw(rng_ptr));
-            }
-            format!(
-                "party_index {} not found in parties {:?}",
-                party_index, parties_at_keygen
-            )
-        })? as u16;
-
-    // Create the signing state machine
-    // - `party_position`: 0-based index of this party within the signing group
-    // - `parties_static`: keygen indices of all parties in the signing group
-    let sm = cggmp24::signing(eid, party_position, parties_static, key_share_ref)
-        .enforce_reliable_broadcast(true)
-        .sign_sync(rng_ref, prehashed_ref);
-
-    // Wrap in type-erased wrapper
-    let dyn_sm: Box<dyn DynSignSM> = Box::new(SmWrapper { sm });
-
-    let mut session = SignSession {
-        sm: ManuallyDrop::new(dyn_sm),
+    let recipe = ecdsa_recipe(
+        core_share_bytes,
+        aux_info_bytes,
+        data,
         party_index,
-        parties_at_keygen: parties_at_keygen.to_vec(),
-        _key_share_ptr: key_share_ptr,
-        _rng_ptr: rng_ptr,
-        _prehashed_ptr: prehashed_ptr,
-        signature: None,
-    };
-
-    // Drive the state machine to produce initial messages
-    let messages = drive_batch(&mut session)?;
+        parties_at_keygen,
+        eid_bytes,
+        options,
+    )?;
+    let created = register_session(|| build_session(&recipe))?;
+    note_eid(eid_bytes);
+    Ok(created)
+}
 
-    // Generate session ID
-    let session_id = uuid_v4();
+/// [`create_session`] without the first drive: the session is built (key
+/// material deserialised, eid reserved) and stored, but its first messages
+/// are left for [`warmup`] or the first `process_round` to compute. The
+/// result has status `computing`, no messages and `complete` false, even
+/// for a single-party key.
+pub fn create_session_deferred(
+    core_share_bytes: &[u8],
+    aux_info_bytes: &[u8],
+    data: SignData<'_>,
+    party_index: u16,
+    parties_at_keygen: &[u16],
+    eid_bytes: &[u8],
+    options: &SignOptions,
+) -> Result<CreateSessionResult, String> {
+    let recipe = ecdsa_recipe(
+        core_share_bytes,
+        aux_info_bytes,
+        data,
+        party_index,
+        parties_at_keygen,
+        eid_bytes,
+        options,
+    )?;
+    check_session_cap(1)?;
+    let start = clock::now_ms();
+    let mut session = build_session(&recipe)?;
+    session.metrics.setup_ms = clock::now_ms() - start;
 
-    // Store session
+    let session_id = uuid_v4()?;
+    let protocol_info = session.protocol_info.clone();
     SESSIONS.with(|sessions| {
         sessions.borrow_mut().insert(session_id.clone(), session);
     });
-
+    note_eid(eid_bytes);
     Ok(CreateSessionResult {
         session_id,
-        messages,
+        messages: Vec::new(),
+        complete: false,
+        signature: None,
+        protocol_info,
+        outgoing_bytes: 0,
+        more: false,
     })
 }
 
-/// Process a round of incoming messages for an existing session.
+/// Drive a session from [`create_session_deferred`] towards its first
+/// messages for about `budget_ms` (at least one step), returning those it
+/// produced — forward them as `create_session`'s. Status `computing` means
+/// there is more to do; `waiting` that the first messages are all out.
+///
+/// Only the work before the first relay round is done here: on a session
+/// that has one behind it, or isn't computing, this drives nothing and
+/// returns no messages, so idle callbacks may keep calling it. However far
+/// it got, `process_round` finishes the rest before anything new, and the
+/// signature is the one an eagerly created session with the same seed
+/// would produce.
+pub fn warmup(session_id: &str, budget_ms: f64) -> Result<ProcessRoundResult, String> {
+    with_single_session(session_id, |session| {
+        session.times = PhaseTimes::default();
+        if session.round > 0 || session.status() != SessionStatus::Computing || !session.pending.is_empty() {
+            return Ok(round_result(session, Vec::new(), 0));
+        }
+        let deadline = clock::now_ms() + budget_ms.max(0.0);
+        let messages = failing(session, |session| drive(session, None, Some(deadline)))?;
+        Ok(round_result(session, messages, 0))
+    })
+    .and_then(|result| ask_approval(session_id, result))
+}
+
+/// The recipe of a new ECDSA session, with a fresh seed.
+fn ecdsa_recipe(
+    core_share_bytes: &[u8],
+    aux_info_bytes: &[u8],
+    data: SignData<'_>,
+    party_index: u16,
+    parties_at_keygen: &[u16],
+    eid_bytes: &[u8],
+    options: &SignOptions,
+) -> Result<EcdsaRecipe, String> {
+    let (message_hash, message) = match data {
+        SignData::Prehashed(hash) => (hash.to_vec(), None),
+        SignData::Message(message) => (Sha256::digest(message).to_vec(), Some(message.to_vec())),
+    };
+    Ok(EcdsaRecipe {
+        core_share: core_share_bytes.to_vec(),
+        aux_info: aux_info_bytes.to_vec(),
+        message_hash,
+        message,
+        party_index,
+        parties_at_keygen: parties_at_keygen.to_vec(),
+        eid: eid_bytes.to_vec(),
+        options: with_resolved_encoding(options)?,
+        seed: fresh_seed()?,
+        created_at: clock::unix_seconds(),
+        key_id: None,
+    })
+}
+
+/// Create one session signing every hash in `message_hashes` with the same
+/// key share and signer set. Every relay round advances all members, so N
+/// signatures cost the round trips of one; each member runs under its own
+/// eid derived from the batch's (see `binding::batch_member_eid`). Batches
+/// can't be exported.
+///
+/// Arguments are those of [`create_session`]; every party must pass the
+/// same hashes in the same order. The initial messages of all members come
+/// back together, each tagged with its `message_index`.
+pub fn create_batch_session(
+    core_share_bytes: &[u8],
+    aux_info_bytes: &[u8],
+    message_hashes: &[impl AsRef<[u8]>],
+    party_index: u16,
+    parties_at_keygen: &[u16],
+    eid_bytes: &[u8],
+    options: &SignOptions,
+) -> Result<CreateSessionResult, String> {
+    if message_hashes.is_empty() || message_hashes.len() > MAX_BATCH_SIZE {
+        return Err(format!(
+            "INVALID_BATCH: a batch signs 1 to {MAX_BATCH_SIZE} hashes, got {}",
+            message_hashes.len()
+        ));
+    }
+    check_session_cap(message_hashes.len())?;
+    let options = with_resolved_encoding(options)?;
+
+    let mut members = Vec::with_capacity(message_hashes.len());
+    let mut messages = Vec::new();
+    // `message_index` is a u32 on the wire; MAX_BATCH_SIZE keeps it small
+    for (index, message_hash) in (0u32..).zip(message_hashes) {
+        let recipe = EcdsaRecipe {
+            core_share: core_share_bytes.to_vec(),
+            aux_info: aux_info_bytes.to_vec(),
+            message_hash: message_hash.as_ref().to_vec(),
+            message: None,
+            party_index,
+            parties_at_keygen: parties_at_keygen.to_vec(),
+            eid: binding::batch_member_eid(eid_bytes, index).to_vec(),
+            options: options.clone(),
+            seed: fresh_seed()?,
+            created_at: clock::unix_seconds(),
+            key_id: None,
+        };
+        let (session, member_messages) =
+            start_session_with(|| build_session(&recipe)).map_err(|e| format!("message {index}: {e}"))?;
+        messages.extend(with_message_index(member_messages, index));
+        members.push(session);
+    }
+
+    let session_id = uuid_v4()?;
+    let complete = members.iter().all(|member| member.signature.is_some());
+    let protocol_info = members[0].protocol_info.clone();
+    let (messages, outgoing_bytes) = release_batch_outgoing(&mut members, messages);
+    let more = members.iter().any(|member| !member.held.is_empty());
+    BATCHES.with(|batches| {
+        batches.borrow_mut().insert(session_id.clone(), members);
+    });
+    note_eid(eid_bytes);
+    Ok(CreateSessionResult {
+        session_id,
+        messages,
+        complete,
+        signature: None,
+        protocol_info,
+        outgoing_bytes,
+        more,
+    })
+}
+
+/// Build an ECDSA session's state machine from its recipe, for the curve
+/// in the core share header and the `SecurityLevel` in the aux blob's (see
+/// `share_format`).
+fn build_session(recipe: &EcdsaRecipe) -> Result<SignSession, String> {
+    if recipe.message_hash.len() != 32 {
+        return Err(format!(
+            "message_hash must be 32 bytes, got {}",
+            recipe.message_hash.len()
+        ));
+    }
+
+    // The blob headers decide which curve / SecurityLevel monomorphization we run
+    let requested_level = recipe
+        .options
+        .security_level
+        .as_deref()
+        .map(Level::parse)
+        .transpose()?;
+    let requested_curve = recipe.options.curve.as_deref().map(CurveId::parse).transpose()?;
+    share_metadata::check_environment(&recipe.core_share)?;
+    share_metadata::check_environment(&recipe.aux_info)?;
+    let (core_header, core_payload) =
+        share_format::split_expect(&recipe.core_share, BlobKind::CoreShare)?;
+    let (aux_header, aux_payload) = share_format::split_expect(&recipe.aux_info, BlobKind::AuxInfo)?;
+    let curve = share_format::ensure_curve(&core_header, requested_curve)?;
+    let level = share_format::ensure_level(&aux_header, requested_level)?;
+
+    let mut session = with_curve!(curve, E => with_security_level!(level, L => {
+        start_session::<E, L>(recipe, core_payload, aux_payload)
+    }))?;
+    session.approval = recipe.options.approval_callback.clone().map(Approval::new);
+    Ok(session)
+}
+
+/// Build a session, produce its initial messages and store it under a
+/// fresh ID.
+pub(crate) fn register_session(
+    build: impl FnOnce() -> Result<SignSession, String>,
+) -> Result<CreateSessionResult, String> {
+    check_session_cap(1)?;
+    let (mut session, messages) = start_session_with(build)?;
+    let (messages, outgoing_bytes) = release_outgoing(&mut session, messages);
+
+    // Generate session ID
+    let session_id = uuid_v4()?;
+    let signature = session.signature.clone();
+    let protocol_info = session.protocol_info.clone();
+    let more = !session.held.is_empty();
+
+    // Store session
+    SESSIONS.with(|sessions| {
+        sessions.borrow_mut().insert(session_id.clone(), session);
+    });
+
+    Ok(CreateSessionResult {
+        session_id,
+        messages,
+        complete: signature.is_some(),
+        signature,
+        protocol_info,
+        outgoing_bytes,
+        more,
+    })
+}
+
+/// Fails with `TOO_MANY_SESSIONS` if `adding` more sessions would take the
+/// live ones past `config::max_sessions`. Every member of a batch holds
+/// key material and a state machine of its own, so each counts. Lowering
+/// the cap ends no session; new ones are refused until enough are
+/// destroyed.
+fn check_session_cap(adding: usize) -> Result<(), String> {
+    let live = SESSIONS.with(|sessions| sessions.borrow().len())
+        + BATCHES.with(|batches| batches.borrow().values().map(Vec::len).sum::<usize>());
+    let max = config::max_sessions();
+    if u32::try_from(live.saturating_add(adding)).map_or(true, |total| total > max) {
+        return Err(format!(
+            "TOO_MANY_SESSIONS: {live} signing sessions are live (batch members included), \
+             {adding} more would pass the configured maximum of {max}"
+        ));
+    }
+    Ok(())
+}
+
+/// Build a session and produce its initial messages. The time `build`
+/// takes is the session's `setup_ms`.
+fn start_session_with(
+    build: impl FnOnce() -> Result<SignSession, String>,
+) -> Result<(SignSession, Vec<WasmSignMessage>), String> {
+    let start = clock::now_ms();
+    let mut session = build()?;
+    session.metrics.setup_ms = clock::now_ms() - start;
+
+    // Drive the state machine to produce initial messages
+    let messages = drive(&mut session, None, None)?;
+    // A single-party key is done already. The session isn't stored yet,
+    // so the callback may call into the module
+    if let Some((callback, request)) = session.approval_question() {
+        session.answer_approval(approval::ask(&callback, &request))?;
+    }
+    let times = std::mem::take(&mut session.times);
+    session.metrics.record_start(&times);
+    Ok((session, messages))
+}
+
+/// Deserialize key material and build the signing state machine for curve
+/// `E` at level `L`.
+fn start_session<E, L>(
+    recipe: &EcdsaRecipe,
+    core_payload: &[u8],
+    aux_payload: &[u8],
+) -> Result<SignSession, String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+{
+    let message_hash = recipe.message_hash.as_slice();
+    let party_index = recipe.party_index;
+    let parties_at_keygen = recipe.parties_at_keygen.as_slice();
+    let eid_bytes = recipe.eid.as_slice();
+
+    // Deserialize key material, unless the cache has it already
+    let (key_share, cache_hit) =
+        cached_key_share::<E, L>(&recipe.core_share, &recipe.aux_info, core_payload, aux_payload)?;
+    let public_key = Point::from(key_share.shared_public_key());
+    let reservation = eid_reservation(eid_bytes, public_key.to_bytes(true).as_bytes(), party_index);
+    if !recipe.options.allow_eid_reuse {
+        check_eid_reuse(&reservation)?;
+    }
+
+    // A key generated with party labels is signed for by label; the
+    // session keeps the labels, the state machine gets the keygen indices
+    let keygen_party = share_metadata::keygen_indices(&recipe.core_share, &[party_index])?[0];
+    let keygen_parties = share_metadata::keygen_indices(&recipe.core_share, parties_at_keygen)?;
+
+    // Map party_index (keygen index) → position within the parties array.
+    // The cggmp24 crate expects `i` to be the 0-based position, not the
+    // keygen party index. For parties=[0,1] the two are identical, but for
+    // parties=[1,2] keygen index 2 is at position 1. A set that could never
+    // finish is refused here, before anything is leaked.
+    let party_position = key_material::check_signer_set(
+        key_share.n(),
+        key_share.min_signers(),
+        keygen_party,
+        &keygen_parties,
+    )?;
+
+    // Leak everything the state machine borrows for 'static (reclaimed on Drop).
+    // For a raw message `message_hash` is its SHA-256, the scalar cggmp24
+    // signs, so the signature checks below hold in both modes.
+    let scalar = Scalar::<E>::from_be_bytes_mod_order(message_hash);
+    let key_share: Leaked<Arc<cggmp24::KeyShare<E, L>>> = Leaked::new(key_share);
+    let eid_owned = Leaked::new(eid_bytes.to_vec());
+    let parties_owned = Leaked::new(keygen_parties);
+    let labels = parties_at_keygen.to_vec();
+    let rng = Leaked::new(session_rng(recipe.seed));
+
+    // SAFETY: all handles are stored in the session alongside `sm` and are
+    // dropped only after it (see `SignSession::drop`).
+    let (key_share_ref, eid_static, parties_static, rng_ref) = unsafe {
+        (
+            &**key_share.as_static(),
+            eid_owned.as_static().as_slice(),
+            parties_owned.as_static().as_slice(),
+            rng.as_static_mut(),
+        )
+    };
+    let eid = cggmp24::ExecutionId::new(eid_static);
+
+    // Create the signing state machine
+    // - `party_position`: 0-based index of this party within the signing group
+    // - `parties_static`: keygen indices of all parties in the signing group
+    let signing = cggmp24::signing(eid, party_position, parties_static, key_share_ref)
+        .enforce_reliable_broadcast(recipe.options.reliable_broadcast());
+
+    let encoding = Encoding::parse_opt(recipe.options.encoding.as_deref())?;
+    let verify = !recipe.options.skip_verify;
+    let chain_id = recipe.options.chain_id;
+    let include_recovered_pubkey = recipe.options.include_recovered_pubkey;
+    let normalize_s = recipe.options.normalize_s();
+    let finish = move |result: Result<cggmp24::signing::Signature<E>, cggmp24::signing::SigningError>| {
+        // Blame is reported by the index the caller named the party by
+        // (see `protocol_failure`)
+        let sig = result
+            .map_err(|e| ProtocolFailure::from_signing_error(&e, &labels).into_error())?;
+        // Extract r, s as 32-byte big-endian arrays
+        let mut sig_bytes = vec![0u8; cggmp24::signing::Signature::<E>::serialized_len()];
+        sig.write_to_slice(&mut sig_bytes);
+        // Low-s form (required for Ethereum) unless the caller wants the raw
+        // s; everything below, the recovery id included, sees the s returned
+        let normalized = normalize_s && signature::normalize_s::<E>(&mut sig_bytes)?;
+
+        // Never hand out a signature that doesn't verify under the
+        // wallet's key — e.g. a stale parties list on the caller side.
+        if verify {
+            signature::ensure_valid(&public_key, &scalar, &sig_bytes)?;
+        }
+
+        let recovery_id = (chain_id.is_some() || include_recovered_pubkey)
+            .then(|| {
+                signature::recovery_id(&public_key, &scalar, &sig_bytes)
+                    .ok_or("RECOVERY_ID_FAILED: signature does not recover the shared public key")
+            })
+            .transpose()?;
+        let v_eip155 = chain_id
+            .zip(recovery_id)
+            .map(|(chain_id, recovery_id)| signature::eip155_v(recovery_id, chain_id))
+            .transpose()?;
+        let recovered_pubkey = recovery_id
+            .filter(|_| include_recovered_pubkey)
+            .and_then(|recovery_id| signature::recover_public_key(&scalar, &sig_bytes, recovery_id))
+            .map(|key| ByteBuf::from(key.to_bytes(true).as_bytes().to_vec()));
+
+        Ok(SignatureResult {
+            r: sig_bytes[..32].to_vec(),
+            s: sig_bytes[32..].to_vec(),
+            normalized,
+            v_eip155,
+            recovered_pubkey,
+        })
+    };
+
+    // The data to sign is leaked too; its type depends on the mode. The
+    // protocol digest only changes the state machine's type
+    let digest = ProtocolDigest::parse_opt(recipe.options.digest.as_deref())?;
+    let (sm, data): (Box<dyn DynSignSM>, Box<dyn LeakedAlloc>) = with_digest!(digest, D => {
+        let signing = signing.set_digest::<D>();
+        match &recipe.message {
+            Some(message) => {
+                let data = Leaked::new(DataToSign::<E>::digest::<Sha256>(message));
+                // SAFETY: as above
+                let data_ref = unsafe { data.as_static() };
+                (SmWrapper::boxed(signing.sign_sync(rng_ref, data_ref), encoding, finish), Box::new(data))
+            }
+            None => {
+                let data = Leaked::new(PrehashedDataToSign::from_scalar(scalar));
+                // SAFETY: as above
+                let data_ref = unsafe { data.as_static() };
+                (SmWrapper::boxed(signing.sign_sync(rng_ref, data_ref), encoding, finish), Box::new(data))
+            }
+        }
+    });
+
+    let mut session = SignSession::new(
+        sm,
+        party_index,
+        parties_at_keygen.to_vec(),
+        vec![
+            Box::new(key_share),
+            data,
+            Box::new(eid_owned),
+            Box::new(parties_owned),
+            Box::new(rng),
+        ],
+        SessionRecipe::Ecdsa(recipe.clone()),
+        binding::session_binding(eid_bytes, message_hash, parties_at_keygen),
+    )?;
+    session.metrics.cache_hit = cache_hit;
+    session.reserve_eid(reservation);
+    session.audit = Some(AuditTrail::new(
+        &session.binding,
+        message_hash,
+        parties_at_keygen,
+        party_index,
+        recipe.created_at,
+        move |sig| signature::recovery_id(&public_key, &scalar, sig),
+    ));
+    Ok(session)
+}
+
+/// Process a round of incoming messages for an existing session.
 ///
 /// For each incoming message: deliver to the state machine, then drive
-/// until NeedsInput or Output.
+/// until NeedsInput or Output. Every message is checked first, and one
+/// that fails is rejected on its own — listed in `rejected` with its code
+/// and never delivered — while the others go through as usual and are
+/// listed in `accepted`. A message is rejected if it speaks another
+/// envelope version (`UNSUPPORTED_MESSAGE_VERSION`), belongs to another
+/// session (`SESSION_BINDING_MISMATCH`), is in the other encoding
+/// (`ENCODING_MISMATCH`), lacks a valid auth tag in a session created with
+/// `auth_secret` (`MESSAGE_AUTH_FAILED`), comes from outside the session
+/// (`UNKNOWN_SENDER`), breaks the session's message limits
+/// (`MESSAGE_TOO_LARGE` / `TOO_MANY_MESSAGES`) or doesn't decode
+/// (`MALFORMED_MESSAGE`). So with three messages of which the second is
+/// corrupted, `accepted` is `[0, 2]` and `rejected` holds index 1. The
+/// call still fails outright on a peer's abort, or once the protocol
+/// itself fails, which fails the session. A call whose messages were all
+/// rejected doesn't count as a relay round.
+///
+/// For a batch session, every message must carry a `message_index` below
+/// the batch size (rejected with `INVALID_MESSAGE_INDEX` otherwise) and
+/// the result lists the `signatures` so far.
+///
+/// With `max_millis`, the call stops driving once that much time has gone
+/// (after at least one step) and returns the messages so far with status
+/// `computing`; call again with no messages to continue. A batch shares
+/// the budget across its members.
 pub fn process_round(
     session_id: &str,
     incoming: &[WasmSignMessage],
+    max_millis: Option<f64>,
 ) -> Result<ProcessRoundResult, String> {
-    SESSIONS.with(|sessions| {
-        let mut sessions = sessions.borrow_mut();
-        let session = sessions
-            .get_mut(session_id)
-            .ok_or_else(|| format!("no sign session found: {session_id}"))?;
+    process_round_for(Protocol::Ecdsa, session_id, incoming, max_millis)
+}
 
-        let mut all_outgoing = Vec::new();
-        let mut delivered = 0u32;
-
-        // Deliver each incoming message, then drive.
-        // Two key transformations:
-        //   1. Filter out P2P messages not addressed to us.
-        //   2. Map sender from keygen index (wire format) to 0-based
-        //      position within the signing group (what the round_based
-        //      state machine expects).
-        for msg in incoming {
-            // Filter: skip P2P messages not addressed to this party
-            if !msg.is_broadcast {
-                if let Some(recipient) = msg.recipient {
-                    if recipient != session.party_index {
-                        continue; // Not for us
-                    }
-                }
+/// [`process_round`] for a session that must be running `protocol`.
+pub(crate) fn process_round_for(
+    protocol: Protocol,
+    session_id: &str,
+    incoming: &[WasmSignMessage],
+    max_millis: Option<f64>,
+) -> Result<ProcessRoundResult, String> {
+    let deadline = max_millis.map(|max| clock::now_ms() + max);
+    if protocol == Protocol::Ecdsa && is_batch(session_id) {
+        let result = process_batch_round(session_id, incoming, deadline)?;
+        return ask_batch_approvals(session_id, result);
+    }
+    with_single_session(session_id, |session| {
+        if session.protocol != protocol {
+            return Err(format!(
+                "WRONG_PROTOCOL: session {session_id} runs {}, not {}",
+                session.protocol.as_str(),
+                protocol.as_str()
+            ));
+        }
+        let resumed = resumes(session, incoming);
+        let result = apply_round(session, incoming, deadline)?;
+        if starts_round(resumed, incoming, &result.accepted) {
+            session.log.push(accepted_messages(incoming, &result.accepted).cloned().collect());
+        }
+        Ok(result)
+    })
+    .and_then(|result| ask_approval(session_id, result))
+}
+
+pub(crate) fn is_batch(session_id: &str) -> bool {
+    BATCHES.with(|batches| batches.borrow().contains_key(session_id))
+}
+
+/// [`process_round`] for a batch session: route each message to the member
+/// its `message_index` names, then run one round on every member still
+/// signing.
+fn process_batch_round(
+    batch_id: &str,
+    incoming: &[WasmSignMessage],
+    deadline: Option<f64>,
+) -> Result<ProcessRoundResult, String> {
+    BATCHES.with(|batches| {
+        let mut batches = batches.borrow_mut();
+        let members = batches
+            .get_mut(batch_id)
+            .ok_or_else(|| format!("no sign session found: {batch_id}"))?;
+        members.iter().try_for_each(SignSession::ensure_usable)?;
+
+        let result = apply_batch_round(members, incoming, deadline);
+        if let Err(e) = &result {
+            // The members run in lockstep, so one failing fails them all
+            if members.iter().any(|member| member.failed.is_some()) {
+                members.iter_mut().for_each(|member| member.fail(e));
             }
+        }
+        if members.iter().any(|member| member.aborted) {
+            batches.remove(batch_id);
+        }
+        result
+    })
+}
 
-            // Map sender from keygen index → position in parties array
-            let sender_pos = session.parties_at_keygen
-                .iter()
-                .position(|&p| p == msg.sender)
-                .ok_or_else(|| format!(
-                    "unknown sender {} not in parties {:?}",
-                    msg.sender, session.parties_at_keygen
-                ))? as u16;
+fn apply_batch_round(
+    members: &mut [SignSession],
+    incoming: &[WasmSignMessage],
+    deadline: Option<f64>,
+) -> Result<ProcessRoundResult, String> {
+    // Route by message_index, keeping each message's place in `incoming`
+    let mut routed = vec![Vec::new(); members.len()];
+    let mut places = vec![Vec::new(); members.len()];
+    let mut accepted = Vec::new();
+    let mut rejected = Vec::new();
+    for (place, msg) in (0u32..).zip(incoming) {
+        let index = msg
+            .message_index
+            .and_then(|i| usize::try_from(i).ok())
+            .filter(|&i| i < members.len());
+        let Some(index) = index else {
+            rejected.push(RejectedMessage::new(
+                place,
+                &format!(
+                    "INVALID_MESSAGE_INDEX: message from party {} has message_index {:?}, the batch signs {} hashes",
+                    msg.sender,
+                    msg.message_index,
+                    members.len()
+                ),
+            ));
+            continue;
+        };
+        routed[index].push(msg.clone());
+        places[index].push(place);
+    }
 
-            let msg_type: u8 = if msg.is_broadcast { 0 } else { 1 };
-            let payload_bytes = msg.payload.as_bytes();
+    let mut messages = Vec::new();
+    let mut stale_messages = 0u32;
+    let mut misrouted_skipped = 0u32;
+    let mut metrics = RoundMetrics::default();
+    for (index, ((member, batch), places)) in (0u32..).zip(members.iter_mut().zip(routed).zip(places)) {
+        if member.signature.is_some() {
+            // Done; anything still arriving for it is a late duplicate.
+            // What it holds still goes out
+            stale_messages = stale_messages.saturating_add(u32::try_from(batch.len()).unwrap_or(u32::MAX));
+            accepted.extend(places);
+            messages.extend(member.held.drain(..));
+            continue;
+        }
+        let resumed = resumes(member, &batch);
+        let round = apply_round(member, &batch, deadline).map_err(|e| format!("message {index}: {e}"))?;
+        if starts_round(resumed, &batch, &round.accepted) {
+            member.log.push(accepted_messages(&batch, &round.accepted).cloned().collect());
+        }
+        // Back from the member's batch to `incoming`
+        let place = |i: u32| places[i as usize];
+        accepted.extend(round.accepted.iter().map(|&i| place(i)));
+        rejected.extend(round.rejected.into_iter().map(|rejection| RejectedMessage {
+            index: place(rejection.index),
+            ..rejection
+        }));
+        messages.extend(with_message_index(round.messages, index));
+        stale_messages += round.stale_messages;
+        misrouted_skipped += round.misrouted_skipped;
+        metrics.absorb(&round.metrics);
+    }
 
-            session
-                .sm
-                .receive_msg(sender_pos, msg_type, payload_bytes)?;
+    let (messages, outgoing_bytes) = release_batch_outgoing(members, messages);
+    let signatures: Vec<_> = members.iter().map(|member| member.signature.clone()).collect();
+    let complete = signatures.iter().all(Option::is_some);
+    accepted.sort_unstable();
+    rejected.sort_by_key(|rejection| rejection.index);
+    Ok(ProcessRoundResult {
+        messages,
+        complete,
+        status: batch_status(members),
+        progress: batch_progress(members),
+        signature: None,
+        stale_messages,
+        misrouted_skipped,
+        accepted,
+        rejected,
+        metrics,
+        aborted: false,
+        reason: None,
+        signatures,
+        audit: None,
+        outgoing_bytes,
+        more: members.iter().any(|member| !member.held.is_empty()),
+    })
+}
+
+fn with_message_index(mut messages: Vec<WasmSignMessage>, index: u32) -> Vec<WasmSignMessage> {
+    for msg in &mut messages {
+        msg.message_index = Some(index);
+    }
+    messages
+}
+
+/// Check, deliver and drive one batch of incoming messages. Shared by
+/// [`process_round_for`] and the replay in [`import_session`].
+///
+/// The same as feeding each message and polling without a step limit,
+/// but for the `deadline` (`clock::now_ms` time). A call that
+/// [`resumes`] a session doesn't start a new relay round.
+fn apply_round(
+    session: &mut SignSession,
+    incoming: &[WasmSignMessage],
+    deadline: Option<f64>,
+) -> Result<ProcessRoundResult, String> {
+    let resumed = resumes(session, incoming);
+    session.times = PhaseTimes::default();
+    let Screened { accepted, rejected } = screen_incoming(session, incoming)?;
+    if starts_round(resumed, incoming, &accepted) {
+        session.round = session.round.saturating_add(1);
+        session.feeding = false;
+    }
+
+    failing(session, |session| {
+        let mut stale_messages = 0u32;
+        for msg in accepted_messages(incoming, &accepted) {
+            if queue_message(session, msg)? == Queued::Stale {
+                stale_messages += 1;
+            }
+        }
+        let messages = drive(session, None, deadline)?;
+        Ok(ProcessRoundResult {
+            accepted,
+            rejected,
+            ..round_result(session, messages, stale_messages)
+        })
+    })
+}
+
+/// Whether a call with `incoming`, of which it took `accepted`, is a relay
+/// round: it doesn't just [`resume`](resumes) the session, and not every
+/// message was rejected. Only relay rounds bump the round and go in the
+/// replay log, which holds just the messages taken.
+fn starts_round(resumed: bool, incoming: &[WasmSignMessage], accepted: &[u32]) -> bool {
+    !resumed && (incoming.is_empty() || !accepted.is_empty())
+}
+
+/// Whether a `process_round` with `incoming` only continues a session an
+/// earlier call's budget cut short, or fetches the messages
+/// `max_outgoing_bytes` held back. Such calls aren't relay rounds: they
+/// don't bump the round or go in the replay log.
+fn resumes(session: &SignSession, incoming: &[WasmSignMessage]) -> bool {
+    incoming.is_empty() && (session.status() == SessionStatus::Computing || !session.held.is_empty())
+}
 
-            delivered += 1;
+/// Size of a message as the host relays it: its JSON.
+fn wire_len(msg: &WasmSignMessage) -> u64 {
+    serde_json::to_vec(msg).map_or(0, |json| json.len() as u64)
+}
+
+/// Split `messages` at `max_bytes`: the longest run from the front that
+/// fits — at least one message, so an oversized one still goes out — and
+/// the rest. Also returns the size of the first part.
+fn split_outgoing(
+    mut messages: Vec<WasmSignMessage>,
+    max_bytes: Option<u32>,
+) -> (Vec<WasmSignMessage>, Vec<WasmSignMessage>, u64) {
+    let mut total = 0u64;
+    let mut fit = messages.len();
+    for (i, msg) in messages.iter().enumerate() {
+        let len = wire_len(msg);
+        if i > 0 && max_bytes.is_some_and(|max| total + len > u64::from(max)) {
+            fit = i;
+            break;
+        }
+        total += len;
+    }
+    let rest = messages.split_off(fit);
+    (messages, rest, total)
+}
+
+/// What of `session`'s held messages and then `fresh` goes out in this
+/// result, holding back the rest; with its size.
+fn release_outgoing(session: &mut SignSession, fresh: Vec<WasmSignMessage>) -> (Vec<WasmSignMessage>, u64) {
+    let queued: Vec<_> = session.held.drain(..).chain(fresh).collect();
+    let (messages, rest, bytes) = split_outgoing(queued, session.max_outgoing_bytes);
+    session.held = rest.into();
+    (messages, bytes)
+}
 
-            // Drive after each message delivery
-            let batch = drive_batch(session)?;
-            all_outgoing.extend(batch);
+/// [`release_outgoing`] across a batch, whose members share the cap: a
+/// message that doesn't fit goes back to its member, ahead of what that
+/// member already holds.
+fn release_batch_outgoing(
+    members: &mut [SignSession],
+    messages: Vec<WasmSignMessage>,
+) -> (Vec<WasmSignMessage>, u64) {
+    let max_bytes = members.first().and_then(|member| member.max_outgoing_bytes);
+    let (messages, rest, bytes) = split_outgoing(messages, max_bytes);
+    for msg in rest.into_iter().rev() {
+        let index = msg.message_index.and_then(|i| usize::try_from(i).ok()).unwrap_or(0);
+        if let Some(member) = members.get_mut(index) {
+            member.held.push_front(msg);
         }
+    }
+    (messages, bytes)
+}
+
+/// Run `f`, failing the session if it does: a round that fails part-way
+/// leaves a state machine that can't be driven any further (see
+/// [`SignSession::fail`]).
+fn failing<T>(
+    session: &mut SignSession,
+    f: impl FnOnce(&mut SignSession) -> Result<T, String>,
+) -> Result<T, String> {
+    f(session).map_err(|e| {
+        session.fail(&e);
+        e
+    })
+}
+
+/// What [`screen_incoming`] made of a batch of incoming messages.
+#[derive(Default)]
+struct Screened {
+    /// Indices into the batch
+    accepted: Vec<u32>,
+    rejected: Vec<RejectedMessage>,
+}
 
-        // If no messages were delivered, just drive (for initial round processing)
-        if delivered == 0 {
-            let batch = drive_batch(session)?;
-            all_outgoing.extend(batch);
+/// Check every message of a batch before any of it is queued, rejecting
+/// those [`check_incoming`] refuses and taking the rest. A peer's `abort`
+/// among those taken marks the session aborted and fails with
+/// `PEER_ABORTED`.
+fn screen_incoming(session: &mut SignSession, incoming: &[WasmSignMessage]) -> Result<Screened, String> {
+    let mut screened = Screened::default();
+    for (index, msg) in (0u32..).zip(incoming) {
+        match check_incoming(session, msg, screened.accepted.len()) {
+            Ok(()) => {
+                session.received = session.received.saturating_add(1);
+                screened.accepted.push(index);
+            }
+            Err(e) => screened.rejected.push(RejectedMessage::new(index, &e)),
         }
+    }
+
+    if let Some(abort) = accepted_messages(incoming, &screened.accepted)
+        .find(|m| m.kind == MessageKind::Abort && !misrouted(session, m))
+    {
+        session.aborted = true;
+        return Err(format!(
+            "PEER_ABORTED: party {} aborted the session: {}",
+            abort.sender, abort.payload
+        ));
+    }
+    Ok(screened)
+}
+
+/// Why `msg` can't be taken, if it can't: not for this party under
+/// `on_misrouted: "error"`, a wrong envelope version, binding, digest,
+/// encoding or auth tag, a sender outside the session, over the session's
+/// limits with `in_round` messages already taken in this call, or a
+/// payload that doesn't decode (`MALFORMED_MESSAGE`).
+fn check_incoming(session: &mut SignSession, msg: &WasmSignMessage, in_round: usize) -> Result<(), String> {
+    if session.on_misrouted == OnMisrouted::Error && misrouted(session, msg) {
+        let recipient = match msg.recipient.filter(|_| !msg.is_broadcast) {
+            Some(recipient) => format!("party {recipient}"),
+            None => "everyone".to_string(),
+        };
+        return Err(format!(
+            "MISROUTED_MESSAGE: message from party {} to {recipient} reached party {}",
+            msg.sender, session.party_index
+        ));
+    }
+    if msg.version != MESSAGE_VERSION {
+        return Err(format!(
+            "UNSUPPORTED_MESSAGE_VERSION: message from party {} has version {}, this build speaks {MESSAGE_VERSION}",
+            msg.sender, msg.version
+        ));
+    }
+    binding::check_binding(&session.binding, msg.sender, msg.binding.as_deref())?;
+    if msg.kind == MessageKind::Protocol {
+        session.digest.check(msg.sender, msg.digest.as_deref())?;
+        message_codec::check_encoding(session.encoding, msg.sender, &msg.payload)?;
+    }
+    if let Some(key) = &session.auth_key {
+        key.verify(&msg.auth_envelope(), msg.auth_tag.as_deref())?;
+    }
+    if !session.parties_at_keygen.contains(&msg.sender) {
+        return Err(format!(
+            "UNKNOWN_SENDER: message from party {}, not one of {:?}",
+            msg.sender, session.parties_at_keygen
+        ));
+    }
+    session
+        .limits
+        .check_message(session.received, in_round, msg.sender, msg.payload.len())?;
+    if msg.kind == MessageKind::Protocol && !misrouted(session, msg) {
+        session
+            .sm
+            .check_payload(msg.payload.as_bytes(), &mut session.times)
+            .map_err(|e| format!("MALFORMED_MESSAGE: message from party {}: {e}", msg.sender))?;
+    }
+    Ok(())
+}
 
-        let complete = session.signature.is_some();
-        let signature = session.signature.clone();
+/// The messages of `incoming` that `accepted` lists, in order.
+fn accepted_messages<'a>(
+    incoming: &'a [WasmSignMessage],
+    accepted: &'a [u32],
+) -> impl Iterator<Item = &'a WasmSignMessage> {
+    (0u32..)
+        .zip(incoming)
+        .filter(|(index, _)| accepted.contains(index))
+        .map(|(_, msg)| msg)
+}
+
+/// What [`queue_message`] did with a message.
+#[derive(PartialEq, Eq)]
+enum Queued {
+    Yes,
+    /// From a round the sender has already moved past, or a copy of a
+    /// message already queued
+    Stale,
+    /// Not for this party (see [`misrouted`])
+    NotForUs,
+}
 
+/// Whether `msg` isn't for this party: P2P to another party, or sent under
+/// this party's own index (our own messages reflected back).
+fn misrouted(session: &SignSession, msg: &WasmSignMessage) -> bool {
+    msg.sender == session.party_index
+        || (!msg.is_broadcast && msg.recipient.is_some_and(|recipient| recipient != session.party_index))
+}
+
+/// Queue one accepted message for delivery, unless it is stale or for
+/// someone else.
+fn queue_message(session: &mut SignSession, msg: &WasmSignMessage) -> Result<Queued, String> {
+    if misrouted(session, msg) {
+        if session.on_misrouted == OnMisrouted::Warn {
+            session.misrouted_skipped = session.misrouted_skipped.saturating_add(1);
+        }
+        return Ok(Queued::NotForUs);
+    }
+
+    // Drop anything from a round the sender has already moved past, and
+    // replays or a relay's retries
+    let recipient = msg.recipient.filter(|_| !msg.is_broadcast);
+    if !session.seen.insert(msg.sender, msg.round, recipient, &msg.payload) {
+        return Ok(Queued::Stale);
+    }
+
+    // The state machine knows senders by their position in the signing
+    // group, the wire by keygen index
+    let sender_pos = session
+        .parties_at_keygen
+        .iter()
+        .position(|&p| p == msg.sender)
+        .ok_or_else(|| format!(
+            "unknown sender {} not in parties {:?}",
+            msg.sender, session.parties_at_keygen
+        ))?;
+    let sender_pos = party_limits::index(sender_pos)?;
+    session.pending.push_back((sender_pos, msg.clone()));
+    Ok(Queued::Yes)
+}
+
+/// The result of a call that drove `session`, closing its timings.
+fn round_result(
+    session: &mut SignSession,
+    messages: Vec<WasmSignMessage>,
+    stale_messages: u32,
+) -> ProcessRoundResult {
+    let times = std::mem::take(&mut session.times);
+    let metrics = session.metrics.record_round(&times);
+    let (messages, outgoing_bytes) = release_outgoing(session, messages);
+    ProcessRoundResult {
+        messages,
+        complete: session.signature.is_some(),
+        status: session.status(),
+        progress: session.progress(),
+        signature: session.signature.clone(),
+        stale_messages,
+        misrouted_skipped: std::mem::take(&mut session.misrouted_skipped),
+        accepted: Vec::new(),
+        rejected: Vec::new(),
+        metrics,
+        aborted: false,
+        reason: None,
+        signatures: Vec::new(),
+        audit: session.signature.as_ref().and(session.audit.as_ref()).map(|audit| audit.record().clone()),
+        outgoing_bytes,
+        more: !session.held.is_empty(),
+    }
+}
+
+/// Deliver one incoming message to a single session without driving it;
+/// [`poll`] delivers and drives. Checks are those of [`process_round`].
+///
+/// Returns false if the message was dropped: stale, or not for this
+/// party (see [`OnMisrouted`]; counted in the next result under `"warn"`).
+pub fn feed_message(session_id: &str, msg: &WasmSignMessage) -> Result<bool, String> {
+    with_single_session(session_id, |session| {
+        let screened = screen_incoming(session, std::slice::from_ref(msg))?;
+        if let Some(rejected) = screened.rejected.first() {
+            return Err(rejected.error());
+        }
+        if failing(session, |session| queue_message(session, msg))? != Queued::Yes {
+            return Ok(false);
+        }
+        if !session.feeding {
+            session.feeding = true;
+            session.round = session.round.saturating_add(1);
+            session.log.push(Vec::new());
+        }
+        if let Some(batch) = session.log.last_mut() {
+            batch.push(msg.clone());
+        }
+        Ok(true)
+    })
+}
+
+/// Drive a single session for at most `max_steps` `proceed()` steps (no
+/// limit if `None`), handing it fed messages whenever it asks for one.
+/// Returns the outgoing messages and, once done, the signature.
+///
+/// The messages fed since the session last sent or finished are one relay
+/// round, however many polls deliver them: the first feed of a round bumps
+/// it and opens its log entry, and the poll that delivers the last of them
+/// and gets the session sending (or done) closes it. That way feeding a
+/// round's messages and polling until the session stops asking is exactly
+/// one `process_round` with the same messages, replay log included.
+pub fn poll(session_id: &str, max_steps: Option<u32>) -> Result<ProcessRoundResult, String> {
+    with_single_session(session_id, |session| {
+        session.times = PhaseTimes::default();
+        let messages = failing(session, |session| drive(session, max_steps, None))?;
+        if session.pending.is_empty() && (!messages.is_empty() || session.finished()) {
+            session.feeding = false;
+        }
+        Ok(round_result(session, messages, 0))
+    })
+    .and_then(|result| ask_approval(session_id, result))
+}
+
+/// Run `f` on the non-batch session `session_id`, dropping the session if
+/// a peer's abort turned up.
+fn with_single_session<T>(
+    session_id: &str,
+    f: impl FnOnce(&mut SignSession) -> Result<T, String>,
+) -> Result<T, String> {
+    if is_batch(session_id) {
+        return Err(format!(
+            "UNSUPPORTED: batch session {session_id} is driven by process_round only"
+        ));
+    }
+    SESSIONS.with(|sessions| {
+        let mut sessions = sessions.borrow_mut();
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| format!("no sign session found: {session_id}"))?;
+        session.ensure_usable()?;
+        let result = f(session);
+        if session.aborted {
+            // A peer gave up; nothing can complete this session any more
+            sessions.remove(session_id);
+        }
+        result
+    })
+}
+
+/// Ask the approval callback of the single session `session_id` about a
+/// signature the call that returned `result` withheld, and bring `result`
+/// up to date with the verdict.
+///
+/// The callback runs between two borrows of `SESSIONS`, so it may call
+/// back into the module (see `approval`).
+fn ask_approval(session_id: &str, result: ProcessRoundResult) -> Result<ProcessRoundResult, String> {
+    let question = SESSIONS.with(|sessions| {
+        sessions
+            .borrow_mut()
+            .get_mut(session_id)
+            .and_then(SignSession::approval_question)
+    });
+    let Some((callback, request)) = question else {
+        return Ok(result);
+    };
+    let answer = approval::ask(&callback, &request);
+    with_single_session(session_id, |session| {
+        session.answer_approval(answer)?;
+        Ok(ProcessRoundResult {
+            complete: session.signature.is_some(),
+            status: session.status(),
+            progress: session.progress(),
+            signature: session.signature.clone(),
+            audit: session.signature.as_ref().and(session.audit.as_ref()).map(|audit| audit.record().clone()),
+            ..result
+        })
+    })
+}
+
+/// [`ask_approval`] for the members of the batch `batch_id`. A rejection
+/// fails the whole batch, as any member's failure does.
+fn ask_batch_approvals(batch_id: &str, result: ProcessRoundResult) -> Result<ProcessRoundResult, String> {
+    let questions: Vec<_> = BATCHES.with(|batches| {
+        batches
+            .borrow_mut()
+            .get_mut(batch_id)
+            .map(|members| {
+                members
+                    .iter_mut()
+                    .enumerate()
+                    .filter_map(|(index, member)| Some((index, member.approval_question()?)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+    if questions.is_empty() {
+        return Ok(result);
+    }
+    let answers: Vec<_> = questions
+        .into_iter()
+        .map(|(index, (callback, request))| (index, approval::ask(&callback, &request)))
+        .collect();
+    BATCHES.with(|batches| {
+        let mut batches = batches.borrow_mut();
+        let members = batches
+            .get_mut(batch_id)
+            .ok_or_else(|| format!("no sign session found: {batch_id}"))?;
+        for (index, answer) in answers {
+            if let Err(e) = members[index].answer_approval(answer) {
+                members.iter_mut().for_each(|member| member.fail(&e));
+                return Err(format!("message {index}: {e}"));
+            }
+        }
+        let signatures: Vec<_> = members.iter().map(|member| member.signature.clone()).collect();
         Ok(ProcessRoundResult {
-            messages: all_outgoing,
-            complete,
-            signature,
+            complete: signatures.iter().all(Option::is_some),
+            status: batch_status(members),
+            progress: batch_progress(members),
+            signatures,
+            ..result
         })
     })
 }
 
-/// Destroy a signing session, freeing all resources.
+/// Whether `session_id` (single or batch) is held here, failed or not.
+pub fn is_live(session_id: &str) -> bool {
+    SESSIONS.with(|sessions| sessions.borrow().contains_key(session_id)) || is_batch(session_id)
+}
+
+/// Destroy a signing session (batch sessions included), freeing all
+/// resources.
 pub fn destroy_session(session_id: &str) -> bool {
     SESSIONS.with(|sessions| sessions.borrow_mut().remove(session_id).is_some())
+        || BATCHES.with(|batches| batches.borrow_mut().remove(session_id).is_some())
+}
+
+/// Abandon a session, e.g. after a policy rejection: destroy it like
+/// [`destroy_session`] and return an `abort` message for the caller to relay,
+/// so the peers' next `process_round` fails with `PEER_ABORTED` instead of
+/// waiting for messages that won't come.
+///
+/// `reason` is cut to [`MAX_ABORT_REASON_BYTES`]. A batch session sends
+/// one notice per hash.
+pub fn abort_session(session_id: &str, reason: &str) -> Result<ProcessRoundResult, String> {
+    let (members, batch) = match SESSIONS.with(|sessions| sessions.borrow_mut().remove(session_id)) {
+        Some(session) => (vec![session], false),
+        None => BATCHES
+            .with(|batches| batches.borrow_mut().remove(session_id))
+            .map(|members| (members, true))
+            .ok_or_else(|| format!("no sign session found: {session_id}"))?,
+    };
+
+    let mut end = reason.len().min(MAX_ABORT_REASON_BYTES);
+    while !reason.is_char_boundary(end) {
+        end -= 1;
+    }
+    let reason = &reason[..end];
+    let mut metrics = RoundMetrics::default();
+    let messages: Vec<_> = (0u32..)
+        .zip(members.iter())
+        .map(|(index, session)| {
+            metrics.absorb(&RoundMetrics {
+                total: session.metrics,
+                ..RoundMetrics::default()
+            });
+            session.stamp_auth(WasmSignMessage {
+                version: MESSAGE_VERSION,
+                round: session.round.saturating_add(1),
+                binding: Some(session.binding.clone()),
+                auth_tag: None,
+                kind: MessageKind::Abort,
+                sender: session.party_index,
+                is_broadcast: true,
+                recipient: None,
+                payload: reason.to_string(),
+                message_index: batch.then_some(index),
+                digest: session.digest.tag(),
+            })
+        })
+        .collect();
+    let outgoing_bytes = messages.iter().map(wire_len).sum();
+    Ok(ProcessRoundResult {
+        messages,
+        complete: false,
+        status: SessionStatus::Aborted,
+        progress: batch_progress(&members),
+        signature: None,
+        stale_messages: 0,
+        misrouted_skipped: 0,
+        accepted: Vec::new(),
+        rejected: Vec::new(),
+        metrics,
+        aborted: true,
+        reason: Some(reason.to_string()),
+        signatures: Vec::new(),
+        audit: None,
+        outgoing_bytes,
+        more: false,
+    })
+}
+
+/// Status of a single session or a batch: failed if any member failed,
+/// complete once all are, computing if any is.
+fn batch_status(members: &[SignSession]) -> SessionStatus {
+    let statuses: Vec<_> = members.iter().map(SignSession::status).collect();
+    if statuses.contains(&SessionStatus::Failed) {
+        SessionStatus::Failed
+    } else if statuses.iter().all(|&s| s == SessionStatus::Complete) {
+        SessionStatus::Complete
+    } else if statuses.contains(&SessionStatus::Computing) {
+        SessionStatus::Computing
+    } else {
+        SessionStatus::Waiting
+    }
+}
+
+/// Average [`SignSession::progress`] over a batch's members.
+fn batch_progress(members: &[SignSession]) -> f32 {
+    members.iter().map(SignSession::progress).sum::<f32>() / members.len().max(1) as f32
+}
+
+/// Liveness of a session, for hosts polling from a timer.
+#[derive(Serialize, Deserialize, Tsify, Debug)]
+pub struct Heartbeat {
+    /// False if no such session exists (destroyed, aborted or never
+    /// created); the other fields are then zero.
+    pub alive: bool,
+    pub status: SessionStatus,
+    /// Relay round reached
+    pub round: u16,
+    /// `proceed()` steps taken so far (batches: summed over members)
+    pub steps: u64,
+    /// See `ProcessRoundResult.progress`
+    pub progress: f32,
+    /// Time since the last step; growing while `status` is `computing`
+    /// means the host stopped polling, while `waiting` it means the peers
+    /// are quiet
+    pub last_progress_ms_ago: f64,
+}
+
+/// Report whether `session_id` (single or batch) is alive and how far it
+/// has got. Never fails: an unknown session is reported as not alive.
+///
+/// Steps only run inside `process_round` and [`poll`], so a session is
+/// only seen moving mid-round when it is polled a few steps at a time.
+pub fn heartbeat(session_id: &str) -> Heartbeat {
+    let now = clock::now_ms();
+    let beat = |members: &[SignSession]| {
+        let status = batch_status(members);
+        let last_step_ms = members.iter().map(|m| m.last_step_ms).fold(f64::MIN, f64::max);
+        Heartbeat {
+            alive: true,
+            status,
+            round: members.iter().map(|m| m.round).min().unwrap_or(0),
+            steps: members.iter().map(|m| m.steps).sum(),
+            progress: batch_progress(members),
+            last_progress_ms_ago: (now - last_step_ms).max(0.0),
+        }
+    };
+    SESSIONS
+        .with(|sessions| sessions.borrow().get(session_id).map(|s| beat(std::slice::from_ref(s))))
+        .or_else(|| BATCHES.with(|batches| batches.borrow().get(session_id).map(|m| beat(m))))
+        .unwrap_or(Heartbeat {
+            alive: false,
+            status: SessionStatus::Waiting,
+            round: 0,
+            steps: 0,
+            progress: 0.0,
+            last_progress_ms_ago: 0.0,
+        })
+}
+
+/// Where a session stands, and why if it failed.
+#[derive(Serialize, Deserialize, Tsify, Debug)]
+pub struct SessionState {
+    pub status: SessionStatus,
+    /// First error of a `failed` session (batches: of the first failed
+    /// member)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub reason: Option<String>,
+}
+
+/// Status of `session_id` (single or batch). Unlike the other calls this
+/// still answers for a failed session, so a host can tell it apart from a
+/// destroyed one.
+pub fn session_status(session_id: &str) -> Result<SessionState, String> {
+    let state = |members: &[SignSession]| SessionState {
+        status: batch_status(members),
+        reason: members.iter().find_map(|m| m.failed.clone()),
+    };
+    SESSIONS
+        .with(|sessions| sessions.borrow().get(session_id).map(|s| state(std::slice::from_ref(s))))
+        .or_else(|| BATCHES.with(|batches| batches.borrow().get(session_id).map(|m| state(m))))
+        .ok_or_else(|| format!("no sign session found: {session_id}"))
+}
+
+/// The audit record of an ECDSA session: started at creation, complete
+/// with `r`, `s`, `v` and the transcript hash once the session is.
+pub fn audit_record(session_id: &str) -> Result<AuditRecord, String> {
+    if is_batch(session_id) {
+        return Err(format!(
+            "UNSUPPORTED: batch session {session_id} keeps one audit record per member, in its results"
+        ));
+    }
+    SESSIONS.with(|sessions| {
+        let sessions = sessions.borrow();
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| format!("no sign session found: {session_id}"))?;
+        session
+            .audit
+            .as_ref()
+            .map(|audit| audit.record().clone())
+            .ok_or_else(|| format!("UNSUPPORTED: session {session_id} keeps no audit record (FROST)"))
+    })
+}
+
+/// Cumulative timings of a live session.
+pub fn session_metrics(session_id: &str) -> Result<SessionMetrics, String> {
+    SESSIONS.with(|sessions| {
+        sessions
+            .borrow()
+            .get(session_id)
+            .map(|session| session.metrics)
+            .ok_or_else(|| format!("no sign session found: {session_id}"))
+    })
+}
+
+/// Version of the [`export_session`] format.
+const EXPORT_VERSION: u8 = 1;
+
+/// A session as a replay log. The state machine can't be serialised, so
+/// the export holds what built it (the recipe, its rng seed included) and
+/// every incoming batch it accepted since; fed again under the same seed,
+/// they retrace the same protocol position.
+#[derive(Serialize, Deserialize)]
+struct SessionExport {
+    version: u8,
+    session_id: String,
+    recipe: SessionRecipe,
+    limits: MessageLimits,
+    /// Incoming batches accepted by `process_round`, in order
+    rounds: Vec<Vec<WasmSignMessage>>,
+    /// Relay round reached; the replay must end on the same one
+    round: u16,
+}
+
+/// Serialise a live session so it can be resumed with [`import_session`],
+/// e.g. after a page reload. The session stays live.
+///
+/// The export holds the session's rng seed and, with
+/// `include_key_material`, the key share: store it like the share itself.
+/// Without, an ECDSA export names the key by its id (`share_fingerprint`)
+/// and the import fails with `KEY_NOT_FOUND` unless that key is registered;
+/// FROST sessions fail with `UNSUPPORTED`. Import it at most once — two
+/// copies of one session fed different messages would reuse the same nonces.
+pub fn export_session(session_id: &str, include_key_material: bool) -> Result<Vec<u8>, String> {
+    if is_batch(session_id) {
+        return Err(format!("UNSUPPORTED: batch session {session_id} can't be exported"));
+    }
+    SESSIONS.with(|sessions| {
+        let sessions = sessions.borrow();
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| format!("no sign session found: {session_id}"))?;
+        session.ensure_usable()?;
+        if session.feeding && session.signature.is_none() {
+            // The replay would close the round the import then carries on
+            return Err(format!(
+                "UNSUPPORTED: session {session_id} is mid-round; feed and poll the rest of the round before exporting"
+            ));
+        }
+        if !session.pending.is_empty() || !(session.awaiting_input || session.signature.is_some()) {
+            // A replay drives every batch to the end; stopping short of
+            // that would lose messages this session hasn't produced yet
+            return Err(format!(
+                "UNSUPPORTED: session {session_id} is mid-poll; poll it until it needs a message before exporting"
+            ));
+        }
+        if session.approval.is_some() {
+            // The callback can't travel in the export; an import would
+            // release the signature unchecked
+            return Err(format!(
+                "UNSUPPORTED: session {session_id} has an approval_callback and can't be exported"
+            ));
+        }
+        if !session.held.is_empty() {
+            // Held messages aren't part of the export and would be lost
+            return Err(format!(
+                "UNSUPPORTED: session {session_id} holds messages over max_outgoing_bytes; \
+                 fetch them with process_round before exporting"
+            ));
+        }
+        let mut recipe = session.recipe.clone();
+        if !include_key_material {
+            recipe.strip_key_material()?;
+        }
+        let export = SessionExport {
+            version: EXPORT_VERSION,
+            session_id: session_id.to_string(),
+            recipe,
+            limits: session.limits,
+            rounds: session.log.clone(),
+            round: session.round,
+        };
+        serde_json::to_vec(&export).map_err(|e| format!("serialize session export: {e}"))
+    })
+}
+
+/// Rebuild a session from [`export_session`] output and register it under
+/// its original ID, which is returned.
+///
+/// Replays every recorded batch against a fresh state machine; the
+/// messages it produces along the way were already sent and are dropped.
+/// The next `process_round` continues where the exported session stood.
+pub fn import_session(bytes: &[u8]) -> Result<String, String> {
+    let mut export: SessionExport = serde_json::from_slice(bytes)
+        .map_err(|e| format!("INVALID_SESSION_EXPORT: {e}"))?;
+    if export.version != EXPORT_VERSION {
+        return Err(format!(
+            "INVALID_SESSION_EXPORT: export version {}, this build reads {EXPORT_VERSION}",
+            export.version
+        ));
+    }
+    if SESSIONS.with(|sessions| sessions.borrow().contains_key(&export.session_id)) {
+        return Err(format!("SESSION_EXISTS: session {} is already live", export.session_id));
+    }
+    check_session_cap(1)?;
+    export.recipe.restore_key_material()?;
+
+    let mut session = match &export.recipe {
+        SessionRecipe::Ecdsa(recipe) => build_session(recipe)?,
+        SessionRecipe::Frost(recipe) => frost::build_session(recipe)?,
+    };
+    session.limits = export.limits;
+    drive(&mut session, None, None)?;
+    let times = std::mem::take(&mut session.times);
+    session.metrics.record_start(&times);
+    for batch in &export.rounds {
+        apply_round(&mut session, batch, None)?;
+    }
+    if session.round != export.round {
+        return Err(format!(
+            "INVALID_SESSION_EXPORT: replay ended at round {}, export was taken at round {}",
+            session.round, export.round
+        ));
+    }
+    // The replay's messages were sent before the export, held ones included
+    session.held.clear();
+    session.log = export.rounds;
+
+    SESSIONS.with(|sessions| {
+        sessions.borrow_mut().insert(export.session_id.clone(), session);
+    });
+    Ok(export.session_id)
 }
 
 // ---------------------------------------------------------------------------
 // Internal helpers
 // ---------------------------------------------------------------------------
 
-/// Drive the state machine until it needs input or produces output.
-/// Collects all outgoing messages produced along the way.
-fn drive_batch(session: &mut SignSession) -> Result<Vec<WasmSignMessage>, String> {
+/// Drive the state machine for up to `max_steps` `proceed()` steps (no
+/// limit if `None`), delivering the next queued message whenever it needs
+/// one. Stops when it needs a message and none is queued, or finishes —
+/// or, after at least one step, once `clock::now_ms` passes `deadline`.
+///
+/// A state machine that keeps yielding without sending, asking for input
+/// or finishing is broken, not busy: the session fails with
+/// `DRIVE_LOOP_STALLED` (see `drive_guard`).
+///
+/// Returns the outgoing messages.
+fn drive(
+    session: &mut SignSession,
+    max_steps: Option<u32>,
+    deadline: Option<f64>,
+) -> Result<Vec<WasmSignMessage>, String> {
     let mut messages = Vec::new();
+    let mut steps = 0u32;
 
-    loop {
-        match session.sm.drive_one(session.party_index)? {
-            DriveOneResult::SendMsg(mpc_msg) => {
-                let wasm_msg = mpc_msg_to_wasm(mpc_msg, &session.parties_at_keygen);
-                messages.push(wasm_msg);
-                // Continue driving
-            }
-            DriveOneResult::NeedsInput => {
+    if session.approval.as_ref().is_some_and(Approval::is_pending) {
+        let verdict = session.approval.as_mut().map_or(Verdict::Pending, Approval::poll);
+        apply_verdict(session, verdict)?;
+    }
+
+    while !session.finished()
+        && max_steps.map_or(true, |max| steps < max)
+        && deadline.map_or(true, |deadline| steps == 0 || clock::now_ms() < deadline)
+    {
+        if session.awaiting_input {
+            let Some((sender_pos, msg)) = session.pending.pop_front() else {
                 // State machine needs more messages — stop driving
                 break;
+            };
+            let msg_type: u8 = if msg.is_broadcast { 0 } else { 1 };
+            session
+                .sm
+                .receive_msg(sender_pos, msg_type, msg.payload.as_bytes(), &mut session.times)?;
+            session.times.messages += 1;
+            session.awaiting_input = false;
+            if let Some(audit) = &mut session.audit {
+                audit.message(msg.sender, msg.recipient.filter(|_| !msg.is_broadcast), &msg.payload);
             }
-            DriveOneResult::Finished(sig) => {
-                session.signature = Some(sig);
-                break;
+        }
+
+        steps += 1;
+        let step = session
+            .sm
+            .drive_one(session.party_index, &mut session.times)
+            .map_err(|e| protocol_failure::stamp_round(e, session.round))?;
+        session.steps += 1;
+        session.last_step_ms = clock::now_ms();
+        session
+            .yields
+            .step(matches!(step, DriveOneResult::Yielded))
+            .map_err(|e| protocol_failure::stamp_round(e, session.round))?;
+        match step {
+            DriveOneResult::SendMsg(mpc_msg) => {
+                let wasm_msg = mpc_msg_to_wasm(mpc_msg, session);
+                if let Some(audit) = &mut session.audit {
+                    audit.message(wasm_msg.sender, wasm_msg.recipient, &wasm_msg.payload);
+                }
+                messages.push(wasm_msg);
             }
-            DriveOneResult::Yielded => {
-                // Continue driving
+            DriveOneResult::NeedsInput => session.awaiting_input = true,
+            DriveOneResult::Finished(sig) => {
+                session.release_eid();
+                TYPICAL_STEPS.with(|typical| typical.borrow_mut().insert(session.protocol, session.steps));
+                // The caller asks the approval callback, with the session
+                // store unborrowed (see `ask_approval`)
+                if let Some(approval) = &mut session.approval {
+                    approval.withhold(sig);
+                } else {
+                    session.release_signature(sig);
+                }
             }
+            DriveOneResult::Yielded => {}
         }
     }
 
     Ok(messages)
 }
 
+/// Act on the approval verdict for a finished session's signature:
+/// release it, keep waiting, or fail with `POLICY_REJECTED`.
+fn apply_verdict(session: &mut SignSession, verdict: Verdict) -> Result<(), String> {
+    match verdict {
+        Verdict::Approved(sig) => {
+            session.release_signature(sig);
+            Ok(())
+        }
+        Verdict::Pending => Ok(()),
+        Verdict::Rejected(reason) => Err(reason),
+    }
+}
+
 /// Convert an internal MpcMessage to a WasmSignMessage for the wire format.
 ///
 /// The protocol's `MessageDestination::OneParty(p)` uses 0-based position
 /// indices within the signing group. We map these to keygen indices using
 /// the `parties` array so the wire format uses consistent keygen indices.
-fn mpc_msg_to_wasm(msg: MpcMessage, parties: &[u16]) -> WasmSignMessage {
-    let (is_broadcast, recipient) = match &msg.recipient {
-        MpcRecipient::Broadcast(_) => (true, None),
-        MpcRecipient::Party(p) => {
+fn mpc_msg_to_wasm(msg: MpcMessage, session: &SignSession) -> WasmSignMessage {
+    let parties = &session.parties_at_keygen;
+    let (is_broadcast, recipient) = match msg.recipient {
+        MpcRecipient::Broadcast => (true, None),
+        MpcRecipient::Party { index: p } => {
             // Map position → keygen index
-            let keygen_idx = parties.get(*p as usize).copied().unwrap_or(*p);
+            let keygen_idx = parties.get(usize::from(p)).copied().unwrap_or(p);
             (false, Some(keygen_idx))
         }
     };
-    WasmSignMessage {
+    session.stamp_auth(WasmSignMessage {
+        version: MESSAGE_VERSION,
+        round: session.round,
+        binding: Some(session.binding.clone()),
+        auth_tag: None,
+        kind: MessageKind::Protocol,
         sender: msg.sender,
         is_broadcast,
         recipient,
         payload: msg.payload,
-    }
+        message_index: None,
+        digest: session.digest.tag(),
+    })
 }
 
 /// Generate a v4 UUID (random) without pulling in the uuid crate.
-fn uuid_v4() -> String {
+fn uuid_v4() -> Result<String, String> {
     let mut bytes = [0u8; 16];
-    getrandom::getrandom(&mut bytes).expect("getrandom failed");
+    entropy::os_fill(&mut bytes)?;
     // Set version 4
     bytes[6] = (bytes[6] & 0x0f) | 0x40;
     // Set variant
     bytes[8] = (bytes[8] & 0x3f) | 0x80;
 
-    format!(
+    Ok(format!(
         "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
         bytes[0], bytes[1], bytes[2], bytes[3],
         bytes[4], bytes[5],
         bytes[6], bytes[7],
         bytes[8], bytes[9],
         bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15],
-    )
+    ))
+}
+
+#[cfg(all(test, feature = "insecure-dev"))]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn a_failed_session_wipes_its_key_material() {
+        let created = test_support::create(0, &[0, 1], &test_support::eid("wipe"), &SignOptions::default());
+        SESSIONS.with(|sessions| {
+            let mut sessions = sessions.borrow_mut();
+            let session = sessions.get_mut(&created.session_id).unwrap();
+            session.fail("test");
+            let SessionRecipe::Ecdsa(recipe) = &session.recipe else {
+                panic!("not an ECDSA session");
+            };
+            assert!(recipe.core_share.is_empty());
+            assert!(recipe.aux_info.is_empty());
+            assert_eq!(recipe.seed, [0; 32]);
+        });
+        assert!(process_round(&created.session_id, &[], None)
+            .unwrap_err()
+            .starts_with("SESSION_FAILED"));
+    }
+
+    #[test]
+    fn each_batch_member_counts_against_the_session_cap() {
+        config::update(&config::ConfigUpdate { max_sessions: Some(3.0), ..Default::default() }).unwrap();
+        let share = &test_support::key().shares[0];
+        let batch = |hashes: usize, eid: &str| {
+            let hashes = vec![crate::test_vectors::MESSAGE_HASH.to_vec(); hashes];
+            let eid = test_support::eid(eid);
+            create_batch_session(&share.core_share, &share.aux_info, &hashes, 0, &[0, 1], &eid, &SignOptions::default())
+        };
+
+        let error = batch(4, "cap: 4 of 3").err().unwrap();
+        assert!(error.starts_with("TOO_MANY_SESSIONS: 0 "), "{error}");
+        batch(2, "cap: 2 of 3").unwrap();
+        let error = batch(2, "cap: 2 more").err().unwrap();
+        assert!(error.starts_with("TOO_MANY_SESSIONS: 2 "), "{error}");
+        test_support::create(0, &[0, 1], &test_support::eid("cap: the third"), &SignOptions::default());
+        let error = create_session(
+            &share.core_share,
+            &share.aux_info,
+            SignData::Prehashed(&crate::test_vectors::MESSAGE_HASH),
+            0,
+            &[0, 1],
+            &test_support::eid("cap: a fourth"),
+            &SignOptions::default(),
+        )
+        .err().unwrap();
+        assert!(error.starts_with("TOO_MANY_SESSIONS: 3 "), "{error}");
+    }
+
+    #[test]
+    fn a_message_from_before_version_and_round_reads_as_version_1_round_0() {
+        let signers = [0, 1];
+        let (ids, first) = test_support::create_all(&signers, &test_support::eid("old format"));
+        let old_format: Vec<WasmSignMessage> = test_support::inbox(0, &signers, &first)
+            .iter()
+            .map(|msg| {
+                let mut json = serde_json::to_value(msg).unwrap();
+                let fields = json.as_object_mut().unwrap();
+                fields.remove("version");
+                fields.remove("round");
+                serde_json::from_value(json).unwrap()
+            })
+            .collect();
+        assert!(old_format.iter().all(|msg| (msg.version, msg.round) == (MESSAGE_VERSION, 0)));
+
+        let result = process_round(&ids[0], &old_format, None).unwrap();
+        test_support::assert_no_rejections(&result);
+        assert_eq!(result.accepted.len(), old_format.len());
+    }
+
+    #[test]
+    fn a_message_of_a_future_version_is_rejected() {
+        let signers = [0, 1];
+        let (ids, first) = test_support::create_all(&signers, &test_support::eid("future version"));
+        let mut inbox = test_support::inbox(0, &signers, &first);
+        inbox[0].version = MESSAGE_VERSION + 1;
+
+        let result = process_round(&ids[0], &inbox, None).unwrap();
+        assert_eq!(result.rejected.len(), 1);
+        assert_eq!(result.rejected[0].index, 0);
+        assert_eq!(result.rejected[0].code, "UNSUPPORTED_MESSAGE_VERSION");
+        assert!(!result.accepted.contains(&0));
+    }
+
+    #[test]
+    fn a_duplicate_is_stale_and_the_signing_still_completes() {
+        let signers = [0, 1];
+        let (ids, first) = test_support::create_all(&signers, &test_support::eid("duplicate"));
+        let inbox = test_support::inbox(0, &signers, &first);
+        let twice: Vec<WasmSignMessage> = inbox.iter().chain(&inbox).cloned().collect();
+
+        let from_0 = process_round(&ids[0], &twice, None).unwrap();
+        test_support::assert_no_rejections(&from_0);
+        assert_eq!(from_0.accepted.len(), twice.len());
+        assert_eq!(from_0.stale_messages as usize, inbox.len());
+        let from_1 = process_round(&ids[1], &test_support::inbox(1, &signers, &first), None).unwrap();
+
+        let signatures = test_support::run(&signers, &ids, [from_0.messages, from_1.messages].concat());
+        assert!(signatures.iter().all(test_support::verifies));
+    }
+
+    #[test]
+    fn a_message_from_an_earlier_round_than_one_delivered_is_stale() {
+        let signers = [0, 1];
+        let (ids, first) = test_support::create_all(&signers, &test_support::eid("reordered"));
+        // Party 1 moves on, and its next messages reach party 0 ahead of
+        // a replay of its first ones
+        let second = process_round(&ids[1], &test_support::inbox(1, &signers, &first), None)
+            .unwrap()
+            .messages;
+        let replayed = test_support::inbox(0, &signers, &first);
+        let reordered: Vec<WasmSignMessage> = test_support::inbox(0, &signers, &second)
+            .into_iter()
+            .chain(replayed.iter().cloned())
+            .collect();
+
+        let from_0 = process_round(&ids[0], &reordered, None).unwrap();
+        test_support::assert_no_rejections(&from_0);
+        assert_eq!(from_0.accepted.len(), reordered.len());
+        assert_eq!(from_0.stale_messages as usize, replayed.len());
+    }
+
+    #[test]
+    fn feeding_and_polling_a_step_at_a_time_is_process_round() {
+        let signers = [0, 1];
+        let (ids, first) = test_support::create_all(&signers, &test_support::eid("feed and poll"));
+        let exports: Vec<Vec<u8>> = ids.iter().map(|id| export_session(id, true).unwrap()).collect();
+
+        let by_process_round = test_support::run(&signers, &ids, first.clone());
+        let log = export_session(&ids[0], true).unwrap();
+        for (id, export) in ids.iter().zip(&exports) {
+            assert!(destroy_session(id));
+            import_session(export).unwrap();
+        }
+
+        // The same sessions again, party 0 fed one message at a time and
+        // polled one step at a time
+        let mut messages = first;
+        let mut signatures = [None, None];
+        for _ in 0..16 {
+            if signatures.iter().all(Option::is_some) {
+                break;
+            }
+            let mut sent = Vec::new();
+            if signatures[0].is_none() {
+                for msg in test_support::inbox(0, &signers, &messages) {
+                    assert!(feed_message(&ids[0], &msg).unwrap());
+                    sent.extend(poll(&ids[0], Some(1)).unwrap().messages);
+                }
+                loop {
+                    let polled = poll(&ids[0], Some(1)).unwrap();
+                    sent.extend(polled.messages);
+                    signatures[0] = polled.signature;
+                    if polled.status != SessionStatus::Computing {
+                        break;
+                    }
+                }
+            }
+            if signatures[1].is_none() {
+                let result = process_round(&ids[1], &test_support::inbox(1, &signers, &messages), None).unwrap();
+                sent.extend(result.messages);
+                signatures[1] = result.signature;
+            }
+            messages = sent;
+        }
+
+        for (sig, expected) in signatures.iter().zip(&by_process_round) {
+            let sig = sig.as_ref().expect("signing did not complete");
+            assert_eq!((&sig.r, &sig.s), (&expected.r, &expected.s));
+        }
+        assert_eq!(export_session(&ids[0], true).unwrap(), log);
+    }
+
+    #[test]
+    fn a_batch_reports_each_message_and_the_signing_still_completes() {
+        let signers = [0, 1];
+        let (ids, first) = test_support::create_all(&signers, &test_support::eid("mixed batch"));
+        let inbox = test_support::inbox(0, &signers, &first);
+        // A copy of a message already in the batch, and one bound to
+        // another session
+        let mut foreign = inbox[0].clone();
+        foreign.binding = Some("another session".into());
+        let batch: Vec<WasmSignMessage> = inbox.iter().cloned().chain([inbox[0].clone(), foreign]).collect();
+
+        let from_0 = process_round(&ids[0], &batch, None).unwrap();
+        let last = batch.len() as u32 - 1;
+        assert_eq!(from_0.accepted, (0..last).collect::<Vec<u32>>());
+        assert_eq!(from_0.rejected.len(), 1);
+        assert_eq!(from_0.rejected[0].index, last);
+        assert_eq!(from_0.rejected[0].code, "SESSION_BINDING_MISMATCH");
+        assert_eq!(from_0.stale_messages, 1);
+        let from_1 = process_round(&ids[1], &test_support::inbox(1, &signers, &first), None).unwrap();
+
+        let signatures = test_support::run(&signers, &ids, [from_0.messages, from_1.messages].concat());
+        assert!(signatures.iter().all(test_support::verifies));
+    }
+
+    #[test]
+    fn a_corrupted_message_mid_batch_is_rejected_alone() {
+        let signers = [0, 1, 2];
+        let (ids, first) = test_support::create_all(&signers, &test_support::eid("corrupted"));
+        let inbox = test_support::inbox(0, &signers, &first);
+        let mut corrupted = inbox[0].clone();
+        // Whole base64 quanta, so only the protocol message fails to decode
+        corrupted.payload.truncate(corrupted.payload.len() / 8 * 4);
+
+        let batch = [inbox[0].clone(), corrupted, inbox[1].clone()];
+        let from_0 = process_round(&ids[0], &batch, None).unwrap();
+        assert_eq!(from_0.accepted, [0, 2]);
+        assert_eq!(from_0.rejected.len(), 1);
+        assert_eq!(from_0.rejected[0].index, 1);
+        assert_eq!(from_0.rejected[0].code, "MALFORMED_MESSAGE");
+        let rest = process_round(&ids[0], &inbox[2..], None).unwrap();
+        test_support::assert_no_rejections(&rest);
+
+        let mut messages = [from_0.messages, rest.messages].concat();
+        for (&party, id) in signers.iter().zip(&ids).skip(1) {
+            let result = process_round(id, &test_support::inbox(party, &signers, &first), None).unwrap();
+            messages.extend(result.messages);
+        }
+        let signatures = test_support::run(&signers, &ids, messages);
+        assert!(signatures.iter().all(test_support::verifies));
+    }
+
+    #[test]
+    fn a_session_exported_after_round_1_and_imported_signs_identically() {
+        let signers = [0, 1];
+        let (ids, first) = test_support::create_all(&signers, &test_support::eid("resume"));
+        // Party 1 at creation, to run it again unchanged
+        let party_1 = export_session(&ids[1], true).unwrap();
+        let mut round_1 = Vec::new();
+        for (&party, id) in signers.iter().zip(&ids) {
+            round_1.extend(process_round(id, &test_support::inbox(party, &signers, &first), None).unwrap().messages);
+        }
+        let party_0 = export_session(&ids[0], true).unwrap();
+        let uninterrupted = test_support::run(&signers, &ids, round_1.clone());
+
+        // The instance loses every session; party 0 resumes from its
+        // export, party 1 retraces round 1
+        for id in &ids {
+            assert!(destroy_session(id));
+        }
+        assert_eq!(import_session(&party_0).unwrap(), ids[0]);
+        assert_eq!(import_session(&party_1).unwrap(), ids[1]);
+        process_round(&ids[1], &test_support::inbox(1, &signers, &first), None).unwrap();
+        let resumed = test_support::run(&signers, &ids, round_1);
+
+        for (sig, expected) in resumed.iter().zip(&uninterrupted) {
+            assert_eq!((&sig.r, &sig.s), (&expected.r, &expected.s));
+        }
+        assert!(resumed.iter().all(test_support::verifies));
+    }
 }
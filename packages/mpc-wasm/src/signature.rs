@@ -0,0 +1,209 @@
+//! ECDSA and BIP-340 Schnorr signature helpers.
+//!
+//! Plain signature arithmetic over `generic-ec`, independent of the threshold
+//! protocols. Used to self-check signatures before they leave a signing
+//! session (WASM and native-gen compile this module).
+
+use generic_ec::coords::{Coordinate, HasAffineX, HasAffineXAndParity, Parity};
+use generic_ec::curves::Secp256k1;
+use generic_ec::{Curve, Point, Scalar};
+use sha2::{Digest, Sha256};
+
+use crate::hex_codec;
+
+/// Verify an ECDSA signature `(r, s)` over a prehashed message scalar `z`.
+///
+/// Standard verification: `R = z/s·G + r/s·Q`, accept iff `R.x mod n == r`.
+pub fn verify_prehashed<E: Curve>(
+    public_key: &Point<E>,
+    message: &Scalar<E>,
+    r: &Scalar<E>,
+    s: &Scalar<E>,
+) -> bool
+where
+    Point<E>: HasAffineX<E>,
+{
+    if r.is_zero() || s.is_zero() || public_key.is_zero() {
+        return false;
+    }
+    let Some(s_inv) = s.invert() else {
+        return false;
+    };
+    let u1 = message * s_inv;
+    let u2 = r * s_inv;
+    let point = Point::generator() * u1 + public_key * u2;
+    match point.x() {
+        Some(x) => x.to_scalar() == *r,
+        None => false,
+    }
+}
+
+/// Check a serialised `r || s` signature against `public_key` and `message`.
+///
+/// Fails with `SIGNATURE_VERIFY_FAILED` and enough context (public key,
+/// message, signature) to diagnose a stale parties list or mismatched shares.
+pub fn ensure_valid<E: Curve>(
+    public_key: &Point<E>,
+    message: &Scalar<E>,
+    sig_bytes: &[u8],
+) -> Result<(), String>
+where
+    Point<E>: HasAffineX<E>,
+{
+    let (r_bytes, s_bytes) = sig_bytes.split_at(sig_bytes.len() / 2);
+    let valid = match (Scalar::<E>::from_be_bytes(r_bytes), Scalar::<E>::from_be_bytes(s_bytes)) {
+        (Ok(r), Ok(s)) => verify_prehashed(public_key, message, &r, &s),
+        _ => false,
+    };
+    if valid {
+        return Ok(());
+    }
+    Err(format!(
+        "SIGNATURE_VERIFY_FAILED: signature r={} s={} does not verify over message {} \
+         for public key {} (check parties_at_keygen and key material)",
+        hex_codec::encode(r_bytes),
+        hex_codec::encode(s_bytes),
+        hex_codec::encode(message.to_be_bytes().as_bytes()),
+        hex_codec::encode(public_key.to_bytes(true).as_bytes()),
+    ))
+}
+
+/// Bring the `s` half of a serialised `r || s` signature to its low form:
+/// `n - s` when `s > n / 2`, as Ethereum (EIP-2) and Bitcoin (BIP-62)
+/// require. Both forms verify; the flip also flips the signature's
+/// recovery id, so find that after normalising. Returns whether `s` was
+/// flipped; fails with `INVALID_SIGNATURE` if `r` or `s` isn't a nonzero
+/// scalar.
+pub fn normalize_s<E: Curve>(sig_bytes: &mut [u8]) -> Result<bool, String> {
+    let (r_bytes, s_bytes) = sig_bytes.split_at_mut(sig_bytes.len() / 2);
+    let scalar = |name: &str, bytes: &[u8]| {
+        Scalar::<E>::from_be_bytes(bytes)
+            .ok()
+            .filter(|x| !x.is_zero())
+            .ok_or_else(|| format!("INVALID_SIGNATURE: {name} must be nonzero and below the curve order"))
+    };
+    scalar("r", r_bytes)?;
+    let s = scalar("s", s_bytes)?;
+    // n - s < s exactly when s is in the high half
+    let negated = -s;
+    if negated.to_be_bytes().as_bytes() >= s.to_be_bytes().as_bytes() {
+        return Ok(false);
+    }
+    s_bytes.copy_from_slice(negated.to_be_bytes().as_bytes());
+    Ok(true)
+}
+
+/// Recovery id (`0` / `1`: the parity of `R.y`) of a valid serialised
+/// `r || s` signature, found by recovering the key for each parity and
+/// comparing with `public_key`. `None` if neither recovers it — including
+/// the negligible case `R.x ≥ n`, which would need ids 2 / 3.
+pub fn recovery_id<E: Curve>(public_key: &Point<E>, message: &Scalar<E>, sig_bytes: &[u8]) -> Option<u8> {
+    (0u8..2).find(|&id| recover_public_key(message, sig_bytes, id).is_some_and(|key| key == *public_key))
+}
+
+/// The public key a serialised `r || s` signature over `message` recovers
+/// to with `recovery_id` (`0` / `1`: the parity of `R.y`). `None` if the
+/// signature is malformed or recovers no key.
+pub fn recover_public_key<E: Curve>(message: &Scalar<E>, sig_bytes: &[u8], recovery_id: u8) -> Option<Point<E>> {
+    if recovery_id > 1 {
+        return None;
+    }
+    let (r_bytes, s_bytes) = sig_bytes.split_at(sig_bytes.len() / 2);
+    let r = Scalar::<E>::from_be_bytes(r_bytes).ok()?;
+    let s = Scalar::<E>::from_be_bytes(s_bytes).ok()?;
+    let r_inv = r.invert()?;
+    // Q = r⁻¹·(s·R − z·G) for the candidate R with that y parity
+    let mut encoded = Vec::with_capacity(1 + r_bytes.len());
+    encoded.push(0x02 | recovery_id);
+    encoded.extend_from_slice(r_bytes);
+    let big_r = Point::<E>::from_bytes(&encoded).ok()?;
+    let key = (big_r * s - Point::generator() * message) * r_inv;
+    (!key.is_zero()).then_some(key)
+}
+
+/// Recovery id from an Ethereum-style `v`: the id itself (0 / 1), 27 / 28,
+/// or an EIP-155 `chain_id * 2 + 35 + id`. Fails with `INVALID_RECOVERY_ID`
+/// for anything else.
+pub fn recovery_id_from_v(v: u64) -> Result<u8, String> {
+    match v {
+        0 | 1 => Ok(v as u8),
+        27 | 28 => Ok((v - 27) as u8),
+        35.. => Ok(((v - 35) % 2) as u8),
+        _ => Err(format!("INVALID_RECOVERY_ID: expected 0, 1, 27, 28 or an EIP-155 v, got {v}")),
+    }
+}
+
+/// EIP-155 `v` for a legacy transaction: `chain_id * 2 + 35 + recovery_id`.
+///
+/// Fails with `INVALID_RECOVERY_ID` unless `recovery_id` is 0 or 1, and
+/// with `CHAIN_ID_OVERFLOW` if the result doesn't fit in a u64.
+pub fn eip155_v(recovery_id: u8, chain_id: u64) -> Result<u64, String> {
+    if recovery_id > 1 {
+        return Err(format!("INVALID_RECOVERY_ID: expected 0 or 1, got {recovery_id}"));
+    }
+    chain_id
+        .checked_mul(2)
+        .and_then(|v| v.checked_add(35 + u64::from(recovery_id)))
+        .ok_or_else(|| format!("CHAIN_ID_OVERFLOW: chain id {chain_id} is too large for EIP-155"))
+}
+
+/// Verify a BIP-340 signature `R.x || s` over `message` under the x-only
+/// form of `public_key` (the key is negated if its y is odd, as BIP-340
+/// signers do).
+pub fn verify_bip340(public_key: &Point<Secp256k1>, message: &[u8], sig_bytes: &[u8]) -> bool {
+    if sig_bytes.len() != 64 {
+        return false;
+    }
+    let Some((pk_x, parity)) = public_key.x_and_parity() else {
+        return false;
+    };
+    let public_key = match parity {
+        Parity::Even => *public_key,
+        Parity::Odd => -*public_key,
+    };
+    let (r_bytes, s_bytes) = sig_bytes.split_at(32);
+    let (Ok(r), Ok(s)) = (
+        Coordinate::<Secp256k1>::from_be_bytes(r_bytes),
+        Scalar::<Secp256k1>::from_be_bytes(s_bytes),
+    ) else {
+        return false;
+    };
+
+    // e = int(tagged_hash("BIP0340/challenge", R.x || P.x || m)) mod n
+    let tag = Sha256::digest(b"BIP0340/challenge");
+    let challenge = Sha256::new()
+        .chain_update(tag)
+        .chain_update(tag)
+        .chain_update(r_bytes)
+        .chain_update(pk_x.as_be_bytes())
+        .chain_update(message)
+        .finalize();
+    let e = Scalar::<Secp256k1>::from_be_bytes_mod_order(challenge);
+
+    // R = s·G − e·P must have even y and x == r
+    let point = Point::generator() * s - public_key * e;
+    match point.x_and_parity() {
+        Some((x, Parity::Even)) => x == r,
+        _ => false,
+    }
+}
+
+/// Check a serialised BIP-340 signature against `public_key` and `message`.
+///
+/// Fails with `SIGNATURE_VERIFY_FAILED`, mirroring [`ensure_valid`].
+pub fn ensure_valid_bip340(
+    public_key: &Point<Secp256k1>,
+    message: &[u8],
+    sig_bytes: &[u8],
+) -> Result<(), String> {
+    if verify_bip340(public_key, message, sig_bytes) {
+        return Ok(());
+    }
+    Err(format!(
+        "SIGNATURE_VERIFY_FAILED: BIP-340 signature {} does not verify over message {} \
+         for public key {} (check signers and key material)",
+        hex_codec::encode(sig_bytes),
+        hex_codec::encode(message),
+        hex_codec::encode(public_key.to_bytes(true).as_bytes()),
+    ))
+}
@@ -1,15 +1,42 @@
 //! Local MPC protocol simulation.
 //!
 //! Runs multiple state machines (parties) locally with automatic message routing.
-//! Used for DKG where all parties run on the server.
+//! Used for DKG where all parties run on the server, and by [`sign_local`]
+//! for signing ceremonies that never leave the process (benchmarks).
 //!
 //! Based on the `SimulationSync` pattern from `round-based` but without
 //! the `dev` feature dependency (which pulls in tokio, problematic for WASM).
+//!
+//! Parties are routed by position: `MessageDestination::OneParty(j)` is the
+//! `j`th state machine given. For DKG positions are keygen indices; a
+//! signing group like `[0, 2, 4]` runs at positions 0..3, and errors name
+//! its parties by keygen index (see [`run_counted_as`]).
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
 
 use std::collections::VecDeque;
 
+use cggmp24::security_level::SecurityLevel;
+use cggmp24::signing::PrehashedDataToSign;
+use generic_ec::coords::HasAffineX;
+use generic_ec::{Curve, Point, Scalar};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
 use round_based::state_machine::{ProceedResult, StateMachine};
 use round_based::{Incoming, MessageDestination, MessageType};
+use serde::Serialize;
+
+use crate::drive_guard::YieldGuard;
+use crate::key_material;
+use crate::party_limits;
+
+/// Messages sent during a run and their total serde_json size. A broadcast
+/// counts once.
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Traffic {
+    pub messages: u64,
+    pub bytes: u64,
+}
 
 /// Run a protocol simulation with all parties locally.
 ///
@@ -17,12 +44,86 @@ use round_based::{Incoming, MessageDestination, MessageType};
 /// Messages are automatically routed between parties.
 ///
 /// Returns one output per party, or an error if the protocol fails.
-pub fn run<S>(mut parties: Vec<S>) -> Result<Vec<S::Output>, String>
+pub fn run<S>(parties: Vec<S>) -> Result<Vec<S::Output>, String>
+where
+    S: StateMachine,
+    S::Msg: Clone,
+{
+    run_observed(parties, None, |_| {}, |_, _| Ok(()))
+}
+
+/// [`run`], calling `check(finished, n)` before every pass over the
+/// parties; an error from it stops the run and is returned as is.
+pub fn run_checked<S>(
+    parties: Vec<S>,
+    check: impl FnMut(usize, usize) -> Result<(), String>,
+) -> Result<Vec<S::Output>, String>
+where
+    S: StateMachine,
+    S::Msg: Clone,
+{
+    run_observed(parties, None, |_| {}, check)
+}
+
+/// [`run`], also reporting the [`Traffic`] the parties generated.
+pub fn run_counted<S>(parties: Vec<S>) -> Result<(Vec<S::Output>, Traffic), String>
+where
+    S: StateMachine,
+    S::Msg: Clone + Serialize,
+{
+    counted(parties, None)
+}
+
+/// [`run_counted`] for a signing group: the party at position `i` is
+/// keygen index `keygen_indices[i]`, and error messages call it that.
+pub fn run_counted_as<S>(parties: Vec<S>, keygen_indices: &[u16]) -> Result<(Vec<S::Output>, Traffic), String>
+where
+    S: StateMachine,
+    S::Msg: Clone + Serialize,
+{
+    if keygen_indices.len() != parties.len() {
+        return Err(format!(
+            "{} keygen indices for {} parties",
+            keygen_indices.len(),
+            parties.len()
+        ));
+    }
+    counted(parties, Some(keygen_indices))
+}
+
+fn counted<S>(parties: Vec<S>, keygen_indices: Option<&[u16]>) -> Result<(Vec<S::Output>, Traffic), String>
+where
+    S: StateMachine,
+    S::Msg: Clone + Serialize,
+{
+    let mut traffic = Traffic::default();
+    let outputs = run_observed(
+        parties,
+        keygen_indices,
+        |msg| {
+            traffic.messages += 1;
+            traffic.bytes += serde_json::to_vec(msg).map_or(0, |bytes| bytes.len() as u64);
+        },
+        |_, _| Ok(()),
+    )?;
+    Ok((outputs, traffic))
+}
+
+/// [`run`], calling `on_send` with every message a party sends and `check`
+/// before every pass. Errors name parties by `keygen_indices`, if given
+/// (one per party), else by position.
+fn run_observed<S>(
+    mut parties: Vec<S>,
+    keygen_indices: Option<&[u16]>,
+    mut on_send: impl FnMut(&S::Msg),
+    mut check: impl FnMut(usize, usize) -> Result<(), String>,
+) -> Result<Vec<S::Output>, String>
 where
     S: StateMachine,
     S::Msg: Clone,
 {
     let n = parties.len();
+    let name = |i: usize| keygen_indices.map_or(i, |indices| usize::from(indices[i]));
     let mut queues: Vec<VecDeque<Incoming<S::Msg>>> = (0..n).map(|_| VecDeque::new()).collect();
     let mut wants_msg = vec![false; n];
     let mut outputs: Vec<Option<S::Output>> = (0..n).map(|_| None).collect();
@@ -31,18 +132,23 @@ where
 
     // Bounded iteration to prevent infinite loops in case of protocol bugs
     for _ in 0..100_000 {
+        check(done, n)?;
         for i in 0..n {
             if outputs[i].is_some() {
                 continue;
             }
+            let sender = party_limits::index(i)?;
 
+            // The outer bound counts passes; one party yielding forever
+            // would never finish its pass
+            let mut yields = YieldGuard::default();
             loop {
                 // If the party wants a message, try to deliver one
                 if wants_msg[i] {
                     if let Some(msg) = queues[i].pop_front() {
                         parties[i]
                             .received_msg(msg)
-                            .map_err(|_| format!("party {i} failed to receive message"))?;
+                            .map_err(|_| format!("party {} failed to receive message", name(i)))?;
                         wants_msg[i] = false;
                     } else {
                         // No messages available, skip to next party
@@ -50,15 +156,20 @@ where
                     }
                 }
 
-                match parties[i].proceed() {
+                let proceeded = parties[i].proceed();
+                yields
+                    .step(matches!(proceeded, ProceedResult::Yielded))
+                    .map_err(|e| format!("party {}: {e}", name(i)))?;
+                match proceeded {
                     ProceedResult::SendMsg(outgoing) => {
+                        on_send(&outgoing.msg);
                         match outgoing.recipient {
                             MessageDestination::AllParties => {
                                 for j in 0..n {
                                     if j != i {
                                         queues[j].push_back(Incoming {
                                             id: next_id,
-                                            sender: i as u16,
+                                            sender,
                                             msg_type: MessageType::Broadcast,
                                             msg: outgoing.msg.clone(),
                                         });
@@ -67,9 +178,14 @@ where
                                 }
                             }
                             MessageDestination::OneParty(dest) => {
-                                queues[dest as usize].push_back(Incoming {
+                                let queue = queues
+                                    .get_mut(usize::from(dest))
+                                    .ok_or_else(|| {
+                                        format!("party {} sent to unknown position {dest} of {n}", name(i))
+                                    })?;
+                                queue.push_back(Incoming {
                                     id: next_id,
-                                    sender: i as u16,
+                                    sender,
                                     msg_type: MessageType::P2P,
                                     msg: outgoing.msg,
                                 });
@@ -91,7 +207,7 @@ where
                         // Continue processing this party
                     }
                     ProceedResult::Error(e) => {
-                        return Err(format!("party {i} protocol error: {e}"));
+                        return Err(format!("party {} protocol error: {e}", name(i)));
                     }
                 }
             }
@@ -111,6 +227,140 @@ where
     outputs
         .into_iter()
         .enumerate()
-        .map(|(i, o)| o.ok_or_else(|| format!("party {i} missing output")))
+        .map(|(i, o)| o.ok_or_else(|| format!("party {} missing output", name(i))))
         .collect()
 }
+
+/// Rng for party `i` of a seeded local ceremony: one ChaCha20 stream per
+/// party, so no two parties share randomness.
+pub fn party_rng(seed: [u8; 32], i: usize) -> ChaCha20Rng {
+    let mut rng = ChaCha20Rng::from_seed(seed);
+    rng.set_stream(i as u64);
+    rng
+}
+
+/// Sign `message_hash` with every signer local: `key_shares[i]` belongs to
+/// keygen index `parties[i]` and draws its randomness from `party_rng(i)`.
+/// With seeded rngs (see [`party_rng`]) a given seed always yields the same
+/// transcript.
+///
+/// `parties` may be any valid signer set, e.g. `[0, 2, 4]` of a 3-of-5
+/// key: the state machines run at positions `0..parties.len()`, as in an
+/// interactive session. Fails with the `key_material::check_signer_set`
+/// codes for a set that can't sign, and `KEY_SHARE_MISMATCH` when a share
+/// isn't the one of the keygen index it is given for.
+///
+/// Returns the low-s signature and the traffic of the ceremony.
+pub fn sign_local<E, L, R>(
+    key_shares: &[cggmp24::KeyShare<E, L>],
+    parties: &[u16],
+    message_hash: &[u8],
+    eid: &[u8],
+    party_rng: impl Fn(usize) -> R,
+) -> Result<(cggmp24::signing::Signature<E>, Traffic), String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+    R: RngCore + CryptoRng,
+{
+    check_local_signers(key_shares, parties)?;
+    let prehashed = PrehashedDataToSign::from_scalar(Scalar::<E>::from_be_bytes_mod_order(message_hash));
+    let mut rngs: Vec<R> = (0..parties.len()).map(party_rng).collect();
+
+    let machines = key_shares
+        .iter()
+        .zip(rngs.iter_mut())
+        .enumerate()
+        .map(|(i, (key_share, rng))| {
+            Ok(cggmp24::signing(cggmp24::ExecutionId::new(eid), party_limits::index(i)?, parties, key_share)
+                .enforce_reliable_broadcast(true)
+                .sign_sync(rng, &prehashed))
+        })
+        .collect::<Result<_, String>>()?;
+
+    let (outputs, traffic) = run_counted_as(machines, parties)?;
+    let mut signature = None;
+    for (i, output) in outputs.into_iter().enumerate() {
+        let sig = output.map_err(|e| format!("signing party {} failed: {e:?}", parties[i]))?;
+        signature.get_or_insert(sig);
+    }
+    let signature = signature.ok_or("sign_local: no signers")?;
+    Ok((signature.normalize_s(), traffic))
+}
+
+/// [`sign_local`] in two steps: the signers first run the interactive
+/// presigning protocol, then each issues a partial signature over
+/// `message_hash` from its presignature and the partials are combined —
+/// the message-dependent part takes no round trip. Same arguments, checks
+/// and result.
+pub fn presign_local<E, L, R>(
+    key_shares: &[cggmp24::KeyShare<E, L>],
+    parties: &[u16],
+    message_hash: &[u8],
+    eid: &[u8],
+    party_rng: impl Fn(usize) -> R,
+) -> Result<(cggmp24::signing::Signature<E>, Traffic), String>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+    R: RngCore + CryptoRng,
+{
+    check_local_signers(key_shares, parties)?;
+    let prehashed = PrehashedDataToSign::from_scalar(Scalar::<E>::from_be_bytes_mod_order(message_hash));
+    let mut rngs: Vec<R> = (0..parties.len()).map(party_rng).collect();
+
+    let machines = key_shares
+        .iter()
+        .zip(rngs.iter_mut())
+        .enumerate()
+        .map(|(i, (key_share, rng))| {
+            Ok(cggmp24::signing(cggmp24::ExecutionId::new(eid), party_limits::index(i)?, parties, key_share)
+                .enforce_reliable_broadcast(true)
+                .generate_presignature_sync(rng))
+        })
+        .collect::<Result<_, String>>()?;
+
+    let (outputs, traffic) = run_counted_as(machines, parties)?;
+    let partials = outputs
+        .into_iter()
+        .enumerate()
+        .map(|(i, output)| {
+            output
+                .map(|presignature| presignature.issue_partial_signature(prehashed))
+                .map_err(|e| format!("presigning party {} failed: {e:?}", parties[i]))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let signature = cggmp24::signing::PartialSignature::combine(&partials)
+        .ok_or("presign_local: partial signatures don't combine")?;
+    Ok((signature.normalize_s(), traffic))
+}
+
+/// Fail unless `key_shares` can sign as `parties`, one share per party in
+/// the same order (see [`sign_local`]).
+fn check_local_signers<E: Curve, L: SecurityLevel>(
+    key_shares: &[cggmp24::KeyShare<E, L>],
+    parties: &[u16],
+) -> Result<(), String> {
+    if key_shares.len() != parties.len() {
+        return Err(format!(
+            "sign_local: {} key shares for {} parties",
+            key_shares.len(),
+            parties.len()
+        ));
+    }
+    let first = key_shares.first().ok_or("sign_local: no signers")?;
+    key_material::check_signer_set(first.n(), first.min_signers(), first.core.i, parties)?;
+    let mismatch = key_shares
+        .iter()
+        .zip(parties)
+        .find(|(key_share, index)| key_share.core.i != **index);
+    if let Some((key_share, index)) = mismatch {
+        return Err(format!(
+            "KEY_SHARE_MISMATCH: the key share of party {} is given for party {index}",
+            key_share.core.i
+        ));
+    }
+    Ok(())
+}
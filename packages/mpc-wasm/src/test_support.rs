@@ -0,0 +1,134 @@
+//! Shared by the unit tests: the test vectors' 2-of-3 key, minted once per
+//! test binary, and a relay that runs signing sessions to completion.
+
+use std::sync::OnceLock;
+
+use base64::Engine;
+use generic_ec::curves::Secp256k1;
+use generic_ec::{Point, Scalar};
+use sha2::{Digest, Sha256};
+
+use crate::hex_codec;
+use crate::relay;
+use crate::sign::{self, CreateSessionResult, ProcessRoundResult, SignData, SignOptions, WasmSignMessage};
+use crate::signature;
+use crate::test_vectors;
+use crate::types::SignatureResult;
+
+/// Relay rounds a test signing may take.
+const MAX_ROUNDS: usize = 16;
+
+pub struct TestKey {
+    /// Compressed SEC1
+    pub public_key: Vec<u8>,
+    /// By keygen index
+    pub shares: Vec<TestShare>,
+}
+
+/// One party's blobs, as `run_dkg` returns them.
+pub struct TestShare {
+    pub core_share: Vec<u8>,
+    pub aux_info: Vec<u8>,
+}
+
+/// The [`test_vectors`] key (see [`test_vectors::dealer_key_shares`]).
+pub fn key() -> &'static TestKey {
+    static KEY: OnceLock<TestKey> = OnceLock::new();
+    KEY.get_or_init(|| {
+        let key_shares = test_vectors::dealer_key_shares().unwrap();
+        let vector = test_vectors::key_vector(&key_shares).unwrap();
+        let b64 = |text: &str| base64::engine::general_purpose::STANDARD.decode(text).unwrap();
+        TestKey {
+            public_key: hex_codec::decode(&vector.public_key, "public_key").unwrap(),
+            shares: vector
+                .shares
+                .iter()
+                .map(|share| TestShare {
+                    core_share: b64(&share.core_share),
+                    aux_info: b64(&share.aux_info),
+                })
+                .collect(),
+        }
+    })
+}
+
+/// An execution id of its own for each test.
+pub fn eid(test: &str) -> Vec<u8> {
+    Sha256::digest(test.as_bytes()).to_vec()
+}
+
+/// A session for `party` among `signers`, signing
+/// [`test_vectors::MESSAGE_HASH`] under `eid`.
+pub fn create(party: u16, signers: &[u16], eid: &[u8], options: &SignOptions) -> CreateSessionResult {
+    let share = &key().shares[usize::from(party)];
+    sign::create_session(
+        &share.core_share,
+        &share.aux_info,
+        SignData::Prehashed(&test_vectors::MESSAGE_HASH),
+        party,
+        signers,
+        eid,
+        options,
+    )
+    .unwrap()
+}
+
+/// Every session of `signers` under `eid`, with default options: their
+/// ids, by signer, and their first messages.
+pub fn create_all(signers: &[u16], eid: &[u8]) -> (Vec<String>, Vec<WasmSignMessage>) {
+    let mut ids = Vec::new();
+    let mut messages = Vec::new();
+    for &party in signers {
+        let created = create(party, signers, eid, &SignOptions::default());
+        ids.push(created.session_id);
+        messages.extend(created.messages);
+    }
+    (ids, messages)
+}
+
+/// What a relay hands `party` of `messages`.
+pub fn inbox(party: u16, signers: &[u16], messages: &[WasmSignMessage]) -> Vec<WasmSignMessage> {
+    relay::route_messages(messages, signers, WasmSignMessage::route)
+        .unwrap()
+        .remove(&party)
+        .unwrap_or_default()
+}
+
+/// Relay `messages` between the sessions `ids` of `signers` with
+/// `process_round`, one call per session per relay round, until every one
+/// completes. Returns the signatures, by signer.
+pub fn run(signers: &[u16], ids: &[String], mut messages: Vec<WasmSignMessage>) -> Vec<SignatureResult> {
+    let mut signatures: Vec<Option<SignatureResult>> = vec![None; ids.len()];
+    for _ in 0..MAX_ROUNDS {
+        let mut sent = Vec::new();
+        for (i, (&party, id)) in signers.iter().zip(ids).enumerate() {
+            if signatures[i].is_some() {
+                continue;
+            }
+            let result = sign::process_round(id, &inbox(party, signers, &messages), None).unwrap();
+            assert_no_rejections(&result);
+            sent.extend(result.messages);
+            signatures[i] = result.signature;
+        }
+        if signatures.iter().all(Option::is_some) {
+            return signatures.into_iter().flatten().collect();
+        }
+        messages = sent;
+    }
+    panic!("signing did not complete in {MAX_ROUNDS} relay rounds");
+}
+
+pub fn assert_no_rejections(result: &ProcessRoundResult) {
+    assert!(result.rejected.is_empty(), "rejected: {:?}", result.rejected);
+}
+
+/// Whether `sig` is a signature of [`test_vectors::MESSAGE_HASH`] under
+/// the test key.
+pub fn verifies(sig: &SignatureResult) -> bool {
+    let public_key = Point::<Secp256k1>::from_bytes(&key().public_key).unwrap();
+    let message = Scalar::<Secp256k1>::from_be_bytes_mod_order(test_vectors::MESSAGE_HASH);
+    let (Ok(r), Ok(s)) = (Scalar::from_be_bytes(&sig.r), Scalar::from_be_bytes(&sig.s)) else {
+        return false;
+    };
+    signature::verify_prehashed(&public_key, &message, &r, &s)
+}
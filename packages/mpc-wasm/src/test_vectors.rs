@@ -0,0 +1,390 @@
+//! Frozen test vectors for other implementations of the wire format.
+//!
+//! One fixed set: a 2-of-3 secp256k1 key minted by the trusted dealer,
+//! and the transcript of parties 0 and 1 signing [`MESSAGE_HASH`] under
+//! [`EID`], with reliable broadcast on and off. Every party draws from
+//! `simulate::party_rng(SEED, i)`, so the same build always produces the
+//! same bytes.
+//!
+//! Transcripts are recorded the way a relay sees a session: in relay
+//! rounds (round 0 is what every party sends before hearing from anyone,
+//! round r what it sends once round r - 1 is delivered), with keygen
+//! indices and base64 JSON payloads exactly as in a `WasmSignMessage`.
+//! The envelope's binding and auth fields aren't part of the vectors; they
+//! depend on per-session inputs, not on the protocol.
+//!
+//! native-gen's `gen-vectors` (an `insecure-dev` build: the dealer needs
+//! `spof`) writes the set; [`verify_vectors`] replays it — re-encoding the
+//! key blobs and re-running both signings — and fails with
+//! `VECTOR_MISMATCH` at the first byte that differs, so a change to the
+//! blob or message format shows up as a failed check rather than a silent
+//! drift.
+//!
+//! No wasm-bindgen dependency — native-gen compiles this module too.
+
+use std::collections::VecDeque;
+
+use base64::Engine;
+use cggmp24::security_level::SecurityLevel128;
+use cggmp24::signing::PrehashedDataToSign;
+use cggmp24::supported_curves::Secp256k1;
+use generic_ec::Scalar;
+use rand_chacha::ChaCha20Rng;
+use round_based::state_machine::{ProceedResult, StateMachine};
+use round_based::{Incoming, MessageDestination, MessageType};
+use serde::{Deserialize, Serialize};
+
+use crate::drive_guard::YieldGuard;
+use crate::hex_codec;
+use crate::key_material;
+use crate::message_codec;
+use crate::party_limits;
+use crate::share_format::{self, BlobKind, CurveId, Encoding, Level};
+use crate::simulate;
+
+/// Bumped whenever the layout of the vector files changes.
+pub const FORMAT_VERSION: u32 = 1;
+
+pub const SEED: [u8; 32] = *b"guardian-wallet test vectors v1\0";
+pub const EID: &[u8] = b"guardian-wallet test vectors eid";
+pub const MESSAGE_HASH: [u8; 32] = [0x42; 32];
+pub const N: u16 = 3;
+pub const T: u16 = 2;
+pub const SIGNERS: [u16; 2] = [0, 1];
+
+/// `party_rng` stream of the dealer, clear of the signers' streams.
+const DEALER_STREAM: usize = 1 << 16;
+
+/// Relay rounds a signing may take before the run is abandoned.
+const MAX_ROUNDS: u16 = 32;
+
+pub type VectorKeyShare = cggmp24::KeyShare<Secp256k1, SecurityLevel128>;
+
+/// `key.json`: the dealer's shares, as `dkg` would emit them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct KeyVector {
+    pub n: u16,
+    pub t: u16,
+    pub curve: String,
+    pub security_level: String,
+    /// Compressed, hex
+    pub public_key: String,
+    pub shares: Vec<KeyVectorShare>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct KeyVectorShare {
+    pub index: u16,
+    /// Share blob, base64
+    pub core_share: String,
+    /// Aux info blob, base64
+    pub aux_info: String,
+}
+
+/// `sign-*.json`: one signing, message by message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct SignVector {
+    pub reliable_broadcast: bool,
+    /// Hex
+    pub seed: String,
+    pub eid: String,
+    pub message_hash: String,
+    pub signers: Vec<u16>,
+    /// In the order they were sent
+    pub messages: Vec<VectorMessage>,
+    /// Low-s signature, hex
+    pub r: String,
+    pub s: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct VectorMessage {
+    /// Relay round, as `WasmSignMessage::round`
+    pub round: u16,
+    pub sender: u16,
+    /// `None` for a broadcast
+    pub recipient: Option<u16>,
+    /// Base64 JSON, as `WasmSignMessage::payload`
+    pub payload: String,
+}
+
+/// Everything [`verify_vectors`] checks: `key.json` as `key` and every
+/// `sign-*.json` in `sign`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct VectorSet {
+    pub key: KeyVector,
+    pub sign: Vec<SignVector>,
+}
+
+/// What [`verify_vectors`] replayed.
+#[derive(Serialize, Clone, Debug, Default)]
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+pub struct VectorReport {
+    pub key_shares: usize,
+    pub transcripts: usize,
+    pub messages: usize,
+}
+
+/// The dealer's rng: seeded from [`SEED`] like the signers, on its own
+/// stream.
+pub fn dealer_rng() -> ChaCha20Rng {
+    simulate::party_rng(SEED, DEALER_STREAM)
+}
+
+/// The vectors' key: [`N`] shares at threshold [`T`], minted by the
+/// dealer from [`dealer_rng`]. Paillier primes included, so it takes as
+/// long as generating them.
+#[cfg(feature = "insecure-dev")]
+pub fn dealer_key_shares() -> Result<Vec<VectorKeyShare>, String> {
+    cggmp24::trusted_dealer::builder::<Secp256k1, SecurityLevel128>(N)
+        .set_threshold(Some(T))
+        .generate_shares(&mut dealer_rng())
+        .map_err(|e| format!("trusted dealer: {e}"))
+}
+
+/// `key_shares` encoded as `key.json`. Blobs are always JSON with the
+/// dealer flag, whatever the default encoding.
+pub fn key_vector(key_shares: &[VectorKeyShare]) -> Result<KeyVector, String> {
+    let first = key_shares.first().ok_or("VECTOR_INVALID: no key shares")?;
+    let b64 = |bytes: Vec<u8>| base64::engine::general_purpose::STANDARD.encode(bytes);
+    let shares = key_shares
+        .iter()
+        .map(|key_share| {
+            let core_share = share_format::serialize_encoded(
+                BlobKind::CoreShare,
+                Some(CurveId::Secp256k1),
+                None,
+                share_format::FLAG_DEALER,
+                Encoding::Json,
+                &key_share.core,
+            )?;
+            let aux_info = share_format::serialize_encoded(
+                BlobKind::AuxInfo,
+                None,
+                Some(Level::L128),
+                share_format::FLAG_DEALER,
+                Encoding::Json,
+                &key_share.aux,
+            )?;
+            Ok(KeyVectorShare {
+                index: key_share.core.i,
+                core_share: b64(core_share),
+                aux_info: b64(aux_info),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(KeyVector {
+        n: first.n(),
+        t: first.min_signers(),
+        curve: CurveId::Secp256k1.as_str().into(),
+        security_level: Level::L128.as_str().into(),
+        public_key: hex_codec::encode(first.shared_public_key().to_bytes(true).as_bytes()),
+        shares,
+    })
+}
+
+/// The key shares held in `key`, in index order.
+pub fn key_shares(key: &KeyVector) -> Result<Vec<VectorKeyShare>, String> {
+    key.shares
+        .iter()
+        .map(|share| {
+            let decode = |field: &str, text: &str| {
+                base64::engine::general_purpose::STANDARD
+                    .decode(text)
+                    .map_err(|e| format!("VECTOR_INVALID: share {} {field}: {e}", share.index))
+            };
+            let core = decode("core_share", &share.core_share)?;
+            let aux = decode("aux_info", &share.aux_info)?;
+            let (_, core) = share_format::split_expect(&core, BlobKind::CoreShare)?;
+            let (_, aux) = share_format::split_expect(&aux, BlobKind::AuxInfo)?;
+            let iks = share_format::deserialize(BlobKind::CoreShare, core)
+                .map_err(|e| format!("VECTOR_INVALID: share {} core_share: {e}", share.index))?;
+            let aux = share_format::deserialize(BlobKind::AuxInfo, aux)
+                .map_err(|e| format!("VECTOR_INVALID: share {} aux_info: {e}", share.index))?;
+            key_material::pair_up(iks, aux, None)
+                .map_err(|errors| format!("VECTOR_INVALID: share {}: {}", share.index, errors.join("; ")))
+        })
+        .collect()
+}
+
+/// Sign [`MESSAGE_HASH`] as [`SIGNERS`] and record the transcript.
+pub fn sign_vector(key_shares: &[VectorKeyShare], reliable_broadcast: bool) -> Result<SignVector, String> {
+    let signers: Vec<&VectorKeyShare> = SIGNERS
+        .iter()
+        .map(|&i| {
+            key_shares
+                .iter()
+                .find(|share| share.core.i == i)
+                .ok_or_else(|| format!("VECTOR_INVALID: no key share for party {i}"))
+        })
+        .collect::<Result<_, _>>()?;
+    let prehashed = PrehashedDataToSign::from_scalar(Scalar::<Secp256k1>::from_be_bytes_mod_order(MESSAGE_HASH));
+    let mut rngs: Vec<ChaCha20Rng> = (0..SIGNERS.len()).map(|i| simulate::party_rng(SEED, i)).collect();
+
+    let machines = signers
+        .iter()
+        .zip(rngs.iter_mut())
+        .enumerate()
+        .map(|(i, (&key_share, rng))| {
+            Ok(cggmp24::signing(cggmp24::ExecutionId::new(EID), party_limits::index(i)?, &SIGNERS, key_share)
+                .enforce_reliable_broadcast(reliable_broadcast)
+                .sign_sync(rng, &prehashed))
+        })
+        .collect::<Result<_, String>>()?;
+
+    let (outputs, messages) = run_in_rounds(machines, &SIGNERS)?;
+    let mut signature = None;
+    for (i, output) in outputs.into_iter().enumerate() {
+        let sig = output.map_err(|e| format!("signing party {} failed: {e:?}", SIGNERS[i]))?;
+        signature.get_or_insert(sig);
+    }
+    let signature = signature.ok_or("VECTOR_INVALID: no signers")?.normalize_s();
+    let mut sig_bytes = vec![0u8; cggmp24::signing::Signature::<Secp256k1>::serialized_len()];
+    signature.write_to_slice(&mut sig_bytes);
+
+    Ok(SignVector {
+        reliable_broadcast,
+        seed: hex_codec::encode(&SEED),
+        eid: hex_codec::encode(EID),
+        message_hash: hex_codec::encode(&MESSAGE_HASH),
+        signers: SIGNERS.to_vec(),
+        messages,
+        r: hex_codec::encode(&sig_bytes[..32]),
+        s: hex_codec::encode(&sig_bytes[32..]),
+    })
+}
+
+/// Replay `set` under this build (see the module docs).
+pub fn verify_vectors(set: &VectorSet) -> Result<VectorReport, String> {
+    if set.sign.is_empty() {
+        return Err("VECTOR_INVALID: no signing transcripts".into());
+    }
+    let key_shares = key_shares(&set.key)?;
+    if key_vector(&key_shares)? != set.key {
+        return Err("VECTOR_MISMATCH: key: the shares don't re-encode to the same bytes".into());
+    }
+
+    let mut report = VectorReport {
+        key_shares: key_shares.len(),
+        ..Default::default()
+    };
+    for expected in &set.sign {
+        let name = format!("sign (reliable_broadcast {})", expected.reliable_broadcast);
+        let found = sign_vector(&key_shares, expected.reliable_broadcast)?;
+        let inputs = (&expected.seed, &expected.eid, &expected.message_hash, &expected.signers);
+        if inputs != (&found.seed, &found.eid, &found.message_hash, &found.signers) {
+            return Err(format!(
+                "VECTOR_MISMATCH: {name}: seed, eid, message_hash or signers aren't this set's fixed inputs"
+            ));
+        }
+        if let Some(i) = (0..expected.messages.len().max(found.messages.len()))
+            .find(|&i| expected.messages.get(i) != found.messages.get(i))
+        {
+            let at = expected.messages.get(i).or(found.messages.get(i)).expect("one side has message i");
+            return Err(format!(
+                "VECTOR_MISMATCH: {name}: message {i} (round {}, from party {}) differs",
+                at.round, at.sender
+            ));
+        }
+        if (&expected.r, &expected.s) != (&found.r, &found.s) {
+            return Err(format!("VECTOR_MISMATCH: {name}: signature differs"));
+        }
+        report.transcripts += 1;
+        report.messages += found.messages.len();
+    }
+    Ok(report)
+}
+
+/// Run `parties` in lock-step relay rounds: each round every party is
+/// handed what was sent to it the round before, then driven until it
+/// needs more. Returns the outputs and every message sent, named by
+/// `keygen_indices`.
+fn run_in_rounds<S>(
+    mut parties: Vec<S>,
+    keygen_indices: &[u16],
+) -> Result<(Vec<S::Output>, Vec<VectorMessage>), String>
+where
+    S: StateMachine,
+    S::Msg: Clone + Serialize,
+{
+    let n = parties.len();
+    let mut inboxes: Vec<VecDeque<Incoming<S::Msg>>> = (0..n).map(|_| VecDeque::new()).collect();
+    let mut outputs: Vec<Option<S::Output>> = (0..n).map(|_| None).collect();
+    let mut transcript = Vec::new();
+    let mut next_id = 0u64;
+
+    for round in 0..MAX_ROUNDS {
+        let mut sent = Vec::new();
+        for i in 0..n {
+            if outputs[i].is_some() {
+                continue;
+            }
+            let mut yields = YieldGuard::default();
+            loop {
+                let proceeded = parties[i].proceed();
+                yields.step(matches!(proceeded, ProceedResult::Yielded))?;
+                match proceeded {
+                    ProceedResult::SendMsg(outgoing) => sent.push((i, outgoing)),
+                    ProceedResult::NeedsOneMoreMessage => match inboxes[i].pop_front() {
+                        Some(msg) => parties[i]
+                            .received_msg(msg)
+                            .map_err(|_| format!("party {} failed to receive message", keygen_indices[i]))?,
+                        None => break,
+                    },
+                    ProceedResult::Output(output) => {
+                        outputs[i] = Some(output);
+                        break;
+                    }
+                    ProceedResult::Yielded => {}
+                    ProceedResult::Error(e) => {
+                        return Err(format!("party {} protocol error: {e}", keygen_indices[i]));
+                    }
+                }
+            }
+        }
+
+        if outputs.iter().all(Option::is_some) {
+            let outputs = outputs.into_iter().map(|o| o.expect("checked above")).collect();
+            return Ok((outputs, transcript));
+        }
+        if sent.is_empty() {
+            return Err(format!("protocol stalled in relay round {round}"));
+        }
+
+        for (i, outgoing) in sent {
+            let sender = party_limits::index(i)?;
+            let payload = message_codec::encode(Encoding::Json, &outgoing.msg)?;
+            let (recipient, destinations) = match outgoing.recipient {
+                MessageDestination::AllParties => (None, (0..n).filter(|&j| j != i).collect::<Vec<_>>()),
+                MessageDestination::OneParty(dest) => {
+                    let dest = usize::from(dest);
+                    let recipient = *keygen_indices
+                        .get(dest)
+                        .ok_or_else(|| format!("party {} sent to unknown position {dest} of {n}", keygen_indices[i]))?;
+                    (Some(recipient), vec![dest])
+                }
+            };
+            for j in destinations {
+                inboxes[j].push_back(Incoming {
+                    id: next_id,
+                    sender,
+                    msg_type: if recipient.is_none() { MessageType::Broadcast } else { MessageType::P2P },
+                    msg: outgoing.msg.clone(),
+                });
+                next_id += 1;
+            }
+            transcript.push(VectorMessage {
+                round,
+                sender: keygen_indices[i],
+                recipient,
+                payload,
+            });
+        }
+    }
+    Err(format!("protocol did not complete in {MAX_ROUNDS} relay rounds"))
+}
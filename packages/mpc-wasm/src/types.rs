@@ -1,20 +1,15 @@
 //! Serde types for JS interop.
 //!
-//! These types are serialised to/from JS via serde-wasm-bindgen.
-//! Currently only used for signing session state (future).
+//! These types are serialised to/from JS via serde-wasm-bindgen, with byte
+//! fields marked `serde_bytes` so they cross as `Uint8Array`. The `Tsify`
+//! derives here and in sign.rs / frost.rs / lib.rs emit their TypeScript
+//! definitions into the generated .d.ts.
+//! Signing sessions are resumed through `sign::export_session` /
+//! `sign::import_session` rather than by passing state back each round.
 
 use serde::{Deserialize, Serialize};
-
-/// Result from a round of a signing protocol (per-party, for HTTP round-trips).
-#[derive(Serialize, Deserialize)]
-pub struct RoundResult {
-    /// Serialised state machine bytes (opaque, pass back to next round)
-    pub state: Vec<u8>,
-    /// Outgoing messages to send to other parties
-    pub outgoing: Vec<MpcMessage>,
-    /// Whether the protocol has finished
-    pub finished: bool,
-}
+use serde_bytes::ByteBuf;
+use tsify::Tsify;
 
 /// Message exchanged between parties during MPC protocols.
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -25,16 +20,68 @@ pub struct MpcMessage {
     pub payload: String,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(untagged)]
+/// Serialised tagged: `{"type":"broadcast"}` / `{"type":"party","index":3}`.
+///
+/// The old untagged form (`"all"` for broadcast, a bare number for a party)
+/// is still accepted on input for one release. Any other string is rejected:
+/// a quoted `"3"` used to silently become a broadcast.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum MpcRecipient {
-    Broadcast(String),
-    Party(u16),
+    Broadcast,
+    Party { index: u16 },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum TaggedRecipient {
+    Broadcast,
+    Party { index: u16 },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RecipientRepr {
+    Tagged(TaggedRecipient),
+    // Deprecated untagged forms
+    LegacyParty(u16),
+    LegacyBroadcast(String),
+}
+
+impl<'de> Deserialize<'de> for MpcRecipient {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match RecipientRepr::deserialize(deserializer)? {
+            RecipientRepr::Tagged(TaggedRecipient::Broadcast) => Ok(MpcRecipient::Broadcast),
+            RecipientRepr::Tagged(TaggedRecipient::Party { index }) => Ok(MpcRecipient::Party { index }),
+            RecipientRepr::LegacyParty(index) => Ok(MpcRecipient::Party { index }),
+            RecipientRepr::LegacyBroadcast(s) if s == "all" => Ok(MpcRecipient::Broadcast),
+            RecipientRepr::LegacyBroadcast(s) => Err(serde::de::Error::custom(format!(
+                "ambiguous recipient {s:?}: use {{\"type\":\"broadcast\"}} or {{\"type\":\"party\",\"index\":N}}"
+            ))),
+        }
+    }
 }
 
 /// Full signing result.
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Tsify)]
 pub struct SignatureResult {
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
     pub r: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
     pub s: Vec<u8>,
+    /// `s` was above `n / 2` and was flipped to `n - s` (see
+    /// `SignOptions.normalize_s`); always false for BIP-340
+    #[serde(default)]
+    pub normalized: bool,
+    /// `chain_id * 2 + 35 + recovery_id`, when `SignOptions.chain_id` was set
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[tsify(optional)]
+    pub v_eip155: Option<u64>,
+    /// 33-byte compressed key the signature recovers to, when
+    /// `SignOptions.include_recovered_pubkey` was set
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[tsify(optional, type = "Uint8Array")]
+    pub recovered_pubkey: Option<ByteBuf>,
 }
@@ -0,0 +1,106 @@
+//! An `approval_callback` that calls back into the module, on wasm32:
+//!
+//! ```text
+//! wasm-pack test --node -- --features fixtures
+//! ```
+
+#![cfg(all(target_arch = "wasm32", feature = "fixtures"))]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use guardian_mpc_wasm::{
+    fixture_dkg_result, route_messages, sign_create_session, sign_destroy_session, sign_heartbeat,
+    sign_process_round, sign_session_status,
+};
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsError, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Relay rounds a signing may take.
+const MAX_ROUNDS: usize = 16;
+
+fn ok<T>(result: Result<T, JsError>) -> T {
+    result.map_err(JsValue::from).unwrap()
+}
+
+fn get(object: &JsValue, key: &str) -> JsValue {
+    Reflect::get(object, &JsValue::from_str(key)).unwrap()
+}
+
+fn arg<T: JsCast>(bytes: &[u8]) -> T {
+    JsValue::from(Uint8Array::from(bytes)).unchecked_into()
+}
+
+/// Party 0's callback asks for the status and heartbeat of its own session
+/// while that session waits on it; the signature is released all the same.
+#[wasm_bindgen_test]
+fn the_callback_may_ask_for_its_own_session_status() {
+    let signers = [0u16, 1];
+    let fixture: JsValue = ok(fixture_dkg_result()).into();
+    let shares = Array::from(&get(&fixture, "shares"));
+
+    let own_id = Rc::new(RefCell::new(String::new()));
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let callback = {
+        let (own_id, seen) = (Rc::clone(&own_id), Rc::clone(&seen));
+        Closure::<dyn FnMut(JsValue) -> bool>::new(move |_request: JsValue| {
+            let id = own_id.borrow();
+            let state: JsValue = ok(sign_session_status(&id)).into();
+            let beat: JsValue = ok(sign_heartbeat(&id)).into();
+            assert!(get(&beat, "alive").is_truthy());
+            seen.borrow_mut().push(get(&state, "status").as_string().unwrap());
+            true
+        })
+    };
+    let options = Object::new();
+    Reflect::set(&options, &JsValue::from_str("approval_callback"), callback.as_ref()).unwrap();
+
+    let mut ids = Vec::new();
+    let mut messages = Array::new();
+    for &party in &signers {
+        let share = shares.get(u32::from(party));
+        let created: JsValue = ok(sign_create_session(
+            get(&share, "core_share").unchecked_into(),
+            Some(get(&share, "aux_info").unchecked_into()),
+            arg(&[0x42; 32]),
+            party,
+            &signers,
+            arg(&[4; 32]),
+            (party == 0).then(|| JsValue::from(&options).unchecked_into()),
+        ))
+        .into();
+        ids.push(get(&created, "session_id").as_string().unwrap());
+        messages.extend(Array::from(&get(&created, "messages")).iter());
+    }
+    *own_id.borrow_mut() = ids[0].clone();
+
+    let mut complete = [false; 2];
+    for _ in 0..MAX_ROUNDS {
+        let routed: JsValue = ok(route_messages(messages.unchecked_into(), signers.to_vec())).into();
+        let sent = Array::new();
+        for (i, (&party, id)) in signers.iter().zip(&ids).enumerate() {
+            if complete[i] {
+                continue;
+            }
+            let inbox = get(&routed, &party.to_string());
+            let result: JsValue = ok(sign_process_round(id, inbox.unchecked_into(), None, None)).into();
+            sent.extend(Array::from(&get(&result, "messages")).iter());
+            complete[i] = get(&result, "complete").is_truthy();
+            if complete[i] && i == 0 {
+                assert!(!get(&result, "signature").is_undefined());
+            }
+        }
+        if complete.iter().all(|&done| done) {
+            break;
+        }
+        messages = sent;
+    }
+
+    assert!(complete.iter().all(|&done| done), "signing did not complete");
+    assert_eq!(*seen.borrow(), ["computing"]);
+    for id in &ids {
+        sign_destroy_session(id);
+    }
+}
@@ -0,0 +1,136 @@
+//! DKG → distribute → sign through the exports, on wasm32, the way the SDK
+//! drives them. The vendored fixture key's aux info stands in for the
+//! Paillier phase, so keygen and both signings take seconds:
+//!
+//! ```text
+//! wasm-pack test --node -- --features fixtures
+//! ```
+
+#![cfg(all(target_arch = "wasm32", feature = "fixtures"))]
+
+use guardian_mpc_wasm::{
+    fixture_dkg_result, recover_public_key, route_messages, run_keygen_for_parties, sign_create_session,
+    sign_destroy_session, sign_process_round,
+};
+use js_sys::{Array, Reflect, Uint8Array};
+use wasm_bindgen::{JsCast, JsError, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Relay rounds a signing may take.
+const MAX_ROUNDS: usize = 16;
+
+const MESSAGE_HASH: [u8; 32] = [0x42; 32];
+
+fn ok<T>(result: Result<T, JsError>) -> T {
+    result.map_err(JsValue::from).unwrap()
+}
+
+fn get(object: &JsValue, key: &str) -> JsValue {
+    Reflect::get(object, &JsValue::from_str(key)).unwrap()
+}
+
+/// `bytes` as a byte-accepting argument.
+fn arg<T: JsCast>(bytes: &[u8]) -> T {
+    JsValue::from(Uint8Array::from(bytes)).unchecked_into()
+}
+
+/// A 2-of-3 key of the parties `indices` from a keygen over the fixture
+/// key's aux info.
+fn keygen(indices: &[u16]) -> JsValue {
+    let fixture: JsValue = ok(fixture_dkg_result()).into();
+    let aux_infos: Array = Array::from(&get(&fixture, "shares"))
+        .iter()
+        .map(|share| get(&share, "aux_info"))
+        .collect();
+    ok(run_keygen_for_parties(arg(&[1; 32]), indices.to_vec(), 2, aux_infos.unchecked_into(), None)).into()
+}
+
+/// Sign [`MESSAGE_HASH`] with the shares of `signers`, relaying every
+/// session's messages through `route_messages` until all complete. Returns
+/// `(r, s)`, which every signer must agree on.
+fn sign(key: &JsValue, signers: &[u16], eid: [u8; 32]) -> (Vec<u8>, Vec<u8>) {
+    let shares = Array::from(&get(key, "shares"));
+    let labels: Vec<u16> = Array::from(&get(key, "party_labels"))
+        .iter()
+        .map(|label| label.as_f64().unwrap() as u16)
+        .collect();
+    let mut ids = Vec::new();
+    let mut messages = Array::new();
+    for &party in signers {
+        let position = labels.iter().position(|&label| label == party).unwrap();
+        let share = shares.get(position as u32);
+        let created: JsValue = ok(sign_create_session(
+            get(&share, "core_share").unchecked_into(),
+            Some(get(&share, "aux_info").unchecked_into()),
+            arg(&MESSAGE_HASH),
+            party,
+            signers,
+            arg(&eid),
+            None,
+        ))
+        .into();
+        ids.push(get(&created, "session_id").as_string().unwrap());
+        messages.extend(Array::from(&get(&created, "messages")).iter());
+    }
+
+    let mut signatures = vec![None; signers.len()];
+    for _ in 0..MAX_ROUNDS {
+        let routed: JsValue = ok(route_messages(messages.unchecked_into(), signers.to_vec())).into();
+        let sent = Array::new();
+        for (i, (&party, id)) in signers.iter().zip(&ids).enumerate() {
+            if signatures[i].is_some() {
+                continue;
+            }
+            let inbox = get(&routed, &party.to_string());
+            let result: JsValue = ok(sign_process_round(id, inbox.unchecked_into(), None, None)).into();
+            assert_eq!(Array::from(&get(&result, "rejected")).length(), 0, "party {party} rejected messages");
+            sent.extend(Array::from(&get(&result, "messages")).iter());
+            if get(&result, "complete").is_truthy() {
+                let signature = get(&result, "signature");
+                let bytes = |field: &str| Uint8Array::new(&get(&signature, field)).to_vec();
+                signatures[i] = Some((bytes("r"), bytes("s")));
+            }
+        }
+        if signatures.iter().all(Option::is_some) {
+            for id in &ids {
+                sign_destroy_session(id);
+            }
+            let signatures: Vec<_> = signatures.into_iter().flatten().collect();
+            assert!(signatures.windows(2).all(|pair| pair[0] == pair[1]), "signers disagree");
+            return signatures.into_iter().next().unwrap();
+        }
+        messages = sent;
+    }
+    panic!("signing by {signers:?} did not complete in {MAX_ROUNDS} relay rounds");
+}
+
+/// Whether `(r, s)` signs [`MESSAGE_HASH`] under `public_key`, by recovery.
+fn verifies(public_key: &[u8], (r, s): &(Vec<u8>, Vec<u8>)) -> bool {
+    (0..2).any(|v| {
+        recover_public_key(arg(&MESSAGE_HASH), arg(r), arg(s), v, None)
+            .is_ok_and(|recovered| recovered == public_key)
+    })
+}
+
+#[wasm_bindgen_test]
+fn keygen_then_sign_with_parties_0_and_1() {
+    let key = keygen(&[0, 1, 2]);
+    let public_key = Uint8Array::new(&get(&key, "public_key")).to_vec();
+    assert!(verifies(&public_key, &sign(&key, &[0, 1], [2; 32])));
+}
+
+/// Keygen index and signing position differ for party 2.
+#[wasm_bindgen_test]
+fn keygen_then_sign_with_parties_1_and_2() {
+    let key = keygen(&[0, 1, 2]);
+    let public_key = Uint8Array::new(&get(&key, "public_key")).to_vec();
+    assert!(verifies(&public_key, &sign(&key, &[1, 2], [3; 32])));
+}
+
+/// Parties named by label throughout, from keygen to the relay.
+#[wasm_bindgen_test]
+fn keygen_of_parties_3_7_and_9_then_sign_with_parties_3_and_9() {
+    let key = keygen(&[3, 7, 9]);
+    let public_key = Uint8Array::new(&get(&key, "public_key")).to_vec();
+    assert!(verifies(&public_key, &sign(&key, &[3, 9], [5; 32])));
+}